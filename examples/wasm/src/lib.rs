@@ -0,0 +1,69 @@
+/*!
+An example of using the `aws-iam` crate from JavaScript, for example in a browser-based policy
+editor or a Cloudflare Worker. The crate is used without default features, so that no file
+system functions are included.
+
+Build with [wasm-pack](https://rustwasm.github.io/wasm-pack/):
+
+```bash
+wasm-pack build --target web
+```
+
+And call from JavaScript:
+
+```javascript
+import init, { validate, format } from "./pkg/aws_iam_wasm.js";
+
+await init();
+for (const finding of validate(editor.value, "resource")) {
+    console.log(finding);
+}
+editor.value = format(editor.value);
+```
+*/
+
+use aws_iam::model::Policy;
+use aws_iam::validate::{PolicyType, ValidationOptions};
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Validate the JSON `policy` as a policy of type `policy_type`, one of `identity`, `resource`,
+/// `trust`, `boundary`, `scp`, or `endpoint`, returning the text of each finding.
+///
+#[wasm_bindgen]
+pub fn validate(policy: &str, policy_type: &str) -> Result<Vec<String>, JsError> {
+    let policy_type = match policy_type {
+        "identity" => PolicyType::Identity,
+        "resource" => PolicyType::Resource,
+        "trust" => PolicyType::Trust,
+        "boundary" => PolicyType::PermissionsBoundary,
+        "scp" => PolicyType::ServiceControl,
+        "endpoint" => PolicyType::Endpoint,
+        _ => {
+            return Err(JsError::new(&format!(
+                "unknown policy type {:?}",
+                policy_type
+            )))
+        }
+    };
+    let policy = Policy::from_str(policy).map_err(|e| JsError::new(&e.to_string()))?;
+    Ok(policy
+        .validate(&ValidationOptions::for_type(policy_type))
+        .iter()
+        .map(|finding| finding.to_string())
+        .collect())
+}
+
+///
+/// Parse the JSON `policy` and return it in the standard, pretty-printed, form.
+///
+#[wasm_bindgen]
+pub fn format(policy: &str) -> Result<String, JsError> {
+    let policy = Policy::from_str(policy).map_err(|e| JsError::new(&e.to_string()))?;
+    aws_iam::io::to_string(&policy, true).map_err(|e| JsError::new(&e.to_string()))
+}
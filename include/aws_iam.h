@@ -0,0 +1,126 @@
+#ifndef AWS_IAM_H
+#define AWS_IAM_H
+
+/* This file is generated by cbindgen, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Validate as an identity-based policy, see `PolicyType::Identity`.
+ */
+#define AWS_IAM_POLICY_TYPE_IDENTITY 0
+
+/**
+ * Validate as a resource-based policy, see `PolicyType::Resource`.
+ */
+#define AWS_IAM_POLICY_TYPE_RESOURCE 1
+
+/**
+ * Validate as a role trust policy, see `PolicyType::Trust`.
+ */
+#define AWS_IAM_POLICY_TYPE_TRUST 2
+
+/**
+ * Validate as a permissions boundary, see `PolicyType::PermissionsBoundary`.
+ */
+#define AWS_IAM_POLICY_TYPE_PERMISSIONS_BOUNDARY 3
+
+/**
+ * Validate as a service control policy, see `PolicyType::ServiceControl`.
+ */
+#define AWS_IAM_POLICY_TYPE_SERVICE_CONTROL 4
+
+/**
+ * Validate as a VPC endpoint policy, see `PolicyType::Endpoint`.
+ */
+#define AWS_IAM_POLICY_TYPE_ENDPOINT 5
+
+/**
+ * The request is allowed, returned by `aws_iam_evaluate`.
+ */
+#define AWS_IAM_ALLOW 1
+
+/**
+ * The request is denied, returned by `aws_iam_evaluate`.
+ */
+#define AWS_IAM_DENY 0
+
+/**
+ * The request could not be evaluated, returned by `aws_iam_evaluate`.
+ */
+#define AWS_IAM_ERROR -1
+
+/**
+ * An opaque handle to a parsed policy.
+ */
+typedef struct AwsIamPolicy AwsIamPolicy;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Parse the JSON policy document `json`, returning `NULL` on failure. If `error` is not `NULL`
+ * it is set to a description of the failure, or to `NULL` on success.
+ *
+ * # Safety
+ *
+ * `json` must be a valid, NUL terminated, string and `error`, if not `NULL`, must be valid for
+ * writes. The returned policy must be released with `aws_iam_policy_free`.
+ */
+AwsIamPolicy *aws_iam_parse(const char *json, char **error);
+
+/**
+ * Validate `policy` as the kind of policy `policy_type`, one of the `AWS_IAM_POLICY_TYPE_*`
+ * values, returning the findings as a JSON array of objects with the properties `severity`,
+ * `code`, `message`, and `statement`. Returns `NULL` on failure, see `aws_iam_parse` for the
+ * use of `error`.
+ *
+ * # Safety
+ *
+ * `policy` must have been returned by `aws_iam_parse` and not yet released. The returned
+ * string must be released with `aws_iam_string_free`.
+ */
+char *aws_iam_validate(const AwsIamPolicy *policy, uint32_t policy_type, char **error);
+
+#if defined(AWS_IAM_OFFLINE_EVAL)
+/**
+ * Evaluate the JSON request `request`, in the form described by the
+ * [`offline`](../offline/index.html) module, against `policy`; returning `AWS_IAM_ALLOW`,
+ * `AWS_IAM_DENY`, or `AWS_IAM_ERROR`. See `aws_iam_parse` for the use of `error`. This
+ * function requires the `offline_eval` feature.
+ *
+ * # Safety
+ *
+ * `policy` must have been returned by `aws_iam_parse` and not yet released, and `request`
+ * must be a valid, NUL terminated, string.
+ */
+int32_t aws_iam_evaluate(const AwsIamPolicy *policy, const char *request, char **error);
+#endif
+
+/**
+ * Release a policy returned by `aws_iam_parse`; `NULL` is ignored.
+ *
+ * # Safety
+ *
+ * `policy` must have been returned by `aws_iam_parse` and not yet released.
+ */
+void aws_iam_policy_free(AwsIamPolicy *policy);
+
+/**
+ * Release a string returned by this API; `NULL` is ignored.
+ *
+ * # Safety
+ *
+ * `s` must have been returned by this API and not yet released.
+ */
+void aws_iam_string_free(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* AWS_IAM_H */
@@ -0,0 +1,53 @@
+/*!
+Provides warnings for statements that allow actions with `Write` or `Permissions management`
+access on any resource.
+*/
+
+use crate::model::{OrAny, Policy, Resource};
+use crate::service::actions::AccessLevel;
+use crate::validate::{Finding, Findings};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+/// An allow statement allows actions with `Write` or `Permissions management` access on any
+/// resource.
+pub const FINDING_WRITE_ACCESS_ANY_RESOURCE: &str = "WriteAccessAnyResource";
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns a warning for each allow statement of `policy` whose most privileged action, see
+/// `Statement::max_access_level`, has `Write` or `Permissions management` access and which
+/// applies to any resource, either by `"*"` or by `NotResource`. Only actions in the
+/// [`actions`](../service/actions/index.html) catalog are classified.
+///
+pub fn check_access_levels(policy: &Policy) -> Findings {
+    let mut findings = Findings::default();
+    for (index, statement) in policy.statement.iter().enumerate() {
+        let any_resource = match &statement.resource {
+            Some(Resource::Resource(resource)) => matches!(resource, OrAny::Any),
+            Some(Resource::NotResource(_)) => true,
+            None => false,
+        };
+        if !statement.is_allow() || !any_resource {
+            continue;
+        }
+        if let Some(level) = statement.max_access_level() {
+            if level >= AccessLevel::Write {
+                findings.push(Finding::warning(
+                    FINDING_WRITE_ACCESS_ANY_RESOURCE,
+                    format!(
+                        "the statement allows actions with {} access on any resource",
+                        level
+                    ),
+                    Some(index),
+                ));
+            }
+        }
+    }
+    findings
+}
@@ -0,0 +1,74 @@
+/*!
+Provides warnings for statements that can never take effect, as they are shadowed by a deny
+statement or duplicate an earlier statement.
+*/
+
+use crate::analysis::shadowing::shadows;
+use crate::model::{Policy, Statement};
+use crate::validate::{Finding, Findings};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+/// An allow statement is shadowed by a deny statement, without conditions, that denies all of
+/// its actions on all of its resources; the allow statement can never take effect.
+pub const FINDING_SHADOWED_ALLOW: &str = "ShadowedAllow";
+
+/// The statement is identical, apart from its `Sid`, to an earlier statement.
+pub const FINDING_DUPLICATE_STATEMENT: &str = "DuplicateStatement";
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns a warning for each statement of `policy` that can never take effect, identifying the
+/// statement and, where there is one, the statement responsible.
+///
+/// * An allow statement is shadowed if a deny statement without conditions, and with no
+///   principal, any principal, or the same principal, denies every one of its actions on every
+///   one of its resources. Coverage is determined from the patterns alone, so a `NotAction`
+///   deny only shadows actions listed without wildcards.
+/// * A statement is a duplicate if it is equal to an earlier statement once their `Sid`s are
+///   removed.
+///
+pub fn check_contradictions(policy: &Policy) -> Findings {
+    let unnamed = |statement: &Statement| Statement {
+        sid: None,
+        ..statement.clone()
+    };
+    let mut findings = Findings::default();
+    for (index, statement) in policy.statement.iter().enumerate() {
+        let this = unnamed(statement);
+        if let Some(original) = policy.statement[..index]
+            .iter()
+            .position(|earlier| unnamed(earlier) == this)
+        {
+            findings.push(Finding::warning(
+                FINDING_DUPLICATE_STATEMENT,
+                format!("the statement duplicates statement {}", original),
+                Some(index),
+            ));
+            continue;
+        }
+        if statement.is_deny() {
+            continue;
+        }
+        if let Some(deny) = policy
+            .statement
+            .iter()
+            .position(|deny| deny.is_deny() && shadows(deny, statement))
+        {
+            findings.push(Finding::warning(
+                FINDING_SHADOWED_ALLOW,
+                format!(
+                    "the statement's actions and resources are all denied by statement {}",
+                    deny
+                ),
+                Some(index),
+            ));
+        }
+    }
+    findings
+}
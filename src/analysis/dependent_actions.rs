@@ -0,0 +1,59 @@
+/*!
+Provides notes for actions that are allowed without the other actions they depend on.
+*/
+
+use crate::model::{Action, OrAny, Policy, QualifiedName};
+use crate::service::actions::dependent_actions;
+use crate::validate::{Finding, Findings};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+/// A statement allows an action whose dependent actions, such as `iam:PassRole`, are not
+/// allowed by any statement of the policy; they may be allowed by another policy.
+pub const FINDING_MISSING_DEPENDENT_ACTION: &str = "MissingDependentAction";
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns an informational finding for each dependent action, see
+/// `service::actions::dependent_actions`, of an action allowed by a statement of `policy`
+/// that no allow statement of `policy` allows. Statements with `NotAction` or `"*"` are not
+/// checked, and some dependent actions, such as `kms:Decrypt` for objects encrypted with
+/// SSE-KMS, are only required for some resources.
+///
+pub fn check_dependent_actions(policy: &Policy) -> Findings {
+    let mut findings = Findings::default();
+    for (index, statement) in policy.statement.iter().enumerate() {
+        let actions = match &statement.action {
+            Action::Action(OrAny::Some(actions)) if statement.is_allow() => actions,
+            _ => continue,
+        };
+        let mut reported: Vec<QualifiedName> = Default::default();
+        for action in actions {
+            for dependent in dependent_actions(action) {
+                if reported.contains(&dependent)
+                    || policy
+                        .statement
+                        .iter()
+                        .any(|other| other.allows_action(&dependent))
+                {
+                    continue;
+                }
+                findings.push(Finding::info(
+                    FINDING_MISSING_DEPENDENT_ACTION,
+                    format!(
+                        "the action {} may also require {}, which is not allowed by the policy",
+                        action, dependent
+                    ),
+                    Some(index),
+                ));
+                reported.push(dependent);
+            }
+        }
+    }
+    findings
+}
@@ -0,0 +1,133 @@
+/*!
+Provides the period in which a policy is effective, from the `Date` conditions of its
+statements on the current time, and warnings for statements that can no longer apply.
+*/
+
+use crate::context::keys::{AWS_CURRENT_TIME, AWS_EPOCH_TIME};
+use crate::model::condition::GlobalOperator;
+use crate::model::{Policy, Statement};
+use crate::validate::{Finding, Findings};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+/// A statement's `Date` conditions on the current time only hold before a time that has
+/// passed, so the statement can no longer apply to any request.
+pub const FINDING_STATEMENT_EXPIRED: &str = "StatementExpired";
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns the period in which `policy` is effective, as seconds since the epoch, from the
+/// `DateGreaterThan`, `DateGreaterThanEquals`, `DateLessThan`, and `DateLessThanEquals`
+/// conditions on `aws:CurrentTime` or `aws:EpochTime` in its allow statements. The start is
+/// inclusive and the end exclusive, either is `None` if unbounded; the result is `None` if no
+/// allow statement has such a condition.
+///
+/// The period is that in which any allow statement may apply, so an allow statement without
+/// these conditions makes the period unbounded. Deny statements, which can only narrow it, and
+/// other conditions are not considered.
+///
+pub fn validity_window(policy: &Policy) -> Option<(Option<i64>, Option<i64>)> {
+    let windows: Vec<(Option<i64>, Option<i64>)> = policy
+        .statement
+        .iter()
+        .filter(|statement| !statement.is_deny())
+        .map(statement_window)
+        .collect();
+    if windows.iter().all(|window| *window == (None, None)) {
+        return None;
+    }
+    let start = windows
+        .iter()
+        .map(|(start, _)| *start)
+        .collect::<Option<Vec<i64>>>()
+        .and_then(|starts| starts.into_iter().min());
+    let end = windows
+        .iter()
+        .map(|(_, end)| *end)
+        .collect::<Option<Vec<i64>>>()
+        .and_then(|ends| ends.into_iter().max());
+    Some((start, end))
+}
+
+///
+/// Returns a warning for each statement of `policy` whose `Date` conditions on the current
+/// time, see [`validity_window`](fn.validity_window.html), end at or before `now`, in seconds
+/// since the epoch.
+///
+pub fn check_expired(policy: &Policy, now: i64) -> Findings {
+    let mut findings = Findings::default();
+    for (index, statement) in policy.statement.iter().enumerate() {
+        if let (_, Some(end)) = statement_window(statement) {
+            if end <= now {
+                findings.push(Finding::warning(
+                    FINDING_STATEMENT_EXPIRED,
+                    format!(
+                        "the statement's conditions on the current time ended at epoch time {}",
+                        end
+                    ),
+                    Some(index),
+                ));
+            }
+        }
+    }
+    findings
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The period a single statement may apply, the conditions of a statement must all hold so the
+/// bounds are the latest start and earliest end; the values of one key are alternatives, so
+/// within a key the earliest start and latest end are used.
+///
+fn statement_window(statement: &Statement) -> (Option<i64>, Option<i64>) {
+    let mut start: Option<i64> = None;
+    let mut end: Option<i64> = None;
+    if let Some(condition) = &statement.condition {
+        for (operator, matches) in condition.iter() {
+            for (key, values) in matches.iter() {
+                let key = key.to_string();
+                if !key.eq_ignore_ascii_case(AWS_CURRENT_TIME)
+                    && !key.eq_ignore_ascii_case(AWS_EPOCH_TIME)
+                {
+                    continue;
+                }
+                let times = values.iter().filter_map(|value| value.epoch_seconds());
+                match operator.operator {
+                    GlobalOperator::DateGreaterThan => {
+                        start = later(start, times.map(|time| time + 1).min());
+                    }
+                    GlobalOperator::DateGreaterThanEquals => {
+                        start = later(start, times.min());
+                    }
+                    GlobalOperator::DateLessThan => {
+                        end = earlier(end, times.max());
+                    }
+                    GlobalOperator::DateLessThanEquals => {
+                        end = earlier(end, times.map(|time| time + 1).max());
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    (start, end)
+}
+
+fn later(lhs: Option<i64>, rhs: Option<i64>) -> Option<i64> {
+    lhs.max(rhs)
+}
+
+fn earlier(lhs: Option<i64>, rhs: Option<i64>) -> Option<i64> {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => Some(lhs.min(rhs)),
+        (lhs, rhs) => lhs.or(rhs),
+    }
+}
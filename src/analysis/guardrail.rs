@@ -0,0 +1,240 @@
+/*!
+Provides the inversion of an allow-list policy into an explicit-deny guardrail, suitable for use
+as a service control policy.
+*/
+
+use crate::model::{
+    Action, Effect, OrAny, Policy, QualifiedName, Resource, ResourceEntry, Sid, Statement, Version,
+};
+use crate::validate::{Finding, Findings};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+/// An allow statement has a condition, which is not carried into the guardrail; the guardrail
+/// permits the statement's actions whether or not the condition holds.
+pub const FINDING_GUARDRAIL_CONDITION_DROPPED: &str = "GuardrailConditionDropped";
+
+/// An allow statement uses `NotAction`, the set of allowed actions cannot be listed and so no
+/// actions are denied on its behalf.
+pub const FINDING_GUARDRAIL_NOT_ACTION: &str = "GuardrailNotAction";
+
+/// An allow statement uses `NotResource`, its actions are not restricted to any resources in
+/// the guardrail.
+pub const FINDING_GUARDRAIL_NOT_RESOURCE: &str = "GuardrailNotResource";
+
+/// An allow statement has a principal, which is not carried into the guardrail as service
+/// control policies apply to all principals in an account.
+pub const FINDING_GUARDRAIL_PRINCIPAL_DROPPED: &str = "GuardrailPrincipalDropped";
+
+/// An allowed action pattern overlaps one in another statement with different resources, the
+/// guardrail may deny requests that the original policy allows.
+pub const FINDING_GUARDRAIL_OVERLAPPING_ACTIONS: &str = "GuardrailOverlappingActions";
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Produce the explicit-deny guardrail for the allow-list `policy`, see
+/// [`invert_to_guardrail_with_findings`](fn.invert_to_guardrail_with_findings.html).
+///
+pub fn invert_to_guardrail(policy: &Policy) -> Policy {
+    invert_to_guardrail_with_findings(policy).0
+}
+
+///
+/// Produce the explicit-deny guardrail for the allow-list `policy`, with warnings for each
+/// statement that could not be inverted exactly.
+///
+/// The guardrail contains a statement denying, with `NotAction`, every action not allowed by
+/// any statement and, for each distinct set of allowed resources, a statement denying the
+/// actions allowed on that set on any other resource, with `NotResource`. Deny statements in
+/// `policy` are copied unchanged.
+///
+pub fn invert_to_guardrail_with_findings(policy: &Policy) -> (Policy, Findings) {
+    let mut findings = Findings::default();
+    let mut any_action = false;
+    let mut allowed: Vec<AllowedAction> = Default::default();
+    let mut denies: Vec<Statement> = Default::default();
+
+    for (index, statement) in policy.statement.iter().enumerate() {
+        if statement.is_deny() {
+            denies.push(statement.clone());
+            continue;
+        }
+        if statement.principal.is_some() {
+            findings.push(Finding::warning(
+                FINDING_GUARDRAIL_PRINCIPAL_DROPPED,
+                "the principal of an allow statement is not included in the guardrail",
+                Some(index),
+            ));
+        }
+        if statement.condition.is_some() {
+            findings.push(Finding::warning(
+                FINDING_GUARDRAIL_CONDITION_DROPPED,
+                "the condition of an allow statement is not included in the guardrail",
+                Some(index),
+            ));
+        }
+        let resources = match &statement.resource {
+            None | Some(Resource::Resource(OrAny::Any)) => None,
+            Some(Resource::Resource(OrAny::Some(resources))) => Some(resources.clone()),
+            Some(Resource::NotResource(_)) => {
+                findings.push(Finding::warning(
+                    FINDING_GUARDRAIL_NOT_RESOURCE,
+                    "NotResource cannot be inverted, the statement's actions are allowed on all resources",
+                    Some(index),
+                ));
+                None
+            }
+        };
+        match &statement.action {
+            Action::Action(OrAny::Any) => any_action = true,
+            Action::Action(OrAny::Some(actions)) => {
+                for action in actions {
+                    add_allowed(&mut allowed, action, &resources, index);
+                }
+            }
+            Action::NotAction(_) => {
+                any_action = true;
+                findings.push(Finding::warning(
+                    FINDING_GUARDRAIL_NOT_ACTION,
+                    "NotAction cannot be inverted, no actions are denied for this statement",
+                    Some(index),
+                ));
+            }
+        }
+    }
+
+    check_overlapping(&allowed, &mut findings);
+
+    let mut statements: Vec<Statement> = Default::default();
+    if !any_action && !allowed.is_empty() {
+        statements.push(deny(
+            "DenyUnlistedActions".to_string(),
+            Action::NotAction(OrAny::Some(
+                allowed.iter().map(|a| a.action.clone()).collect(),
+            )),
+            Resource::Resource(OrAny::Any),
+        ));
+    }
+    for (group, (actions, resources)) in resource_groups(&allowed).into_iter().enumerate() {
+        statements.push(deny(
+            format!("DenyUnlistedResources{}", group + 1),
+            Action::Action(OrAny::Some(actions)),
+            Resource::NotResource(OrAny::Some(resources)),
+        ));
+    }
+    statements.extend(denies);
+
+    (
+        Policy {
+            version: Some(Version::V2012),
+            id: None,
+            statement: statements,
+        },
+        findings,
+    )
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// An allowed action and the union of the resources it is allowed on, `None` meaning any
+/// resource, along with the statements that allow it.
+///
+#[derive(Debug)]
+struct AllowedAction {
+    action: QualifiedName,
+    resources: Option<Vec<ResourceEntry>>,
+    statements: Vec<usize>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn add_allowed(
+    allowed: &mut Vec<AllowedAction>,
+    action: &QualifiedName,
+    resources: &Option<Vec<ResourceEntry>>,
+    index: usize,
+) {
+    if let Some(existing) = allowed.iter_mut().find(|a| &a.action == action) {
+        existing.statements.push(index);
+        match (&mut existing.resources, resources) {
+            (Some(existing), Some(resources)) => {
+                for resource in resources {
+                    if !existing.contains(resource) {
+                        existing.push(resource.clone());
+                    }
+                }
+            }
+            (existing, _) => *existing = None,
+        }
+    } else {
+        allowed.push(AllowedAction {
+            action: action.clone(),
+            resources: resources.clone(),
+            statements: vec![index],
+        });
+    }
+}
+
+fn check_overlapping(allowed: &[AllowedAction], findings: &mut Findings) {
+    for (i, lhs) in allowed.iter().enumerate() {
+        for rhs in allowed.iter().skip(i + 1) {
+            if (lhs.action.matches(&rhs.action) || rhs.action.matches(&lhs.action))
+                && !match (&lhs.resources, &rhs.resources) {
+                    (None, None) => true,
+                    (Some(lhs), Some(rhs)) => same_resources(lhs, rhs),
+                    _ => false,
+                }
+            {
+                findings.push(Finding::warning(
+                    FINDING_GUARDRAIL_OVERLAPPING_ACTIONS,
+                    format!(
+                        "actions {} and {} overlap but are allowed on different resources",
+                        lhs.action, rhs.action
+                    ),
+                    rhs.statements.first().copied(),
+                ));
+            }
+        }
+    }
+}
+
+fn resource_groups(allowed: &[AllowedAction]) -> Vec<(Vec<QualifiedName>, Vec<ResourceEntry>)> {
+    let mut groups: Vec<(Vec<QualifiedName>, Vec<ResourceEntry>)> = Default::default();
+    for allowed in allowed {
+        if let Some(resources) = &allowed.resources {
+            match groups
+                .iter_mut()
+                .find(|(_, group)| same_resources(group, resources))
+            {
+                Some((actions, _)) => actions.push(allowed.action.clone()),
+                None => groups.push((vec![allowed.action.clone()], resources.clone())),
+            }
+        }
+    }
+    groups
+}
+
+fn same_resources(lhs: &[ResourceEntry], rhs: &[ResourceEntry]) -> bool {
+    lhs.iter().all(|r| rhs.contains(r)) && rhs.iter().all(|r| lhs.contains(r))
+}
+
+fn deny(sid: String, action: Action, resource: Resource) -> Statement {
+    Statement {
+        sid: Some(Sid::new_unchecked(sid)),
+        principal: None,
+        effect: Effect::Deny,
+        action,
+        resource: Some(resource),
+        condition: None,
+    }
+}
@@ -0,0 +1,147 @@
+/*!
+Provides analyses that derive new policies from existing ones.
+
+[`invert_to_guardrail`](fn.invert_to_guardrail.html) takes an allow-list policy, one that
+grants a set of actions on a set of resources, and produces the explicit-deny policy that
+denies everything else. Such a guardrail is suitable for use as an AWS Organizations service
+control policy (SCP), which can only restrict and never grant permissions.
+
+Not every allow-list can be inverted exactly, so
+[`invert_to_guardrail_with_findings`](fn.invert_to_guardrail_with_findings.html) also returns
+a warning for each statement where the guardrail is more, or less, permissive than the
+original.
+
+[`validity_window`](fn.validity_window.html) reports when a policy is effective, from the
+`Date` conditions its allow statements place on `aws:CurrentTime` or `aws:EpochTime`, and
+[`check_expired`](fn.check_expired.html) warns of statements that can no longer apply.
+
+[`risk_score`](fn.risk_score.html) scores how broadly a policy grants access, from factors
+such as wildcard actions, resources, or principals, missing conditions, and
+[powerful actions](constant.POWERFUL_ACTIONS.html), with the contribution of each statement.
+
+[`check_sensitive_actions`](fn.check_sensitive_actions.html) warns of statements that allow any
+of the [`ESCALATION_ACTIONS`](constant.ESCALATION_ACTIONS.html), which can be used to gain
+further privileges, and of policies that together allow every action of one of the known
+[`ESCALATION_PATHS`](constant.ESCALATION_PATHS.html).
+
+[`check_contradictions`](fn.check_contradictions.html) reports statements that can never take
+effect: allow statements whose actions and resources are all denied by another statement,
+statements none of whose actions apply to any of their resources, and duplicate statements.
+
+With the feature `service_config`, [`check_access_levels`](fn.check_access_levels.html) warns
+of statements that allow actions with `Write` or `Permissions management` access, see
+[`AccessLevel`](../service/actions/enum.AccessLevel.html), on any resource, and
+[`check_dependent_actions`](fn.check_dependent_actions.html) notes actions that are allowed
+without the other actions they depend on.
+
+Also with `service_config`, [`rewrite_not_elements`](fn.rewrite_not_elements.html) rewrites
+statements using `NotAction` or `NotResource`, which many organizations forbid, into ones listing
+the complementary actions or resource types of the services involved, from the known actions
+and the given service configurations. The rewritten policy is never more permissive than the
+original, and a finding reports each statement whose rewrite is not exactly equivalent.
+
+[`stats`](fn.stats.html) counts the statements, actions, services, resources, and wildcards of
+a policy, and its size against the limit for managed policies, for inventories of many
+policies.
+
+[`corpus_stats`](fn.corpus_stats.html) aggregates counts over a corpus of policies, such as
+every policy of an organization, to inform internal standards: how often each condition operator
+and family of actions is used, and the rate at which actions, resources, and principals are
+wildcards. The statistics are anonymized, they name only services, resource types, action verbs,
+and operators, the parts of a policy that [`Policy::redact`](../model/struct.Policy.html#method.redact)
+keeps, and never account IDs or resource names.
+
+[`who_can`](fn.who_can.html) reports, across the users and roles of an account as read by
+[`io::read_authorization_details`](../io/fn.read_authorization_details.html), the principals,
+policies, and statements that allow an action on a resource, and
+[`permissions_matrix`](fn.permissions_matrix.html) tabulates the access of every principal to
+a set of actions, such as those of a service.
+
+# Example
+
+```rust
+use aws_iam::analysis::invert_to_guardrail;
+use aws_iam::arn::ARN;
+use aws_iam::model::Policy;
+use std::str::FromStr;
+
+let policy = Policy::from_str(
+    r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject","Resource":"arn:aws:s3:::bucket/key"}]}"#,
+)
+.unwrap();
+let guardrail = invert_to_guardrail(&policy);
+
+let other = ARN::from_str("arn:aws:s3:::other/key").unwrap();
+assert!(guardrail
+    .statement
+    .iter()
+    .any(|st| st.is_deny() && st.action().matches("s3:PutObject")));
+assert!(guardrail
+    .statement
+    .iter()
+    .any(|st| st.action().matches("s3:GetObject") && st.applies_to_resource(&other)));
+```
+*/
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn has_wildcards(s: &str) -> bool {
+    s.contains('*') || s.contains('?')
+}
+
+// ------------------------------------------------------------------------------------------------
+// Modules
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(feature = "service_config")]
+mod access_level;
+#[cfg(feature = "service_config")]
+pub use access_level::{check_access_levels, FINDING_WRITE_ACCESS_ANY_RESOURCE};
+
+mod contradictions;
+pub use contradictions::{
+    check_contradictions, FINDING_DUPLICATE_STATEMENT, FINDING_SHADOWED_ALLOW,
+};
+
+#[cfg(feature = "service_config")]
+mod dependent_actions;
+#[cfg(feature = "service_config")]
+pub use dependent_actions::{check_dependent_actions, FINDING_MISSING_DEPENDENT_ACTION};
+
+mod expiry;
+pub use expiry::{check_expired, validity_window, FINDING_STATEMENT_EXPIRED};
+
+mod guardrail;
+pub use guardrail::{
+    invert_to_guardrail, invert_to_guardrail_with_findings, FINDING_GUARDRAIL_CONDITION_DROPPED,
+    FINDING_GUARDRAIL_NOT_ACTION, FINDING_GUARDRAIL_NOT_RESOURCE,
+    FINDING_GUARDRAIL_OVERLAPPING_ACTIONS, FINDING_GUARDRAIL_PRINCIPAL_DROPPED,
+};
+
+mod principals;
+pub use principals::{
+    permissions_matrix, who_can, Access, MatrixRow, PermissionsMatrix, PrincipalMatch,
+};
+
+#[cfg(feature = "service_config")]
+mod rewrite;
+#[cfg(feature = "service_config")]
+pub use rewrite::{
+    rewrite_not_elements, FINDING_REWRITE_NOT_EQUIVALENT, FINDING_REWRITE_NOT_POSSIBLE,
+};
+
+mod risk;
+pub use risk::{
+    check_sensitive_actions, risk_score, EscalationPath, RiskFactor, RiskReport, StatementRisk,
+    ESCALATION_ACTIONS, ESCALATION_PATHS, FINDING_ESCALATION_PATH, FINDING_SENSITIVE_ACTION,
+    POWERFUL_ACTIONS,
+};
+
+mod shadowing;
+
+mod stats;
+#[cfg(feature = "fs")]
+pub use stats::corpus_stats_for_directory;
+pub use stats::{corpus_stats, stats, CorpusStats, PolicyStats, MANAGED_POLICY_SIZE_LIMIT};
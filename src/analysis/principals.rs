@@ -0,0 +1,212 @@
+/*!
+Provides the principals of an account that are allowed an action on a resource, and the
+matrix of the access of every principal to a set of actions.
+*/
+
+use crate::arn::ARN;
+use crate::io::{AccountAuthorizationDetails, PolicySource};
+use crate::model::{OrAny, Resource, Sid, Statement};
+use serde::Serialize;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A statement in an identity policy that allows a principal to perform an action on a
+/// resource, see [`who_can`](fn.who_can.html).
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrincipalMatch {
+    /// The ARN of the user or role.
+    pub principal: String,
+    /// The policy containing the statement.
+    pub policy: PolicySource,
+    /// The index of the statement in the policy.
+    pub statement: usize,
+    /// The `Sid` of the statement, if it has one.
+    pub sid: Option<Sid>,
+    /// `true` if the statement has conditions, which are not evaluated, and so may only allow
+    /// some requests.
+    pub conditional: bool,
+}
+
+///
+/// The access of a principal to an action, a cell of a
+/// [`PermissionsMatrix`](struct.PermissionsMatrix.html).
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Access {
+    /// A statement without conditions allows the action.
+    Allow,
+    /// Only statements with conditions allow the action.
+    Conditional,
+    /// No statement allows the action, or a statement denies it.
+    Deny,
+}
+
+///
+/// The access of a set of principals to a set of actions, see
+/// [`permissions_matrix`](fn.permissions_matrix.html).
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PermissionsMatrix {
+    /// The actions, the columns of the matrix.
+    pub actions: Vec<String>,
+    /// The rows of the matrix, for each principal and resource.
+    pub rows: Vec<MatrixRow>,
+}
+
+///
+/// A row of a [`PermissionsMatrix`](struct.PermissionsMatrix.html).
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MatrixRow {
+    /// The ARN of the user or role.
+    pub principal: String,
+    /// The resource, if the matrix was computed for specific resources.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource: Option<String>,
+    /// The access to each of the matrix's actions, in the same order.
+    pub access: Vec<Access>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns, for each user and role in `details`, the statements of its identity policies that
+/// allow `action` on `resource`; answering the question "who can perform this action on this
+/// resource?".
+///
+/// A principal is not reported if one of its identity policies has an unconditional deny
+/// statement for the action and resource, or if it has a permissions boundary that does not
+/// allow them. Conditions are not evaluated, statements with conditions are reported and marked
+/// as `conditional`. Resource policies, service control policies, and session policies are not
+/// part of the account details and are not considered.
+///
+pub fn who_can(
+    details: &AccountAuthorizationDetails,
+    action: &str,
+    resource: &ARN,
+) -> Vec<PrincipalMatch> {
+    details
+        .principal_arns()
+        .flat_map(|principal| principal_grants(details, principal, action, Some(resource)))
+        .collect()
+}
+
+///
+/// Returns the access of each user and role in `details`, in turn with each of `resources`, to
+/// each of `actions`; a row of the matrix for each principal and resource. If `resources` is
+/// empty there is one row for each principal, and an action is allowed if it is allowed on any
+/// resource. The rules of [`who_can`](fn.who_can.html) apply to each cell.
+///
+/// The actions may be those of a service, see `syntax::all_actions_for_service`, and the matrix
+/// written in a tabular form, see `io::write_matrix_csv`.
+///
+pub fn permissions_matrix<S>(
+    details: &AccountAuthorizationDetails,
+    actions: &[S],
+    resources: &[ARN],
+) -> PermissionsMatrix
+where
+    S: AsRef<str>,
+{
+    let resources: Vec<Option<&ARN>> = if resources.is_empty() {
+        vec![None]
+    } else {
+        resources.iter().map(Some).collect()
+    };
+    PermissionsMatrix {
+        actions: actions.iter().map(|a| a.as_ref().to_string()).collect(),
+        rows: details
+            .principal_arns()
+            .flat_map(|principal| {
+                resources.iter().map(move |resource| MatrixRow {
+                    principal: principal.to_string(),
+                    resource: resource.map(ARN::to_string),
+                    access: actions
+                        .iter()
+                        .map(|action| {
+                            let grants =
+                                principal_grants(details, principal, action.as_ref(), *resource);
+                            if grants.iter().any(|grant| !grant.conditional) {
+                                Access::Allow
+                            } else if grants.is_empty() {
+                                Access::Deny
+                            } else {
+                                Access::Conditional
+                            }
+                        })
+                        .collect(),
+                })
+            })
+            .collect(),
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The statements of `principal`'s identity policies that allow `action` on `resource`, or on
+/// any resource if `None`; empty if the action is denied. A deny statement only applies to any
+/// resource if it denies every resource.
+///
+fn principal_grants(
+    details: &AccountAuthorizationDetails,
+    principal: &str,
+    action: &str,
+    resource: Option<&ARN>,
+) -> Vec<PrincipalMatch> {
+    let grants = |statement: &Statement| {
+        statement.allows_action(action)
+            && resource
+                .map(|resource| statement.applies_to_resource(resource))
+                .unwrap_or(true)
+    };
+    let denies = |statement: &Statement| {
+        statement.is_deny()
+            && statement.condition.is_none()
+            && statement.action.matches(action)
+            && match resource {
+                Some(resource) => statement.applies_to_resource(resource),
+                None => matches!(
+                    statement.resource,
+                    None | Some(Resource::Resource(OrAny::Any))
+                ),
+            }
+    };
+    let policies = details.identity_policy_sources(principal);
+    if policies
+        .iter()
+        .flat_map(|(_, policy)| policy.statement.iter())
+        .any(denies)
+    {
+        return Default::default();
+    }
+    if let Some(boundary) = details.permissions_boundary(principal) {
+        if !boundary.statement.iter().any(grants) || boundary.statement.iter().any(denies) {
+            return Default::default();
+        }
+    }
+    let mut matches: Vec<PrincipalMatch> = Default::default();
+    for (source, policy) in policies {
+        for (index, statement) in policy.statement.iter().enumerate() {
+            if grants(statement) {
+                matches.push(PrincipalMatch {
+                    principal: principal.to_string(),
+                    policy: source.clone(),
+                    statement: index,
+                    sid: statement.sid.clone(),
+                    conditional: statement.condition.is_some(),
+                });
+            }
+        }
+    }
+    matches
+}
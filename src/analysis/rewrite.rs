@@ -0,0 +1,279 @@
+/*!
+Provides the rewriting of statements using `NotAction` or `NotResource` into ones listing the
+complementary actions or resource types.
+*/
+
+use crate::analysis::has_wildcards;
+use crate::arn::ArnParseMode;
+use crate::model::redaction::arn_resource_type;
+use crate::model::{Action, OrAny, Policy, QualifiedName, Resource, ResourceEntry, Statement};
+use crate::service::actions::{service_metadata, ActionMetadata};
+use crate::service::ServiceConfig;
+use crate::syntax::POLICY_WILDCARD_VALUE;
+use crate::validate::{Finding, Findings};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+/// A `NotAction` or `NotResource` statement was rewritten, but the rewrite is not exactly
+/// equivalent; an allow statement allows less than the original, a deny statement denies more.
+pub const FINDING_REWRITE_NOT_EQUIVALENT: &str = "RewriteNotEquivalent";
+
+/// A `NotAction` or `NotResource` statement could not be rewritten without making the policy
+/// more permissive, or without a catalog of its services, and is unchanged.
+pub const FINDING_REWRITE_NOT_POSSIBLE: &str = "RewriteNotPossible";
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns `policy` with statements using `NotAction` or `NotResource` rewritten into
+/// equivalent statements using `Action` and `Resource`, along with findings for each statement
+/// whose rewrite is not exactly equivalent, or that could not be rewritten.
+///
+/// A `NotAction` list is replaced by the known actions, see `service::actions`, of the
+/// services it names that it does not match. `NotAction` also matches every action of every
+/// other service, and so the rewrite is only exact if the statement's resources all belong to
+/// the named services. Otherwise an allow statement is rewritten to allow less, and a deny
+/// statement, which would deny less, is left unchanged.
+///
+/// A `NotResource` list is replaced by a wildcard ARN for each resource type, of the
+/// configuration in `services` for each service of the statement's actions, that none of the
+/// listed resources has the form of. Where a listed resource excludes only some resources of a
+/// type the complement cannot be listed; an allow statement omits the type, and a deny
+/// statement includes it.
+///
+/// Action and resource catalogs are not complete, and the rewrite is only as complete as they
+/// are; statements naming services for which there is no catalog are left unchanged.
+///
+pub fn rewrite_not_elements(policy: &Policy, services: &[ServiceConfig]) -> (Policy, Findings) {
+    let mut findings = Findings::default();
+    let mut statements: Vec<Statement> = Default::default();
+    for (index, statement) in policy.statement.iter().enumerate() {
+        let mut statement = statement.clone();
+        if let Action::NotAction(OrAny::Some(excluded)) = &statement.action {
+            if let Some(actions) = rewrite_not_action(&statement, excluded, index, &mut findings) {
+                statement.action = Action::Action(OrAny::Some(actions));
+            }
+        }
+        if let (
+            Action::Action(OrAny::Some(actions)),
+            Some(Resource::NotResource(OrAny::Some(excluded))),
+        ) = (&statement.action, &statement.resource)
+        {
+            if let Some(resources) = rewrite_not_resource(
+                statement.is_allow(),
+                actions,
+                excluded,
+                services,
+                index,
+                &mut findings,
+            ) {
+                statement.resource = Some(Resource::Resource(OrAny::Some(resources)));
+            }
+        }
+        statements.push(statement);
+    }
+    (
+        Policy {
+            statement: statements,
+            ..policy.clone()
+        },
+        findings,
+    )
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const PARTITION_PLACEHOLDER: &str = "${Partition}";
+
+const DEFAULT_PARTITION: &str = "aws";
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn rewrite_not_action(
+    statement: &Statement,
+    excluded: &[QualifiedName],
+    index: usize,
+    findings: &mut Findings,
+) -> Option<Vec<QualifiedName>> {
+    let mut namespaces: Vec<String> = Default::default();
+    for action in excluded {
+        let namespace = action.namespace().to_string().to_lowercase();
+        if !namespaces.contains(&namespace) {
+            namespaces.push(namespace);
+        }
+    }
+    let mut actions: Vec<QualifiedName> = Default::default();
+    for namespace in &namespaces {
+        match service_metadata(namespace) {
+            Some(metadata) => actions.extend(
+                metadata
+                    .iter()
+                    .map(ActionMetadata::qualified_name)
+                    .filter(|action| statement.action.matches(action)),
+            ),
+            None => {
+                findings.push(Finding::warning(
+                    FINDING_REWRITE_NOT_POSSIBLE,
+                    format!(
+                        "NotAction was not rewritten, the actions of the service {} are not known",
+                        namespace
+                    ),
+                    Some(index),
+                ));
+                return None;
+            }
+        }
+    }
+    if actions.is_empty() {
+        findings.push(Finding::warning(
+            FINDING_REWRITE_NOT_POSSIBLE,
+            "NotAction was not rewritten, it excludes every known action of its services",
+            Some(index),
+        ));
+        return None;
+    }
+    let exact = match &statement.resource {
+        Some(Resource::Resource(OrAny::Some(resources))) => resources.iter().all(|resource| {
+            arn_resource_type(&resource.to_string())
+                .map(|resource_type| {
+                    let service = resource_type.split(':').next().unwrap_or_default();
+                    namespaces.iter().any(|namespace| namespace == service)
+                })
+                .unwrap_or_default()
+        }),
+        _ => false,
+    };
+    if exact {
+        Some(actions)
+    } else if statement.is_allow() {
+        findings.push(Finding::warning(
+            FINDING_REWRITE_NOT_EQUIVALENT,
+            format!(
+                "NotAction was rewritten to the actions of {} only, the original also allowed the actions of every other service",
+                namespaces.join(", ")
+            ),
+            Some(index),
+        ));
+        Some(actions)
+    } else {
+        findings.push(Finding::warning(
+            FINDING_REWRITE_NOT_POSSIBLE,
+            "NotAction was not rewritten, it denies the actions of services other than those it names, which cannot be listed",
+            Some(index),
+        ));
+        None
+    }
+}
+
+fn rewrite_not_resource(
+    is_allow: bool,
+    actions: &[QualifiedName],
+    excluded: &[ResourceEntry],
+    services: &[ServiceConfig],
+    index: usize,
+    findings: &mut Findings,
+) -> Option<Vec<ResourceEntry>> {
+    let excluded: Vec<String> = excluded.iter().map(ResourceEntry::to_string).collect();
+    let partition = excluded
+        .iter()
+        .find_map(|resource| resource.strip_prefix("arn:")?.split(':').next())
+        .filter(|partition| !has_wildcards(partition))
+        .unwrap_or(DEFAULT_PARTITION);
+    let mut resources: Vec<ResourceEntry> = Default::default();
+    let mut partial: Vec<String> = Default::default();
+    let mut namespaces: Vec<String> = Default::default();
+    for action in actions {
+        let namespace = action.namespace().to_string().to_lowercase();
+        if namespaces.contains(&namespace) {
+            continue;
+        }
+        let service = match services
+            .iter()
+            .find(|service| service.namespace().eq_ignore_ascii_case(&namespace))
+        {
+            Some(service) => service,
+            None => {
+                findings.push(Finding::warning(
+                    FINDING_REWRITE_NOT_POSSIBLE,
+                    format!(
+                        "NotResource was not rewritten, the resource types of the service {} are not known",
+                        namespace
+                    ),
+                    Some(index),
+                ));
+                return None;
+            }
+        };
+        for resource_type in service.resource_types() {
+            let wildcard = resource_type_wildcard(&resource_type.arn_pattern, partition);
+            if excluded.iter().any(|resource| resource == &wildcard) {
+                continue;
+            }
+            if excluded.iter().any(|resource| {
+                resource == POLICY_WILDCARD_VALUE || resource_type.matches(resource)
+            }) {
+                partial.push(format!("{}:{}", namespace, resource_type.name));
+                if is_allow {
+                    continue;
+                }
+            }
+            match ResourceEntry::parse(&wildcard, ArnParseMode::Permissive) {
+                Ok(resource) => resources.push(resource),
+                Err(_) => partial.push(format!("{}:{}", namespace, resource_type.name)),
+            }
+        }
+        namespaces.push(namespace);
+    }
+    if resources.is_empty() {
+        findings.push(Finding::warning(
+            FINDING_REWRITE_NOT_POSSIBLE,
+            "NotResource was not rewritten, it excludes resources of every resource type of its services",
+            Some(index),
+        ));
+        return None;
+    }
+    if !partial.is_empty() {
+        findings.push(Finding::warning(
+            FINDING_REWRITE_NOT_EQUIVALENT,
+            format!(
+                "NotResource excludes only some resources of the types {}, which are {} the rewritten statement",
+                partial.join(", "),
+                if is_allow { "omitted from" } else { "included in" }
+            ),
+            Some(index),
+        ));
+    }
+    Some(resources)
+}
+
+///
+/// The ARN `pattern` of a resource type with its partition placeholder replaced by `partition`
+/// and every other placeholder by a wildcard, such as `arn:aws:s3:::*/*`.
+///
+fn resource_type_wildcard(pattern: &str, partition: &str) -> String {
+    let mut wildcard = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+    while let Some(start) = rest.find("${") {
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => break,
+        };
+        wildcard.push_str(&rest[..start]);
+        wildcard.push_str(if &rest[start..=end] == PARTITION_PLACEHOLDER {
+            partition
+        } else {
+            POLICY_WILDCARD_VALUE
+        });
+        rest = &rest[end + 1..];
+    }
+    wildcard.push_str(rest);
+    wildcard
+}
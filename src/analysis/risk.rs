@@ -0,0 +1,363 @@
+/*!
+Provides a score of how broadly a policy grants access, and warnings for statements and
+policies that allow privilege escalation.
+*/
+
+use crate::model::{Action, OrAny, Policy, QualifiedName, Resource, Statement};
+use crate::validate::{Finding, Findings};
+use std::fmt::{Display, Formatter};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A property of an allow statement that broadens the access it grants, see
+/// [`risk_score`](fn.risk_score.html).
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RiskFactor {
+    /// The statement allows any action, `"Action": "*"`.
+    AnyAction,
+    /// The statement allows every action except those listed, with `NotAction`.
+    NotAction,
+    /// The statement applies to any resource, `"Resource": "*"` or no resource.
+    AnyResource,
+    /// The statement applies to every resource except those listed, with `NotResource`.
+    NotResource,
+    /// The statement allows everyone, see `Principal::is_public`.
+    AnyPrincipal,
+    /// The statement has no conditions.
+    NoCondition,
+    /// The statement allows an action matching one of the
+    /// [`POWERFUL_ACTIONS`](constant.POWERFUL_ACTIONS.html).
+    PowerfulAction(&'static str),
+}
+
+///
+/// A combination of actions that, when all are allowed, lets a principal gain the permissions
+/// of another; see [`ESCALATION_PATHS`](constant.ESCALATION_PATHS.html).
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EscalationPath {
+    /// A short name for the path.
+    pub name: &'static str,
+    /// The actions that must all be allowed.
+    pub actions: &'static [&'static str],
+}
+
+///
+/// The risk factors of a single statement and its contribution to the score of the policy.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatementRisk {
+    /// The index of the statement within the policy.
+    pub statement: usize,
+    /// The `Sid` of the statement, if any.
+    pub sid: Option<String>,
+    /// The factors found in the statement; deny statements have none.
+    pub factors: Vec<RiskFactor>,
+    /// The sum of the weights of `factors`.
+    pub score: u32,
+}
+
+///
+/// The risk score of a policy, the sum of the scores of its statements.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RiskReport {
+    /// The score of the policy; zero if no statement has any risk factor.
+    pub score: u32,
+    /// The contribution of each statement, in the order of the policy.
+    pub statements: Vec<StatementRisk>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+/// The actions that, when allowed, contribute a
+/// [`RiskFactor::PowerfulAction`](enum.RiskFactor.html#variant.PowerfulAction); these allow
+/// privilege escalation or access to protected data.
+pub const POWERFUL_ACTIONS: &[&str] = &["iam:*", "sts:AssumeRole", "kms:Decrypt"];
+
+/// Actions that can be used to escalate privileges, by changing permissions, creating
+/// credentials, or passing a role to a service that runs code.
+pub const ESCALATION_ACTIONS: &[&str] = &[
+    "iam:AddUserToGroup",
+    "iam:AttachGroupPolicy",
+    "iam:AttachRolePolicy",
+    "iam:AttachUserPolicy",
+    "iam:CreateAccessKey",
+    "iam:CreateLoginProfile",
+    "iam:CreatePolicyVersion",
+    "iam:PassRole",
+    "iam:PutGroupPolicy",
+    "iam:PutRolePolicy",
+    "iam:PutUserPolicy",
+    "iam:SetDefaultPolicyVersion",
+    "iam:UpdateAssumeRolePolicy",
+    "iam:UpdateLoginProfile",
+    "sts:AssumeRole",
+    "cloudformation:CreateStack",
+    "datapipeline:CreatePipeline",
+    "datapipeline:PutPipelineDefinition",
+    "ec2:RunInstances",
+    "glue:CreateDevEndpoint",
+    "glue:UpdateDevEndpoint",
+    "lambda:CreateEventSourceMapping",
+    "lambda:CreateFunction",
+    "lambda:InvokeFunction",
+    "lambda:UpdateFunctionCode",
+];
+
+/// The known combinations of actions that allow a principal to pass a role to a service and
+/// then run code, or create resources, with that role's permissions.
+pub const ESCALATION_PATHS: &[EscalationPath] = &[
+    EscalationPath {
+        name: "PassRoleToEc2",
+        actions: &["iam:PassRole", "ec2:RunInstances"],
+    },
+    EscalationPath {
+        name: "PassRoleToLambda",
+        actions: &[
+            "iam:PassRole",
+            "lambda:CreateFunction",
+            "lambda:InvokeFunction",
+        ],
+    },
+    EscalationPath {
+        name: "PassRoleToLambdaEventSource",
+        actions: &[
+            "iam:PassRole",
+            "lambda:CreateFunction",
+            "lambda:CreateEventSourceMapping",
+        ],
+    },
+    EscalationPath {
+        name: "PassRoleToGlue",
+        actions: &["iam:PassRole", "glue:CreateDevEndpoint"],
+    },
+    EscalationPath {
+        name: "PassRoleToCloudFormation",
+        actions: &["iam:PassRole", "cloudformation:CreateStack"],
+    },
+    EscalationPath {
+        name: "PassRoleToDataPipeline",
+        actions: &[
+            "iam:PassRole",
+            "datapipeline:CreatePipeline",
+            "datapipeline:PutPipelineDefinition",
+        ],
+    },
+];
+
+/// An allow statement allows one or more of the
+/// [`ESCALATION_ACTIONS`](constant.ESCALATION_ACTIONS.html).
+pub const FINDING_SENSITIVE_ACTION: &str = "SensitiveAction";
+
+/// The allow statements of a policy together allow every action of one of the
+/// [`ESCALATION_PATHS`](constant.ESCALATION_PATHS.html).
+pub const FINDING_ESCALATION_PATH: &str = "EscalationPath";
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Score how broadly `policy` grants access. Each allow statement contributes the sum of the
+/// [weights](enum.RiskFactor.html#method.weight) of its risk factors; deny statements, which
+/// only remove access, contribute nothing. The score is intended to rank policies and track
+/// changes over time, not as an absolute measure.
+///
+pub fn risk_score(policy: &Policy) -> RiskReport {
+    let statements: Vec<StatementRisk> = policy
+        .statement
+        .iter()
+        .enumerate()
+        .map(|(index, statement)| {
+            let factors = if statement.is_deny() {
+                Default::default()
+            } else {
+                risk_factors(statement)
+            };
+            StatementRisk {
+                statement: index,
+                sid: statement.sid.as_ref().map(|sid| sid.to_string()),
+                score: factors.iter().map(RiskFactor::weight).sum(),
+                factors,
+            }
+        })
+        .collect();
+    RiskReport {
+        score: statements.iter().map(|statement| statement.score).sum(),
+        statements,
+    }
+}
+
+///
+/// Returns a warning for each allow statement of `policy` that allows any of the
+/// [`ESCALATION_ACTIONS`](constant.ESCALATION_ACTIONS.html), and for each of the
+/// [`ESCALATION_PATHS`](constant.ESCALATION_PATHS.html) whose actions are all allowed by the
+/// policy's statements together. An action is not counted if a deny statement without
+/// conditions denies it on all resources; resources and other conditions are not considered,
+/// so a path may be reported whose actions apply to unrelated resources.
+///
+pub fn check_sensitive_actions(policy: &Policy) -> Findings {
+    let mut findings = Findings::default();
+    let denied = |action: &str| {
+        policy.statement.iter().any(|statement| {
+            statement.is_deny()
+                && statement.condition.is_none()
+                && matches!(
+                    statement.resource,
+                    None | Some(Resource::Resource(OrAny::Any))
+                )
+                && statement.action.matches(action)
+        })
+    };
+    let allowed_by = |action: &str| -> Vec<usize> {
+        if denied(action) {
+            return Default::default();
+        }
+        policy
+            .statement
+            .iter()
+            .enumerate()
+            .filter(|(_, statement)| !statement.is_deny() && statement.action.matches(action))
+            .map(|(index, _)| index)
+            .collect()
+    };
+
+    for (index, statement) in policy.statement.iter().enumerate() {
+        if statement.is_deny() {
+            continue;
+        }
+        let actions: Vec<&str> = ESCALATION_ACTIONS
+            .iter()
+            .copied()
+            .filter(|action| statement.action.matches(action) && !denied(action))
+            .collect();
+        if !actions.is_empty() {
+            findings.push(Finding::warning(
+                FINDING_SENSITIVE_ACTION,
+                format!(
+                    "the statement allows actions that may escalate privileges: {}",
+                    actions.join(", ")
+                ),
+                Some(index),
+            ));
+        }
+    }
+
+    for path in ESCALATION_PATHS {
+        let statements: Vec<Vec<usize>> = path
+            .actions
+            .iter()
+            .map(|action| allowed_by(action))
+            .collect();
+        if statements.iter().all(|statements| !statements.is_empty()) {
+            let mut indices: Vec<usize> = statements.into_iter().flatten().collect();
+            indices.sort_unstable();
+            indices.dedup();
+            findings.push(Finding::warning(
+                FINDING_ESCALATION_PATH,
+                format!(
+                    "the policy allows the escalation path {} ({}) in statements {:?}",
+                    path.name,
+                    path.actions.join(", "),
+                    indices
+                ),
+                None,
+            ));
+        }
+    }
+    findings
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for RiskFactor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AnyAction => write!(f, "allows any action"),
+            Self::NotAction => write!(f, "allows all but the listed actions"),
+            Self::AnyResource => write!(f, "applies to any resource"),
+            Self::NotResource => write!(f, "applies to all but the listed resources"),
+            Self::AnyPrincipal => write!(f, "allows any principal"),
+            Self::NoCondition => write!(f, "has no conditions"),
+            Self::PowerfulAction(action) => write!(f, "allows the powerful action {}", action),
+        }
+    }
+}
+
+impl RiskFactor {
+    /// The contribution of this factor to the score of a statement.
+    pub fn weight(&self) -> u32 {
+        match self {
+            Self::AnyAction => 40,
+            Self::NotAction => 30,
+            Self::AnyResource => 20,
+            Self::NotResource => 15,
+            Self::AnyPrincipal => 50,
+            Self::NoCondition => 10,
+            Self::PowerfulAction(_) => 25,
+        }
+    }
+}
+
+impl RiskReport {
+    /// Returns the statements that have at least one risk factor.
+    pub fn risky_statements(&self) -> impl Iterator<Item = &StatementRisk> {
+        self.statements
+            .iter()
+            .filter(|statement| !statement.factors.is_empty())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn risk_factors(statement: &Statement) -> Vec<RiskFactor> {
+    let mut factors: Vec<RiskFactor> = Default::default();
+    match &statement.action {
+        Action::Action(OrAny::Any) => factors.push(RiskFactor::AnyAction),
+        Action::NotAction(_) => factors.push(RiskFactor::NotAction),
+        Action::Action(OrAny::Some(actions)) => {
+            for powerful in POWERFUL_ACTIONS {
+                let powerful_name = QualifiedName::new_unchecked(*powerful);
+                if actions
+                    .iter()
+                    .any(|action| action.matches(&powerful_name) || powerful_name.matches(action))
+                {
+                    factors.push(RiskFactor::PowerfulAction(powerful));
+                }
+            }
+        }
+    }
+    match &statement.resource {
+        None | Some(Resource::Resource(OrAny::Any)) => factors.push(RiskFactor::AnyResource),
+        Some(Resource::NotResource(_)) => factors.push(RiskFactor::NotResource),
+        Some(Resource::Resource(OrAny::Some(_))) => {}
+    }
+    if statement
+        .principal
+        .as_ref()
+        .map(|principal| principal.is_public())
+        .unwrap_or_default()
+    {
+        factors.push(RiskFactor::AnyPrincipal);
+    }
+    if statement
+        .condition
+        .as_ref()
+        .map(|condition| condition.is_empty())
+        .unwrap_or(true)
+    {
+        factors.push(RiskFactor::NoCondition);
+    }
+    factors
+}
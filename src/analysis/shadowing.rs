@@ -0,0 +1,60 @@
+/*!
+Determines whether a deny statement shadows an allow statement, denying every one of its
+actions on every one of its resources, from the patterns of both statements alone.
+*/
+
+use crate::analysis::has_wildcards;
+use crate::model::{Action, OrAny, Resource, ResourceEntry, Statement};
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns `true` if the deny statement `deny` shadows the allow statement `allow`, see
+/// [`check_contradictions`](../fn.check_contradictions.html).
+///
+pub(crate) fn shadows(deny: &Statement, allow: &Statement) -> bool {
+    let principal_covered = match (&deny.principal, &allow.principal) {
+        (None, _) => true,
+        (Some(deny), _) if deny.is_any() => true,
+        (Some(deny), Some(allow)) => deny == allow,
+        (Some(_), None) => false,
+    };
+    principal_covered
+        && deny.condition.is_none()
+        && actions_covered(&deny.action, &allow.action)
+        && resources_covered(&deny.resource, &allow.resource)
+}
+
+fn actions_covered(deny: &Action, allow: &Action) -> bool {
+    match (deny, allow) {
+        (Action::Action(OrAny::Any), _) => true,
+        (Action::Action(OrAny::Some(denied)), Action::Action(OrAny::Some(allowed))) => allowed
+            .iter()
+            .all(|allowed| denied.iter().any(|denied| denied.matches(allowed))),
+        (Action::NotAction(OrAny::Some(excluded)), Action::Action(OrAny::Some(allowed))) => {
+            allowed.iter().all(|allowed| {
+                !has_wildcards(allowed)
+                    && !excluded.iter().any(|excluded| excluded.matches(allowed))
+            })
+        }
+        _ => false,
+    }
+}
+
+fn resources_covered(deny: &Option<Resource>, allow: &Option<Resource>) -> bool {
+    match (deny, allow) {
+        (None, _) | (Some(Resource::Resource(OrAny::Any)), _) => true,
+        (
+            Some(Resource::Resource(OrAny::Some(denied))),
+            Some(Resource::Resource(OrAny::Some(allowed))),
+        ) => allowed.iter().all(|allowed| {
+            denied.iter().any(|denied| match allowed {
+                ResourceEntry::Arn(arn) => denied.matches(arn),
+                _ => denied == allowed,
+            })
+        }),
+        _ => false,
+    }
+}
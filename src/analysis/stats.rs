@@ -0,0 +1,370 @@
+/*!
+Provides counts describing a single policy, and anonymized counts over a corpus of policies.
+*/
+
+use crate::analysis::has_wildcards;
+#[cfg(feature = "fs")]
+use crate::error::IamError;
+#[cfg(feature = "fs")]
+use crate::io::read_from_file;
+use crate::model::redaction::arn_resource_type;
+use crate::model::{Action, OrAny, Policy, QualifiedName, Resource};
+use crate::syntax::POLICY_WILDCARD_VALUE;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+#[cfg(feature = "fs")]
+use std::fs::read_dir;
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Counts describing a policy, see [`stats`](fn.stats.html). Actions and services are compared
+/// ignoring case, as in IAM.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PolicyStats {
+    /// The number of statements.
+    pub statements: usize,
+    /// The number of allow statements.
+    pub allow_statements: usize,
+    /// The number of deny statements.
+    pub deny_statements: usize,
+    /// The number of statements with conditions.
+    pub conditional_statements: usize,
+    /// The number of distinct actions and action patterns named in `Action` or `NotAction`.
+    pub unique_actions: usize,
+    /// The number of distinct service namespaces of those actions.
+    pub unique_services: usize,
+    /// The number of distinct resources named in `Resource` or `NotResource`.
+    pub unique_resources: usize,
+    /// The number of action values that are, or contain, a wildcard.
+    pub wildcard_actions: usize,
+    /// The number of resource values that are, or contain, a wildcard.
+    pub wildcard_resources: usize,
+    /// The number of statements whose principal is anyone, see `Principal::is_public`.
+    pub public_principals: usize,
+    /// The size of the policy in characters, not counting white space, as IAM counts it.
+    pub size: usize,
+    /// The size limit used, [`MANAGED_POLICY_SIZE_LIMIT`](constant.MANAGED_POLICY_SIZE_LIMIT.html).
+    pub size_limit: usize,
+}
+
+///
+/// Anonymized counts over a corpus of policies, see [`corpus_stats`](fn.corpus_stats.html).
+/// Each map is keyed by a term of the IAM grammar, or a service name, and never by a value
+/// specific to an account.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CorpusStats {
+    /// The number of policies.
+    pub policies: usize,
+    /// The number of files that could not be read as policies, see
+    /// [`corpus_stats_for_directory`](fn.corpus_stats_for_directory.html).
+    pub unreadable_files: usize,
+    /// The number of statements.
+    pub statements: usize,
+    /// The number of allow statements.
+    pub allow_statements: usize,
+    /// The number of deny statements.
+    pub deny_statements: usize,
+    /// The number of statements with conditions.
+    pub conditional_statements: usize,
+    /// The number of statements using each condition operator, such as `StringEquals` or
+    /// `ForAnyValue:StringLikeIfExists`.
+    pub operators: BTreeMap<String, usize>,
+    /// The number of action values in each family, the service namespace and the verb that
+    /// starts the action name, such as `s3:Get` for `s3:GetObject`, or `*` for any action.
+    pub action_families: BTreeMap<String, usize>,
+    /// The number of resource values of each type, the service and resource type of the ARN,
+    /// such as `iam:role` or `s3`, or `*` for any resource.
+    pub resource_types: BTreeMap<String, usize>,
+    /// The number of action values, in `Action` or `NotAction`.
+    pub actions: usize,
+    /// The number of action values that are, or contain, a wildcard.
+    pub wildcard_actions: usize,
+    /// The number of resource values, in `Resource` or `NotResource`.
+    pub resources: usize,
+    /// The number of resource values that are, or contain, a wildcard.
+    pub wildcard_resources: usize,
+    /// The number of statements whose principal is anyone, see `Principal::is_public`.
+    pub public_principals: usize,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+/// The maximum size, in characters not counting white space, of a customer managed policy.
+pub const MANAGED_POLICY_SIZE_LIMIT: usize = 6144;
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Count the statements, actions, services, resources, and wildcards of `policy`, and measure
+/// its size against [`MANAGED_POLICY_SIZE_LIMIT`](constant.MANAGED_POLICY_SIZE_LIMIT.html).
+///
+pub fn stats(policy: &Policy) -> PolicyStats {
+    let mut actions: BTreeSet<String> = Default::default();
+    let mut services: BTreeSet<String> = Default::default();
+    let mut resources: BTreeSet<String> = Default::default();
+    let mut stats = PolicyStats {
+        size: policy.size(),
+        size_limit: MANAGED_POLICY_SIZE_LIMIT,
+        ..Default::default()
+    };
+    for statement in &policy.statement {
+        stats.statements += 1;
+        if statement.is_allow() {
+            stats.allow_statements += 1;
+        } else {
+            stats.deny_statements += 1;
+        }
+        if statement.condition.is_some() {
+            stats.conditional_statements += 1;
+        }
+        if statement.principal_is_public() {
+            stats.public_principals += 1;
+        }
+        let (Action::Action(action) | Action::NotAction(action)) = &statement.action;
+        match action {
+            OrAny::Any => {
+                stats.wildcard_actions += 1;
+                let _ = actions.insert(POLICY_WILDCARD_VALUE.to_string());
+            }
+            OrAny::Some(names) => {
+                for name in names {
+                    if has_wildcards(&name.to_string()) {
+                        stats.wildcard_actions += 1;
+                    }
+                    let _ = actions.insert(name.to_string().to_lowercase());
+                    let _ = services.insert(name.namespace().to_string().to_lowercase());
+                }
+            }
+        }
+        if let Some(Resource::Resource(resource) | Resource::NotResource(resource)) =
+            &statement.resource
+        {
+            match resource {
+                OrAny::Any => {
+                    stats.wildcard_resources += 1;
+                    let _ = resources.insert(POLICY_WILDCARD_VALUE.to_string());
+                }
+                OrAny::Some(entries) => {
+                    for entry in entries {
+                        let entry = entry.to_string();
+                        if has_wildcards(&entry) {
+                            stats.wildcard_resources += 1;
+                        }
+                        let _ = resources.insert(entry);
+                    }
+                }
+            }
+        }
+    }
+    stats.unique_actions = actions.len();
+    stats.unique_services = services.len();
+    stats.unique_resources = resources.len();
+    stats
+}
+
+///
+/// Aggregate anonymized counts over the corpus `policies`, see
+/// [`CorpusStats`](struct.CorpusStats.html).
+///
+pub fn corpus_stats<'a, I>(policies: I) -> CorpusStats
+where
+    I: IntoIterator<Item = &'a Policy>,
+{
+    let mut stats = CorpusStats::default();
+    for policy in policies {
+        stats.add_policy(policy);
+    }
+    stats
+}
+
+///
+/// Aggregate anonymized counts over the policy files, those with the extension `json`, in
+/// `directory` and its sub-directories. Files that cannot be read as policies are counted in
+/// `unreadable_files`, an error is only returned if the directory cannot be read.
+///
+#[cfg(feature = "fs")]
+pub fn corpus_stats_for_directory(directory: &Path) -> Result<CorpusStats, IamError> {
+    let mut stats = CorpusStats::default();
+    let mut directories = vec![directory.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        for entry in read_dir(directory)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                directories.push(path);
+            } else if path.extension().map(|e| e == "json").unwrap_or_default() {
+                match read_from_file(&path) {
+                    Ok(policy) => stats.add_policy(&policy),
+                    Err(_) => stats.unreadable_files += 1,
+                }
+            }
+        }
+    }
+    Ok(stats)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl PolicyStats {
+    /// Returns `true` if the size of the policy is within `size_limit`.
+    pub fn is_within_limit(&self) -> bool {
+        self.size <= self.size_limit
+    }
+}
+
+impl CorpusStats {
+    ///
+    /// Add the counts of `policy` to these statistics.
+    ///
+    pub fn add_policy(&mut self, policy: &Policy) {
+        self.policies += 1;
+        for statement in &policy.statement {
+            self.statements += 1;
+            if statement.is_allow() {
+                self.allow_statements += 1;
+            } else {
+                self.deny_statements += 1;
+            }
+            if let Some(condition) = &statement.condition {
+                self.conditional_statements += 1;
+                for operator in condition.keys() {
+                    *self.operators.entry(operator.to_string()).or_default() += 1;
+                }
+            }
+            if statement.principal_is_public() {
+                self.public_principals += 1;
+            }
+            let (Action::Action(action) | Action::NotAction(action)) = &statement.action;
+            match action {
+                OrAny::Any => self.add_action(POLICY_WILDCARD_VALUE.to_string(), true),
+                OrAny::Some(names) => {
+                    for name in names {
+                        self.add_action(action_family(name), has_wildcards(&name.to_string()));
+                    }
+                }
+            }
+            if let Some(Resource::Resource(resource) | Resource::NotResource(resource)) =
+                &statement.resource
+            {
+                match resource {
+                    OrAny::Any => self.add_resource(POLICY_WILDCARD_VALUE.to_string(), true),
+                    OrAny::Some(entries) => {
+                        for entry in entries {
+                            let entry = entry.to_string();
+                            let resource_type = if entry == POLICY_WILDCARD_VALUE {
+                                Some(POLICY_WILDCARD_VALUE.to_string())
+                            } else {
+                                arn_resource_type(&entry)
+                            };
+                            match resource_type {
+                                Some(resource_type) => {
+                                    self.add_resource(resource_type, has_wildcards(&entry))
+                                }
+                                None => {
+                                    self.resources += 1;
+                                    if has_wildcards(&entry) {
+                                        self.wildcard_resources += 1;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    ///
+    /// Remove the operators, action families, and resource types counted fewer than `minimum`
+    /// times; a rarely used term, such as the name of a private service, may identify the
+    /// source of the corpus. The totals are not changed.
+    ///
+    pub fn with_minimum_count(self, minimum: usize) -> Self {
+        let mut self_mut = self;
+        self_mut.operators.retain(|_, count| *count >= minimum);
+        self_mut
+            .action_families
+            .retain(|_, count| *count >= minimum);
+        self_mut.resource_types.retain(|_, count| *count >= minimum);
+        self_mut
+    }
+
+    /// The fraction of action values that are, or contain, a wildcard.
+    pub fn wildcard_action_rate(&self) -> f64 {
+        rate(self.wildcard_actions, self.actions)
+    }
+
+    /// The fraction of resource values that are, or contain, a wildcard.
+    pub fn wildcard_resource_rate(&self) -> f64 {
+        rate(self.wildcard_resources, self.resources)
+    }
+
+    /// The fraction of statements whose principal is anyone.
+    pub fn public_principal_rate(&self) -> f64 {
+        rate(self.public_principals, self.statements)
+    }
+
+    /// The fraction of statements with conditions.
+    pub fn conditional_rate(&self) -> f64 {
+        rate(self.conditional_statements, self.statements)
+    }
+
+    fn add_action(&mut self, family: String, wildcard: bool) {
+        self.actions += 1;
+        if wildcard {
+            self.wildcard_actions += 1;
+        }
+        *self.action_families.entry(family).or_default() += 1;
+    }
+
+    fn add_resource(&mut self, resource_type: String, wildcard: bool) {
+        self.resources += 1;
+        if wildcard {
+            self.wildcard_resources += 1;
+        }
+        *self.resource_types.entry(resource_type).or_default() += 1;
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The namespace of `action`, and the verb that starts its name: its first character and any
+/// lowercase letters that follow, `s3:Get` for `s3:GetObject` and `s3:*` for `s3:*`.
+///
+fn action_family(action: &QualifiedName) -> String {
+    let name = action.name();
+    let verb_length = name
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| !c.is_ascii_lowercase())
+        .map(|(index, _)| index)
+        .unwrap_or(name.len());
+    format!(
+        "{}:{}",
+        action.namespace().to_string().to_lowercase(),
+        &name[..verb_length]
+    )
+}
+
+fn rate(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64
+    }
+}
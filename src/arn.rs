@@ -0,0 +1,798 @@
+/*!
+Provides helpers for constructing and matching Amazon Resource Names (ARNs), wrapping the
+[`aws_arn`](https://docs.rs/aws-arn) crate used throughout the model.
+
+An [`ArnBuilder`](struct.ArnBuilder.html) constructs a validated `ARN` from its components, while
+an [`ArnPattern`](struct.ArnPattern.html) represents an ARN as it may appear in the `Resource`
+element of a policy, where any component may contain the wildcards `*` and `?`.
+
+# Example
+
+```rust
+use aws_iam::arn::{ArnBuilder, ArnPattern};
+use std::str::FromStr;
+
+let object = ArnBuilder::s3_object("examplebucket", "reports/2021.csv")
+    .build()
+    .unwrap();
+assert_eq!(object.to_string(), "arn:aws:s3:::examplebucket/reports/2021.csv");
+
+let pattern = ArnPattern::from_str("arn:aws:s3:::examplebucket/reports/20??.*").unwrap();
+assert!(pattern.matches(&object));
+```
+*/
+
+use crate::error::{unexpected_value_for_type, IamFormatError};
+use crate::syntax::{
+    ARN_PATTERN_NAME, CHAR_WILD, CHAR_WILD_ALL, PARTITION_NAME, POLICY_WILDCARD_VALUE, REGION_NAME,
+    RESOURCE_PATH_SEPARATOR,
+};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::ops::Deref;
+use std::str::FromStr;
+
+pub use aws_arn::{AccountIdentifier, ArnError, Identifier, ResourceIdentifier, ARN};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A fluent builder for `ARN` values; the result of `build` is validated by parsing the
+/// complete ARN string so that the builder cannot produce values `ARN::from_str` would reject.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArnBuilder {
+    partition: String,
+    service: String,
+    region: String,
+    account_id: String,
+    resource: String,
+}
+
+///
+/// Determines how strictly ARN values are validated when reading a policy. The default,
+/// `Strict`, requires every value to be parsed as an `ARN`; `Permissive` also accepts values
+/// that are templated (`arn:${Partition}:s3:::bucket`), partial, or not ARNs at all (such as
+/// S3 access point aliases) and retains them as raw patterns.
+///
+/// The mode also applies to condition operators: `Strict` only accepts the operators
+/// documented by IAM, while `Permissive` retains any other operator as `GlobalOperator::Other`.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ArnParseMode {
+    /// Every value must be a well-formed ARN.
+    #[default]
+    Strict,
+    /// Values that are not well-formed ARNs are accepted as raw patterns where possible, and
+    /// unknown condition operators as `GlobalOperator::Other`.
+    Permissive,
+}
+
+///
+/// An ARN as it may appear in a policy `Resource` element. Each of the partition, service,
+/// region, account, and resource components may contain the wildcards `*` (zero or more
+/// characters) and `?` (exactly one character); the single value `"*"` matches any ARN.
+///
+/// Note that, as in IAM, wildcards only match within their component, so `*` in the region
+/// position will not match across the `:` separator. The resource component is the exception
+/// as it is the remainder of the ARN and may itself contain `:` and `/`.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArnPattern {
+    partition: String,
+    service: String,
+    region: String,
+    account_id: String,
+    resource: String,
+}
+
+///
+/// An AWS partition, a group of regions, which is the second component of every ARN. Resources
+/// in one partition cannot be accessed using credentials or policies from another.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Partition {
+    /// The standard AWS regions, `aws`.
+    #[default]
+    Aws,
+    /// The AWS China regions, `aws-cn`.
+    AwsCn,
+    /// The AWS GovCloud (US) regions, `aws-us-gov`.
+    AwsUsGov,
+}
+
+///
+/// An AWS region name, such as `us-east-1`, as it appears in an ARN or the value of the
+/// `aws:RequestedRegion` condition key.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Region(String);
+
+///
+/// A pattern, as matched by `wildcard_match`, prepared once for matching against many values.
+/// Patterns that are a literal, or a literal followed by a single `*`, are compared directly and
+/// all others are compiled to a regular expression.
+///
+#[derive(Debug, Clone)]
+pub struct WildcardPattern {
+    pattern: String,
+    ignore_case: bool,
+    matcher: WildcardMatcher,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+enum WildcardMatcher {
+    Any,
+    Exact(String),
+    Prefix(String),
+    Automaton(Regex),
+    Backtracking,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Match `value` against `pattern` where the pattern may contain the wildcards `*`, matching
+/// zero or more characters, and `?`, matching exactly one character.
+///
+pub fn wildcard_match(pattern: &str, value: &str) -> bool {
+    wildcard_match_with(pattern, value, |p, v| p == v)
+}
+
+///
+/// Match `value` against `pattern`, as `wildcard_match`, ignoring case.
+///
+pub fn wildcard_match_ignore_case(pattern: &str, value: &str) -> bool {
+    wildcard_match_with(pattern, value, |p, v| {
+        p == v || p.to_lowercase().eq(v.to_lowercase())
+    })
+}
+
+///
+/// Re-target `arn`, which may be an ARN or an ARN pattern, to `partition` and, if provided,
+/// `region`. Each component is only replaced if it is a known partition or a region name, so
+/// wildcards, variables, and empty regions are retained. Values that are not ARN-shaped are
+/// returned unchanged.
+///
+pub fn retarget_arn_str(arn: &str, partition: Partition, region: Option<&Region>) -> String {
+    match split_arn(arn) {
+        Some(mut parts) => {
+            let partition = partition.to_string();
+            if Partition::from_str(parts[1]).is_ok() {
+                parts[1] = &partition;
+            }
+            if let Some(region) = region {
+                if Region::is_valid(parts[3]) {
+                    parts[3] = region;
+                }
+            }
+            parts.join(&ARN_SEPARATOR.to_string())
+        }
+        None => arn.to_string(),
+    }
+}
+
+///
+/// Rewrite the account of `arn`, which may be an ARN, an ARN pattern, or a bare account ID, if
+/// it is one of the keys of `accounts`. Wildcards, variables, and values that are neither
+/// ARN-shaped nor an account ID are returned unchanged.
+///
+pub fn rewrite_account_str(
+    arn: &str,
+    accounts: &HashMap<AccountIdentifier, AccountIdentifier>,
+) -> String {
+    let rewrite = |account: &str| {
+        accounts
+            .get(&AccountIdentifier::new_unchecked(account))
+            .map(|account| account.to_string())
+    };
+    match split_arn(arn) {
+        Some(mut parts) => match rewrite(parts[4]) {
+            Some(account) => {
+                parts[4] = &account;
+                parts.join(&ARN_SEPARATOR.to_string())
+            }
+            None => arn.to_string(),
+        },
+        None => rewrite(arn).unwrap_or_else(|| arn.to_string()),
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+const ARN_PREFIX: &str = "arn";
+
+const ARN_SEPARATOR: char = ':';
+
+const DEFAULT_PARTITION: &str = "aws";
+
+const SERVICE_S3: &str = "s3";
+
+const SERVICE_IAM: &str = "iam";
+
+const PARTITION_AWS: &str = "aws";
+
+const PARTITION_AWS_CN: &str = "aws-cn";
+
+const PARTITION_AWS_US_GOV: &str = "aws-us-gov";
+
+const REGION_PREFIX_CN: &str = "cn-";
+
+const REGION_PREFIX_US_GOV: &str = "us-gov-";
+
+lazy_static! {
+    static ref REGION_SYNTAX: Regex = Regex::new(r"^[a-z]{2}(-[a-z]+)+-[0-9]+$").unwrap();
+}
+
+// ------------------------------------------------------------------------------------------------
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Partition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Aws => PARTITION_AWS,
+                Self::AwsCn => PARTITION_AWS_CN,
+                Self::AwsUsGov => PARTITION_AWS_US_GOV,
+            }
+        )
+    }
+}
+
+impl FromStr for Partition {
+    type Err = IamFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            PARTITION_AWS => Ok(Self::Aws),
+            PARTITION_AWS_CN => Ok(Self::AwsCn),
+            PARTITION_AWS_US_GOV => Ok(Self::AwsUsGov),
+            _ => unexpected_value_for_type(PARTITION_NAME, s).into(),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Region {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Region {
+    type Err = IamFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if Self::is_valid(s) {
+            Ok(Self(s.to_string()))
+        } else {
+            unexpected_value_for_type(REGION_NAME, s).into()
+        }
+    }
+}
+
+impl Deref for Region {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Region {
+    /// Returns `true` if `s` has the form of a region name, such as `us-east-1`.
+    pub fn is_valid(s: &str) -> bool {
+        REGION_SYNTAX.is_match(s)
+    }
+
+    /// Returns the partition this region belongs to, determined by the region name's prefix.
+    pub fn partition(&self) -> Partition {
+        if self.0.starts_with(REGION_PREFIX_CN) {
+            Partition::AwsCn
+        } else if self.0.starts_with(REGION_PREFIX_US_GOV) {
+            Partition::AwsUsGov
+        } else {
+            Partition::Aws
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl ArnBuilder {
+    pub fn new<S>(service: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            partition: DEFAULT_PARTITION.to_string(),
+            service: service.into(),
+            region: Default::default(),
+            account_id: Default::default(),
+            resource: Default::default(),
+        }
+    }
+
+    pub fn s3_bucket<S>(bucket: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new(SERVICE_S3).resource(bucket)
+    }
+
+    pub fn s3_object<S1, S2>(bucket: S1, key: S2) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        Self::new(SERVICE_S3).resource_path(vec![bucket.into(), key.into()])
+    }
+
+    pub fn iam_root<S>(account_id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new(SERVICE_IAM)
+            .in_account(account_id)
+            .resource("root")
+    }
+
+    pub fn iam_user<S1, S2>(account_id: S1, user_name: S2) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        Self::new(SERVICE_IAM)
+            .in_account(account_id)
+            .resource_path(vec!["user".to_string(), user_name.into()])
+    }
+
+    pub fn iam_role<S1, S2>(account_id: S1, role_name: S2) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        Self::new(SERVICE_IAM)
+            .in_account(account_id)
+            .resource_path(vec!["role".to_string(), role_name.into()])
+    }
+
+    pub fn in_partition<S>(self, partition: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            partition: partition.into(),
+            ..self
+        }
+    }
+
+    pub fn in_region<S>(self, region: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            region: region.into(),
+            ..self
+        }
+    }
+
+    pub fn in_account<S>(self, account_id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            account_id: account_id.into(),
+            ..self
+        }
+    }
+
+    pub fn resource<S>(self, resource: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            resource: resource.into(),
+            ..self
+        }
+    }
+
+    pub fn resource_path(self, path: Vec<String>) -> Self {
+        Self {
+            resource: path.join(&RESOURCE_PATH_SEPARATOR.to_string()),
+            ..self
+        }
+    }
+
+    pub fn build(self) -> Result<ARN, IamFormatError> {
+        Ok(ARN::from_str(&self.to_string())?)
+    }
+
+    pub fn build_pattern(self) -> Result<ArnPattern, IamFormatError> {
+        ArnPattern::from_str(&self.to_string())
+    }
+}
+
+impl Display for ArnBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}:{}:{}",
+            ARN_PREFIX, self.partition, self.service, self.region, self.account_id, self.resource
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Default for ArnPattern {
+    fn default() -> Self {
+        Self::any()
+    }
+}
+
+impl Display for ArnPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_any() {
+            write!(f, "{}", POLICY_WILDCARD_VALUE)
+        } else {
+            write!(
+                f,
+                "{}:{}:{}:{}:{}:{}",
+                ARN_PREFIX,
+                self.partition,
+                self.service,
+                self.region,
+                self.account_id,
+                self.resource
+            )
+        }
+    }
+}
+
+impl FromStr for ArnPattern {
+    type Err = IamFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == POLICY_WILDCARD_VALUE {
+            return Ok(Self::any());
+        }
+        let parts: Vec<&str> = s.splitn(6, ARN_SEPARATOR).collect();
+        if parts.len() != 6
+            || parts[0] != ARN_PREFIX
+            || parts[2].is_empty()
+            || s.chars().any(|c| c.is_control() || c.is_whitespace())
+        {
+            unexpected_value_for_type(ARN_PATTERN_NAME, s).into()
+        } else {
+            Ok(Self {
+                partition: parts[1].to_string(),
+                service: parts[2].to_string(),
+                region: parts[3].to_string(),
+                account_id: parts[4].to_string(),
+                resource: parts[5].to_string(),
+            })
+        }
+    }
+}
+
+impl From<&ARN> for ArnPattern {
+    fn from(arn: &ARN) -> Self {
+        Self {
+            partition: arn
+                .partition
+                .as_ref()
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| DEFAULT_PARTITION.to_string()),
+            service: arn.service.to_string(),
+            region: arn
+                .region
+                .as_ref()
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+            account_id: arn
+                .account_id
+                .as_ref()
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+            resource: arn.resource.to_string(),
+        }
+    }
+}
+
+impl From<ARN> for ArnPattern {
+    fn from(arn: ARN) -> Self {
+        Self::from(&arn)
+    }
+}
+
+impl ArnPattern {
+    /// A pattern that matches any ARN, written as `"*"` in a policy.
+    pub fn any() -> Self {
+        let any = POLICY_WILDCARD_VALUE.to_string();
+        Self {
+            partition: any.clone(),
+            service: any.clone(),
+            region: any.clone(),
+            account_id: any.clone(),
+            resource: any,
+        }
+    }
+
+    /// Returns `true` if this pattern matches any ARN.
+    pub fn is_any(&self) -> bool {
+        [
+            &self.partition,
+            &self.service,
+            &self.region,
+            &self.account_id,
+            &self.resource,
+        ]
+        .iter()
+        .all(|c| c.as_str() == POLICY_WILDCARD_VALUE)
+    }
+
+    /// Returns `true` if any component of this pattern contains a wildcard.
+    pub fn has_wildcards(&self) -> bool {
+        self.to_string().contains([CHAR_WILD, CHAR_WILD_ALL])
+    }
+
+    pub fn partition(&self) -> &str {
+        &self.partition
+    }
+
+    pub fn service(&self) -> &str {
+        &self.service
+    }
+
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    pub fn resource(&self) -> &str {
+        &self.resource
+    }
+
+    /// Returns `true` if `arn` is matched by this pattern.
+    pub fn matches(&self, arn: &ARN) -> bool {
+        self.matches_components([
+            arn.partition.as_deref().unwrap_or(DEFAULT_PARTITION),
+            &arn.service,
+            arn.region.as_deref().unwrap_or_default(),
+            arn.account_id.as_deref().unwrap_or_default(),
+            &arn.resource,
+        ])
+    }
+
+    ///
+    /// Returns `true` if the string `arn` is matched by this pattern. The string is not required
+    /// to be a valid `ARN` (for example it may use a partition `aws_arn` does not recognize) but
+    /// it must have all six components.
+    ///
+    pub fn matches_str(&self, arn: &str) -> bool {
+        let mut parts = arn.splitn(6, ARN_SEPARATOR);
+        if parts.next() != Some(ARN_PREFIX) {
+            return false;
+        }
+        match (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) {
+            (Some(partition), Some(service), Some(region), Some(account_id), Some(resource))
+                if !service.is_empty()
+                    && !arn.chars().any(|c| c.is_control() || c.is_whitespace()) =>
+            {
+                self.matches_components([partition, service, region, account_id, resource])
+            }
+            _ => false,
+        }
+    }
+
+    fn matches_components(&self, components: [&str; 5]) -> bool {
+        self.is_any()
+            || (wildcard_match(&self.partition, components[0])
+                && wildcard_match(&self.service, components[1])
+                && wildcard_match(&self.region, components[2])
+                && wildcard_match(&self.account_id, components[3])
+                && wildcard_match(&self.resource, components[4]))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for WildcardPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.pattern)
+    }
+}
+
+impl PartialEq for WildcardPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern && self.ignore_case == other.ignore_case
+    }
+}
+
+impl Eq for WildcardPattern {}
+
+impl WildcardPattern {
+    /// Prepare `pattern`, which is matched as `wildcard_match` would.
+    pub fn new(pattern: &str) -> Self {
+        Self::with_case(pattern, false)
+    }
+
+    /// Prepare `pattern`, which is matched as `wildcard_match_ignore_case` would.
+    pub fn new_ignore_case(pattern: &str) -> Self {
+        Self::with_case(pattern, true)
+    }
+
+    /// Returns `true` if `value` is matched by this pattern.
+    pub fn matches(&self, value: &str) -> bool {
+        match &self.matcher {
+            WildcardMatcher::Any => true,
+            WildcardMatcher::Exact(literal) => {
+                if self.ignore_case {
+                    literal.eq_ignore_ascii_case(value)
+                } else {
+                    literal == value
+                }
+            }
+            WildcardMatcher::Prefix(prefix) => match value.get(..prefix.len()) {
+                Some(start) if self.ignore_case => prefix.eq_ignore_ascii_case(start),
+                Some(start) => prefix == start,
+                None => false,
+            },
+            WildcardMatcher::Automaton(regex) => regex.is_match(value),
+            WildcardMatcher::Backtracking => {
+                if self.ignore_case {
+                    wildcard_match_ignore_case(&self.pattern, value)
+                } else {
+                    wildcard_match(&self.pattern, value)
+                }
+            }
+        }
+    }
+
+    /// Returns the pattern as written.
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    fn with_case(pattern: &str, ignore_case: bool) -> Self {
+        let wildcards = pattern.matches([CHAR_WILD, CHAR_WILD_ALL]).count();
+        // a case-insensitive comparison of a literal is only done directly for ASCII, which
+        // covers action names and almost all ARNs.
+        let direct = !ignore_case || pattern.is_ascii();
+        let matcher = if !pattern.is_empty() && pattern.chars().all(|c| c == CHAR_WILD_ALL) {
+            WildcardMatcher::Any
+        } else if wildcards == 0 && direct {
+            WildcardMatcher::Exact(pattern.to_string())
+        } else if wildcards == 1 && pattern.ends_with(CHAR_WILD_ALL) && direct {
+            WildcardMatcher::Prefix(pattern[..pattern.len() - 1].to_string())
+        } else {
+            wildcard_regex(pattern, ignore_case)
+                .map(WildcardMatcher::Automaton)
+                .unwrap_or(WildcardMatcher::Backtracking)
+        };
+        Self {
+            pattern: pattern.to_string(),
+            ignore_case,
+            matcher,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Translate a wildcard pattern into an anchored regular expression; this fails only if the
+/// expression exceeds the size limits of the `regex` crate.
+///
+fn wildcard_regex(pattern: &str, ignore_case: bool) -> Option<Regex> {
+    let mut expression = String::from(if ignore_case { "(?si)^" } else { "(?s)^" });
+    let mut literal = String::new();
+    for c in pattern.chars() {
+        if c == CHAR_WILD || c == CHAR_WILD_ALL {
+            expression.push_str(&regex::escape(&literal));
+            literal.clear();
+            expression.push_str(if c == CHAR_WILD { "." } else { ".*" });
+        } else {
+            literal.push(c);
+        }
+    }
+    expression.push_str(&regex::escape(&literal));
+    expression.push('$');
+    Regex::new(&expression).ok()
+}
+
+/// The partition of the ARN-shaped `arn`, if it is a known partition.
+pub(crate) fn arn_partition(arn: &str) -> Option<Partition> {
+    split_arn(arn).and_then(|parts| Partition::from_str(parts[1]).ok())
+}
+
+/// The region of the ARN-shaped `arn`, if it has one that is not a wildcard or variable.
+pub(crate) fn arn_region(arn: &str) -> Option<Region> {
+    split_arn(arn).and_then(|parts| Region::from_str(parts[3]).ok())
+}
+
+/// The account of the ARN-shaped `arn`, if it has one that is not a wildcard or variable.
+pub(crate) fn arn_account(arn: &str) -> Option<AccountIdentifier> {
+    split_arn(arn).and_then(|parts| account_id(parts[4]))
+}
+
+/// The account ID `s`, if it is exactly twelve digits.
+pub(crate) fn account_id(s: &str) -> Option<AccountIdentifier> {
+    if s.len() == 12 && s.chars().all(|c| c.is_ascii_digit()) {
+        Some(AccountIdentifier::new_unchecked(s))
+    } else {
+        None
+    }
+}
+
+///
+/// The matching loop shared by `wildcard_match` and `wildcard_match_ignore_case`; it walks both
+/// strings by character offset, so neither is copied.
+///
+fn wildcard_match_with<F>(pattern: &str, value: &str, eq: F) -> bool
+where
+    F: Fn(char, char) -> bool,
+{
+    let (mut p, mut v) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while let Some(value_char) = value[v..].chars().next() {
+        let pattern_char = pattern[p..].chars().next();
+        match pattern_char {
+            Some(c) if c == CHAR_WILD || (c != CHAR_WILD_ALL && eq(c, value_char)) => {
+                p += c.len_utf8();
+                v += value_char.len_utf8();
+            }
+            Some(CHAR_WILD_ALL) => {
+                backtrack = Some((p, v));
+                p += CHAR_WILD_ALL.len_utf8();
+            }
+            _ => match backtrack {
+                Some((star_p, star_v)) => {
+                    // let the `*` consume one more character of the value and try again.
+                    let skipped = value[star_v..].chars().next().map(char::len_utf8);
+                    p = star_p + CHAR_WILD_ALL.len_utf8();
+                    v = star_v + skipped.unwrap_or_default();
+                    backtrack = Some((star_p, v));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].chars().all(|c| c == CHAR_WILD_ALL)
+}
+
+fn split_arn(arn: &str) -> Option<Vec<&str>> {
+    let parts: Vec<&str> = arn.splitn(6, ARN_SEPARATOR).collect();
+    if parts.len() == 6 && parts[0] == ARN_PREFIX {
+        Some(parts)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,86 @@
+use crate::authorizer::{Authorizer, RequestMapper};
+use crate::offline::Request;
+use actix_web::dev::Payload;
+use actix_web::error::{ErrorForbidden, ErrorInternalServerError};
+use actix_web::web::Data;
+use actix_web::{Error, FromRequest, HttpRequest};
+use std::fmt::{Debug, Formatter};
+use std::future::{ready, Ready};
+use std::ops::Deref;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The authorizer and mapper used by the [`Authorized`](struct.Authorized.html) extractor,
+/// registered with `App::app_data(Data::new(...))`.
+///
+pub struct ActixAuthorizer {
+    authorizer: Authorizer<'static>,
+    mapper: Box<dyn RequestMapper<HttpRequest>>,
+}
+
+///
+/// An actix-web extractor that succeeds, with the evaluated request, only if the request is
+/// allowed by the application's [`ActixAuthorizer`](struct.ActixAuthorizer.html). A denied
+/// request fails with `403 Forbidden`, and if no authorizer is registered every request fails
+/// with `500 Internal Server Error`.
+///
+#[derive(Debug, Clone)]
+pub struct Authorized(pub Request);
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Debug for ActixAuthorizer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ActixAuthorizer")
+            .field("authorizer", &self.authorizer)
+            .finish()
+    }
+}
+
+impl ActixAuthorizer {
+    pub fn new<M>(authorizer: Authorizer<'static>, mapper: M) -> Self
+    where
+        M: RequestMapper<HttpRequest> + 'static,
+    {
+        Self {
+            authorizer,
+            mapper: Box::new(mapper),
+        }
+    }
+
+    fn authorize(&self, request: &HttpRequest) -> Option<Request> {
+        self.mapper
+            .map(request)
+            .filter(|request| self.authorizer.is_allowed(request))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Deref for Authorized {
+    type Target = Request;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl FromRequest for Authorized {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(request: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(match request.app_data::<Data<ActixAuthorizer>>() {
+            Some(authorizer) => authorizer
+                .authorize(request)
+                .map(Authorized)
+                .ok_or_else(|| ErrorForbidden("the request is not authorized")),
+            None => Err(ErrorInternalServerError("no authorizer is registered")),
+        })
+    }
+}
@@ -0,0 +1,138 @@
+/*!
+Provides an authorizer for web applications, which decides whether each HTTP request is allowed
+by a set of policies using the compiled offline evaluator. Requires feature `authorizer`.
+
+An application describes its own actions and resources in ordinary policies, for example the
+action `shop:GetOrder` on the resource `arn:aws:shop:::order/1234`. A
+[`RequestMapper`](trait.RequestMapper.html), provided by the application, maps each HTTP
+request to an evaluator [`Request`](../offline/struct.Request.html) with its action, resource,
+principal, and any context values, such as the caller's identity established by an earlier
+authentication layer. Requests that the mapper cannot map are denied, as are requests whose
+evaluation fails.
+
+For tower-based frameworks, including axum, an
+[`AuthorizerLayer`](struct.AuthorizerLayer.html) wraps a service and answers denied requests
+with `403 Forbidden` without calling it; allowed requests are passed on with the evaluator
+request in their extensions. With the feature `authorizer_actix`, the
+[`Authorized`](struct.Authorized.html) extractor does the same for actix-web handlers, using
+an [`ActixAuthorizer`](struct.ActixAuthorizer.html) registered as application data.
+
+An [`Authorizer`](struct.Authorizer.html) compiles its policies once, when it is created, and
+borrows them, see [`CompiledPolicy`](../offline/struct.CompiledPolicy.html). The middleware
+lives as long as the server and so requires an authorizer of `'static` policies, such as those
+read at startup and held in a `lazy_static`, or leaked with `Box::leak`.
+
+# Example
+
+```rust,no_run
+use aws_iam::authorizer::{Authorizer, AuthorizerLayer};
+use aws_iam::io;
+use aws_iam::model::QualifiedName;
+use aws_iam::offline::Request;
+use http::request::Parts;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+let policy = io::read_from_file(&PathBuf::from("policies/shop.json")).unwrap();
+let policy: &'static _ = Box::leak(Box::new(policy));
+
+let layer = AuthorizerLayer::new(Authorizer::new(&[policy]), |parts: &Parts| {
+    let order = parts.uri.path().strip_prefix("/orders/")?;
+    Some(Request {
+        request_id: None,
+        principal: None,
+        action: QualifiedName::from_str("shop:GetOrder").ok()?,
+        resource: format!("arn:aws:shop:::order/{}", order),
+        source_arn: None,
+        source_account: None,
+        called_via: Default::default(),
+        environment: Default::default(),
+    })
+});
+// let app = axum::Router::new().route("/orders/:id", get(order)).layer(layer);
+```
+*/
+
+use crate::model::Policy;
+use crate::offline::{
+    evaluate_all_compiled, CompiledPolicy, EvaluationError, EvaluationResult, Request,
+};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Maps a framework's request, such as `http::request::Parts`, to the request to evaluate;
+/// returns `None` if the request cannot be mapped, in which case it is denied. Any function
+/// of the request with the same signature is a mapper.
+///
+pub trait RequestMapper<R>: Send + Sync {
+    /// Map `request` to the request to evaluate.
+    fn map(&self, request: &R) -> Option<Request>;
+}
+
+///
+/// A set of policies, compiled once, against which requests are authorized.
+///
+#[derive(Debug)]
+pub struct Authorizer<'a> {
+    policies: Vec<CompiledPolicy<'a>>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<R, F> RequestMapper<R> for F
+where
+    F: Fn(&R) -> Option<Request> + Send + Sync,
+{
+    fn map(&self, request: &R) -> Option<Request> {
+        self(request)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<'a> Authorizer<'a> {
+    /// Compile `policies` for authorizing requests.
+    pub fn new(policies: &[&'a Policy]) -> Self {
+        Self {
+            policies: policies
+                .iter()
+                .map(|policy| CompiledPolicy::new(policy))
+                .collect(),
+        }
+    }
+
+    /// The policies requests are authorized against.
+    pub fn policies(&self) -> impl Iterator<Item = &Policy> {
+        self.policies.iter().map(|compiled| compiled.policy())
+    }
+
+    /// Evaluate `request` against all the policies, see `offline::evaluate_all_compiled`.
+    pub fn authorize(&self, request: &Request) -> Result<EvaluationResult, EvaluationError> {
+        evaluate_all_compiled(request, &self.policies)
+    }
+
+    ///
+    /// Returns `true` only if `request` is allowed by the policies; a request whose
+    /// evaluation fails is not allowed.
+    ///
+    pub fn is_allowed(&self, request: &Request) -> bool {
+        matches!(self.authorize(request), Ok(EvaluationResult::Allow(_)))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Modules
+// ------------------------------------------------------------------------------------------------
+
+mod tower;
+pub use self::tower::{AuthorizerLayer, AuthorizerService};
+
+#[cfg(feature = "authorizer_actix")]
+mod actix;
+#[cfg(feature = "authorizer_actix")]
+pub use actix::{ActixAuthorizer, Authorized};
@@ -0,0 +1,145 @@
+use crate::authorizer::{Authorizer, RequestMapper};
+use futures_util::future::{ready, Either, Ready};
+use http::request::Parts;
+use http::{Request, Response, StatusCode};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A tower layer, which may be used with axum, that authorizes each request before it reaches
+/// the wrapped service, see [`AuthorizerService`](struct.AuthorizerService.html).
+///
+pub struct AuthorizerLayer<M> {
+    authorizer: Arc<Authorizer<'static>>,
+    mapper: Arc<M>,
+}
+
+///
+/// A service that maps each request with its mapper and evaluates it with its authorizer. A
+/// denied request is answered with `403 Forbidden` and an empty body, and an allowed request
+/// is passed to the wrapped service with the evaluated `offline::Request` added to its
+/// extensions.
+///
+pub struct AuthorizerService<S, M> {
+    inner: S,
+    authorizer: Arc<Authorizer<'static>>,
+    mapper: Arc<M>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<M> Debug for AuthorizerLayer<M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthorizerLayer")
+            .field("authorizer", &self.authorizer)
+            .finish()
+    }
+}
+
+impl<M> Clone for AuthorizerLayer<M> {
+    fn clone(&self) -> Self {
+        Self {
+            authorizer: self.authorizer.clone(),
+            mapper: self.mapper.clone(),
+        }
+    }
+}
+
+impl<M> AuthorizerLayer<M>
+where
+    M: RequestMapper<Parts>,
+{
+    pub fn new(authorizer: Authorizer<'static>, mapper: M) -> Self {
+        Self {
+            authorizer: Arc::new(authorizer),
+            mapper: Arc::new(mapper),
+        }
+    }
+}
+
+impl<S, M> Layer<S> for AuthorizerLayer<M> {
+    type Service = AuthorizerService<S, M>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthorizerService {
+            inner,
+            authorizer: self.authorizer.clone(),
+            mapper: self.mapper.clone(),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<S, M> Debug for AuthorizerService<S, M>
+where
+    S: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthorizerService")
+            .field("inner", &self.inner)
+            .field("authorizer", &self.authorizer)
+            .finish()
+    }
+}
+
+impl<S, M> Clone for AuthorizerService<S, M>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            authorizer: self.authorizer.clone(),
+            mapper: self.mapper.clone(),
+        }
+    }
+}
+
+impl<S, M, B, R> Service<Request<B>> for AuthorizerService<S, M>
+where
+    S: Service<Request<B>, Response = Response<R>>,
+    M: RequestMapper<Parts>,
+    R: Default,
+{
+    type Response = Response<R>;
+    type Error = S::Error;
+    type Future = Either<S::Future, Ready<Result<Self::Response, Self::Error>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let (mut parts, body) = request.into_parts();
+        match self.mapper.map(&parts) {
+            Some(request) if self.authorizer.is_allowed(&request) => {
+                parts.extensions.insert(request);
+                Either::Left(self.inner.call(Request::from_parts(parts, body)))
+            }
+            _ => Either::Right(ready(Ok(forbidden()))),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn forbidden<R>() -> Response<R>
+where
+    R: Default,
+{
+    let mut response = Response::new(R::default());
+    *response.status_mut() = StatusCode::FORBIDDEN;
+    response
+}
@@ -0,0 +1,488 @@
+/*!
+Provides organization baselines, the guardrails an organization requires of every policy,
+written as a small declarative document rather than as code.
+
+A [`Baseline`](struct.Baseline.html) is a list of rules. Each rule selects statements, by their
+`Effect`, by the actions they may grant, and by whether they have a `Principal`, and then either
+requires every selected statement to have a condition or forbids such statements entirely. A
+baseline is usually read from a JSON file alongside the policies it checks, and is enforced by
+setting it on the [`ValidationOptions`](../validate/struct.ValidationOptions.html) used to
+validate them, each violation is reported as a finding with the code
+[`FINDING_BASELINE_VIOLATION`](constant.FINDING_BASELINE_VIOLATION.html). A baseline is also a
+[`LintRule`](../lint/trait.LintRule.html), and so may be registered with the other lint rules.
+
+# Schema
+
+The document has a single element, `Rules`, an array of rule objects with the following
+elements; element names follow those of a policy statement.
+
+* `Id` - required, identifies the rule in findings.
+* `Description` - optional, a description of the rule used in findings.
+* `Severity` - optional, `Error` (the default), `Warning`, or `Info`.
+* `Effect` - optional, only statements with this effect are selected.
+* `Action` - optional, a name or array of names, which may include wildcards; only statements
+  that may grant a matching action are selected. Statements using `NotAction` are selected
+  unless all the names are excluded.
+* `HasPrincipal` - optional, when `true` only statements with a `Principal` are selected, when
+  `false` only those without.
+* `Condition` - a condition, in the same form as in a statement, that every selected statement
+  must have. The statement must use the same operators and keys, and none of its values may
+  be outside those of the rule, so a statement may be more restrictive than the rule but not
+  less.
+* `Forbid` - `true` if no statement may be selected by the rule.
+
+Exactly one of `Condition` and `Forbid` is required.
+
+# Example
+
+```rust
+use aws_iam::baseline::{Baseline, FINDING_BASELINE_VIOLATION};
+use aws_iam::model::Policy;
+use aws_iam::validate::ValidationOptions;
+use std::str::FromStr;
+
+let baseline = Baseline::from_str(
+    r#"{"Rules":[
+        {"Id":"S3RequiresTls","Effect":"Allow","Action":"s3:*",
+         "Condition":{"Bool":{"aws:SecureTransport":"true"}}},
+        {"Id":"OrgPrincipalsOnly","Effect":"Allow","HasPrincipal":true,
+         "Condition":{"StringEquals":{"aws:PrincipalOrgID":"o-123"}}},
+        {"Id":"NoIamWildcard","Severity":"Warning","Effect":"Allow","Action":"iam:*","Forbid":true}
+    ]}"#,
+)
+.unwrap();
+
+let policy = Policy::from_str(
+    r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject","Resource":"*"}]}"#,
+)
+.unwrap();
+
+let findings = policy.validate(&ValidationOptions::default().with_baseline(baseline));
+assert_eq!(findings.len(), 1);
+assert_eq!(findings[0].code, FINDING_BASELINE_VIOLATION);
+assert!(findings[0].message.contains("S3RequiresTls"));
+```
+*/
+
+use crate::error::{
+    missing_property, type_mismatch, unexpected_properties, unexpected_value_for_property,
+    IamFormatError,
+};
+use crate::model::{Action, Condition, Effect, OrAny, Policy, QualifiedName, Statement};
+use crate::syntax::{
+    display_vec_to_json, from_json_str, json_type_name, vec_from_str_json, IamProperty, IamValue,
+    ACTION_VALUE_ACTION, CONDITION_NAME, EFFECT_NAME, JSON_TYPE_NAME_ARRAY, JSON_TYPE_NAME_BOOL,
+    JSON_TYPE_NAME_OBJECT, JSON_TYPE_NAME_STRING,
+};
+use crate::validate::{Finding, Findings, Severity};
+use serde_json::{Map, Value};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The rules an organization requires of every policy, see the module documentation.
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Baseline {
+    /// The rules, checked in order.
+    pub rules: Vec<BaselineRule>,
+}
+
+///
+/// A single rule of a baseline; the statements it selects must meet its requirement.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaselineRule {
+    /// Identifies the rule in findings.
+    pub id: String,
+    /// A description of the rule used in findings.
+    pub description: Option<String>,
+    /// The severity of the findings for statements that violate the rule.
+    pub severity: Severity,
+    /// If set, only statements with this effect are selected.
+    pub effect: Option<Effect>,
+    /// If not empty, only statements that may grant one of these actions are selected.
+    pub actions: Vec<QualifiedName>,
+    /// If set, only statements that have, or do not have, a principal are selected.
+    pub has_principal: Option<bool>,
+    /// What is required of the selected statements.
+    pub requirement: Requirement,
+}
+
+///
+/// What a baseline rule requires of the statements it selects.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum Requirement {
+    /// Each statement must have this condition, or a more restrictive one.
+    Condition(Condition),
+    /// No statement may be selected.
+    Forbidden,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+/// A statement does not meet the requirement of a rule in the organization's baseline.
+pub const FINDING_BASELINE_VIOLATION: &str = "BaselineViolation";
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Baseline {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        crate::syntax::value_to_fragment(self, f)
+    }
+}
+
+impl FromStr for Baseline {
+    type Err = IamFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::syntax::value_from_fragment(s)
+    }
+}
+
+impl IamValue for Baseline {
+    fn to_json(&self) -> Result<Value, IamFormatError> {
+        let rules: Result<Vec<Value>, IamFormatError> =
+            self.rules.iter().map(IamValue::to_json).collect();
+        let mut object = Map::default();
+        let _ = object.insert(RULES_NAME.to_string(), Value::Array(rules?));
+        Ok(Value::Object(object))
+    }
+
+    fn from_json(value: &Value) -> Result<Self, IamFormatError>
+    where
+        Self: Sized,
+    {
+        let object = match value {
+            Value::Object(object) => object,
+            _ => {
+                return type_mismatch(BASELINE_NAME, JSON_TYPE_NAME_OBJECT, json_type_name(value))
+                    .into()
+            }
+        };
+        match object.get(RULES_NAME) {
+            Some(Value::Array(rules)) => Ok(Self {
+                rules: rules
+                    .iter()
+                    .map(BaselineRule::from_json)
+                    .collect::<Result<Vec<BaselineRule>, IamFormatError>>()?,
+            }),
+            Some(value) => {
+                type_mismatch(RULES_NAME, JSON_TYPE_NAME_ARRAY, json_type_name(value)).into()
+            }
+            None => missing_property(RULES_NAME).into(),
+        }
+    }
+}
+
+impl Baseline {
+    /// Add `rule`, checked after those already in the baseline.
+    pub fn with_rule(mut self, rule: BaselineRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    ///
+    /// Check each statement of `policy` against each rule, returning a finding for every
+    /// statement that does not meet the requirement of a rule that selects it.
+    ///
+    pub fn check(&self, policy: &Policy) -> Findings {
+        let mut findings = Findings::default();
+        for rule in &self.rules {
+            for (index, statement) in policy.statements().enumerate() {
+                if rule.selects(statement) && !rule.is_met_by(statement) {
+                    findings.push(rule.finding(index));
+                }
+            }
+        }
+        findings
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl IamValue for BaselineRule {
+    fn to_json(&self) -> Result<Value, IamFormatError> {
+        let mut rule = Map::default();
+        let _ = rule.insert(RULE_ID_NAME.to_string(), Value::String(self.id.clone()));
+        if let Some(description) = &self.description {
+            let _ = rule.insert(
+                RULE_DESCRIPTION_NAME.to_string(),
+                Value::String(description.clone()),
+            );
+        }
+        if self.severity != Severity::Error {
+            let _ = rule.insert(
+                RULE_SEVERITY_NAME.to_string(),
+                Value::String(severity_name(self.severity).to_string()),
+            );
+        }
+        if let Some(effect) = &self.effect {
+            let _ = rule.insert(EFFECT_NAME.to_string(), effect.to_json()?);
+        }
+        if !self.actions.is_empty() {
+            let _ = rule.insert(
+                ACTION_VALUE_ACTION.to_string(),
+                display_vec_to_json(&self.actions)?,
+            );
+        }
+        if let Some(has_principal) = self.has_principal {
+            let _ = rule.insert(
+                RULE_HAS_PRINCIPAL_NAME.to_string(),
+                Value::Bool(has_principal),
+            );
+        }
+        match &self.requirement {
+            Requirement::Condition(condition) => condition.into_json_object(&mut rule)?,
+            Requirement::Forbidden => {
+                let _ = rule.insert(RULE_FORBID_NAME.to_string(), Value::Bool(true));
+            }
+        }
+        Ok(Value::Object(rule))
+    }
+
+    fn from_json(value: &Value) -> Result<Self, IamFormatError>
+    where
+        Self: Sized,
+    {
+        let rule = match value {
+            Value::Object(rule) => rule,
+            _ => {
+                return type_mismatch(RULE_NAME, JSON_TYPE_NAME_OBJECT, json_type_name(value))
+                    .into()
+            }
+        };
+        let id = match rule.get(RULE_ID_NAME) {
+            Some(Value::String(id)) => id.clone(),
+            Some(value) => {
+                return type_mismatch(RULE_ID_NAME, JSON_TYPE_NAME_STRING, json_type_name(value))
+                    .into()
+            }
+            None => return missing_property(RULE_ID_NAME).into(),
+        };
+        let description = match rule.get(RULE_DESCRIPTION_NAME) {
+            Some(Value::String(description)) => Some(description.clone()),
+            Some(value) => {
+                return type_mismatch(
+                    RULE_DESCRIPTION_NAME,
+                    JSON_TYPE_NAME_STRING,
+                    json_type_name(value),
+                )
+                .into()
+            }
+            None => None,
+        };
+        let severity = match rule.get(RULE_SEVERITY_NAME) {
+            Some(Value::String(s)) if s == "Error" => Severity::Error,
+            Some(Value::String(s)) if s == "Warning" => Severity::Warning,
+            Some(Value::String(s)) if s == "Info" => Severity::Info,
+            Some(value) => {
+                return unexpected_value_for_property(RULE_SEVERITY_NAME, value.to_string()).into()
+            }
+            None => Severity::Error,
+        };
+        let effect = match rule.get(EFFECT_NAME) {
+            Some(value) => Some(from_json_str(value, EFFECT_NAME)?),
+            None => None,
+        };
+        let actions = match rule.get(ACTION_VALUE_ACTION) {
+            Some(value) => vec_from_str_json(value, ACTION_VALUE_ACTION)?,
+            None => Default::default(),
+        };
+        let has_principal = match rule.get(RULE_HAS_PRINCIPAL_NAME) {
+            Some(Value::Bool(has_principal)) => Some(*has_principal),
+            Some(value) => {
+                return type_mismatch(
+                    RULE_HAS_PRINCIPAL_NAME,
+                    JSON_TYPE_NAME_BOOL,
+                    json_type_name(value),
+                )
+                .into()
+            }
+            None => None,
+        };
+        let requirement = match (
+            Condition::from_json_object_optional(rule)?,
+            rule.get(RULE_FORBID_NAME),
+        ) {
+            (Some(condition), None) => Requirement::Condition(condition),
+            (None, Some(Value::Bool(true))) => Requirement::Forbidden,
+            (None, Some(value)) => {
+                return unexpected_value_for_property(RULE_FORBID_NAME, value.to_string()).into()
+            }
+            (None, None) => return missing_property(CONDITION_NAME).into(),
+            (Some(_), Some(_)) => return unexpected_properties(RULE_NAME).into(),
+        };
+        Ok(Self {
+            id,
+            description,
+            severity,
+            effect,
+            actions,
+            has_principal,
+            requirement,
+        })
+    }
+}
+
+impl BaselineRule {
+    /// A rule requiring every statement it selects to have `condition`.
+    pub fn require_condition<S>(id: S, condition: Condition) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new(id.into(), Requirement::Condition(condition))
+    }
+
+    /// A rule forbidding every statement it selects.
+    pub fn forbid<S>(id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new(id.into(), Requirement::Forbidden)
+    }
+
+    pub fn with_description<S>(self, description: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            description: Some(description.into()),
+            ..self
+        }
+    }
+
+    pub fn with_severity(self, severity: Severity) -> Self {
+        Self { severity, ..self }
+    }
+
+    pub fn with_effect(self, effect: Effect) -> Self {
+        Self {
+            effect: Some(effect),
+            ..self
+        }
+    }
+
+    pub fn with_actions(self, actions: Vec<QualifiedName>) -> Self {
+        Self { actions, ..self }
+    }
+
+    pub fn with_principal(self, has_principal: bool) -> Self {
+        Self {
+            has_principal: Some(has_principal),
+            ..self
+        }
+    }
+
+    /// Returns `true` if `statement` is subject to this rule.
+    pub fn selects(&self, statement: &Statement) -> bool {
+        self.effect
+            .as_ref()
+            .map(|effect| effect == &statement.effect)
+            .unwrap_or(true)
+            && self
+                .has_principal
+                .map(|has_principal| has_principal == statement.principal.is_some())
+                .unwrap_or(true)
+            && (self.actions.is_empty() || self.may_grant(&statement.action))
+    }
+
+    /// Returns `true` if `statement` meets the requirement of this rule.
+    pub fn is_met_by(&self, statement: &Statement) -> bool {
+        match &self.requirement {
+            Requirement::Forbidden => false,
+            Requirement::Condition(required) => match &statement.condition {
+                None => false,
+                Some(condition) => required.iter().all(|(operator, required)| {
+                    required.iter().all(|(key, allowed)| {
+                        condition.iter().any(|(other, matches)| {
+                            other == operator
+                                && matches.iter().any(|(other, values)| {
+                                    other.to_string().eq_ignore_ascii_case(&key.to_string())
+                                        && values.iter().all(|value| allowed.contains(value))
+                                })
+                        })
+                    })
+                }),
+            },
+        }
+    }
+
+    fn new(id: String, requirement: Requirement) -> Self {
+        Self {
+            id,
+            description: None,
+            severity: Severity::Error,
+            effect: None,
+            actions: Default::default(),
+            has_principal: None,
+            requirement,
+        }
+    }
+
+    fn may_grant(&self, action: &Action) -> bool {
+        match action {
+            Action::Action(OrAny::Any) => true,
+            Action::NotAction(OrAny::Any) => false,
+            Action::Action(OrAny::Some(granted)) => granted.iter().any(|granted| {
+                self.actions
+                    .iter()
+                    .any(|name| name.matches(granted) || granted.matches(name))
+            }),
+            Action::NotAction(OrAny::Some(excluded)) => !self
+                .actions
+                .iter()
+                .all(|name| excluded.iter().any(|excluded| excluded.matches(name))),
+        }
+    }
+
+    fn finding(&self, index: usize) -> Finding {
+        let description = match (&self.description, &self.requirement) {
+            (Some(description), _) => description.clone(),
+            (None, Requirement::Condition(condition)) => {
+                format!("statement must have the condition {}", condition)
+            }
+            (None, Requirement::Forbidden) => "statement is forbidden".to_string(),
+        };
+        Finding {
+            severity: self.severity,
+            code: FINDING_BASELINE_VIOLATION,
+            message: format!("baseline rule {:?}: {}", self.id, description),
+            statement: Some(index),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const BASELINE_NAME: &str = "Baseline";
+const RULES_NAME: &str = "Rules";
+const RULE_NAME: &str = "Rule";
+const RULE_ID_NAME: &str = "Id";
+const RULE_DESCRIPTION_NAME: &str = "Description";
+const RULE_SEVERITY_NAME: &str = "Severity";
+const RULE_HAS_PRINCIPAL_NAME: &str = "HasPrincipal";
+const RULE_FORBID_NAME: &str = "Forbid";
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "Error",
+        Severity::Warning => "Warning",
+        Severity::Info => "Info",
+    }
+}
@@ -0,0 +1,290 @@
+/*!
+A minimal language server for IAM policy documents, files named `*.iam.json`.
+
+The server communicates over stdin and stdout and offers:
+
+* diagnostics, the findings of validation located at the element they concern;
+* hover text describing condition operators and global condition keys;
+* completion of condition operators and global condition keys.
+
+Documents are synchronized in full on each change.
+*/
+
+use aws_iam::context::keys::describe_key;
+use aws_iam::diagnostic::{diagnose_str, SourceMap};
+use aws_iam::model::Operator;
+use aws_iam::syntax::{all_condition_operators, all_global_condition_keys};
+use aws_iam::validate::{Severity, ValidationOptions};
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+    Notification as NotificationType, PublishDiagnostics,
+};
+use lsp_types::request::{Completion, HoverRequest, Request as RequestType};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, Diagnostic,
+    DiagnosticSeverity, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, Hover, HoverContents, HoverParams, HoverProviderCapability,
+    MarkupContent, MarkupKind, NumberOrString, Position, PublishDiagnosticsParams, Range,
+    ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use std::collections::HashMap;
+use std::error::Error;
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Main
+// ------------------------------------------------------------------------------------------------
+
+fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!["\"".to_string(), ":".to_string()]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let _ = connection.initialize(serde_json::to_value(capabilities)?)?;
+    Server::default().run(&connection)?;
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug, Default)]
+struct Server {
+    documents: HashMap<Url, String>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const DOCUMENT_SUFFIX: &str = ".iam.json";
+
+const DIAGNOSTIC_SOURCE: &str = "aws-iam";
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Server {
+    fn run(&mut self, connection: &Connection) -> Result<(), Box<dyn Error + Send + Sync>> {
+        for message in &connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    if connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    let response = self.request(request)?;
+                    connection.sender.send(Message::Response(response))?;
+                }
+                Message::Notification(notification) => {
+                    if let Some(published) = self.notification(notification)? {
+                        connection.sender.send(Message::Notification(published))?;
+                    }
+                }
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn request(&self, request: Request) -> Result<Response, serde_json::Error> {
+        let id = request.id.clone();
+        match request.method.as_str() {
+            HoverRequest::METHOD => {
+                let params: HoverParams = serde_json::from_value(request.params)?;
+                let position = params.text_document_position_params;
+                let hover = self
+                    .documents
+                    .get(&position.text_document.uri)
+                    .and_then(|text| hover(text, position.position));
+                Ok(Response::new_ok(id, hover))
+            }
+            Completion::METHOD => {
+                let params: CompletionParams = serde_json::from_value(request.params)?;
+                let items = if self
+                    .documents
+                    .contains_key(&params.text_document_position.text_document.uri)
+                {
+                    completions()
+                } else {
+                    Default::default()
+                };
+                Ok(Response::new_ok(id, items))
+            }
+            _ => Ok(method_not_found(id, &request.method)),
+        }
+    }
+
+    fn notification(
+        &mut self,
+        notification: Notification,
+    ) -> Result<Option<Notification>, serde_json::Error> {
+        let uri = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                if !is_policy_document(&uri) {
+                    return Ok(None);
+                }
+                let _ = self
+                    .documents
+                    .insert(uri.clone(), params.text_document.text);
+                uri
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                match (
+                    self.documents.get_mut(&uri),
+                    params.content_changes.into_iter().last(),
+                ) {
+                    (Some(text), Some(change)) => *text = change.text,
+                    _ => return Ok(None),
+                }
+                uri
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                if self.documents.remove(&uri).is_none() {
+                    return Ok(None);
+                }
+                return Ok(Some(publish(uri, Default::default())));
+            }
+            _ => return Ok(None),
+        };
+        let diagnostics = diagnostics(&self.documents[&uri]);
+        Ok(Some(publish(uri, diagnostics)))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn is_policy_document(uri: &Url) -> bool {
+    uri.path().ends_with(DOCUMENT_SUFFIX)
+}
+
+fn method_not_found(id: RequestId, method: &str) -> Response {
+    Response::new_err(
+        id,
+        lsp_server::ErrorCode::MethodNotFound as i32,
+        format!("method {:?} is not supported", method),
+    )
+}
+
+fn publish(uri: Url, diagnostics: Vec<Diagnostic>) -> Notification {
+    Notification::new(
+        PublishDiagnostics::METHOD.to_string(),
+        PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version: None,
+        },
+    )
+}
+
+fn diagnostics(text: &str) -> Vec<Diagnostic> {
+    diagnose_str(text, &ValidationOptions::default())
+        .into_iter()
+        .map(|diagnostic| Diagnostic {
+            range: Range::new(
+                to_position(text, diagnostic.span.start.offset),
+                to_position(text, diagnostic.span.end.offset),
+            ),
+            severity: Some(match diagnostic.severity {
+                Severity::Error => DiagnosticSeverity::ERROR,
+                Severity::Warning => DiagnosticSeverity::WARNING,
+                Severity::Info => DiagnosticSeverity::INFORMATION,
+            }),
+            code: Some(NumberOrString::String(diagnostic.code.to_string())),
+            source: Some(DIAGNOSTIC_SOURCE.to_string()),
+            message: diagnostic.message,
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn hover(text: &str, position: Position) -> Option<Hover> {
+    let source_map = SourceMap::from_str(text).ok()?;
+    let (_, span) = source_map.key_at(to_offset(text, position))?;
+    let key: String = serde_json::from_str(&text[span.range()]).ok()?;
+    let description = match Operator::from_str(&key) {
+        Ok(operator) if operator.operator.is_known() => operator.operator.description(),
+        _ => describe_key(&key),
+    }?;
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: format!("`{}`\n\n{}", key, description),
+        }),
+        range: Some(Range::new(
+            to_position(text, span.start.offset),
+            to_position(text, span.end.offset),
+        )),
+    })
+}
+
+fn completions() -> Vec<CompletionItem> {
+    all_condition_operators()
+        .iter()
+        .map(|operator| CompletionItem {
+            label: operator.to_string(),
+            kind: Some(CompletionItemKind::OPERATOR),
+            detail: operator.description().map(str::to_string),
+            ..Default::default()
+        })
+        .chain(
+            all_global_condition_keys()
+                .iter()
+                .map(|(key, description)| CompletionItem {
+                    label: key.to_string(),
+                    kind: Some(CompletionItemKind::FIELD),
+                    detail: Some(description.to_string()),
+                    ..Default::default()
+                }),
+        )
+        .collect()
+}
+
+///
+/// LSP positions count lines from 0 and characters in UTF-16 code units.
+///
+fn to_position(text: &str, offset: usize) -> Position {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    Position::new(
+        before.matches('\n').count() as u32,
+        before[line_start..].encode_utf16().count() as u32,
+    )
+}
+
+fn to_offset(text: &str, position: Position) -> usize {
+    let line_start: usize = text
+        .split_inclusive('\n')
+        .take(position.line as usize)
+        .map(str::len)
+        .sum();
+    let mut units = 0;
+    for (i, c) in text[line_start..].char_indices() {
+        if units >= position.character as usize || c == '\n' {
+            return line_start + i;
+        }
+        units += c.len_utf16();
+    }
+    text.len()
+}
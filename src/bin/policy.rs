@@ -4,17 +4,26 @@ Command-line tool to read and verify policy files and create new from templates.
 #[macro_use]
 extern crate tracing;
 
+use aws_iam::analysis;
 use aws_iam::document;
-use aws_iam::document::{LatexGenerator, MarkdownGenerator};
+use aws_iam::document::{LatexGenerator, MarkdownGenerator, TerminalGenerator};
+use aws_iam::error::IamError;
+#[cfg(feature = "plugins_wasmtime")]
+use aws_iam::error::PluginError;
 use aws_iam::io;
-use aws_iam::model::Policy;
-use std::error::Error;
-use std::fmt;
-use std::fs::OpenOptions;
-use std::io::{stdin, Write};
-use std::path::PathBuf;
+use aws_iam::io::SarifArtifact;
+use aws_iam::lint::LintRegistry;
+use aws_iam::model::{Action, Policy, Resource};
+use aws_iam::offline::run_spec;
+#[cfg(feature = "plugins_wasmtime")]
+use aws_iam::plugin::load_plugins;
+use aws_iam::validate::{validate_str, ValidationOptions};
+use std::fs::{File, OpenOptions};
+use std::io::{stdin, stdout, Read, Write};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use structopt::StructOpt;
+use thiserror::Error;
 use tracing_subscriber::filter::LevelFilter;
 use tracing_subscriber::{EnvFilter, FmtSubscriber};
 
@@ -48,13 +57,61 @@ enum Command {
     },
     /// Verify an existing policy document
     Verify {
-        /// Output format for successful results (latex, markdown, rust)
+        /// Output format for successful results (latex, markdown, rust, terminal), or for validation
+        /// findings (sarif)
         #[structopt(long, short)]
         format: Option<Format>,
         /// The input file to validate, stdin if not present
         #[structopt(parse(from_os_str))]
         file_name: Option<PathBuf>,
     },
+    /// Run the lint rules, and those of any rule packs, over a policy document
+    Lint {
+        /// A directory of rule packs, `.wasm` files, whose rules are run with the built-in rules
+        #[cfg(feature = "plugins_wasmtime")]
+        #[structopt(long, short, parse(from_os_str), env = "POLICY_PLUGINS")]
+        plugins: Option<PathBuf>,
+        /// The input file to lint, stdin if not present
+        #[structopt(parse(from_os_str))]
+        file_name: Option<PathBuf>,
+    },
+    /// Show a summary table of the statements in a policy document
+    Show {
+        /// The input file to summarize, stdin if not present
+        #[structopt(parse(from_os_str))]
+        file_name: Option<PathBuf>,
+    },
+    /// Show counts of the statements, actions, resources, and wildcards in a policy document
+    Stats {
+        /// Write the counts as a JSON object
+        #[structopt(long, short)]
+        json: bool,
+        /// The input file to count, stdin if not present
+        #[structopt(parse(from_os_str))]
+        file_name: Option<PathBuf>,
+    },
+    /// Show anonymized counts of the operators, actions, resources, and wildcards in a
+    /// directory of policy documents
+    Corpus {
+        /// Write the counts as a JSON object
+        #[structopt(long, short)]
+        json: bool,
+        /// Omit operators, action families, and resource types counted fewer times than this
+        #[structopt(long, short, default_value = "1")]
+        minimum_count: usize,
+        /// The directory of policy documents, searched recursively
+        #[structopt(parse(from_os_str))]
+        directory: PathBuf,
+    },
+    /// Run the test cases of a policy test specification
+    Test {
+        /// Also write the results as a JUnit XML report to this file
+        #[structopt(long, short, parse(from_os_str))]
+        junit: Option<PathBuf>,
+        /// The policy test specification file, JSON or, with the extension `.yaml`, YAML
+        #[structopt(name = "SPEC", parse(from_os_str))]
+        spec_file: PathBuf,
+    },
 }
 
 #[derive(Debug)]
@@ -62,11 +119,15 @@ enum Format {
     Rust,
     Markdown,
     Latex,
+    Sarif,
+    Terminal,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Error)]
 enum FormatError {
+    #[error("No format was provided")]
     MissingFormat,
+    #[error("Input not a valid format")]
     InvalidFormat,
 }
 
@@ -76,6 +137,8 @@ impl ToString for Format {
             Format::Rust => "rust".to_string(),
             Format::Markdown => "markdown".to_string(),
             Format::Latex => "latex".to_string(),
+            Format::Sarif => "sarif".to_string(),
+            Format::Terminal => "terminal".to_string(),
         }
     }
 }
@@ -92,32 +155,47 @@ impl FromStr for Format {
             Ok(Format::Markdown)
         } else if s == "latex" {
             Ok(Format::Latex)
+        } else if s == "sarif" {
+            Ok(Format::Sarif)
+        } else if s == "terminal" {
+            Ok(Format::Terminal)
         } else {
             Err(FormatError::InvalidFormat)
         }
     }
 }
 
-impl ToString for FormatError {
-    fn to_string(&self) -> String {
-        match self {
-            FormatError::MissingFormat => "No format was provided".to_string(),
-            FormatError::InvalidFormat => "Input not a valid format".to_string(),
-        }
-    }
-}
-
 // ------------------------------------------------------------------------------------------------
 // Main Function
 // ------------------------------------------------------------------------------------------------
 
-#[derive(Debug)]
+#[derive(Debug, Error)]
 enum ToolError {
-    CannotOpenForRead(String),
-    CannotOpenForWrite(String),
+    #[error("Error reading from file: {file_name}")]
+    CannotOpenForRead { file_name: String },
+    #[error("Error writing to file: {file_name}")]
+    CannotOpenForWrite {
+        file_name: String,
+        #[source]
+        source: Option<std::io::Error>,
+    },
+    #[error("No template named '{0}' supported")]
     InvalidTemplateName(String),
-    WriteToFile,
-    VerifyFailed,
+    #[error("Write operation to file failed")]
+    WriteToFile(#[source] std::io::Error),
+    #[error("Verification of policy failed")]
+    VerifyFailed(#[source] IamError),
+    #[error("Policy could not be read")]
+    ReadFailed(#[source] IamError),
+    #[error("Policy test specification could not be run")]
+    TestFailed(#[source] IamError),
+    #[error("{failures} test(s) failed, {errors} could not be evaluated")]
+    TestsDidNotPass { failures: usize, errors: usize },
+    #[error("{errors} lint error(s) found")]
+    LintFailed { errors: usize },
+    #[cfg(feature = "plugins_wasmtime")]
+    #[error("Rule packs could not be loaded")]
+    PluginFailed(#[source] PluginError),
 }
 
 fn main() -> Result<(), ToolError> {
@@ -138,6 +216,20 @@ fn main() -> Result<(), ToolError> {
             }
         }
         Command::Verify { file_name, format } => verify_file(file_name, format),
+        #[cfg(feature = "plugins_wasmtime")]
+        Command::Lint { file_name, plugins } => {
+            lint_registry(plugins).and_then(|registry| lint_file(file_name, registry))
+        }
+        #[cfg(not(feature = "plugins_wasmtime"))]
+        Command::Lint { file_name } => lint_file(file_name, LintRegistry::default()),
+        Command::Show { file_name } => show_file(file_name),
+        Command::Stats { file_name, json } => stats_file(file_name, json),
+        Command::Corpus {
+            directory,
+            json,
+            minimum_count,
+        } => corpus_stats(directory, json, minimum_count),
+        Command::Test { spec_file, junit } => test_spec(spec_file, junit),
     }
 }
 
@@ -155,18 +247,26 @@ fn init_tracing(verbosity: i8) {
         _ => LevelFilter::TRACE,
     };
 
-    let filter = EnvFilter::from_default_env()
-        .add_directive(
-            format!("{}={}", module_path!(), log_level)
-                .parse()
-                .expect("Issue with command-line trace directive"),
-        )
-        .add_directive(
-            format!("aws_iam={}", log_level)
-                .parse()
-                .expect("Issue with library trace directive"),
-        );
-    let subscriber = FmtSubscriber::builder().with_env_filter(filter).finish();
+    // without `-v` the filter is taken from `RUST_LOG`, so that collectors may select events.
+    let filter = if verbosity == 0 {
+        EnvFilter::from_default_env()
+    } else {
+        EnvFilter::from_default_env()
+            .add_directive(
+                format!("{}={}", module_path!(), log_level)
+                    .parse()
+                    .expect("Issue with command-line trace directive"),
+            )
+            .add_directive(
+                format!("aws_iam={}", log_level)
+                    .parse()
+                    .expect("Issue with library trace directive"),
+            )
+    };
+    let subscriber = FmtSubscriber::builder()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .finish();
 
     tracing::subscriber::set_global_default(subscriber)
         .expect("Unable to set global default tracing subscriber");
@@ -195,12 +295,10 @@ fn create_new_file(
         Some(file_name) => {
             if file_name.exists() && file_name.is_file() && !force_write {
                 error!("could not open file for write, not a file, or missing -f");
-                Err(ToolError::CannotOpenForWrite(
-                    file_name
-                        .to_str()
-                        .unwrap_or("{error in file name}")
-                        .to_string(),
-                ))
+                Err(ToolError::CannotOpenForWrite {
+                    file_name: display_name(&file_name),
+                    source: None,
+                })
             } else {
                 debug!("opening output file");
                 match OpenOptions::new()
@@ -215,18 +313,16 @@ fn create_new_file(
                             Ok(()) => Ok(()),
                             Err(e) => {
                                 error!("write error: {:?}", e);
-                                Err(ToolError::WriteToFile)
+                                Err(ToolError::WriteToFile(e))
                             }
                         }
                     }
                     Err(e) => {
                         error!("could not open file for write, error {:?}", e);
-                        Err(ToolError::CannotOpenForWrite(
-                            file_name
-                                .to_str()
-                                .unwrap_or("{error in file name}")
-                                .to_string(),
-                        ))
+                        Err(ToolError::CannotOpenForWrite {
+                            file_name: display_name(&file_name),
+                            source: Some(e),
+                        })
                     }
                 }
             }
@@ -242,6 +338,9 @@ fn create_new_file(
 fn verify_file(file_name: Option<PathBuf>, format: Option<Format>) -> Result<(), ToolError> {
     let span = debug_span!("verify_file", ?file_name, ?format);
     let _enter = span.enter();
+    if let Some(Format::Sarif) = format {
+        return validate_file(file_name);
+    }
     match file_name {
         Some(file_name) => {
             if file_name.exists() && file_name.is_file() {
@@ -249,12 +348,9 @@ fn verify_file(file_name: Option<PathBuf>, format: Option<Format>) -> Result<(),
                 verify_file_result(io::read_from_file(&file_name), format)
             } else {
                 error!("could not read from file");
-                Err(ToolError::CannotOpenForRead(
-                    file_name
-                        .to_str()
-                        .unwrap_or("{error in file name}")
-                        .to_string(),
-                ))
+                Err(ToolError::CannotOpenForRead {
+                    file_name: display_name(&file_name),
+                })
             }
         }
         None => {
@@ -264,8 +360,45 @@ fn verify_file(file_name: Option<PathBuf>, format: Option<Format>) -> Result<(),
     }
 }
 
+fn validate_file(file_name: Option<PathBuf>) -> Result<(), ToolError> {
+    let span = debug_span!("validate_file", ?file_name);
+    let _enter = span.enter();
+    let (uri, text) = match &file_name {
+        Some(file_name) => {
+            debug!("reading file");
+            match std::fs::read_to_string(file_name) {
+                Ok(text) => (display_name(file_name), text),
+                Err(_) => {
+                    error!("could not read from file");
+                    return Err(ToolError::CannotOpenForRead {
+                        file_name: display_name(file_name),
+                    });
+                }
+            }
+        }
+        None => {
+            debug!("reading from stdin");
+            let mut text = String::new();
+            let _ = stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| ToolError::VerifyFailed(e.into()))?;
+            ("stdin".to_string(), text)
+        }
+    };
+    let findings =
+        validate_str(&text, &ValidationOptions::default()).map_err(ToolError::VerifyFailed)?;
+    debug!(findings = findings.len(), "validated successfully");
+    io::write_findings_sarif(
+        stdout(),
+        &[SarifArtifact::new(&uri, &findings).with_text(&text)],
+    )
+    .map_err(ToolError::VerifyFailed)?;
+    println!();
+    Ok(())
+}
+
 fn verify_file_result(
-    result: Result<Policy, io::Error>,
+    result: Result<Policy, IamError>,
     format: Option<Format>,
 ) -> Result<(), ToolError> {
     let span = debug_span!("verify_file_result", ?result, ?format);
@@ -285,6 +418,11 @@ fn verify_file_result(
                             let mut generator = LatexGenerator::default();
                             document::visitor::walk_policy(&policy, &mut generator);
                         }
+                        Format::Terminal => {
+                            let mut generator = TerminalGenerator::default();
+                            document::visitor::walk_policy(&policy, &mut generator);
+                        }
+                        Format::Sarif => unreachable!("handled by validate_file"),
                     }
                 }
                 None => debug!("parsed successfully"),
@@ -292,45 +430,315 @@ fn verify_file_result(
             Ok(())
         }
         Err(e) => {
-            match e {
-                io::Error::DeserializingJson(s) => {
-                    error!("failed to parse, error: {:?}", s);
-                }
-                io::Error::ReadingFile(e) => {
-                    error!(
-                        "failed to read, error: {:?}, cause: {}",
-                        e,
-                        match e.source() {
-                            Some(source) => source.to_string(),
-                            None => "unknown".to_string(),
-                        }
-                    );
-                }
-                err => {
-                    error!("failed with an unexpected error: {:?}", err);
-                }
+            match &e {
+                IamError::Json(e) => error!("failed to parse, error: {}", e),
+                IamError::Format(e) => error!("failed to parse, error: {}", e),
+                IamError::Io(e) => error!("failed to read, error: {}", e),
+                e => error!("failed with an unexpected error: {}", e),
             }
-            Err(ToolError::VerifyFailed)
+            Err(ToolError::VerifyFailed(e))
         }
     }
 }
 
-impl fmt::Display for ToolError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        match self {
-            ToolError::CannotOpenForRead(file_name) => {
-                write!(f, "Error reading from file: {}", file_name)
+fn lint_file(file_name: Option<PathBuf>, registry: LintRegistry) -> Result<(), ToolError> {
+    let span = debug_span!("lint_file", ?file_name, rules = ?registry.names());
+    let _enter = span.enter();
+    let policy = read_policy(file_name)?;
+    let findings = registry.check(&policy);
+    for finding in findings.iter() {
+        println!("{}", finding);
+    }
+    match findings.errors().count() {
+        0 => Ok(()),
+        errors => Err(ToolError::LintFailed { errors }),
+    }
+}
+
+///
+/// The built-in lint rules, and the rules of each rule pack in the directory `plugins`.
+///
+#[cfg(feature = "plugins_wasmtime")]
+fn lint_registry(plugins: Option<PathBuf>) -> Result<LintRegistry, ToolError> {
+    let mut registry = LintRegistry::default();
+    if let Some(plugins) = plugins {
+        for pack in load_plugins(&plugins).map_err(ToolError::PluginFailed)? {
+            let rules = pack
+                .register(&mut registry)
+                .map_err(ToolError::PluginFailed)?;
+            debug!(rules, "registered rule pack");
+        }
+    }
+    Ok(registry)
+}
+
+fn show_file(file_name: Option<PathBuf>) -> Result<(), ToolError> {
+    let span = debug_span!("show_file", ?file_name);
+    let _enter = span.enter();
+    let policy = read_policy(file_name)?;
+
+    let mut rows = vec![vec![
+        "#".to_string(),
+        "Sid".to_string(),
+        "Effect".to_string(),
+        "Actions".to_string(),
+        "Resources".to_string(),
+        "Conditions".to_string(),
+    ]];
+    let (mut allows, mut actions, mut resources, mut conditions) = (0, 0, 0, 0);
+    for (index, statement) in policy.statements().enumerate() {
+        if statement.is_allow() {
+            allows += 1;
+        }
+        let (negated, action_count) = match statement.action() {
+            Action::Action(v) => (false, v.some().map(Vec::len)),
+            Action::NotAction(v) => (true, v.some().map(Vec::len)),
+        };
+        actions += action_count.unwrap_or_default();
+        let resource_count = match statement.resource() {
+            Some(Resource::Resource(v)) => Some((false, v.some().map(Vec::len))),
+            Some(Resource::NotResource(v)) => Some((true, v.some().map(Vec::len))),
+            None => None,
+        };
+        resources += resource_count
+            .and_then(|(_, count)| count)
+            .unwrap_or_default();
+        let condition_summary: Vec<String> = statement
+            .condition()
+            .map(|condition| {
+                condition
+                    .iter()
+                    .flat_map(|(operator, matches)| {
+                        matches
+                            .keys()
+                            .map(move |key| format!("{}({})", operator, key))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        conditions += condition_summary.len();
+        rows.push(vec![
+            index.to_string(),
+            statement
+                .sid()
+                .map(|sid| sid.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            statement.effect().to_string(),
+            count_cell(negated, action_count),
+            resource_count
+                .map(|(negated, count)| count_cell(negated, count))
+                .unwrap_or_else(|| "-".to_string()),
+            if condition_summary.is_empty() {
+                "-".to_string()
+            } else {
+                condition_summary.join(", ")
+            },
+        ]);
+    }
+    let statements = rows.len() - 1;
+
+    let widths: Vec<usize> = (0..rows[0].len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    for row in &rows {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    }
+    println!();
+    println!(
+        "{} statement(s), {} allow, {} deny; {} action(s), {} resource(s), {} condition(s)",
+        statements,
+        allows,
+        statements - allows,
+        actions,
+        resources,
+        conditions
+    );
+    Ok(())
+}
+
+fn stats_file(file_name: Option<PathBuf>, json: bool) -> Result<(), ToolError> {
+    let span = debug_span!("stats_file", ?file_name, ?json);
+    let _enter = span.enter();
+    let stats = analysis::stats(&read_policy(file_name)?);
+    if json {
+        serde_json::to_writer_pretty(stdout(), &stats)
+            .map_err(|e| ToolError::WriteToFile(e.into()))?;
+        println!();
+    } else {
+        println!(
+            "statements:         {} ({} allow, {} deny, {} with conditions)",
+            stats.statements,
+            stats.allow_statements,
+            stats.deny_statements,
+            stats.conditional_statements
+        );
+        println!("unique actions:     {}", stats.unique_actions);
+        println!("unique services:    {}", stats.unique_services);
+        println!("unique resources:   {}", stats.unique_resources);
+        println!("wildcard actions:   {}", stats.wildcard_actions);
+        println!("wildcard resources: {}", stats.wildcard_resources);
+        println!("public principals:  {}", stats.public_principals);
+        println!(
+            "size:               {} of {} characters{}",
+            stats.size,
+            stats.size_limit,
+            if stats.is_within_limit() {
+                ""
+            } else {
+                ", over the limit"
             }
-            ToolError::CannotOpenForWrite(file_name) => {
-                write!(f, "Error writing to file: {}", file_name)
+        );
+    }
+    Ok(())
+}
+
+fn corpus_stats(directory: PathBuf, json: bool, minimum_count: usize) -> Result<(), ToolError> {
+    let span = debug_span!("corpus_stats", ?directory, ?json, ?minimum_count);
+    let _enter = span.enter();
+    let stats = analysis::corpus_stats_for_directory(&directory)
+        .map_err(ToolError::ReadFailed)?
+        .with_minimum_count(minimum_count);
+    if json {
+        serde_json::to_writer_pretty(stdout(), &stats)
+            .map_err(|e| ToolError::WriteToFile(e.into()))?;
+        println!();
+    } else {
+        println!(
+            "policies:           {} ({} unreadable files)",
+            stats.policies, stats.unreadable_files
+        );
+        println!(
+            "statements:         {} ({} allow, {} deny, {} with conditions)",
+            stats.statements,
+            stats.allow_statements,
+            stats.deny_statements,
+            stats.conditional_statements
+        );
+        println!(
+            "wildcard actions:   {} of {} ({:.1}%)",
+            stats.wildcard_actions,
+            stats.actions,
+            stats.wildcard_action_rate() * 100.0
+        );
+        println!(
+            "wildcard resources: {} of {} ({:.1}%)",
+            stats.wildcard_resources,
+            stats.resources,
+            stats.wildcard_resource_rate() * 100.0
+        );
+        println!(
+            "public principals:  {} ({:.1}%)",
+            stats.public_principals,
+            stats.public_principal_rate() * 100.0
+        );
+        for (title, counts) in [
+            ("operators", &stats.operators),
+            ("action families", &stats.action_families),
+            ("resource types", &stats.resource_types),
+        ] {
+            println!("{}:", title);
+            let mut counts: Vec<(&String, &usize)> = counts.iter().collect();
+            counts.sort_by(|(_, lhs), (_, rhs)| rhs.cmp(lhs));
+            for (term, count) in counts {
+                println!("  {:<40} {}", term, count);
             }
-            ToolError::InvalidTemplateName(name) => {
-                write!(f, "No template named '{}' supported", name)
+        }
+    }
+    Ok(())
+}
+
+fn read_policy(file_name: Option<PathBuf>) -> Result<Policy, ToolError> {
+    match file_name {
+        Some(file_name) => {
+            if file_name.exists() && file_name.is_file() {
+                debug!("reading file");
+                io::read_from_file(&file_name)
+            } else {
+                error!("could not read from file");
+                return Err(ToolError::CannotOpenForRead {
+                    file_name: display_name(&file_name),
+                });
             }
-            ToolError::WriteToFile => write!(f, "Write operation to file failed"),
-            ToolError::VerifyFailed => write!(f, "Verification of policy failed"),
+        }
+        None => {
+            debug!("reading from stdin");
+            io::read_from_reader(stdin())
         }
     }
+    .map_err(ToolError::ReadFailed)
+}
+
+fn count_cell(negated: bool, count: Option<usize>) -> String {
+    format!(
+        "{}{}",
+        if negated { "NOT " } else { "" },
+        match count {
+            Some(count) => count.to_string(),
+            None => "*".to_string(),
+        }
+    )
+}
+
+fn test_spec(spec_file: PathBuf, junit: Option<PathBuf>) -> Result<(), ToolError> {
+    let span = debug_span!("test_spec", ?spec_file, ?junit);
+    let _enter = span.enter();
+    let report = run_spec(&spec_file).map_err(ToolError::TestFailed)?;
+    for result in report.results() {
+        let status = if result.is_success() {
+            "ok"
+        } else if result.is_error() {
+            "ERROR"
+        } else {
+            "FAILED"
+        };
+        match &result.result {
+            Ok(actual) => println!("{} ... {} ({})", result.name, status, actual),
+            Err(e) => println!("{} ... {} ({})", result.name, status, e),
+        }
+    }
+    let coverage = report.coverage();
+    println!(
+        "{} of {} statement(s) matched",
+        coverage.covered(),
+        coverage.statements().len()
+    );
+    for statement in coverage.uncovered() {
+        println!("{} ... not matched", statement);
+    }
+    if let Some(junit) = junit {
+        let file = File::create(&junit).map_err(|e| ToolError::CannotOpenForWrite {
+            file_name: display_name(&junit),
+            source: Some(e),
+        })?;
+        report.write_junit(file).map_err(ToolError::WriteToFile)?;
+    }
+    if report.is_success() {
+        println!(
+            "{}: {} test(s) passed",
+            report.name(),
+            report.results().len()
+        );
+        Ok(())
+    } else {
+        Err(ToolError::TestsDidNotPass {
+            failures: report.failures(),
+            errors: report.errors(),
+        })
+    }
+}
+
+fn display_name(file_name: &Path) -> String {
+    file_name.to_string_lossy().to_string()
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -0,0 +1,339 @@
+/*!
+A decision service that answers authorization requests against a directory of policies, in the
+manner of a policy agent deployed beside an application.
+
+The service loads every `*.json` policy file in its directory at startup and reloads them when
+any file is added, removed, or changed, see `io::watch_directory`; if a reload fails, because
+a policy cannot be read or is not valid, the policies already loaded are kept.
+With the feature `signing`, and one or more `--trusted-key` files, each containing a base64
+public key, a policy is only loaded if its signature file was made by one of those keys, see
+the `signing` module.
+It offers a small HTTP/JSON API:
+
+* `POST /v1/authorize`, with an evaluator request as its body, returns the decision, the
+  statement that decided it, and a trace of every statement's contribution, see
+  `offline::explain`; a body larger than `--max-body` bytes is rejected with `413`;
+* `GET /v1/policies` returns the names of the policy files loaded;
+* `GET /health` returns `200 OK` once policies are loaded.
+
+```bash
+$ policyd --address 127.0.0.1:8181 ./policies
+$ curl -s localhost:8181/v1/authorize -d '{"action": "s3:GetObject",
+    "resource": "arn:aws:s3:::bucket/key", "environment": {}}'
+```
+*/
+
+#[macro_use]
+extern crate tracing;
+
+use aws_iam::error::IamError;
+use aws_iam::io::{watch_directory_with, PolicySet, PolicySetEvent, PolicySetHandle, WatchOptions};
+use aws_iam::offline::{
+    evaluate_all_compiled, explain_compiled, EvaluationResult, Request, StatementRef,
+};
+#[cfg(feature = "signing")]
+use aws_iam::signing::{verifying_key_from_str, VerifyingKey};
+use serde_json::{json, Value};
+use std::io::Read;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use structopt::StructOpt;
+use thiserror::Error;
+use tiny_http::{Header, Method, Response, Server, StatusCode};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::{EnvFilter, FmtSubscriber};
+
+// ------------------------------------------------------------------------------------------------
+// Command-Line Parsing
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "policyd")]
+struct Cli {
+    /// The level of logging to perform, from off to trace
+    #[structopt(long, short = "v", parse(from_occurrences))]
+    verbose: i8,
+    /// The address to listen on
+    #[structopt(long, short, default_value = "127.0.0.1:8181")]
+    address: SocketAddr,
+    /// The number of threads answering requests
+    #[structopt(long, default_value = "4")]
+    threads: usize,
+    /// Seconds between checks for changed policy files
+    #[structopt(long, default_value = "5")]
+    reload_interval: u64,
+    /// The largest request body accepted, in bytes
+    #[structopt(long, default_value = "65536")]
+    max_body: u64,
+    /// A file containing a base64 public key; policies must be signed by a trusted key
+    #[cfg(feature = "signing")]
+    #[structopt(long, parse(from_os_str))]
+    trusted_key: Vec<PathBuf>,
+    /// The directory containing the policy files
+    #[structopt(name = "DIR", parse(from_os_str))]
+    directory: PathBuf,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Main
+// ------------------------------------------------------------------------------------------------
+
+fn main() -> Result<(), DaemonError> {
+    let args = Cli::from_args();
+
+    init_tracing(args.verbose);
+
+    let interval = Duration::from_secs(args.reload_interval.max(1));
+    let options = WatchOptions::default().with_interval(interval);
+    #[cfg(feature = "signing")]
+    let options = options.with_trusted_keys(read_trusted_keys(&args.trusted_key)?);
+    let policies = Arc::new(
+        watch_directory_with(&args.directory, options).map_err(|source| {
+            DaemonError::ReadPolicies {
+                directory: args.directory.clone(),
+                source,
+            }
+        })?,
+    );
+    info!(
+        "loaded {} policies from {:?}",
+        policies.current().len(),
+        args.directory
+    );
+
+    let events = policies.subscribe();
+    let _ = thread::spawn(move || {
+        for event in events {
+            match event {
+                PolicySetEvent::Reloaded(set) => info!("reloaded {} policies", set.len()),
+                PolicySetEvent::Rejected(e) => {
+                    error!("reload failed, keeping current policies: {}", e)
+                }
+            }
+        }
+    });
+
+    let server = Arc::new(Server::http(args.address).map_err(DaemonError::Listen)?);
+    info!("listening on {}", args.address);
+    let workers: Vec<_> = (0..args.threads.max(1))
+        .map(|_| {
+            let server = server.clone();
+            let policies = policies.clone();
+            let max_body = args.max_body;
+            thread::spawn(move || serve(&server, &policies, max_body))
+        })
+        .collect();
+    for worker in workers {
+        let _ = worker.join();
+    }
+    Ok(())
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug, Error)]
+enum DaemonError {
+    #[error("Error reading policies from directory: {directory}")]
+    ReadPolicies {
+        directory: PathBuf,
+        #[source]
+        source: IamError,
+    },
+    #[error("Error listening for requests")]
+    Listen(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[cfg(feature = "signing")]
+    #[error("Error reading trusted key: {path}")]
+    ReadKey {
+        path: PathBuf,
+        #[source]
+        source: IamError,
+    },
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const CONTENT_TYPE_JSON: &str = "application/json";
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(feature = "signing")]
+fn read_trusted_keys(paths: &[PathBuf]) -> Result<Vec<VerifyingKey>, DaemonError> {
+    paths
+        .iter()
+        .map(|path| {
+            std::fs::read_to_string(path)
+                .map_err(IamError::from)
+                .and_then(|key| verifying_key_from_str(&key).map_err(IamError::from))
+                .map_err(|source| DaemonError::ReadKey {
+                    path: path.clone(),
+                    source,
+                })
+        })
+        .collect()
+}
+
+fn init_tracing(verbosity: i8) {
+    let log_level = match verbosity {
+        0 => LevelFilter::OFF,
+        1 => LevelFilter::ERROR,
+        2 => LevelFilter::WARN,
+        3 => LevelFilter::INFO,
+        4 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    };
+
+    // without `-v` the filter is taken from `RUST_LOG`, so that collectors may select events.
+    let filter = if verbosity == 0 {
+        EnvFilter::from_default_env()
+    } else {
+        EnvFilter::from_default_env()
+            .add_directive(
+                format!("{}={}", module_path!(), log_level)
+                    .parse()
+                    .expect("Issue with command-line trace directive"),
+            )
+            .add_directive(
+                format!("aws_iam={}", log_level)
+                    .parse()
+                    .expect("Issue with library trace directive"),
+            )
+    };
+    let subscriber = FmtSubscriber::builder()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .finish();
+
+    tracing::subscriber::set_global_default(subscriber)
+        .expect("Unable to set global default tracing subscriber");
+    info!("Log level set to `LevelFilter::{:?}`", log_level);
+}
+
+fn authorize(policies: &PolicySet, request: &Request) -> Value {
+    let (decision, reason, statement) = match evaluate_all_compiled(request, policies.compiled()) {
+        Ok(result @ EvaluationResult::Allow(_)) => {
+            ("allow", result.to_string(), result.statement().cloned())
+        }
+        Ok(result) => ("deny", result.to_string(), result.statement().cloned()),
+        Err(error) => ("deny", error.to_string(), None),
+    };
+    let trace: Vec<Value> = policies
+        .files()
+        .zip(policies.compiled())
+        .flat_map(|((path, _), compiled)| {
+            let name = file_name(path);
+            explain_compiled(request, compiled)
+                .into_iter()
+                .map(move |report| {
+                    json!({
+                        "policy": name,
+                        "statement": report.statement,
+                        "sid": report.sid,
+                        "effect": report.effect.to_string(),
+                        "principal": report.principal.to_string(),
+                        "action": report.action.to_string(),
+                        "resource": report.resource.to_string(),
+                        "conditions": report.conditions.iter().map(|condition| json!({
+                            "operator": condition.operator.to_string(),
+                            "key": condition.key.to_string(),
+                            "result": match &condition.result {
+                                Ok(result) => result.to_string(),
+                                Err(error) => error.to_string(),
+                            },
+                        })).collect::<Vec<Value>>(),
+                        "contribution": report.contribution.to_string(),
+                    })
+                })
+        })
+        .collect();
+    json!({
+        "decision": decision,
+        "reason": reason,
+        "statement": statement.map(|statement| statement_value(policies, &statement)),
+        "trace": trace,
+    })
+}
+
+fn statement_value(policies: &PolicySet, statement: &StatementRef) -> Value {
+    json!({
+        "policy": policies.files().nth(statement.policy).map(|(path, _)| file_name(path)),
+        "statement": statement.statement,
+        "sid": statement.sid,
+    })
+}
+
+///
+/// Read the body of `request`, or `None` if it is longer than `limit` bytes; no more than one
+/// byte over the limit is read, whatever length the client claims.
+///
+fn read_body(request: &mut tiny_http::Request, limit: u64) -> std::io::Result<Option<String>> {
+    if request.body_length().map(|length| length as u64 > limit) == Some(true) {
+        return Ok(None);
+    }
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(limit + 1)
+        .read_to_string(&mut body)?;
+    Ok(if body.len() as u64 > limit {
+        None
+    } else {
+        Some(body)
+    })
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn serve(server: &Server, policies: &PolicySetHandle, max_body: u64) {
+    for mut request in server.incoming_requests() {
+        let method = request.method().clone();
+        let url = request.url().to_string();
+        let (status, body) = match (&method, url.as_str()) {
+            (Method::Post, "/v1/authorize") => match read_body(&mut request, max_body) {
+                Ok(Some(body)) => match serde_json::from_str::<Request>(&body) {
+                    Ok(request) => (200, authorize(&policies.current(), &request)),
+                    Err(e) => (400, json!({ "error": e.to_string() })),
+                },
+                Ok(None) => (413, json!({ "error": "request body too large" })),
+                Err(e) => (400, json!({ "error": e.to_string() })),
+            },
+            (Method::Get, "/v1/policies") => (
+                200,
+                json!({
+                    "policies": policies
+                        .current()
+                        .files()
+                        .map(|(path, _)| file_name(path))
+                        .collect::<Vec<String>>(),
+                }),
+            ),
+            (Method::Get, "/health") => (200, json!({})),
+            (_, "/v1/authorize") | (_, "/v1/policies") | (_, "/health") => {
+                (405, json!({ "error": "method not allowed" }))
+            }
+            _ => (404, json!({ "error": "not found" })),
+        };
+        debug!("{} {} {}", method, url, status);
+        let response = Response::from_string(body.to_string())
+            .with_status_code(StatusCode(status))
+            .with_header(
+                Header::from_bytes(&b"Content-Type"[..], CONTENT_TYPE_JSON.as_bytes())
+                    .expect("Issue with content type header"),
+            );
+        if let Err(e) = request.respond(response) {
+            warn!("error writing response: {}", e);
+        }
+    }
+}
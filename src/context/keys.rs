@@ -16,6 +16,19 @@ From [AWS Global Condition Context Keys](https://docs.aws.amazon.com/IAM/latest/
 // Public Values
 // ------------------------------------------------------------------------------------------------
 
+/// Use this key to compare the services that made requests on behalf of the principal,
+/// in order, with the services that you specify in the policy. This key is multi-valued
+/// and so is used with the `ForAnyValue` and `ForAllValues` set operators.
+pub const AWS_CALLED_VIA: &str = "aws:CalledVia";
+
+/// Use this key to compare the first service that made a request on behalf of the
+/// principal with the service that you specify in the policy.
+pub const AWS_CALLED_VIA_FIRST: &str = "aws:CalledViaFirst";
+
+/// Use this key to compare the last service that made a request on behalf of the
+/// principal with the service that you specify in the policy.
+pub const AWS_CALLED_VIA_LAST: &str = "aws:CalledViaLast";
+
 /// Use this key to compare the date and time of the request with the date and time
 ///  that you specify in the policy.
 pub const AWS_CURRENT_TIME: &str = "aws:CurrentTime";
@@ -25,6 +38,11 @@ pub const AWS_CURRENT_TIME: &str = "aws:CurrentTime";
 /// of seconds since January 1, 1970.
 pub const AWS_EPOCH_TIME: &str = "aws:EpochTime";
 
+/// Use this key to compare the identity provider (IdP) that the principal was
+/// authenticated with when federating into AWS with the provider that you specify
+/// in the policy.
+pub const AWS_FEDERATED_PROVIDER: &str = "aws:FederatedProvider";
+
 /// Use this key to compare the number of seconds since the requesting principal
 /// was authorized using MFA with the number that you specify in the policy.
 pub const AWS_MFA_AGE: &str = "aws:MultiFactorAuthAge";
@@ -48,6 +66,10 @@ pub const AWS_PRINCIPAL_ARN: &str = "aws:PrincipalArn";
 /// the policy.
 pub const AWS_PRINCIPAL_ORG_ID: &str = "aws:PrincipalOrgID";
 
+/// Use this key to compare the service principal name in the policy with the
+/// service principal that is making requests to resources in your account.
+pub const AWS_PRINCIPAL_SERVICE_NAME: &str = "aws:PrincipalServiceName";
+
 /// Use this key to compare the tag attached to the principal making the request
 /// with the tag that you specify in the policy. If the principal has more than
 /// one tag attached, the request context includes one aws:PrincipalTag key for
@@ -74,6 +96,10 @@ pub const AWS_REQUESTED_REGION: &str = "aws:RequestedRegion";
 /// "Accounting".
 pub const AWS_REQUEST_TAG: &str = "aws:RequestTag/";
 
+/// Use this key to compare the account ID of the resource being accessed with the
+/// account that you specify in the policy.
+pub const AWS_RESOURCE_ACCOUNT: &str = "aws:ResourceAccount";
+
 /// Use this key to compare the tag key-value pair that you specify in the policy
 /// with the key-value pair that is attached to the resource. For example, you
 /// could require that access to a resource is allowed only if the resource has
@@ -142,8 +168,202 @@ pub const AWS_USER_ID: &str = "aws:userid";
 /// specify in the policy.
 pub const AWS_USER_NAME: &str = "aws:username";
 
+/// Use this key to check whether an AWS service makes a request to another service
+/// using the credentials of the principal. The request context returns true or false.
+pub const AWS_VIA_AWS_SERVICE: &str = "aws:ViaAWSService";
+
 /// Use this key to compare the IP address from which a request was made with the
 /// IP address that you specify in the policy. In a policy, the key matches only
 /// if the request originates from the specified IP address and it goes through
 /// a VPC endpoint.
 pub const AWS_VPC_SOURCE_ID: &str = "aws:VpcSourceIp";
+
+///
+/// The global condition keys above, each with the first sentence of its description, for tools
+/// that describe keys to users; the tag keys end in `/` and match any tag key name.
+///
+pub const GLOBAL_CONDITION_KEYS: &[(&str, &str)] = &[
+    (
+        AWS_CALLED_VIA,
+        "Use this key to compare the services that made requests on behalf of the principal, \
+         in order, with the services that you specify in the policy.",
+    ),
+    (
+        AWS_CALLED_VIA_FIRST,
+        "Use this key to compare the first service that made a request on behalf of the \
+         principal with the service that you specify in the policy.",
+    ),
+    (
+        AWS_CALLED_VIA_LAST,
+        "Use this key to compare the last service that made a request on behalf of the \
+         principal with the service that you specify in the policy.",
+    ),
+    (
+        AWS_CURRENT_TIME,
+        "Use this key to compare the date and time of the request with the date and time \
+         that you specify in the policy.",
+    ),
+    (
+        AWS_EPOCH_TIME,
+        "Use this key to compare the date and time of the request in epoch or Unix time \
+         with the value that you specify in the policy.",
+    ),
+    (
+        AWS_FEDERATED_PROVIDER,
+        "Use this key to compare the identity provider (IdP) that the principal was \
+         authenticated with when federating into AWS with the provider that you specify \
+         in the policy.",
+    ),
+    (
+        AWS_MFA_AGE,
+        "Use this key to compare the number of seconds since the requesting principal \
+         was authorized using MFA with the number that you specify in the policy.",
+    ),
+    (
+        AWS_MFA_PRESENT,
+        "Use this key to check whether multi-factor authentication (MFA) was used to \
+         validate the temporary security credentials that made the request.",
+    ),
+    (
+        AWS_PRINCIPAL_ACCOUNT,
+        "Use this key to compare the account to which the requesting principal belongs \
+         with the account identifier that you specify in the policy.",
+    ),
+    (
+        AWS_PRINCIPAL_ARN,
+        "Use this key to compare the Amazon Resource Name (ARN) of the principal that \
+         made the request with the ARN that you specify in the policy.",
+    ),
+    (
+        AWS_PRINCIPAL_ORG_ID,
+        "Use this key to compare the identifier of the organization in AWS Organizations \
+         to which the requesting principal belongs with the identifier specified in \
+         the policy.",
+    ),
+    (
+        AWS_PRINCIPAL_SERVICE_NAME,
+        "Use this key to compare the service principal name in the policy with the \
+         service principal that is making requests to resources in your account.",
+    ),
+    (
+        AWS_PRINCIPAL_TAG,
+        "Use this key to compare the tag attached to the principal making the request \
+         with the tag that you specify in the policy.",
+    ),
+    (
+        AWS_PRINCIPAL_TYPE,
+        "Use this key to compare the type of principal making the request with the \
+         principal type that you specify in the policy.",
+    ),
+    (
+        AWS_REFERER,
+        "Use this key to compare who referred the request in the client browser with \
+         the referer that you specify in the policy.",
+    ),
+    (
+        AWS_REQUESTED_REGION,
+        "Use this key to compare the AWS Region that was called in the request with \
+         the region that you specify in the policy.",
+    ),
+    (
+        AWS_REQUEST_TAG,
+        "Use this key to compare the tag key-value pair that was passed in the request \
+         with the tag pair that you specify in the policy.",
+    ),
+    (
+        AWS_RESOURCE_ACCOUNT,
+        "Use this key to compare the account ID of the resource being accessed with the \
+         account that you specify in the policy.",
+    ),
+    (
+        AWS_RESOURCE_TAG,
+        "Use this key to compare the tag key-value pair that you specify in the policy \
+         with the key-value pair that is attached to the resource.",
+    ),
+    (
+        AWS_SECURE_TRANSPORT,
+        "Use this key to check whether the request was sent using SSL.",
+    ),
+    (
+        AWS_SOURCE_ACCOUNT,
+        "Use this key to compare the source of the request with the account ID that \
+         you specify in the policy.",
+    ),
+    (
+        AWS_SOURCE_ARN,
+        "Use this key to compare the source of the request with the Amazon Resource \
+         Name (ARN) that you specify in the policy.",
+    ),
+    (
+        AWS_SOURCE_IP,
+        "Use this key to compare the requester's IP address with the IP address that \
+         you specify in the policy.",
+    ),
+    (
+        AWS_SOURCE_VPC,
+        "Use this key to check whether the request comes from the VPC that you specify \
+         in the policy.",
+    ),
+    (
+        AWS_SOURCE_VPCE,
+        "Use this key to compare the VPC endpoint identifier of the request with the \
+         endpoint ID that you specify in the policy.",
+    ),
+    (
+        AWS_TAG_KEYS,
+        "Use this key to compare the tag keys in a request with the keys that you \
+         specify in the policy.",
+    ),
+    (
+        AWS_TOKEN_ISSUE_TIME,
+        "Use this key to compare the date and time that temporary security credentials \
+         were issued with the date and time that you specify in the policy.",
+    ),
+    (
+        AWS_USER_AGENT,
+        "Use this key to compare the requester's client application with the \
+         application that you specify in the policy.",
+    ),
+    (
+        AWS_USER_ID,
+        "Use this key to compare the requester's principal identifier with the ID that \
+         you specify in the policy.",
+    ),
+    (
+        AWS_USER_NAME,
+        "Use this key to compare the requester's user name with the user name that you \
+         specify in the policy.",
+    ),
+    (
+        AWS_VIA_AWS_SERVICE,
+        "Use this key to check whether an AWS service makes a request to another service \
+         using the credentials of the principal.",
+    ),
+    (
+        AWS_VPC_SOURCE_ID,
+        "Use this key to compare the IP address from which a request was made with the \
+         IP address that you specify in the policy.",
+    ),
+];
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Return the description of the global condition key `key`, if it is one; as in policies,
+/// the comparison ignores case.
+///
+pub fn describe_key(key: &str) -> Option<&'static str> {
+    GLOBAL_CONDITION_KEYS
+        .iter()
+        .find(|(name, _)| {
+            if name.ends_with('/') {
+                key.len() > name.len()
+                    && matches!(key.get(..name.len()), Some(prefix) if prefix.eq_ignore_ascii_case(name))
+            } else {
+                key.eq_ignore_ascii_case(name)
+            }
+        })
+        .map(|(_, description)| *description)
+}
@@ -0,0 +1,559 @@
+/*!
+Provides diagnostics with source locations, for editor and language server integrations that
+underline the offending part of a policy document.
+
+Policies are parsed from a `serde_json::Value`, which does not retain where each element was
+in the document. A [`SourceMap`](struct.SourceMap.html) is built from the same text, when
+locations are wanted, and records the [`Span`](struct.Span.html) of the key and value of every
+element by its path, such as `Statement[1].Condition`; paths use the same form as the
+messages of [`FINDING_DUPLICATE_KEY`](../validate/constant.FINDING_DUPLICATE_KEY.html).
+
+[`diagnose_str`](fn.diagnose_str.html) parses and validates a document, as
+[`validate_str`](../validate/fn.validate_str.html) does, and returns each problem as a
+[`Diagnostic`](struct.Diagnostic.html) located at the element it concerns: the `Sid` of a
+duplicate `Sid`, the `Condition` key of a condition problem, the repeated key of a duplicate
+key, and so on. Findings from elsewhere, such as a
+[`LintRegistry`](../lint/struct.LintRegistry.html), are located with `SourceMap::locate`.
+
+Lines and columns are counted from 1, and columns are counted in characters; offsets are in
+bytes from the start of the document.
+
+# Example
+
+```rust
+use aws_iam::diagnostic::diagnose_str;
+use aws_iam::validate::{ValidationOptions, FINDING_DUPLICATE_SID};
+
+let document = r#"{
+  "Statement": [
+    {"Sid": "Read", "Effect": "Allow", "Action": "s3:GetObject", "Resource": "*"},
+    {"Sid": "Read", "Effect": "Allow", "Action": "s3:ListBucket", "Resource": "*"}
+  ]
+}"#;
+let diagnostics = diagnose_str(document, &ValidationOptions::default());
+
+assert_eq!(diagnostics.len(), 1);
+assert_eq!(diagnostics[0].code, FINDING_DUPLICATE_SID);
+assert_eq!(diagnostics[0].span.start.line, 4);
+assert_eq!(diagnostics[0].span.start.column, 13);
+assert_eq!(&document[diagnostics[0].span.range()], "\"Read\"");
+```
+*/
+
+use crate::error::{IamError, IamFormatError};
+use crate::syntax::{
+    CONDITION_NAME, PRINCIPAL_VALUE_NOT_PRINCIPAL, PRINCIPAL_VALUE_PRINCIPAL, SID_NAME,
+    STATEMENT_NAME,
+};
+use crate::validate::{
+    validate_str, Finding, Severity, ValidationOptions, FINDING_DUPLICATE_CONDITION_KEY,
+    FINDING_DUPLICATE_KEY, FINDING_DUPLICATE_SID, FINDING_INAPPLICABLE_CONDITION_KEY,
+    FINDING_INVALID_CONDITION_VALUE, FINDING_INVALID_SID, FINDING_TRUST_MISSING_EXTERNAL_ID,
+    FINDING_TRUST_MISSING_SOURCE, FINDING_TRUST_PUBLIC_PRINCIPAL,
+    FINDING_UNKNOWN_CONDITION_OPERATOR,
+};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A location in a document.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    /// The offset, in bytes, from the start of the document.
+    pub offset: usize,
+    /// The line, counted from 1.
+    pub line: usize,
+    /// The column, in characters, counted from 1.
+    pub column: usize,
+}
+
+///
+/// The part of a document between two positions; `end` is the position just after the last
+/// character.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span {
+    /// The position of the first character.
+    pub start: Position,
+    /// The position after the last character.
+    pub end: Position,
+}
+
+///
+/// The locations of the elements of a JSON document, by path.
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceMap {
+    entries: Vec<Entry>,
+}
+
+///
+/// A problem in a document, with its location.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// How serious the problem is.
+    pub severity: Severity,
+    /// A stable identifier for the kind of problem, one of the `FINDING_*` or `DIAGNOSTIC_*`
+    /// constants.
+    pub code: &'static str,
+    /// A human-readable description of the problem.
+    pub message: String,
+    /// Where the problem is.
+    pub span: Span,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+/// The document is not valid JSON.
+pub const DIAGNOSTIC_SYNTAX_ERROR: &str = "SyntaxError";
+
+/// The document is valid JSON, but is not a correctly formed policy.
+pub const DIAGNOSTIC_FORMAT_ERROR: &str = "FormatError";
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Parse the policy document `s` and validate it according to `options`, returning each
+/// problem found with its location. If the document cannot be parsed the only diagnostic is
+/// the reason, located where the parser stopped or at the element it names.
+///
+pub fn diagnose_str(s: &str, options: &ValidationOptions) -> Vec<Diagnostic> {
+    let source_map = match SourceMap::from_str(s) {
+        Ok(source_map) => source_map,
+        Err(e) => {
+            let position = match &e {
+                IamError::Json(e) => position_at(s, e.line(), e.column()),
+                _ => Position::default(),
+            };
+            return vec![Diagnostic {
+                severity: Severity::Error,
+                code: DIAGNOSTIC_SYNTAX_ERROR,
+                message: e.to_string(),
+                span: Span {
+                    start: position,
+                    end: position,
+                },
+            }];
+        }
+    };
+    match validate_str(s, options) {
+        Ok(findings) => {
+            let mut duplicates = source_map.duplicates();
+            findings
+                .iter()
+                .map(|finding| {
+                    let span = if finding.code == FINDING_DUPLICATE_KEY {
+                        duplicates
+                            .next()
+                            .unwrap_or_else(|| source_map.locate(finding))
+                    } else {
+                        source_map.locate(finding)
+                    };
+                    Diagnostic::new(finding, span)
+                })
+                .collect()
+        }
+        Err(IamError::Format(e)) => vec![Diagnostic {
+            severity: Severity::Error,
+            code: DIAGNOSTIC_FORMAT_ERROR,
+            span: source_map.locate_error(&e),
+            message: e.to_string(),
+        }],
+        Err(e) => vec![Diagnostic {
+            severity: Severity::Error,
+            code: DIAGNOSTIC_FORMAT_ERROR,
+            message: e.to_string(),
+            span: source_map.document(),
+        }],
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    path: String,
+    key: Option<Span>,
+    value: Span,
+    string: Option<String>,
+    duplicate: bool,
+}
+
+struct Scanner<'a> {
+    text: &'a str,
+    bytes: &'a [u8],
+    offset: usize,
+    line_starts: Vec<usize>,
+    entries: Vec<Entry>,
+    paths: HashSet<String>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+impl Span {
+    /// The byte range of the span, to index the document.
+    pub fn range(&self) -> Range<usize> {
+        self.start.offset..self.end.offset
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl FromStr for SourceMap {
+    type Err = IamError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let _: Value = serde_json::from_str(s)?;
+        let mut scanner = Scanner::new(s);
+        scanner.value(String::new(), None);
+        Ok(Self {
+            entries: scanner.entries,
+        })
+    }
+}
+
+impl SourceMap {
+    /// The span of the whole document.
+    pub fn document(&self) -> Span {
+        self.entries
+            .first()
+            .map(|entry| entry.value)
+            .unwrap_or_default()
+    }
+
+    ///
+    /// The span of the key of the element at `path`; if the key is repeated this is the last
+    /// occurrence, whose value is used.
+    ///
+    pub fn key(&self, path: &str) -> Option<Span> {
+        self.entry(path).and_then(|entry| entry.key)
+    }
+
+    /// The span of the value of the element at `path`.
+    pub fn value(&self, path: &str) -> Option<Span> {
+        self.entry(path).map(|entry| entry.value)
+    }
+
+    /// The span of the statement at `index`.
+    pub fn statement(&self, index: usize) -> Option<Span> {
+        self.value(&statement_path(index))
+    }
+
+    /// The paths of all the elements in the document, in order.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|entry| entry.path.as_str())
+    }
+
+    ///
+    /// The path of the element whose key contains the byte `offset`, and the span of that key,
+    /// for example to describe the key under an editor's cursor.
+    ///
+    pub fn key_at(&self, offset: usize) -> Option<(&str, Span)> {
+        self.entries.iter().find_map(|entry| match entry.key {
+            Some(key) if key.range().contains(&offset) => Some((entry.path.as_str(), key)),
+            _ => None,
+        })
+    }
+
+    /// The spans of the keys that repeat an earlier key in the same object, in order.
+    pub fn duplicates(&self) -> impl Iterator<Item = Span> + '_ {
+        self.entries
+            .iter()
+            .filter(|entry| entry.duplicate)
+            .filter_map(|entry| entry.key)
+    }
+
+    ///
+    /// The span of the element `finding` concerns: a key within its statement where the
+    /// finding's code identifies one, otherwise its statement, or the whole document.
+    ///
+    pub fn locate(&self, finding: &Finding) -> Span {
+        let statement = match finding.statement {
+            Some(index) => statement_path(index),
+            None => return self.document(),
+        };
+        let keys: &[&str] = match finding.code {
+            FINDING_INVALID_SID | FINDING_DUPLICATE_SID => {
+                if let Some(span) = self.value(&format!("{}.{}", statement, SID_NAME)) {
+                    return span;
+                }
+                &[]
+            }
+            FINDING_DUPLICATE_CONDITION_KEY
+            | FINDING_UNKNOWN_CONDITION_OPERATOR
+            | FINDING_INVALID_CONDITION_VALUE
+            | FINDING_INAPPLICABLE_CONDITION_KEY => &[CONDITION_NAME],
+            FINDING_TRUST_PUBLIC_PRINCIPAL
+            | FINDING_TRUST_MISSING_EXTERNAL_ID
+            | FINDING_TRUST_MISSING_SOURCE => {
+                &[PRINCIPAL_VALUE_PRINCIPAL, PRINCIPAL_VALUE_NOT_PRINCIPAL]
+            }
+            _ => &[],
+        };
+        keys.iter()
+            .find_map(|key| self.key(&format!("{}.{}", statement, key)))
+            .or_else(|| self.value(&statement))
+            .unwrap_or_else(|| self.document())
+    }
+
+    fn locate_error(&self, error: &IamFormatError) -> Span {
+        let found = match error {
+            IamFormatError::TypeMismatch { name, .. } => self
+                .entries
+                .iter()
+                .rev()
+                .find(|entry| last_key(&entry.path) == Some(name.as_str()))
+                .and_then(|entry| entry.key),
+            IamFormatError::UnexpectedValue { value, .. }
+            | IamFormatError::UnexpectedTypeValue { value, .. } => self
+                .entries
+                .iter()
+                .find(|entry| entry.string.as_ref() == Some(value))
+                .map(|entry| entry.value)
+                .or_else(|| {
+                    self.entries
+                        .iter()
+                        .find(|entry| last_key(&entry.path) == Some(value.as_str()))
+                        .and_then(|entry| entry.key)
+                }),
+            _ => None,
+        };
+        found.unwrap_or_else(|| self.document())
+    }
+
+    fn entry(&self, path: &str) -> Option<&Entry> {
+        self.entries.iter().rev().find(|entry| entry.path == path)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Diagnostic {
+    /// Create a diagnostic for `finding`, located at `span`.
+    pub fn new(finding: &Finding, span: Span) -> Self {
+        Self {
+            severity: finding.severity,
+            code: finding.code,
+            message: finding.message.clone(),
+            span,
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} [{}] {}",
+            self.span.start, self.severity, self.code, self.message
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<'a> Scanner<'a> {
+    fn new(text: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(
+            text.bytes()
+                .enumerate()
+                .filter(|(_, b)| *b == b'\n')
+                .map(|(i, _)| i + 1),
+        );
+        Self {
+            text,
+            bytes: text.as_bytes(),
+            offset: 0,
+            line_starts,
+            entries: Default::default(),
+            paths: Default::default(),
+        }
+    }
+
+    fn position(&self, offset: usize) -> Position {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        };
+        let start = self.line_starts[line];
+        Position {
+            offset,
+            line: line + 1,
+            column: self.text[start..offset].chars().count() + 1,
+        }
+    }
+
+    fn span(&self, start: usize, end: usize) -> Span {
+        Span {
+            start: self.position(start),
+            end: self.position(end),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.offset < self.bytes.len() && self.bytes[self.offset].is_ascii_whitespace() {
+            self.offset += 1;
+        }
+    }
+
+    // The text has already been parsed by serde_json, so only valid JSON need be handled.
+    fn value(&mut self, path: String, key: Option<Span>) {
+        self.skip_whitespace();
+        let start = self.offset;
+        let index = self.entries.len();
+        let duplicate = !self.paths.insert(path.clone());
+        self.entries.push(Entry {
+            path: path.clone(),
+            key,
+            value: Span::default(),
+            string: None,
+            duplicate,
+        });
+        match self.bytes[self.offset] {
+            b'{' => self.object(&path),
+            b'[' => self.array(&path),
+            b'"' => self.entries[index].string = Some(self.string()),
+            _ => {
+                while self.offset < self.bytes.len()
+                    && !matches!(self.bytes[self.offset], b',' | b'}' | b']')
+                    && !self.bytes[self.offset].is_ascii_whitespace()
+                {
+                    self.offset += 1;
+                }
+            }
+        }
+        self.entries[index].value = self.span(start, self.offset);
+    }
+
+    fn object(&mut self, path: &str) {
+        self.offset += 1;
+        loop {
+            self.skip_whitespace();
+            match self.bytes[self.offset] {
+                b'}' => {
+                    self.offset += 1;
+                    return;
+                }
+                b',' => self.offset += 1,
+                _ => {
+                    let start = self.offset;
+                    let key = self.string();
+                    let key_span = self.span(start, self.offset);
+                    self.skip_whitespace();
+                    self.offset += 1; // the ':'
+                    let path = if path.is_empty() {
+                        key
+                    } else {
+                        format!("{}.{}", path, key)
+                    };
+                    self.value(path, Some(key_span));
+                }
+            }
+        }
+    }
+
+    fn array(&mut self, path: &str) {
+        self.offset += 1;
+        let mut index = 0;
+        loop {
+            self.skip_whitespace();
+            match self.bytes[self.offset] {
+                b']' => {
+                    self.offset += 1;
+                    return;
+                }
+                b',' => self.offset += 1,
+                _ => {
+                    self.value(format!("{}[{}]", path, index), None);
+                    index += 1;
+                }
+            }
+        }
+    }
+
+    fn string(&mut self) -> String {
+        let start = self.offset;
+        self.offset += 1;
+        let mut escaped = false;
+        while self.bytes[self.offset] != b'"' {
+            if self.bytes[self.offset] == b'\\' {
+                escaped = true;
+                self.offset += 1;
+            }
+            self.offset += 1;
+        }
+        self.offset += 1;
+        let raw = &self.text[start..self.offset];
+        if escaped {
+            serde_json::from_str(raw).unwrap_or_default()
+        } else {
+            raw[1..raw.len() - 1].to_string()
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn statement_path(index: usize) -> String {
+    format!("{}[{}]", STATEMENT_NAME, index)
+}
+
+fn last_key(path: &str) -> Option<&str> {
+    if path.ends_with(']') {
+        None
+    } else {
+        Some(path.rsplit('.').next().unwrap_or(path))
+    }
+}
+
+fn position_at(s: &str, line: usize, column: usize) -> Position {
+    let start: usize = s
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    let offset = s[start..]
+        .char_indices()
+        .nth(column.saturating_sub(1))
+        .map(|(i, _)| start + i)
+        .unwrap_or(s.len());
+    Position {
+        offset,
+        line,
+        column,
+    }
+}
@@ -0,0 +1,461 @@
+/*!
+Provides the comparison of versions of a policy, such as those in the history of a policy file
+stored in git, and the three-way merge of two versions that both changed a common ancestor.
+
+Statements are compared, not lines of text. A statement with a `Sid` is identified by it, and so
+is the same statement in each version however its elements change or it is moved. A statement
+without a `Sid` is the same as an identical statement in the other version, if there is one;
+those that are left are then paired, in order, with the statements without a `Sid` that are
+left in the other version, so that changing a statement without a `Sid` is seen as modifying
+it rather than removing it and adding another.
+
+[`diff`](fn.diff.html) lists the statements added, removed, and modified between two versions,
+and the elements of each modified statement that changed.
+
+[`three_way_merge`](fn.three_way_merge.html) combines the changes that two versions, `ours` and
+`theirs`, made to their common ancestor, `base`. Changes to different statements, or to
+different elements of the same statement, are merged automatically; a
+[`MergeConflict`](struct.MergeConflict.html) is reported where both versions changed the same
+element of a statement, such as its actions, differently, where one version modified a
+statement the other removed, or where both added a different statement with the same `Sid`.
+
+# Example
+
+```rust
+use aws_iam::diff::three_way_merge;
+use aws_iam::model::Policy;
+use std::str::FromStr;
+
+let base = Policy::from_str(r#"{"Statement":[
+    {"Sid":"Read","Effect":"Allow","Action":"s3:GetObject","Resource":"arn:aws:s3:::reports"}
+]}"#).unwrap();
+let ours = Policy::from_str(r#"{"Statement":[
+    {"Sid":"Read","Effect":"Allow","Action":["s3:GetObject","s3:ListBucket"],"Resource":"arn:aws:s3:::reports"}
+]}"#).unwrap();
+let theirs = Policy::from_str(r#"{"Statement":[
+    {"Sid":"Read","Effect":"Allow","Action":"s3:GetObject","Resource":"arn:aws:s3:::reports",
+     "Condition":{"Bool":{"aws:SecureTransport":"true"}}}
+]}"#).unwrap();
+
+let merge = three_way_merge(&base, &ours, &theirs);
+assert!(merge.is_clean());
+let statement = &merge.policy.statement[0];
+assert!(statement.action().matches("s3:ListBucket"));
+assert!(statement.condition.is_some());
+```
+*/
+
+use crate::model::{Policy, Sid, Statement};
+use crate::syntax::{
+    ACTION_NAME, CONDITION_NAME, EFFECT_NAME, ID_NAME, PRINCIPAL_NAME, RESOURCE_NAME, VERSION_NAME,
+};
+use std::fmt::{Display, Formatter};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A change to a statement between two versions of a policy, see [`diff`](fn.diff.html).
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatementChange {
+    /// The statement is only in the later version.
+    Added(Statement),
+    /// The statement is only in the earlier version.
+    Removed(Statement),
+    /// The statement is in both versions but has changed.
+    Modified {
+        /// The statement in the earlier version.
+        before: Box<Statement>,
+        /// The statement in the later version.
+        after: Box<Statement>,
+        /// The names of the elements that changed, such as `Action`.
+        elements: Vec<&'static str>,
+    },
+}
+
+///
+/// The kind of a [`MergeConflict`](struct.MergeConflict.html).
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// Both versions changed the element differently.
+    BothModified,
+    /// One version modified the statement, and the other removed it.
+    ModifiedAndRemoved,
+    /// Both versions added a statement with the same `Sid`, but with different content.
+    BothAdded,
+}
+
+///
+/// A change made by both versions in a three-way merge that could not be resolved, see
+/// [`three_way_merge`](fn.three_way_merge.html).
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// The `Sid` of the statement in conflict, `None` if the conflict is in an element of the
+    /// policy itself, or in a statement without a `Sid`.
+    pub sid: Option<Sid>,
+    /// The name of the element in conflict, such as `Action`; `None` if the conflict is over the
+    /// whole statement.
+    pub element: Option<&'static str>,
+    /// The kind of conflict.
+    pub kind: ConflictKind,
+}
+
+///
+/// The result of a three-way merge, see [`three_way_merge`](fn.three_way_merge.html).
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Merge {
+    /// The merged policy; any element in conflict has its value in `ours`, and a statement
+    /// modified in one version and removed in the other is kept as modified.
+    pub policy: Policy,
+    /// The conflicts, those in the elements of the policy itself followed by those in its
+    /// statements, in the order of the merged policy.
+    pub conflicts: Vec<MergeConflict>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns the changes to the statements of `before` that produce those of `after`; removed and
+/// modified statements in the order of `before`, followed by added statements in the order of
+/// `after`.
+///
+pub fn diff(before: &Policy, after: &Policy) -> Vec<StatementChange> {
+    let ancestors = ancestors(before, after);
+    let mut changes = Vec::new();
+    for (index, statement) in before.statement.iter().enumerate() {
+        match version_of(after, &ancestors, index) {
+            None => changes.push(StatementChange::Removed(statement.clone())),
+            Some(other) if other != statement => changes.push(StatementChange::Modified {
+                before: Box::new(statement.clone()),
+                after: Box::new(other.clone()),
+                elements: changed_elements(statement, other),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (statement, ancestor) in after.statement.iter().zip(&ancestors) {
+        if ancestor.is_none() {
+            changes.push(StatementChange::Added(statement.clone()));
+        }
+    }
+    changes
+}
+
+///
+/// Merge the changes that `ours` and `theirs` each made to `base`, their common ancestor. The
+/// merged policy has the statements of `ours`, with the changes of `theirs` applied, followed by
+/// the statements added by `theirs`. Where both versions changed the same element the conflict
+/// is reported and the element of `ours` is used.
+///
+pub fn three_way_merge(base: &Policy, ours: &Policy, theirs: &Policy) -> Merge {
+    let mut conflicts = Vec::new();
+    let version = merge_element(
+        None,
+        VERSION_NAME,
+        &base.version,
+        &ours.version,
+        &theirs.version,
+        &mut conflicts,
+    );
+    let id = merge_element(
+        None,
+        ID_NAME,
+        &base.id,
+        &ours.id,
+        &theirs.id,
+        &mut conflicts,
+    );
+
+    let our_ancestors = ancestors(base, ours);
+    let their_ancestors = ancestors(base, theirs);
+    let mut merged = vec![false; theirs.statement.len()];
+    let mut statements = Vec::new();
+    for (statement, ancestor) in ours.statement.iter().zip(&our_ancestors) {
+        match ancestor {
+            Some(index) => {
+                let original = &base.statement[*index];
+                match their_ancestors.iter().position(|other| other == ancestor) {
+                    Some(other) => {
+                        merged[other] = true;
+                        statements.push(merge_statement(
+                            original,
+                            statement,
+                            &theirs.statement[other],
+                            &mut conflicts,
+                        ));
+                    }
+                    None => {
+                        if original != statement {
+                            conflicts.push(MergeConflict::statement(
+                                statement,
+                                ConflictKind::ModifiedAndRemoved,
+                            ));
+                            statements.push(statement.clone());
+                        }
+                    }
+                }
+            }
+            None => {
+                let added = theirs
+                    .statement
+                    .iter()
+                    .enumerate()
+                    .position(|(index, other)| {
+                        !merged[index]
+                            && their_ancestors[index].is_none()
+                            && match &statement.sid {
+                                Some(sid) => other.sid.as_ref() == Some(sid),
+                                None => other == statement,
+                            }
+                    });
+                if let Some(other) = added {
+                    merged[other] = true;
+                    if theirs.statement[other] != *statement {
+                        conflicts
+                            .push(MergeConflict::statement(statement, ConflictKind::BothAdded));
+                    }
+                }
+                statements.push(statement.clone());
+            }
+        }
+    }
+    for (index, statement) in theirs.statement.iter().enumerate() {
+        if !merged[index] {
+            match their_ancestors[index] {
+                Some(original) => {
+                    if base.statement[original] != *statement {
+                        conflicts.push(MergeConflict::statement(
+                            statement,
+                            ConflictKind::ModifiedAndRemoved,
+                        ));
+                        statements.push(statement.clone());
+                    }
+                }
+                None => statements.push(statement.clone()),
+            }
+        }
+    }
+
+    Merge {
+        policy: Policy {
+            version,
+            id,
+            statement: statements,
+        },
+        conflicts,
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for ConflictKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::BothModified => "modified differently in both versions",
+                Self::ModifiedAndRemoved => "modified in one version and removed in the other",
+                Self::BothAdded => "added differently in both versions",
+            }
+        )
+    }
+}
+
+impl Display for MergeConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (&self.sid, self.element) {
+            (Some(sid), Some(element)) => write!(f, "statement {}, {}", sid, element)?,
+            (Some(sid), None) => write!(f, "statement {}", sid)?,
+            (None, Some(element)) => write!(f, "{}", element)?,
+            (None, None) => write!(f, "statement")?,
+        }
+        write!(f, " {}", self.kind)
+    }
+}
+
+impl MergeConflict {
+    fn statement(statement: &Statement, kind: ConflictKind) -> Self {
+        Self {
+            sid: statement.sid.clone(),
+            element: None,
+            kind,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Merge {
+    /// Returns `true` if the merge has no conflicts.
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns, for each statement of `other`, the index of the statement of `base` that it is a
+/// version of, if any. A statement with a `Sid` is a version of the statement with the same
+/// `Sid`, and one without is a version of an identical statement; the statements without a
+/// `Sid` that are left in each policy are then paired in order.
+///
+fn ancestors(base: &Policy, other: &Policy) -> Vec<Option<usize>> {
+    let mut matched = vec![false; base.statement.len()];
+    let mut ancestors = vec![None; other.statement.len()];
+    for (index, statement) in other.statement.iter().enumerate() {
+        ancestors[index] = match &statement.sid {
+            Some(sid) => match_first(base, &mut matched, |base| base.sid.as_ref() == Some(sid)),
+            None => match_first(base, &mut matched, |base| base == statement),
+        };
+    }
+    for (index, statement) in other.statement.iter().enumerate() {
+        if statement.sid.is_none() && ancestors[index].is_none() {
+            ancestors[index] = match_first(base, &mut matched, |base| base.sid.is_none());
+        }
+    }
+    ancestors
+}
+
+///
+/// Returns the index of the first statement of `base` that is not yet `matched` and satisfies
+/// `predicate`, marking it as matched.
+///
+fn match_first<F>(base: &Policy, matched: &mut [bool], predicate: F) -> Option<usize>
+where
+    F: Fn(&Statement) -> bool,
+{
+    let found = base
+        .statement
+        .iter()
+        .enumerate()
+        .position(|(index, statement)| !matched[index] && predicate(statement));
+    if let Some(index) = found {
+        matched[index] = true;
+    }
+    found
+}
+
+///
+/// Returns the statement of `other` that is a version of the statement of its base at `index`,
+/// if any; `ancestors` is the result of `ancestors` for `other`.
+///
+fn version_of<'a>(
+    other: &'a Policy,
+    ancestors: &[Option<usize>],
+    index: usize,
+) -> Option<&'a Statement> {
+    ancestors
+        .iter()
+        .position(|ancestor| *ancestor == Some(index))
+        .map(|found| &other.statement[found])
+}
+
+fn changed_elements(before: &Statement, after: &Statement) -> Vec<&'static str> {
+    let mut elements = Vec::new();
+    if before.principal != after.principal {
+        elements.push(PRINCIPAL_NAME);
+    }
+    if before.effect != after.effect {
+        elements.push(EFFECT_NAME);
+    }
+    if before.action != after.action {
+        elements.push(ACTION_NAME);
+    }
+    if before.resource != after.resource {
+        elements.push(RESOURCE_NAME);
+    }
+    if before.condition != after.condition {
+        elements.push(CONDITION_NAME);
+    }
+    elements
+}
+
+fn merge_statement(
+    base: &Statement,
+    ours: &Statement,
+    theirs: &Statement,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Statement {
+    let sid = ours.sid.as_ref();
+    Statement {
+        sid: ours.sid.clone(),
+        principal: merge_element(
+            sid,
+            PRINCIPAL_NAME,
+            &base.principal,
+            &ours.principal,
+            &theirs.principal,
+            conflicts,
+        ),
+        effect: merge_element(
+            sid,
+            EFFECT_NAME,
+            &base.effect,
+            &ours.effect,
+            &theirs.effect,
+            conflicts,
+        ),
+        action: merge_element(
+            sid,
+            ACTION_NAME,
+            &base.action,
+            &ours.action,
+            &theirs.action,
+            conflicts,
+        ),
+        resource: merge_element(
+            sid,
+            RESOURCE_NAME,
+            &base.resource,
+            &ours.resource,
+            &theirs.resource,
+            conflicts,
+        ),
+        condition: merge_element(
+            sid,
+            CONDITION_NAME,
+            &base.condition,
+            &ours.condition,
+            &theirs.condition,
+            conflicts,
+        ),
+    }
+}
+
+fn merge_element<T>(
+    sid: Option<&Sid>,
+    element: &'static str,
+    base: &T,
+    ours: &T,
+    theirs: &T,
+    conflicts: &mut Vec<MergeConflict>,
+) -> T
+where
+    T: Clone + PartialEq,
+{
+    if ours == theirs || base == theirs {
+        ours.clone()
+    } else if base == ours {
+        theirs.clone()
+    } else {
+        conflicts.push(MergeConflict {
+            sid: sid.cloned(),
+            element: Some(element),
+            kind: ConflictKind::BothModified,
+        });
+        ours.clone()
+    }
+}
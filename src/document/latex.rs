@@ -1,5 +1,7 @@
+use crate::document::principal_types;
 use crate::document::visitor::*;
 use crate::model::*;
+use std::fmt::Display;
 use std::io::{stdout, Write};
 
 // ------------------------------------------------------------------------------------------------
@@ -102,6 +104,11 @@ impl PolicyVisitor for LatexGenerator {
         .expect(IO_ERROR_MSG);
     }
 
+    fn comment(&mut self, c: &str) {
+        self.newln();
+        writeln!(self.writer.as_mut(), "\\emph{{{}}}", text_value(c)).expect(IO_ERROR_MSG);
+    }
+
     fn statement_visitor(&mut self) -> Option<Box<&mut dyn StatementVisitor>> {
         Some(Box::new(self))
     }
@@ -175,6 +182,10 @@ impl StatementVisitor for LatexGenerator {
         .expect(IO_ERROR_MSG);
     }
 
+    fn comment(&mut self, c: &str) {
+        write!(self.writer.as_mut(), "\\emph{{{}}} ", text_value(c)).expect(IO_ERROR_MSG);
+    }
+
     fn effect(&mut self, e: &Effect) {
         writeln!(
             self.writer.as_mut(),
@@ -194,29 +205,30 @@ impl StatementVisitor for LatexGenerator {
             Principal::Principal(v) => (false, v),
             Principal::NotPrincipal(v) => (true, v),
         };
+        let principals = match values {
+            OrAny::Any => {
+                writeln!(
+                    self.writer.as_mut(),
+                    "    \\item The request's \\textit{{principal}} {}.",
+                    any(negated)
+                )
+                .expect(IO_ERROR_MSG);
+                return;
+            }
+            OrAny::Some(principals) => principals,
+        };
         writeln!(
             self.writer.as_mut(),
             "    \\item The request \\textit{{principal}} matches any of: "
         )
         .expect(IO_ERROR_MSG);
         writeln!(self.writer.as_mut(), "    \\begin{{itemize}}").expect(IO_ERROR_MSG);
-        for (kind, value) in values {
+        for (kind, ids) in principal_types(principals) {
             writeln!(
                 self.writer.as_mut(),
-                "        \\item \\textit{{type}} $=$ {:?} $\\wedge$ \\textit{{id}} {}.",
+                "        \\item \\textit{{type}} $=$ {} $\\wedge$ \\textit{{id}} {}.",
                 kind,
-                match value {
-                    OneOrAny::Any => any(negated),
-                    OneOrAny::One(v) => string_or_any(v, negated),
-                    OneOrAny::AnyOf(vs) => format!(
-                        "{} \\{{{}\\}}",
-                        if negated { "$\\notin$" } else { "$\\in$" },
-                        vs.iter()
-                            .map(|s| string_value(s))
-                            .collect::<Vec<String>>()
-                            .join(", ")
-                    ),
-                }
+                list(&ids, negated)
             )
             .expect(IO_ERROR_MSG);
         }
@@ -231,18 +243,7 @@ impl StatementVisitor for LatexGenerator {
         writeln!(
             self.writer.as_mut(),
             "    \\item The request's \\textit{{action}} {}.",
-            match value {
-                OneOrAny::Any => any(negated),
-                OneOrAny::One(v) => string_or_any(&v.to_string(), negated),
-                OneOrAny::AnyOf(vs) => format!(
-                    "{} \\{{{}\\}}",
-                    if negated { "$\\notin$" } else { "$\\in$" },
-                    vs.iter()
-                        .map(|s| string_value(&s.to_string()))
-                        .collect::<Vec<String>>()
-                        .join(", ")
-                ),
-            }
+            values(value, negated)
         )
         .expect(IO_ERROR_MSG);
     }
@@ -255,18 +256,7 @@ impl StatementVisitor for LatexGenerator {
         writeln!(
             self.writer.as_mut(),
             "    \\item The request's \\textit{{resource}} {}.",
-            match value {
-                OneOrAny::Any => any(negated),
-                OneOrAny::One(v) => string_or_any(v, negated),
-                OneOrAny::AnyOf(vs) => format!(
-                    "{} \\{{{}\\}}",
-                    if negated { "$\\notin$" } else { "$\\in$" },
-                    vs.iter()
-                        .map(|s| string_value(s))
-                        .collect::<Vec<String>>()
-                        .join(", ")
-                ),
-            }
+            values(value, negated)
         )
         .expect(IO_ERROR_MSG);
     }
@@ -292,7 +282,7 @@ impl StatementVisitor for LatexGenerator {
 }
 
 impl ConditionVisitor for LatexGenerator {
-    fn left(&mut self, f: &QString, op: &ConditionOperator) {
+    fn left(&mut self, f: &QualifiedName, op: &Operator) {
         write!(
             self.writer.as_mut(),
             "        \\item {}{}{}",
@@ -306,27 +296,25 @@ impl ConditionVisitor for LatexGenerator {
             },
             match op.quantifier {
                 None => "",
-                Some(ConditionOperatorQuantifier::ForAllValues) => "$\\forall(v)$",
-                Some(ConditionOperatorQuantifier::ForAnyValue) => "$\\exists(v)$",
+                Some(Quantifier::ForAllValues) => "$\\forall(v)$",
+                Some(Quantifier::ForAnyValue) => "$\\exists(v)$",
             },
             format!("\\textit{{{}}}", f)
         )
         .expect(IO_ERROR_MSG);
     }
 
-    fn operator(&mut self, op: &ConditionOperator) {
+    fn operator(&mut self, op: &Operator) {
         write!(self.writer.as_mut(), " {} ", operator_string(op),).expect(IO_ERROR_MSG);
     }
 
-    fn right(&mut self, v: &OneOrAll<ConditionValue>, _op: &ConditionOperator) {
+    fn right(&mut self, v: &[ConditionValue], _op: &Operator) {
         writeln!(
             self.writer.as_mut(),
             "{}",
             match v {
-                OneOrAll::One(v) => {
-                    condition_value(v)
-                }
-                OneOrAll::All(vs) => format!(
+                [v] => condition_value(v),
+                vs => format!(
                     "\\{{{}\\}}",
                     vs.iter()
                         .map(condition_value)
@@ -344,18 +332,47 @@ impl ConditionVisitor for LatexGenerator {
 // ------------------------------------------------------------------------------------------------
 
 fn string_value(v: &str) -> String {
-    format!("``{}''", v)
-        .replace('$', r"\$")
+    text_value(&format!("``{}''", v))
+}
+
+fn text_value(v: &str) -> String {
+    v.replace('$', r"\$")
         .replace('{', r"\{")
         .replace('}', r"\}")
 }
 
 fn condition_value(v: &ConditionValue) -> String {
-    match v {
-        ConditionValue::String(v) => string_value(v),
-        ConditionValue::Integer(v) => v.to_string(),
-        ConditionValue::Float(v) => v.to_string(),
-        ConditionValue::Bool(v) => v.to_string(),
+    if v.is_string() {
+        string_value(v)
+    } else {
+        v.to_string()
+    }
+}
+
+fn values<T>(value: &OrAny<Vec<T>>, negated: bool) -> String
+where
+    T: Display,
+{
+    match value {
+        OrAny::Any => any(negated),
+        OrAny::Some(vs) => list(
+            &vs.iter().map(ToString::to_string).collect::<Vec<String>>(),
+            negated,
+        ),
+    }
+}
+
+fn list(vs: &[String], negated: bool) -> String {
+    match vs {
+        [v] => string_or_any(v, negated),
+        vs => format!(
+            "{} \\{{{}\\}}",
+            if negated { "$\\notin$" } else { "$\\in$" },
+            vs.iter()
+                .map(|s| string_value(s))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
     }
 }
 
@@ -393,43 +410,43 @@ fn op_str(op: &str) -> String {
     format!("${}$", op)
 }
 
-fn operator_string(op: &ConditionOperator) -> String {
+fn operator_string(op: &Operator) -> String {
     match &op.operator {
-        GlobalConditionOperator::StringEquals => op_str("="),
-        GlobalConditionOperator::StringNotEquals => op_str("\\neq"),
-        GlobalConditionOperator::StringEqualsIgnoreCase => op_str("\\equiv"),
-        GlobalConditionOperator::StringNotEqualsIgnoreCase => op_str("\\not\\equiv"),
-        GlobalConditionOperator::StringLike => op_str("\\approx"),
-        GlobalConditionOperator::StringNotLike => op_str("\\not\\approx"),
-
-        GlobalConditionOperator::NumericEquals => op_str("="),
-        GlobalConditionOperator::NumericNotEquals => op_str("\\neq"),
-        GlobalConditionOperator::NumericLessThan => op_str("<"),
-        GlobalConditionOperator::NumericLessThanEquals => op_str("\\leq"),
-        GlobalConditionOperator::NumericGreaterThan => op_str(">"),
-        GlobalConditionOperator::NumericGreaterThanEquals => op_str("\\geq"),
-
-        GlobalConditionOperator::DateEquals => op_str("="),
-        GlobalConditionOperator::DateNotEquals => op_str("\\neq"),
-        GlobalConditionOperator::DateLessThan => op_str("<"),
-        GlobalConditionOperator::DateLessThanEquals => op_str("\\leq"),
-        GlobalConditionOperator::DateGreaterThan => op_str(">"),
-        GlobalConditionOperator::DateGreaterThanEquals => op_str("\\geq"),
-
-        GlobalConditionOperator::Bool => op_str("="),
-
-        GlobalConditionOperator::BinaryEquals => op_str("="),
-
-        GlobalConditionOperator::IpAddress => op_str("="),
-        GlobalConditionOperator::NotIpAddress => op_str("\\neq"),
-
-        GlobalConditionOperator::ArnEquals => op_str("="),
-        GlobalConditionOperator::ArnLike => op_str("\\approx"),
-        GlobalConditionOperator::ArnNotEquals => op_str("\\neq"),
-        GlobalConditionOperator::ArnNotLike => op_str("\\not\\approx"),
-
-        GlobalConditionOperator::Null => op_str("?"),
-
-        GlobalConditionOperator::Other(id) => op_str(&id.to_string()),
+        GlobalOperator::StringEquals => op_str("="),
+        GlobalOperator::StringNotEquals => op_str("\\neq"),
+        GlobalOperator::StringEqualsIgnoreCase => op_str("\\equiv"),
+        GlobalOperator::StringNotEqualsIgnoreCase => op_str("\\not\\equiv"),
+        GlobalOperator::StringLike => op_str("\\approx"),
+        GlobalOperator::StringNotLike => op_str("\\not\\approx"),
+
+        GlobalOperator::NumericEquals => op_str("="),
+        GlobalOperator::NumericNotEquals => op_str("\\neq"),
+        GlobalOperator::NumericLessThan => op_str("<"),
+        GlobalOperator::NumericLessThanEquals => op_str("\\leq"),
+        GlobalOperator::NumericGreaterThan => op_str(">"),
+        GlobalOperator::NumericGreaterThanEquals => op_str("\\geq"),
+
+        GlobalOperator::DateEquals => op_str("="),
+        GlobalOperator::DateNotEquals => op_str("\\neq"),
+        GlobalOperator::DateLessThan => op_str("<"),
+        GlobalOperator::DateLessThanEquals => op_str("\\leq"),
+        GlobalOperator::DateGreaterThan => op_str(">"),
+        GlobalOperator::DateGreaterThanEquals => op_str("\\geq"),
+
+        GlobalOperator::Bool => op_str("="),
+
+        GlobalOperator::BinaryEquals => op_str("="),
+
+        GlobalOperator::IpAddress => op_str("="),
+        GlobalOperator::NotIpAddress => op_str("\\neq"),
+
+        GlobalOperator::ArnEquals => op_str("="),
+        GlobalOperator::ArnLike => op_str("\\approx"),
+        GlobalOperator::ArnNotEquals => op_str("\\neq"),
+        GlobalOperator::ArnNotLike => op_str("\\not\\approx"),
+
+        GlobalOperator::Null => op_str("?"),
+
+        GlobalOperator::Other(id) => op_str(&id.to_string()),
     }
 }
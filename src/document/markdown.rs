@@ -1,6 +1,9 @@
+use crate::document::principal_types;
 use crate::document::visitor::*;
 use crate::model::*;
+use std::fmt::Display;
 use std::io::{stdout, Write};
+use std::ops::Deref;
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
@@ -71,6 +74,11 @@ impl PolicyVisitor for MarkdownGenerator {
         .expect(IO_ERROR_MSG);
     }
 
+    fn comment(&mut self, c: &str) {
+        self.newln();
+        writeln!(self.writer.as_mut(), "{}", c).expect(IO_ERROR_MSG);
+    }
+
     fn statement_visitor(&mut self) -> Option<Box<&mut dyn StatementVisitor>> {
         Some(Box::new(self))
     }
@@ -87,6 +95,11 @@ impl StatementVisitor for MarkdownGenerator {
         writeln!(self.writer.as_mut(), "> Statement ID: {}", s).expect(IO_ERROR_MSG);
     }
 
+    fn comment(&mut self, c: &str) {
+        self.newln();
+        writeln!(self.writer.as_mut(), "{}", c).expect(IO_ERROR_MSG);
+    }
+
     fn effect(&mut self, e: &Effect) {
         self.newln();
         writeln!(
@@ -106,29 +119,31 @@ impl StatementVisitor for MarkdownGenerator {
             Principal::Principal(v) => (false, v),
             Principal::NotPrincipal(v) => (true, v),
         };
-        writeln!(
-            self.writer.as_mut(),
-            "* `Principal {}`**`IN`**",
-            if negated { "`**`NOT`**` " } else { "" }
-        )
-        .expect(IO_ERROR_MSG);
-        for (kind, value) in values {
-            writeln!(
+        match values {
+            OrAny::Any => writeln!(
                 self.writer.as_mut(),
-                "   * *`type`*` = {:?} `**`AND`**` `*`id`*` {}`",
-                kind,
-                match value {
-                    OneOrAny::Any => {
-                        format!("{}`**`ANY`**`", if negated { "" } else { "`**`IS`**` " })
-                    }
-                    OneOrAny::One(v) => format!("= \"{}\"", v),
-                    OneOrAny::AnyOf(vs) => format!(
-                        "`**`IN`**` {:?}",
-                        vs.iter().map(|s| s.to_string()).collect::<Vec<String>>()
-                    ),
-                }
+                "* `Principal {}{}`",
+                if negated { "`**`NOT`**` " } else { "" },
+                any(negated)
             )
-            .expect(IO_ERROR_MSG);
+            .expect(IO_ERROR_MSG),
+            OrAny::Some(principals) => {
+                writeln!(
+                    self.writer.as_mut(),
+                    "* `Principal {}`**`IN`**",
+                    if negated { "`**`NOT`**` " } else { "" }
+                )
+                .expect(IO_ERROR_MSG);
+                for (kind, ids) in principal_types(principals) {
+                    writeln!(
+                        self.writer.as_mut(),
+                        "   * *`type`*` = {} `**`AND`**` `*`id`*` {}`",
+                        kind,
+                        list(&ids)
+                    )
+                    .expect(IO_ERROR_MSG);
+                }
+            }
         }
     }
 
@@ -141,14 +156,7 @@ impl StatementVisitor for MarkdownGenerator {
             self.writer.as_mut(),
             "* `Action {}{}`",
             if negated { "`**`NOT`**` " } else { "" },
-            match value {
-                OneOrAny::Any => format!("{}`**`ANY`**`", if negated { "" } else { "`**`IS`**` " }),
-                OneOrAny::One(v) => format!("= \"{}\"", v),
-                OneOrAny::AnyOf(vs) => format!(
-                    "`**`IN`**` {:?}",
-                    vs.iter().map(|s| s.to_string()).collect::<Vec<String>>()
-                ),
-            }
+            values(value, negated)
         )
         .expect(IO_ERROR_MSG);
     }
@@ -162,14 +170,7 @@ impl StatementVisitor for MarkdownGenerator {
             self.writer.as_mut(),
             "* `Resource {} {}`",
             if negated { "`**`NOT`**`" } else { "" },
-            match value {
-                OneOrAny::Any => format!("{}`**`ANY`**`", if negated { "" } else { "`**`IS`**` " }),
-                OneOrAny::One(v) => format!("= \"{}\"", v),
-                OneOrAny::AnyOf(vs) => format!(
-                    "`**`IN`**` {:?}",
-                    vs.iter().map(|s| s.to_string()).collect::<Vec<String>>()
-                ),
-            }
+            values(value, negated)
         )
         .expect(IO_ERROR_MSG);
     }
@@ -184,7 +185,7 @@ impl ConditionVisitor for MarkdownGenerator {
         write!(self.writer.as_mut(), "* `Condition ").expect(IO_ERROR_MSG);
     }
 
-    fn left(&mut self, f: &QString, op: &ConditionOperator) {
+    fn left(&mut self, f: &QualifiedName, op: &Operator) {
         write!(
             self.writer.as_mut(),
             "{}`*`{}`*`{}",
@@ -203,35 +204,30 @@ impl ConditionVisitor for MarkdownGenerator {
         .expect(IO_ERROR_MSG);
     }
 
-    fn operator(&mut self, op: &ConditionOperator) {
+    fn operator(&mut self, op: &Operator) {
         write!(
             self.writer.as_mut(),
-            " `**`{:?}`**`{} ",
+            " `**`{}`**`{} ",
             op.operator,
             match op.quantifier {
                 None => "",
-                Some(ConditionOperatorQuantifier::ForAllValues) => " `**`∀`**`",
-                Some(ConditionOperatorQuantifier::ForAnyValue) => " `**`∃`**`",
+                Some(Quantifier::ForAllValues) => " `**`∀`**`",
+                Some(Quantifier::ForAnyValue) => " `**`∃`**`",
             }
         )
         .expect(IO_ERROR_MSG);
     }
 
-    fn right(&mut self, v: &OneOrAll<ConditionValue>, _op: &ConditionOperator) {
+    fn right(&mut self, v: &[ConditionValue], _op: &Operator) {
         write!(
             self.writer.as_mut(),
             "{}",
             match v {
-                OneOrAll::One(v) => {
-                    if let ConditionValue::String(s) = v {
-                        format!("{:?}", s)
-                    } else {
-                        condition_value(v)
-                    }
-                }
-                OneOrAll::All(vs) => format!(
+                [v] if v.is_string() => format!("{:?}", v.deref()),
+                [v] => v.to_string(),
+                vs => format!(
                     "{:?}",
-                    vs.iter().map(condition_value).collect::<Vec<String>>()
+                    vs.iter().map(ToString::to_string).collect::<Vec<String>>()
                 ),
             }
         )
@@ -247,11 +243,23 @@ impl ConditionVisitor for MarkdownGenerator {
 // Private Functions
 // ------------------------------------------------------------------------------------------------
 
-fn condition_value(v: &ConditionValue) -> String {
-    match v {
-        ConditionValue::String(v) => v.to_string(),
-        ConditionValue::Integer(v) => v.to_string(),
-        ConditionValue::Float(v) => v.to_string(),
-        ConditionValue::Bool(v) => v.to_string(),
+fn any(negated: bool) -> String {
+    format!("{}`**`ANY`**`", if negated { "" } else { "`**`IS`**` " })
+}
+
+fn values<T>(value: &OrAny<Vec<T>>, negated: bool) -> String
+where
+    T: Display,
+{
+    match value {
+        OrAny::Any => any(negated),
+        OrAny::Some(vs) => list(&vs.iter().map(ToString::to_string).collect::<Vec<String>>()),
+    }
+}
+
+fn list(vs: &[String]) -> String {
+    match vs {
+        [v] => format!("= \"{}\"", v),
+        vs => format!("`**`IN`**` {:?}", vs),
     }
 }
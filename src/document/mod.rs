@@ -14,7 +14,7 @@ use aws_iam::{io, model::*, document};
 use std::path::PathBuf;
 
 let policy = io::read_from_file(
-        &PathBuf::from("tests/data/good/example-021.json")
+        &PathBuf::from("tests/data/good/quantified-001.json")
     ).expect("Error reading file");
 
 let mut generator = document::MarkdownGenerator::default();
@@ -22,6 +22,10 @@ let mut generator = document::MarkdownGenerator::default();
 document::visitor::walk_policy(&policy, &mut generator);
 ```
 
+The comments of a policy, its `x-comment` elements, are used as descriptions of the policy and
+its statements when the policy is read with `io::read_from_file_with_comments` and walked with
+[`walk_policy_with_comments`](visitor/fn.walk_policy_with_comments.html).
+
 # Building a new Visitor
 
 To build a new documentation tool, ot any tool that wishes to inspect the structure of a policy,
@@ -32,6 +36,12 @@ to handle need be implemented.
 
 */
 
+use crate::model::{HostName, PrincipalMap};
+use crate::syntax::{
+    PRINCIPAL_TYPE_AWS, PRINCIPAL_TYPE_CANONICAL_USER, PRINCIPAL_TYPE_FEDERATED,
+    PRINCIPAL_TYPE_SERVICE,
+};
+
 // ------------------------------------------------------------------------------------------------
 // Modules
 // ------------------------------------------------------------------------------------------------
@@ -42,4 +52,49 @@ pub use markdown::MarkdownGenerator;
 mod latex;
 pub use latex::LatexGenerator;
 
+mod terminal;
+pub use terminal::TerminalGenerator;
+
 pub mod visitor;
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The principals of each type listed in `principals`, named and written as in the policy
+/// document; types with no principals are omitted.
+///
+pub(crate) fn principal_types(principals: &PrincipalMap) -> Vec<(&'static str, Vec<String>)> {
+    let types: Vec<(&'static str, Vec<String>)> = vec![
+        (
+            PRINCIPAL_TYPE_AWS,
+            principals.aws_iter().map(ToString::to_string).collect(),
+        ),
+        (
+            PRINCIPAL_TYPE_FEDERATED,
+            principals
+                .federated_iter()
+                .map(ToString::to_string)
+                .collect(),
+        ),
+        (
+            PRINCIPAL_TYPE_SERVICE,
+            principals
+                .service_iter()
+                .map(|service| HostName::from(service.clone()).to_string())
+                .collect(),
+        ),
+        (
+            PRINCIPAL_TYPE_CANONICAL_USER,
+            principals
+                .canonical_user_iter()
+                .map(ToString::to_string)
+                .collect(),
+        ),
+    ];
+    types
+        .into_iter()
+        .filter(|(_, values)| !values.is_empty())
+        .collect()
+}
@@ -0,0 +1,273 @@
+use crate::document::principal_types;
+use crate::document::visitor::*;
+use crate::model::*;
+use std::io::{stdout, Write};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// This types implements `PolicyVisitor`, `StatementVisitor`, and `ConditionVisitor` to
+/// produce an aligned, and optionally colorized, rendering of a Policy for ANSI terminals.
+/// Effects are shown in green (allow) or red (deny), and wildcards in any value are
+/// highlighted.
+///
+#[allow(missing_debug_implementations)]
+pub struct TerminalGenerator {
+    writer: Box<dyn Write>,
+    color: bool,
+    has_conditions: bool,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+const IO_ERROR_MSG: &str = "Unexpected write error";
+
+const LABEL_WIDTH: usize = 12;
+
+const ANSI_RESET: &str = "\x1b[0m";
+const ANSI_BOLD: &str = "\x1b[1m";
+const ANSI_DIM: &str = "\x1b[2m";
+const ANSI_RED: &str = "\x1b[1;31m";
+const ANSI_GREEN: &str = "\x1b[1;32m";
+const ANSI_YELLOW: &str = "\x1b[1;33m";
+const ANSI_CYAN: &str = "\x1b[36m";
+
+impl TerminalGenerator {
+    ///
+    /// Create a new generator that will write colorized content to `writer`. If you wish
+    /// to write to `stdout` use `Default::default()`, which does not use color if the
+    /// `NO_COLOR` environment variable is set.
+    ///
+    pub fn new<T>(writer: T) -> Self
+    where
+        T: Write + Sized + 'static,
+    {
+        TerminalGenerator {
+            writer: Box::new(writer),
+            color: true,
+            has_conditions: false,
+        }
+    }
+
+    /// Set whether ANSI color and style codes are written.
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    fn style(&self, style: &str, s: &str) -> String {
+        if self.color {
+            format!("{}{}{}", style, s, ANSI_RESET)
+        } else {
+            s.to_string()
+        }
+    }
+
+    fn value(&self, s: &str) -> String {
+        if s.contains(['*', '?']) {
+            self.style(ANSI_YELLOW, s)
+        } else {
+            s.to_string()
+        }
+    }
+
+    fn values<T>(&self, value: &OrAny<Vec<T>>, negated: bool) -> String
+    where
+        T: ToString,
+    {
+        match value {
+            OrAny::Any => format!("{}{}", self.not(negated), self.style(ANSI_YELLOW, "*")),
+            OrAny::Some(vs) => self.list(
+                &vs.iter().map(ToString::to_string).collect::<Vec<String>>(),
+                negated,
+            ),
+        }
+    }
+
+    fn list(&self, vs: &[String], negated: bool) -> String {
+        format!(
+            "{}{}",
+            self.not(negated),
+            vs.iter()
+                .map(|v| self.value(v))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+
+    fn not(&self, negated: bool) -> String {
+        if negated {
+            format!("{} ", self.style(ANSI_BOLD, "NOT"))
+        } else {
+            "".to_string()
+        }
+    }
+
+    fn label(&mut self, label: &str) {
+        let label = format!("  {:<width$}", label, width = LABEL_WIDTH);
+        let label = self.style(ANSI_CYAN, &label);
+        write!(self.writer.as_mut(), "{}", label).expect(IO_ERROR_MSG);
+    }
+}
+
+impl Default for TerminalGenerator {
+    fn default() -> Self {
+        TerminalGenerator {
+            writer: Box::new(stdout()),
+            color: std::env::var_os("NO_COLOR").is_none(),
+            has_conditions: false,
+        }
+    }
+}
+
+impl PolicyVisitor for TerminalGenerator {
+    fn start(&mut self) {
+        let title = self.style(ANSI_BOLD, "Policy");
+        writeln!(self.writer.as_mut(), "{}", title).expect(IO_ERROR_MSG);
+    }
+
+    fn id(&mut self, i: &str) {
+        self.label("Id");
+        writeln!(self.writer.as_mut(), "{}", i).expect(IO_ERROR_MSG);
+    }
+
+    fn version(&mut self, v: &Version) {
+        self.label("Version");
+        writeln!(
+            self.writer.as_mut(),
+            "{}",
+            match v {
+                Version::V2008 => "2008-10-17",
+                Version::V2012 => "2012-10-17",
+            }
+        )
+        .expect(IO_ERROR_MSG);
+    }
+
+    fn comment(&mut self, c: &str) {
+        self.label("Comment");
+        writeln!(self.writer.as_mut(), "{}", c).expect(IO_ERROR_MSG);
+    }
+
+    fn statement_visitor(&mut self) -> Option<Box<&mut dyn StatementVisitor>> {
+        Some(Box::new(self))
+    }
+}
+
+impl StatementVisitor for TerminalGenerator {
+    fn start(&mut self) {
+        let title = self.style(ANSI_BOLD, "Statement");
+        writeln!(self.writer.as_mut(), "\n{}", title).expect(IO_ERROR_MSG);
+        self.has_conditions = false;
+    }
+
+    fn sid(&mut self, s: &str) {
+        self.label("Sid");
+        writeln!(self.writer.as_mut(), "{}", s).expect(IO_ERROR_MSG);
+    }
+
+    fn comment(&mut self, c: &str) {
+        self.label("Comment");
+        writeln!(self.writer.as_mut(), "{}", c).expect(IO_ERROR_MSG);
+    }
+
+    fn effect(&mut self, e: &Effect) {
+        let effect = match e {
+            Effect::Allow => self.style(ANSI_GREEN, "ALLOW"),
+            Effect::Deny => self.style(ANSI_RED, "DENY"),
+        };
+        self.label("Effect");
+        writeln!(self.writer.as_mut(), "{}", effect).expect(IO_ERROR_MSG);
+    }
+
+    fn principal(&mut self, p: &Principal) {
+        let (negated, values) = match p {
+            Principal::Principal(v) => (false, v),
+            Principal::NotPrincipal(v) => (true, v),
+        };
+        let principals = match values {
+            OrAny::Any => {
+                let value = self.values::<String>(&OrAny::Any, negated);
+                self.label("Principal");
+                writeln!(self.writer.as_mut(), "{}", value).expect(IO_ERROR_MSG);
+                return;
+            }
+            OrAny::Some(principals) => principals,
+        };
+        for (index, (kind, ids)) in principal_types(principals).iter().enumerate() {
+            let value = format!("{} {}", self.style(ANSI_DIM, kind), self.list(ids, negated));
+            self.label(if index == 0 { "Principal" } else { "" });
+            writeln!(self.writer.as_mut(), "{}", value).expect(IO_ERROR_MSG);
+        }
+    }
+
+    fn action(&mut self, a: &Action) {
+        let (negated, value) = match a {
+            Action::Action(v) => (false, v),
+            Action::NotAction(v) => (true, v),
+        };
+        let value = self.values(value, negated);
+        self.label("Action");
+        writeln!(self.writer.as_mut(), "{}", value).expect(IO_ERROR_MSG);
+    }
+
+    fn resource(&mut self, r: &Resource) {
+        let (negated, value) = match r {
+            Resource::Resource(v) => (false, v),
+            Resource::NotResource(v) => (true, v),
+        };
+        let value = self.values(value, negated);
+        self.label("Resource");
+        writeln!(self.writer.as_mut(), "{}", value).expect(IO_ERROR_MSG);
+    }
+
+    fn condition_visitor(&mut self) -> Option<Box<&mut dyn ConditionVisitor>> {
+        Some(Box::new(self))
+    }
+}
+
+impl ConditionVisitor for TerminalGenerator {
+    fn start(&mut self) {
+        self.label(if self.has_conditions { "" } else { "Condition" });
+        self.has_conditions = true;
+    }
+
+    fn left(&mut self, f: &QualifiedName, _op: &Operator) {
+        write!(self.writer.as_mut(), "{}", f).expect(IO_ERROR_MSG);
+    }
+
+    fn operator(&mut self, op: &Operator) {
+        let operator = op.to_string();
+        let operator = self.style(ANSI_BOLD, &operator);
+        write!(self.writer.as_mut(), " {} ", operator).expect(IO_ERROR_MSG);
+    }
+
+    fn right(&mut self, v: &[ConditionValue], _op: &Operator) {
+        let value = v
+            .iter()
+            .map(|v| self.value(&condition_value(v)))
+            .collect::<Vec<String>>()
+            .join(", ");
+        write!(self.writer.as_mut(), "{}", value).expect(IO_ERROR_MSG);
+    }
+
+    fn finish(&mut self) {
+        writeln!(self.writer.as_mut()).expect(IO_ERROR_MSG);
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn condition_value(v: &ConditionValue) -> String {
+    if v.is_string() {
+        format!("{:?}", v.to_string())
+    } else {
+        v.to_string()
+    }
+}
@@ -4,7 +4,6 @@ be concerning domain-specific validation, or simply documentation.
 */
 
 use crate::model::*;
-use std::collections::HashMap;
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
@@ -17,6 +16,7 @@ use std::collections::HashMap;
 /// 1. `start()`
 /// 1. `id()`
 /// 1. `version()`
+/// 1. `comment()`
 /// 1. let statement visitor = `statement()`
 /// 1. if statement visitor, visit each statement in turn (in the order they are in the JSON file)
 /// 1. `finish()`
@@ -32,6 +32,10 @@ pub trait PolicyVisitor {
     /// Called by the walker to allow handling of the `version` component of the Policy.
     fn version(&mut self, v: &Version) {}
 
+    /// Called by the walker with the comment on the Policy, if any; comments are only
+    /// provided by `walk_policy_with_comments`.
+    fn comment(&mut self, c: &str) {}
+
     /// Return an associated `StatementVisitor` if necessary.
     fn statement_visitor(&mut self) -> Option<Box<&mut dyn StatementVisitor>> {
         None
@@ -47,6 +51,7 @@ pub trait PolicyVisitor {
 ///
 /// 1. `start()`
 /// 1. `sid()`
+/// 1. `comment()`
 /// 1. `effect()`
 /// 1. `principal()`
 /// 1. `action()`
@@ -63,6 +68,10 @@ pub trait StatementVisitor {
     /// Called by the walker to allow handling of the `sid` component of the Statement.
     fn sid(&mut self, s: &str) {}
 
+    /// Called by the walker with the comment on the Statement, if any; comments are only
+    /// provided by `walk_policy_with_comments`.
+    fn comment(&mut self, c: &str) {}
+
     /// Called by the walker to allow handling of the `effect` component of the Statement.
     fn effect(&mut self, e: &Effect) {}
 
@@ -102,13 +111,13 @@ pub trait ConditionVisitor {
     fn start(&mut self) {}
 
     /// Called by the walker to allow handling of the `left` component of the Condition.
-    fn left(&mut self, f: &QString, op: &ConditionOperator) {}
+    fn left(&mut self, f: &QualifiedName, op: &Operator) {}
 
     /// Called by the walker to allow handling of the `operator` component of the Condition.
-    fn operator(&mut self, op: &ConditionOperator) {}
+    fn operator(&mut self, op: &Operator) {}
 
     /// Called by the walker to allow handling of the `right` component of the Condition.
-    fn right(&mut self, v: &OneOrAll<ConditionValue>, op: &ConditionOperator) {}
+    fn right(&mut self, v: &[ConditionValue], op: &Operator) {}
 
     /// Called to signal the walker has finished the Condition.
     fn finish(&mut self) {}
@@ -123,6 +132,18 @@ pub trait ConditionVisitor {
 /// with each component of `policy` and may choose to use the `writer`.
 ///
 pub fn walk_policy(policy: &Policy, visitor: &mut impl PolicyVisitor) {
+    walk_policy_with_comments(policy, &Comments::default(), visitor)
+}
+
+///
+/// Walk `policy` as `walk_policy` does, also passing the `comments` read with it to the
+/// visitors, so that generators can use them as descriptions.
+///
+pub fn walk_policy_with_comments(
+    policy: &Policy,
+    comments: &Comments,
+    visitor: &mut impl PolicyVisitor,
+) {
     visitor.start();
     if let Some(id) = &policy.id {
         visitor.id(id);
@@ -130,14 +151,16 @@ pub fn walk_policy(policy: &Policy, visitor: &mut impl PolicyVisitor) {
     if let Some(version) = &policy.version {
         visitor.version(version);
     }
-    if let Some(statement_visitor) = visitor.statement_visitor() {
-        match &policy.statement {
-            OneOrAll::One(statement) => walk_statement(statement, statement_visitor),
-            OneOrAll::All(statements) => {
-                for statement in statements {
-                    walk_statement(statement, visitor.statement_visitor().unwrap())
-                }
-            }
+    if let Some(comment) = comments.policy() {
+        visitor.comment(comment);
+    }
+    if visitor.statement_visitor().is_some() {
+        for (index, statement) in policy.statement.iter().enumerate() {
+            walk_statement(
+                statement,
+                comments.statement(index),
+                visitor.statement_visitor().unwrap(),
+            )
         }
     }
     visitor.finish();
@@ -147,17 +170,26 @@ pub fn walk_policy(policy: &Policy, visitor: &mut impl PolicyVisitor) {
 // Private Functions
 // ------------------------------------------------------------------------------------------------
 
-fn walk_statement(statement: &Statement, visitor: Box<&mut dyn StatementVisitor>) {
+fn walk_statement(
+    statement: &Statement,
+    comment: Option<&str>,
+    visitor: Box<&mut dyn StatementVisitor>,
+) {
     visitor.start();
     if let Some(sid) = &statement.sid {
         visitor.sid(sid);
     }
+    if let Some(comment) = comment {
+        visitor.comment(comment);
+    }
     visitor.effect(&statement.effect);
     if let Some(principal) = &statement.principal {
         visitor.principal(principal);
     }
     visitor.action(&statement.action);
-    visitor.resource(&statement.resource);
+    if let Some(resource) = &statement.resource {
+        visitor.resource(resource);
+    }
     if let Some(conditions) = &statement.condition {
         if let Some(condition_visitor) = visitor.condition_visitor() {
             walk_conditions(conditions, condition_visitor)
@@ -166,12 +198,9 @@ fn walk_statement(statement: &Statement, visitor: Box<&mut dyn StatementVisitor>
     visitor.finish();
 }
 
-fn walk_conditions(
-    conditions: &HashMap<ConditionOperator, HashMap<QString, OneOrAll<ConditionValue>>>,
-    visitor: Box<&mut dyn ConditionVisitor>,
-) {
-    for (op, rhs) in conditions {
-        for (field, values) in rhs {
+fn walk_conditions(conditions: &Condition, visitor: Box<&mut dyn ConditionVisitor>) {
+    for (op, rhs) in conditions.iter() {
+        for (field, values) in rhs.iter() {
             visitor.start();
             visitor.left(field, op);
             visitor.operator(op);
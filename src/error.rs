@@ -1,28 +1,113 @@
 /*!
-One-line description.
-More detailed description, with
+Provides the error types for this crate. All public operations that may fail return, or may be
+converted into, [`IamError`](enum.IamError.html), and each error retains the error that
+caused it, if any, as its `source`.
+
+All the error types in this module are marked `#[non_exhaustive]`; matches on them should
+include a wildcard arm, as new variants may be added in minor versions.
+
 # Example
+
+```rust
+use aws_iam::error::IamError;
+use aws_iam::io;
+use std::error::Error;
+
+let error: IamError = io::read_from_string(r#"{"Statement": 42}"#).unwrap_err();
+let mut source = error.source();
+while let Some(cause) = source {
+    println!("caused by: {}", cause);
+    source = cause.source();
+}
+```
  */
 
+#[cfg(feature = "aws_online")]
+use crate::offline::simulator::SimulationError;
+#[cfg(feature = "offline_eval")]
+use crate::offline::EvaluationError;
 use thiserror::Error;
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
 // ------------------------------------------------------------------------------------------------
 
+///
+/// The error type for this crate; each variant wraps one of the more specific error types.
+///
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum IamError {
+    /// A policy, or one of its components, is not correctly formed.
     #[error(transparent)]
     Format(#[from] IamFormatError),
 
+    /// A policy document is not valid JSON.
     #[error(transparent)]
     Json(#[from] serde_json::Error),
 
+    /// A policy document is not valid JSON5, see `io::read_from_string_relaxed`.
+    #[cfg(feature = "relaxed_json")]
+    #[error(transparent)]
+    RelaxedJson(#[from] json5::Error),
+
+    /// A document is not valid YAML, see `offline::run_spec`.
+    #[cfg(feature = "yaml")]
+    #[error(transparent)]
+    Yaml(#[from] serde_yaml::Error),
+
+    /// A policy document could not be read or written.
     #[error(transparent)]
     Io(#[from] std::io::Error),
+
+    /// A policy could not be built.
+    #[error(transparent)]
+    Build(#[from] BuildError),
+    /// A policy template could not be read or rendered.
+    #[error(transparent)]
+    Template(#[from] TemplateError),
+
+    /// A fragment included by a policy document could not be read or expanded.
+    #[cfg(feature = "fs")]
+    #[error(transparent)]
+    Include(#[from] IncludeError),
+
+    /// A request could not be evaluated.
+    #[cfg(feature = "offline_eval")]
+    #[error(transparent)]
+    Evaluation(#[from] EvaluationError),
+
+    /// A request could not be reconciled with the policy simulator.
+    #[cfg(feature = "aws_online")]
+    #[error(transparent)]
+    Simulation(#[from] SimulationError),
+
+    /// A rule pack could not be loaded or run.
+    #[cfg(feature = "plugins")]
+    #[error(transparent)]
+    Plugin(#[from] PluginError),
+
+    /// A watched directory of policies could not be read.
+    #[cfg(feature = "watch")]
+    #[error(transparent)]
+    Watch(#[from] WatchError),
+
+    /// A policy signature could not be read or verified.
+    #[cfg(feature = "signing")]
+    #[error(transparent)]
+    Signing(#[from] SigningError),
+
+    /// A policy bundle could not be written, read, or verified.
+    #[cfg(feature = "bundle")]
+    #[error(transparent)]
+    Bundle(#[from] BundleError),
 }
 
+///
+/// Errors reported when a policy, or one of its components, is not correctly formed.
+///
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum IamFormatError {
     #[error("A required property `{name}` was not found")]
     MissingProperty { name: String },
@@ -49,6 +134,13 @@ pub enum IamFormatError {
     #[error("Could not serialize a value to JSON")]
     CouldNotSerialize,
 
+    #[error("Could not parse a JSON value")]
+    Json(
+        #[from]
+        #[source]
+        serde_json::Error,
+    ),
+
     #[error("Could not parse an ARN")]
     Arn(
         #[from]
@@ -58,6 +150,174 @@ pub enum IamFormatError {
 
     #[error("Could not expand a variable in the value `{value}`")]
     InvalidVariable { value: String },
+
+    #[error("The condition operator `{operator}` already tests the key `{key}` with other values")]
+    ConflictingCondition { operator: String, key: String },
+}
+
+///
+/// Errors reported by the `build` methods of the builders in `model::builder`.
+///
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum BuildError {
+    #[error("A statement must have at least one action")]
+    MissingAction,
+
+    #[error("A policy must have at least one statement")]
+    NoStatements,
+
+    #[error("Could not build statement {index}: {source}")]
+    Statement {
+        index: usize,
+        #[source]
+        source: Box<BuildError>,
+    },
+
+    #[error(transparent)]
+    Format(#[from] IamFormatError),
+}
+
+///
+/// Errors reported when reading, or rendering, a `template::PolicyTemplate`.
+///
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum TemplateError {
+    #[error("The placeholder `{value}` is not correctly formed")]
+    InvalidPlaceholder { value: String },
+    #[error("No value was provided for the parameter `{name}`")]
+    MissingParameter { name: String },
+    #[error("The parameter `{name}` is not used by the template")]
+    UnexpectedParameter { name: String },
+    #[cfg(feature = "templating")]
+    #[error("The template is not correctly formed{}: {message}", at_line(.line))]
+    Syntax {
+        line: Option<usize>,
+        message: String,
+    },
+    #[cfg(feature = "templating")]
+    #[error("The template could not be rendered{}: {message}", at_line(.line))]
+    Render {
+        line: Option<usize>,
+        message: String,
+    },
+    #[cfg(feature = "templating")]
+    #[error("The rendered template is not a valid policy{}", at_line(.line))]
+    InvalidPolicy {
+        line: Option<usize>,
+        #[source]
+        source: Box<IamError>,
+    },
+}
+
+///
+/// Errors reported when the fragments included by a policy document are expanded, see
+/// `io::read_with_includes`.
+///
+#[cfg(feature = "fs")]
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum IncludeError {
+    #[error("The policy fragment `{path}` could not be read")]
+    Read {
+        path: std::path::PathBuf,
+        #[source]
+        source: Box<IamError>,
+    },
+    #[error("The policy fragment `{path}` includes itself")]
+    Cycle { path: std::path::PathBuf },
+    #[error("The policy fragment `{path}` is merged into an object but is not an object")]
+    NotAnObject { path: std::path::PathBuf },
+    #[error("The include value `{value}` is not a path or an array of paths")]
+    InvalidValue { value: String },
+}
+
+///
+/// Errors reported by a rule pack, see `plugin::PluginRuntime`.
+///
+#[cfg(feature = "plugins")]
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum PluginError {
+    #[error("The rule pack reported an error: {0}")]
+    Runtime(String),
+    #[error("The rule pack has no rule named `{0}`")]
+    UnknownRule(String),
+    #[error("The rule pack has no transform named `{0}`")]
+    UnknownTransform(String),
+    #[error("The transform `{0}` returned a policy that is not correctly formed")]
+    InvalidPolicy(String, #[source] IamFormatError),
+    #[cfg(feature = "plugins_wasmtime")]
+    #[error("The rule pack `{path}` could not be loaded: {message}")]
+    Load {
+        path: std::path::PathBuf,
+        message: String,
+    },
+}
+
+///
+/// Errors reported when reading a directory of policies, see `io::watch_directory`.
+///
+#[cfg(feature = "watch")]
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum WatchError {
+    #[error("The policy file `{path}` could not be read")]
+    Read {
+        path: std::path::PathBuf,
+        #[source]
+        source: Box<IamError>,
+    },
+    #[error("The policy file `{path}` is not valid: {message}")]
+    Invalid {
+        path: std::path::PathBuf,
+        message: String,
+    },
+}
+
+///
+/// Errors reported when a policy is signed or verified, see the `signing` module.
+///
+#[cfg(feature = "signing")]
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SigningError {
+    #[error("The key is not valid: {0}")]
+    InvalidKey(String),
+    #[error("The signature is not valid: {0}")]
+    InvalidSignature(String),
+    #[error("The signature file `{path}` was not found")]
+    MissingSignature { path: std::path::PathBuf },
+    #[error("The policy was not signed by a trusted key")]
+    Untrusted,
+}
+
+///
+/// Errors reported when a policy bundle is written or read, see `io::bundle`.
+///
+#[cfg(feature = "bundle")]
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum BundleError {
+    #[error("The name `{0}` is not a valid policy file name")]
+    InvalidName(String),
+    #[error("The bundle does not contain the file `{0}`")]
+    MissingFile(String),
+    #[error("The bundle format `{0}` is not supported")]
+    UnsupportedFormat(String),
+    #[error("The policy `{0}` does not match its fingerprint")]
+    FingerprintMismatch(String),
+    #[error("The policy `{0}` is not signed")]
+    Unsigned(String),
+    #[error("The bundle is encrypted but no key was provided")]
+    MissingKey,
+    #[error("The bundle is not encrypted but a key was provided")]
+    NotEncrypted,
+    #[error("The bundle could not be encrypted")]
+    Encryption,
+    #[error("The bundle could not be decrypted, the key is not correct or the bundle is damaged")]
+    Decryption,
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -126,6 +386,17 @@ pub fn could_not_serialize() -> IamFormatError {
     IamFormatError::CouldNotSerialize
 }
 
+pub fn conflicting_condition<S1, S2>(operator: S1, key: S2) -> IamFormatError
+where
+    S1: Into<String>,
+    S2: Into<String>,
+{
+    IamFormatError::ConflictingCondition {
+        operator: operator.into(),
+        key: key.into(),
+    }
+}
+
 // ------------------------------------------------------------------------------------------------
 // Implementations
 // ------------------------------------------------------------------------------------------------
@@ -142,6 +413,22 @@ impl<T> From<IamFormatError> for Result<T, IamFormatError> {
     }
 }
 
+impl<T> From<BuildError> for Result<T, BuildError> {
+    fn from(e: BuildError) -> Self {
+        Err(e)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(feature = "templating")]
+fn at_line(line: &Option<usize>) -> String {
+    line.map(|line| format!(" at template line {}", line))
+        .unwrap_or_default()
+}
+
 // ------------------------------------------------------------------------------------------------
 // Modules
 // ------------------------------------------------------------------------------------------------
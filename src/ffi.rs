@@ -0,0 +1,318 @@
+/*!
+Provides a small C-compatible API so that tools written in other languages, such as Python
+using `ctypes` or Go using `cgo`, can reuse the policy parser, validation, and offline
+evaluation. This module is only available with the `ffi` feature.
+
+The header `include/aws_iam.h` is generated from this module with
+[cbindgen](https://github.com/mozilla/cbindgen), using the configuration in `cbindgen.toml`:
+
+```bash
+cbindgen --config cbindgen.toml --crate aws-iam --output include/aws_iam.h
+```
+
+A shared library can be built with:
+
+```bash
+cargo rustc --release --features ffi --crate-type cdylib
+```
+
+# Memory
+
+A policy returned by `aws_iam_parse` must be released with `aws_iam_policy_free`, and every
+string returned by this API, including error messages, must be released with
+`aws_iam_string_free`. Strings passed to this API are borrowed and must be valid, NUL
+terminated, UTF-8.
+
+# Errors
+
+Functions that can fail return `NULL`, or `AWS_IAM_ERROR`, and describe the failure in their
+`error` argument. A panic is never allowed to unwind into the caller; it is reported as a
+failure in the same way.
+
+# Example
+
+```c
+#include "aws_iam.h"
+
+char *error = NULL;
+AwsIamPolicy *policy = aws_iam_parse("{\"Statement\":[...]}", &error);
+if (policy == NULL) {
+    fprintf(stderr, "%s\n", error);
+    aws_iam_string_free(error);
+} else {
+    char *findings = aws_iam_validate(policy, AWS_IAM_POLICY_TYPE_RESOURCE, NULL);
+    printf("%s\n", findings);
+    aws_iam_string_free(findings);
+    aws_iam_policy_free(policy);
+}
+```
+*/
+
+use crate::model::Policy;
+use crate::validate::{PolicyType, ValidationOptions};
+use serde_json::{json, Value};
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// An opaque handle to a parsed policy.
+///
+#[derive(Debug)]
+pub struct AwsIamPolicy(Policy);
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+/// Validate as an identity-based policy, see `PolicyType::Identity`.
+pub const AWS_IAM_POLICY_TYPE_IDENTITY: u32 = 0;
+
+/// Validate as a resource-based policy, see `PolicyType::Resource`.
+pub const AWS_IAM_POLICY_TYPE_RESOURCE: u32 = 1;
+
+/// Validate as a role trust policy, see `PolicyType::Trust`.
+pub const AWS_IAM_POLICY_TYPE_TRUST: u32 = 2;
+
+/// Validate as a permissions boundary, see `PolicyType::PermissionsBoundary`.
+pub const AWS_IAM_POLICY_TYPE_PERMISSIONS_BOUNDARY: u32 = 3;
+
+/// Validate as a service control policy, see `PolicyType::ServiceControl`.
+pub const AWS_IAM_POLICY_TYPE_SERVICE_CONTROL: u32 = 4;
+
+/// Validate as a VPC endpoint policy, see `PolicyType::Endpoint`.
+pub const AWS_IAM_POLICY_TYPE_ENDPOINT: u32 = 5;
+
+/// The request is allowed, returned by `aws_iam_evaluate`.
+pub const AWS_IAM_ALLOW: i32 = 1;
+
+/// The request is denied, returned by `aws_iam_evaluate`.
+pub const AWS_IAM_DENY: i32 = 0;
+
+/// The request could not be evaluated, returned by `aws_iam_evaluate`.
+pub const AWS_IAM_ERROR: i32 = -1;
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Parse the JSON policy document `json`, returning `NULL` on failure. If `error` is not `NULL`
+/// it is set to a description of the failure, or to `NULL` on success.
+///
+/// # Safety
+///
+/// `json` must be a valid, NUL terminated, string and `error`, if not `NULL`, must be valid for
+/// writes. The returned policy must be released with `aws_iam_policy_free`.
+///
+#[no_mangle]
+pub unsafe extern "C" fn aws_iam_parse(
+    json: *const c_char,
+    error: *mut *mut c_char,
+) -> *mut AwsIamPolicy {
+    let result = catch_panic(|| {
+        borrow_str(json).and_then(|json| Policy::from_str(json).map_err(|e| e.to_string()))
+    });
+    match result {
+        Ok(policy) => {
+            set_error(error, None);
+            Box::into_raw(Box::new(AwsIamPolicy(policy)))
+        }
+        Err(e) => {
+            set_error(error, Some(e));
+            ptr::null_mut()
+        }
+    }
+}
+
+///
+/// Validate `policy` as the kind of policy `policy_type`, one of the `AWS_IAM_POLICY_TYPE_*`
+/// values, returning the findings as a JSON array of objects with the properties `severity`,
+/// `code`, `message`, and `statement`. Returns `NULL` on failure, see `aws_iam_parse` for the
+/// use of `error`.
+///
+/// # Safety
+///
+/// `policy` must have been returned by `aws_iam_parse` and not yet released. The returned
+/// string must be released with `aws_iam_string_free`.
+///
+#[no_mangle]
+pub unsafe extern "C" fn aws_iam_validate(
+    policy: *const AwsIamPolicy,
+    policy_type: u32,
+    error: *mut *mut c_char,
+) -> *mut c_char {
+    let policy = match policy.as_ref() {
+        Some(policy) => &policy.0,
+        None => {
+            set_error(error, Some("policy is NULL".to_string()));
+            return ptr::null_mut();
+        }
+    };
+    let policy_type = match policy_type {
+        AWS_IAM_POLICY_TYPE_IDENTITY => PolicyType::Identity,
+        AWS_IAM_POLICY_TYPE_RESOURCE => PolicyType::Resource,
+        AWS_IAM_POLICY_TYPE_TRUST => PolicyType::Trust,
+        AWS_IAM_POLICY_TYPE_PERMISSIONS_BOUNDARY => PolicyType::PermissionsBoundary,
+        AWS_IAM_POLICY_TYPE_SERVICE_CONTROL => PolicyType::ServiceControl,
+        AWS_IAM_POLICY_TYPE_ENDPOINT => PolicyType::Endpoint,
+        _ => {
+            set_error(error, Some(format!("unknown policy type {}", policy_type)));
+            return ptr::null_mut();
+        }
+    };
+    let result = catch_panic(|| {
+        let findings: Vec<Value> = policy
+            .validate(&ValidationOptions::for_type(policy_type))
+            .iter()
+            .map(|finding| {
+                json!({
+                    "severity": finding.severity.to_string(),
+                    "code": finding.code,
+                    "message": finding.message,
+                    "statement": finding.statement,
+                })
+            })
+            .collect();
+        Ok(Value::Array(findings).to_string())
+    });
+    match result {
+        Ok(findings) => {
+            set_error(error, None);
+            into_c_string(findings)
+        }
+        Err(e) => {
+            set_error(error, Some(e));
+            ptr::null_mut()
+        }
+    }
+}
+
+///
+/// Evaluate the JSON request `request`, in the form described by the
+/// [`offline`](../offline/index.html) module, against `policy`; returning `AWS_IAM_ALLOW`,
+/// `AWS_IAM_DENY`, or `AWS_IAM_ERROR`. See `aws_iam_parse` for the use of `error`. This
+/// function requires the `offline_eval` feature.
+///
+/// # Safety
+///
+/// `policy` must have been returned by `aws_iam_parse` and not yet released, and `request`
+/// must be a valid, NUL terminated, string.
+///
+#[cfg(feature = "offline_eval")]
+#[no_mangle]
+pub unsafe extern "C" fn aws_iam_evaluate(
+    policy: *const AwsIamPolicy,
+    request: *const c_char,
+    error: *mut *mut c_char,
+) -> i32 {
+    use crate::offline::{evaluate, EvaluationResult, Request};
+
+    let policy = match policy.as_ref() {
+        Some(policy) => &policy.0,
+        None => {
+            set_error(error, Some("policy is NULL".to_string()));
+            return AWS_IAM_ERROR;
+        }
+    };
+    let result = catch_panic(|| {
+        borrow_str(request)
+            .and_then(|request| serde_json::from_str::<Request>(request).map_err(|e| e.to_string()))
+            .and_then(|request| evaluate(&request, policy).map_err(|e| e.to_string()))
+    });
+    match result {
+        Ok(EvaluationResult::Allow(_)) => {
+            set_error(error, None);
+            AWS_IAM_ALLOW
+        }
+        Ok(EvaluationResult::Deny(_, _, _)) => {
+            set_error(error, None);
+            AWS_IAM_DENY
+        }
+        Err(e) => {
+            set_error(error, Some(e));
+            AWS_IAM_ERROR
+        }
+    }
+}
+
+///
+/// Release a policy returned by `aws_iam_parse`; `NULL` is ignored.
+///
+/// # Safety
+///
+/// `policy` must have been returned by `aws_iam_parse` and not yet released.
+///
+#[no_mangle]
+pub unsafe extern "C" fn aws_iam_policy_free(policy: *mut AwsIamPolicy) {
+    if !policy.is_null() {
+        drop(Box::from_raw(policy));
+    }
+}
+
+///
+/// Release a string returned by this API; `NULL` is ignored.
+///
+/// # Safety
+///
+/// `s` must have been returned by this API and not yet released.
+///
+#[no_mangle]
+pub unsafe extern "C" fn aws_iam_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Run `f`, returning a panic as an error so that it does not unwind across the C boundary.
+///
+fn catch_panic<T, F>(f: F) -> Result<T, String>
+where
+    F: FnOnce() -> Result<T, String>,
+{
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown cause".to_string()
+        };
+        Err(format!("panicked: {}", message))
+    })
+}
+
+unsafe fn borrow_str<'a>(s: *const c_char) -> Result<&'a str, String> {
+    if s.is_null() {
+        Err("string is NULL".to_string())
+    } else {
+        CStr::from_ptr(s).to_str().map_err(|e| e.to_string())
+    }
+}
+
+unsafe fn set_error(error: *mut *mut c_char, message: Option<String>) {
+    if !error.is_null() {
+        *error = match message {
+            Some(message) => into_c_string(message),
+            None => ptr::null_mut(),
+        };
+    }
+}
+
+fn into_c_string(s: String) -> *mut c_char {
+    // JSON and error messages never contain NUL, but replace any rather than fail.
+    CString::new(s.replace('\0', " "))
+        .map(CString::into_raw)
+        .unwrap_or(ptr::null_mut())
+}
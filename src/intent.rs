@@ -0,0 +1,180 @@
+/*!
+Provides the generation of least-privilege policies from coarse descriptions of the access
+required, such as "read-only access to this bucket". Requires feature `service_config`.
+
+Each [`Intent`](enum.Intent.html) names a service namespace, a level of access, and the
+resources it applies to. It is expanded, using the access levels of the service's actions in
+the [`actions`](../service/actions/index.html) catalog, to a single statement that allows
+exactly those actions; no wildcards are used, so actions added to a service later are not
+granted.
+
+| Intent      | Access levels                                        |
+|-------------|------------------------------------------------------|
+| `ListOnly`  | List                                                 |
+| `ReadOnly`  | List, Read                                           |
+| `ReadWrite` | List, Read, Write                                    |
+| `Admin`     | List, Read, Write, Tagging, Permissions management   |
+
+Some actions, such as `s3:ListAllMyBuckets`, do not support resource-level permissions and are
+only allowed if the intent's resource is `"*"`; the catalog does not record which actions
+these are, and so they are included as for any other action.
+
+# Example
+
+```rust
+use aws_iam::intent::{generate_policy, Intent};
+
+let policy = generate_policy(&[
+    Intent::ReadOnly("s3".to_string(), "arn:aws:s3:::bucket".to_string()),
+    Intent::Admin(
+        "dynamodb".to_string(),
+        "arn:aws:dynamodb:us-east-1:123456789012:table/orders".to_string(),
+    ),
+])
+.unwrap();
+
+assert_eq!(policy.statement.len(), 2);
+assert!(policy.statement[0].allows_action("s3:GetObject"));
+assert!(!policy.statement[0].allows_action("s3:PutObject"));
+assert!(policy.statement[1].allows_action("dynamodb:DeleteTable"));
+```
+*/
+
+use crate::arn::ArnParseMode;
+use crate::error::{empty_vector_property, unexpected_value_for_type, IamFormatError};
+use crate::model::{Action, Effect, Policy, Resource, ResourceEntry, Sid, Statement, Version};
+use crate::service::actions::{service_metadata, AccessLevel};
+use crate::syntax::{NAMESPACE_NAME, POLICY_WILDCARD_VALUE, STATEMENT_NAME};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A coarse description of the access required to the resources of a service; each variant
+/// holds the service namespace, such as `s3`, and a resource ARN, or `"*"` for any resource.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Intent {
+    /// List resources, but not read their contents.
+    ListOnly(String, String),
+    /// List and read resources and their attributes.
+    ReadOnly(String, String),
+    /// List, read, create, modify, and delete resources.
+    ReadWrite(String, String),
+    /// All actions, including those that tag resources and manage their permissions.
+    Admin(String, String),
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Generate a `2012-10-17` version policy with one statement for each of `intents`, see
+/// `Intent::statement`.
+///
+pub fn generate_policy(intents: &[Intent]) -> Result<Policy, IamFormatError> {
+    if intents.is_empty() {
+        return empty_vector_property(STATEMENT_NAME).into();
+    }
+    let statements: Result<Vec<Statement>, IamFormatError> =
+        intents.iter().map(Intent::statement).collect();
+    Policy::unnamed_with_version(statements?, Version::V2012)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Intent {
+    /// The service namespace this intent applies to.
+    pub fn namespace(&self) -> &str {
+        self.parts().0
+    }
+
+    /// The resource this intent applies to.
+    pub fn resource(&self) -> &str {
+        self.parts().1
+    }
+
+    /// The access levels of the actions this intent allows.
+    pub fn access_levels(&self) -> &'static [AccessLevel] {
+        match self {
+            Self::ListOnly(_, _) => &[AccessLevel::List],
+            Self::ReadOnly(_, _) => &[AccessLevel::List, AccessLevel::Read],
+            Self::ReadWrite(_, _) => &[AccessLevel::List, AccessLevel::Read, AccessLevel::Write],
+            Self::Admin(_, _) => &[
+                AccessLevel::List,
+                AccessLevel::Read,
+                AccessLevel::Write,
+                AccessLevel::Tagging,
+                AccessLevel::PermissionsManagement,
+            ],
+        }
+    }
+
+    ///
+    /// Expand this intent to a statement that allows the known actions of the service with one
+    /// of its access levels on its resource. Returns an error if the service is not in the
+    /// catalog, if it has no actions at these levels, or if the resource is not valid.
+    ///
+    pub fn statement(&self) -> Result<Statement, IamFormatError> {
+        let levels = self.access_levels();
+        let actions = service_metadata(self.namespace())
+            .ok_or_else(|| unexpected_value_for_type(NAMESPACE_NAME, self.namespace()))?
+            .iter()
+            .filter(|metadata| levels.contains(&metadata.access_level))
+            .map(|metadata| metadata.qualified_name())
+            .collect::<Vec<_>>();
+        if actions.is_empty() {
+            return unexpected_value_for_type(NAMESPACE_NAME, self.namespace()).into();
+        }
+        let resource = if self.resource() == POLICY_WILDCARD_VALUE {
+            Resource::any_resource()
+        } else {
+            Resource::this_resource(ResourceEntry::parse(
+                self.resource(),
+                ArnParseMode::default(),
+            )?)
+        };
+        Ok(Statement {
+            sid: Some(Sid::new_unchecked(self.sid())),
+            principal: None,
+            effect: Effect::Allow,
+            action: Action::these_actions(actions),
+            resource: Some(resource),
+            condition: None,
+        })
+    }
+
+    fn parts(&self) -> (&str, &str) {
+        match self {
+            Self::ListOnly(namespace, resource)
+            | Self::ReadOnly(namespace, resource)
+            | Self::ReadWrite(namespace, resource)
+            | Self::Admin(namespace, resource) => (namespace, resource),
+        }
+    }
+
+    ///
+    /// For example `S3ReadOnly`; Sids may only contain alphanumeric characters.
+    ///
+    fn sid(&self) -> String {
+        let mut chars = self.namespace().chars().filter(char::is_ascii_alphanumeric);
+        let service: String = match chars.next() {
+            Some(first) => first.to_ascii_uppercase().to_string() + &chars.collect::<String>(),
+            None => String::new(),
+        };
+        format!(
+            "{}{}",
+            service,
+            match self {
+                Self::ListOnly(_, _) => "ListOnly",
+                Self::ReadOnly(_, _) => "ReadOnly",
+                Self::ReadWrite(_, _) => "ReadWrite",
+                Self::Admin(_, _) => "Admin",
+            }
+        )
+    }
+}
@@ -0,0 +1,225 @@
+/*!
+Provides a model of the findings reported by
+[IAM Access Analyzer](https://docs.aws.amazon.com/IAM/latest/UserGuide/what-is-access-analyzer.html)
+for resources shared outside of an account, and a way to correlate each finding with the
+statements of a local policy that grant the access it describes.
+
+# Example
+
+```rust
+use aws_iam::io::read_access_analyzer_findings;
+use aws_iam::model::Policy;
+use std::str::FromStr;
+
+let findings = read_access_analyzer_findings(
+    r#"{"findings":[{
+        "id":"a1b2c3d4-5678-90ab-cdef-EXAMPLE11111",
+        "principal":{"AWS":"444455556666"},
+        "action":["s3:GetObject"],
+        "resource":"arn:aws:s3:::bucket",
+        "resourceType":"AWS::S3::Bucket",
+        "isPublic":false,
+        "status":"ACTIVE"
+    }]}"#
+    .as_bytes(),
+)
+.unwrap();
+
+let policy = Policy::from_str(
+    r#"{"Statement":[
+        {"Effect":"Allow","Principal":{"AWS":"arn:aws:iam::444455556666:root"},
+         "Action":"s3:Get*","Resource":"arn:aws:s3:::bucket"},
+        {"Effect":"Allow","Principal":{"AWS":"arn:aws:iam::444455556666:root"},
+         "Action":"s3:PutObject","Resource":"arn:aws:s3:::bucket"}
+    ]}"#,
+)
+.unwrap();
+assert_eq!(findings[0].statements(&policy), vec![0]);
+```
+*/
+
+use crate::arn::{account_id, wildcard_match, ARN};
+use crate::error::{type_mismatch, IamError};
+use crate::model::{HostName, OrAny, Policy, Principal, PrincipalMap, ServiceName, Statement};
+use crate::syntax::{
+    json_type_name, JSON_TYPE_NAME_OBJECT, POLICY_WILDCARD_VALUE, PRINCIPAL_TYPE_AWS,
+    PRINCIPAL_TYPE_FEDERATED, PRINCIPAL_TYPE_SERVICE,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Read;
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A single external access finding, as returned by the Access Analyzer `GetFinding` and
+/// `ListFindings` operations. Only the properties needed to correlate a finding with a policy
+/// are retained.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessAnalyzerFinding {
+    /// The identifier of the finding.
+    pub id: String,
+    /// The external principal that has access, keyed by principal type such as `AWS`.
+    #[serde(default)]
+    pub principal: IndexMap<String, String>,
+    /// The actions the external principal is allowed to perform.
+    #[serde(default)]
+    pub action: Vec<String>,
+    /// The ARN of the resource that is shared.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource: Option<String>,
+    /// The type of the resource, such as `AWS::S3::Bucket`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<String>,
+    /// The account that owns the resource.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_owner_account: Option<String>,
+    /// `true` if the resource is shared with everyone.
+    #[serde(default)]
+    pub is_public: bool,
+    /// The condition keys, and values, that restrict the access.
+    #[serde(default)]
+    pub condition: IndexMap<String, String>,
+    /// The status of the finding, one of `ACTIVE`, `ARCHIVED`, or `RESOLVED`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Read Access Analyzer findings from any implementation of `std::io::Read`. The content may be
+/// the output of `ListFindings`, an object with a `findings` array, the output of `GetFinding`,
+/// an object with a `finding` property, an array of findings, or a single finding.
+///
+pub fn read_access_analyzer_findings<R>(reader: R) -> Result<Vec<AccessAnalyzerFinding>, IamError>
+where
+    R: Read + Sized,
+{
+    let value: Value = serde_json::from_reader(reader)?;
+    let value = match value {
+        Value::Object(mut object) => {
+            if let Some(findings) = object.remove(FINDINGS_PROPERTY) {
+                findings
+            } else if let Some(finding) = object.remove(FINDING_PROPERTY) {
+                Value::Array(vec![finding])
+            } else {
+                Value::Array(vec![Value::Object(object)])
+            }
+        }
+        Value::Array(_) => value,
+        _ => {
+            return Err(type_mismatch(
+                FINDINGS_PROPERTY,
+                JSON_TYPE_NAME_OBJECT,
+                json_type_name(&value),
+            )
+            .into())
+        }
+    };
+    Ok(serde_json::from_value(value)?)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl AccessAnalyzerFinding {
+    ///
+    /// Returns the indices of the statements in `policy` that grant the access described by
+    /// this finding; that is, `Allow` statements that allow one of the finding's actions, on
+    /// its resource, to its principal. Conditions are not compared, a statement that grants the
+    /// access only under a condition is still returned.
+    ///
+    pub fn statements(&self, policy: &Policy) -> Vec<usize> {
+        policy
+            .statement
+            .iter()
+            .enumerate()
+            .filter(|(_, statement)| self.is_granted_by(statement))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    ///
+    /// Returns `true` if `statement` grants the access described by this finding, see
+    /// `statements`.
+    ///
+    pub fn is_granted_by(&self, statement: &Statement) -> bool {
+        statement.is_allow()
+            && self
+                .action
+                .iter()
+                .any(|action| statement.allows_action(action))
+            && self
+                .resource
+                .as_ref()
+                .and_then(|resource| ARN::from_str(resource).ok())
+                .map(|resource| statement.applies_to_resource(&resource))
+                .unwrap_or(true)
+            && self.principal_matches(&statement.principal)
+    }
+
+    fn principal_matches(&self, principal: &Option<Principal>) -> bool {
+        match principal {
+            None => true,
+            Some(Principal::Principal(OrAny::Any)) => true,
+            Some(Principal::Principal(OrAny::Some(map))) => {
+                (self.is_public && map.has_aws_wildcard())
+                    || self
+                        .principal
+                        .iter()
+                        .any(|(kind, value)| map_contains(map, kind, value))
+            }
+            Some(Principal::NotPrincipal(_)) => self.is_public,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const FINDINGS_PROPERTY: &str = "findings";
+
+const FINDING_PROPERTY: &str = "finding";
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// An account in a finding may be listed in a policy either by its ID or by the ARN of its
+/// root user, which is how this crate reads an account ID principal. Service principals are
+/// compared by name, so `cloudtrail` and `cloudtrail.amazonaws.com` are the same.
+///
+fn map_contains(map: &PrincipalMap, kind: &str, value: &str) -> bool {
+    if kind == PRINCIPAL_TYPE_AWS {
+        let value = match account_id(value) {
+            Some(account) => ARN::from(account).to_string(),
+            None => value.to_string(),
+        };
+        map.aws_iter().any(|entry| {
+            let entry = entry.to_string();
+            entry == POLICY_WILDCARD_VALUE || wildcard_match(&entry, &value)
+        })
+    } else if kind == PRINCIPAL_TYPE_SERVICE {
+        ServiceName::from_str(value)
+            .map(|value| map.service_iter().any(|service| service == &value))
+            .unwrap_or_default()
+    } else if kind == PRINCIPAL_TYPE_FEDERATED {
+        HostName::from_str(value)
+            .map(|value| map.federated_iter().any(|host| host == &value))
+            .unwrap_or_default()
+    } else {
+        false
+    }
+}
@@ -0,0 +1,549 @@
+/*!
+Provides a model of the output of the IAM `GetAccountAuthorizationDetails` operation, as written
+by `aws iam get-account-authorization-details`: the users, groups, and roles of an account,
+their inline and attached policies, and the documents of the managed policies.
+
+Once loaded, the identity policies that apply to each principal are found with
+[`AccountAuthorizationDetails::identity_policies`](struct.AccountAuthorizationDetails.html#method.identity_policies),
+including, for a user, those of its groups. With the feature `offline_eval` these may be
+evaluated for every principal in the account, see `offline::principals_allowed`.
+
+Policy documents are accepted either as JSON objects, as written by the AWS CLI, or as
+URL-encoded strings, as returned by the API itself. Only the default version of each managed
+policy is retained. AWS managed policies are only included in the output when requested with
+`--filter AWSManagedPolicy`; a policy attached to a principal but not included is skipped.
+
+# Example
+
+```rust
+use aws_iam::io::read_authorization_details;
+
+let details = read_authorization_details(
+    r#"{
+    "UserDetailList": [{
+        "UserName": "alice",
+        "UserId": "AIDAEXAMPLE",
+        "Arn": "arn:aws:iam::123456789012:user/alice",
+        "Path": "/",
+        "GroupList": ["readers"],
+        "UserPolicyList": [],
+        "AttachedManagedPolicies": []
+    }],
+    "GroupDetailList": [{
+        "GroupName": "readers",
+        "GroupId": "AGPAEXAMPLE",
+        "Arn": "arn:aws:iam::123456789012:group/readers",
+        "Path": "/",
+        "GroupPolicyList": [{
+            "PolicyName": "read",
+            "PolicyDocument": {"Statement": [
+                {"Effect": "Allow", "Action": "s3:GetObject", "Resource": "*"}
+            ]}
+        }],
+        "AttachedManagedPolicies": []
+    }],
+    "RoleDetailList": [],
+    "Policies": []
+}"#
+    .as_bytes(),
+)
+.unwrap();
+
+let policies = details.identity_policies("arn:aws:iam::123456789012:user/alice");
+assert_eq!(policies.len(), 1);
+assert!(policies[0].statement[0].allows_action("s3:GetObject"));
+```
+*/
+
+use crate::error::IamError;
+use crate::model::Policy;
+use crate::syntax::IamValue;
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The users, groups, roles, and managed policies of an account.
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccountAuthorizationDetails {
+    /// The IAM users of the account.
+    pub users: Vec<UserDetail>,
+    /// The IAM groups of the account.
+    pub groups: Vec<GroupDetail>,
+    /// The IAM roles of the account.
+    pub roles: Vec<RoleDetail>,
+    /// The managed policies, customer and, if requested, AWS managed.
+    pub policies: Vec<ManagedPolicyDetail>,
+}
+
+///
+/// An IAM user and the policies that apply to it.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserDetail {
+    /// The name of the user.
+    pub name: String,
+    /// The ARN of the user.
+    pub arn: String,
+    /// The path of the user.
+    pub path: String,
+    /// The names of the groups the user is a member of.
+    pub groups: Vec<String>,
+    /// The policies embedded in the user.
+    pub inline_policies: Vec<InlinePolicy>,
+    /// The ARNs of the managed policies attached to the user.
+    pub attached_policies: Vec<String>,
+    /// The ARN of the managed policy used as the user's permissions boundary, if any.
+    pub permissions_boundary: Option<String>,
+}
+
+///
+/// An IAM group and the policies that apply to its members.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupDetail {
+    /// The name of the group.
+    pub name: String,
+    /// The ARN of the group.
+    pub arn: String,
+    /// The path of the group.
+    pub path: String,
+    /// The policies embedded in the group.
+    pub inline_policies: Vec<InlinePolicy>,
+    /// The ARNs of the managed policies attached to the group.
+    pub attached_policies: Vec<String>,
+}
+
+///
+/// An IAM role, its trust policy, and the policies that apply to it.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoleDetail {
+    /// The name of the role.
+    pub name: String,
+    /// The ARN of the role.
+    pub arn: String,
+    /// The path of the role.
+    pub path: String,
+    /// The trust policy, which controls who may assume the role.
+    pub trust_policy: Option<Policy>,
+    /// The policies embedded in the role.
+    pub inline_policies: Vec<InlinePolicy>,
+    /// The ARNs of the managed policies attached to the role.
+    pub attached_policies: Vec<String>,
+    /// The ARN of the managed policy used as the role's permissions boundary, if any.
+    pub permissions_boundary: Option<String>,
+}
+
+///
+/// A managed policy and the document of its default version.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManagedPolicyDetail {
+    /// The name of the policy.
+    pub name: String,
+    /// The ARN of the policy.
+    pub arn: String,
+    /// The path of the policy.
+    pub path: String,
+    /// The document of the default version, if it was included.
+    pub document: Option<Policy>,
+}
+
+///
+/// Identifies where an identity policy of a principal was found.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PolicySource {
+    /// A policy embedded in the user, group, or role with the ARN `identity`.
+    Inline {
+        /// The ARN of the user, group, or role.
+        identity: String,
+        /// The name of the policy.
+        name: String,
+    },
+    /// The managed policy with this ARN, attached to the principal or one of its groups.
+    Managed(String),
+}
+
+///
+/// A policy embedded in a user, group, or role.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct InlinePolicy {
+    /// The name of the policy, unique within the identity.
+    pub name: String,
+    /// The policy document.
+    pub document: Policy,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Read the output of `GetAccountAuthorizationDetails` from any implementation of
+/// `std::io::Read`. Lists missing from the output are treated as empty.
+///
+pub fn read_authorization_details<R>(reader: R) -> Result<AccountAuthorizationDetails, IamError>
+where
+    R: Read + Sized,
+{
+    let raw: RawDetails = serde_json::from_reader(reader)?;
+    Ok(AccountAuthorizationDetails {
+        users: raw
+            .user_detail_list
+            .into_iter()
+            .map(|user| {
+                Ok(UserDetail {
+                    name: user.user_name,
+                    arn: user.arn,
+                    path: user.path,
+                    groups: user.group_list,
+                    inline_policies: inline_policies(user.user_policy_list)?,
+                    attached_policies: attached_policies(user.attached_managed_policies),
+                    permissions_boundary: user
+                        .permissions_boundary
+                        .map(|boundary| boundary.permissions_boundary_arn),
+                })
+            })
+            .collect::<Result<_, IamError>>()?,
+        groups: raw
+            .group_detail_list
+            .into_iter()
+            .map(|group| {
+                Ok(GroupDetail {
+                    name: group.group_name,
+                    arn: group.arn,
+                    path: group.path,
+                    inline_policies: inline_policies(group.group_policy_list)?,
+                    attached_policies: attached_policies(group.attached_managed_policies),
+                })
+            })
+            .collect::<Result<_, IamError>>()?,
+        roles: raw
+            .role_detail_list
+            .into_iter()
+            .map(|role| {
+                Ok(RoleDetail {
+                    name: role.role_name,
+                    arn: role.arn,
+                    path: role.path,
+                    trust_policy: role
+                        .assume_role_policy_document
+                        .as_ref()
+                        .map(policy_document)
+                        .transpose()?,
+                    inline_policies: inline_policies(role.role_policy_list)?,
+                    attached_policies: attached_policies(role.attached_managed_policies),
+                    permissions_boundary: role
+                        .permissions_boundary
+                        .map(|boundary| boundary.permissions_boundary_arn),
+                })
+            })
+            .collect::<Result<_, IamError>>()?,
+        policies: raw
+            .policies
+            .into_iter()
+            .map(|policy| {
+                Ok(ManagedPolicyDetail {
+                    name: policy.policy_name,
+                    arn: policy.arn,
+                    path: policy.path,
+                    document: policy
+                        .policy_version_list
+                        .iter()
+                        .find(|version| version.is_default_version)
+                        .map(|version| policy_document(&version.document))
+                        .transpose()?,
+                })
+            })
+            .collect::<Result<_, IamError>>()?,
+    })
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl AccountAuthorizationDetails {
+    /// The ARNs of the principals of the account, users and then roles.
+    pub fn principal_arns(&self) -> impl Iterator<Item = &str> {
+        self.users
+            .iter()
+            .map(|user| user.arn.as_str())
+            .chain(self.roles.iter().map(|role| role.arn.as_str()))
+    }
+
+    /// Returns the user with the ARN `arn`, if any.
+    pub fn user(&self, arn: &str) -> Option<&UserDetail> {
+        self.users.iter().find(|user| user.arn == arn)
+    }
+
+    /// Returns the group named `name`, if any.
+    pub fn group(&self, name: &str) -> Option<&GroupDetail> {
+        self.groups.iter().find(|group| group.name == name)
+    }
+
+    /// Returns the role with the ARN `arn`, if any.
+    pub fn role(&self, arn: &str) -> Option<&RoleDetail> {
+        self.roles.iter().find(|role| role.arn == arn)
+    }
+
+    /// Returns the document of the managed policy with the ARN `arn`, if it was included.
+    pub fn managed_policy(&self, arn: &str) -> Option<&Policy> {
+        self.policies
+            .iter()
+            .find(|policy| policy.arn == arn)
+            .and_then(|policy| policy.document.as_ref())
+    }
+
+    ///
+    /// Returns the identity policies that apply to the user or role with the ARN
+    /// `principal_arn`: its inline policies and attached managed policies and, for a user,
+    /// those of each of its groups. This is empty if there is no such principal.
+    ///
+    pub fn identity_policies(&self, principal_arn: &str) -> Vec<&Policy> {
+        self.identity_policy_sources(principal_arn)
+            .into_iter()
+            .map(|(_, policy)| policy)
+            .collect()
+    }
+
+    ///
+    /// Returns the same policies as `identity_policies`, each with the source it was found in.
+    ///
+    pub fn identity_policy_sources(&self, principal_arn: &str) -> Vec<(PolicySource, &Policy)> {
+        let mut policies: Vec<(PolicySource, &Policy)> = Default::default();
+        if let Some(user) = self.user(principal_arn) {
+            self.add_policies(
+                &mut policies,
+                &user.arn,
+                &user.inline_policies,
+                &user.attached_policies,
+            );
+            for group in user.groups.iter().filter_map(|name| self.group(name)) {
+                self.add_policies(
+                    &mut policies,
+                    &group.arn,
+                    &group.inline_policies,
+                    &group.attached_policies,
+                );
+            }
+        } else if let Some(role) = self.role(principal_arn) {
+            self.add_policies(
+                &mut policies,
+                &role.arn,
+                &role.inline_policies,
+                &role.attached_policies,
+            );
+        }
+        policies
+    }
+
+    /// Returns the permissions boundary of the user or role with the ARN `principal_arn`, if any.
+    pub fn permissions_boundary(&self, principal_arn: &str) -> Option<&Policy> {
+        let boundary = match self.user(principal_arn) {
+            Some(user) => user.permissions_boundary.as_ref(),
+            None => self
+                .role(principal_arn)
+                .and_then(|role| role.permissions_boundary.as_ref()),
+        }?;
+        self.managed_policy(boundary)
+    }
+
+    fn add_policies<'a>(
+        &'a self,
+        policies: &mut Vec<(PolicySource, &'a Policy)>,
+        identity: &str,
+        inline: &'a [InlinePolicy],
+        attached: &[String],
+    ) {
+        policies.extend(inline.iter().map(|policy| {
+            (
+                PolicySource::Inline {
+                    identity: identity.to_string(),
+                    name: policy.name.clone(),
+                },
+                &policy.document,
+            )
+        }));
+        policies.extend(attached.iter().filter_map(|arn| {
+            self.managed_policy(arn)
+                .map(|policy| (PolicySource::Managed(arn.clone()), policy))
+        }));
+    }
+}
+
+impl Display for PolicySource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolicySource::Inline { identity, name } => write!(f, "{}#{}", identity, name),
+            PolicySource::Managed(arn) => write!(f, "{}", arn),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawDetails {
+    #[serde(default)]
+    user_detail_list: Vec<RawUser>,
+    #[serde(default)]
+    group_detail_list: Vec<RawGroup>,
+    #[serde(default)]
+    role_detail_list: Vec<RawRole>,
+    #[serde(default)]
+    policies: Vec<RawManagedPolicy>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawUser {
+    user_name: String,
+    arn: String,
+    #[serde(default)]
+    path: String,
+    #[serde(default)]
+    group_list: Vec<String>,
+    #[serde(default)]
+    user_policy_list: Vec<RawInlinePolicy>,
+    #[serde(default)]
+    attached_managed_policies: Vec<RawAttachedPolicy>,
+    permissions_boundary: Option<RawPermissionsBoundary>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawGroup {
+    group_name: String,
+    arn: String,
+    #[serde(default)]
+    path: String,
+    #[serde(default)]
+    group_policy_list: Vec<RawInlinePolicy>,
+    #[serde(default)]
+    attached_managed_policies: Vec<RawAttachedPolicy>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawRole {
+    role_name: String,
+    arn: String,
+    #[serde(default)]
+    path: String,
+    assume_role_policy_document: Option<Value>,
+    #[serde(default)]
+    role_policy_list: Vec<RawInlinePolicy>,
+    #[serde(default)]
+    attached_managed_policies: Vec<RawAttachedPolicy>,
+    permissions_boundary: Option<RawPermissionsBoundary>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawManagedPolicy {
+    policy_name: String,
+    arn: String,
+    #[serde(default)]
+    path: String,
+    #[serde(default)]
+    policy_version_list: Vec<RawPolicyVersion>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawPolicyVersion {
+    document: Value,
+    #[serde(default)]
+    is_default_version: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawInlinePolicy {
+    policy_name: String,
+    policy_document: Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawAttachedPolicy {
+    policy_arn: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawPermissionsBoundary {
+    permissions_boundary_arn: String,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn inline_policies(policies: Vec<RawInlinePolicy>) -> Result<Vec<InlinePolicy>, IamError> {
+    policies
+        .into_iter()
+        .map(|policy| {
+            Ok(InlinePolicy {
+                document: policy_document(&policy.policy_document)?,
+                name: policy.policy_name,
+            })
+        })
+        .collect()
+}
+
+fn attached_policies(policies: Vec<RawAttachedPolicy>) -> Vec<String> {
+    policies
+        .into_iter()
+        .map(|policy| policy.policy_arn)
+        .collect()
+}
+
+///
+/// The API returns documents URL-encoded, the CLI decodes them into JSON objects.
+///
+fn policy_document(value: &Value) -> Result<Policy, IamError> {
+    match value {
+        Value::String(s) => Ok(Policy::from_str(&url_decode(s))?),
+        _ => Ok(Policy::from_json(value)?),
+    }
+}
+
+fn url_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => match s
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                Some(byte) => {
+                    decoded.push(byte);
+                    i += 3;
+                    continue;
+                }
+                None => decoded.push(b'%'),
+            },
+            b'+' => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
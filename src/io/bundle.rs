@@ -0,0 +1,478 @@
+/*!
+Provides the `.iampack` bundle format, which ships a suite of policies as a single artifact
+that is verified as a whole when it is read. Requires feature `bundle`.
+
+A bundle is a tar archive containing a manifest, `manifest.json`, and the canonical form of
+each policy, see `io::to_canonical_string`, as `policies/<name>`. The manifest lists each
+policy with the SHA-256 fingerprint of its canonical form and, optionally, a detached
+signature, see the [`signing`](../../signing/index.html) module.
+
+```json
+{
+  "format": "iampack",
+  "version": 1,
+  "policies": [
+    {
+      "name": "read-only.json",
+      "fingerprint": "sha256:5d41402abc4b2a76b9719d911017c592...",
+      "signature": "Pz8/Pz8/Pz8..."
+    }
+  ]
+}
+```
+
+When an encryption key is provided the archive is encrypted at rest with AES-256-GCM; the
+file then starts with the marker `IAMPACK-AES256GCM`, followed by a random nonce and the
+encrypted archive, and it cannot be read without the same key. When reading with a key a
+bundle that is not encrypted is rejected, so that an encrypted bundle cannot be replaced by an
+unencrypted one.
+
+A bundle is only returned by [`read`](fn.read.html) if its manifest is well formed, every
+policy it lists is present, and every fingerprint matches; with the feature `signing`, and
+trusted keys in the [`BundleOptions`](struct.BundleOptions.html), every policy must also have
+a signature made by one of those keys.
+
+# Example
+
+```rust
+use aws_iam::io::bundle::{read_from_reader, write_to_writer, Bundle, BundleOptions};
+use aws_iam::io::read_from_string;
+
+let policy = read_from_string(
+    r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject","Resource":"*"}]}"#,
+)
+.unwrap();
+let bundle = Bundle::default()
+    .with_policy("read-only.json", policy)
+    .unwrap();
+let options = BundleOptions::default().with_encryption_key([42; 32]);
+
+let mut bytes: Vec<u8> = Vec::new();
+write_to_writer(&mut bytes, &bundle, &options).unwrap();
+
+assert!(read_from_reader(bytes.as_slice(), &BundleOptions::default()).is_err());
+let read = read_from_reader(bytes.as_slice(), &options).unwrap();
+assert_eq!(read, bundle);
+```
+*/
+
+use crate::error::{BundleError, IamError};
+use crate::io::{read_from_string, to_canonical_string};
+use crate::model::Policy;
+#[cfg(feature = "signing")]
+use crate::signing::{
+    sign_policy, signature_from_str, signature_to_string, verify_policy, SigningKey, VerifyingKey,
+};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::Path;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The options used to write and read a bundle.
+///
+#[derive(Clone, Default, PartialEq)]
+pub struct BundleOptions {
+    /// If set, bundles are written encrypted with, and encrypted bundles read with, this key.
+    pub encryption_key: Option<[u8; 32]>,
+    /// If not empty, each policy in a bundle read must be signed by one of these keys.
+    #[cfg(feature = "signing")]
+    pub trusted_keys: Vec<VerifyingKey>,
+}
+
+///
+/// A suite of named policies; the names are unique and kept in sorted order.
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Bundle {
+    entries: Vec<BundleEntry>,
+}
+
+///
+/// A policy in a bundle, with its fingerprint and, if it has one, its signature.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct BundleEntry {
+    name: String,
+    policy: Policy,
+    fingerprint: String,
+    signature: Option<String>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+/// The extension of bundle files.
+pub const BUNDLE_FILE_EXTENSION: &str = "iampack";
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Write `bundle` to a new file at `path`, encrypting it if `options` has an encryption key.
+///
+pub fn write(path: &Path, bundle: &Bundle, options: &BundleOptions) -> Result<(), IamError> {
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    write_to_writer(file, bundle, options)
+}
+
+///
+/// Write `bundle` to `writer`, encrypting it if `options` has an encryption key.
+///
+pub fn write_to_writer<W>(
+    writer: W,
+    bundle: &Bundle,
+    options: &BundleOptions,
+) -> Result<(), IamError>
+where
+    W: Write + Sized,
+{
+    let mut writer = writer;
+    let archive = archive(bundle)?;
+    match &options.encryption_key {
+        None => writer.write_all(&archive)?,
+        Some(key) => {
+            let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+            let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+            let encrypted = cipher
+                .encrypt(&nonce, archive.as_slice())
+                .map_err(|_| BundleError::Encryption)?;
+            writer.write_all(ENCRYPTED_MARKER)?;
+            writer.write_all(&nonce)?;
+            writer.write_all(&encrypted)?;
+        }
+    }
+    Ok(())
+}
+
+///
+/// Read, and verify, the bundle in the file at `path`.
+///
+pub fn read(path: &Path, options: &BundleOptions) -> Result<Bundle, IamError> {
+    read_from_reader(File::open(path)?, options)
+}
+
+///
+/// Read, and verify, the bundle from `reader`; see the module documentation for the checks
+/// made.
+///
+pub fn read_from_reader<R>(reader: R, options: &BundleOptions) -> Result<Bundle, IamError>
+where
+    R: Read + Sized,
+{
+    let mut reader = reader;
+    let mut bytes = Vec::new();
+    let _ = reader.read_to_end(&mut bytes)?;
+    let archive = if let Some(encrypted) = bytes.strip_prefix(ENCRYPTED_MARKER) {
+        let key = options
+            .encryption_key
+            .as_ref()
+            .ok_or(BundleError::MissingKey)?;
+        if encrypted.len() < NONCE_LENGTH {
+            return Err(BundleError::Decryption.into());
+        }
+        let (nonce, encrypted) = encrypted.split_at(NONCE_LENGTH);
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+            .decrypt(Nonce::from_slice(nonce), encrypted)
+            .map_err(|_| BundleError::Decryption)?
+    } else if options.encryption_key.is_some() {
+        return Err(BundleError::NotEncrypted.into());
+    } else {
+        bytes
+    };
+    let bundle = unarchive(&archive)?;
+    #[cfg(feature = "signing")]
+    bundle.verify(&options.trusted_keys)?;
+    Ok(bundle)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    format: String,
+    version: u32,
+    policies: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ManifestEntry {
+    name: String,
+    fingerprint: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const FORMAT_NAME: &str = "iampack";
+
+const FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+const POLICY_DIRECTORY_NAME: &str = "policies/";
+
+const FINGERPRINT_PREFIX: &str = "sha256:";
+
+const ENCRYPTED_MARKER: &[u8] = b"IAMPACK-AES256GCM";
+
+const NONCE_LENGTH: usize = 12;
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Debug for BundleOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("BundleOptions");
+        let _ = debug.field(
+            "encryption_key",
+            &self.encryption_key.as_ref().map(|_| "<redacted>"),
+        );
+        #[cfg(feature = "signing")]
+        let _ = debug.field("trusted_keys", &self.trusted_keys);
+        debug.finish()
+    }
+}
+
+impl BundleOptions {
+    pub fn with_encryption_key(self, encryption_key: [u8; 32]) -> Self {
+        Self {
+            encryption_key: Some(encryption_key),
+            ..self
+        }
+    }
+
+    #[cfg(feature = "signing")]
+    pub fn with_trusted_keys(self, trusted_keys: Vec<VerifyingKey>) -> Self {
+        Self {
+            trusted_keys,
+            ..self
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Bundle {
+    ///
+    /// Add `policy` to the bundle as `name`, replacing any policy with the same name.
+    /// Returns an error if the name is not a plain file name.
+    ///
+    pub fn with_policy(self, name: &str, policy: Policy) -> Result<Self, IamError> {
+        let mut self_mut = self;
+        let entry = BundleEntry::new(name, policy)?;
+        self_mut.insert(entry);
+        Ok(self_mut)
+    }
+
+    ///
+    /// Sign every policy in the bundle with `key`, replacing any existing signatures.
+    ///
+    #[cfg(feature = "signing")]
+    pub fn sign(&mut self, key: &SigningKey) -> Result<(), IamError> {
+        for entry in &mut self.entries {
+            entry.signature = Some(signature_to_string(&sign_policy(key, &entry.policy)?));
+        }
+        Ok(())
+    }
+
+    ///
+    /// Verify that every policy in the bundle is signed by one of the `trusted` keys; if there
+    /// are no trusted keys no signatures are required.
+    ///
+    #[cfg(feature = "signing")]
+    pub fn verify(&self, trusted: &[VerifyingKey]) -> Result<(), IamError> {
+        if trusted.is_empty() {
+            return Ok(());
+        }
+        for entry in &self.entries {
+            let signature = entry
+                .signature
+                .as_ref()
+                .ok_or_else(|| BundleError::Unsigned(entry.name.clone()))?;
+            verify_policy(&entry.policy, &signature_from_str(signature)?, trusted)?;
+        }
+        Ok(())
+    }
+
+    /// The policies in the bundle, in the order of their names.
+    pub fn entries(&self) -> impl Iterator<Item = &BundleEntry> {
+        self.entries.iter()
+    }
+
+    /// Returns the policy named `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&Policy> {
+        self.entries
+            .iter()
+            .find(|entry| entry.name == name)
+            .map(|entry| &entry.policy)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn insert(&mut self, entry: BundleEntry) {
+        match self
+            .entries
+            .binary_search_by(|existing| existing.name.cmp(&entry.name))
+        {
+            Ok(index) => self.entries[index] = entry,
+            Err(index) => self.entries.insert(index, entry),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl BundleEntry {
+    fn new(name: &str, policy: Policy) -> Result<Self, IamError> {
+        if name.is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." {
+            return Err(BundleError::InvalidName(name.to_string()).into());
+        }
+        let fingerprint = fingerprint(&to_canonical_string(&policy)?);
+        Ok(Self {
+            name: name.to_string(),
+            policy,
+            fingerprint,
+            signature: None,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    /// The SHA-256 hash of the policy's canonical form, as `sha256:` and 64 hex digits.
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    /// The base64 form of the policy's signature, if it has been signed.
+    pub fn signature(&self) -> Option<&str> {
+        self.signature.as_deref()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn fingerprint(canonical: &str) -> String {
+    format!(
+        "{}{}",
+        FINGERPRINT_PREFIX,
+        hex::encode(Sha256::digest(canonical.as_bytes()))
+    )
+}
+
+///
+/// Entries are written with fixed modes and times, so the same bundle is always written as
+/// the same archive.
+///
+fn archive(bundle: &Bundle) -> Result<Vec<u8>, IamError> {
+    let mut policies = Vec::with_capacity(bundle.len());
+    let mut manifest = Manifest {
+        format: FORMAT_NAME.to_string(),
+        version: FORMAT_VERSION,
+        policies: Vec::with_capacity(bundle.len()),
+    };
+    for entry in bundle.entries() {
+        policies.push((
+            format!("{}{}", POLICY_DIRECTORY_NAME, entry.name),
+            to_canonical_string(&entry.policy)?,
+        ));
+        manifest.policies.push(ManifestEntry {
+            name: entry.name.clone(),
+            fingerprint: entry.fingerprint.clone(),
+            signature: entry.signature.clone(),
+        });
+    }
+    let mut builder = tar::Builder::new(Vec::new());
+    append(
+        &mut builder,
+        MANIFEST_FILE_NAME,
+        serde_json::to_string_pretty(&manifest)?.as_bytes(),
+    )?;
+    for (path, canonical) in policies {
+        append(&mut builder, &path, canonical.as_bytes())?;
+    }
+    Ok(builder.into_inner()?)
+}
+
+fn append(builder: &mut tar::Builder<Vec<u8>>, path: &str, data: &[u8]) -> Result<(), IamError> {
+    let mut header = tar::Header::new_ustar();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(0);
+    builder.append_data(&mut header, path, data)?;
+    Ok(())
+}
+
+fn unarchive(archive: &[u8]) -> Result<Bundle, IamError> {
+    let mut files: HashMap<String, String> = Default::default();
+    for entry in tar::Archive::new(archive).entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.to_string_lossy().to_string();
+        let mut content = String::new();
+        let _ = entry.read_to_string(&mut content)?;
+        let _ = files.insert(path, content);
+    }
+
+    let manifest: Manifest = serde_json::from_str(
+        files
+            .get(MANIFEST_FILE_NAME)
+            .ok_or_else(|| BundleError::MissingFile(MANIFEST_FILE_NAME.to_string()))?,
+    )?;
+    if manifest.format != FORMAT_NAME || manifest.version != FORMAT_VERSION {
+        return Err(BundleError::UnsupportedFormat(format!(
+            "{} version {}",
+            manifest.format, manifest.version
+        ))
+        .into());
+    }
+
+    let mut bundle = Bundle::default();
+    for listed in manifest.policies {
+        let path = format!("{}{}", POLICY_DIRECTORY_NAME, listed.name);
+        let canonical = files
+            .get(&path)
+            .ok_or_else(|| BundleError::MissingFile(path.clone()))?;
+        if fingerprint(canonical) != listed.fingerprint {
+            return Err(BundleError::FingerprintMismatch(listed.name).into());
+        }
+        let mut entry = BundleEntry::new(&listed.name, read_from_string(canonical)?)?;
+        entry.signature = listed.signature;
+        bundle.insert(entry);
+    }
+    Ok(bundle)
+}
@@ -0,0 +1,534 @@
+/*!
+Provides a graph of the authorization data of an account, as read by
+[`read_authorization_details`](fn.read_authorization_details.html), for graph databases and
+analytics libraries such as Neo4j or NetworkX.
+
+The nodes of the graph are the users, groups, and roles of the account, their policies, the
+statements of those policies, and the actions, resources, and principals the statements name.
+Each edge has a kind, as follows.
+
+| Kind                   | From                  | To        |
+|------------------------|-----------------------|-----------|
+| `member_of`            | user                  | group     |
+| `attached`             | user, group, or role  | policy    |
+| `inline`               | user, group, or role  | policy    |
+| `permissions_boundary` | user or role          | policy    |
+| `trust_policy`         | role                  | policy    |
+| `contains`             | policy                | statement |
+| `allows`               | statement             | action    |
+| `denies`               | statement             | action    |
+| `applies_to`           | statement             | resource  |
+| `principal`            | statement             | principal |
+
+An edge from a `NotAction`, `NotResource`, or `NotPrincipal` element is marked as `negated`.
+The graph is written as JSON, with [`write_graph_json`](fn.write_graph_json.html), or as
+[GraphML](http://graphml.graphdrawing.org/), with [`write_graphml`](fn.write_graphml.html), with
+the kind, label, and negation as data attributes.
+
+# Example
+
+```rust
+use aws_iam::io::{read_authorization_details, EdgeKind, PermissionGraph};
+
+let details = read_authorization_details(
+    r#"{
+    "RoleDetailList": [{
+        "RoleName": "reader",
+        "Arn": "arn:aws:iam::123456789012:role/reader",
+        "RolePolicyList": [{
+            "PolicyName": "read",
+            "PolicyDocument": {"Statement": [
+                {"Effect": "Allow", "Action": "s3:GetObject", "Resource": "*"}
+            ]}
+        }]
+    }]
+}"#
+    .as_bytes(),
+)
+.unwrap();
+let graph = PermissionGraph::from_details(&details);
+
+assert!(graph
+    .edges
+    .iter()
+    .any(|edge| edge.kind == EdgeKind::Allows && edge.target == "action:s3:GetObject"));
+```
+*/
+
+use crate::error::IamError;
+use crate::io::{AccountAuthorizationDetails, InlinePolicy, PolicySource};
+use crate::model::{Action, Effect, HostName, OrAny, Policy, Principal, PrincipalMap, Resource};
+use crate::syntax::POLICY_WILDCARD_VALUE;
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The kind of entity a [`GraphNode`](struct.GraphNode.html) represents.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    /// An IAM user.
+    User,
+    /// An IAM group.
+    Group,
+    /// An IAM role.
+    Role,
+    /// A managed, inline, or trust policy.
+    Policy,
+    /// A statement of a policy.
+    Statement,
+    /// An action, or action pattern, named by a statement.
+    Action,
+    /// A resource, or resource pattern, named by a statement.
+    Resource,
+    /// A principal named by a statement, for example in a trust policy.
+    Principal,
+}
+
+///
+/// The kind of relationship a [`GraphEdge`](struct.GraphEdge.html) represents, see the module
+/// documentation.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    /// A user is a member of a group.
+    MemberOf,
+    /// A managed policy is attached to a user, group, or role.
+    Attached,
+    /// A policy is embedded in a user, group, or role.
+    Inline,
+    /// A managed policy is the permissions boundary of a user or role.
+    PermissionsBoundary,
+    /// A policy is the trust policy of a role.
+    TrustPolicy,
+    /// A policy contains a statement.
+    Contains,
+    /// An allow statement names an action.
+    Allows,
+    /// A deny statement names an action.
+    Denies,
+    /// A statement names a resource.
+    AppliesTo,
+    /// A statement names a principal.
+    Principal,
+}
+
+///
+/// A node of a [`PermissionGraph`](struct.PermissionGraph.html).
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphNode {
+    /// The unique identifier of the node; the ARN of users, groups, roles, and managed
+    /// policies, and otherwise derived from the entity and its kind.
+    pub id: String,
+    /// The kind of entity.
+    pub kind: NodeKind,
+    /// A readable name for the entity.
+    pub label: String,
+}
+
+///
+/// An edge of a [`PermissionGraph`](struct.PermissionGraph.html).
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphEdge {
+    /// The identifier of the source node.
+    pub source: String,
+    /// The identifier of the target node.
+    pub target: String,
+    /// The kind of relationship.
+    pub kind: EdgeKind,
+    /// `true` if the edge is from a `NotAction`, `NotResource`, or `NotPrincipal` element.
+    pub negated: bool,
+}
+
+///
+/// The graph of the users, groups, roles, policies, statements, actions, resources, and
+/// principals of an account.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PermissionGraph {
+    /// The nodes, each with a unique identifier.
+    pub nodes: Vec<GraphNode>,
+    /// The edges between nodes.
+    pub edges: Vec<GraphEdge>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Write `graph` as JSON, an object with the arrays `nodes` and `edges`, to any implementation
+/// of `std::io::Write`.
+///
+pub fn write_graph_json<W>(writer: W, graph: &PermissionGraph) -> Result<(), IamError>
+where
+    W: Write,
+{
+    serde_json::to_writer_pretty(writer, graph)?;
+    Ok(())
+}
+
+///
+/// Write `graph` as GraphML to any implementation of `std::io::Write`.
+///
+pub fn write_graphml<W>(writer: W, graph: &PermissionGraph) -> Result<(), IamError>
+where
+    W: Write,
+{
+    let mut writer = writer;
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    writeln!(
+        writer,
+        r#"  <key id="kind" for="all" attr.name="kind" attr.type="string"/>"#
+    )?;
+    writeln!(
+        writer,
+        r#"  <key id="label" for="node" attr.name="label" attr.type="string"/>"#
+    )?;
+    writeln!(
+        writer,
+        r#"  <key id="negated" for="edge" attr.name="negated" attr.type="boolean"/>"#
+    )?;
+    writeln!(writer, r#"  <graph id="account" edgedefault="directed">"#)?;
+    for node in &graph.nodes {
+        writeln!(writer, r#"    <node id="{}">"#, escape_xml(&node.id))?;
+        writeln!(writer, r#"      <data key="kind">{}</data>"#, node.kind)?;
+        writeln!(
+            writer,
+            r#"      <data key="label">{}</data>"#,
+            escape_xml(&node.label)
+        )?;
+        writeln!(writer, "    </node>")?;
+    }
+    for edge in &graph.edges {
+        writeln!(
+            writer,
+            r#"    <edge source="{}" target="{}">"#,
+            escape_xml(&edge.source),
+            escape_xml(&edge.target)
+        )?;
+        writeln!(writer, r#"      <data key="kind">{}</data>"#, edge.kind)?;
+        writeln!(
+            writer,
+            r#"      <data key="negated">{}</data>"#,
+            edge.negated
+        )?;
+        writeln!(writer, "    </edge>")?;
+    }
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")?;
+    Ok(())
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for NodeKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::User => "user",
+                Self::Group => "group",
+                Self::Role => "role",
+                Self::Policy => "policy",
+                Self::Statement => "statement",
+                Self::Action => "action",
+                Self::Resource => "resource",
+                Self::Principal => "principal",
+            }
+        )
+    }
+}
+
+impl Display for EdgeKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::MemberOf => "member_of",
+                Self::Attached => "attached",
+                Self::Inline => "inline",
+                Self::PermissionsBoundary => "permissions_boundary",
+                Self::TrustPolicy => "trust_policy",
+                Self::Contains => "contains",
+                Self::Allows => "allows",
+                Self::Denies => "denies",
+                Self::AppliesTo => "applies_to",
+                Self::Principal => "principal",
+            }
+        )
+    }
+}
+
+impl PermissionGraph {
+    ///
+    /// Create the graph of the users, groups, roles, and managed policies in `details`. Each
+    /// entity is a single node, however many times it is named; a managed policy attached to a
+    /// principal but not included in `details` is a node with no statements.
+    ///
+    pub fn from_details(details: &AccountAuthorizationDetails) -> Self {
+        let mut builder = GraphBuilder::default();
+        for policy in &details.policies {
+            builder.policy(&policy.arn, &policy.name, policy.document.as_ref());
+        }
+        for group in &details.groups {
+            builder.node(&group.arn, NodeKind::Group, &group.name);
+            builder.identity_policies(
+                details,
+                &group.arn,
+                &group.inline_policies,
+                &group.attached_policies,
+            );
+        }
+        for user in &details.users {
+            builder.node(&user.arn, NodeKind::User, &user.name);
+            builder.identity_policies(
+                details,
+                &user.arn,
+                &user.inline_policies,
+                &user.attached_policies,
+            );
+            for group in user.groups.iter().filter_map(|name| details.group(name)) {
+                builder.edge(&user.arn, &group.arn, EdgeKind::MemberOf, false);
+            }
+            if let Some(boundary) = &user.permissions_boundary {
+                builder.managed_policy(details, boundary);
+                builder.edge(&user.arn, boundary, EdgeKind::PermissionsBoundary, false);
+            }
+        }
+        for role in &details.roles {
+            builder.node(&role.arn, NodeKind::Role, &role.name);
+            builder.identity_policies(
+                details,
+                &role.arn,
+                &role.inline_policies,
+                &role.attached_policies,
+            );
+            if let Some(boundary) = &role.permissions_boundary {
+                builder.managed_policy(details, boundary);
+                builder.edge(&role.arn, boundary, EdgeKind::PermissionsBoundary, false);
+            }
+            if let Some(trust_policy) = &role.trust_policy {
+                let id = format!("{}#{}", role.arn, TRUST_POLICY_NAME);
+                builder.policy(&id, TRUST_POLICY_NAME, Some(trust_policy));
+                builder.edge(&role.arn, &id, EdgeKind::TrustPolicy, false);
+            }
+        }
+        builder.graph()
+    }
+
+    /// Returns the node with the identifier `id`, if any.
+    pub fn node(&self, id: &str) -> Option<&GraphNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+}
+
+impl GraphBuilder {
+    fn graph(self) -> PermissionGraph {
+        PermissionGraph {
+            nodes: self.nodes.into_values().collect(),
+            edges: self.edges,
+        }
+    }
+
+    fn node(&mut self, id: &str, kind: NodeKind, label: &str) {
+        if !self.nodes.contains_key(id) {
+            let _ = self.nodes.insert(
+                id.to_string(),
+                GraphNode {
+                    id: id.to_string(),
+                    kind,
+                    label: label.to_string(),
+                },
+            );
+        }
+    }
+
+    fn edge(&mut self, source: &str, target: &str, kind: EdgeKind, negated: bool) {
+        self.edges.push(GraphEdge {
+            source: source.to_string(),
+            target: target.to_string(),
+            kind,
+            negated,
+        });
+    }
+
+    fn identity_policies(
+        &mut self,
+        details: &AccountAuthorizationDetails,
+        identity: &str,
+        inline: &[InlinePolicy],
+        attached: &[String],
+    ) {
+        for policy in inline {
+            let id = PolicySource::Inline {
+                identity: identity.to_string(),
+                name: policy.name.clone(),
+            }
+            .to_string();
+            self.policy(&id, &policy.name, Some(&policy.document));
+            self.edge(identity, &id, EdgeKind::Inline, false);
+        }
+        for arn in attached {
+            self.managed_policy(details, arn);
+            self.edge(identity, arn, EdgeKind::Attached, false);
+        }
+    }
+
+    fn managed_policy(&mut self, details: &AccountAuthorizationDetails, arn: &str) {
+        if !self.nodes.contains_key(arn) {
+            let name = arn.rsplit('/').next().unwrap_or(arn);
+            self.policy(arn, name, details.managed_policy(arn));
+        }
+    }
+
+    fn policy(&mut self, id: &str, name: &str, document: Option<&Policy>) {
+        if self.nodes.contains_key(id) {
+            return;
+        }
+        self.node(id, NodeKind::Policy, name);
+        for (index, statement) in document.iter().flat_map(|p| p.statement.iter()).enumerate() {
+            let statement_id = format!("{}/{}", id, index);
+            let label = statement
+                .sid
+                .as_ref()
+                .map(|sid| sid.to_string())
+                .unwrap_or_else(|| index.to_string());
+            self.node(&statement_id, NodeKind::Statement, &label);
+            self.edge(id, &statement_id, EdgeKind::Contains, false);
+
+            let kind = match statement.effect {
+                Effect::Allow => EdgeKind::Allows,
+                Effect::Deny => EdgeKind::Denies,
+            };
+            let (negated, actions) = match &statement.action {
+                Action::Action(actions) => (false, actions),
+                Action::NotAction(actions) => (true, actions),
+            };
+            let actions: Vec<String> = match actions {
+                OrAny::Any => vec![POLICY_WILDCARD_VALUE.to_string()],
+                OrAny::Some(actions) => actions.iter().map(ToString::to_string).collect(),
+            };
+            for action in actions {
+                self.target(&statement_id, NodeKind::Action, &action, kind, negated);
+            }
+
+            if let Some(resource) = &statement.resource {
+                let (negated, resources) = match resource {
+                    Resource::Resource(resources) => (false, resources),
+                    Resource::NotResource(resources) => (true, resources),
+                };
+                let resources: Vec<String> = match resources {
+                    OrAny::Any => vec![POLICY_WILDCARD_VALUE.to_string()],
+                    OrAny::Some(resources) => resources.iter().map(ToString::to_string).collect(),
+                };
+                for resource in resources {
+                    self.target(
+                        &statement_id,
+                        NodeKind::Resource,
+                        &resource,
+                        EdgeKind::AppliesTo,
+                        negated,
+                    );
+                }
+            }
+
+            if let Some(principal) = &statement.principal {
+                let (negated, principals) = match principal {
+                    Principal::Principal(principals) => (false, principals),
+                    Principal::NotPrincipal(principals) => (true, principals),
+                };
+                let principals: Vec<String> = match principals {
+                    OrAny::Any => vec![POLICY_WILDCARD_VALUE.to_string()],
+                    OrAny::Some(principals) => principal_values(principals),
+                };
+                for principal in principals {
+                    self.target(
+                        &statement_id,
+                        NodeKind::Principal,
+                        &principal,
+                        EdgeKind::Principal,
+                        negated,
+                    );
+                }
+            }
+        }
+    }
+
+    ///
+    /// Actions, resources, and principals are identified by their kind and value, as the same
+    /// value, such as `"*"`, may be any of them.
+    ///
+    fn target(
+        &mut self,
+        source: &str,
+        kind: NodeKind,
+        value: &str,
+        edge_kind: EdgeKind,
+        negated: bool,
+    ) {
+        let id = format!("{}:{}", kind, value);
+        self.node(&id, kind, value);
+        self.edge(source, &id, edge_kind, negated);
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug, Default)]
+struct GraphBuilder {
+    nodes: IndexMap<String, GraphNode>,
+    edges: Vec<GraphEdge>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const TRUST_POLICY_NAME: &str = "trust";
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn principal_values(principals: &PrincipalMap) -> Vec<String> {
+    principals
+        .aws_iter()
+        .map(ToString::to_string)
+        .chain(principals.federated_iter().map(ToString::to_string))
+        .chain(
+            principals
+                .service_iter()
+                .map(|service| HostName::from(service.clone()).to_string()),
+        )
+        .chain(principals.canonical_user_iter().map(ToString::to_string))
+        .collect()
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
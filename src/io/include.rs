@@ -0,0 +1,184 @@
+use crate::error::{IamError, IncludeError};
+use crate::model::Policy;
+use crate::syntax::IamValue;
+use serde_json::{Map, Value};
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The key, not part of the IAM policy grammar, whose value names the fragment, or fragments,
+/// to include; see `read_with_includes`.
+///
+pub const INCLUDE_KEY: &str = "x-include";
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Read a `Policy` document from the file at `path`, expanding the fragments it includes. Any
+/// JSON object may have an `x-include` key whose value is the path, or an array of paths, of
+/// fragment files; relative paths are resolved against the directory of the including file.
+///
+/// * An object in an array that has only the `x-include` key is replaced by the fragments; a
+///   fragment that is itself an array, such as a list of statements, is spliced in.
+/// * Otherwise each fragment must be an object, and is merged into the including object;
+///   values for the same key are merged if both are objects, appended if either is an array,
+///   and the including object's value is used for any other value.
+///
+/// Fragments may include other fragments; a fragment that includes itself, directly or
+/// through others, is reported as an error. The returned policy contains no `x-include` keys.
+///
+pub fn read_with_includes(path: &Path) -> Result<Policy, IamError> {
+    let mut including = Vec::new();
+    let value = read_fragment(path, &mut including)?;
+    Ok(Policy::from_json(&value)?)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// `including` is the stack of files currently being expanded, used to detect cycles.
+///
+fn read_fragment(path: &Path, including: &mut Vec<PathBuf>) -> Result<Value, IamError> {
+    let canonical = path.canonicalize().map_err(|source| IncludeError::Read {
+        path: path.to_path_buf(),
+        source: Box::new(source.into()),
+    })?;
+    if including.contains(&canonical) {
+        return Err(IncludeError::Cycle {
+            path: path.to_path_buf(),
+        }
+        .into());
+    }
+    let value: Value = read_to_string(&canonical)
+        .map_err(IamError::from)
+        .and_then(|s| serde_json::from_str(&s).map_err(IamError::from))
+        .map_err(|source| IncludeError::Read {
+            path: path.to_path_buf(),
+            source: Box::new(source),
+        })?;
+    let directory = canonical
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    including.push(canonical);
+    let expanded = expand(value, &directory, including);
+    let _ = including.pop();
+    expanded
+}
+
+fn expand(value: Value, directory: &Path, including: &mut Vec<PathBuf>) -> Result<Value, IamError> {
+    match value {
+        Value::Object(object) => {
+            let mut object = object;
+            let includes = object.remove(INCLUDE_KEY);
+            let mut expanded = Map::with_capacity(object.len());
+            for (key, value) in object {
+                let _ = expanded.insert(key, expand(value, directory, including)?);
+            }
+            match includes {
+                None => Ok(Value::Object(expanded)),
+                Some(includes) => {
+                    let mut merged = Map::new();
+                    for (path, fragment) in fragments(&includes, directory, including)? {
+                        match fragment {
+                            Value::Object(fragment) => merge(&mut merged, fragment),
+                            _ => return Err(IncludeError::NotAnObject { path }.into()),
+                        }
+                    }
+                    merge(&mut merged, expanded);
+                    Ok(Value::Object(merged))
+                }
+            }
+        }
+        Value::Array(values) => {
+            let mut expanded = Vec::with_capacity(values.len());
+            for value in values {
+                match value {
+                    Value::Object(object)
+                        if object.len() == 1 && object.contains_key(INCLUDE_KEY) =>
+                    {
+                        for (_, fragment) in fragments(&object[INCLUDE_KEY], directory, including)?
+                        {
+                            match fragment {
+                                Value::Array(values) => expanded.extend(values),
+                                value => expanded.push(value),
+                            }
+                        }
+                    }
+                    value => expanded.push(expand(value, directory, including)?),
+                }
+            }
+            Ok(Value::Array(expanded))
+        }
+        value => Ok(value),
+    }
+}
+
+///
+/// Read, and expand, the fragments named by the value of an `x-include` key, with their paths.
+///
+fn fragments(
+    includes: &Value,
+    directory: &Path,
+    including: &mut Vec<PathBuf>,
+) -> Result<Vec<(PathBuf, Value)>, IamError> {
+    let paths = match includes {
+        Value::String(path) => vec![path.as_str()],
+        Value::Array(paths) => paths
+            .iter()
+            .map(|path| path.as_str().ok_or_else(|| invalid_include(includes)))
+            .collect::<Result<Vec<&str>, IncludeError>>()?,
+        _ => return Err(invalid_include(includes).into()),
+    };
+    paths
+        .into_iter()
+        .map(|path| {
+            let path = directory.join(path);
+            read_fragment(&path, including).map(|fragment| (path, fragment))
+        })
+        .collect()
+}
+
+fn invalid_include(includes: &Value) -> IncludeError {
+    IncludeError::InvalidValue {
+        value: includes.to_string(),
+    }
+}
+
+///
+/// Merge `from` into `into`; objects are merged, arrays appended, and other values replaced.
+///
+fn merge(into: &mut Map<String, Value>, from: Map<String, Value>) {
+    for (key, value) in from {
+        let merged = match (into.remove(&key), value) {
+            (None, value) => value,
+            (Some(Value::Object(mut existing)), Value::Object(value)) => {
+                merge(&mut existing, value);
+                Value::Object(existing)
+            }
+            (Some(Value::Array(mut existing)), Value::Array(value)) => {
+                existing.extend(value);
+                Value::Array(existing)
+            }
+            (Some(Value::Array(mut existing)), value) => {
+                existing.push(value);
+                Value::Array(existing)
+            }
+            (Some(existing), Value::Array(value)) if !existing.is_object() => {
+                let mut values = vec![existing];
+                values.extend(value);
+                Value::Array(values)
+            }
+            (Some(_), value) => value,
+        };
+        let _ = into.insert(key, merged);
+    }
+}
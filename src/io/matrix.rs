@@ -0,0 +1,126 @@
+/*!
+Provides output of a [`PermissionsMatrix`](../analysis/struct.PermissionsMatrix.html), the
+access of the principals of an account to a set of actions, for spreadsheets and BI tools.
+
+The CSV form has a header row, `principal`, then `resource` if the matrix was computed for
+specific resources, then each action; each following row is a principal, or a principal and
+resource, with one of `allow`, `conditional`, or `deny` for each action. The JSON form is the
+serialized matrix.
+
+# Example
+
+```rust
+use aws_iam::analysis::permissions_matrix;
+use aws_iam::io::{read_authorization_details, write_matrix_csv};
+
+let details = read_authorization_details(
+    r#"{
+    "RoleDetailList": [{
+        "RoleName": "reader",
+        "Arn": "arn:aws:iam::123456789012:role/reader",
+        "RolePolicyList": [{
+            "PolicyName": "read",
+            "PolicyDocument": {"Statement": [
+                {"Effect": "Allow", "Action": "s3:Get*", "Resource": "*"}
+            ]}
+        }]
+    }]
+}"#
+    .as_bytes(),
+)
+.unwrap();
+let matrix = permissions_matrix(&details, &["s3:GetObject", "s3:PutObject"], &[]);
+
+let mut csv: Vec<u8> = Vec::new();
+write_matrix_csv(&mut csv, &matrix).unwrap();
+assert_eq!(
+    String::from_utf8(csv).unwrap(),
+    "principal,s3:GetObject,s3:PutObject\narn:aws:iam::123456789012:role/reader,allow,deny\n"
+);
+```
+*/
+
+use crate::analysis::{Access, PermissionsMatrix};
+use crate::error::IamError;
+use std::io::Write;
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Write `matrix` in CSV form, see the module documentation, to any implementation of
+/// `std::io::Write`.
+///
+pub fn write_matrix_csv<W>(writer: W, matrix: &PermissionsMatrix) -> Result<(), IamError>
+where
+    W: Write,
+{
+    let mut writer = writer;
+    let has_resources = matrix.rows.iter().any(|row| row.resource.is_some());
+    let mut header: Vec<&str> = vec![PRINCIPAL_COLUMN];
+    if has_resources {
+        header.push(RESOURCE_COLUMN);
+    }
+    header.extend(matrix.actions.iter().map(String::as_str));
+    write_csv_row(&mut writer, &header)?;
+    for row in &matrix.rows {
+        let mut fields: Vec<&str> = vec![row.principal.as_str()];
+        if has_resources {
+            fields.push(row.resource.as_deref().unwrap_or_default());
+        }
+        fields.extend(row.access.iter().map(|access| access_value(*access)));
+        write_csv_row(&mut writer, &fields)?;
+    }
+    Ok(())
+}
+
+///
+/// Write `matrix` in JSON form to any implementation of `std::io::Write`.
+///
+pub fn write_matrix_json<W>(writer: W, matrix: &PermissionsMatrix) -> Result<(), IamError>
+where
+    W: Write,
+{
+    serde_json::to_writer_pretty(writer, matrix)?;
+    Ok(())
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const PRINCIPAL_COLUMN: &str = "principal";
+
+const RESOURCE_COLUMN: &str = "resource";
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn access_value(access: Access) -> &'static str {
+    match access {
+        Access::Allow => "allow",
+        Access::Conditional => "conditional",
+        Access::Deny => "deny",
+    }
+}
+
+fn write_csv_row<W>(writer: &mut W, fields: &[&str]) -> Result<(), IamError>
+where
+    W: Write,
+{
+    let row = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+    writeln!(writer, "{}", row)?;
+    Ok(())
+}
@@ -4,7 +4,57 @@ Provides basic file read/write functions for policies.
 This module wraps basic read and write operations and the relevant Serde serialization and
 deserialization logic. Both read and write functions come in two forms, one which takes a
 file name in the form of a `PathBuf` and one which either takes an implementation of
-`std::io::Read` or `std::io::Write`.
+`std::io::Read` or `std::io::Write`. The file-based functions require the feature `fs`, which
+is enabled by default.
+
+Policies may be composed from fragment files with
+[`read_with_includes`](fn.read_with_includes.html), which expands the non-AWS `x-include` key,
+for example `"x-include": "fragments/mfa.json"`, wherever it appears and returns a pure IAM
+policy.
+
+With the feature `relaxed_json`, policies edited by hand, with line and block comments or
+trailing commas, can be read with [`read_from_string_relaxed`](fn.read_from_string_relaxed.html)
+and the related functions; policies are always written as strict JSON.
+
+With the feature `async` the [`tokio`](tokio/index.html) module provides asynchronous versions
+of these functions.
+
+Policy documents may be annotated with the extension element `x-comment`, see
+[`Comments`](../model/struct.Comments.html); the plain read functions ignore it, and the plain
+write functions strip it, while `read_from_string_with_comments` and `to_string_with_comments`
+keep the comments beside the policy.
+
+Written documents list the elements of each statement in the order used by the AWS console,
+`Sid`, `Effect`, `Principal`, `Action`, `Resource`, and then `Condition`, while the operators
+and keys within a condition keep the order in which they were read; so a document written by
+the console is written back unchanged.
+
+The findings reported by IAM Access Analyzer can also be read, with
+[`read_access_analyzer_findings`](fn.read_access_analyzer_findings.html), and correlated with
+the statements of a local policy. Validation and analysis findings can be written in the SARIF
+format, with [`findings_to_sarif`](fn.findings_to_sarif.html), for code scanning tools.
+
+The output of `aws iam get-account-authorization-details`, the users, groups, roles, and
+managed policies of an account, is read with
+[`read_authorization_details`](fn.read_authorization_details.html); the resulting
+[`AccountAuthorizationDetails`](struct.AccountAuthorizationDetails.html) returns the identity
+policies that apply to each principal. A
+[`PermissionsMatrix`](../analysis/struct.PermissionsMatrix.html) of their access to a set of
+actions can be written as CSV, with [`write_matrix_csv`](fn.write_matrix_csv.html), or JSON,
+and a [`PermissionGraph`](struct.PermissionGraph.html) of their relationships as JSON or
+GraphML for graph analytics.
+
+With the feature `watch`, [`watch_directory`](fn.watch_directory.html) reads a directory of
+policy files and watches it for changes; each time the files change they are read and
+validated again and, only if every policy is valid, the new
+[`PolicySet`](struct.PolicySet.html), with its policies compiled for evaluation, replaces the active set of the returned
+[`PolicySetHandle`](struct.PolicySetHandle.html), whose subscribers are notified. A service
+embedding the handle takes the active set for each request, and so never sees a partial set.
+With the feature `signing` as well, the options may name trusted keys, and then only policy
+files signed by one of them are loaded, see the [`signing`](../signing/index.html) module.
+
+With the feature `bundle` the [`bundle`](bundle/index.html) module writes a suite of policies,
+with their fingerprints and signatures, as a single `.iampack` file, which may be encrypted.
 
 # Example
 
@@ -21,11 +71,13 @@ let policy = io::read_from_file(
 */
 
 use crate::error::IamError;
-use crate::model::Policy;
+use crate::model::{Comments, Policy};
 use crate::syntax::IamValue;
-use serde_json::Value;
+use serde_json::{Map, Value};
+#[cfg(feature = "fs")]
 use std::fs::OpenOptions;
 use std::io::{Read, Write};
+#[cfg(feature = "fs")]
 use std::path::Path;
 
 // ------------------------------------------------------------------------------------------------
@@ -39,6 +91,7 @@ use std::path::Path;
 ///
 /// Read a `Policy` document from the file at `path`.
 ///
+#[cfg(feature = "fs")]
 pub fn read_from_file(path: &Path) -> Result<Policy, IamError> {
     match OpenOptions::new().read(true).open(path) {
         Ok(f) => read_from_reader(f),
@@ -68,10 +121,73 @@ pub fn read_from_string(s: &str) -> Result<Policy, IamError> {
     Ok(policy)
 }
 
+///
+/// Read a `Policy` document from the file at `path`, accepting the relaxed syntax described by
+/// `read_from_string_relaxed`.
+///
+#[cfg(all(feature = "fs", feature = "relaxed_json"))]
+pub fn read_from_file_relaxed(path: &Path) -> Result<Policy, IamError> {
+    read_from_reader_relaxed(OpenOptions::new().read(true).open(path)?)
+}
+
+///
+/// Read a `Policy` document from any implementation of `std::io::Read`, accepting the relaxed
+/// syntax described by `read_from_string_relaxed`.
+///
+#[cfg(feature = "relaxed_json")]
+pub fn read_from_reader_relaxed<R>(reader: R) -> Result<Policy, IamError>
+where
+    R: Read + Sized,
+{
+    let mut reader = reader;
+    let mut buffer = String::new();
+    let _ = reader.read_to_string(&mut buffer)?;
+    read_from_string_relaxed(&buffer)
+}
+
+///
+/// Read a `Policy` document from a string written in [JSON5](https://json5.org/), a superset
+/// of JSON that includes the JSONC dialect used by many editors. This allows `//` and `/* */`
+/// comments, trailing commas, single-quoted strings, and unquoted keys; a document written
+/// by any of the write functions is always strict JSON.
+///
+#[cfg(feature = "relaxed_json")]
+pub fn read_from_string_relaxed(s: &str) -> Result<Policy, IamError> {
+    let v: Value = json5::from_str(s)?;
+    let policy = Policy::from_json(&v)?;
+    Ok(policy)
+}
+
+///
+/// Read a `Policy` document, and the comments it contains, from the file at `path`; see
+/// `model::Comments`.
+///
+#[cfg(feature = "fs")]
+pub fn read_from_file_with_comments(path: &Path) -> Result<(Policy, Comments), IamError> {
+    let mut buffer = String::new();
+    let _ = OpenOptions::new()
+        .read(true)
+        .open(path)?
+        .read_to_string(&mut buffer)?;
+    read_from_string_with_comments(&buffer)
+}
+
+///
+/// Read a `Policy` document, and the comments it contains, from a string; see
+/// `model::Comments`.
+///
+pub fn read_from_string_with_comments(s: &str) -> Result<(Policy, Comments), IamError> {
+    let v: Value = serde_json::from_str(s)?;
+    let policy = Policy::from_json(&v)?;
+    let comments = Comments::from_json(&v)?;
+    Ok((policy, comments))
+}
+
 ///
 /// Write the `policy` object to a file at `path`, this will create a file if it does
 /// not exist and overwrite any file if it exists.
 ///
+#[cfg(feature = "fs")]
 pub fn write_to_file(path: &Path, policy: &Policy, pretty: bool) -> Result<(), IamError> {
     match OpenOptions::new()
         .write(true)
@@ -105,3 +221,118 @@ pub fn to_string(policy: &Policy, pretty: bool) -> Result<String, IamError> {
     };
     Ok(json)
 }
+
+///
+/// The canonical form of `policy`: its JSON representation, without whitespace, and with the
+/// keys of every object in sorted order. Two policies that differ only in formatting, or in
+/// the order of their elements, have the same canonical form.
+///
+pub fn to_canonical_string(policy: &Policy) -> Result<String, IamError> {
+    let json = policy.to_json()?;
+    Ok(serde_json::to_string(&sorted(json))?)
+}
+
+///
+/// Write the `policy` object to a file at `path`, with the `comments` read with it; the file
+/// is created, or overwritten, as for `write_to_file`.
+///
+#[cfg(feature = "fs")]
+pub fn write_to_file_with_comments(
+    path: &Path,
+    policy: &Policy,
+    comments: &Comments,
+    pretty: bool,
+) -> Result<(), IamError> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    file.write_all(to_string_with_comments(policy, comments, pretty)?.as_bytes())?;
+    Ok(())
+}
+
+///
+/// The JSON form of `policy`, with the `comments` read with it; `to_string` writes the policy
+/// without comments.
+///
+pub fn to_string_with_comments(
+    policy: &Policy,
+    comments: &Comments,
+    pretty: bool,
+) -> Result<String, IamError> {
+    let mut json = policy.to_json()?;
+    comments.annotate(&mut json);
+    if pretty {
+        Ok(serde_json::to_string_pretty(&json)?)
+    } else {
+        Ok(serde_json::to_string(&json)?)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Objects keep the order in which their keys were inserted, see the `preserve_order` feature
+/// of `serde_json`, and so are rebuilt in sorted order.
+///
+pub(crate) fn sorted(value: Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<(String, Value)> = object.into_iter().collect();
+            entries.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sorted(value)))
+                    .collect::<Map<String, Value>>(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(sorted).collect()),
+        value => value,
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Modules
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(feature = "fs")]
+mod include;
+#[cfg(feature = "fs")]
+pub use include::{read_with_includes, INCLUDE_KEY};
+
+mod access_analyzer;
+pub use access_analyzer::{read_access_analyzer_findings, AccessAnalyzerFinding};
+
+mod authorization_details;
+pub use authorization_details::{
+    read_authorization_details, AccountAuthorizationDetails, GroupDetail, InlinePolicy,
+    ManagedPolicyDetail, PolicySource, RoleDetail, UserDetail,
+};
+
+mod graph;
+pub use graph::{
+    write_graph_json, write_graphml, EdgeKind, GraphEdge, GraphNode, NodeKind, PermissionGraph,
+};
+
+mod matrix;
+pub use matrix::{write_matrix_csv, write_matrix_json};
+
+mod sarif;
+pub use sarif::{findings_to_sarif, write_findings_sarif, SarifArtifact};
+
+#[cfg(feature = "watch")]
+mod watch;
+#[cfg(feature = "watch")]
+pub use watch::{
+    watch_directory, watch_directory_with, PolicySet, PolicySetEvent, PolicySetHandle, WatchOptions,
+};
+
+#[cfg(feature = "async")]
+pub mod tokio;
+
+#[cfg(feature = "bundle")]
+pub mod bundle;
@@ -0,0 +1,246 @@
+/*!
+Provides output of validation and analysis findings in the
+[SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) format, as
+read by GitHub code scanning and other tools that annotate source files.
+
+Each policy document is a [`SarifArtifact`](struct.SarifArtifact.html), its location and the
+findings reported for it. Each finding code is a rule, and each finding a result located at
+the statement it concerns; the line of the statement is only known if the text of the
+document is provided, otherwise results are located at the first line.
+
+# Example
+
+```rust
+use aws_iam::io::{findings_to_sarif, SarifArtifact};
+use aws_iam::validate::{validate_str, ValidationOptions};
+
+let text = r#"{
+  "Statement": [
+    {"Effect": "Allow", "Action": "s3:GetObject"}
+  ]
+}"#;
+let findings = validate_str(text, &ValidationOptions::default()).unwrap();
+let sarif = findings_to_sarif(&[SarifArtifact::new("policy.json", &findings).with_text(text)]);
+
+let result = &sarif["runs"][0]["results"][0];
+assert_eq!(result["ruleId"], "MissingResource");
+assert_eq!(result["level"], "error");
+assert_eq!(
+    result["locations"][0]["physicalLocation"]["region"]["startLine"],
+    3
+);
+```
+*/
+
+use crate::error::IamError;
+use crate::syntax::STATEMENT_NAME;
+use crate::validate::{Finding, Findings, Severity};
+use serde_json::{json, Value};
+use std::io::Write;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A policy document, and the findings reported for it, to be written as SARIF.
+///
+#[derive(Debug, Clone)]
+pub struct SarifArtifact<'a> {
+    uri: &'a str,
+    text: Option<&'a str>,
+    findings: &'a Findings,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns a SARIF log, with a single run, containing the findings for each of `artifacts`.
+///
+pub fn findings_to_sarif(artifacts: &[SarifArtifact<'_>]) -> Value {
+    let mut rules: Vec<&'static str> = Default::default();
+    let mut results: Vec<Value> = Default::default();
+    for artifact in artifacts {
+        let lines = artifact.text.map(statement_lines).unwrap_or_default();
+        for finding in artifact.findings.iter() {
+            let rule_index = match rules.iter().position(|rule| *rule == finding.code) {
+                Some(index) => index,
+                None => {
+                    rules.push(finding.code);
+                    rules.len() - 1
+                }
+            };
+            results.push(result_to_sarif(artifact, finding, rule_index, &lines));
+        }
+    }
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules
+                        .iter()
+                        .map(|rule| json!({ "id": rule, "name": rule }))
+                        .collect::<Vec<Value>>(),
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+///
+/// Write the SARIF log for `artifacts`, see `findings_to_sarif`, to any implementation of
+/// `std::io::Write`.
+///
+pub fn write_findings_sarif<W>(writer: W, artifacts: &[SarifArtifact<'_>]) -> Result<(), IamError>
+where
+    W: Write,
+{
+    serde_json::to_writer_pretty(writer, &findings_to_sarif(artifacts))?;
+    Ok(())
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<'a> SarifArtifact<'a> {
+    ///
+    /// The findings for the document at `uri`, which should be relative to the root of the
+    /// repository for code scanning.
+    ///
+    pub fn new(uri: &'a str, findings: &'a Findings) -> Self {
+        Self {
+            uri,
+            text: None,
+            findings,
+        }
+    }
+
+    ///
+    /// Provide the text of the document, so that results can be located at the line of the
+    /// statement they concern.
+    ///
+    pub fn with_text(self, text: &'a str) -> Self {
+        Self {
+            text: Some(text),
+            ..self
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+const SARIF_VERSION: &str = "2.1.0";
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn result_to_sarif(
+    artifact: &SarifArtifact<'_>,
+    finding: &Finding,
+    rule_index: usize,
+    lines: &[usize],
+) -> Value {
+    let line = finding
+        .statement
+        .and_then(|index| lines.get(index))
+        .copied()
+        .unwrap_or(1);
+    let mut location = json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": artifact.uri },
+            "region": { "startLine": line },
+        }
+    });
+    if let Some(index) = finding.statement {
+        location["logicalLocations"] = json!([{
+            "fullyQualifiedName": format!("{}[{}]", STATEMENT_NAME, index),
+            "kind": "object",
+        }]);
+    }
+    json!({
+        "ruleId": finding.code,
+        "ruleIndex": rule_index,
+        "level": match finding.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "note",
+        },
+        "message": { "text": finding.message },
+        "locations": [location],
+    })
+}
+
+///
+/// Returns the line, counting from 1, on which each statement of the policy document `text`
+/// starts; a single statement object is treated as the first statement. The text is scanned
+/// rather than parsed, so an invalid document returns the statements found before the error.
+///
+fn statement_lines(text: &str) -> Vec<usize> {
+    let mut lines: Vec<usize> = Default::default();
+    let mut line = 1;
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut string_start = 0;
+    let mut last_key: Option<&str> = None;
+    let mut in_statement = false;
+    for (index, c) in text.char_indices() {
+        if c == '\n' {
+            line += 1;
+        }
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+                if depth == 1 {
+                    last_key = Some(&text[string_start..index]);
+                }
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                string_start = index + 1;
+            }
+            '{' | '[' => {
+                if depth == 1 && last_key == Some(STATEMENT_NAME) {
+                    if c == '{' {
+                        lines.push(line);
+                    } else {
+                        in_statement = true;
+                    }
+                } else if depth == 2 && in_statement && c == '{' {
+                    lines.push(line);
+                }
+                depth += 1;
+            }
+            '}' | ']' => {
+                depth -= 1;
+                if depth == 1 {
+                    in_statement = false;
+                    last_key = None;
+                }
+            }
+            _ => {}
+        }
+    }
+    lines
+}
@@ -0,0 +1,81 @@
+/*!
+Provides asynchronous versions of the read and write functions in the parent module, using
+[Tokio](https://tokio.rs), enabled by the feature `async`. These allow policies to be read and
+written from within an async service without blocking the executor or the use of
+`spawn_blocking`.
+
+# Example
+
+```rust,ignore
+use aws_iam::io::tokio as io;
+use std::path::PathBuf;
+
+let policy = io::read_from_file(&PathBuf::from("tests/data/good/example-021.json"))
+    .await
+    .expect("Error reading file");
+```
+*/
+
+use crate::error::IamError;
+use crate::io::{read_from_string, to_string};
+use crate::model::Policy;
+#[cfg(feature = "fs")]
+use ::tokio::fs;
+use ::tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Read a `Policy` document from the file at `path`.
+///
+#[cfg(feature = "fs")]
+pub async fn read_from_file(path: &Path) -> Result<Policy, IamError> {
+    let buffer = fs::read_to_string(path).await?;
+    read_from_string(&buffer)
+}
+
+///
+/// Read a `Policy` document from any implementation of `tokio::io::AsyncRead`.
+///
+pub async fn read_from_async_reader<R>(reader: R) -> Result<Policy, IamError>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut reader = reader;
+    let mut buffer = String::new();
+    let _ = reader.read_to_string(&mut buffer).await?;
+    read_from_string(&buffer)
+}
+
+///
+/// Write the `policy` object to a file at `path`, this will create a file if it does
+/// not exist and overwrite any file if it exists.
+///
+#[cfg(feature = "fs")]
+pub async fn write_to_file(path: &Path, policy: &Policy, pretty: bool) -> Result<(), IamError> {
+    fs::write(path, to_string(policy, pretty)?).await?;
+    Ok(())
+}
+
+///
+/// Write the `policy` object to any implementation of `tokio::io::AsyncWrite`.
+///
+pub async fn write_to_async_writer<W>(
+    writer: W,
+    policy: &Policy,
+    pretty: bool,
+) -> Result<(), IamError>
+where
+    W: AsyncWrite + Unpin,
+{
+    let mut writer = writer;
+    writer
+        .write_all(to_string(policy, pretty)?.as_bytes())
+        .await?;
+    writer.flush().await?;
+    Ok(())
+}
@@ -0,0 +1,377 @@
+use crate::error::{IamError, WatchError};
+use crate::io::read_from_file;
+use crate::model::Policy;
+use crate::offline::CompiledPolicy;
+#[cfg(feature = "signing")]
+use crate::signing::{read_verified_file, VerifyingKey, SIGNATURE_FILE_EXTENSION};
+use crate::validate::{validate_policy, ValidationOptions};
+use std::fs::read_dir;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The options used by `watch_directory_with`.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchOptions {
+    /// How often the directory is checked for changes.
+    pub interval: Duration,
+    /// The options each policy is validated with; a policy with errors is rejected.
+    pub validation: ValidationOptions,
+    /// If not empty, each policy file must have a signature file made by one of these keys;
+    /// see the `signing` module.
+    #[cfg(feature = "signing")]
+    pub trusted_keys: Vec<VerifyingKey>,
+}
+
+///
+/// The policies read from a directory at one time; a new set replaces the active set only
+/// if every policy file in the directory was read and is valid. Policies are compiled when the
+/// set is read, so requests evaluated against the set do not compile them again.
+///
+#[derive(Debug)]
+pub struct PolicySet {
+    generation: u64,
+    paths: Vec<PathBuf>,
+    policies: Vec<CompiledPolicy<'static>>,
+    files: Vec<FileStamp>,
+}
+
+///
+/// A notification of a change, or a rejected change, to the active policy set of a
+/// `PolicySetHandle`.
+///
+#[derive(Debug, Clone)]
+pub enum PolicySetEvent {
+    /// The files changed and the new set is now active.
+    Reloaded(Arc<PolicySet>),
+    /// The files changed but could not be read, or are not valid; the active set is unchanged.
+    Rejected(String),
+}
+
+///
+/// The active policy set of a watched directory; see `watch_directory`. The directory is
+/// watched until the handle is dropped.
+///
+#[derive(Debug)]
+pub struct PolicySetHandle {
+    shared: Arc<Shared>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Read the policy files, those with the extension `json`, in the directory at `path` and
+/// check them every five seconds for changes, validating each policy as an identity policy;
+/// see `watch_directory_with`.
+///
+pub fn watch_directory(path: &Path) -> Result<PolicySetHandle, IamError> {
+    watch_directory_with(path, WatchOptions::default())
+}
+
+///
+/// Read the policy files, those with the extension `json`, in the directory at `path` and
+/// watch it for changes. When a file is added, removed, or modified the directory is read
+/// again and, if every policy is valid, the new set replaces the active set and subscribers
+/// are notified. Returns an error if the initial set cannot be read or is not valid.
+///
+pub fn watch_directory_with(
+    path: &Path,
+    options: WatchOptions,
+) -> Result<PolicySetHandle, IamError> {
+    let initial = PolicySet::load(path, &options, 0)?;
+    let shared = Arc::new(Shared {
+        directory: path.to_path_buf(),
+        options,
+        current: RwLock::new(Arc::new(initial)),
+        rejected: Default::default(),
+        subscribers: Default::default(),
+        stopped: AtomicBool::new(false),
+    });
+    let watcher = shared.clone();
+    let _ = thread::spawn(move || {
+        while !watcher.stopped.load(Ordering::Acquire) {
+            thread::sleep(watcher.options.interval);
+            let _ = watcher.reload();
+        }
+    });
+    Ok(PolicySetHandle { shared })
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+type FileStamp = (PathBuf, Option<SystemTime>);
+
+#[derive(Debug)]
+struct Shared {
+    directory: PathBuf,
+    options: WatchOptions,
+    current: RwLock<Arc<PolicySet>>,
+    rejected: Mutex<Option<Vec<FileStamp>>>,
+    subscribers: Mutex<Vec<Sender<PolicySetEvent>>>,
+    stopped: AtomicBool,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const POLICY_FILE_EXTENSION: &str = "json";
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_INTERVAL,
+            validation: Default::default(),
+            #[cfg(feature = "signing")]
+            trusted_keys: Default::default(),
+        }
+    }
+}
+
+impl WatchOptions {
+    pub fn with_interval(self, interval: Duration) -> Self {
+        Self { interval, ..self }
+    }
+
+    pub fn with_validation(self, validation: ValidationOptions) -> Self {
+        Self { validation, ..self }
+    }
+
+    #[cfg(feature = "signing")]
+    pub fn with_trusted_keys(self, trusted_keys: Vec<VerifyingKey>) -> Self {
+        Self {
+            trusted_keys,
+            ..self
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl PolicySet {
+    /// The number of sets that have replaced another; the initial set is generation `0`.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The policies, in the order of their file names.
+    pub fn policies(&self) -> impl Iterator<Item = &Policy> {
+        self.policies.iter().map(CompiledPolicy::policy)
+    }
+
+    ///
+    /// The compiled policies, in the order of their file names; for use with
+    /// `offline::evaluate_all_compiled`.
+    ///
+    pub fn compiled(&self) -> &[CompiledPolicy<'static>] {
+        &self.policies
+    }
+
+    /// The path of each policy file and the policy read from it.
+    pub fn files(&self) -> impl Iterator<Item = (&Path, &Policy)> {
+        self.paths.iter().map(PathBuf::as_path).zip(self.policies())
+    }
+
+    /// Returns the policy read from the file named `file_name` in the directory, if any.
+    pub fn get(&self, file_name: &str) -> Option<&Policy> {
+        self.files()
+            .find(|(path, _)| path.file_name().map(|name| name == file_name) == Some(true))
+            .map(|(_, policy)| policy)
+    }
+
+    pub fn len(&self) -> usize {
+        self.policies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.policies.is_empty()
+    }
+
+    fn load(directory: &Path, options: &WatchOptions, generation: u64) -> Result<Self, IamError> {
+        let files = policy_files(directory)?;
+        let mut paths = Vec::with_capacity(files.len());
+        let mut policies = Vec::with_capacity(files.len());
+        for (path, _) in files.iter().filter(|(path, _)| is_policy_file(path)) {
+            let policy = read_policy_file(path, options).map_err(|source| WatchError::Read {
+                path: path.clone(),
+                source: Box::new(source),
+            })?;
+            let findings = validate_policy(&policy, &options.validation);
+            if !findings.is_valid() {
+                return Err(WatchError::Invalid {
+                    path: path.clone(),
+                    message: findings
+                        .errors()
+                        .map(ToString::to_string)
+                        .collect::<Vec<String>>()
+                        .join("; "),
+                }
+                .into());
+            }
+            paths.push(path.clone());
+            policies.push(CompiledPolicy::from(policy));
+        }
+        Ok(Self {
+            generation,
+            paths,
+            policies,
+            files,
+        })
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Drop for PolicySetHandle {
+    fn drop(&mut self) {
+        self.shared.stopped.store(true, Ordering::Release);
+    }
+}
+
+impl PolicySetHandle {
+    ///
+    /// The active policy set. The set is never modified, a change replaces it, so the value
+    /// returned is consistent however long it is held.
+    ///
+    pub fn current(&self) -> Arc<PolicySet> {
+        self.shared.current()
+    }
+
+    /// The directory being watched.
+    pub fn directory(&self) -> &Path {
+        &self.shared.directory
+    }
+
+    /// Returns a receiver for each change, or rejected change, to the active set.
+    pub fn subscribe(&self) -> Receiver<PolicySetEvent> {
+        let (sender, receiver) = channel();
+        self.shared.subscribers.lock().unwrap().push(sender);
+        receiver
+    }
+
+    ///
+    /// Check the directory for changes now, rather than waiting for the next check. Returns
+    /// `true` if a new set is active, and an error if the files changed but were rejected;
+    /// files that were rejected are not read again until they change.
+    ///
+    pub fn reload(&self) -> Result<bool, IamError> {
+        self.shared.reload()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Shared {
+    fn current(&self) -> Arc<PolicySet> {
+        self.current.read().unwrap().clone()
+    }
+
+    ///
+    /// Reloads are serialized by the lock on the files last rejected, which are not read again
+    /// until they change.
+    ///
+    fn reload(&self) -> Result<bool, IamError> {
+        let mut rejected = self.rejected.lock().unwrap();
+        let files = policy_files(&self.directory)?;
+        let current = self.current();
+        if files == current.files || rejected.as_ref() == Some(&files) {
+            return Ok(false);
+        }
+        match PolicySet::load(&self.directory, &self.options, current.generation + 1) {
+            Ok(reloaded) => {
+                let reloaded = Arc::new(reloaded);
+                *self.current.write().unwrap() = reloaded.clone();
+                *rejected = None;
+                self.notify(PolicySetEvent::Reloaded(reloaded));
+                Ok(true)
+            }
+            Err(e) => {
+                *rejected = Some(files);
+                self.notify(PolicySetEvent::Rejected(e.to_string()));
+                Err(e)
+            }
+        }
+    }
+
+    fn notify(&self, event: PolicySetEvent) {
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The policy files in `directory`, and with the feature `signing` their signature files,
+/// sorted by name, with their modification times.
+///
+fn policy_files(directory: &Path) -> Result<Vec<FileStamp>, IamError> {
+    let mut files = Vec::new();
+    for entry in read_dir(directory)? {
+        let path = entry?.path();
+        if path.is_file() && (is_policy_file(&path) || is_signature_file(&path)) {
+            let modified = path
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .ok();
+            files.push((path, modified));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension().map(|actual| actual == extension) == Some(true)
+}
+
+fn is_policy_file(path: &Path) -> bool {
+    has_extension(path, POLICY_FILE_EXTENSION)
+}
+
+#[cfg(feature = "signing")]
+fn is_signature_file(path: &Path) -> bool {
+    has_extension(path, SIGNATURE_FILE_EXTENSION)
+}
+
+#[cfg(not(feature = "signing"))]
+fn is_signature_file(_: &Path) -> bool {
+    false
+}
+
+#[cfg(feature = "signing")]
+fn read_policy_file(path: &Path, options: &WatchOptions) -> Result<Policy, IamError> {
+    if options.trusted_keys.is_empty() {
+        read_from_file(path)
+    } else {
+        read_verified_file(path, &options.trusted_keys)
+    }
+}
+
+#[cfg(not(feature = "signing"))]
+fn read_policy_file(path: &Path, _: &WatchOptions) -> Result<Policy, IamError> {
+    read_from_file(path)
+}
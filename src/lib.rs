@@ -19,7 +19,9 @@ From [AWS Identity and Access Management Documentation](https://docs.aws.amazon.
 This crate provides a set of types that can be used to serialize and deserialize IAM Policy
 documents. For a simpler experience creating documents a [`builder`](model/builder/index.html)
 module provides a more _fluent_ method for construction. The [`io`](io/index.html) module
-provides basic support for reading and writing JSON files.
+provides basic support for reading and writing JSON files, and the [`store`](store/index.html)
+module provides a cache for tools that load large numbers of policies. Policies that differ only
+in a few values can be generated from a [`template`](template/index.html).
 
 # Usage
 
@@ -84,16 +86,61 @@ println!("{}", policy.to_string());
 
 The following features are supported by this crate and can be included as needed. By default the
 crate only provides an in-memory data model with Serde support for reading and writing to the
-standard JSON representation.
-
+standard JSON representation, and functions to read and write policy files.
+
+* `async` - provides asynchronous versions of the [`io`](io/index.html) functions using Tokio.
+* `authorizer` - provides the [`authorizer`](authorizer/index.html) module, a tower, and so axum,
+  middleware that authorizes HTTP requests against a set of policies with the offline evaluator;
+  this implies `offline_eval`.
+* `authorizer_actix` - provides an actix-web extractor in the `authorizer` module; this implies
+  `authorizer`.
+* `aws_online` - provides reconciliation of the offline evaluator against the IAM policy
+  simulator, see `offline_eval`.
+* `bundle` - provides the `io::bundle` module, which writes and reads `.iampack` files, a suite
+  of policies with their fingerprints and signatures, optionally encrypted, as one artifact.
 * `command_line` - provides a command line tool, `policy` that can verify and evaluate existing
   policy documents and create new ones from a set of templates.
+* `daemon` - provides a decision service, `policyd`, that answers authorization requests over
+  HTTP/JSON against a directory of policies, reloading them when they change.
 * `document` - produces formatted documentation for a policy. This can be in the form of Markdown
-  or LaTeX and would usually be used to document a policy template for others to follow.
+  or LaTeX and would usually be used to document a policy template for others to follow, or a
+  colorized rendering for ANSI terminals.
+* `ffi` - provides a C-compatible API, and header, for the parser, validation, and offline
+  evaluation.
+* `fs` - provides the file-based functions in the [`io`](io/index.html) module; this is the
+  only feature enabled by default.
+* `lsp` - provides the `policy-lsp` language server, which offers diagnostics, hover text, and
+  completion of condition operators and global condition keys for `*.iam.json` files.
+* `macros` - provides the `policy!` macro for writing policies inline.
 * `offline_eval` - provides a simple, by which we mean incomplete, evaluation of a policy  using
   a request object to match. This is useful but not sufficient for testing policies.
+* `parallel` - provides evaluation of many requests against many policies, in parallel, using
+  rayon; this implies `offline_eval`.
+* `plugins` - provides the host side of rule packs, lint rules and transforms compiled to
+  WebAssembly against the interface in `wit/rule-pack.wit`; the WebAssembly runtime itself is
+  provided by the tool.
+* `plugins_wasmtime` - loads rule packs, compiled as WebAssembly components, with an embedded
+  wasmtime runtime; this implies `plugins`. With `command_line` the `policy lint` command runs
+  the rules of the rule packs in the directory given by `--plugins`.
+* `relaxed_json` - provides functions in the [`io`](io/index.html) module that read policies
+  written in JSON5, or JSONC, with comments and trailing commas.
 * `service_config` - adds to the verification of policies by storing service-specific configuration
-  on actions, resource formats, and condition keys.
+  on actions, resource formats, and condition keys, and provides the [`intent`](intent/index.html)
+  module, which generates least-privilege policies from coarse descriptions of access.
+* `signing` - provides the [`signing`](signing/index.html) module, which signs policies, and
+  verifies them when they are loaded, with detached ed25519 signatures.
+* `templating` - provides `template::HandlebarsTemplate`, which renders policy templates with
+  the Handlebars engine, reporting errors by template line.
+* `watch` - provides `io::watch_directory`, which watches a directory of policy files and
+  replaces the active set of policies, once validated and compiled, when the files change.
+* `yaml` - allows policy test specifications, see `offline::run_spec`, to be written in YAML.
+
+# WebAssembly
+
+Without default features the crate does not use the file system, and so the model, builders,
+validation, and analysis may be compiled for `wasm32-unknown-unknown` and used in browser-based
+policy editors or edge workers. The crate still depends on `std`, it is not `no_std`. An
+example using `wasm-bindgen` is in the `examples/wasm` directory.
 
 */
 
@@ -128,15 +175,56 @@ pub mod syntax;
 
 pub mod model;
 
+pub mod arn;
+
+pub mod validate;
+
+pub mod baseline;
+
+pub mod lint;
+
+pub mod schema;
+
+pub mod diagnostic;
+
+pub mod analysis;
+
+pub mod diff;
+
+pub mod store;
+
+pub mod template;
+
 pub mod context;
 
 pub mod io;
 
+#[cfg(feature = "macros")]
+pub mod macros;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
 #[cfg(feature = "document")]
 pub mod document;
 
 #[cfg(feature = "offline_eval")]
 pub mod offline;
 
+#[cfg(feature = "authorizer")]
+pub mod authorizer;
+
+#[cfg(feature = "plugins")]
+pub mod plugin;
+
+#[cfg(feature = "signing")]
+pub mod signing;
+
 #[cfg(feature = "service_config")]
 pub mod service;
+
+#[cfg(feature = "service_config")]
+pub mod intent;
+
+#[cfg(feature = "testkit")]
+pub mod testkit;
@@ -0,0 +1,372 @@
+/*!
+Provides a registry of lint rules, so that the checks in this crate and those written by others
+can be run together over a policy.
+
+A [`LintRule`](trait.LintRule.html) has a name, unique within a registry, a severity, and a
+`check` method that adds its findings for a policy. A
+[`LintRegistry`](struct.LintRegistry.html) created with `default` contains the built-in rules,
+one for each of the checks in [`validate`](../validate/index.html) and
+[`analysis`](../analysis/index.html); further rules, for example those from a crate of an
+organization's private rules, are registered at runtime and run after the built-in ones.
+
+The `code` of each finding identifies the kind of problem found and is stable across releases;
+the codes of the built-in rules are the `FINDING_*` constants of the modules above. Rules
+written elsewhere should use codes that will not be confused with these, for example by adding
+a prefix for the organization.
+
+# Example
+
+```rust
+use aws_iam::lint::{LintRegistry, LintRule};
+use aws_iam::model::Policy;
+use aws_iam::validate::{Finding, Findings, Severity};
+use std::str::FromStr;
+
+struct NoDeny;
+
+impl LintRule for NoDeny {
+    fn name(&self) -> &'static str {
+        "ExampleNoDeny"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn check(&self, policy: &Policy, findings: &mut Findings) {
+        for (index, statement) in policy.statements().enumerate() {
+            if statement.is_deny() {
+                findings.push(Finding::warning("ExampleDeny", "deny statement", Some(index)));
+            }
+        }
+    }
+}
+
+let registry = LintRegistry::default().with_rule(NoDeny);
+let policy = Policy::from_str(
+    r#"{"Statement":[{"Effect":"Deny","Action":"s3:DeleteBucket","Resource":"*"}]}"#,
+)
+.unwrap();
+
+let findings = registry.check(&policy);
+assert!(findings.iter().any(|finding| finding.code == "ExampleDeny"));
+```
+*/
+
+#[cfg(feature = "service_config")]
+use crate::analysis::{check_access_levels, check_dependent_actions};
+use crate::analysis::{check_contradictions, check_expired, check_sensitive_actions};
+use crate::baseline::Baseline;
+use crate::model::Policy;
+use crate::validate::{validate_policy, Findings, Severity, ValidationOptions};
+use std::fmt::{Debug, Formatter};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A check that reports findings for a policy.
+///
+pub trait LintRule {
+    /// The name of the rule, unique within a registry.
+    fn name(&self) -> &'static str;
+
+    /// The most severe finding this rule reports.
+    fn severity(&self) -> Severity;
+
+    /// Add any findings for `policy` to `findings`.
+    fn check(&self, policy: &Policy, findings: &mut Findings);
+}
+
+///
+/// The rules run over each policy, in the order they were registered.
+///
+pub struct LintRegistry {
+    rules: Vec<Box<dyn LintRule>>,
+}
+
+///
+/// The built-in rule that validates a policy with `validate_policy`.
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationRule(pub ValidationOptions);
+
+///
+/// The built-in rule that reports sensitive actions and escalation paths with
+/// `check_sensitive_actions`.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SensitiveActionsRule;
+
+///
+/// The built-in rule that reports statements that can never take effect with
+/// `check_contradictions`.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContradictionsRule;
+
+///
+/// The built-in rule that reports statements allowing write access on any resource with
+/// `check_access_levels`.
+///
+#[cfg(feature = "service_config")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AccessLevelRule;
+
+///
+/// The built-in rule that notes actions allowed without the actions they depend on with
+/// `check_dependent_actions`.
+///
+#[cfg(feature = "service_config")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DependentActionsRule;
+
+///
+/// The built-in rule that reports statements that have expired, at the time of the check,
+/// with `check_expired`.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExpiredStatementsRule;
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+/// The name of [`ValidationRule`](struct.ValidationRule.html).
+pub const RULE_VALIDATION: &str = "Validation";
+
+/// The name of [`SensitiveActionsRule`](struct.SensitiveActionsRule.html).
+pub const RULE_SENSITIVE_ACTIONS: &str = "SensitiveActions";
+
+/// The name of [`ContradictionsRule`](struct.ContradictionsRule.html).
+pub const RULE_CONTRADICTIONS: &str = "Contradictions";
+
+/// The name of [`ExpiredStatementsRule`](struct.ExpiredStatementsRule.html).
+pub const RULE_EXPIRED_STATEMENTS: &str = "ExpiredStatements";
+
+/// The name of [`AccessLevelRule`](struct.AccessLevelRule.html).
+#[cfg(feature = "service_config")]
+pub const RULE_ACCESS_LEVEL: &str = "AccessLevel";
+
+/// The name of [`DependentActionsRule`](struct.DependentActionsRule.html).
+#[cfg(feature = "service_config")]
+pub const RULE_DEPENDENT_ACTIONS: &str = "DependentActions";
+
+/// The name of the rule for an organization's [`Baseline`](../baseline/struct.Baseline.html).
+pub const RULE_BASELINE: &str = "Baseline";
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Debug for LintRegistry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LintRegistry")
+            .field("rules", &self.names())
+            .finish()
+    }
+}
+
+impl Default for LintRegistry {
+    fn default() -> Self {
+        let registry = Self::new()
+            .with_rule(ValidationRule::default())
+            .with_rule(SensitiveActionsRule)
+            .with_rule(ContradictionsRule)
+            .with_rule(ExpiredStatementsRule);
+        #[cfg(feature = "service_config")]
+        let registry = registry
+            .with_rule(AccessLevelRule)
+            .with_rule(DependentActionsRule);
+        registry
+    }
+}
+
+impl LintRegistry {
+    /// Create a registry with no rules, not even the built-in ones.
+    pub fn new() -> Self {
+        Self {
+            rules: Default::default(),
+        }
+    }
+
+    /// Register `rule`, see `register`.
+    pub fn with_rule<R>(mut self, rule: R) -> Self
+    where
+        R: LintRule + 'static,
+    {
+        self.register(rule);
+        self
+    }
+
+    ///
+    /// Register `rule`; if a rule with the same name is already registered it is replaced,
+    /// and keeps its place in the order, otherwise `rule` is run after those already
+    /// registered.
+    ///
+    pub fn register<R>(&mut self, rule: R)
+    where
+        R: LintRule + 'static,
+    {
+        let rule: Box<dyn LintRule> = Box::new(rule);
+        match self.rules.iter().position(|r| r.name() == rule.name()) {
+            Some(index) => self.rules[index] = rule,
+            None => self.rules.push(rule),
+        }
+    }
+
+    /// Remove the rule named `name`, returning `true` if it was registered.
+    pub fn unregister(&mut self, name: &str) -> bool {
+        let count = self.rules.len();
+        self.rules.retain(|rule| rule.name() != name);
+        self.rules.len() != count
+    }
+
+    /// The names of the registered rules, in the order they are run.
+    pub fn names(&self) -> Vec<&'static str> {
+        self.rules.iter().map(|rule| rule.name()).collect()
+    }
+
+    /// Returns the rule named `name`, if it is registered.
+    pub fn get(&self, name: &str) -> Option<&dyn LintRule> {
+        self.rules
+            .iter()
+            .find(|rule| rule.name() == name)
+            .map(|rule| rule.as_ref())
+    }
+
+    /// Run every registered rule over `policy`, returning all their findings.
+    pub fn check(&self, policy: &Policy) -> Findings {
+        let mut findings = Findings::default();
+        for rule in &self.rules {
+            rule.check(policy, &mut findings);
+        }
+        findings
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl LintRule for ValidationRule {
+    fn name(&self) -> &'static str {
+        RULE_VALIDATION
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check(&self, policy: &Policy, findings: &mut Findings) {
+        for finding in validate_policy(policy, &self.0) {
+            findings.push(finding);
+        }
+    }
+}
+
+impl LintRule for SensitiveActionsRule {
+    fn name(&self) -> &'static str {
+        RULE_SENSITIVE_ACTIONS
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, policy: &Policy, findings: &mut Findings) {
+        for finding in check_sensitive_actions(policy) {
+            findings.push(finding);
+        }
+    }
+}
+
+impl LintRule for ContradictionsRule {
+    fn name(&self) -> &'static str {
+        RULE_CONTRADICTIONS
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, policy: &Policy, findings: &mut Findings) {
+        for finding in check_contradictions(policy) {
+            findings.push(finding);
+        }
+    }
+}
+
+impl LintRule for ExpiredStatementsRule {
+    fn name(&self) -> &'static str {
+        RULE_EXPIRED_STATEMENTS
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, policy: &Policy, findings: &mut Findings) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs() as i64)
+            .unwrap_or_default();
+        for finding in check_expired(policy, now) {
+            findings.push(finding);
+        }
+    }
+}
+
+#[cfg(feature = "service_config")]
+impl LintRule for AccessLevelRule {
+    fn name(&self) -> &'static str {
+        RULE_ACCESS_LEVEL
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, policy: &Policy, findings: &mut Findings) {
+        for finding in check_access_levels(policy) {
+            findings.push(finding);
+        }
+    }
+}
+
+#[cfg(feature = "service_config")]
+impl LintRule for DependentActionsRule {
+    fn name(&self) -> &'static str {
+        RULE_DEPENDENT_ACTIONS
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn check(&self, policy: &Policy, findings: &mut Findings) {
+        for finding in check_dependent_actions(policy) {
+            findings.push(finding);
+        }
+    }
+}
+
+impl LintRule for Baseline {
+    fn name(&self) -> &'static str {
+        RULE_BASELINE
+    }
+
+    fn severity(&self) -> Severity {
+        self.rules
+            .iter()
+            .map(|rule| rule.severity)
+            .min()
+            .unwrap_or(Severity::Info)
+    }
+
+    fn check(&self, policy: &Policy, findings: &mut Findings) {
+        for finding in Baseline::check(self, policy) {
+            findings.push(finding);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+/*!
+Provides the `policy!` macro, a compact declarative syntax for writing policies inline. This
+module is only available with the `macros` feature.
+
+Each statement starts with its effect, `allow` or `deny`, followed by a list of actions, an
+optional list of resources introduced by `on`, and an optional list of conditions introduced
+by `when`. Statements are separated by `;`.
+
+```text
+policy! {
+    allow ["s3:GetObject", "s3:ListBucket"]
+        on ["arn:aws:s3:::bucket", "arn:aws:s3:::bucket/public-*"]
+        when Bool("aws:SecureTransport") == true
+            and StringEquals("aws:PrincipalOrgID") == "o-a1b2c3d4e5";
+    deny ["s3:DeleteObject"] on ["*"]
+}
+```
+
+The condition operator must be the name of a `GlobalOperator` variant, so misspelled operator
+names are reported when the macro is compiled. Actions, resources, and condition keys are
+parsed when the expanded code runs, and any errors are returned as a `BuildError`; the
+macro evaluates to a `Result<Policy, BuildError>`.
+
+# Example
+
+```rust
+use aws_iam::policy;
+
+let policy = policy! {
+    allow ["s3:GetObject"] on ["arn:aws:s3:::bucket/public-*"] when Bool("aws:SecureTransport") == true
+}
+.unwrap();
+assert_eq!(policy.statement.len(), 1);
+```
+
+An unknown operator does not compile.
+
+```rust,compile_fail
+use aws_iam::policy;
+
+let policy = policy! {
+    allow ["s3:GetObject"] on ["*"] when Boolean("aws:SecureTransport") == true
+};
+```
+*/
+
+use crate::arn::ArnParseMode;
+use crate::error::BuildError;
+use crate::model::builder::{ActionBuilder, ResourceBuilder, StatementBuilder};
+use crate::model::{Effect, QualifiedName, ResourceEntry};
+use crate::syntax::POLICY_WILDCARD_VALUE;
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Public Macros
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Construct a `Policy` from a compact, inline, description; see the [`macros`](macros/index.html)
+/// module for the syntax. Evaluates to a `Result<Policy, BuildError>`.
+///
+#[macro_export]
+macro_rules! policy {
+    ($(
+        $effect:ident [$($action:expr),* $(,)?]
+        $(on [$($resource:expr),* $(,)?])?
+        $(when $($operator:ident ($key:expr) == $value:literal)and+)?
+    );+ $(;)?) => {
+        (|| -> ::std::result::Result<$crate::model::Policy, $crate::error::BuildError> {
+            $crate::model::builder::PolicyBuilder::new()
+                .for_version($crate::model::Version::V2012)
+                .evaluate_all(vec![$(
+                    $crate::macros::statement($crate::__policy_effect!($effect))
+                        .actions($crate::macros::actions(&[$($action),*])?)
+                        $(.resources($crate::macros::resources(&[$($resource),*])?))?
+                        $($(.if_condition(
+                            $crate::model::builder::ConditionBuilder::new(
+                                $crate::model::GlobalOperator::$operator,
+                            )
+                            .right_hand_str($crate::macros::condition_key($key)?, $value.to_string()),
+                        ))+)?
+                ),+])
+                .build()
+        })()
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __policy_effect {
+    (allow) => {
+        $crate::model::Effect::Allow
+    };
+    (deny) => {
+        $crate::model::Effect::Deny
+    };
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+#[doc(hidden)]
+pub fn statement(effect: Effect) -> StatementBuilder {
+    match effect {
+        Effect::Allow => StatementBuilder::new().allows(),
+        Effect::Deny => StatementBuilder::new().does_not_allow(),
+    }
+}
+
+#[doc(hidden)]
+pub fn actions(actions: &[&str]) -> Result<ActionBuilder, BuildError> {
+    if actions == [POLICY_WILDCARD_VALUE] {
+        Ok(ActionBuilder::any())
+    } else {
+        Ok(ActionBuilder::any_of().these(
+            actions
+                .iter()
+                .map(|action| QualifiedName::from_str(action))
+                .collect::<Result<Vec<QualifiedName>, _>>()?,
+        ))
+    }
+}
+
+#[doc(hidden)]
+pub fn resources(resources: &[&str]) -> Result<ResourceBuilder, BuildError> {
+    if resources == [POLICY_WILDCARD_VALUE] {
+        Ok(ResourceBuilder::any())
+    } else {
+        Ok(ResourceBuilder::any_of().these(
+            resources
+                .iter()
+                .map(|resource| ResourceEntry::parse(resource, ArnParseMode::Strict))
+                .collect::<Result<Vec<ResourceEntry>, _>>()?,
+        ))
+    }
+}
+
+#[doc(hidden)]
+pub fn condition_key(key: &str) -> Result<QualifiedName, BuildError> {
+    Ok(QualifiedName::from_str(key)?)
+}
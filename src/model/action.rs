@@ -4,13 +4,15 @@ More detailed description, with
 # Example
  */
 
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
 use crate::error::{missing_property, type_mismatch, unexpected_properties, IamFormatError};
 use crate::model::{MaybeAny, OrAny, QualifiedName};
 use crate::syntax::{
-    display_vec_to_json, from_json_str, json_type_name, IamProperty, IamValue, ACTION_NAME,
-    ACTION_VALUE_ACTION, ACTION_VALUE_NOT_ACTION, JSON_TYPE_NAME_STRING, POLICY_WILDCARD_VALUE,
+    display_vec_to_json, from_json_str, json_type_name, property_from_fragment,
+    property_to_fragment, IamProperty, IamValue, ACTION_NAME, ACTION_VALUE_ACTION,
+    ACTION_VALUE_NOT_ACTION, JSON_TYPE_NAME_STRING, POLICY_WILDCARD_VALUE,
 };
 use aws_arn::ARN;
 use serde_json::{Map, Value};
@@ -55,7 +57,7 @@ use serde_json::{Map, Value};
 /// ]
 /// ```
 ///
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Action {
     /// Asserts that the action in the request **must** match one of the specified ones.
     Action(OrAny<Vec<QualifiedName>>),
@@ -77,6 +79,20 @@ impl Default for Action {
     }
 }
 
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        property_to_fragment(self, f)
+    }
+}
+
+impl FromStr for Action {
+    type Err = IamFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        property_from_fragment(s, ACTION_NAME)
+    }
+}
+
 impl IamProperty for Action {
     fn into_json_object(
         &self,
@@ -170,6 +186,20 @@ impl Action {
             None
         }
     }
+
+    ///
+    /// Returns `true` if the action named `action`, for example `s3:GetObject`, is covered by
+    /// this element; that is, for `Action` it matches one of the listed actions and for
+    /// `NotAction` it matches none of them. Action names are compared ignoring case and the
+    /// listed actions may contain the wildcards `*` and `?`.
+    ///
+    pub fn matches(&self, action: &str) -> bool {
+        let matched = match self.inner() {
+            OrAny::Any => true,
+            OrAny::Some(values) => values.iter().any(|v| v.matches_str(action)),
+        };
+        matched != self.is_negative()
+    }
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -1,11 +1,13 @@
+use crate::error::{empty_vector_property, BuildError};
 use crate::model::{Action, OrAny, QualifiedName};
+use crate::syntax::ACTION_NAME;
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
 // ------------------------------------------------------------------------------------------------
 
 ///
-/// A `Action` builder, used with `StatementBuilder::action()`.
+/// A `Action` builder, used with `StatementBuilder::actions()`.
 ///
 #[derive(Clone, Debug)]
 pub struct ActionBuilder {
@@ -26,16 +28,6 @@ impl Default for ActionBuilder {
     }
 }
 
-impl From<ActionBuilder> for Action {
-    fn from(builder: ActionBuilder) -> Self {
-        if builder.not_action {
-            Action::NotAction(builder.actions)
-        } else {
-            Action::Action(builder.actions)
-        }
-    }
-}
-
 impl ActionBuilder {
     pub fn any() -> Self {
         Self {
@@ -54,28 +46,40 @@ impl ActionBuilder {
     pub fn any_of() -> Self {
         Self {
             not_action: false,
-            actions: OrAny::Any,
+            actions: OrAny::Some(Default::default()),
         }
     }
 
     pub fn none_of() -> Self {
         Self {
             not_action: true,
-            actions: OrAny::Any,
+            actions: OrAny::Some(Default::default()),
         }
     }
 
     /// Sets the action of this statement to be only this value.
     pub fn this(self, action: QualifiedName) -> Self {
-        self.these(vec![action]);
-        self
+        self.these(vec![action])
     }
 
     /// Sets the action of this statement to be any of these values.
-    pub fn these(self, actions: Vec<QualifiedName>) -> Self {
-        if let OrAny::Some(action_vec) = self.actions {
+    pub fn these(mut self, actions: Vec<QualifiedName>) -> Self {
+        if let OrAny::Some(action_vec) = &mut self.actions {
             action_vec.extend(actions);
         }
         self
     }
+
+    /// Build the action, returning an error if `any_of` or `none_of` were used without
+    /// adding any actions.
+    pub fn build(self) -> Result<Action, BuildError> {
+        if matches!(&self.actions, OrAny::Some(actions) if actions.is_empty()) {
+            return BuildError::Format(empty_vector_property(ACTION_NAME)).into();
+        }
+        Ok(if self.not_action {
+            Action::NotAction(self.actions)
+        } else {
+            Action::Action(self.actions)
+        })
+    }
 }
@@ -1,7 +1,11 @@
+use crate::arn::ARN;
+use crate::error::{empty_vector_property, unexpected_value_for_type, BuildError};
+use crate::model::condition::GlobalConditionKey;
 use crate::model::{
-    Condition, ConditionValue, GlobalOperator, Operator, QualifiedName, Quantifier,
+    Condition, ConditionValue, GlobalOperator, Match, Operator, QualifiedName, Quantifier,
 };
-use std::collections::HashMap;
+use crate::syntax::{CONDITION_NAME, QUALIFIED_NAME_NAME, QUALIFIED_TAG_SEPARATOR};
+use std::str::FromStr;
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
@@ -13,9 +17,14 @@ use std::collections::HashMap;
 #[derive(Clone, Debug)]
 pub struct ConditionBuilder {
     operator: Operator,
-    matches: HashMap<QualifiedName, Vec<ConditionValue>>,
+    matches: Match,
+    invalid: Vec<String>,
 }
 
+///
+/// A builder for a single condition key and its values, used with
+/// `ConditionBuilder::matching()`.
+///
 #[derive(Clone, Debug)]
 pub struct MatchBuilder {
     condition_key: QualifiedName,
@@ -26,21 +35,6 @@ pub struct MatchBuilder {
 // Implementations
 // ------------------------------------------------------------------------------------------------
 
-impl Default for ConditionBuilder {
-    fn default() -> Self {
-        Self {
-            operator: Default::default(),
-            matches: Default::default(),
-        }
-    }
-}
-
-impl From<ConditionBuilder> for Condition {
-    fn from(builder: ConditionBuilder) -> Self {
-        todo!()
-    }
-}
-
 impl ConditionBuilder {
     /// Create a new Condition with the provided operator.
     pub fn new(operator: GlobalOperator) -> Self {
@@ -51,6 +45,7 @@ impl ConditionBuilder {
                 if_exists: false,
             },
             matches: Default::default(),
+            invalid: Default::default(),
         }
     }
 
@@ -64,6 +59,26 @@ impl ConditionBuilder {
         Self::new(GlobalOperator::StringNotEquals)
     }
 
+    /// Create a new Condition with operator = `StringEqualsIgnoreCase`
+    pub fn new_string_equals_ignore_case() -> Self {
+        Self::new(GlobalOperator::StringEqualsIgnoreCase)
+    }
+
+    /// Create a new Condition with operator = `StringNotEqualsIgnoreCase`
+    pub fn new_string_not_equals_ignore_case() -> Self {
+        Self::new(GlobalOperator::StringNotEqualsIgnoreCase)
+    }
+
+    /// Create a new Condition with operator = `StringLike`
+    pub fn new_string_like() -> Self {
+        Self::new(GlobalOperator::StringLike)
+    }
+
+    /// Create a new Condition with operator = `StringNotLike`
+    pub fn new_string_not_like() -> Self {
+        Self::new(GlobalOperator::StringNotLike)
+    }
+
     /// Create a new Condition with operator = `NumericEquals`
     pub fn new_numeric_equals() -> Self {
         Self::new(GlobalOperator::NumericEquals)
@@ -71,7 +86,57 @@ impl ConditionBuilder {
 
     /// Create a new Condition with operator = `NumericNotEquals`
     pub fn new_numeric_not_equals() -> Self {
-        Self::new(GlobalOperator::NumericEquals)
+        Self::new(GlobalOperator::NumericNotEquals)
+    }
+
+    /// Create a new Condition with operator = `NumericLessThan`
+    pub fn new_numeric_less_than() -> Self {
+        Self::new(GlobalOperator::NumericLessThan)
+    }
+
+    /// Create a new Condition with operator = `NumericLessThanEquals`
+    pub fn new_numeric_less_than_equals() -> Self {
+        Self::new(GlobalOperator::NumericLessThanEquals)
+    }
+
+    /// Create a new Condition with operator = `NumericGreaterThan`
+    pub fn new_numeric_greater_than() -> Self {
+        Self::new(GlobalOperator::NumericGreaterThan)
+    }
+
+    /// Create a new Condition with operator = `NumericGreaterThanEquals`
+    pub fn new_numeric_greater_than_equals() -> Self {
+        Self::new(GlobalOperator::NumericGreaterThanEquals)
+    }
+
+    /// Create a new Condition with operator = `DateEquals`
+    pub fn new_date_equals() -> Self {
+        Self::new(GlobalOperator::DateEquals)
+    }
+
+    /// Create a new Condition with operator = `DateNotEquals`
+    pub fn new_date_not_equals() -> Self {
+        Self::new(GlobalOperator::DateNotEquals)
+    }
+
+    /// Create a new Condition with operator = `DateLessThan`
+    pub fn new_date_less_than() -> Self {
+        Self::new(GlobalOperator::DateLessThan)
+    }
+
+    /// Create a new Condition with operator = `DateLessThanEquals`
+    pub fn new_date_less_than_equals() -> Self {
+        Self::new(GlobalOperator::DateLessThanEquals)
+    }
+
+    /// Create a new Condition with operator = `DateGreaterThan`
+    pub fn new_date_greater_than() -> Self {
+        Self::new(GlobalOperator::DateGreaterThan)
+    }
+
+    /// Create a new Condition with operator = `DateGreaterThanEquals`
+    pub fn new_date_greater_than_equals() -> Self {
+        Self::new(GlobalOperator::DateGreaterThanEquals)
     }
 
     /// Create a new Condition with operator = `Bool`
@@ -79,25 +144,145 @@ impl ConditionBuilder {
         Self::new(GlobalOperator::Bool)
     }
 
+    /// Create a new Condition with operator = `BinaryEquals`
+    pub fn new_binary_equals() -> Self {
+        Self::new(GlobalOperator::BinaryEquals)
+    }
+
+    /// Create a new Condition with operator = `IpAddress`
+    pub fn new_ip_address() -> Self {
+        Self::new(GlobalOperator::IpAddress)
+    }
+
+    /// Create a new Condition with operator = `NotIpAddress`
+    pub fn new_not_ip_address() -> Self {
+        Self::new(GlobalOperator::NotIpAddress)
+    }
+
+    /// Create a new Condition with operator = `ArnEquals`
+    pub fn new_arn_equals() -> Self {
+        Self::new(GlobalOperator::ArnEquals)
+    }
+
+    /// Create a new Condition with operator = `ArnNotEquals`
+    pub fn new_arn_not_equals() -> Self {
+        Self::new(GlobalOperator::ArnNotEquals)
+    }
+
+    /// Create a new Condition with operator = `ArnLike`
+    pub fn new_arn_like() -> Self {
+        Self::new(GlobalOperator::ArnLike)
+    }
+
+    /// Create a new Condition with operator = `ArnNotLike`
+    pub fn new_arn_not_like() -> Self {
+        Self::new(GlobalOperator::ArnNotLike)
+    }
+
+    /// Create a new Condition with operator = `Null`
+    pub fn new_null() -> Self {
+        Self::new(GlobalOperator::Null)
+    }
+
     /// Add the _for-all-values_ quantifier.
-    pub fn for_all(self) -> Self {
+    pub fn for_all(mut self) -> Self {
         self.operator.quantifier = Some(Quantifier::ForAllValues);
         self
     }
 
     /// Add the _for-any-value_ quantifier.
-    pub fn for_any(self) -> Self {
+    pub fn for_any(mut self) -> Self {
         self.operator.quantifier = Some(Quantifier::ForAnyValue);
         self
     }
 
-    pub fn match_push(&self, match_value: Match) {
-        todo!()
+    /// Add the _if-exists_ suffix to the operator.
+    pub fn if_exists(mut self) -> Self {
+        self.operator.if_exists = true;
+        self
+    }
+
+    /// Add a string value to match against the condition key.
+    pub fn right_hand_str<S>(mut self, condition_key: QualifiedName, value: S) -> Self
+    where
+        S: Into<ConditionValue>,
+    {
+        self.matches.insert(condition_key, value);
+        self
+    }
+
+    /// Add a boolean value to match against the condition key.
+    pub fn right_hand_bool(self, condition_key: QualifiedName, value: bool) -> Self {
+        self.right_hand_str(condition_key, value.to_string())
+    }
+
+    /// Add a numeric value to match against the condition key.
+    pub fn right_hand_number<N>(self, condition_key: QualifiedName, value: N) -> Self
+    where
+        N: Into<f64>,
+    {
+        self.right_hand_str(condition_key, value.into().to_string())
+    }
+
+    /// Add an ARN value to match against the condition key.
+    pub fn right_hand_arn(self, condition_key: QualifiedName, value: &ARN) -> Self {
+        self.right_hand_str(condition_key, value.to_string())
+    }
+
+    ///
+    /// Add a value to match against the tagged condition key `condition_key/tag`, for
+    /// example `right_hand_tag("aws:PrincipalTag", "team", "payments")` matches the value
+    /// `payments` against the key `aws:PrincipalTag/team`. If the key or tag are not valid
+    /// the error is reported by `build`.
+    ///
+    pub fn right_hand_tag<S1, S2, S3>(mut self, condition_key: S1, tag: S2, value: S3) -> Self
+    where
+        S1: AsRef<str>,
+        S2: Into<String>,
+        S3: Into<ConditionValue>,
+    {
+        let condition_key = condition_key.as_ref();
+        let tag = tag.into();
+        match QualifiedName::from_str(condition_key).and_then(|key| key.with_tag(tag.clone())) {
+            Ok(tagged_key) => self.right_hand_str(tagged_key, value),
+            Err(_) => {
+                self.invalid.push(format!(
+                    "{}{}{}",
+                    condition_key, QUALIFIED_TAG_SEPARATOR, tag
+                ));
+                self
+            }
+        }
+    }
+
+    /// Add the key and values from `match_value` to this condition.
+    pub fn matching(mut self, match_value: MatchBuilder) -> Self {
+        self.matches
+            .extend(match_value.condition_key, match_value.values);
+        self
+    }
+
+    /// Build the condition, returning an error if any tagged key was not valid, or if no
+    /// values were added.
+    pub fn build(self) -> Result<Condition, BuildError> {
+        if let Some(key) = self.invalid.into_iter().next() {
+            BuildError::Format(unexpected_value_for_type(QUALIFIED_NAME_NAME, key)).into()
+        } else if self.matches.is_empty() {
+            BuildError::Format(empty_vector_property(CONDITION_NAME)).into()
+        } else {
+            Ok(Condition::new_match(self.operator, self.matches))
+        }
     }
 }
 
 // ------------------------------------------------------------------------------------------------
 
+impl From<MatchBuilder> for Match {
+    fn from(builder: MatchBuilder) -> Self {
+        Match::new(builder.condition_key, builder.values)
+    }
+}
+
 impl MatchBuilder {
     pub fn new(condition_key: QualifiedName, values: Vec<ConditionValue>) -> Self {
         Self {
@@ -107,30 +292,26 @@ impl MatchBuilder {
     }
 
     pub fn aws_called_via(values: Vec<ConditionValue>) -> Self {
-        Self::new(condition::aws_called_via(), values)
+        Self::new(GlobalConditionKey::CalledVia.into(), values)
     }
 
     pub fn aws_called_via_first(value: ConditionValue) -> Self {
-        Self::new(condition::aws_called_via_first(), vec![value])
+        Self::new(GlobalConditionKey::CalledViaFirst.into(), vec![value])
     }
 
     pub fn aws_called_via_last(value: ConditionValue) -> Self {
-        // type: String
-        // single-valued
+        Self::new(GlobalConditionKey::CalledViaLast.into(), vec![value])
     }
 
     pub fn aws_current_time(value: ConditionValue) -> Self {
-        // type: Date
-        // single-valued
+        Self::new(GlobalConditionKey::CurrentTime.into(), vec![value])
     }
 
     pub fn aws_epoch_time(value: ConditionValue) -> Self {
-        // type: Date or Number
-        // single-valued
+        Self::new(GlobalConditionKey::EpochTime.into(), vec![value])
     }
 
     pub fn aws_federated_provider(value: ConditionValue) -> Self {
-        // type: String
-        // single-valued
+        Self::new(GlobalConditionKey::FederatedProvider.into(), vec![value])
     }
 }
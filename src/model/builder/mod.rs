@@ -11,24 +11,41 @@ use std::io::stdout;
 
 let policy: Policy = PolicyBuilder::new()
     .named("confidential-data-access")
-    .evaluate_statement(
+    .evaluate(
         StatementBuilder::new()
             .auto_named()
             .allows()
-            .unspecified_principals()
-            .may_perform_actions(vec!["s3:List*", "s3:Get*"])
-            .on_resources(vec![
-                "arn:aws:s3:::confidential-data",
-                "arn:aws:s3:::confidential-data/_*",
-            ])
+            .actions(ActionBuilder::any_of().these(vec![
+                "s3:List*".parse().unwrap(),
+                "s3:Get*".parse().unwrap(),
+            ]))
+            .resources(ResourceBuilder::any_of().these(vec![
+                ResourceEntry::parse("arn:aws:s3:::confidential-data", Default::default()).unwrap(),
+                ResourceEntry::parse("arn:aws:s3:::confidential-data/_*", Default::default()).unwrap(),
+            ]))
             .if_condition(
                 ConditionBuilder::new_bool()
-                    .right_hand_bool("aws:MultiFactorAuthPresent", true)
+                    .right_hand_bool("aws:MultiFactorAuthPresent".parse().unwrap(), true)
                     .if_exists(),
             ),
     )
-    .into();
-write_to_writer(stdout(), &policy);
+    .build()
+    .unwrap();
+write_to_writer(stdout(), &policy, true).unwrap();
+```
+
+Each builder has a `build` method that returns an error, rather than panicking, if the value
+being built is incomplete; for example a statement without any action, or a policy without
+any statements.
+
+```rust
+use aws_iam::error::BuildError;
+use aws_iam::model::builder::*;
+
+let result = PolicyBuilder::new()
+    .evaluate(StatementBuilder::new().allows())
+    .build();
+assert!(matches!(result, Err(BuildError::Statement { index: 0, .. })));
 ```
 */
 
@@ -68,29 +85,38 @@ pub use condition::{ConditionBuilder, MatchBuilder};
 mod tests {
     use super::*;
     use crate::io::write_to_writer;
+    use crate::model::{Policy, ResourceEntry};
     use std::io::stdout;
 
     #[test]
     fn test_simple_builder() {
         let policy: Policy = PolicyBuilder::new()
             .named("confidential-data-access")
-            .evaluate_statement(
+            .evaluate(
                 StatementBuilder::new()
                     .auto_named()
                     .allows()
-                    .unspecified_principals()
-                    .may_perform_actions(vec!["s3:List*", "s3:Get*"])
-                    .on_resources(vec![
-                        "arn:aws:s3:::confidential-data",
-                        "arn:aws:s3:::confidential-data/*",
-                    ])
+                    .actions(ActionBuilder::any_of().these(vec![
+                        "s3:List*".parse().unwrap(),
+                        "s3:Get*".parse().unwrap(),
+                    ]))
+                    .resources(ResourceBuilder::any_of().these(vec![
+                        ResourceEntry::parse("arn:aws:s3:::confidential-data", Default::default())
+                            .unwrap(),
+                        ResourceEntry::parse(
+                            "arn:aws:s3:::confidential-data/*",
+                            Default::default(),
+                        )
+                        .unwrap(),
+                    ]))
                     .if_condition(
                         ConditionBuilder::new_bool()
-                            .right_hand_bool("aws:MultiFactorAuthPresent", true)
+                            .right_hand_bool("aws:MultiFactorAuthPresent".parse().unwrap(), true)
                             .if_exists(),
                     ),
             )
-            .into();
-        write_to_writer(stdout(), &policy).expect("well that was unexpected");
+            .build()
+            .unwrap();
+        write_to_writer(stdout(), &policy, true).expect("well that was unexpected");
     }
 }
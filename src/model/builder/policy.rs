@@ -1,5 +1,7 @@
 use super::StatementBuilder;
-use crate::model::{Policy, Statement, Version};
+use crate::error::BuildError;
+use crate::model::{id, Policy, Statement, Version};
+use std::convert::TryFrom;
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
@@ -8,36 +10,42 @@ use crate::model::{Policy, Statement, Version};
 ///
 /// The top-level `Policy` builder.
 ///
+/// A policy must include at least one statement, so `build` will return
+/// `BuildError::NoStatements` if `evaluate` or `evaluate_all` were never called. Any error
+/// building an individual statement is reported as `BuildError::Statement` with the index
+/// of the failing statement.
+///
 #[derive(Debug, Default)]
 pub struct PolicyBuilder {
     version: Option<Version>,
     id: Option<String>,
-    statements: Vec<Statement>,
+    statements: Vec<StatementBuilder>,
 }
 
 // ------------------------------------------------------------------------------------------------
 // Implementations
 // ------------------------------------------------------------------------------------------------
 
-impl From<PolicyBuilder> for Policy {
-    fn from(builder: PolicyBuilder) -> Self {
-        match (builder.id, builder.version) {
-            (None, None) => Policy::unnamed(builder.statements),
-            (None, Some(version)) => Policy::unnamed_with_version(builder.statements, version),
-            (Some(id), None) => Policy::named(id, builder.statements),
-            (Some(id), Some(version)) => {
-                Policy::named_with_version(id, builder.statements, version)
-            }
-        }
-        .expect("Could not create new Policy")
+impl TryFrom<PolicyBuilder> for Policy {
+    type Error = BuildError;
+
+    fn try_from(builder: PolicyBuilder) -> Result<Self, Self::Error> {
+        builder.build()
     }
 }
 
 impl PolicyBuilder {
+    /// Create a new, empty, policy builder
+    pub fn new() -> Self {
+        Default::default()
+    }
+
     /// Set the version of this policy.
     pub fn for_version(self, version: Version) -> Self {
-        self.version = Some(version);
-        self
+        Self {
+            version: Some(version),
+            ..self
+        }
     }
 
     /// Set the id of this policy
@@ -45,36 +53,53 @@ impl PolicyBuilder {
     where
         S: Into<String>,
     {
-        self.id = Some(id.into());
-        self
+        Self {
+            id: Some(id.into()),
+            ..self
+        }
     }
 
     /// Set the id of this policy to a randomly generate value.
-    pub fn auto_name(self) -> Self {
-        self.id = Some(random_id());
-        self
+    pub fn auto_named(self) -> Self {
+        Self {
+            id: Some(id::new_external_id()),
+            ..self
+        }
     }
 
     /// Add a statement to this policy.
-    pub fn evaluate(self, statement: StatementBuilder) -> Self {
-        self.statements.push(statement.into());
+    pub fn evaluate(mut self, statement: StatementBuilder) -> Self {
+        self.statements.push(statement);
         self
     }
 
     /// Add a list of statements to this policy.
-    pub fn evaluate_all(self, statements: Vec<StatementBuilder>) -> Self {
-        let statements: Vec<Statement> = statements.into_iter().map(|sb| sb.into()).collect();
+    pub fn evaluate_all(mut self, statements: Vec<StatementBuilder>) -> Self {
         self.statements.extend(statements);
         self
     }
-}
-
-// ------------------------------------------------------------------------------------------------
-// Private Functions
-// ------------------------------------------------------------------------------------------------
 
-#[inline]
-fn random_id() -> String {
-    let id = uuid::Uuid::new_v4();
-    id.to_string()
+    /// Build the policy, returning an error if it, or any statement, is incomplete.
+    pub fn build(self) -> Result<Policy, BuildError> {
+        if self.statements.is_empty() {
+            return BuildError::NoStatements.into();
+        }
+        let statements = self
+            .statements
+            .into_iter()
+            .enumerate()
+            .map(|(index, statement)| {
+                statement.build().map_err(|e| BuildError::Statement {
+                    index,
+                    source: Box::new(e),
+                })
+            })
+            .collect::<Result<Vec<Statement>, BuildError>>()?;
+        Ok(match (self.id, self.version) {
+            (None, None) => Policy::unnamed(statements),
+            (None, Some(version)) => Policy::unnamed_with_version(statements, version),
+            (Some(id), None) => Policy::named(id, statements),
+            (Some(id), Some(version)) => Policy::named_with_version(id, statements, version),
+        }?)
+    }
 }
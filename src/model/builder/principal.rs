@@ -1,18 +1,25 @@
-use aws_arn::ARN;
-
-use crate::model::{CanonicalUserId, HostName, OrAny, Principal, PrincipalMap, ServiceName};
+use crate::error::{empty_vector_property, unexpected_value_for_type, BuildError};
+use crate::model::{
+    CanonicalUserId, HostName, OrAny, Principal, PrincipalMap, ResourceEntry, ServiceName,
+};
+use crate::syntax::{HOST_NAME_NAME, PRINCIPAL_NAME, SERVICE_NAME_NAME};
+use std::str::FromStr;
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
 // ------------------------------------------------------------------------------------------------
 
 ///
-/// A `Principal` builder, used with `StatementBuilder::principal()`.
+/// A `Principal` builder, used with `StatementBuilder::principals()`.
+///
+/// The `service` and `federated` helpers accept string values; any that cannot be parsed are
+/// reported as an error by `build` rather than when they are added.
 ///
 #[derive(Clone, Debug)]
 pub struct PrincipalBuilder {
     not_principal: bool,
     principals: OrAny<PrincipalMap>,
+    invalid: Vec<(&'static str, String)>,
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -24,16 +31,7 @@ impl Default for PrincipalBuilder {
         Self {
             not_principal: false,
             principals: OrAny::Any,
-        }
-    }
-}
-
-impl From<PrincipalBuilder> for Principal {
-    fn from(builder: PrincipalBuilder) -> Self {
-        if builder.not_principal {
-            Principal::NotPrincipal(builder.principals)
-        } else {
-            Principal::Principal(builder.principals)
+            invalid: Default::default(),
         }
     }
 }
@@ -43,6 +41,7 @@ impl PrincipalBuilder {
         Self {
             not_principal: false,
             principals: OrAny::Any,
+            invalid: Default::default(),
         }
     }
 
@@ -50,6 +49,7 @@ impl PrincipalBuilder {
         Self {
             not_principal: false,
             principals: OrAny::Some(Default::default()),
+            invalid: Default::default(),
         }
     }
 
@@ -57,6 +57,7 @@ impl PrincipalBuilder {
         Self {
             not_principal: true,
             principals: OrAny::Any,
+            invalid: Default::default(),
         }
     }
 
@@ -64,17 +65,84 @@ impl PrincipalBuilder {
         Self {
             not_principal: true,
             principals: OrAny::Some(Default::default()),
+            invalid: Default::default(),
+        }
+    }
+
+    /// A builder for the single **AWS** principal `principal`, an account or IAM identity ARN.
+    pub fn aws<A>(principal: A) -> Self
+    where
+        A: Into<ResourceEntry>,
+    {
+        Self::any_of().this_aws(principal)
+    }
+
+    ///
+    /// A builder for the single **Service** principal `service`, either the short name
+    /// (`"lambda"`) or the full service principal (`"lambda.amazonaws.com"`).
+    ///
+    pub fn service<S>(service: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        Self::any_of().with_service(service)
+    }
+
+    ///
+    /// A builder for the single **Federated** principal `provider`, the host name of a web
+    /// identity provider such as `"cognito-identity.amazonaws.com"`.
+    ///
+    pub fn federated<S>(provider: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        Self::any_of().with_federated(provider)
+    }
+
+    /// Adds the **Service** principal `service`, parsed from a string.
+    pub fn with_service<S>(mut self, service: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        let service = service.as_ref();
+        match ServiceName::from_str(service) {
+            Ok(service) => self.this_service(service),
+            Err(_) => {
+                self.invalid.push((SERVICE_NAME_NAME, service.to_string()));
+                self
+            }
+        }
+    }
+
+    /// Adds the **Federated** principal `provider`, parsed from a string.
+    pub fn with_federated<S>(mut self, provider: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        let provider = provider.as_ref();
+        match HostName::from_str(provider) {
+            Ok(provider) => self.this_federated(provider),
+            Err(_) => {
+                self.invalid.push((HOST_NAME_NAME, provider.to_string()));
+                self
+            }
         }
     }
 
     /// Sets the **AWS** principal of this statement to be only this value.
-    pub fn this_aws(self, principal: ARN) -> Self {
+    pub fn this_aws<A>(self, principal: A) -> Self
+    where
+        A: Into<ResourceEntry>,
+    {
         self.these_aws(vec![principal])
     }
 
     /// Sets the **AWS** principal of this statement to be any of these values.
-    pub fn these_aws(self, principals: Vec<ARN>) -> Self {
-        if let OrAny::Some(principal_map) = self.principals {
+    pub fn these_aws<A>(mut self, principals: Vec<A>) -> Self
+    where
+        A: Into<ResourceEntry>,
+    {
+        if let OrAny::Some(principal_map) = &mut self.principals {
             principal_map.extend_aws(principals)
         }
         self
@@ -86,8 +154,8 @@ impl PrincipalBuilder {
     }
 
     /// Sets the **Federated** principal of this statement to be any of these values.
-    pub fn these_federated(self, principals: Vec<HostName>) -> Self {
-        if let OrAny::Some(principal_map) = self.principals {
+    pub fn these_federated(mut self, principals: Vec<HostName>) -> Self {
+        if let OrAny::Some(principal_map) = &mut self.principals {
             principal_map.extend_federated(principals)
         }
         self
@@ -99,8 +167,8 @@ impl PrincipalBuilder {
     }
 
     /// Sets the **Service** principal of this statement to be any of these values.
-    pub fn these_service(self, principals: Vec<ServiceName>) -> Self {
-        if let OrAny::Some(principal_map) = self.principals {
+    pub fn these_service(mut self, principals: Vec<ServiceName>) -> Self {
+        if let OrAny::Some(principal_map) = &mut self.principals {
             principal_map.extend_services(principals)
         }
         self
@@ -112,10 +180,26 @@ impl PrincipalBuilder {
     }
 
     /// Sets the **Canonical User** principal of this statement to be any of these values.
-    pub fn these_canonical_user(self, principals: Vec<CanonicalUserId>) -> Self {
-        if let OrAny::Some(principal_map) = self.principals {
+    pub fn these_canonical_user(mut self, principals: Vec<CanonicalUserId>) -> Self {
+        if let OrAny::Some(principal_map) = &mut self.principals {
             principal_map.extend_canonical_users(principals)
         }
         self
     }
+
+    /// Build the principal, returning an error if any string value could not be parsed, or
+    /// if `any_of` or `none_of` were used without adding any principals.
+    pub fn build(self) -> Result<Principal, BuildError> {
+        if let Some((type_name, value)) = self.invalid.into_iter().next() {
+            return BuildError::Format(unexpected_value_for_type(type_name, value)).into();
+        }
+        if matches!(&self.principals, OrAny::Some(principals) if principals.is_empty()) {
+            return BuildError::Format(empty_vector_property(PRINCIPAL_NAME)).into();
+        }
+        Ok(if self.not_principal {
+            Principal::NotPrincipal(self.principals)
+        } else {
+            Principal::Principal(self.principals)
+        })
+    }
 }
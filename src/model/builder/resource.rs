@@ -1,17 +1,18 @@
-use crate::model::{OrAny, Resource};
-use aws_arn::ARN;
+use crate::error::{empty_vector_property, BuildError};
+use crate::model::{OrAny, Resource, ResourceEntry};
+use crate::syntax::RESOURCE_NAME;
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
 // ------------------------------------------------------------------------------------------------
 
 ///
-/// A `Resource` builder, used with `StatementBuilder::resource()`.
+/// A `Resource` builder, used with `StatementBuilder::resources()`.
 ///
 #[derive(Clone, Debug)]
 pub struct ResourceBuilder {
     not_resource: bool,
-    resources: OrAny<Vec<ARN>>,
+    resources: OrAny<Vec<ResourceEntry>>,
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -27,16 +28,6 @@ impl Default for ResourceBuilder {
     }
 }
 
-impl From<ResourceBuilder> for Resource {
-    fn from(builder: ResourceBuilder) -> Self {
-        if builder.not_resource {
-            Resource::NotResource(builder.resources)
-        } else {
-            Resource::Resource(builder.resources)
-        }
-    }
-}
-
 impl ResourceBuilder {
     pub fn any() -> Self {
         Self {
@@ -55,28 +46,46 @@ impl ResourceBuilder {
     pub fn any_of() -> Self {
         Self {
             not_resource: false,
-            resources: OrAny::Any,
+            resources: OrAny::Some(Default::default()),
         }
     }
 
     pub fn none_of() -> Self {
         Self {
             not_resource: true,
-            resources: OrAny::Any,
+            resources: OrAny::Some(Default::default()),
         }
     }
 
-    /// Sets the action of this statement to be only this value.
-    pub fn this(self, resource: ARN) -> Self {
-        self.these(vec![resource]);
-        self
+    /// Sets the resource of this statement to be only this value.
+    pub fn this<R>(self, resource: R) -> Self
+    where
+        R: Into<ResourceEntry>,
+    {
+        self.these(vec![resource])
     }
 
-    /// Sets the action of this statement to be any of these values.
-    pub fn these(self, resources: Vec<ARN>) -> Self {
-        if let OrAny::Some(resource_vec) = self.resources {
-            resource_vec.extend(resources);
+    /// Sets the resource of this statement to be any of these values.
+    pub fn these<R>(mut self, resources: Vec<R>) -> Self
+    where
+        R: Into<ResourceEntry>,
+    {
+        if let OrAny::Some(resource_vec) = &mut self.resources {
+            resource_vec.extend(resources.into_iter().map(R::into));
         }
         self
     }
+
+    /// Build the resource, returning an error if `any_of` or `none_of` were used without
+    /// adding any resources.
+    pub fn build(self) -> Result<Resource, BuildError> {
+        if matches!(&self.resources, OrAny::Some(resources) if resources.is_empty()) {
+            return BuildError::Format(empty_vector_property(RESOURCE_NAME)).into();
+        }
+        Ok(if self.not_resource {
+            Resource::NotResource(self.resources)
+        } else {
+            Resource::Resource(self.resources)
+        })
+    }
 }
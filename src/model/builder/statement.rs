@@ -1,25 +1,31 @@
 use super::{ActionBuilder, ConditionBuilder, PrincipalBuilder, ResourceBuilder};
-use crate::model::{Effect, Statement};
+use crate::error::BuildError;
+use crate::model::{Condition, Effect, ResourceEntry, Sid, Statement};
+use std::convert::TryFrom;
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
 // ------------------------------------------------------------------------------------------------
 
 ///
-/// A `Statement` builder, used with `PolicyBuilder::evaluate_statement()`.
+/// A `Statement` builder, used with `PolicyBuilder::evaluate()`.
+///
+/// A statement must include an action, so `build` will return `BuildError::MissingAction` if
+/// `actions` was never called. If `resources` is never called the statement has no `Resource`
+/// element, which is only valid in resource-based policies.
 ///
 #[derive(Clone, Debug)]
 pub struct StatementBuilder {
-    sid: Option<String>,
+    sid: Option<Sid>,
     effect: Effect,
 
     principals: Option<PrincipalBuilder>,
 
-    actions: ActionBuilder,
+    actions: Option<ActionBuilder>,
 
-    resources: ResourceBuilder,
+    resources: Option<ResourceBuilder>,
 
-    condition: ConditionBuilder,
+    conditions: Vec<ConditionBuilder>,
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -34,21 +40,16 @@ impl Default for StatementBuilder {
             principals: Default::default(),
             actions: Default::default(),
             resources: Default::default(),
-            condition: Default::default(),
+            conditions: Default::default(),
         }
     }
 }
 
-impl From<StatementBuilder> for Statement {
-    fn from(builder: StatementBuilder) -> Self {
-        Statement {
-            sid: builder.sid.clone(),
-            principal: builder.principals.map(|builder| builder.into()),
-            effect: builder.effect.clone(),
-            action: builder.actions.into(),
-            resource: builder.resources.into(),
-            condition: builder.condition.clone(),
-        }
+impl TryFrom<StatementBuilder> for Statement {
+    type Error = BuildError;
+
+    fn try_from(builder: StatementBuilder) -> Result<Self, Self::Error> {
+        builder.build()
     }
 }
 
@@ -59,57 +60,125 @@ impl StatementBuilder {
     }
 
     /// Set the id of this statement
-    pub fn named(self, sid: &str) -> Self {
-        self.sid = Some(sid.to_string());
-        self
+    pub fn named(self, sid: Sid) -> Self {
+        Self {
+            sid: Some(sid),
+            ..self
+        }
     }
 
     /// Set the id of this statement to a randomly generate value.
-    pub fn auto_name(self) -> Self {
-        self.sid = Some(random_id());
-        self
+    pub fn auto_named(self) -> Self {
+        Self {
+            sid: Some(Sid::new_random()),
+            ..self
+        }
     }
 
     /// Set the effect of this statement to `Allow`.
     pub fn allows(self) -> Self {
-        self.effect = Effect::Allow;
-        self
+        Self {
+            effect: Effect::Allow,
+            ..self
+        }
     }
 
     /// Set the effect of this statement to `Deny`.
     pub fn does_not_allow(self) -> Self {
-        self.effect = Effect::Deny;
-        self
+        Self {
+            effect: Effect::Deny,
+            ..self
+        }
     }
 
     pub fn principals(self, principals: PrincipalBuilder) -> Self {
-        self.principals = Some(principals);
-        self
+        Self {
+            principals: Some(principals),
+            ..self
+        }
+    }
+
+    /// Adds the **AWS** principal `principal` to the principals of this statement.
+    pub fn principal_aws<A>(self, principal: A) -> Self
+    where
+        A: Into<ResourceEntry>,
+    {
+        self.update_principals(|principals| principals.this_aws(principal))
+    }
+
+    /// Adds the **Service** principal `service`, such as `"lambda"`, to the principals of this
+    /// statement.
+    pub fn principal_service<S>(self, service: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.update_principals(|principals| principals.with_service(service))
+    }
+
+    /// Adds the **Federated** principal `provider` to the principals of this statement.
+    pub fn federated<S>(self, provider: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.update_principals(|principals| principals.with_federated(provider))
     }
 
     pub fn actions(self, actions: ActionBuilder) -> Self {
-        self.actions = actions;
-        self
+        Self {
+            actions: Some(actions),
+            ..self
+        }
     }
 
     pub fn resources(self, resources: ResourceBuilder) -> Self {
-        self.resources = resources;
-        self
+        Self {
+            resources: Some(resources),
+            ..self
+        }
     }
 
-    /// Adds this condition to the statement.
-    pub fn if_condition(self, condition: ConditionBuilder) -> Self {
-        self.condition = condition;
+    ///
+    /// Adds this condition to the statement; conditions with different operators are combined
+    /// into a single `Condition` element, all of which must be true.
+    ///
+    pub fn if_condition(mut self, condition: ConditionBuilder) -> Self {
+        self.conditions.push(condition);
         self
     }
-}
 
-// ------------------------------------------------------------------------------------------------
-// Private Functions
-// ------------------------------------------------------------------------------------------------
+    /// Build the statement, returning an error if it is incomplete.
+    pub fn build(self) -> Result<Statement, BuildError> {
+        let action = match self.actions {
+            None => return BuildError::MissingAction.into(),
+            Some(actions) => actions.build()?,
+        };
+        let mut condition: Option<Condition> = None;
+        for builder in self.conditions {
+            let built = builder.build()?;
+            match &mut condition {
+                None => condition = Some(built),
+                Some(existing) => existing.merge(built),
+            }
+        }
+        Ok(Statement {
+            sid: self.sid,
+            principal: self.principals.map(PrincipalBuilder::build).transpose()?,
+            effect: self.effect,
+            action,
+            resource: self.resources.map(ResourceBuilder::build).transpose()?,
+            condition,
+        })
+    }
 
-#[inline]
-fn random_id() -> String {
-    let id = uuid::Uuid::new_v4();
-    id.to_string()
+    fn update_principals<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(PrincipalBuilder) -> PrincipalBuilder,
+    {
+        let principals = self
+            .principals
+            .take()
+            .unwrap_or_else(PrincipalBuilder::any_of);
+        self.principals = Some(f(principals));
+        self
+    }
 }
@@ -0,0 +1,175 @@
+/*!
+Provides the `Comments` side-table, which holds the annotations of a policy document.
+
+IAM policy documents have no syntax for comments, and AWS rejects unknown elements, so this
+crate uses the extension element `x-comment` for a string annotation on the policy or on any of
+its statements. The parser accepts, and ignores, the element; the comments of a document are
+read into a separate `Comments` value with `io::read_from_string_with_comments`, and are written
+back, beside the `Sid` of each statement, with `io::to_string_with_comments`. The plain writers
+strip them, producing a document AWS accepts.
+
+# Example
+
+```rust
+use aws_iam::io::{read_from_string_with_comments, to_string, to_string_with_comments};
+
+let (policy, comments) = read_from_string_with_comments(r#"{
+  "x-comment": "Owned by the data team",
+  "Statement": [{
+    "Sid": "ReadReports",
+    "x-comment": "Reports are public within the account",
+    "Effect": "Allow",
+    "Action": "s3:GetObject",
+    "Resource": "arn:aws:s3:::reports/2020.csv"
+  }]
+}"#)
+.unwrap();
+
+assert_eq!(comments.policy(), Some("Owned by the data team"));
+assert_eq!(comments.statement(0), Some("Reports are public within the account"));
+assert!(!to_string(&policy, false).unwrap().contains("x-comment"));
+assert!(to_string_with_comments(&policy, &comments, false).unwrap().contains("x-comment"));
+```
+*/
+
+use crate::error::{type_mismatch, IamFormatError};
+use crate::syntax::{
+    json_type_name, COMMENT_NAME, JSON_TYPE_NAME_STRING, SID_NAME, STATEMENT_NAME,
+};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The comments of a policy document, on the policy itself and on its statements, which are
+/// identified by their index.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Comments {
+    policy: Option<String>,
+    statements: BTreeMap<usize, String>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Comments {
+    ///
+    /// Read the `x-comment` elements of the policy document `value`; returns an error if a
+    /// comment is not a string.
+    ///
+    pub fn from_json(value: &Value) -> Result<Self, IamFormatError> {
+        let mut comments = Self::default();
+        if let Value::Object(object) = value {
+            comments.policy = comment(object)?;
+            if let Some(Value::Array(statements)) = object.get(STATEMENT_NAME) {
+                for (index, statement) in statements.iter().enumerate() {
+                    if let Value::Object(statement) = statement {
+                        if let Some(comment) = comment(statement)? {
+                            let _ = comments.statements.insert(index, comment);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(comments)
+    }
+
+    pub fn with_policy_comment<S>(self, comment: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            policy: Some(comment.into()),
+            ..self
+        }
+    }
+
+    pub fn with_statement_comment<S>(self, index: usize, comment: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut self_mut = self;
+        let _ = self_mut.statements.insert(index, comment.into());
+        self_mut
+    }
+
+    /// The comment on the policy, if any.
+    pub fn policy(&self) -> Option<&str> {
+        self.policy.as_deref()
+    }
+
+    /// The comment on the statement at `index`, if any.
+    pub fn statement(&self, index: usize) -> Option<&str> {
+        self.statements.get(&index).map(String::as_str)
+    }
+
+    /// The index, and comment, of each statement with a comment.
+    pub fn statements(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.statements
+            .iter()
+            .map(|(index, comment)| (*index, comment.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.policy.is_none() && self.statements.is_empty()
+    }
+
+    ///
+    /// Add these comments to the policy document `value`, such as that returned by
+    /// `Policy::to_json`. A statement comment follows the statement's `Sid`, or is its first
+    /// element if it has none.
+    ///
+    pub fn annotate(&self, value: &mut Value) {
+        if let Value::Object(object) = value {
+            if let Some(comment) = &self.policy {
+                annotate(object, comment);
+            }
+            if let Some(Value::Array(statements)) = object.get_mut(STATEMENT_NAME) {
+                for (index, comment) in &self.statements {
+                    if let Some(Value::Object(statement)) = statements.get_mut(*index) {
+                        annotate(statement, comment);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn comment(object: &Map<String, Value>) -> Result<Option<String>, IamFormatError> {
+    match object.get(COMMENT_NAME) {
+        None => Ok(None),
+        Some(Value::String(comment)) => Ok(Some(comment.to_string())),
+        Some(value) => {
+            type_mismatch(COMMENT_NAME, JSON_TYPE_NAME_STRING, json_type_name(value)).into()
+        }
+    }
+}
+
+///
+/// Objects keep the order in which their keys were inserted, and so the object is rebuilt to
+/// place the comment first, or after the `Sid`.
+///
+fn annotate(object: &mut Map<String, Value>, comment: &str) {
+    let _ = object.remove(COMMENT_NAME);
+    let entries = std::mem::take(object);
+    let mut inserted = false;
+    for (key, value) in entries {
+        if !inserted && key != SID_NAME {
+            let _ = object.insert(COMMENT_NAME.to_string(), Value::String(comment.to_string()));
+            inserted = true;
+        }
+        let _ = object.insert(key, value);
+    }
+    if !inserted {
+        let _ = object.insert(COMMENT_NAME.to_string(), Value::String(comment.to_string()));
+    }
+}
@@ -77,51 +77,63 @@ Instances.
 
  */
 
-use crate::error::{type_mismatch, unexpected_value_for_type, IamFormatError};
+use super::{id, variables};
+use crate::arn::{
+    retarget_arn_str, rewrite_account_str, AccountIdentifier, ArnParseMode, Partition, Region,
+};
+use crate::error::{
+    conflicting_condition, empty_vector_property, missing_property, type_mismatch,
+    unexpected_value_for_type, IamError, IamFormatError,
+};
 use crate::model::QualifiedName;
 use crate::syntax::{
-    display_vec_map_to_json, json_type_name, string_vec_from_json, IamProperty, IamValue,
-    CONDITION_NAME, CONDITION_OPERATOR_ARN_EQUALS, CONDITION_OPERATOR_ARN_LIKE,
-    CONDITION_OPERATOR_ARN_NOT_EQUALS, CONDITION_OPERATOR_ARN_NOT_LIKE,
-    CONDITION_OPERATOR_BINARY_EQUALS, CONDITION_OPERATOR_BOOL, CONDITION_OPERATOR_DATE_EQUALS,
-    CONDITION_OPERATOR_DATE_GREATER_THAN, CONDITION_OPERATOR_DATE_GREATER_THAN_EQUALS,
-    CONDITION_OPERATOR_DATE_LESS_THAN, CONDITION_OPERATOR_DATE_LESS_THAN_EQUALS,
-    CONDITION_OPERATOR_DATE_NOT_EQUALS, CONDITION_OPERATOR_IP_ADDRESS,
-    CONDITION_OPERATOR_NOT_IP_ADDRESS, CONDITION_OPERATOR_NULL, CONDITION_OPERATOR_NUMERIC_EQUALS,
-    CONDITION_OPERATOR_NUMERIC_GREATER_THAN, CONDITION_OPERATOR_NUMERIC_GREATER_THAN_EQUALS,
-    CONDITION_OPERATOR_NUMERIC_LESS_THAN, CONDITION_OPERATOR_NUMERIC_LESS_THAN_EQUALS,
-    CONDITION_OPERATOR_NUMERIC_NOT_EQUALS, CONDITION_OPERATOR_STRING_EQUALS,
-    CONDITION_OPERATOR_STRING_EQUALS_IGNORE_CASE, CONDITION_OPERATOR_STRING_LIKE,
-    CONDITION_OPERATOR_STRING_NOT_EQUALS, CONDITION_OPERATOR_STRING_NOT_EQUALS_IGNORE_CASE,
-    CONDITION_OPERATOR_STRING_NOT_LIKE, CONDITION_QUANTIFIER_FOR_ALL, CONDITION_QUANTIFIER_FOR_ANY,
-    CONDITION_QUANTIFIER_IF_EXISTS, CONDITION_VALUE_NAME, GLOBAL_CONDITION_KEY_CALLED_VIA,
-    GLOBAL_CONDITION_KEY_CALLED_VIA_FIRST, GLOBAL_CONDITION_KEY_CALLED_VIA_LAST,
-    GLOBAL_CONDITION_KEY_CURRENT_TIME, GLOBAL_CONDITION_KEY_EPOCH_TIME,
-    GLOBAL_CONDITION_KEY_FEDERATED_PROVIDER, GLOBAL_CONDITION_KEY_MULTIFACTOR_AUTH_AGE,
-    GLOBAL_CONDITION_KEY_MULTIFACTOR_AUTH_PRESENT, GLOBAL_CONDITION_KEY_NAMESPACE,
-    GLOBAL_CONDITION_KEY_PRINCIPAL_ACCOUNT, GLOBAL_CONDITION_KEY_PRINCIPAL_ARN,
-    GLOBAL_CONDITION_KEY_PRINCIPAL_IS_AWS_SERVICE, GLOBAL_CONDITION_KEY_PRINCIPAL_ORG_ID,
-    GLOBAL_CONDITION_KEY_PRINCIPAL_ORG_PATHS, GLOBAL_CONDITION_KEY_PRINCIPAL_SERVICE_NAME,
-    GLOBAL_CONDITION_KEY_PRINCIPAL_SERVICE_NAMES_LIST, GLOBAL_CONDITION_KEY_PRINCIPAL_TAG,
-    GLOBAL_CONDITION_KEY_PRINCIPAL_TYPE, GLOBAL_CONDITION_KEY_REFERER,
-    GLOBAL_CONDITION_KEY_REQUESTED_REGION, GLOBAL_CONDITION_KEY_REQUEST_TAG,
-    GLOBAL_CONDITION_KEY_RESOURCE_ACCOUNT, GLOBAL_CONDITION_KEY_RESOURCE_ORG_ID,
-    GLOBAL_CONDITION_KEY_RESOURCE_ORG_PATHS, GLOBAL_CONDITION_KEY_RESOURCE_TAG,
-    GLOBAL_CONDITION_KEY_SECURE_TRANSPORT, GLOBAL_CONDITION_KEY_SOURCE_ACCOUNT,
-    GLOBAL_CONDITION_KEY_SOURCE_ARN, GLOBAL_CONDITION_KEY_SOURCE_IDENTITY,
-    GLOBAL_CONDITION_KEY_SOURCE_IP, GLOBAL_CONDITION_KEY_SOURCE_VPC,
-    GLOBAL_CONDITION_KEY_SOURCE_VPCE, GLOBAL_CONDITION_KEY_TAG_KEYS,
-    GLOBAL_CONDITION_KEY_TOKEN_ISSUE_TIME, GLOBAL_CONDITION_KEY_USERID,
-    GLOBAL_CONDITION_KEY_USERNAME, GLOBAL_CONDITION_KEY_USER_AGENT,
-    GLOBAL_CONDITION_KEY_VIA_AWS_SERVICE, GLOBAL_CONDITION_KEY_VPC_SOURCE_IP,
-    JSON_TYPE_NAME_OBJECT, NAMESPACE_SEPARATOR,
+    json_type_name, property_from_fragment, property_to_fragment, value_from_fragment,
+    value_to_fragment, IamProperty, IamValue, CONDITION_NAME, CONDITION_OPERATOR_ARN_EQUALS,
+    CONDITION_OPERATOR_ARN_LIKE, CONDITION_OPERATOR_ARN_NOT_EQUALS,
+    CONDITION_OPERATOR_ARN_NOT_LIKE, CONDITION_OPERATOR_BINARY_EQUALS, CONDITION_OPERATOR_BOOL,
+    CONDITION_OPERATOR_DATE_EQUALS, CONDITION_OPERATOR_DATE_GREATER_THAN,
+    CONDITION_OPERATOR_DATE_GREATER_THAN_EQUALS, CONDITION_OPERATOR_DATE_LESS_THAN,
+    CONDITION_OPERATOR_DATE_LESS_THAN_EQUALS, CONDITION_OPERATOR_DATE_NOT_EQUALS,
+    CONDITION_OPERATOR_IP_ADDRESS, CONDITION_OPERATOR_NOT_IP_ADDRESS, CONDITION_OPERATOR_NULL,
+    CONDITION_OPERATOR_NUMERIC_EQUALS, CONDITION_OPERATOR_NUMERIC_GREATER_THAN,
+    CONDITION_OPERATOR_NUMERIC_GREATER_THAN_EQUALS, CONDITION_OPERATOR_NUMERIC_LESS_THAN,
+    CONDITION_OPERATOR_NUMERIC_LESS_THAN_EQUALS, CONDITION_OPERATOR_NUMERIC_NOT_EQUALS,
+    CONDITION_OPERATOR_STRING_EQUALS, CONDITION_OPERATOR_STRING_EQUALS_IGNORE_CASE,
+    CONDITION_OPERATOR_STRING_LIKE, CONDITION_OPERATOR_STRING_NOT_EQUALS,
+    CONDITION_OPERATOR_STRING_NOT_EQUALS_IGNORE_CASE, CONDITION_OPERATOR_STRING_NOT_LIKE,
+    CONDITION_QUANTIFIER_FOR_ALL, CONDITION_QUANTIFIER_FOR_ANY, CONDITION_QUANTIFIER_IF_EXISTS,
+    CONDITION_VALUE_NAME, GLOBAL_CONDITION_KEY_CALLED_VIA, GLOBAL_CONDITION_KEY_CALLED_VIA_FIRST,
+    GLOBAL_CONDITION_KEY_CALLED_VIA_LAST, GLOBAL_CONDITION_KEY_CURRENT_TIME,
+    GLOBAL_CONDITION_KEY_EPOCH_TIME, GLOBAL_CONDITION_KEY_FEDERATED_PROVIDER,
+    GLOBAL_CONDITION_KEY_MULTIFACTOR_AUTH_AGE, GLOBAL_CONDITION_KEY_MULTIFACTOR_AUTH_PRESENT,
+    GLOBAL_CONDITION_KEY_NAMESPACE, GLOBAL_CONDITION_KEY_PRINCIPAL_ACCOUNT,
+    GLOBAL_CONDITION_KEY_PRINCIPAL_ARN, GLOBAL_CONDITION_KEY_PRINCIPAL_IS_AWS_SERVICE,
+    GLOBAL_CONDITION_KEY_PRINCIPAL_ORG_ID, GLOBAL_CONDITION_KEY_PRINCIPAL_ORG_PATHS,
+    GLOBAL_CONDITION_KEY_PRINCIPAL_SERVICE_NAME, GLOBAL_CONDITION_KEY_PRINCIPAL_SERVICE_NAMES_LIST,
+    GLOBAL_CONDITION_KEY_PRINCIPAL_TAG, GLOBAL_CONDITION_KEY_PRINCIPAL_TYPE,
+    GLOBAL_CONDITION_KEY_REFERER, GLOBAL_CONDITION_KEY_REQUESTED_REGION,
+    GLOBAL_CONDITION_KEY_REQUEST_TAG, GLOBAL_CONDITION_KEY_RESOURCE_ACCOUNT,
+    GLOBAL_CONDITION_KEY_RESOURCE_ORG_ID, GLOBAL_CONDITION_KEY_RESOURCE_ORG_PATHS,
+    GLOBAL_CONDITION_KEY_RESOURCE_TAG, GLOBAL_CONDITION_KEY_SECURE_TRANSPORT,
+    GLOBAL_CONDITION_KEY_SOURCE_ACCOUNT, GLOBAL_CONDITION_KEY_SOURCE_ARN,
+    GLOBAL_CONDITION_KEY_SOURCE_IDENTITY, GLOBAL_CONDITION_KEY_SOURCE_IP,
+    GLOBAL_CONDITION_KEY_SOURCE_VPC, GLOBAL_CONDITION_KEY_SOURCE_VPCE,
+    GLOBAL_CONDITION_KEY_TAG_KEYS, GLOBAL_CONDITION_KEY_TOKEN_ISSUE_TIME,
+    GLOBAL_CONDITION_KEY_USERID, GLOBAL_CONDITION_KEY_USERNAME, GLOBAL_CONDITION_KEY_USER_AGENT,
+    GLOBAL_CONDITION_KEY_VIA_AWS_SERVICE, GLOBAL_CONDITION_KEY_VPC_SOURCE_IP, JSON_TYPE_NAME_ARRAY,
+    JSON_TYPE_NAME_OBJECT, JSON_TYPE_NAME_STRING, NAMESPACE_SEPARATOR,
 };
+use indexmap::IndexMap;
 use lazy_static::lazy_static;
-use regex::{Captures, Regex};
-use serde_json::{Map, Value};
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Number, Value};
+use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::fmt::Display;
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::iter::FromIterator;
+use std::net::IpAddr;
 use std::ops::Deref;
 use std::str::FromStr;
 
@@ -129,21 +141,62 @@ use std::str::FromStr;
 // Public Types
 // ------------------------------------------------------------------------------------------------
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct Condition(HashMap<Operator, Match>);
+///
+/// The condition element of a statement, a map from operator to the keys and values it
+/// tests. Operators are kept in the order they were added, or read from a policy, although
+/// equality and hashing do not depend on that order. An operator with a quantifier, such as
+/// `ForAnyValue:StringEquals`, is distinct from the same operator without one. Where a
+/// policy lists the same operator more than once their keys and values are combined.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition(IndexMap<Operator, Match>);
+
+///
+/// The condition keys, and their values, tested by a single operator. Keys are kept in the
+/// order they were added; equality and hashing do not depend on the order of keys, but do
+/// depend on the order of values. Where a policy lists the same key more than once for an
+/// operator their values are combined.
+///
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Match(IndexMap<QualifiedName, Vec<ConditionValue>>);
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct Match(HashMap<QualifiedName, Vec<ConditionValue>>);
+///
+/// A condition value, retained as the string from the policy. Numeric values are compared
+/// textually and so `"1"` and `"1.0"` are different values; as no floating point conversion
+/// is performed a value such as `"NaN"` is equal to itself and `Eq` and `Hash` are consistent.
+///
+/// Values may be written as JSON strings, numbers, or booleans, and a value is written back
+/// with the type it was read with, so that `{"s3:max-keys": 10}` is not rewritten as
+/// `{"s3:max-keys": "10"}`; the type does not affect equality, hashing, or ordering. A number
+/// is retained as it is written, so that `1.50` is not read as `1.5` and integers larger than
+/// an `f64` can represent exactly keep every digit.
+///
+#[derive(Debug, Default, Clone)]
+pub struct ConditionValue(String, ValueType);
 
-#[derive(Debug, Default, Clone, PartialEq)]
-pub struct ConditionValue(String);
+///
+/// A condition value that was read, as values are retained as strings, but which cannot be
+/// used by its operator; for example a `DateEquals` value that is neither an ISO 8601 date nor
+/// an epoch time. See `Condition::parse_warnings`.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    /// The operator the value is used with.
+    pub operator: Operator,
+    /// The condition key the value is tested against.
+    pub key: QualifiedName,
+    /// The value itself.
+    pub value: ConditionValue,
+    /// A description of the expected form of the value.
+    pub message: String,
+}
 
 ///
 /// Pulls apart the string form of an operator used by IAM. It identifies the
 /// quantifiers which are used as string prefixes and recognizes the _if exist_
 /// suffix as well.
 ///
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Operator {
     /// Used to test multiple keys or multiple values for a single key in a request.
     pub quantifier: Option<Quantifier>,
@@ -167,7 +220,7 @@ pub struct Operator {
 /// From [Creating a Condition with Multiple Keys or
 /// Values](https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_policies_multi-value-conditions.html).
 ///
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Quantifier {
     /// The condition **must** hold true for **all** values provided.
     ForAllValues,
@@ -186,7 +239,7 @@ pub enum Quantifier {
 /// From [IAM JSON Policy Elements: Condition
 /// Operators](https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_policies_elements_condition_operators.html).
 ///
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum GlobalOperator {
     // ----- String Condition Operators
     /// Exact matching, case sensitive
@@ -266,6 +319,11 @@ pub enum GlobalOperator {
     /// either true (the key doesn't exist — it is null) or false (the key
     /// exists and its value is not null).
     Null,
+    // ------ Other Condition Operators
+    /// An operator not known to this crate, such as a service-specific operator or one added
+    /// after this release; it is only read in `ArnParseMode::Permissive`, where it is retained,
+    /// and written, exactly as read but is reported by validation as it cannot be checked.
+    Other(String),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -330,16 +388,44 @@ pub enum IamConditionKey {
 // ------------------------------------------------------------------------------------------------
 
 impl Deref for Condition {
-    type Target = HashMap<Operator, Match>;
+    type Target = IndexMap<Operator, Match>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
+impl From<IndexMap<Operator, Match>> for Condition {
+    fn from(v: IndexMap<Operator, Match>) -> Self {
+        Self(v)
+    }
+}
+
 impl From<HashMap<Operator, Match>> for Condition {
     fn from(v: HashMap<Operator, Match>) -> Self {
-        Self(v)
+        Self(v.into_iter().collect())
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        property_to_fragment(self, f)
+    }
+}
+
+impl FromStr for Condition {
+    type Err = IamFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        property_from_fragment(s, CONDITION_NAME)
+    }
+}
+
+impl Hash for Condition {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut entries: Vec<(&Operator, &Match)> = self.0.iter().collect();
+        entries.sort_by(|lhs, rhs| lhs.0.cmp(rhs.0));
+        entries.hash(state);
     }
 }
 
@@ -361,22 +447,74 @@ impl IamProperty for Condition {
     where
         Self: Sized,
     {
+        Self::from_json_object_optional_with(value, ArnParseMode::default())
+    }
+
+    fn from_json_object(value: &Map<String, Value>) -> Result<Self, IamFormatError>
+    where
+        Self: Sized,
+    {
+        Self::from_json_object_with(value, ArnParseMode::default())
+    }
+}
+
+impl Condition {
+    ///
+    /// Parse a condition from the JSON object `s`, containing only the `Condition` element of a
+    /// statement, for example `{"Condition":{"Bool":{"aws:SecureTransport":"true"}}}`.
+    ///
+    pub fn from_json_str(s: &str) -> Result<Self, IamError> {
+        Ok(Self::from_str(s)?)
+    }
+
+    ///
+    /// Parse a condition from the JSON object `s`, as for `from_json_str`, parsing each operator
+    /// according to `mode`.
+    ///
+    pub fn from_json_str_with(s: &str, mode: ArnParseMode) -> Result<Self, IamError> {
+        let value: Value = serde_json::from_str(s)?;
+        if let Value::Object(object) = value {
+            Ok(Self::from_json_object_with(&object, mode)?)
+        } else {
+            Err(type_mismatch(
+                CONDITION_NAME,
+                JSON_TYPE_NAME_OBJECT,
+                json_type_name(&value),
+            )
+            .into())
+        }
+    }
+
+    ///
+    /// Read the `Condition` property from `value`, parsing each operator according to `mode`.
+    ///
+    pub fn from_json_object_with(
+        value: &Map<String, Value>,
+        mode: ArnParseMode,
+    ) -> Result<Self, IamFormatError> {
+        match Self::from_json_object_optional_with(value, mode)? {
+            Some(condition) => Ok(condition),
+            None => missing_property(CONDITION_NAME).into(),
+        }
+    }
+
+    ///
+    /// Read the `Condition` property, if present, from `value`, parsing each operator according
+    /// to `mode`.
+    ///
+    pub fn from_json_object_optional_with(
+        value: &Map<String, Value>,
+        mode: ArnParseMode,
+    ) -> Result<Option<Self>, IamFormatError> {
         if value.contains_key(CONDITION_NAME) {
             let value = value.get(CONDITION_NAME).unwrap();
             if let Value::Object(object) = value {
-                let results: Result<Vec<(Operator, Match)>, IamFormatError> = object
-                    .iter()
-                    .map(
-                        |(k, v)| match (Operator::from_str(k), Match::from_json(v)) {
-                            (Ok(k), Ok(v)) => Ok((k, v)),
-                            (Ok(_), Err(e)) => Err(e),
-                            (Err(e), Ok(_)) => Err(e),
-                            (Err(e), Err(_)) => Err(e),
-                        },
-                    )
-                    .collect();
-                let inner_object = HashMap::from_iter(results?.into_iter());
-                Ok(Some(Self(inner_object)))
+                let mut condition = Self(Default::default());
+                for (k, v) in object {
+                    let operator = Operator::parse(k, mode)?;
+                    condition.merge(Self::new_match(operator, Match::from_json(v)?));
+                }
+                Ok(Some(condition))
             } else {
                 type_mismatch(CONDITION_NAME, JSON_TYPE_NAME_OBJECT, json_type_name(value)).into()
             }
@@ -385,15 +523,6 @@ impl IamProperty for Condition {
         }
     }
 
-    fn from_json_object(_: &Map<String, Value>) -> Result<Self, IamFormatError>
-    where
-        Self: Sized,
-    {
-        unimplemented!()
-    }
-}
-
-impl Condition {
     pub fn string_equals(matches: Match) -> Self {
         Self::new_match(Operator::string_equals(), matches)
     }
@@ -410,6 +539,10 @@ impl Condition {
         Self::new_match(Operator::string_not_equals_ignore_case(), matches)
     }
 
+    pub fn string_like(matches: Match) -> Self {
+        Self::new_match(Operator::string_like(), matches)
+    }
+
     pub fn string_not_like(matches: Match) -> Self {
         Self::new_match(Operator::string_not_like(), matches)
     }
@@ -498,6 +631,96 @@ impl Condition {
         Self::new_match(Operator::null(), matches)
     }
 
+    /// Matches requests that were sent through one of the VPC endpoints in `vpce_ids`,
+    /// using the `aws:SourceVpce` key.
+    pub fn source_vpce<S>(vpce_ids: Vec<S>) -> Self
+    where
+        S: Into<ConditionValue>,
+    {
+        Self::new(
+            Operator::string_equals(),
+            GlobalConditionKey::SourceVpce.into(),
+            vpce_ids,
+        )
+    }
+
+    /// Matches requests that were **not** sent through any of the VPC endpoints in
+    /// `vpce_ids`, using the `aws:SourceVpce` key.
+    pub fn not_source_vpce<S>(vpce_ids: Vec<S>) -> Self
+    where
+        S: Into<ConditionValue>,
+    {
+        Self::new(
+            Operator::string_not_equals(),
+            GlobalConditionKey::SourceVpce.into(),
+            vpce_ids,
+        )
+    }
+
+    /// Matches requests that originate from one of the VPCs in `vpc_ids`, using the
+    /// `aws:SourceVpc` key.
+    pub fn source_vpc<S>(vpc_ids: Vec<S>) -> Self
+    where
+        S: Into<ConditionValue>,
+    {
+        Self::new(
+            Operator::string_equals(),
+            GlobalConditionKey::SourceVpc.into(),
+            vpc_ids,
+        )
+    }
+
+    /// Matches requests that do **not** originate from any of the VPCs in `vpc_ids`,
+    /// using the `aws:SourceVpc` key.
+    pub fn not_source_vpc<S>(vpc_ids: Vec<S>) -> Self
+    where
+        S: Into<ConditionValue>,
+    {
+        Self::new(
+            Operator::string_not_equals(),
+            GlobalConditionKey::SourceVpc.into(),
+            vpc_ids,
+        )
+    }
+
+    /// Matches requests made by principals in one of the AWS Organizations in `org_ids`,
+    /// using the `aws:PrincipalOrgID` key. Each identifier must be well-formed, see
+    /// [`id::is_valid_org_id`](super::id::is_valid_org_id).
+    pub fn principal_org_id<S>(org_ids: Vec<S>) -> Result<Self, IamFormatError>
+    where
+        S: Into<String>,
+    {
+        let org_ids = validated_values(org_ids, GLOBAL_CONDITION_KEY_PRINCIPAL_ORG_ID, |s| {
+            id::is_valid_org_id(s)
+        })?;
+        Ok(Self::new(
+            Operator::string_equals(),
+            GlobalConditionKey::PrincipalOrgID.into(),
+            org_ids,
+        ))
+    }
+
+    /// Matches requests made by principals in any of the organization entity paths in
+    /// `org_paths`, using the `aws:PrincipalOrgPaths` key. As a principal may belong to
+    /// more than one path this uses the `ForAnyValue:StringLike` operator. Each path
+    /// must be well-formed, see [`id::is_valid_org_path`](super::id::is_valid_org_path).
+    pub fn principal_org_paths<S>(org_paths: Vec<S>) -> Result<Self, IamFormatError>
+    where
+        S: Into<String>,
+    {
+        let org_paths =
+            validated_values(org_paths, GLOBAL_CONDITION_KEY_PRINCIPAL_ORG_PATHS, |s| {
+                id::is_valid_org_path(s)
+            })?;
+        let mut operator = Operator::string_like();
+        operator.set_for_any();
+        Ok(Self::new(
+            operator,
+            GlobalConditionKey::PrincipalOrgPaths.into(),
+            org_paths,
+        ))
+    }
+
     pub fn new_one<S>(operator: Operator, context_key: QualifiedName, value: S) -> Self
     where
         S: Into<ConditionValue>,
@@ -513,7 +736,7 @@ impl Condition {
     }
 
     pub fn new_match(operator: Operator, matches: Match) -> Self {
-        Self(HashMap::from_iter(vec![(operator, matches)].into_iter()))
+        Self(IndexMap::from_iter(vec![(operator, matches)]))
     }
 
     pub fn insert<S>(&mut self, operator: Operator, context_key: QualifiedName, value: S)
@@ -527,7 +750,221 @@ impl Condition {
         }
     }
 
-    pub fn into_inner(self) -> HashMap<Operator, Match> {
+    ///
+    /// Merge all the operators and values from `other` into this condition. Where
+    /// both conditions use the same operator and key the values are combined.
+    ///
+    pub fn merge(&mut self, other: Condition) {
+        for (operator, matches) in other.0 {
+            if let Some(existing) = self.0.get_mut(&operator) {
+                for (context_key, values) in matches.into_inner() {
+                    existing.extend(context_key, values);
+                }
+            } else {
+                self.0.insert(operator, matches);
+            }
+        }
+    }
+
+    ///
+    /// Add the operators and values of `guard` to this condition such that the result can only
+    /// match fewer requests. `merge` combines the values of a key that both conditions test
+    /// with the same operator, which widens rather than narrows the condition; so in that case
+    /// the key is left unchanged if its values are the same as those in `guard`, and otherwise
+    /// an error is returned and this condition is not changed. Operators are compared as
+    /// normalized, and keys ignoring case, see `normalize`.
+    ///
+    pub fn add_guard(&mut self, guard: Condition) -> Result<(), IamFormatError> {
+        let mut added = Self(Default::default());
+        for (operator, matches) in guard.0 {
+            let normalized = operator.normalize();
+            for (key, values) in matches.into_inner() {
+                let existing = self
+                    .0
+                    .iter()
+                    .filter(|(op, _)| op.normalize() == normalized)
+                    .flat_map(|(_, matches)| matches.iter())
+                    .find(|(k, _)| k.eq_ignore_ascii_case(&key));
+                match existing {
+                    None => added.merge(Self::new(operator.clone(), key, values)),
+                    Some((_, existing)) => {
+                        if !(values.iter().all(|v| existing.contains(v))
+                            && existing.iter().all(|v| values.contains(v)))
+                        {
+                            return Err(conflicting_condition(
+                                operator.to_string(),
+                                key.to_string(),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        self.merge(added);
+        Ok(())
+    }
+
+    /// Returns `true` if any of the condition values contain policy variables.
+    pub fn has_variables(&self) -> bool {
+        self.0.values().any(Match::has_variables)
+    }
+
+    ///
+    /// Escape any policy variables in the condition values so that they are read as literal
+    /// strings, see [`variables::escape_variables`](../variables/fn.escape_variables.html).
+    ///
+    pub fn escape_variables(&self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|(operator, matches)| (operator.clone(), matches.escape_variables()))
+                .collect(),
+        )
+    }
+
+    ///
+    /// Re-target any ARN values in this condition, such as those of `aws:SourceArn`, see
+    /// [`retarget_arn_str`](../../arn/fn.retarget_arn_str.html).
+    ///
+    pub fn retarget(&self, partition: Partition, region: Option<&Region>) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|(operator, matches)| (operator.clone(), matches.retarget(partition, region)))
+                .collect(),
+        )
+    }
+
+    ///
+    /// Rewrite the accounts of any ARN values in this condition, and any values that are
+    /// themselves account IDs such as those of `aws:SourceAccount`, if they are one of the keys
+    /// of `accounts`; see [`rewrite_account_str`](../../arn/fn.rewrite_account_str.html).
+    ///
+    pub fn rewrite_accounts(
+        &self,
+        accounts: &HashMap<AccountIdentifier, AccountIdentifier>,
+    ) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|(operator, matches)| (operator.clone(), matches.rewrite_accounts(accounts)))
+                .collect(),
+        )
+    }
+
+    ///
+    /// Returns `true` if this condition tests the key `context_key` with `operator`, regardless
+    /// of any quantifier or _if-exists_ suffix. Keys are compared ignoring case.
+    ///
+    pub fn has_key(&self, operator: GlobalOperator, context_key: &str) -> bool {
+        self.0.iter().any(|(op, matches)| {
+            op.operator == operator
+                && matches
+                    .keys()
+                    .any(|key| key.to_string().eq_ignore_ascii_case(context_key))
+        })
+    }
+
+    ///
+    /// Returns `true` if this condition tests the key `context_key` with any operator. Keys are
+    /// compared ignoring case.
+    ///
+    pub fn tests_key(&self, context_key: &str) -> bool {
+        self.0.values().any(|matches| {
+            matches
+                .keys()
+                .any(|key| key.to_string().eq_ignore_ascii_case(context_key))
+        })
+    }
+
+    ///
+    /// Check each value against the form required by its operator: dates must be ISO 8601 or
+    /// epoch times, numbers must be integer or decimal, and IP addresses must be an address or
+    /// CIDR block. Values containing policy variables, and those of other operators, are not
+    /// checked. Strict tools may reject a policy with any warnings.
+    ///
+    pub fn parse_warnings(&self) -> Vec<ParseWarning> {
+        let mut warnings: Vec<ParseWarning> = Default::default();
+        for (operator, matches) in self.iter() {
+            for (key, values) in matches.iter() {
+                for value in values.iter().filter(|v| !v.has_variables()) {
+                    if let Some(message) = value.check(&operator.operator) {
+                        warnings.push(ParseWarning {
+                            operator: operator.clone(),
+                            key: key.clone(),
+                            value: value.clone(),
+                            message: message.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+        warnings
+    }
+
+    ///
+    /// Returns this condition in a normal form, which tests the same keys and values but can be
+    /// compared with, or reviewed against, other conditions:
+    ///
+    /// * an operator that differs from a known operator only in case, such as `stringEquals`
+    ///   read with `ArnParseMode::Permissive`, is replaced by the known operator, and its keys and values moved to any others for
+    ///   that operator;
+    /// * the service prefix of each key is lower-cased, as IAM compares keys ignoring case;
+    ///   the name after the prefix is unchanged, as tag keys, such as in
+    ///   `aws:ResourceTag/Project`, are not;
+    /// * `Bool` and `Null` values are written `true` or `false`;
+    /// * a value repeated for the same key is only kept once.
+    ///
+    /// Keys that would then be tested more than once by the same operator are left exactly as
+    /// they were written, as each must be met, and combining their values would instead allow
+    /// any of them; these are reported by validation as `DuplicateConditionKey`. Single values
+    /// are always written without an array, see `Match`.
+    ///
+    pub fn normalize(&self) -> Self {
+        let mut counts: HashMap<(Operator, QualifiedName), usize> = Default::default();
+        for (operator, matches) in &self.0 {
+            for key in matches.keys() {
+                *counts
+                    .entry((operator.normalize(), normalize_key(key)))
+                    .or_default() += 1;
+            }
+        }
+        let mut normalized = Self(Default::default());
+        for (operator, matches) in &self.0 {
+            for (key, values) in matches.iter() {
+                let normal = (operator.normalize(), normalize_key(key));
+                let (operator, key) = if counts[&normal] > 1 {
+                    (operator.clone(), key.clone())
+                } else {
+                    normal
+                };
+                let boolean = matches!(
+                    operator.operator,
+                    GlobalOperator::Bool | GlobalOperator::Null
+                );
+                let existing = normalized
+                    .0
+                    .entry(operator)
+                    .or_default()
+                    .0
+                    .entry(key)
+                    .or_default();
+                for value in values {
+                    let value = if boolean {
+                        normalize_boolean(value)
+                    } else {
+                        value.clone()
+                    };
+                    if !existing.contains(&value) {
+                        existing.push(value);
+                    }
+                }
+            }
+        }
+        normalized
+    }
+
+    pub fn into_inner(self) -> IndexMap<Operator, Match> {
         self.0
     }
 }
@@ -535,41 +972,74 @@ impl Condition {
 // ------------------------------------------------------------------------------------------------
 
 impl Deref for Match {
-    type Target = HashMap<QualifiedName, Vec<ConditionValue>>;
+    type Target = IndexMap<QualifiedName, Vec<ConditionValue>>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
+impl From<IndexMap<QualifiedName, Vec<ConditionValue>>> for Match {
+    fn from(v: IndexMap<QualifiedName, Vec<ConditionValue>>) -> Self {
+        Self(v)
+    }
+}
+
 impl From<HashMap<QualifiedName, Vec<ConditionValue>>> for Match {
     fn from(v: HashMap<QualifiedName, Vec<ConditionValue>>) -> Self {
-        Self(v)
+        Self(v.into_iter().collect())
+    }
+}
+
+impl Hash for Match {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut entries: Vec<(&QualifiedName, &Vec<ConditionValue>)> = self.0.iter().collect();
+        entries.sort_by(|lhs, rhs| lhs.0.cmp(rhs.0));
+        entries.hash(state);
+    }
+}
+
+impl Display for Match {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        value_to_fragment(self, f)
+    }
+}
+
+impl FromStr for Match {
+    type Err = IamFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        value_from_fragment(s)
     }
 }
 
 impl IamValue for Match {
     fn to_json(&self) -> Result<Value, IamFormatError> {
-        display_vec_map_to_json(self)
+        let mut object = Map::with_capacity(self.0.len());
+        for (key, values) in &self.0 {
+            let value = match values.as_slice() {
+                [] => Value::Null,
+                [value] => value.to_json()?,
+                values => Value::Array(
+                    values
+                        .iter()
+                        .map(IamValue::to_json)
+                        .collect::<Result<Vec<Value>, IamFormatError>>()?,
+                ),
+            };
+            let _ = object.insert(key.to_string(), value);
+        }
+        Ok(Value::Object(object))
     }
 
     fn from_json(value: &Value) -> Result<Self, IamFormatError> {
         if let Value::Object(object) = value {
-            let results: Result<Vec<(QualifiedName, Vec<ConditionValue>)>, IamFormatError> = object
-                .iter()
-                .map(|(k, v)| {
-                    match (
-                        QualifiedName::from_str(k),
-                        string_vec_from_json(v, CONDITION_VALUE_NAME),
-                    ) {
-                        (Ok(k), Ok(v)) => Ok((k, v)),
-                        (Ok(_), Err(e)) => Err(e),
-                        (Err(e), Ok(_)) => Err(e),
-                        (Err(e), Err(_)) => Err(e),
-                    }
-                })
-                .collect();
-            Ok(Self(HashMap::from_iter(results?)))
+            let mut matches = Self::default();
+            for (k, v) in object {
+                let context_key = QualifiedName::from_str(k)?;
+                matches.extend(context_key, condition_values_from_json(v)?);
+            }
+            Ok(matches)
         } else {
             type_mismatch(CONDITION_NAME, JSON_TYPE_NAME_OBJECT, json_type_name(value)).into()
         }
@@ -588,9 +1058,10 @@ impl Match {
     where
         S: Into<ConditionValue>,
     {
-        Self(HashMap::from_iter(
-            vec![(context_key, values.into_iter().map(|v| v.into()).collect())].into_iter(),
-        ))
+        Self(IndexMap::from_iter(vec![(
+            context_key,
+            values.into_iter().map(|v| v.into()).collect(),
+        )]))
     }
 
     pub fn insert<S>(&mut self, context_key: QualifiedName, value: S)
@@ -618,7 +1089,71 @@ impl Match {
         }
     }
 
-    pub fn into_inner(self) -> HashMap<QualifiedName, Vec<ConditionValue>> {
+    /// Returns `true` if any of the values contain policy variables.
+    pub fn has_variables(&self) -> bool {
+        self.0
+            .values()
+            .any(|values| values.iter().any(ConditionValue::has_variables))
+    }
+
+    /// Escape any policy variables in the values, see `Condition::escape_variables`.
+    pub fn escape_variables(&self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|(key, values)| {
+                    (
+                        key.clone(),
+                        values
+                            .iter()
+                            .map(|v| v.map(variables::escape_variables))
+                            .collect(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Re-target any ARN values, see `Condition::retarget`.
+    pub fn retarget(&self, partition: Partition, region: Option<&Region>) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|(key, values)| {
+                    (
+                        key.clone(),
+                        values
+                            .iter()
+                            .map(|v| v.map(|s| retarget_arn_str(s, partition, region)))
+                            .collect(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Rewrite the accounts of any ARN or account ID values, see `Condition::rewrite_accounts`.
+    pub fn rewrite_accounts(
+        &self,
+        accounts: &HashMap<AccountIdentifier, AccountIdentifier>,
+    ) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|(key, values)| {
+                    (
+                        key.clone(),
+                        values
+                            .iter()
+                            .map(|v| v.map(|s| rewrite_account_str(s, accounts)))
+                            .collect(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    pub fn into_inner(self) -> IndexMap<QualifiedName, Vec<ConditionValue>> {
         self.0
     }
 }
@@ -647,6 +1182,16 @@ impl FromStr for Operator {
     type Err = IamFormatError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, ArnParseMode::Strict)
+    }
+}
+
+impl Operator {
+    ///
+    /// Parse an operator, with its optional quantifier and _if-exists_ suffix, according to
+    /// `mode`; see `GlobalOperator::parse`.
+    ///
+    pub fn parse(s: &str, mode: ArnParseMode) -> Result<Self, IamFormatError> {
         let mut parts: Vec<&str> = s.split(CHAR_OPERATOR_SEP).collect();
         if parts.len() == 1 || parts.len() == 2 {
             let mut operator = Operator {
@@ -665,15 +1210,13 @@ impl FromStr for Operator {
                 operator.if_exists = true;
                 op_string = &op_string[..op_string.len() - CONDITION_QUANTIFIER_IF_EXISTS.len()];
             }
-            operator.operator = GlobalOperator::from_str(op_string)?;
+            operator.operator = GlobalOperator::parse(op_string, mode)?;
             Ok(operator)
         } else {
             unexpected_value_for_type(CONDITION_NAME, s).into()
         }
     }
-}
 
-impl Operator {
     pub fn string_equals() -> Self {
         Self {
             quantifier: None,
@@ -706,6 +1249,14 @@ impl Operator {
         }
     }
 
+    pub fn string_like() -> Self {
+        Self {
+            quantifier: None,
+            operator: GlobalOperator::StringLike,
+            if_exists: false,
+        }
+    }
+
     pub fn string_not_like() -> Self {
         Self {
             quantifier: None,
@@ -717,7 +1268,7 @@ impl Operator {
     pub fn numeric_equals() -> Self {
         Self {
             quantifier: None,
-            operator: GlobalOperator::StringNotLike,
+            operator: GlobalOperator::NumericEquals,
             if_exists: false,
         }
     }
@@ -882,6 +1433,30 @@ impl Operator {
         }
     }
 
+    ///
+    /// Replace an `Other` operator that differs from a known operator, with or without the
+    /// _if-exists_ suffix, only in case by the known operator.
+    ///
+    fn normalize(&self) -> Self {
+        let mut normalized = self.clone();
+        if let GlobalOperator::Other(name) = &self.operator {
+            let lower = name.to_ascii_lowercase();
+            let (lower, if_exists) =
+                match lower.strip_suffix(&CONDITION_QUANTIFIER_IF_EXISTS.to_ascii_lowercase()) {
+                    Some(stripped) => (stripped.to_string(), true),
+                    None => (lower, false),
+                };
+            if let Some(known) = GlobalOperator::KNOWN
+                .iter()
+                .find(|known| known.to_string().to_ascii_lowercase() == lower)
+            {
+                normalized.operator = known.clone();
+                normalized.if_exists = normalized.if_exists || if_exists;
+            }
+        }
+        normalized
+    }
+
     pub fn is_for_any(&self) -> bool {
         matches!(self.quantifier, Some(Quantifier::ForAnyValue))
     }
@@ -975,6 +1550,7 @@ impl Display for GlobalOperator {
                 Self::ArnLike => CONDITION_OPERATOR_ARN_LIKE,
                 Self::ArnNotLike => CONDITION_OPERATOR_ARN_NOT_LIKE,
                 Self::Null => CONDITION_OPERATOR_NULL,
+                Self::Other(s) => s,
             }
         )
     }
@@ -986,7 +1562,7 @@ impl FromStr for GlobalOperator {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             CONDITION_OPERATOR_STRING_EQUALS => Ok(Self::StringEquals),
-            CONDITION_OPERATOR_STRING_NOT_EQUALS => Ok(Self::StringEquals),
+            CONDITION_OPERATOR_STRING_NOT_EQUALS => Ok(Self::StringNotEquals),
             CONDITION_OPERATOR_STRING_EQUALS_IGNORE_CASE => Ok(Self::StringEqualsIgnoreCase),
             CONDITION_OPERATOR_STRING_NOT_EQUALS_IGNORE_CASE => Ok(Self::StringNotEqualsIgnoreCase),
             CONDITION_OPERATOR_STRING_LIKE => Ok(Self::StringLike),
@@ -1017,18 +1593,186 @@ impl FromStr for GlobalOperator {
     }
 }
 
-// ------------------------------------------------------------------------------------------------
+impl GlobalOperator {
+    ///
+    /// Parse an operator according to `mode`. In strict mode, as for `from_str`, only the
+    /// operators documented by IAM are accepted; in permissive mode any other alphanumeric name,
+    /// such as a service-specific operator, is retained as `Other`.
+    ///
+    pub fn parse(s: &str, mode: ArnParseMode) -> Result<Self, IamFormatError> {
+        match Self::from_str(s) {
+            Err(_)
+                if mode == ArnParseMode::Permissive
+                    && !s.is_empty()
+                    && s.chars().all(|c| c.is_ascii_alphanumeric()) =>
+            {
+                Ok(Self::Other(s.to_string()))
+            }
+            result => result,
+        }
+    }
 
-lazy_static! {
-    static ref REGEX_VARIABLE: Regex = Regex::new(r"\$\{([^$}]+)\}").unwrap();
+    /// The operators documented by IAM, in the order of that documentation.
+    pub const KNOWN: &'static [GlobalOperator] = &[
+        Self::StringEquals,
+        Self::StringNotEquals,
+        Self::StringEqualsIgnoreCase,
+        Self::StringNotEqualsIgnoreCase,
+        Self::StringLike,
+        Self::StringNotLike,
+        Self::NumericEquals,
+        Self::NumericNotEquals,
+        Self::NumericLessThan,
+        Self::NumericLessThanEquals,
+        Self::NumericGreaterThan,
+        Self::NumericGreaterThanEquals,
+        Self::DateEquals,
+        Self::DateNotEquals,
+        Self::DateLessThan,
+        Self::DateLessThanEquals,
+        Self::DateGreaterThan,
+        Self::DateGreaterThanEquals,
+        Self::Bool,
+        Self::BinaryEquals,
+        Self::IpAddress,
+        Self::NotIpAddress,
+        Self::ArnEquals,
+        Self::ArnNotEquals,
+        Self::ArnLike,
+        Self::ArnNotLike,
+        Self::Null,
+    ];
+
+    /// Returns `true` if this is one of the operators documented by IAM, not `Other`.
+    pub fn is_known(&self) -> bool {
+        !matches!(self, Self::Other(_))
+    }
+
+    /// A short description of the operator, for tools that describe operators to users.
+    pub fn description(&self) -> Option<&'static str> {
+        match self {
+            Self::StringEquals => Some("Exact matching, case sensitive."),
+            Self::StringNotEquals => Some("Negated exact matching, case sensitive."),
+            Self::StringEqualsIgnoreCase => Some("Exact matching, ignoring case."),
+            Self::StringNotEqualsIgnoreCase => Some("Negated exact matching, ignoring case."),
+            Self::StringLike => Some(
+                "Case-sensitive matching; the values can include a multi-character match \
+                 wildcard (*) or a single-character match wildcard (?) anywhere in the string.",
+            ),
+            Self::StringNotLike => Some(
+                "Negated case-sensitive matching; the values can include a multi-character \
+                 match wildcard (*) or a single-character match wildcard (?) anywhere in the \
+                 string.",
+            ),
+            Self::NumericEquals => Some("Numeric matching."),
+            Self::NumericNotEquals => Some("Negated numeric matching."),
+            Self::NumericLessThan => Some("\"Less than\" numeric matching."),
+            Self::NumericLessThanEquals => Some("\"Less than or equals\" numeric matching."),
+            Self::NumericGreaterThan => Some("\"Greater than\" numeric matching."),
+            Self::NumericGreaterThanEquals => Some("\"Greater than or equals\" numeric matching."),
+            Self::DateEquals => Some("Matching a specific date and time."),
+            Self::DateNotEquals => Some("Negated matching of a specific date and time."),
+            Self::DateLessThan => Some("Matching before a specific date and time."),
+            Self::DateLessThanEquals => Some("Matching at or before a specific date and time."),
+            Self::DateGreaterThan => Some("Matching after a specific date and time."),
+            Self::DateGreaterThanEquals => Some("Matching at or after a specific date and time."),
+            Self::Bool => Some("Boolean matching."),
+            Self::BinaryEquals => Some(
+                "Compares the value of the key byte for byte against a base-64 encoded \
+                 representation of the binary value in the policy.",
+            ),
+            Self::IpAddress => Some("Matching the specified IP address or range."),
+            Self::NotIpAddress => {
+                Some("Matching all IP addresses except the specified IP address or range.")
+            }
+            Self::ArnEquals | Self::ArnLike => Some(
+                "Case-sensitive matching of the ARN; each of the six colon-delimited \
+                 components is checked separately and each can include a multi-character \
+                 match wildcard (*) or a single-character match wildcard (?).",
+            ),
+            Self::ArnNotEquals | Self::ArnNotLike => Some("Negated matching of the ARN."),
+            Self::Null => Some(
+                "Checks whether the key is present at the time of authorization; true if \
+                 the key must not exist, false if it must exist and not be null.",
+            ),
+            Self::Other(_) => None,
+        }
+    }
 }
 
+// ------------------------------------------------------------------------------------------------
+
 impl Display for ConditionValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
     }
 }
 
+impl FromStr for ConditionValue {
+    type Err = IamFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.to_string(), ValueType::String))
+    }
+}
+
+impl PartialEq for ConditionValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for ConditionValue {}
+
+impl Hash for ConditionValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl PartialOrd for ConditionValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ConditionValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl IamValue for ConditionValue {
+    fn to_json(&self) -> Result<Value, IamFormatError> {
+        Ok(match self.1 {
+            ValueType::Number => match Number::from_str(&self.0) {
+                Ok(number) => Value::Number(number),
+                Err(_) => Value::String(self.0.clone()),
+            },
+            ValueType::Boolean => match self.0.as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                _ => Value::String(self.0.clone()),
+            },
+            ValueType::String => Value::String(self.0.clone()),
+        })
+    }
+
+    fn from_json(value: &Value) -> Result<Self, IamFormatError> {
+        match value {
+            Value::String(s) => Ok(Self(s.clone(), ValueType::String)),
+            Value::Number(n) => Ok(Self(n.to_string(), ValueType::Number)),
+            Value::Bool(b) => Ok(Self(b.to_string(), ValueType::Boolean)),
+            _ => type_mismatch(
+                CONDITION_VALUE_NAME,
+                JSON_TYPE_NAME_STRING,
+                json_type_name(value),
+            )
+            .into(),
+        }
+    }
+}
+
 impl Deref for ConditionValue {
     type Target = str;
 
@@ -1042,15 +1786,54 @@ where
     T: Into<String>,
 {
     fn from(s: T) -> Self {
-        Self(s.into())
+        Self(s.into(), ValueType::String)
+    }
+}
+
+impl Serialize for ConditionValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_json()
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ConditionValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Self::from_json(&Value::deserialize(deserializer)?).map_err(serde::de::Error::custom)
     }
 }
 
 impl ConditionValue {
+    /// Construct a boolean value, written as a JSON boolean.
+    pub fn boolean(value: bool) -> Self {
+        Self(value.to_string(), ValueType::Boolean)
+    }
+
+    /// Construct a numeric value, written as a JSON number.
+    pub fn number<N>(value: N) -> Self
+    where
+        N: Into<Number>,
+    {
+        Self(value.into().to_string(), ValueType::Number)
+    }
+
+    /// Return `true` if the value is written as a JSON string, rather than a number or
+    /// boolean.
+    pub fn is_string(&self) -> bool {
+        self.1 == ValueType::String
+    }
+
     /// Return `true` if the identifier contains variables of the form
     /// `${name}`, else `false`.
     pub fn has_variables(&self) -> bool {
-        REGEX_VARIABLE.is_match(self.deref())
+        variables::has_variables(self.deref())
     }
 
     /// Replace any variables in the string with values from the context,
@@ -1060,14 +1843,70 @@ impl ConditionValue {
     where
         V: Clone + Into<String>,
     {
-        let new_text = REGEX_VARIABLE.replace_all(self.deref(), |caps: &Captures<'_>| {
-            if let Some(value) = context.get(&caps[1]) {
-                value.clone().into()
-            } else {
-                format!("${{{}}}", &caps[1])
+        Ok(self.map(|s| variables::replace_variables(s, context)))
+    }
+
+    ///
+    /// Return the value as seconds since the epoch, if it is an epoch time or an ISO 8601 date,
+    /// the forms accepted by the `Date*` operators; otherwise `None`.
+    ///
+    pub fn epoch_seconds(&self) -> Option<i64> {
+        epoch_seconds(&self.0)
+    }
+
+    /// A new value, of the same type, with the text of this value mapped by `f`.
+    fn map<F>(&self, f: F) -> Self
+    where
+        F: FnOnce(&str) -> String,
+    {
+        Self(f(&self.0), self.1)
+    }
+
+    fn check(&self, operator: &GlobalOperator) -> Option<&'static str> {
+        match operator {
+            GlobalOperator::DateEquals
+            | GlobalOperator::DateNotEquals
+            | GlobalOperator::DateLessThan
+            | GlobalOperator::DateLessThanEquals
+            | GlobalOperator::DateGreaterThan
+            | GlobalOperator::DateGreaterThanEquals => {
+                if is_date(&self.0) {
+                    None
+                } else {
+                    Some("expected an ISO 8601 date or an epoch time")
+                }
+            }
+            GlobalOperator::NumericEquals
+            | GlobalOperator::NumericNotEquals
+            | GlobalOperator::NumericLessThan
+            | GlobalOperator::NumericLessThanEquals
+            | GlobalOperator::NumericGreaterThan
+            | GlobalOperator::NumericGreaterThanEquals => {
+                if NUMERIC_SYNTAX.is_match(&self.0) {
+                    None
+                } else {
+                    Some("expected an integer or decimal number")
+                }
+            }
+            GlobalOperator::IpAddress | GlobalOperator::NotIpAddress => {
+                if is_cidr(&self.0) {
+                    None
+                } else {
+                    Some("expected an IP address or CIDR block")
+                }
             }
-        });
-        Ok(Self(new_text.to_string()))
+            _ => None,
+        }
+    }
+}
+
+impl Display for ParseWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the value {:?} of {} for {} is not valid, {}",
+            self.value.0, self.key, self.operator, self.message
+        )
     }
 }
 
@@ -1131,6 +1970,170 @@ impl From<GlobalConditionKey> for QualifiedName {
     }
 }
 
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The JSON type a condition value was written with.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum ValueType {
+    #[default]
+    String,
+    Number,
+    Boolean,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+lazy_static! {
+    static ref DATE_SYNTAX: Regex = Regex::new(
+        r"^(\d{4})-(\d{2})-(\d{2})(?:T(\d{2}):(\d{2})(?::(\d{2})(?:\.\d+)?)?(Z|([+-])(\d{2}):?(\d{2}))?)?$"
+    )
+    .unwrap();
+    static ref EPOCH_SYNTAX: Regex = Regex::new(r"^\d+$").unwrap();
+    static ref NUMERIC_SYNTAX: Regex = Regex::new(r"^[+-]?(?:\d+(?:\.\d*)?|\.\d+)$").unwrap();
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The key with its service prefix, the text before the first `:`, lower-cased.
+///
+fn normalize_key(key: &QualifiedName) -> QualifiedName {
+    match key.split_once(NAMESPACE_SEPARATOR) {
+        Some((prefix, name)) if prefix.chars().any(|c| c.is_ascii_uppercase()) => {
+            QualifiedName::new_unchecked(format!(
+                "{}{}{}",
+                prefix.to_ascii_lowercase(),
+                NAMESPACE_SEPARATOR,
+                name
+            ))
+        }
+        _ => key.clone(),
+    }
+}
+
+fn normalize_boolean(value: &ConditionValue) -> ConditionValue {
+    if value.eq_ignore_ascii_case("true") {
+        value.map(|_| "true".to_string())
+    } else if value.eq_ignore_ascii_case("false") {
+        value.map(|_| "false".to_string())
+    } else {
+        value.clone()
+    }
+}
+
+fn condition_values_from_json(value: &Value) -> Result<Vec<ConditionValue>, IamFormatError> {
+    if let Value::Array(values) = value {
+        values.iter().map(ConditionValue::from_json).collect()
+    } else if value.is_object() || value.is_null() {
+        type_mismatch(
+            CONDITION_VALUE_NAME,
+            JSON_TYPE_NAME_ARRAY,
+            json_type_name(value),
+        )
+        .into()
+    } else {
+        Ok(vec![ConditionValue::from_json(value)?])
+    }
+}
+
+fn is_date(s: &str) -> bool {
+    epoch_seconds(s).is_some()
+}
+
+///
+/// Parse an epoch time, or an ISO 8601 date with an optional time and offset, into seconds
+/// since the epoch; a time without an offset is taken to be UTC.
+///
+fn epoch_seconds(s: &str) -> Option<i64> {
+    if EPOCH_SYNTAX.is_match(s) {
+        return s.parse().ok();
+    }
+    let captures = DATE_SYNTAX.captures(s)?;
+    let field = |i: usize, max: i64| match captures.get(i) {
+        Some(m) => m.as_str().parse::<i64>().ok().filter(|v| *v <= max),
+        None => Some(0),
+    };
+    let year: i64 = captures[1].parse().ok()?;
+    let month: i64 = captures[2].parse().ok()?;
+    let day: i64 = captures[3].parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let time = field(4, 23)? * 3600 + field(5, 59)? * 60 + field(6, 60)?;
+    let offset = match captures.get(8) {
+        Some(sign) => {
+            let offset = field(9, 23)? * 3600 + field(10, 59)? * 60;
+            if sign.as_str() == "-" {
+                -offset
+            } else {
+                offset
+            }
+        }
+        None => 0,
+    };
+    Some(days_from_civil(year, month, day) * 86_400 + time - offset)
+}
+
+// from http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn is_cidr(s: &str) -> bool {
+    let (address, prefix) = match s.split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix)),
+        None => (s, None),
+    };
+    match IpAddr::from_str(address) {
+        Ok(address) => match prefix {
+            None => true,
+            Some(prefix) => match prefix.parse::<u8>() {
+                Ok(prefix) if address.is_ipv4() => prefix <= 32,
+                Ok(prefix) => prefix <= 128,
+                Err(_) => false,
+            },
+        },
+        Err(_) => false,
+    }
+}
+
+fn validated_values<S>(
+    values: Vec<S>,
+    key_name: &str,
+    is_valid: impl Fn(&str) -> bool,
+) -> Result<Vec<String>, IamFormatError>
+where
+    S: Into<String>,
+{
+    if values.is_empty() {
+        return empty_vector_property(key_name).into();
+    }
+    values
+        .into_iter()
+        .map(|v| {
+            let v = v.into();
+            if is_valid(&v) {
+                Ok(v)
+            } else {
+                Err(unexpected_value_for_type(key_name, v))
+            }
+        })
+        .collect()
+}
+
 // ------------------------------------------------------------------------------------------------
 // Modules
 // ------------------------------------------------------------------------------------------------
@@ -1,3 +1,6 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
 /// https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_iam-quotas.html
 /// The external ID value that a third party uses to assume a role must
 /// have a minimum of 2 characters and a maximum of 1,224 characters. The
@@ -23,3 +26,68 @@ where
 pub fn new_external_id() -> String {
     uuid::Uuid::new_v4().to_string()
 }
+
+lazy_static! {
+    static ref ORG_ID_SYNTAX: Regex = Regex::new(r"^o-[a-z0-9]{10,32}$").unwrap();
+    static ref ORG_ROOT_ID_SYNTAX: Regex = Regex::new(r"^r-[0-9a-z]{4,32}$").unwrap();
+    static ref ORG_UNIT_ID_SYNTAX: Regex =
+        Regex::new(r"^ou-[0-9a-z]{4,32}-[a-z0-9]{8,32}$").unwrap();
+}
+
+/// https://docs.aws.amazon.com/organizations/latest/APIReference/API_Organization.html
+/// The identifier of an organization; the string "o-" followed by from 10 to 32
+/// lowercase letters or digits.
+#[inline]
+pub fn is_valid_org_id<S>(s: S) -> bool
+where
+    S: AsRef<str>,
+{
+    ORG_ID_SYNTAX.is_match(s.as_ref())
+}
+
+/// https://docs.aws.amazon.com/organizations/latest/APIReference/API_Root.html
+/// The identifier of an organization root; the string "r-" followed by from 4 to 32
+/// lowercase letters or digits.
+#[inline]
+pub fn is_valid_org_root_id<S>(s: S) -> bool
+where
+    S: AsRef<str>,
+{
+    ORG_ROOT_ID_SYNTAX.is_match(s.as_ref())
+}
+
+/// https://docs.aws.amazon.com/organizations/latest/APIReference/API_OrganizationalUnit.html
+/// The identifier of an organizational unit; the string "ou-" followed by from 4 to 32
+/// lowercase letters or digits, the ID of the root that contains the OU, followed by a
+/// second "-" and from 8 to 32 additional lowercase letters or digits.
+#[inline]
+pub fn is_valid_org_unit_id<S>(s: S) -> bool
+where
+    S: AsRef<str>,
+{
+    ORG_UNIT_ID_SYNTAX.is_match(s.as_ref())
+}
+
+/// https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_policies_condition-keys.html#condition-keys-principalorgpaths
+/// An organization entity path, the organization ID followed by the root ID and zero
+/// or more organizational unit IDs, separated by "/", for example
+/// `o-a1b2c3d4e5/r-ab12/ou-ab12-11111111/`. As these values are compared with
+/// `StringLike` any segment may instead contain the wildcards "*" or "?".
+#[inline]
+pub fn is_valid_org_path<S>(s: S) -> bool
+where
+    S: AsRef<str>,
+{
+    let s = s.as_ref();
+    let s = s.strip_suffix('/').unwrap_or(s);
+    let segments: Vec<&str> = s.split('/').collect();
+    let is_wild = |seg: &str| !seg.is_empty() && seg.contains(['*', '?']);
+    segments.iter().enumerate().all(|(i, seg)| {
+        is_wild(seg)
+            || match i {
+                0 => ORG_ID_SYNTAX.is_match(seg),
+                1 => ORG_ROOT_ID_SYNTAX.is_match(seg),
+                _ => ORG_UNIT_ID_SYNTAX.is_match(seg),
+            }
+    })
+}
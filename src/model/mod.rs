@@ -84,7 +84,10 @@ policy  = {
    in this implementation.
 1. While most values for `condition_type_string` defined in [IAM JSON Policy Elements:
    Condition Operators](https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_policies_elements_condition_operators.html)
-   are provided, the prefixes `ForAllValues` and `ForAnyValue` are not supported.
+   are provided, as are the prefixes `ForAllValues` and `ForAnyValue` and the suffix
+   `IfExists`; these are retained and written exactly as read. Operators that are not
+   known are rejected, unless the policy is read with `ArnParseMode::Permissive` in which
+   case they are read as `GlobalOperator::Other`.
 1. The value of `condition_key_string` is in effect an open-set enumeration, and
    while some values are described within [AWS Global Condition Context
    Keys](https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_policies_condition-keys.html)
@@ -168,6 +171,8 @@ From [Overview of JSON Policies](https://docs.aws.amazon.com/IAM/latest/UserGuid
 
 */
 
+use std::hash::{Hash, Hasher};
+
 // ------------------------------------------------------------------------------------------------
 // Public Macros
 // ------------------------------------------------------------------------------------------------
@@ -253,6 +258,18 @@ impl<T: PartialEq> PartialEq for OrAny<T> {
 
 impl<T: Eq> Eq for OrAny<T> {}
 
+impl<T: Hash> Hash for OrAny<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Self::Any => state.write_u8(0),
+            Self::Some(vs) => {
+                state.write_u8(1);
+                vs.hash(state)
+            }
+        }
+    }
+}
+
 impl<T> OrAny<T> {
     pub fn is_any(&self) -> bool {
         matches!(self, Self::Any)
@@ -300,6 +317,9 @@ pub use version::Version;
 pub mod statement;
 pub use statement::Statement;
 
+pub mod sid;
+pub use sid::{Sid, SidProfile};
+
 pub mod effect;
 pub use effect::Effect;
 
@@ -310,10 +330,25 @@ pub mod action;
 pub use action::Action;
 
 pub mod resource;
-pub use resource::Resource;
+pub use resource::{Resource, ResourceEntry};
 
 pub mod condition;
-pub use condition::{Condition, ConditionValue, GlobalOperator, Match, Operator, Quantifier};
+pub use condition::{
+    Condition, ConditionValue, GlobalOperator, Match, Operator, ParseWarning, Quantifier,
+};
+
+pub mod preset;
+pub use preset::Preset;
+
+pub mod comments;
+pub use comments::Comments;
+
+pub mod redaction;
+pub use redaction::RedactionOptions;
 
 pub mod naming;
 pub use naming::{CanonicalUserId, HostName, QualifiedName, ServiceName};
+
+pub mod variables;
+
+pub mod builder;
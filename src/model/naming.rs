@@ -14,6 +14,7 @@ use std::fmt::{Display, Formatter};
 use std::ops::Deref;
 use std::str::FromStr;
 
+use crate::arn::wildcard_match_ignore_case;
 use crate::error::{unexpected_value_for_type, IamFormatError};
 use crate::syntax::{
     CHAR_WILD, CHAR_WILD_ALL, HOSTNAME_SEPARATOR, HOST_NAME_NAME, NAMESPACE_NAME,
@@ -29,19 +30,19 @@ use crate::syntax::{
 // Public Types
 // ------------------------------------------------------------------------------------------------
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Namespace(String);
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct QualifiedName(String);
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct ServiceName(String);
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct HostName(String);
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct CanonicalUserId(String);
 
 // ------------------------------------------------------------------------------------------------
@@ -69,18 +70,20 @@ lazy_static! {
         .unwrap();
 
     static ref SERVICE_NAME_SYNTAX: Regex = Regex::new(
-        r"^[a-zA-Z]|[a-zA-Z][a-zA-Z0-9\-]*[a-zA-Z0-9]$")
+        r"^([a-zA-Z]|[a-zA-Z][a-zA-Z0-9\-]*[a-zA-Z0-9])$")
         .unwrap();
 
     static ref NAMESPACE_SYNTAX: Regex = Regex::new(
         r"^([a-zA-Z][a-zA-Z0-9\-]*)$")
         .unwrap();
 
-    static ref QNAME_SYNTAX: Regex = Regex::new(
-        r"^([a-zA-Z][a-zA-Z0-9\-]*):([a-zA-Z?*][a-zA-Z0-9\-?*]*)(/([a-zA-Z?*][a-zA-Z0-9\-?*]*)?)?$")
-        .unwrap();
+    static ref QNAME_SYNTAX: Regex = Regex::new(QNAME_PATTERN).unwrap();
 }
 
+/// The syntax of a `QualifiedName`, also used by the policy schema.
+pub(crate) const QNAME_PATTERN: &str =
+    r"^([a-zA-Z][a-zA-Z0-9\-]*):([a-zA-Z?*][a-zA-Z0-9\-?*]*)(/([a-zA-Z0-9_.:=+\-@?*]+)?)?$";
+
 const AWS_SERVICE_TAIL: &str = "amazonaws.com";
 
 // ------------------------------------------------------------------------------------------------
@@ -137,7 +140,7 @@ impl Namespace {
     }
 
     pub fn to_service_name(&self) -> ServiceName {
-        ServiceName::new_unchecked(self.0)
+        ServiceName::new_unchecked(self.0.clone())
     }
 }
 
@@ -239,12 +242,7 @@ impl QualifiedName {
         S: Into<String>,
     {
         let (namespace, name, _) = self.split();
-        let tag = tag.into();
-        if name.ends_with(QUALIFIED_TAG_SEPARATOR) {
-            Self::new_tagged(namespace, name, tag)
-        } else {
-            Self::new(namespace, name)
-        }
+        Self::new_tagged(namespace, name, tag)
     }
 
     pub fn namespace(&self) -> Namespace {
@@ -266,6 +264,29 @@ impl QualifiedName {
         self.0.chars().any(|c| c == CHAR_WILD || c == CHAR_WILD_ALL)
     }
 
+    ///
+    /// Returns `true` if `concrete`, for example `s3:GetObject`, is matched by this name, which
+    /// may be a pattern such as `s3:Get*`. As in IAM the namespaces must be equal, and the
+    /// remainder of the names may differ only where this name has the wildcards `*` or `?`;
+    /// both are compared ignoring case. A name without wildcards only matches itself.
+    ///
+    pub fn matches(&self, concrete: &QualifiedName) -> bool {
+        self.matches_str(concrete)
+    }
+
+    pub(crate) fn matches_str(&self, concrete: &str) -> bool {
+        match (
+            self.0.split_once(NAMESPACE_SEPARATOR),
+            concrete.split_once(NAMESPACE_SEPARATOR),
+        ) {
+            (Some((namespace, name)), Some((concrete_namespace, concrete_name))) => {
+                namespace.eq_ignore_ascii_case(concrete_namespace)
+                    && wildcard_match_ignore_case(name, concrete_name)
+            }
+            _ => false,
+        }
+    }
+
     pub fn is_valid(s: &str) -> bool {
         QNAME_SYNTAX.is_match(s)
     }
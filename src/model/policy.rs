@@ -4,14 +4,19 @@ More detailed description, with
 # Example
  */
 
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 use super::id;
+use crate::arn::{AccountIdentifier, ArnParseMode, Partition, Region};
 use crate::error::{empty_vector_property, unexpected_value_for_type, IamFormatError};
-use crate::model::{Statement, Version};
+use crate::model::{Action, Condition, OrAny, Preset, Sid, Statement, Version};
 use crate::syntax::{
-    display_to_json, json_type_name, IamValue, ID_NAME, JSON_TYPE_NAME_ARRAY,
-    JSON_TYPE_NAME_OBJECT, JSON_TYPE_NAME_STRING, POLICY_NAME, STATEMENT_NAME, VERSION_NAME,
+    display_to_json, json_type_name, value_from_fragment, value_to_fragment, IamValue,
+    COMMENT_NAME, ID_NAME, JSON_TYPE_NAME_ARRAY, JSON_TYPE_NAME_OBJECT, JSON_TYPE_NAME_STRING,
+    POLICY_NAME, POLICY_WILDCARD_VALUE, STATEMENT_NAME, VERSION_NAME,
 };
 use serde_json::{Map, Value};
 
@@ -40,7 +45,7 @@ use serde_json::{Map, Value};
 /// "Id":"cd3ad3d9-2776-4ef1-a904-4c229d1642ee"
 /// ```
 ///
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Policy {
     /// The IAM version of the policy grammar used in this resource
     pub version: Option<Version>,
@@ -72,6 +77,20 @@ impl TryFrom<Vec<Statement>> for Policy {
     }
 }
 
+impl Display for Policy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        value_to_fragment(self, f)
+    }
+}
+
+impl FromStr for Policy {
+    type Err = IamFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        value_from_fragment(s)
+    }
+}
+
 impl IamValue for Policy {
     fn to_json(&self) -> Result<Value, IamFormatError> {
         let mut policy: Map<String, Value> = Default::default();
@@ -94,6 +113,15 @@ impl IamValue for Policy {
     }
 
     fn from_json(value: &Value) -> Result<Self, IamFormatError> {
+        Self::from_json_with(value, ArnParseMode::default())
+    }
+}
+
+impl Policy {
+    ///
+    /// Read a policy from `value`, parsing any resource ARNs according to `mode`.
+    ///
+    pub fn from_json_with(value: &Value, mode: ArnParseMode) -> Result<Self, IamFormatError> {
         let mut policy = Policy {
             version: None,
             id: None,
@@ -120,8 +148,10 @@ impl IamValue for Policy {
             }
             if let Some(statement) = object.get(STATEMENT_NAME) {
                 if let Value::Array(statement) = statement {
-                    let statements: Result<Vec<Statement>, IamFormatError> =
-                        statement.iter().map(Statement::from_json).collect();
+                    let statements: Result<Vec<Statement>, IamFormatError> = statement
+                        .iter()
+                        .map(|st| Statement::from_json_with(st, mode))
+                        .collect();
                     policy.statement = statements?;
                 } else {
                     return Err(IamFormatError::TypeMismatch {
@@ -132,6 +162,10 @@ impl IamValue for Policy {
                 }
                 count += 1;
             }
+            // comments are read separately, see `Comments`.
+            if object.contains_key(COMMENT_NAME) {
+                count += 1;
+            }
             if object.len() != count {
                 Err(IamFormatError::UnexpectedProperties {
                     type_name: POLICY_NAME.to_string(),
@@ -147,9 +181,15 @@ impl IamValue for Policy {
             })
         }
     }
-}
 
-impl Policy {
+    ///
+    /// Create a new, `2012-10-17` version, policy from the well-known shape `preset`; see
+    /// `Preset` for the available shapes.
+    ///
+    pub fn preset(preset: Preset) -> Result<Self, IamFormatError> {
+        Self::unnamed_with_version(preset.statements()?, Version::V2012)
+    }
+
     pub fn unnamed(statements: Vec<Statement>) -> Result<Self, IamFormatError> {
         if statements.is_empty() {
             empty_vector_property(STATEMENT_NAME).into()
@@ -166,14 +206,15 @@ impl Policy {
     where
         S: Into<String>,
     {
-        if !id::is_valid_external_id(policy_id) {
+        let policy_id = policy_id.into();
+        if !id::is_valid_external_id(&policy_id) {
             unexpected_value_for_type(ID_NAME, policy_id).into()
         } else if statements.is_empty() {
             empty_vector_property(STATEMENT_NAME).into()
         } else {
             Ok(Self {
                 version: None,
-                id: Some(policy_id.into()),
+                id: Some(policy_id),
                 statement: statements,
             })
         }
@@ -202,19 +243,49 @@ impl Policy {
     where
         S: Into<String>,
     {
-        if !id::is_valid_external_id(policy_id) {
+        let policy_id = policy_id.into();
+        if !id::is_valid_external_id(&policy_id) {
             unexpected_value_for_type(ID_NAME, policy_id).into()
         } else if statements.is_empty() {
             empty_vector_property(STATEMENT_NAME).into()
         } else {
             Ok(Self {
                 version: Some(version),
-                id: Some(policy_id.into()),
+                id: Some(policy_id),
                 statement: statements,
             })
         }
     }
 
+    ///
+    /// Create a VPC endpoint policy from `statements`. Endpoint policies must identify a
+    /// principal, so any statement without one is given the principal `"*"`.
+    ///
+    pub fn endpoint(statements: Vec<Statement>) -> Result<Self, IamFormatError> {
+        Self::unnamed(
+            statements
+                .into_iter()
+                .map(|mut statement| {
+                    if statement.principal.is_none() {
+                        let _ = statement.any_principal();
+                    }
+                    statement
+                })
+                .collect(),
+        )
+    }
+
+    ///
+    /// The policy AWS attaches to a VPC endpoint when no policy is specified; it allows
+    /// full access to the service through the endpoint.
+    ///
+    pub fn endpoint_full_access() -> Self {
+        Self::from(Statement::endpoint_access(
+            Default::default(),
+            Default::default(),
+        ))
+    }
+
     // --------------------------------------------------------------------------------------------
 
     pub fn version(&self) -> Option<Version> {
@@ -225,6 +296,44 @@ impl Policy {
         self.version = Some(version)
     }
 
+    ///
+    /// Returns the version of the policy language this policy will be evaluated with; if no
+    /// `Version` element is present IAM assumes `2008-10-17`.
+    ///
+    pub fn effective_version(&self) -> Version {
+        self.version.unwrap_or(Version::V2008)
+    }
+
+    ///
+    /// Upgrade this policy to the current version of the policy language, returning `true` if
+    /// the version was changed. A policy already at `2012-10-17` is never changed, and the
+    /// version is never downgraded.
+    ///
+    /// The only difference in evaluation between the versions is that in `2012-10-17` values
+    /// of the form `${name}` are treated as policy variables, rather than literal strings. So
+    /// that the upgraded policy matches the same requests, any such values in the resource,
+    /// principal, and condition values are escaped, using the special variable `${$}` for the
+    /// `$` character; see `Statement::escape_variables`.
+    ///
+    pub fn upgrade_version(&mut self) -> bool {
+        if self.effective_version() == Version::V2012 {
+            false
+        } else {
+            self.version = Some(Version::V2012);
+            self.statement = self
+                .statement
+                .iter()
+                .map(Statement::escape_variables)
+                .collect();
+            true
+        }
+    }
+
+    /// Returns `true` if any statement in this policy contains policy variables.
+    pub fn has_variables(&self) -> bool {
+        self.statement.iter().any(Statement::has_variables)
+    }
+
     // --------------------------------------------------------------------------------------------
 
     pub fn id(&self) -> Option<&String> {
@@ -235,10 +344,11 @@ impl Policy {
     where
         S: Into<String>,
     {
-        if !id::is_valid_external_id(policy_id) {
+        let policy_id = policy_id.into();
+        if !id::is_valid_external_id(&policy_id) {
             unexpected_value_for_type(ID_NAME, policy_id).into()
         } else {
-            self.id = Some(policy_id.into());
+            self.id = Some(policy_id);
             Ok(())
         }
     }
@@ -251,6 +361,40 @@ impl Policy {
         self.id = Some(id::new_external_id())
     }
 
+    ///
+    /// Rename each statement whose `Sid` is the same as that of an earlier statement, so that
+    /// every `Sid` is unique, returning the number of statements renamed. The first statement
+    /// with a `Sid` keeps it, later ones have a number appended, starting at 2, skipping any
+    /// name already in use; so `Read`, `Read`, `Read` becomes `Read`, `Read2`, `Read3`. The
+    /// order of the statements is not changed.
+    ///
+    pub fn rename_duplicate_sids(&mut self) -> usize {
+        let mut used: HashSet<String> = self
+            .statement
+            .iter()
+            .filter_map(|statement| statement.sid.as_ref().map(|sid| sid.to_string()))
+            .collect();
+        let mut seen: HashSet<String> = Default::default();
+        let mut renamed = 0;
+        for statement in self.statement.iter_mut() {
+            if let Some(sid) = &statement.sid {
+                let sid = sid.to_string();
+                if seen.insert(sid.clone()) {
+                    continue;
+                }
+                let new_sid = (2..)
+                    .map(|n| format!("{}{}", sid, n))
+                    .find(|candidate| !used.contains(candidate))
+                    .unwrap();
+                let _ = used.insert(new_sid.clone());
+                let _ = seen.insert(new_sid.clone());
+                statement.sid = Some(Sid::new_unchecked(new_sid));
+                renamed += 1;
+            }
+        }
+        renamed
+    }
+
     // --------------------------------------------------------------------------------------------
 
     pub fn statements(&self) -> impl Iterator<Item = &Statement> {
@@ -268,4 +412,296 @@ impl Policy {
     pub fn statements_extend(&mut self, statements: Vec<Statement>) {
         self.statement.extend(statements.into_iter())
     }
+
+    // --------------------------------------------------------------------------------------------
+
+    ///
+    /// Restrict every statement in this policy to principals in the AWS Organization
+    /// `org_id`, by adding an `aws:PrincipalOrgID` condition to each. The policy is
+    /// left unchanged if `org_id` is not a well-formed organization identifier, or if any
+    /// statement is already restricted to another organization.
+    ///
+    pub fn restrict_to_org<S>(&mut self, org_id: S) -> Result<(), IamFormatError>
+    where
+        S: Into<String>,
+    {
+        let condition = Condition::principal_org_id(vec![org_id])?;
+        self.add_guard_to_all(condition)
+    }
+
+    ///
+    /// Restrict every statement in this policy to principals in any of the organization
+    /// entity paths `org_paths`, by adding an `aws:PrincipalOrgPaths` condition to each. The
+    /// policy is left unchanged if any statement is already restricted to other paths.
+    ///
+    pub fn restrict_to_org_paths<S>(&mut self, org_paths: Vec<S>) -> Result<(), IamFormatError>
+    where
+        S: Into<String>,
+    {
+        let condition = Condition::principal_org_paths(org_paths)?;
+        self.add_guard_to_all(condition)
+    }
+
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the known partitions of all the ARNs in this policy, see
+    /// `Statement::referenced_partitions`.
+    pub fn referenced_partitions(&self) -> BTreeSet<Partition> {
+        self.statement
+            .iter()
+            .flat_map(Statement::referenced_partitions)
+            .collect()
+    }
+
+    /// Returns the regions referenced by this policy, see `Statement::referenced_regions`.
+    pub fn referenced_regions(&self) -> BTreeSet<Region> {
+        self.statement
+            .iter()
+            .flat_map(Statement::referenced_regions)
+            .collect()
+    }
+
+    /// Returns the accounts referenced by this policy, in order and without duplicates; see
+    /// `Statement::referenced_accounts`.
+    pub fn referenced_accounts(&self) -> Vec<AccountIdentifier> {
+        let accounts: BTreeSet<String> = self
+            .statement
+            .iter()
+            .flat_map(Statement::referenced_accounts)
+            .map(|account| account.to_string())
+            .collect();
+        accounts
+            .iter()
+            .map(|account| AccountIdentifier::new_unchecked(account))
+            .collect()
+    }
+
+    ///
+    /// Re-target all the ARNs in this policy to `partition` and, if provided, `region`; see
+    /// `Statement::retarget`. Values of the `aws:RequestedRegion` condition key are not
+    /// changed, as a policy may intentionally refer to regions other than its own.
+    ///
+    pub fn retarget(
+        &self,
+        partition: Partition,
+        region: Option<&Region>,
+    ) -> Result<Self, IamFormatError> {
+        Ok(Self {
+            statement: self
+                .statement
+                .iter()
+                .map(|statement| statement.retarget(partition, region))
+                .collect::<Result<Vec<Statement>, IamFormatError>>()?,
+            ..self.clone()
+        })
+    }
+
+    ///
+    /// Rewrite the accounts referenced by this policy, see `Statement::rewrite_accounts`. This
+    /// allows a policy written for one account to be promoted to another, for example from a
+    /// development to a production account. The map is applied once, so swapping two accounts
+    /// is safe.
+    ///
+    pub fn rewrite_accounts(
+        &self,
+        accounts: &HashMap<AccountIdentifier, AccountIdentifier>,
+    ) -> Result<Self, IamFormatError> {
+        Ok(Self {
+            statement: self
+                .statement
+                .iter()
+                .map(|statement| statement.rewrite_accounts(accounts))
+                .collect::<Result<Vec<Statement>, IamFormatError>>()?,
+            ..self.clone()
+        })
+    }
+
+    ///
+    /// Returns this policy with the conditions of all its statements in normal form, see
+    /// `Condition::normalize`.
+    ///
+    pub fn normalize_conditions(&self) -> Self {
+        Self {
+            statement: self
+                .statement
+                .iter()
+                .map(Statement::normalize_conditions)
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// The size of this policy as IAM measures it against its quotas, the number of characters
+    /// in its JSON form not counting white space.
+    ///
+    pub fn size(&self) -> usize {
+        size_of(&self.to_string())
+    }
+
+    ///
+    /// Partition the statements of this policy into policies, with the same version and id,
+    /// each no larger than `max_chars`, see `size`, so that they may be attached as separate
+    /// managed policies. A policy that is already small enough is returned unchanged.
+    ///
+    /// Related statements, those whose actions are of the same services, are kept in the same
+    /// policy where they fit, and each policy keeps its statements in their original order; the
+    /// order of statements does not affect how a policy is evaluated. A statement that is by
+    /// itself too large for `max_chars` is placed alone in a policy that exceeds it.
+    ///
+    pub fn split(&self, max_chars: usize) -> Vec<Policy> {
+        if self.statement.is_empty() || self.size() <= max_chars {
+            return vec![self.clone()];
+        }
+        let envelope = Self {
+            statement: Default::default(),
+            ..self.clone()
+        }
+        .size();
+        let sizes: Vec<usize> = self
+            .statement
+            .iter()
+            .map(|statement| size_of(&statement.to_string()))
+            .collect();
+
+        // group related statements, in the order each group first appears.
+        let mut groups: Vec<(BTreeSet<String>, Vec<usize>)> = Default::default();
+        for (index, statement) in self.statement.iter().enumerate() {
+            let services = statement_services(statement);
+            match groups.iter_mut().find(|(group, _)| group == &services) {
+                Some((_, members)) => members.push(index),
+                None => groups.push((services, vec![index])),
+            }
+        }
+
+        // place each group, or if it fits in no policy each of its statements, in the first
+        // policy with room for it.
+        let mut parts: Vec<SplitPart> = Default::default();
+        for (_, members) in groups {
+            let group = SplitPart::new(envelope).with_statements(&members, &sizes);
+            if group.size <= max_chars {
+                match parts.iter_mut().find(|part| part.fits(&group, max_chars)) {
+                    Some(part) => part.add(group),
+                    None => parts.push(group),
+                }
+            } else {
+                for index in members {
+                    let single = SplitPart::new(envelope).with_statements(&[index], &sizes);
+                    match parts.iter_mut().find(|part| part.fits(&single, max_chars)) {
+                        Some(part) => part.add(single),
+                        None => parts.push(single),
+                    }
+                }
+            }
+        }
+
+        let mut parts: Vec<Vec<usize>> = parts
+            .into_iter()
+            .map(|part| {
+                let mut statements = part.statements;
+                statements.sort_unstable();
+                statements
+            })
+            .collect();
+        parts.sort_by_key(|statements| statements[0]);
+        parts
+            .into_iter()
+            .map(|statements| Self {
+                statement: statements
+                    .into_iter()
+                    .map(|index| self.statement[index].clone())
+                    .collect(),
+                ..self.clone()
+            })
+            .collect()
+    }
+
+    fn add_guard_to_all(&mut self, guard: Condition) -> Result<(), IamFormatError> {
+        let mut statements = self.statement.clone();
+        for statement in statements.iter_mut() {
+            let _ = statement.add_guard(guard.clone())?;
+        }
+        self.statement = statements;
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The statements, by index, of one of the policies produced by `Policy::split`, and the size
+/// of that policy.
+///
+#[derive(Debug)]
+struct SplitPart {
+    envelope: usize,
+    size: usize,
+    statements: Vec<usize>,
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl SplitPart {
+    /// `envelope` is the size of the policy without any statements.
+    fn new(envelope: usize) -> Self {
+        Self {
+            envelope,
+            size: envelope,
+            statements: Default::default(),
+        }
+    }
+
+    fn with_statements(self, statements: &[usize], sizes: &[usize]) -> Self {
+        let mut self_mut = self;
+        for index in statements {
+            self_mut.size += sizes[*index] + self_mut.separator();
+            self_mut.statements.push(*index);
+        }
+        self_mut
+    }
+
+    fn fits(&self, other: &Self, max_chars: usize) -> bool {
+        self.size + (other.size - other.envelope) + self.separator() <= max_chars
+    }
+
+    fn add(&mut self, other: Self) {
+        self.size += (other.size - other.envelope) + self.separator();
+        self.statements.extend(other.statements);
+    }
+
+    /// The size of the `,` needed before another statement.
+    fn separator(&self) -> usize {
+        if self.statements.is_empty() {
+            0
+        } else {
+            1
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn size_of(json: &str) -> usize {
+    json.chars().filter(|c| !c.is_whitespace()).count()
+}
+
+///
+/// The service namespaces of the actions of `statement`, which identify related statements;
+/// `*` if it names any action.
+///
+fn statement_services(statement: &Statement) -> BTreeSet<String> {
+    let (Action::Action(actions) | Action::NotAction(actions)) = &statement.action;
+    match actions {
+        OrAny::Any => vec![POLICY_WILDCARD_VALUE.to_string()]
+            .into_iter()
+            .collect(),
+        OrAny::Some(actions) => actions
+            .iter()
+            .map(|action| action.namespace().to_string().to_lowercase())
+            .collect(),
+    }
 }
@@ -0,0 +1,203 @@
+/*!
+Provides a set of well-known policy shapes, modeled on AWS-managed policies and the examples
+in the IAM documentation, as starting points to be customized.
+
+# Example
+
+```rust
+use aws_iam::model::{Policy, Preset};
+
+let policy = Policy::preset(Preset::DenyAllOutsideRegions(vec![
+    "us-east-1".to_string(),
+    "eu-west-1".to_string(),
+]))
+.unwrap();
+assert_eq!(policy.statement.len(), 1);
+assert!(policy.statement[0].is_deny());
+
+assert!(Policy::preset(Preset::DenyAllOutsideRegions(vec!["mars".to_string()])).is_err());
+```
+*/
+
+use crate::arn::Region;
+use crate::error::{empty_vector_property, unexpected_value_for_type, IamFormatError};
+use crate::model::condition::GlobalConditionKey;
+use crate::model::{
+    Action, Condition, Effect, Match, Operator, OrAny, QualifiedName, Resource, Sid, Statement,
+};
+use crate::syntax::CONDITION_VALUE_NAME;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A well-known policy shape, used with `Policy::preset`. These are simplified equivalents of
+/// the AWS-managed policies of the same name, and are not kept in step with them.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Preset {
+    /// Allows the `Describe*`, `Get*`, and `List*` actions of a set of core services on all
+    /// resources.
+    ReadOnlyAccess,
+    /// Allows all actions except those of IAM, Organizations, and Account management, with
+    /// the few exceptions needed to use services that create service-linked roles.
+    PowerUserAccess,
+    /// Denies all actions, other than those of global services, requested in any region other
+    /// than those listed.
+    DenyAllOutsideRegions(Vec<String>),
+    /// Denies all actions, other than those needed to configure an MFA device, unless the
+    /// principal authenticated using MFA.
+    RequireMfa,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+const READ_ONLY_SERVICES: &[&str] = &[
+    "cloudformation",
+    "cloudwatch",
+    "dynamodb",
+    "ec2",
+    "iam",
+    "kms",
+    "lambda",
+    "logs",
+    "s3",
+    "sns",
+    "sqs",
+];
+
+const READ_ONLY_PREFIXES: &[&str] = &["Describe*", "Get*", "List*"];
+
+const POWER_USER_EXCLUDED: &[&str] = &["iam:*", "organizations:*", "account:*"];
+
+const POWER_USER_ALLOWED: &[&str] = &[
+    "iam:CreateServiceLinkedRole",
+    "iam:DeleteServiceLinkedRole",
+    "iam:ListRoles",
+    "organizations:DescribeOrganization",
+    "account:ListRegions",
+];
+
+const GLOBAL_SERVICES: &[&str] = &[
+    "budgets:*",
+    "cloudfront:*",
+    "globalaccelerator:*",
+    "iam:*",
+    "importexport:*",
+    "organizations:*",
+    "route53:*",
+    "sts:*",
+    "support:*",
+    "waf:*",
+];
+
+const MFA_SETUP_ACTIONS: &[&str] = &[
+    "iam:CreateVirtualMFADevice",
+    "iam:EnableMFADevice",
+    "iam:GetUser",
+    "iam:ListMFADevices",
+    "iam:ListVirtualMFADevices",
+    "iam:ResyncMFADevice",
+    "sts:GetSessionToken",
+];
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Preset {
+    ///
+    /// Return the statements for this preset, returning an error if any region listed in
+    /// `DenyAllOutsideRegions` is not a valid region name, or if none are listed.
+    ///
+    pub fn statements(&self) -> Result<Vec<Statement>, IamFormatError> {
+        Ok(match self {
+            Self::ReadOnlyAccess => vec![statement(
+                "ReadOnlyAccess",
+                Effect::Allow,
+                Action::these_actions(
+                    READ_ONLY_SERVICES
+                        .iter()
+                        .flat_map(|service| {
+                            READ_ONLY_PREFIXES
+                                .iter()
+                                .map(move |prefix| format!("{}:{}", service, prefix))
+                        })
+                        .map(QualifiedName::new_unchecked)
+                        .collect(),
+                ),
+                None,
+            )],
+            Self::PowerUserAccess => vec![
+                statement(
+                    "PowerUserAccess",
+                    Effect::Allow,
+                    Action::not_these_actions(names(POWER_USER_EXCLUDED)),
+                    None,
+                ),
+                statement(
+                    "PowerUserServiceLinkedRoles",
+                    Effect::Allow,
+                    Action::these_actions(names(POWER_USER_ALLOWED)),
+                    None,
+                ),
+            ],
+            Self::DenyAllOutsideRegions(regions) => {
+                if regions.is_empty() {
+                    return empty_vector_property(CONDITION_VALUE_NAME).into();
+                }
+                if let Some(region) = regions.iter().find(|r| !Region::is_valid(r)) {
+                    return unexpected_value_for_type(CONDITION_VALUE_NAME, region).into();
+                }
+                vec![statement(
+                    "DenyAllOutsideRequestedRegions",
+                    Effect::Deny,
+                    Action::not_these_actions(names(GLOBAL_SERVICES)),
+                    Some(Condition::string_not_equals(Match::new(
+                        GlobalConditionKey::RequestedRegion.into(),
+                        regions.clone(),
+                    ))),
+                )]
+            }
+            Self::RequireMfa => {
+                let mut operator = Operator::bool_equals();
+                operator.set_if_exists();
+                vec![statement(
+                    "DenyAllExceptListedIfNoMFA",
+                    Effect::Deny,
+                    Action::not_these_actions(names(MFA_SETUP_ACTIONS)),
+                    Some(Condition::new_one(
+                        operator,
+                        GlobalConditionKey::MultiFactorAuthPresent.into(),
+                        "false",
+                    )),
+                )]
+            }
+        })
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn statement(sid: &str, effect: Effect, action: Action, condition: Option<Condition>) -> Statement {
+    Statement {
+        sid: Some(Sid::new_unchecked(sid)),
+        principal: None,
+        effect,
+        action,
+        resource: Some(Resource::Resource(OrAny::Any)),
+        condition,
+    }
+}
+
+fn names(actions: &[&str]) -> Vec<QualifiedName> {
+    actions
+        .iter()
+        .map(|action| QualifiedName::new_unchecked(*action))
+        .collect()
+}
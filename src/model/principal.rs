@@ -4,20 +4,27 @@ More detailed description, with
 # Example
  */
 
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
 use crate::{
-    error::{type_mismatch, unexpected_properties, unexpected_value_for_type, IamFormatError},
-    model::{MaybeAny, OrAny},
+    arn::{Partition, Region},
+    error::{
+        missing_property, type_mismatch, unexpected_properties, unexpected_value_for_type,
+        IamError, IamFormatError,
+    },
+    model::{variables, MaybeAny, OrAny, ResourceEntry},
     syntax::{
-        display_vec_to_json, json_type_name, vec_from_str_json, IamProperty, IamValue,
+        display_vec_to_json, json_type_name, property_from_fragment, property_to_fragment,
+        value_from_fragment, value_to_fragment, vec_from_str_json, IamProperty, IamValue,
         JSON_TYPE_NAME_ARRAY, JSON_TYPE_NAME_OBJECT, JSON_TYPE_NAME_STRING, POLICY_WILDCARD_VALUE,
         PRINCIPAL_NAME, PRINCIPAL_TYPE_AWS, PRINCIPAL_TYPE_CANONICAL_USER,
         PRINCIPAL_TYPE_FEDERATED, PRINCIPAL_TYPE_SERVICE, PRINCIPAL_VALUE_NOT_PRINCIPAL,
         PRINCIPAL_VALUE_PRINCIPAL,
     },
 };
-use aws_arn::{AccountIdentifier, ArnError, ARN};
+use aws_arn::{AccountIdentifier, ARN};
 use serde_json::{Map, Value};
 
 use super::naming::{CanonicalUserId, HostName, ServiceName};
@@ -47,7 +54,7 @@ use super::naming::{CanonicalUserId, HostName, ServiceName};
 /// Note that you can use * only to specify "everyone/anonymous." You cannot use
 /// it to specify part of a name or ARN.
 ///
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Principal {
     /// Asserts that the principal in the request **must** match one of the specified ones.
     Principal(OrAny<PrincipalMap>),
@@ -55,17 +62,17 @@ pub enum Principal {
     NotPrincipal(OrAny<PrincipalMap>),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum PrincipalKind {
-    Aws(ARN),
+    Aws(ResourceEntry),
     Federated(HostName),
     Service(ServiceName),
     CanonicalUser(CanonicalUserId),
 }
 
-#[derive(Debug, Default, Clone, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub struct PrincipalMap {
-    aws: Vec<ARN>,
+    aws: Vec<ResourceEntry>,
     federated: Vec<HostName>,
     services: Vec<ServiceName>,
     canonical_users: Vec<CanonicalUserId>,
@@ -83,6 +90,20 @@ pub struct PrincipalMap {
 // Implementations
 // ------------------------------------------------------------------------------------------------
 
+impl Display for Principal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        property_to_fragment(self, f)
+    }
+}
+
+impl FromStr for Principal {
+    type Err = IamFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        property_from_fragment(s, PRINCIPAL_NAME)
+    }
+}
+
 impl IamProperty for Principal {
     fn into_json_object(&self, object: &mut Map<String, Value>) -> Result<(), IamFormatError> {
         let _ = match self {
@@ -117,11 +138,14 @@ impl IamProperty for Principal {
         }
     }
 
-    fn from_json_object(_: &Map<String, Value>) -> Result<Self, IamFormatError>
+    fn from_json_object(value: &Map<String, Value>) -> Result<Self, IamFormatError>
     where
         Self: Sized,
     {
-        unimplemented!()
+        match Self::from_json_object_optional(value)? {
+            Some(principal) => Ok(principal),
+            None => missing_property(PRINCIPAL_NAME).into(),
+        }
     }
 }
 
@@ -153,6 +177,14 @@ impl MaybeAny<PrincipalMap> for Principal {
 }
 
 impl Principal {
+    ///
+    /// Parse a principal from the JSON object `s`, containing only the `Principal` or
+    /// `NotPrincipal` element of a statement, for example `{"Principal":{"AWS":"*"}}`.
+    ///
+    pub fn from_json_str(s: &str) -> Result<Self, IamError> {
+        Ok(Self::from_str(s)?)
+    }
+
     pub fn this<T>(principal: T) -> Self
     where
         T: Into<PrincipalKind>,
@@ -164,7 +196,12 @@ impl Principal {
     where
         T: Into<PrincipalKind>,
     {
-        Self::Principal(OrAny::Some(PrincipalMap::from(principals)))
+        Self::Principal(OrAny::Some(PrincipalMap::from(
+            principals
+                .into_iter()
+                .map(T::into)
+                .collect::<Vec<PrincipalKind>>(),
+        )))
     }
 
     pub fn not_this<T>(principal: T) -> Self
@@ -178,7 +215,12 @@ impl Principal {
     where
         T: Into<PrincipalKind>,
     {
-        Self::NotPrincipal(OrAny::Some(PrincipalMap::from(principals)))
+        Self::NotPrincipal(OrAny::Some(PrincipalMap::from(
+            principals
+                .into_iter()
+                .map(T::into)
+                .collect::<Vec<PrincipalKind>>(),
+        )))
     }
 
     pub fn is_any(&self) -> bool {
@@ -189,6 +231,69 @@ impl Principal {
         matches!(self.inner(), OrAny::Some(_))
     }
 
+    /// Returns `true` if any of the AWS principals contain policy variables.
+    pub fn has_variables(&self) -> bool {
+        match self.inner() {
+            OrAny::Any => false,
+            OrAny::Some(map) => map.has_variables(),
+        }
+    }
+
+    ///
+    /// Returns `true` if this grants access to everyone; that is, it is either `"Principal": "*"`
+    /// or it includes the AWS principal `"*"`. A `NotPrincipal` is never considered public.
+    ///
+    pub fn is_public(&self) -> bool {
+        match self {
+            Principal::Principal(OrAny::Any) => true,
+            Principal::Principal(OrAny::Some(map)) => map.has_aws_wildcard(),
+            Principal::NotPrincipal(_) => false,
+        }
+    }
+
+    /// Escape any policy variables in the AWS principals, see `ResourceEntry::escape_variables`.
+    pub fn escape_variables(&self) -> Self {
+        let escape = |map: &OrAny<PrincipalMap>| match map {
+            OrAny::Any => OrAny::Any,
+            OrAny::Some(map) => OrAny::Some(map.escape_variables()),
+        };
+        match self {
+            Self::Principal(map) => Self::Principal(escape(map)),
+            Self::NotPrincipal(map) => Self::NotPrincipal(escape(map)),
+        }
+    }
+
+    /// Re-target the ARNs of any AWS principals, see `ResourceEntry::retarget`.
+    pub fn retarget(
+        &self,
+        partition: Partition,
+        region: Option<&Region>,
+    ) -> Result<Self, IamFormatError> {
+        let retarget = |map: &OrAny<PrincipalMap>| match map {
+            OrAny::Any => Ok(OrAny::Any),
+            OrAny::Some(map) => map.retarget(partition, region).map(OrAny::Some),
+        };
+        Ok(match self {
+            Self::Principal(map) => Self::Principal(retarget(map)?),
+            Self::NotPrincipal(map) => Self::NotPrincipal(retarget(map)?),
+        })
+    }
+
+    /// Rewrite the accounts of any AWS principals, see `ResourceEntry::rewrite_accounts`.
+    pub fn rewrite_accounts(
+        &self,
+        accounts: &HashMap<AccountIdentifier, AccountIdentifier>,
+    ) -> Result<Self, IamFormatError> {
+        let rewrite = |map: &OrAny<PrincipalMap>| match map {
+            OrAny::Any => Ok(OrAny::Any),
+            OrAny::Some(map) => map.rewrite_accounts(accounts).map(OrAny::Some),
+        };
+        Ok(match self {
+            Self::Principal(map) => Self::Principal(rewrite(map)?),
+            Self::NotPrincipal(map) => Self::NotPrincipal(rewrite(map)?),
+        })
+    }
+
     fn inner_mut(&mut self) -> &mut OrAny<PrincipalMap> {
         match self {
             Principal::Principal(map) => map,
@@ -201,6 +306,12 @@ impl Principal {
 
 impl From<ARN> for PrincipalKind {
     fn from(v: ARN) -> Self {
+        Self::Aws(v.into())
+    }
+}
+
+impl From<ResourceEntry> for PrincipalKind {
+    fn from(v: ResourceEntry) -> Self {
         Self::Aws(v)
     }
 }
@@ -246,7 +357,6 @@ impl IamValue for OrAny<PrincipalMap> {
             }
         } else {
             Ok(OrAny::Some(PrincipalMap::from_json(value)?))
-            // TODO: check for "AWS": "*"
         }
     }
 }
@@ -269,6 +379,20 @@ impl From<Vec<PrincipalKind>> for PrincipalMap {
     }
 }
 
+impl Display for PrincipalMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        value_to_fragment(self, f)
+    }
+}
+
+impl FromStr for PrincipalMap {
+    type Err = IamFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        value_from_fragment(s)
+    }
+}
+
 impl IamValue for PrincipalMap {
     fn to_json(&self) -> Result<Value, IamFormatError> {
         let mut object = Map::default();
@@ -311,7 +435,7 @@ impl IamValue for PrincipalMap {
         if let Value::Object(object) = value {
             let mut principals = PrincipalMap::default();
             if let Some(value) = object.get(PRINCIPAL_TYPE_AWS) {
-                let results: Vec<ARN> = arn_vec_from_str_json(value)?;
+                let results: Vec<ResourceEntry> = arn_vec_from_str_json(value)?;
                 principals.aws = results;
             }
             if let Some(value) = object.get(PRINCIPAL_TYPE_FEDERATED) {
@@ -353,12 +477,26 @@ impl PrincipalMap {
         principals.into_iter().for_each(|p| self.insert(p))
     }
 
-    pub fn insert_aws(&mut self, value: ARN) {
-        self.aws.push(value)
+    /// Returns `true` if this map contains no principals of any kind.
+    pub fn is_empty(&self) -> bool {
+        self.aws.is_empty()
+            && self.federated.is_empty()
+            && self.services.is_empty()
+            && self.canonical_users.is_empty()
     }
 
-    pub fn extend_aws(&mut self, values: Vec<ARN>) {
-        self.aws.extend(values.into_iter());
+    pub fn insert_aws<A>(&mut self, value: A)
+    where
+        A: Into<ResourceEntry>,
+    {
+        self.aws.push(value.into())
+    }
+
+    pub fn extend_aws<A>(&mut self, values: Vec<A>)
+    where
+        A: Into<ResourceEntry>,
+    {
+        self.aws.extend(values.into_iter().map(A::into));
     }
 
     pub fn insert_federated(&mut self, value: HostName) {
@@ -391,10 +529,87 @@ impl PrincipalMap {
     /// When you specify an AWS account, you can use the account ARN
     /// (`arn:aws:iam::AWS-account-ID:root`), or a shortened form that consists of the `AWS:`
     /// prefix followed by the account ID.
-    pub fn aws_iter(&self) -> impl Iterator<Item = &ARN> {
+    ///
+    /// Values may also contain policy variables, in which case they are retained as a
+    /// `ResourceEntry::Template`.
+    pub fn aws_iter(&self) -> impl Iterator<Item = &ResourceEntry> {
         self.aws.iter()
     }
 
+    /// Returns `true` if the AWS principals include the wildcard `"*"`, meaning everyone.
+    pub fn has_aws_wildcard(&self) -> bool {
+        self.aws
+            .iter()
+            .any(|v| matches!(v, ResourceEntry::Pattern(s) if s == POLICY_WILDCARD_VALUE))
+    }
+
+    /// Returns `true` if any of the AWS principals contain policy variables.
+    pub fn has_variables(&self) -> bool {
+        self.aws.iter().any(ResourceEntry::has_variables)
+    }
+
+    /// Escape any policy variables in the AWS principals, see `ResourceEntry::escape_variables`.
+    pub fn escape_variables(&self) -> Self {
+        Self {
+            aws: self
+                .aws
+                .iter()
+                .map(ResourceEntry::escape_variables)
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Substitute any policy variables in the AWS principals with values from `context`.
+    pub fn substitute<V>(&self, context: &HashMap<String, V>) -> Result<Self, IamFormatError>
+    where
+        V: Clone + Into<String>,
+    {
+        let aws: Result<Vec<ResourceEntry>, IamFormatError> =
+            self.aws.iter().map(|v| v.substitute(context)).collect();
+        Ok(Self {
+            aws: aws?,
+            ..self.clone()
+        })
+    }
+
+    /// Re-target the ARNs of the AWS principals, see `ResourceEntry::retarget`.
+    pub fn retarget(
+        &self,
+        partition: Partition,
+        region: Option<&Region>,
+    ) -> Result<Self, IamFormatError> {
+        let aws: Result<Vec<ResourceEntry>, IamFormatError> = self
+            .aws
+            .iter()
+            .map(|v| v.retarget(partition, region))
+            .collect();
+        Ok(Self {
+            aws: aws?,
+            ..self.clone()
+        })
+    }
+
+    ///
+    /// Rewrite the accounts of the AWS principals, see `ResourceEntry::rewrite_accounts`. An
+    /// account principal such as `"123456789012"` is held as its root user ARN and so is
+    /// rewritten in that form.
+    ///
+    pub fn rewrite_accounts(
+        &self,
+        accounts: &HashMap<AccountIdentifier, AccountIdentifier>,
+    ) -> Result<Self, IamFormatError> {
+        let aws: Result<Vec<ResourceEntry>, IamFormatError> = self
+            .aws
+            .iter()
+            .map(|v| v.rewrite_accounts(accounts))
+            .collect();
+        Ok(Self {
+            aws: aws?,
+            ..self.clone()
+        })
+    }
+
     /// Federated users either using web identity federation or using a SAML identity provider.
     pub fn federated_iter(&self) -> impl Iterator<Item = &HostName> {
         self.federated.iter()
@@ -424,13 +639,17 @@ impl PrincipalMap {
 // ------------------------------------------------------------------------------------------------
 
 #[inline]
-pub fn arn_vec_from_str_json(value: &Value) -> Result<Vec<ARN>, IamFormatError> {
-    fn from_str(s: &str) -> Result<ARN, IamFormatError> {
-        if s.contains(':') {
-            ARN::from_str(s).map_err(ArnError::into)
+pub fn arn_vec_from_str_json(value: &Value) -> Result<Vec<ResourceEntry>, IamFormatError> {
+    fn from_str(s: &str) -> Result<ResourceEntry, IamFormatError> {
+        if variables::has_variables(s) {
+            Ok(ResourceEntry::Template(s.to_string()))
+        } else if s == POLICY_WILDCARD_VALUE {
+            Ok(ResourceEntry::Pattern(s.to_string()))
+        } else if s.contains(':') {
+            Ok(ResourceEntry::Arn(ARN::from_str(s)?))
         } else {
             let account = AccountIdentifier::from_str(s)?;
-            Ok(account.into())
+            Ok(ARN::from(account).into())
         }
     }
     if let Value::String(s) = value {
@@ -0,0 +1,378 @@
+/*!
+Provides the redaction of identifying values from a policy, so that a policy can be shared,
+for example in a public bug report, without revealing the accounts and resources it protects.
+
+Redaction replaces values with placeholders while preserving the structure of the policy: a
+redacted ARN is still an ARN of the same service and resource type, and wildcards and policy
+variables are kept. Placeholders are stable, each distinct value is replaced by the same
+placeholder wherever it appears in the policy, and numbered in the order values first appear.
+
+| Value                 | Example                          | Placeholder                 |
+|-----------------------|----------------------------------|-----------------------------|
+| Account ID            | `123456789012`                   | `000000000001`              |
+| S3 bucket name        | `arn:aws:s3:::reports/2020.csv`  | `arn:aws:s3:::bucket-1/resource-1` |
+| Other resource names  | `arn:aws:iam::…:role/Admin`      | `arn:aws:iam::…:role/resource-1` |
+| Organization entities | `o-a1b2c3d4e5`, `ou-ab12-11111111` | `o-example001`, `ou-0001-example1` |
+| IP addresses          | `203.0.113.7/24`                 | `192.0.2.1/24`              |
+
+The replacement IP addresses are in the ranges reserved for documentation.
+
+# Example
+
+```rust
+use aws_iam::io::read_from_string;
+use aws_iam::model::RedactionOptions;
+
+let policy = read_from_string(r#"{"Statement": [{
+    "Effect": "Allow",
+    "Principal": {"AWS": "arn:aws:iam::123456789012:role/ReportWriter"},
+    "Action": "s3:PutObject",
+    "Resource": "arn:aws:s3:::acme-reports/${aws:username}",
+    "Condition": {"StringEquals": {"aws:SourceAccount": "123456789012"}}
+}]}"#)
+.unwrap();
+
+let redacted = policy.redact(RedactionOptions::default()).unwrap().to_string();
+assert!(!redacted.contains("123456789012"));
+assert!(!redacted.contains("acme-reports"));
+assert!(redacted.contains("arn:aws:iam::000000000001:role/resource-1"));
+assert!(redacted.contains("arn:aws:s3:::bucket-1/${aws:username}"));
+```
+*/
+
+use crate::error::IamFormatError;
+use crate::model::id::{is_valid_org_id, is_valid_org_root_id, is_valid_org_unit_id};
+use crate::model::Policy;
+use crate::syntax::IamValue;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashMap;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The kinds of value replaced by `Policy::redact`; all are replaced by default.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RedactionOptions {
+    /// Replace 12-digit account IDs, in ARNs and as values.
+    pub accounts: bool,
+    /// Replace the names in the resource segment of ARNs, such as S3 bucket names and keys.
+    pub resource_names: bool,
+    /// Replace organization, root, and organizational unit IDs.
+    pub organizations: bool,
+    /// Replace IPv4 and IPv6 addresses and CIDR blocks.
+    pub ip_addresses: bool,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind {
+    Account,
+    Bucket,
+    Resource,
+    Organization,
+    OrganizationRoot,
+    OrganizationUnit,
+    IpV4,
+    IpV6,
+}
+
+#[derive(Debug)]
+struct Redactor {
+    options: RedactionOptions,
+    placeholders: HashMap<(Kind, String), String>,
+    counts: HashMap<Kind, usize>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+lazy_static! {
+    static ref ACCOUNT_ID: Regex = Regex::new(r"^\d{12}$").unwrap();
+    static ref IPV4_ADDRESS: Regex = Regex::new(r"^\d{1,3}(\.\d{1,3}){3}(/\d{1,2})?$").unwrap();
+    static ref IPV6_ADDRESS: Regex =
+        Regex::new(r"^[0-9A-Fa-f]*:[0-9A-Fa-f:]*(/\d{1,3})?$").unwrap();
+    static ref VARIABLE_OR_WILDCARD: Regex = Regex::new(r"\$\{[^}]*\}|[*?]").unwrap();
+}
+
+///
+/// Resource names that are part of the ARN grammar of a service, not chosen by a user.
+///
+const WELL_KNOWN_RESOURCES: &[&str] = &["root"];
+
+const S3_SERVICE: &str = "s3";
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Default for RedactionOptions {
+    fn default() -> Self {
+        Self {
+            accounts: true,
+            resource_names: true,
+            organizations: true,
+            ip_addresses: true,
+        }
+    }
+}
+
+impl RedactionOptions {
+    pub fn with_accounts(self, accounts: bool) -> Self {
+        Self { accounts, ..self }
+    }
+
+    pub fn with_resource_names(self, resource_names: bool) -> Self {
+        Self {
+            resource_names,
+            ..self
+        }
+    }
+
+    pub fn with_organizations(self, organizations: bool) -> Self {
+        Self {
+            organizations,
+            ..self
+        }
+    }
+
+    pub fn with_ip_addresses(self, ip_addresses: bool) -> Self {
+        Self {
+            ip_addresses,
+            ..self
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Policy {
+    ///
+    /// Returns a copy of this policy with identifying values replaced by stable placeholders,
+    /// see the `redaction` module.
+    ///
+    pub fn redact(&self, options: RedactionOptions) -> Result<Policy, IamFormatError> {
+        let mut json = self.to_json()?;
+        Redactor::new(options).redact_value(&mut json);
+        Policy::from_json(&json)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Redactor {
+    fn new(options: RedactionOptions) -> Self {
+        Self {
+            options,
+            placeholders: Default::default(),
+            counts: Default::default(),
+        }
+    }
+
+    ///
+    /// Only values are redacted; keys are element names, principal types, condition operators,
+    /// and condition keys.
+    ///
+    fn redact_value(&mut self, value: &mut Value) {
+        match value {
+            Value::String(s) => *s = self.redact_string(s),
+            Value::Array(values) => values.iter_mut().for_each(|value| self.redact_value(value)),
+            Value::Object(object) => object
+                .values_mut()
+                .for_each(|value| self.redact_value(value)),
+            _ => {}
+        }
+    }
+
+    fn redact_string(&mut self, s: &str) -> String {
+        if s.starts_with("arn:") {
+            self.redact_arn(s)
+        } else if self.options.accounts && ACCOUNT_ID.is_match(s) {
+            self.placeholder(Kind::Account, s)
+        } else if self.options.ip_addresses && IPV4_ADDRESS.is_match(s) {
+            self.redact_ip_address(Kind::IpV4, s)
+        } else if self.options.ip_addresses && IPV6_ADDRESS.is_match(s) {
+            self.redact_ip_address(Kind::IpV6, s)
+        } else if self.options.organizations {
+            self.redact_organization_path(s)
+        } else {
+            s.to_string()
+        }
+    }
+
+    fn redact_arn(&mut self, arn: &str) -> String {
+        let mut parts: Vec<String> = arn.splitn(6, ':').map(str::to_string).collect();
+        if parts.len() != 6 {
+            return arn.to_string();
+        }
+        if self.options.accounts && ACCOUNT_ID.is_match(&parts[4]) {
+            parts[4] = self.placeholder(Kind::Account, &parts[4]);
+        }
+        if self.options.resource_names {
+            parts[5] = self.redact_resource(&parts[2], &parts[5]);
+        }
+        parts.join(":")
+    }
+
+    ///
+    /// The first segment of an S3 resource is the bucket name; for other services the resource
+    /// type, if any, is kept.
+    ///
+    fn redact_resource(&mut self, service: &str, resource: &str) -> String {
+        let segments = resource_segments(resource);
+        let has_type = has_resource_type(service, &segments);
+        let mut redacted = String::with_capacity(resource.len());
+        for (index, (name, separator)) in segments.into_iter().enumerate() {
+            let kind = if service == S3_SERVICE && index == 0 {
+                Kind::Bucket
+            } else {
+                Kind::Resource
+            };
+            if (index == 0 && has_type) || WELL_KNOWN_RESOURCES.contains(&name) {
+                redacted.push_str(name);
+            } else {
+                redacted.push_str(&self.redact_literals(kind, name));
+            }
+            redacted.push_str(separator);
+        }
+        redacted
+    }
+
+    ///
+    /// Replace the literal text of `segment`, leaving its wildcards and policy variables.
+    ///
+    fn redact_literals(&mut self, kind: Kind, segment: &str) -> String {
+        let mut redacted = String::with_capacity(segment.len());
+        let mut last = 0;
+        for kept in VARIABLE_OR_WILDCARD.find_iter(segment) {
+            if kept.start() > last {
+                redacted.push_str(&self.placeholder(kind, &segment[last..kept.start()]));
+            }
+            redacted.push_str(kept.as_str());
+            last = kept.end();
+        }
+        if last < segment.len() {
+            redacted.push_str(&self.placeholder(kind, &segment[last..]));
+        }
+        redacted
+    }
+
+    fn redact_ip_address(&mut self, kind: Kind, s: &str) -> String {
+        match s.split_once('/') {
+            Some((address, mask)) => format!("{}/{}", self.placeholder(kind, address), mask),
+            None => self.placeholder(kind, s),
+        }
+    }
+
+    ///
+    /// Values of `aws:PrincipalOrgPaths` are paths of organization entities; a single ID is a
+    /// path of one entity.
+    ///
+    fn redact_organization_path(&mut self, s: &str) -> String {
+        s.split('/')
+            .map(|segment| {
+                if is_valid_org_id(segment) {
+                    self.placeholder(Kind::Organization, segment)
+                } else if is_valid_org_root_id(segment) {
+                    self.placeholder(Kind::OrganizationRoot, segment)
+                } else if is_valid_org_unit_id(segment) {
+                    self.placeholder(Kind::OrganizationUnit, segment)
+                } else {
+                    segment.to_string()
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("/")
+    }
+
+    fn placeholder(&mut self, kind: Kind, value: &str) -> String {
+        if let Some(placeholder) = self.placeholders.get(&(kind, value.to_string())) {
+            return placeholder.clone();
+        }
+        let count = self.counts.entry(kind).or_default();
+        *count += 1;
+        let placeholder = kind.placeholder(*count);
+        let _ = self
+            .placeholders
+            .insert((kind, value.to_string()), placeholder.clone());
+        placeholder
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Kind {
+    fn placeholder(&self, n: usize) -> String {
+        match self {
+            Self::Account => format!("{:012}", n),
+            Self::Bucket => format!("bucket-{}", n),
+            Self::Resource => format!("resource-{}", n),
+            Self::Organization => format!("o-example{:03}", n),
+            Self::OrganizationRoot => format!("r-{:04}", n),
+            Self::OrganizationUnit => format!("ou-{:04}-example{}", n, n),
+            Self::IpV4 => format!("192.0.2.{}", n % 256),
+            Self::IpV6 => format!("2001:db8::{:x}", n),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The service of `arn`, followed by its resource type if it has one, such as `iam:role`; the
+/// parts of the resource that redaction keeps. Returns `None` if `arn` is not an ARN.
+///
+pub(crate) fn arn_resource_type(arn: &str) -> Option<String> {
+    let parts: Vec<&str> = arn.splitn(6, ':').collect();
+    if parts.len() != 6 || parts[0] != "arn" {
+        return None;
+    }
+    let (service, resource) = (parts[2], parts[5]);
+    let segments = resource_segments(resource);
+    if has_resource_type(service, &segments) {
+        Some(format!("{}:{}", service, segments[0].0))
+    } else {
+        Some(service.to_string())
+    }
+}
+
+///
+/// For services other than S3, if the resource has more than one segment, the first is the
+/// resource type.
+///
+fn has_resource_type(service: &str, segments: &[(&str, &str)]) -> bool {
+    service != S3_SERVICE && segments.len() > 1
+}
+
+///
+/// Split an ARN resource into its segments, each with the `/` or `:` separator that follows it;
+/// separators within a policy variable, such as `${aws:username}`, do not split.
+///
+fn resource_segments(resource: &str) -> Vec<(&str, &str)> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut in_variable = false;
+    for (index, c) in resource.char_indices() {
+        match c {
+            '{' if resource[..index].ends_with('$') => in_variable = true,
+            '}' => in_variable = false,
+            '/' | ':' if !in_variable => {
+                segments.push((&resource[start..index], &resource[index..index + 1]));
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    segments.push((&resource[start..], ""));
+    segments
+}
@@ -6,15 +6,25 @@ More detailed description, with
 
 use std::str::FromStr;
 
-use crate::error::{missing_property, type_mismatch, unexpected_properties, IamFormatError};
-use crate::model::{MaybeAny, OrAny};
+use crate::arn::{
+    retarget_arn_str, rewrite_account_str, AccountIdentifier, ArnParseMode, ArnPattern, Partition,
+    Region,
+};
+use crate::error::{
+    missing_property, type_mismatch, unexpected_properties, unexpected_value_for_type,
+    IamFormatError,
+};
+use crate::model::{variables, MaybeAny, OrAny};
 use crate::syntax::{
-    display_vec_to_json, from_json_str, json_type_name, IamProperty, IamValue,
-    JSON_TYPE_NAME_STRING, POLICY_WILDCARD_VALUE, RESOURCE_NAME, RESOURCE_VALUE_NOT_RESOURCE,
-    RESOURCE_VALUE_RESOURCE,
+    display_vec_to_json, json_type_name, property_from_fragment, property_to_fragment, IamProperty,
+    IamValue, JSON_TYPE_NAME_STRING, POLICY_WILDCARD_VALUE, RESOURCE_NAME,
+    RESOURCE_VALUE_NOT_RESOURCE, RESOURCE_VALUE_RESOURCE,
 };
-use aws_arn::ARN;
+use aws_arn::{ArnError, ARN};
 use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter, Write};
+use std::hash::{Hash, Hasher};
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
@@ -36,14 +46,41 @@ use serde_json::{Map, Value};
 /// "Resource":"arn:aws:s3:::examplebucket/*"
 /// ```
 ///
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Resource {
     /// Asserts that the resource in the request **must** match one of the specified ones.
-    Resource(OrAny<Vec<ARN>>),
+    Resource(OrAny<Vec<ResourceEntry>>),
     /// Asserts that the resource in the request **must not** match one of the specified ones.
-    NotResource(OrAny<Vec<ARN>>),
+    NotResource(OrAny<Vec<ResourceEntry>>),
+}
+
+///
+/// A single value in a `Resource` or `NotResource` element. When parsed in
+/// [`ArnParseMode::Strict`](../../arn/enum.ArnParseMode.html) every value must be a well-formed
+/// ARN; in `ArnParseMode::Permissive` values that are recognizably not meant to be validated,
+/// such as templated or partial ARNs and S3 access point aliases, are retained as raw patterns.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResourceEntry {
+    /// A well-formed ARN, which may include wildcards.
+    Arn(ARN),
+    /// A value that contains policy variables, such as `${aws:username}`, which must be
+    /// substituted before it can be treated as an ARN.
+    Template(String),
+    /// A value that is not a well-formed ARN, retained as written.
+    Pattern(String),
 }
 
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Feeds formatted output directly to a hasher, so that an ARN can be hashed by its string
+/// form without allocating that string.
+///
+struct HashWriter<'a, H: Hasher>(&'a mut H);
+
 // ------------------------------------------------------------------------------------------------
 // Public Functions
 // ------------------------------------------------------------------------------------------------
@@ -58,6 +95,20 @@ impl Default for Resource {
     }
 }
 
+impl Display for Resource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        property_to_fragment(self, f)
+    }
+}
+
+impl FromStr for Resource {
+    type Err = IamFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        property_from_fragment(s, RESOURCE_NAME)
+    }
+}
+
 impl IamProperty for Resource {
     fn into_json_object(
         &self,
@@ -78,19 +129,18 @@ impl IamProperty for Resource {
     where
         Self: Sized,
     {
-        match (
-            value.get(RESOURCE_VALUE_RESOURCE),
-            value.get(RESOURCE_VALUE_NOT_RESOURCE),
-        ) {
-            (Some(v), None) => Ok(Resource::Resource(OrAny::<Vec<ARN>>::from_json(v)?)),
-            (None, Some(v)) => Ok(Resource::NotResource(OrAny::<Vec<ARN>>::from_json(v)?)),
-            (None, None) => missing_property(RESOURCE_NAME).into(),
-            (Some(_), Some(_)) => unexpected_properties(RESOURCE_NAME).into(),
-        }
+        Self::from_json_object_with(value, ArnParseMode::default())
+    }
+
+    fn from_json_object_optional(value: &Map<String, Value>) -> Result<Option<Self>, IamFormatError>
+    where
+        Self: Sized,
+    {
+        Self::from_json_object_optional_with(value, ArnParseMode::default())
     }
 }
 
-impl MaybeAny<Vec<ARN>> for Resource {
+impl MaybeAny<Vec<ResourceEntry>> for Resource {
     fn new_any() -> Self
     where
         Self: Sized,
@@ -105,7 +155,7 @@ impl MaybeAny<Vec<ARN>> for Resource {
         Self::NotResource(OrAny::Any)
     }
 
-    fn inner(&self) -> &OrAny<Vec<ARN>> {
+    fn inner(&self) -> &OrAny<Vec<ResourceEntry>> {
         match self {
             Resource::Resource(v) => v,
             Resource::NotResource(v) => v,
@@ -118,23 +168,76 @@ impl MaybeAny<Vec<ARN>> for Resource {
 }
 
 impl Resource {
-    pub fn this_resource(name: ARN) -> Self {
-        Self::Resource(OrAny::Some(vec![name]))
+    pub fn any_resource() -> Self {
+        Self::Resource(OrAny::Any)
+    }
+
+    pub fn no_resource() -> Self {
+        Self::NotResource(OrAny::Any)
     }
 
-    pub fn these_resources(names: Vec<ARN>) -> Self {
-        Self::Resource(OrAny::Some(names))
+    pub fn this_resource<R>(name: R) -> Self
+    where
+        R: Into<ResourceEntry>,
+    {
+        Self::Resource(OrAny::Some(vec![name.into()]))
     }
 
-    pub fn not_this_resource(name: ARN) -> Self {
-        Self::NotResource(OrAny::Some(vec![name]))
+    pub fn these_resources<R>(names: Vec<R>) -> Self
+    where
+        R: Into<ResourceEntry>,
+    {
+        Self::Resource(OrAny::Some(names.into_iter().map(R::into).collect()))
     }
 
-    pub fn not_these_resources(names: Vec<ARN>) -> Self {
-        Self::NotResource(OrAny::Some(names))
+    pub fn not_this_resource<R>(name: R) -> Self
+    where
+        R: Into<ResourceEntry>,
+    {
+        Self::NotResource(OrAny::Some(vec![name.into()]))
     }
 
-    fn inner(&self) -> &OrAny<Vec<ARN>> {
+    pub fn not_these_resources<R>(names: Vec<R>) -> Self
+    where
+        R: Into<ResourceEntry>,
+    {
+        Self::NotResource(OrAny::Some(names.into_iter().map(R::into).collect()))
+    }
+
+    ///
+    /// Read the `Resource` or `NotResource` property from `value`, parsing each resource
+    /// according to `mode`.
+    ///
+    pub fn from_json_object_with(
+        value: &Map<String, Value>,
+        mode: ArnParseMode,
+    ) -> Result<Self, IamFormatError> {
+        match Self::from_json_object_optional_with(value, mode)? {
+            Some(resource) => Ok(resource),
+            None => missing_property(RESOURCE_NAME).into(),
+        }
+    }
+
+    ///
+    /// Read the `Resource` or `NotResource` property, if present, from `value`, parsing each
+    /// resource according to `mode`.
+    ///
+    pub fn from_json_object_optional_with(
+        value: &Map<String, Value>,
+        mode: ArnParseMode,
+    ) -> Result<Option<Self>, IamFormatError> {
+        match (
+            value.get(RESOURCE_VALUE_RESOURCE),
+            value.get(RESOURCE_VALUE_NOT_RESOURCE),
+        ) {
+            (Some(v), None) => Ok(Some(Resource::Resource(entries_from_json(v, mode)?))),
+            (None, Some(v)) => Ok(Some(Resource::NotResource(entries_from_json(v, mode)?))),
+            (None, None) => Ok(None),
+            (Some(_), Some(_)) => unexpected_properties(RESOURCE_NAME).into(),
+        }
+    }
+
+    fn inner(&self) -> &OrAny<Vec<ResourceEntry>> {
         match self {
             Resource::Resource(v) => v,
             Resource::NotResource(v) => v,
@@ -149,18 +252,328 @@ impl Resource {
         matches!(self.inner(), OrAny::Some(_))
     }
 
-    pub fn some(&self) -> Option<&Vec<ARN>> {
+    pub fn some(&self) -> Option<&Vec<ResourceEntry>> {
         if let OrAny::Some(v) = self.inner() {
             Some(v)
         } else {
             None
         }
     }
+
+    /// Returns `true` if any of the resources contain policy variables.
+    pub fn has_variables(&self) -> bool {
+        match self.inner() {
+            OrAny::Any => false,
+            OrAny::Some(values) => values.iter().any(ResourceEntry::has_variables),
+        }
+    }
+
+    /// Escape any policy variables in the resources, see `ResourceEntry::escape_variables`.
+    pub fn escape_variables(&self) -> Self {
+        let escape = |values: &OrAny<Vec<ResourceEntry>>| match values {
+            OrAny::Any => OrAny::Any,
+            OrAny::Some(values) => {
+                OrAny::Some(values.iter().map(ResourceEntry::escape_variables).collect())
+            }
+        };
+        match self {
+            Self::Resource(values) => Self::Resource(escape(values)),
+            Self::NotResource(values) => Self::NotResource(escape(values)),
+        }
+    }
+
+    /// Substitute any policy variables in the resources with values from `context`.
+    pub fn substitute<V>(&self, context: &HashMap<String, V>) -> Result<Self, IamFormatError>
+    where
+        V: Clone + Into<String>,
+    {
+        let substitute = |values: &OrAny<Vec<ResourceEntry>>| match values {
+            OrAny::Any => Ok(OrAny::Any),
+            OrAny::Some(values) => values
+                .iter()
+                .map(|v| v.substitute(context))
+                .collect::<Result<Vec<ResourceEntry>, IamFormatError>>()
+                .map(OrAny::Some),
+        };
+        Ok(match self {
+            Self::Resource(values) => Self::Resource(substitute(values)?),
+            Self::NotResource(values) => Self::NotResource(substitute(values)?),
+        })
+    }
+
+    /// Re-target the ARNs in this element, see `ResourceEntry::retarget`.
+    pub fn retarget(
+        &self,
+        partition: Partition,
+        region: Option<&Region>,
+    ) -> Result<Self, IamFormatError> {
+        let retarget = |values: &OrAny<Vec<ResourceEntry>>| match values {
+            OrAny::Any => Ok(OrAny::Any),
+            OrAny::Some(values) => values
+                .iter()
+                .map(|v| v.retarget(partition, region))
+                .collect::<Result<Vec<ResourceEntry>, IamFormatError>>()
+                .map(OrAny::Some),
+        };
+        Ok(match self {
+            Self::Resource(values) => Self::Resource(retarget(values)?),
+            Self::NotResource(values) => Self::NotResource(retarget(values)?),
+        })
+    }
+
+    /// Rewrite the accounts of the ARNs in this element, see `ResourceEntry::rewrite_accounts`.
+    pub fn rewrite_accounts(
+        &self,
+        accounts: &HashMap<AccountIdentifier, AccountIdentifier>,
+    ) -> Result<Self, IamFormatError> {
+        let rewrite = |values: &OrAny<Vec<ResourceEntry>>| match values {
+            OrAny::Any => Ok(OrAny::Any),
+            OrAny::Some(values) => values
+                .iter()
+                .map(|v| v.rewrite_accounts(accounts))
+                .collect::<Result<Vec<ResourceEntry>, IamFormatError>>()
+                .map(OrAny::Some),
+        };
+        Ok(match self {
+            Self::Resource(values) => Self::Resource(rewrite(values)?),
+            Self::NotResource(values) => Self::NotResource(rewrite(values)?),
+        })
+    }
+
+    ///
+    /// Returns `true` if the resource `arn` is covered by this element; that is, for `Resource`
+    /// it matches one of the listed patterns and for `NotResource` it matches none of them.
+    ///
+    pub fn matches(&self, arn: &ARN) -> bool {
+        let matched = match self.inner() {
+            OrAny::Any => true,
+            OrAny::Some(values) => values.iter().any(|v| v.matches(arn)),
+        };
+        matched != self.is_negative()
+    }
 }
 
 // ------------------------------------------------------------------------------------------------
 
-impl IamValue for OrAny<Vec<ARN>> {
+// `ARN` does not implement `Eq` or `Hash`, although its equality is a simple comparison of
+// its string components, so the ARN is hashed by its string form.
+impl Eq for ResourceEntry {}
+
+impl Hash for ResourceEntry {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Self::Arn(v) => {
+                state.write_u8(0);
+                let _ = write!(HashWriter(state), "{}", v);
+            }
+            Self::Template(v) => {
+                state.write_u8(1);
+                v.hash(state)
+            }
+            Self::Pattern(v) => {
+                state.write_u8(2);
+                v.hash(state)
+            }
+        }
+    }
+}
+
+impl Display for ResourceEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Arn(v) => write!(f, "{}", v),
+            Self::Template(v) => write!(f, "{}", v),
+            Self::Pattern(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+impl FromStr for ResourceEntry {
+    type Err = IamFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, ArnParseMode::Strict)
+    }
+}
+
+impl From<ARN> for ResourceEntry {
+    fn from(v: ARN) -> Self {
+        Self::Arn(v)
+    }
+}
+
+impl ResourceEntry {
+    ///
+    /// Parse a single resource value according to `mode`. In either mode a value containing
+    /// policy variables is retained as a template; in strict mode the template must still have
+    /// the shape of an ARN.
+    ///
+    /// In permissive mode a value that does not parse as an ARN is retained as a pattern if it
+    /// is not ARN-shaped at all (an alias or bare name), has too few components, or names a
+    /// partition that is not recognized. Complete ARNs with invalid components are still
+    /// rejected.
+    ///
+    pub fn parse(s: &str, mode: ArnParseMode) -> Result<Self, IamFormatError> {
+        if s.is_empty() || s.chars().any(|c| c.is_control() || c.is_whitespace()) {
+            return unexpected_value_for_type(RESOURCE_NAME, s).into();
+        }
+        if variables::has_variables(s) {
+            // variables may contain ':' and so confuse the component split.
+            return if mode == ArnParseMode::Strict
+                && ArnPattern::from_str(&variables::replace_variables_with(s, "x")).is_err()
+            {
+                unexpected_value_for_type(RESOURCE_NAME, s).into()
+            } else {
+                Ok(Self::Template(s.to_string()))
+            };
+        }
+        if mode == ArnParseMode::Strict {
+            return Ok(Self::Arn(ARN::from_str(s)?));
+        }
+        if !s.starts_with("arn:") {
+            return Ok(Self::Pattern(s.to_string()));
+        }
+        match ARN::from_str(s) {
+            Ok(arn) => Ok(Self::Arn(arn)),
+            Err(ArnError::TooFewComponents) | Err(ArnError::InvalidPartition) => {
+                Ok(Self::Pattern(s.to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn is_arn(&self) -> bool {
+        matches!(self, Self::Arn(_))
+    }
+
+    pub fn as_arn(&self) -> Option<&ARN> {
+        match self {
+            Self::Arn(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn is_template(&self) -> bool {
+        matches!(self, Self::Template(_))
+    }
+
+    /// Returns `true` if this entry contains policy variables.
+    pub fn has_variables(&self) -> bool {
+        match self {
+            Self::Template(v) => variables::has_variables(v),
+            _ => false,
+        }
+    }
+
+    /// Returns the names of all policy variables in this entry.
+    pub fn variable_names(&self) -> Vec<String> {
+        match self {
+            Self::Template(v) => variables::variable_names(v),
+            _ => Default::default(),
+        }
+    }
+
+    ///
+    /// Escape any policy variables in this entry so that it is read as a literal string, see
+    /// [`variables::escape_variables`](../variables/fn.escape_variables.html).
+    ///
+    pub fn escape_variables(&self) -> Self {
+        match self {
+            Self::Template(v) => Self::Template(variables::escape_variables(v)),
+            _ => self.clone(),
+        }
+    }
+
+    ///
+    /// Substitute any policy variables in this entry with values from `context`. If all the
+    /// variables are replaced the result is parsed, and so validated, as an ARN; if some remain
+    /// the result is still a template.
+    ///
+    pub fn substitute<V>(&self, context: &HashMap<String, V>) -> Result<Self, IamFormatError>
+    where
+        V: Clone + Into<String>,
+    {
+        match self {
+            Self::Template(v) => {
+                let replaced = variables::replace_variables(v, context);
+                if variables::has_variables(&replaced) {
+                    Ok(Self::Template(replaced))
+                } else {
+                    Ok(Self::Arn(ARN::from_str(&replaced)?))
+                }
+            }
+            _ => Ok(self.clone()),
+        }
+    }
+
+    ///
+    /// Re-target this entry to `partition` and, if provided, `region`; see
+    /// [`retarget_arn_str`](../../arn/fn.retarget_arn_str.html). Templates are not changed.
+    ///
+    pub fn retarget(
+        &self,
+        partition: Partition,
+        region: Option<&Region>,
+    ) -> Result<Self, IamFormatError> {
+        match self {
+            Self::Arn(v) => Ok(Self::Arn(ARN::from_str(&retarget_arn_str(
+                &v.to_string(),
+                partition,
+                region,
+            ))?)),
+            Self::Template(_) => Ok(self.clone()),
+            Self::Pattern(v) => Ok(Self::Pattern(retarget_arn_str(v, partition, region))),
+        }
+    }
+
+    ///
+    /// Rewrite the account of this entry if it is one of the keys of `accounts`; see
+    /// [`rewrite_account_str`](../../arn/fn.rewrite_account_str.html). Unlike `retarget`,
+    /// templates are also rewritten, any variables in them are retained.
+    ///
+    pub fn rewrite_accounts(
+        &self,
+        accounts: &HashMap<AccountIdentifier, AccountIdentifier>,
+    ) -> Result<Self, IamFormatError> {
+        match self {
+            Self::Arn(v) => Ok(Self::Arn(ARN::from_str(&rewrite_account_str(
+                &v.to_string(),
+                accounts,
+            ))?)),
+            Self::Template(v) => Ok(Self::Template(rewrite_account_str(v, accounts))),
+            Self::Pattern(v) => Ok(Self::Pattern(rewrite_account_str(v, accounts))),
+        }
+    }
+
+    ///
+    /// Returns `true` if `arn` is matched by this entry; patterns that are not ARN-shaped
+    /// only match an identical string. A template never matches, it must be substituted
+    /// first.
+    ///
+    pub fn matches(&self, arn: &ARN) -> bool {
+        match self {
+            Self::Arn(v) => ArnPattern::from(v).matches(arn),
+            Self::Template(_) => false,
+            Self::Pattern(v) => match ArnPattern::from_str(v) {
+                Ok(pattern) => pattern.matches(arn),
+                Err(_) => v == &arn.to_string(),
+            },
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<H: Hasher> std::fmt::Write for HashWriter<'_, H> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.0.write(s.as_bytes());
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl IamValue for OrAny<Vec<ResourceEntry>> {
     fn to_json(&self) -> Result<Value, IamFormatError> {
         Ok(if let OrAny::Some(values) = self {
             display_vec_to_json(values)?
@@ -173,20 +586,37 @@ impl IamValue for OrAny<Vec<ARN>> {
     where
         Self: Sized,
     {
-        if let Value::String(s) = value {
-            if s == POLICY_WILDCARD_VALUE {
-                Ok(OrAny::Any)
-            } else {
-                Ok(OrAny::Some(vec![ARN::from_str(s)?]))
-            }
-        } else if let Value::Array(arr) = value {
-            let results: Result<Vec<ARN>, IamFormatError> = arr
-                .iter()
-                .map(|v| from_json_str(v, RESOURCE_NAME))
-                .collect();
-            Ok(OrAny::Some(results?))
+        entries_from_json(value, ArnParseMode::default())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn entries_from_json(
+    value: &Value,
+    mode: ArnParseMode,
+) -> Result<OrAny<Vec<ResourceEntry>>, IamFormatError> {
+    if let Value::String(s) = value {
+        if s == POLICY_WILDCARD_VALUE {
+            Ok(OrAny::Any)
         } else {
-            type_mismatch(RESOURCE_NAME, JSON_TYPE_NAME_STRING, json_type_name(value)).into()
+            Ok(OrAny::Some(vec![ResourceEntry::parse(s, mode)?]))
         }
+    } else if let Value::Array(arr) = value {
+        let results: Result<Vec<ResourceEntry>, IamFormatError> = arr
+            .iter()
+            .map(|v| {
+                if let Value::String(s) = v {
+                    ResourceEntry::parse(s, mode)
+                } else {
+                    type_mismatch(RESOURCE_NAME, JSON_TYPE_NAME_STRING, json_type_name(v)).into()
+                }
+            })
+            .collect();
+        Ok(OrAny::Some(results?))
+    } else {
+        type_mismatch(RESOURCE_NAME, JSON_TYPE_NAME_STRING, json_type_name(value)).into()
     }
 }
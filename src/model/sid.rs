@@ -0,0 +1,171 @@
+/*!
+Provides the `Sid` (statement identifier) type, and the service-specific rules for which
+characters a Sid may contain.
+
+From [IAM JSON policy elements: Sid](https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_policies_elements_sid.html):
+
+> For IAM policies, basic alphanumeric characters (A-Z,a-z,0-9) are the only allowed characters
+> in the Sid value. Other AWS services that support resource policies may have other
+> requirements for the Sid value.
+
+# Example
+
+```rust
+use aws_iam::model::{Sid, SidProfile};
+use std::str::FromStr;
+
+assert!(Sid::from_str("AllowReadOnly").is_ok());
+assert!(Sid::from_str("allow-read-only").is_err());
+
+let sid = Sid::new_for("allow-read-only", SidProfile::Sqs).unwrap();
+assert!(sid.is_valid_for(SidProfile::Sns));
+assert!(!sid.is_valid_for(SidProfile::Iam));
+```
+*/
+
+use crate::error::{unexpected_value_for_type, IamFormatError};
+use crate::syntax::SID_NAME;
+use std::fmt::{Display, Formatter};
+use std::ops::Deref;
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A statement identifier. Values created with `new` or `from_str` are validated against the
+/// IAM rules, the most restrictive; use `new_for` when the policy targets a service with
+/// different rules.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Sid(String);
+
+///
+/// The rules, per target service, for the characters allowed in a `Sid`.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SidProfile {
+    /// IAM policies allow only ASCII letters and digits.
+    #[default]
+    Iam,
+    /// SQS queue policies also allow hyphens and underscores.
+    Sqs,
+    /// SNS topic policies also allow hyphens and underscores.
+    Sns,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Sid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<Sid> for String {
+    fn from(v: Sid) -> Self {
+        v.0
+    }
+}
+
+impl Deref for Sid {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl FromStr for Sid {
+    type Err = IamFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new_for(s, SidProfile::Iam)
+    }
+}
+
+impl Sid {
+    pub fn new_unchecked<S>(s: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self(s.into())
+    }
+
+    pub fn new<S>(s: S) -> Result<Self, IamFormatError>
+    where
+        S: Into<String>,
+    {
+        Self::new_for(s, SidProfile::Iam)
+    }
+
+    pub fn new_for<S>(s: S, profile: SidProfile) -> Result<Self, IamFormatError>
+    where
+        S: Into<String>,
+    {
+        let s = s.into();
+        if profile.is_valid(&s) {
+            Ok(Self(s))
+        } else {
+            unexpected_value_for_type(SID_NAME, s).into()
+        }
+    }
+
+    /// A new, random, identifier that is valid for all profiles.
+    pub fn new_random() -> Self {
+        Self(uuid::Uuid::new_v4().simple().to_string())
+    }
+
+    pub fn is_valid(s: &str) -> bool {
+        SidProfile::Iam.is_valid(s)
+    }
+
+    pub fn is_valid_for(&self, profile: SidProfile) -> bool {
+        profile.is_valid(&self.0)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for SidProfile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Iam => "IAM",
+                Self::Sqs => "SQS",
+                Self::Sns => "SNS",
+            }
+        )
+    }
+}
+
+impl FromStr for SidProfile {
+    type Err = IamFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "IAM" => Ok(Self::Iam),
+            "SQS" => Ok(Self::Sqs),
+            "SNS" => Ok(Self::Sns),
+            _ => unexpected_value_for_type("SidProfile", s).into(),
+        }
+    }
+}
+
+impl SidProfile {
+    /// Returns `true` if `s` is a valid `Sid` value under this profile.
+    pub fn is_valid(&self, s: &str) -> bool {
+        !s.is_empty()
+            && match self {
+                Self::Iam => s.chars().all(|c| c.is_ascii_alphanumeric()),
+                Self::Sqs | Self::Sns => s
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+            }
+    }
+}
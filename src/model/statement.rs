@@ -4,14 +4,30 @@ More detailed description, with
 # Example
  */
 
-use super::{id, OrAny};
-use crate::error::{missing_property, type_mismatch, unexpected_value_for_type, IamFormatError};
-use crate::model::{Action, Condition, Effect, Principal, Resource};
+use super::OrAny;
+use crate::arn::{
+    account_id, arn_account, arn_partition, arn_region, AccountIdentifier, ArnParseMode, Partition,
+    Region, ARN,
+};
+use crate::error::{missing_property, type_mismatch, IamError, IamFormatError};
+use crate::io::sorted;
+use crate::model::condition::GlobalConditionKey;
+use crate::model::{
+    Action, Condition, ConditionValue, Effect, GlobalOperator, Principal, QualifiedName, Resource,
+    ResourceEntry, Sid,
+};
+#[cfg(feature = "service_config")]
+use crate::service::actions::{all_metadata, AccessLevel};
 use crate::syntax::{
-    display_to_json, from_json_str, json_type_name, IamProperty, IamValue, EFFECT_NAME,
-    JSON_TYPE_NAME_OBJECT, JSON_TYPE_NAME_STRING, SID_NAME, STATEMENT_NAME,
+    display_to_json, from_json_str, json_type_name, value_from_fragment, value_to_fragment,
+    IamProperty, IamValue, EFFECT_NAME, JSON_TYPE_NAME_OBJECT, JSON_TYPE_NAME_STRING, SID_NAME,
+    STATEMENT_NAME,
 };
 use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+use std::str::FromStr;
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
@@ -40,7 +56,7 @@ use serde_json::{Map, Value};
 /// "Sid": "ThisStatementProvidesPermissionsForConsoleAccess"
 /// ```
 ///
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Statement {
     ///
 
@@ -54,7 +70,7 @@ pub struct Statement {
     ///
     /// From [IAM JSON Policy Elements: Sid](https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_policies_elements_sid.html).
     ///
-    pub sid: Option<String>,
+    pub sid: Option<Sid>,
     ///
     /// The principals, or not-principals to match as part of this statement.
     ///
@@ -68,9 +84,11 @@ pub struct Statement {
     ///
     pub action: Action,
     ///
-    /// The resources, or not-resources to match as part of this statement.
+    /// The resources, or not-resources to match as part of this statement. This is required
+    /// for identity-based policies, but in a resource-based policy may be omitted in which
+    /// case the statement applies to the resource the policy is attached to.
     ///
-    pub resource: Resource,
+    pub resource: Option<Resource>,
     ///
     /// Any condition(s) attached to this statement.
     ///
@@ -85,6 +103,20 @@ pub struct Statement {
 // Implementations
 // ------------------------------------------------------------------------------------------------
 
+impl Display for Statement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        value_to_fragment(self, f)
+    }
+}
+
+impl FromStr for Statement {
+    type Err = IamFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        value_from_fragment(s)
+    }
+}
+
 impl IamValue for Statement {
     fn to_json(&self) -> Result<Value, IamFormatError> {
         let mut statement = Map::default();
@@ -93,15 +125,17 @@ impl IamValue for Statement {
             statement.insert(SID_NAME.to_string(), display_to_json(sid));
         }
 
+        statement.insert(EFFECT_NAME.to_string(), self.effect.to_json()?);
+
         if let Some(values) = &self.principal {
             values.into_json_object(&mut statement)?;
         }
 
-        statement.insert(EFFECT_NAME.to_string(), self.effect.to_json()?);
-
         self.action.into_json_object(&mut statement)?;
 
-        self.resource.into_json_object(&mut statement)?;
+        if let Some(values) = &self.resource {
+            values.into_json_object(&mut statement)?;
+        }
 
         if let Some(values) = &self.condition {
             values.into_json_object(&mut statement)?;
@@ -114,10 +148,28 @@ impl IamValue for Statement {
     where
         Self: Sized,
     {
+        Self::from_json_with(value, ArnParseMode::default())
+    }
+}
+
+impl Statement {
+    ///
+    /// Parse a single statement from the JSON object `s`, as it would appear in the `Statement`
+    /// element of a policy, for example `{"Effect":"Allow","Action":"s3:GetObject"}`.
+    ///
+    pub fn from_json_str(s: &str) -> Result<Self, IamError> {
+        Ok(Self::from_str(s)?)
+    }
+
+    ///
+    /// Read a statement from `value`, parsing any resource ARNs and condition operators
+    /// according to `mode`.
+    ///
+    pub fn from_json_with(value: &Value, mode: ArnParseMode) -> Result<Self, IamFormatError> {
         if let Value::Object(object) = value {
-            let sid: Option<String> = if let Some(value) = object.get(SID_NAME) {
+            let sid: Option<Sid> = if let Some(value) = object.get(SID_NAME) {
                 if let Value::String(s) = value {
-                    Some(s.to_string())
+                    Some(Sid::new_unchecked(s.to_string()))
                 } else {
                     return type_mismatch(SID_NAME, JSON_TYPE_NAME_STRING, json_type_name(value))
                         .into();
@@ -136,9 +188,11 @@ impl IamValue for Statement {
 
             let action: Action = Action::from_json_object(object)?;
 
-            let resource: Resource = Resource::from_json_object(object)?;
+            let resource: Option<Resource> =
+                Resource::from_json_object_optional_with(object, mode)?;
 
-            let condition: Option<Condition> = Condition::from_json_object_optional(object)?;
+            let condition: Option<Condition> =
+                Condition::from_json_object_optional_with(object, mode)?;
 
             Ok(Self {
                 sid,
@@ -152,56 +206,99 @@ impl IamValue for Statement {
             type_mismatch(STATEMENT_NAME, JSON_TYPE_NAME_OBJECT, json_type_name(value)).into()
         }
     }
-}
 
-impl Statement {
     pub fn unnamed() -> Self {
         Self {
             sid: Default::default(),
             principal: Default::default(),
             effect: Default::default(),
             action: Default::default(),
-            resource: Default::default(),
+            resource: Some(Default::default()),
             condition: Default::default(),
         }
     }
 
-    pub fn named(sid: &str) -> Self {
+    pub fn named(sid: Sid) -> Self {
         Self {
-            sid: Some(sid.to_string()),
+            sid: Some(sid),
             principal: Default::default(),
             effect: Default::default(),
             action: Default::default(),
-            resource: Default::default(),
+            resource: Some(Default::default()),
             condition: Default::default(),
         }
     }
 
-    // --------------------------------------------------------------------------------------------
+    ///
+    /// A statement for use in a VPC endpoint policy. Endpoint policies control access
+    /// for any principal using the endpoint, so the principal is always `"*"`.
+    ///
+    pub fn endpoint_access(action: Action, resource: Resource) -> Self {
+        Self {
+            sid: Default::default(),
+            principal: Some(Principal::Principal(OrAny::Any)),
+            effect: Effect::Allow,
+            action,
+            resource: Some(resource),
+            condition: Default::default(),
+        }
+    }
 
-    pub fn sid(&self) -> Option<&String> {
-        self.sid.as_ref()
+    ///
+    /// A resource-based policy statement that denies `action` on `resource` to all
+    /// principals unless the request was sent through one of the VPC endpoints in
+    /// `vpce_ids`.
+    ///
+    pub fn deny_unless_source_vpce<S>(vpce_ids: Vec<S>, action: Action, resource: Resource) -> Self
+    where
+        S: Into<ConditionValue>,
+    {
+        Self {
+            sid: Default::default(),
+            principal: Some(Principal::Principal(OrAny::Any)),
+            effect: Effect::Deny,
+            action,
+            resource: Some(resource),
+            condition: Some(Condition::not_source_vpce(vpce_ids)),
+        }
     }
 
-    pub fn set_sid<S>(&mut self, sid: S) -> Result<(), IamFormatError>
+    ///
+    /// A resource-based policy statement that denies `action` on `resource` to all
+    /// principals unless the request originates from one of the VPCs in `vpc_ids`.
+    ///
+    pub fn deny_unless_source_vpc<S>(vpc_ids: Vec<S>, action: Action, resource: Resource) -> Self
     where
-        S: Into<String>,
+        S: Into<ConditionValue>,
     {
-        if !id::is_valid_external_id(sid) {
-            unexpected_value_for_type(SID_NAME, sid).into()
-        } else {
-            self.sid = Some(sid.into());
-            Ok(())
+        Self {
+            sid: Default::default(),
+            principal: Some(Principal::Principal(OrAny::Any)),
+            effect: Effect::Deny,
+            action,
+            resource: Some(resource),
+            condition: Some(Condition::not_source_vpc(vpc_ids)),
         }
     }
 
+    // --------------------------------------------------------------------------------------------
+
+    pub fn sid(&self) -> Option<&Sid> {
+        self.sid.as_ref()
+    }
+
+    pub fn set_sid(&mut self, sid: Sid) -> &mut Self {
+        self.sid = Some(sid);
+        self
+    }
+
     pub fn unset_sid(&mut self) -> &mut Self {
         self.sid = None;
         self
     }
 
     pub fn set_auto_sid(&mut self) -> &mut Self {
-        self.sid = Some(id::new_external_id());
+        self.sid = Some(Sid::new_random());
         self
     }
 
@@ -265,22 +362,27 @@ impl Statement {
 
     // --------------------------------------------------------------------------------------------
 
-    pub fn resource(&self) -> &Resource {
-        &self.resource
+    pub fn resource(&self) -> Option<&Resource> {
+        self.resource.as_ref()
     }
 
     pub fn set_resource(&mut self, resource: Resource) -> &mut Self {
-        self.resource = resource;
+        self.resource = Some(resource);
+        self
+    }
+
+    pub fn unset_resource(&mut self) -> &mut Self {
+        self.resource = None;
         self
     }
 
     pub fn any_resource(&mut self) -> &mut Self {
-        self.resource = Resource::Resource(OrAny::Any);
+        self.resource = Some(Resource::Resource(OrAny::Any));
         self
     }
 
     pub fn no_resource(&mut self) -> &mut Self {
-        self.resource = Resource::NotResource(OrAny::Any);
+        self.resource = Some(Resource::NotResource(OrAny::Any));
         self
     }
 
@@ -294,4 +396,367 @@ impl Statement {
         self.condition = Some(condition);
         self
     }
+
+    ///
+    /// Add `condition` to any existing condition on this statement, rather than
+    /// replacing it.
+    ///
+    pub fn add_condition(&mut self, condition: Condition) -> &mut Self {
+        match &mut self.condition {
+            Some(existing) => existing.merge(condition),
+            None => self.condition = Some(condition),
+        }
+        self
+    }
+
+    ///
+    /// Add `guard` to any existing condition on this statement such that the statement can
+    /// only match fewer requests; an error is returned, and the statement is not changed, if
+    /// the existing condition already tests one of the keys in `guard`, with the same
+    /// operator, but with other values. See `Condition::add_guard`.
+    ///
+    pub fn add_guard(&mut self, guard: Condition) -> Result<&mut Self, IamFormatError> {
+        match &mut self.condition {
+            Some(existing) => existing.add_guard(guard)?,
+            None => self.condition = Some(guard),
+        }
+        Ok(self)
+    }
+
+    ///
+    /// Restrict this statement to principals in the AWS Organization `org_id`, by adding
+    /// an `aws:PrincipalOrgID` condition. An error is returned if the statement is already
+    /// restricted to another organization.
+    ///
+    pub fn restrict_to_org<S>(&mut self, org_id: S) -> Result<&mut Self, IamFormatError>
+    where
+        S: Into<String>,
+    {
+        self.add_guard(Condition::principal_org_id(vec![org_id])?)
+    }
+
+    ///
+    /// Restrict this statement to principals in any of the organization entity paths
+    /// `org_paths`, by adding an `aws:PrincipalOrgPaths` condition. An error is returned if
+    /// the statement is already restricted to other paths.
+    ///
+    pub fn restrict_to_org_paths<S>(
+        &mut self,
+        org_paths: Vec<S>,
+    ) -> Result<&mut Self, IamFormatError>
+    where
+        S: Into<String>,
+    {
+        self.add_guard(Condition::principal_org_paths(org_paths)?)
+    }
+
+    // --------------------------------------------------------------------------------------------
+
+    ///
+    /// Returns `true` if any of the principal, resource, or condition values in this statement
+    /// contain policy variables; these are only recognized in policies with version
+    /// `2012-10-17`.
+    ///
+    pub fn has_variables(&self) -> bool {
+        self.principal
+            .as_ref()
+            .map(Principal::has_variables)
+            .unwrap_or_default()
+            || self
+                .resource
+                .as_ref()
+                .map(Resource::has_variables)
+                .unwrap_or_default()
+            || self
+                .condition
+                .as_ref()
+                .map(Condition::has_variables)
+                .unwrap_or_default()
+    }
+
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns `true` if the effect of this statement is `Allow`.
+    pub fn is_allow(&self) -> bool {
+        self.effect == Effect::Allow
+    }
+
+    /// Returns `true` if the effect of this statement is `Deny`.
+    pub fn is_deny(&self) -> bool {
+        self.effect == Effect::Deny
+    }
+
+    ///
+    /// Returns `true` if this statement has a condition that tests `context_key` with
+    /// `operator`, see `Condition::has_key`.
+    ///
+    pub fn has_condition(&self, operator: GlobalOperator, context_key: &str) -> bool {
+        self.condition
+            .as_ref()
+            .map(|condition| condition.has_key(operator, context_key))
+            .unwrap_or_default()
+    }
+
+    ///
+    /// Returns `true` if this is an `Allow` statement whose action element covers `action`, see
+    /// `Action::matches`. Any conditions on the statement are not evaluated.
+    ///
+    pub fn allows_action(&self, action: &str) -> bool {
+        self.is_allow() && self.action.matches(action)
+    }
+
+    ///
+    /// Returns `true` if the resource element of this statement covers `arn`, see
+    /// `Resource::matches`. A statement with no resource element, such as in a trust
+    /// policy, applies to the resource the policy is attached to and so always returns `true`.
+    ///
+    pub fn applies_to_resource(&self, arn: &ARN) -> bool {
+        self.resource
+            .as_ref()
+            .map(|resource| resource.matches(arn))
+            .unwrap_or(true)
+    }
+
+    /// Returns `true` if the principal of this statement is public, see `Principal::is_public`.
+    pub fn principal_is_public(&self) -> bool {
+        self.principal
+            .as_ref()
+            .map(Principal::is_public)
+            .unwrap_or_default()
+    }
+
+    ///
+    /// Returns the most privileged access level of the known actions selected by the action
+    /// element of this statement, or `None` if it selects no known action; see
+    /// `service::actions::AccessLevel`. Wildcards, `"*"`, and `NotAction` select every known
+    /// action they match.
+    ///
+    #[cfg(feature = "service_config")]
+    pub fn max_access_level(&self) -> Option<AccessLevel> {
+        all_metadata()
+            .filter(|metadata| self.action.matches(&metadata.qualified_name()))
+            .map(|metadata| metadata.access_level)
+            .max()
+    }
+
+    // --------------------------------------------------------------------------------------------
+
+    ///
+    /// Returns the known partitions of all the ARNs in the resource, principal, and condition
+    /// values of this statement.
+    ///
+    pub fn referenced_partitions(&self) -> BTreeSet<Partition> {
+        self.arn_values()
+            .iter()
+            .filter_map(|arn| arn_partition(arn))
+            .collect()
+    }
+
+    ///
+    /// Returns the regions of all the ARNs in the resource, principal, and condition values of
+    /// this statement, as well as those tested by the `aws:RequestedRegion` condition key.
+    ///
+    pub fn referenced_regions(&self) -> BTreeSet<Region> {
+        let mut regions: BTreeSet<Region> = self
+            .arn_values()
+            .iter()
+            .filter_map(|arn| arn_region(arn))
+            .collect();
+        let requested_region = QualifiedName::from(GlobalConditionKey::RequestedRegion);
+        if let Some(condition) = &self.condition {
+            regions.extend(
+                condition
+                    .values()
+                    .filter_map(|matches| matches.get(&requested_region))
+                    .flatten()
+                    .filter_map(|value| Region::from_str(value).ok()),
+            );
+        }
+        regions
+    }
+
+    ///
+    /// Returns the accounts of all the ARNs in the resource, principal, and condition values of
+    /// this statement, as well as those tested by the `aws:PrincipalAccount`,
+    /// `aws:ResourceAccount`, and `aws:SourceAccount` condition keys. Accounts are returned in
+    /// order, without duplicates; as `AccountIdentifier` is not `Ord` they are returned as a
+    /// `Vec` rather than a set.
+    ///
+    pub fn referenced_accounts(&self) -> Vec<AccountIdentifier> {
+        let mut accounts: BTreeSet<String> = self
+            .arn_values()
+            .iter()
+            .filter_map(|arn| arn_account(arn))
+            .map(|account| account.to_string())
+            .collect();
+        if let Some(condition) = &self.condition {
+            let account_keys: Vec<QualifiedName> = vec![
+                GlobalConditionKey::PrincipalAccount.into(),
+                GlobalConditionKey::ResourceAccount.into(),
+                GlobalConditionKey::SourceAccount.into(),
+            ];
+            accounts.extend(
+                condition
+                    .values()
+                    .flat_map(|matches| account_keys.iter().filter_map(move |k| matches.get(k)))
+                    .flatten()
+                    .filter_map(|value| account_id(value))
+                    .map(|account| account.to_string()),
+            );
+        }
+        accounts
+            .iter()
+            .map(|account| AccountIdentifier::new_unchecked(account))
+            .collect()
+    }
+
+    ///
+    /// Escape any policy variables in the resource, principal, and condition values of this
+    /// statement so that they are read as literal strings, as they are in a policy with
+    /// version `2008-10-17`; see `Policy::upgrade_version`.
+    ///
+    pub fn escape_variables(&self) -> Self {
+        Self {
+            principal: self.principal.as_ref().map(Principal::escape_variables),
+            resource: self.resource.as_ref().map(Resource::escape_variables),
+            condition: self.condition.as_ref().map(Condition::escape_variables),
+            ..self.clone()
+        }
+    }
+
+    ///
+    /// Re-target all the ARNs in the resource, principal, and condition values of this
+    /// statement to `partition` and, if provided, `region`; see
+    /// [`retarget_arn_str`](../../arn/fn.retarget_arn_str.html).
+    ///
+    pub fn retarget(
+        &self,
+        partition: Partition,
+        region: Option<&Region>,
+    ) -> Result<Self, IamFormatError> {
+        Ok(Self {
+            principal: self
+                .principal
+                .as_ref()
+                .map(|principal| principal.retarget(partition, region))
+                .transpose()?,
+            resource: self
+                .resource
+                .as_ref()
+                .map(|resource| resource.retarget(partition, region))
+                .transpose()?,
+            condition: self
+                .condition
+                .as_ref()
+                .map(|condition| condition.retarget(partition, region)),
+            ..self.clone()
+        })
+    }
+
+    ///
+    /// Rewrite the accounts of all the ARNs in the resource, principal, and condition values of
+    /// this statement, and of condition values that are account IDs, that are one of the keys of
+    /// `accounts` to the corresponding value.
+    ///
+    pub fn rewrite_accounts(
+        &self,
+        accounts: &HashMap<AccountIdentifier, AccountIdentifier>,
+    ) -> Result<Self, IamFormatError> {
+        Ok(Self {
+            principal: self
+                .principal
+                .as_ref()
+                .map(|principal| principal.rewrite_accounts(accounts))
+                .transpose()?,
+            resource: self
+                .resource
+                .as_ref()
+                .map(|resource| resource.rewrite_accounts(accounts))
+                .transpose()?,
+            condition: self
+                .condition
+                .as_ref()
+                .map(|condition| condition.rewrite_accounts(accounts)),
+            ..self.clone()
+        })
+    }
+
+    /// Returns this statement with its condition in normal form, see `Condition::normalize`.
+    pub fn normalize_conditions(&self) -> Self {
+        Self {
+            condition: self.condition.as_ref().map(Condition::normalize),
+            ..self.clone()
+        }
+    }
+
+    fn arn_values(&self) -> Vec<String> {
+        let mut values: Vec<String> = Default::default();
+        if let Some(Resource::Resource(OrAny::Some(entries)))
+        | Some(Resource::NotResource(OrAny::Some(entries))) = &self.resource
+        {
+            values.extend(entries.iter().map(ResourceEntry::to_string));
+        }
+        if let Some(Principal::Principal(OrAny::Some(map)))
+        | Some(Principal::NotPrincipal(OrAny::Some(map))) = &self.principal
+        {
+            values.extend(map.aws_iter().map(ResourceEntry::to_string));
+        }
+        if let Some(condition) = &self.condition {
+            values.extend(
+                condition
+                    .values()
+                    .flat_map(|matches| matches.values())
+                    .flatten()
+                    .map(ConditionValue::to_string),
+            );
+        }
+        values
+    }
+
+    // --------------------------------------------------------------------------------------------
+
+    ///
+    /// Returns a hash of the content of this statement, ignoring the `Sid`, so that statements
+    /// that differ only in their identifier have the same identity. Like equality the identity
+    /// does not depend on the order of condition operators or keys, but does depend on the order
+    /// of actions, resources, principals, and condition values.
+    ///
+    /// The identity is the 64-bit FNV-1a hash of the statement's canonical form, its JSON
+    /// representation without the `Sid`, without whitespace, and with the keys of every object
+    /// in sorted order, as for `io::to_canonical_string`. It is therefore stable across
+    /// platforms, compiler versions, and releases of this crate, and may be stored.
+    ///
+    pub fn identity(&self) -> u64 {
+        let mut json = self
+            .to_json()
+            .expect("a statement can always be written as JSON");
+        if let Value::Object(statement) = &mut json {
+            let _ = statement.remove(SID_NAME);
+        }
+        let canonical =
+            serde_json::to_string(&sorted(json)).expect("a JSON value can always be written");
+        fnv_1a(canonical.as_bytes())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The 64-bit FNV-1a hash of `bytes`; unlike `DefaultHasher` the algorithm is fixed, so the
+/// result does not depend on the standard library version or the platform.
+///
+fn fnv_1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
 }
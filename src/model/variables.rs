@@ -0,0 +1,124 @@
+/*!
+Support for policy variables, placeholders of the form `${aws:username}` that may be used in
+the `Resource` element, in `Principal` values, and in condition values.
+
+From [IAM policy elements: Variables and tags](https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_policies_variables.html):
+
+> Use AWS Identity and Access Management (IAM) policy variables as placeholders when you don't
+> know the exact value of a resource or condition key when you write the policy.
+
+A variable may specify a default value, used when the key is not present in the request
+context, as in `${aws:username, 'anonymous'}`. The special variables `${*}`, `${?}`, and `${$}`
+represent the literal characters `*`, `?`, and `$` respectively.
+
+# Example
+
+```rust
+use aws_iam::model::variables::{has_variables, replace_variables, variable_names};
+use std::collections::HashMap;
+
+let value = "arn:aws:s3:::bucket/home/${aws:username}";
+assert!(has_variables(value));
+assert_eq!(variable_names(value), vec!["aws:username".to_string()]);
+
+let mut context: HashMap<String, String> = Default::default();
+context.insert("aws:username".to_string(), "alice".to_string());
+assert_eq!(
+    replace_variables(value, &context),
+    "arn:aws:s3:::bucket/home/alice"
+);
+```
+*/
+
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+/// Return `true` if `s` contains variables of the form `${name}`, else `false`.
+pub fn has_variables(s: &str) -> bool {
+    REGEX_VARIABLE.is_match(s)
+}
+
+///
+/// Return the names of all variables in `s`, in the order they appear. The special
+/// variables `${*}`, `${?}`, and `${$}` are not included.
+///
+pub fn variable_names(s: &str) -> Vec<String> {
+    REGEX_VARIABLE
+        .captures_iter(s)
+        .map(|caps| caps[1].to_string())
+        .filter(|name| special_value(name).is_none())
+        .collect()
+}
+
+///
+/// Replace all variables in `s` with values from `context`. A variable not present in the
+/// context is replaced by its default value, if it has one, else it is left as-is.
+///
+pub fn replace_variables<V>(s: &str, context: &HashMap<String, V>) -> String
+where
+    V: Clone + Into<String>,
+{
+    REGEX_VARIABLE
+        .replace_all(s, |caps: &Captures<'_>| {
+            let name = &caps[1];
+            if let Some(value) = special_value(name) {
+                value.to_string()
+            } else if let Some(value) = context.get(name) {
+                value.clone().into()
+            } else if let Some(default) = caps.get(2) {
+                default.as_str().to_string()
+            } else {
+                caps[0].to_string()
+            }
+        })
+        .to_string()
+}
+
+///
+/// Replace all variables in `s` with `value`, ignoring any defaults. This is useful to
+/// check the shape of a templated value, for example that it has the right number of ARN
+/// components.
+///
+pub fn replace_variables_with(s: &str, value: &str) -> String {
+    REGEX_VARIABLE
+        .replace_all(s, |caps: &Captures<'_>| {
+            special_value(&caps[1]).unwrap_or(value).to_string()
+        })
+        .to_string()
+}
+
+///
+/// Escape all variables in `s` so that it is read as the literal string it would be in a
+/// policy with version `2008-10-17`, in which variables are not recognized; each `${` is
+/// written `${$}{`. A string without variables is returned unchanged.
+///
+pub fn escape_variables(s: &str) -> String {
+    if has_variables(s) {
+        s.replace("${", "${$}{")
+    } else {
+        s.to_string()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+lazy_static! {
+    static ref REGEX_VARIABLE: Regex =
+        Regex::new(r"\$\{\s*([^},\s]+)\s*(?:,\s*'([^']*)'\s*)?\}").unwrap();
+}
+
+fn special_value(name: &str) -> Option<&'static str> {
+    match name {
+        "*" => Some("*"),
+        "?" => Some("?"),
+        "$" => Some("$"),
+        _ => None,
+    }
+}
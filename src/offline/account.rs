@@ -0,0 +1,45 @@
+/*!
+Provides whole-account queries over the users and roles read from the output of
+`aws iam get-account-authorization-details`, see
+[`AccountAuthorizationDetails`](../../io/struct.AccountAuthorizationDetails.html).
+
+Each principal's identity policies, including those of its groups, are evaluated and, where
+the principal has a permissions boundary, the boundary must also allow the request. Resource
+policies, for example KMS key policies or S3 bucket policies, are not part of the output and so
+are not considered; to include them evaluate a principal's policies with
+[`evaluate_joint`](../fn.evaluate_joint.html).
+*/
+
+use crate::io::AccountAuthorizationDetails;
+use crate::offline::{evaluate_all, EvaluationError, Principal, Request};
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns the ARNs of the users and roles in `details` whose identity policies allow
+/// `request`, for example which principals can perform `kms:Decrypt` on a given key. The
+/// principal of `request` is replaced by each user and role in turn.
+///
+pub fn principals_allowed<'a>(
+    details: &'a AccountAuthorizationDetails,
+    request: &Request,
+) -> Result<Vec<&'a str>, EvaluationError> {
+    let mut allowed: Vec<&'a str> = Default::default();
+    for principal_arn in details.principal_arns() {
+        let mut request = request.clone();
+        request.principal = Some(Principal::aws(principal_arn));
+        let policies = details.identity_policies(principal_arn);
+        if !evaluate_all(&request, &policies)?.is_allowed() {
+            continue;
+        }
+        if let Some(boundary) = details.permissions_boundary(principal_arn) {
+            if !evaluate_all(&request, &[boundary])?.is_allowed() {
+                continue;
+            }
+        }
+        allowed.push(principal_arn);
+    }
+    Ok(allowed)
+}
@@ -0,0 +1,207 @@
+use crate::model::Policy;
+use crate::offline::compiled::CompiledPolicy;
+use crate::offline::request::Request;
+use crate::offline::{evaluate_all_compiled, EvaluationError, EvaluationResult};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// An evaluator for use as an application authorizer that caches the most recent decisions.
+/// Decisions are keyed by the request's principal, action, resource, and a hash of its
+/// condition context, see [`Request::context`](struct.Request.html#method.context); when the
+/// cache is full the least recently used decision is discarded. Errors are not cached.
+///
+/// The evaluator borrows the policies it evaluates, so they cannot change while it exists;
+/// to evaluate a different set of policies use `set_policies`, which also discards all cached
+/// decisions. If a decision may depend on anything not in the request, such as the time,
+/// call `invalidate` when that changes.
+///
+/// # Example
+///
+/// ```rust
+/// use aws_iam::io;
+/// use aws_iam::offline::{CachingEvaluator, Request};
+/// use std::path::PathBuf;
+///
+/// let policy = io::read_from_file(&PathBuf::from("tests/data/good/quantified-001.json"))
+///     .expect("Error reading file");
+/// let mut evaluator = CachingEvaluator::new(&[&policy], 1024);
+///
+/// let requests: Vec<Request> = Vec::new();
+/// for request in &requests {
+///     println!("result: {:?}", evaluator.evaluate(request));
+/// }
+/// println!("hits: {}, misses: {}", evaluator.hits(), evaluator.misses());
+/// ```
+///
+#[derive(Debug)]
+pub struct CachingEvaluator<'a> {
+    policies: Vec<CompiledPolicy<'a>>,
+    capacity: usize,
+    decisions: HashMap<CacheKey, (EvaluationResult, u64)>,
+    recency: BTreeMap<u64, CacheKey>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    principal: Option<String>,
+    action: String,
+    resource: String,
+    context: u64,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<'a> CachingEvaluator<'a> {
+    ///
+    /// Create an evaluator for `policies` that retains at most `capacity` decisions; a
+    /// capacity of zero disables caching.
+    ///
+    pub fn new(policies: &[&'a Policy], capacity: usize) -> Self {
+        Self {
+            policies: compile(policies),
+            capacity,
+            decisions: Default::default(),
+            recency: Default::default(),
+            tick: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    ///
+    /// Evaluate `request` against all the policies, returning the cached decision for an
+    /// equivalent request if there is one.
+    ///
+    pub fn evaluate(&mut self, request: &Request) -> Result<EvaluationResult, EvaluationError> {
+        let key = CacheKey::from(request);
+        self.tick += 1;
+        if let Some((result, last_used)) = self.decisions.get_mut(&key) {
+            let _ = self.recency.remove(last_used);
+            *last_used = self.tick;
+            let _ = self.recency.insert(self.tick, key);
+            self.hits += 1;
+            return Ok(result.clone());
+        }
+
+        self.misses += 1;
+        let result = evaluate_all_compiled(request, &self.policies)?;
+        if self.capacity > 0 {
+            if self.decisions.len() >= self.capacity {
+                self.evict_least_recent();
+            }
+            let _ = self.recency.insert(self.tick, key.clone());
+            let _ = self.decisions.insert(key, (result.clone(), self.tick));
+        }
+        Ok(result)
+    }
+
+    ///
+    /// Replace the policies evaluated, discarding all cached decisions.
+    ///
+    pub fn set_policies(&mut self, policies: &[&'a Policy]) {
+        self.policies = compile(policies);
+        self.invalidate();
+    }
+
+    ///
+    /// Discard all cached decisions; the hit and miss counts are retained.
+    ///
+    pub fn invalidate(&mut self) {
+        self.decisions.clear();
+        self.recency.clear();
+    }
+
+    /// Returns the number of cached decisions.
+    pub fn len(&self) -> usize {
+        self.decisions.len()
+    }
+
+    /// Returns `true` if there are no cached decisions.
+    pub fn is_empty(&self) -> bool {
+        self.decisions.is_empty()
+    }
+
+    /// Returns the maximum number of cached decisions.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of requests answered from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Returns the number of requests that were evaluated.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    fn evict_least_recent(&mut self) {
+        let least_recent = self.recency.keys().next().copied();
+        if let Some(tick) = least_recent {
+            if let Some(key) = self.recency.remove(&tick) {
+                let _ = self.decisions.remove(&key);
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl From<&Request> for CacheKey {
+    ///
+    /// The context is hashed in key order, so that two requests with the same context but
+    /// built in a different order share a key. Values are hashed by their JSON form, as not all
+    /// condition values implement `Hash`.
+    ///
+    fn from(request: &Request) -> Self {
+        let context = request.context();
+        let mut entries: Vec<(String, String)> = context
+            .iter()
+            .map(|(key, value)| {
+                (
+                    key.to_string(),
+                    serde_json::to_string(value).unwrap_or_default(),
+                )
+            })
+            .collect();
+        entries.sort();
+        let mut hasher = DefaultHasher::new();
+        entries.hash(&mut hasher);
+        Self {
+            principal: request
+                .principal
+                .as_ref()
+                .and_then(|principal| serde_json::to_string(principal).ok()),
+            action: request.action.to_string(),
+            resource: request.resource.clone(),
+            context: hasher.finish(),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn compile<'a>(policies: &[&'a Policy]) -> Vec<CompiledPolicy<'a>> {
+    policies
+        .iter()
+        .map(|policy| CompiledPolicy::new(policy))
+        .collect()
+}
@@ -0,0 +1,214 @@
+/*!
+Provides the clock used to determine the time of a request, so that tests of time-based
+conditions may fix the time rather than add `aws:CurrentTime` to every request.
+
+A [`Clock`](trait.Clock.html) is given to an [`Evaluator`](../struct.Evaluator.html) with
+[`with_clock`](../struct.Evaluator.html#method.with_clock), which provides `aws:CurrentTime` and
+`aws:EpochTime` from it. [`SystemClock`](struct.SystemClock.html) reads the system clock and
+[`FixedClock`](struct.FixedClock.html) reports a time set by the caller, which may be moved
+forward between evaluations.
+
+The `Date*` condition operators compare times given either as ISO 8601 UTC strings, such as
+`2020-05-20T18:40:00Z` or `2020-05-20`, or as seconds since the epoch.
+*/
+
+use std::cell::Cell;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A source of the current time, in seconds since the epoch.
+///
+pub trait Clock {
+    /// Return the current time, in seconds since the epoch.
+    fn now(&self) -> i64;
+
+    /// Return the current time as an ISO 8601 UTC string, the form of `aws:CurrentTime`.
+    fn current_time(&self) -> String {
+        format_utc_time(self.now())
+    }
+}
+
+///
+/// A clock that reads the system clock.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+///
+/// A clock that reports a time set by the caller; it does not advance unless told to.
+///
+#[derive(Clone, Debug, Default)]
+pub struct FixedClock {
+    now: Cell<i64>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<C> Clock for &C
+where
+    C: Clock + ?Sized,
+{
+    fn now(&self) -> i64 {
+        (**self).now()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Clock for SystemClock {
+    fn now(&self) -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs() as i64)
+            .unwrap_or_default()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Clock for FixedClock {
+    fn now(&self) -> i64 {
+        self.now.get()
+    }
+}
+
+impl FixedClock {
+    /// A clock that reports the time `epoch_seconds`.
+    pub fn at(epoch_seconds: i64) -> Self {
+        Self {
+            now: Cell::new(epoch_seconds),
+        }
+    }
+
+    ///
+    /// A clock that reports the time `time`, an ISO 8601 UTC string; `None` if `time` is not
+    /// valid.
+    ///
+    pub fn at_time(time: &str) -> Option<Self> {
+        parse_utc_time(time).map(Self::at)
+    }
+
+    /// Set the time reported to `epoch_seconds`.
+    pub fn set(&self, epoch_seconds: i64) {
+        self.now.set(epoch_seconds)
+    }
+
+    /// Move the time reported forward by `seconds`.
+    pub fn advance(&self, seconds: i64) {
+        self.now.set(self.now.get() + seconds)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Format `epoch_seconds` as an ISO 8601 UTC string, `YYYY-MM-DDTHH:MM:SSZ`.
+///
+pub(crate) fn format_utc_time(epoch_seconds: i64) -> String {
+    let days = epoch_seconds.div_euclid(SECONDS_PER_DAY);
+    let seconds = epoch_seconds.rem_euclid(SECONDS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    )
+}
+
+///
+/// Parse an ISO 8601 UTC time, `YYYY-MM-DDTHH:MM:SSZ` with optional fractional seconds, or a
+/// date `YYYY-MM-DD`, returning seconds since the epoch.
+///
+pub(crate) fn parse_utc_time(time: &str) -> Option<i64> {
+    let (date, time) = match time.split_once('T') {
+        Some((date, time)) => (date, Some(time.strip_suffix('Z')?)),
+        None => (time, None),
+    };
+    let date = parse_fields(date, '-')?;
+    let time = match time {
+        Some(time) => parse_fields(time.split('.').next()?, ':')?,
+        None => vec![0, 0, 0],
+    };
+    match (date.as_slice(), time.as_slice()) {
+        ([year, month, day], [hours, minutes, seconds])
+            if (1..=12).contains(month)
+                && (1..=31).contains(day)
+                && *hours < 24
+                && *minutes < 60
+                && *seconds < 61 =>
+        {
+            Some(
+                days_from_civil(*year, *month, *day) * SECONDS_PER_DAY
+                    + hours * 3600
+                    + minutes * 60
+                    + seconds,
+            )
+        }
+        _ => None,
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn parse_fields(value: &str, separator: char) -> Option<Vec<i64>> {
+    value
+        .split(separator)
+        .map(|part| {
+            if !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()) {
+                part.parse().ok()
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+// the conversions between days since the epoch and the proleptic Gregorian calendar are from
+// http://howardhinnant.github.io/date_algorithms.html
+
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const SECONDS_PER_DAY: i64 = 86_400;
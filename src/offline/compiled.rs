@@ -0,0 +1,237 @@
+use crate::arn::{ArnPattern, WildcardPattern};
+use crate::model::{Action, Effect, OrAny, Policy, Resource, ResourceEntry, Statement};
+use std::borrow::Cow;
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A policy prepared for evaluation against many requests, such as an in-process authorizer.
+/// When a policy is compiled:
+///
+/// * the resource patterns of each statement are parsed, once, as ARN patterns; values that
+///   are not valid ARN patterns can never match a request and are dropped,
+/// * action patterns are prepared to be compared ignoring case, as IAM does,
+/// * each wildcard pattern is compiled to the cheapest matcher that implements it, see
+///   [`WildcardPattern`](../arn/struct.WildcardPattern.html), and
+/// * statements with the `Deny` effect are ordered before those with the `Allow` effect. As an
+///   explicit deny always overrides an allow, evaluation stops at the first statement that
+///   denies the request.
+///
+/// A compiled policy borrows the policy it was created from; one converted from a `Policy`
+/// owns it instead, and so may be held for as long as needed, such as by a policy set that is
+/// replaced when its files change.
+///
+/// # Example
+///
+/// ```rust
+/// use aws_iam::io;
+/// use aws_iam::offline::{compile, evaluate_compiled, Request};
+/// use std::path::PathBuf;
+///
+/// let policy = io::read_from_file(&PathBuf::from("tests/data/good/quantified-001.json"))
+///     .expect("Error reading file");
+/// let compiled = compile(&policy);
+///
+/// let requests: Vec<Request> = Vec::new();
+/// for request in &requests {
+///     println!("result: {:?}", evaluate_compiled(request, &compiled));
+/// }
+/// ```
+///
+#[derive(Debug)]
+pub struct CompiledPolicy<'a> {
+    policy: Cow<'a, Policy>,
+    statements: Vec<StatementPatterns>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A single statement, with its action and resource patterns compiled.
+///
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CompiledStatement<'a> {
+    pub(crate) index: usize,
+    pub(crate) statement: &'a Statement,
+    pub(crate) actions: &'a Patterns<WildcardPattern>,
+    pub(crate) resources: &'a Patterns<CompiledArnPattern>,
+}
+
+///
+/// The compiled patterns of the statement at `index` in the policy; these are held apart from
+/// the statement so that a compiled policy may own the policy they were compiled from.
+///
+#[derive(Debug)]
+struct StatementPatterns {
+    index: usize,
+    actions: Patterns<WildcardPattern>,
+    resources: Patterns<CompiledArnPattern>,
+}
+
+///
+/// The patterns listed in an `Action`, `NotAction`, `Resource`, or `NotResource` element; the
+/// distinction between a single value and a list is not needed once compiled.
+///
+#[derive(Debug)]
+pub(crate) enum Patterns<T> {
+    Any,
+    Some(Vec<T>),
+}
+
+///
+/// An ARN pattern with each component compiled; as in IAM, wildcards only match within their
+/// component other than in the resource, which is the remainder of the ARN.
+///
+#[derive(Debug)]
+pub(crate) struct CompiledArnPattern {
+    components: Vec<WildcardPattern>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl From<Policy> for CompiledPolicy<'static> {
+    fn from(policy: Policy) -> Self {
+        let statements = compile_statements(&policy);
+        Self {
+            policy: Cow::Owned(policy),
+            statements,
+        }
+    }
+}
+
+impl<'a> CompiledPolicy<'a> {
+    /// Compile the statements of `policy`, see `compile`.
+    pub fn new(policy: &'a Policy) -> Self {
+        Self {
+            policy: Cow::Borrowed(policy),
+            statements: compile_statements(policy),
+        }
+    }
+
+    /// Return the policy this was compiled from.
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    pub(crate) fn statements(&self) -> impl Iterator<Item = CompiledStatement<'_>> {
+        self.statements
+            .iter()
+            .map(move |compiled| CompiledStatement {
+                index: compiled.index,
+                statement: &self.policy.statement[compiled.index],
+                actions: &compiled.actions,
+                resources: &compiled.resources,
+            })
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl StatementPatterns {
+    fn new(index: usize, statement: &Statement) -> Self {
+        let actions = match &statement.action {
+            Action::Action(actions) | Action::NotAction(actions) => match actions {
+                OrAny::Any => Patterns::Any,
+                OrAny::Some(actions) => {
+                    Patterns::Some(actions.iter().map(action_pattern).collect())
+                }
+            },
+        };
+        let resources = match &statement.resource {
+            // without a resource element the statement applies to the resource the policy is
+            // attached to, which is the resource of every request.
+            None => Patterns::Any,
+            Some(Resource::Resource(resources)) | Some(Resource::NotResource(resources)) => {
+                match resources {
+                    OrAny::Any => Patterns::Any,
+                    OrAny::Some(resources) => Patterns::Some(resource_patterns(resources)),
+                }
+            }
+        };
+        Self {
+            index,
+            actions,
+            resources,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl CompiledArnPattern {
+    ///
+    /// Returns `true` if the string `arn` is matched by this pattern, see
+    /// `ArnPattern::matches_str`.
+    ///
+    pub(crate) fn matches(&self, arn: &str) -> bool {
+        let mut parts = arn.splitn(6, ARN_SEPARATOR);
+        parts.next() == Some(ARN_PREFIX)
+            && self
+                .components
+                .iter()
+                .all(|component| parts.next().is_some_and(|part| component.matches(part)))
+    }
+}
+
+impl From<&ArnPattern> for CompiledArnPattern {
+    fn from(pattern: &ArnPattern) -> Self {
+        Self {
+            components: vec![
+                pattern.partition(),
+                pattern.service(),
+                pattern.region(),
+                pattern.account_id(),
+                pattern.resource(),
+            ]
+            .into_iter()
+            .map(WildcardPattern::new)
+            .collect(),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const ARN_PREFIX: &str = "arn";
+
+const ARN_SEPARATOR: char = ':';
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn compile_statements(policy: &Policy) -> Vec<StatementPatterns> {
+    let mut statements: Vec<StatementPatterns> = policy
+        .statement
+        .iter()
+        .enumerate()
+        .map(|(index, statement)| StatementPatterns::new(index, statement))
+        .collect();
+    // a stable sort, so statements with the same effect retain their order.
+    statements.sort_by_key(|compiled| policy.statement[compiled.index].effect != Effect::Deny);
+    statements
+}
+
+fn action_pattern<S>(action: S) -> WildcardPattern
+where
+    S: ToString,
+{
+    WildcardPattern::new_ignore_case(&action.to_string())
+}
+
+fn resource_patterns(resources: &[ResourceEntry]) -> Vec<CompiledArnPattern> {
+    resources
+        .iter()
+        .filter_map(|resource| ArnPattern::from_str(&resource.to_string()).ok())
+        .map(|pattern| CompiledArnPattern::from(&pattern))
+        .collect()
+}
@@ -0,0 +1,207 @@
+/*!
+Provides counterexamples for denied requests, changes to the request context that would allow
+a request that is denied.
+
+Each `Allow` statement whose principal, action, and resource elements select the request is
+examined; for each of its conditions that denies the request a change to the context is
+proposed from the condition's operator and values. Equality operators, including `Bool`, set
+the key to the condition's value, and numeric comparisons to the nearest value that satisfies
+them. Negated operators, and `Null` with the value `true`, remove the key. Conditions with a
+quantifier, wildcards, or policy variables in their values are not changed, and a statement
+with any such failing condition is not proposed. Each proposal is then evaluated against every
+policy, so that a proposal that would still be denied, for example by an explicit `Deny`
+statement, is discarded.
+*/
+
+use crate::model::{ConditionValue, Effect, GlobalOperator, Operator, Policy, QualifiedName};
+use crate::offline::policy::policy_id;
+use crate::offline::statement::{failing_conditions, sid_string};
+use crate::offline::{
+    compile_all, evaluate_in_environment, EvaluationError, EvaluationResult, Request,
+};
+use std::fmt::{Display, Formatter};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A single change to the context of a request.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum ContextChange {
+    /// Set the key to the value.
+    Set(QualifiedName, ConditionValue),
+    /// Remove the key from the context.
+    Remove(QualifiedName),
+}
+
+///
+/// A set of changes to the context of a request that would allow it, by applying the `Allow`
+/// statement identified.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Counterexample {
+    /// The `Id` of the policy containing the statement or, if it has none, its index in
+    /// brackets.
+    pub policy: String,
+    /// The index of the statement in the policy.
+    pub statement: usize,
+    /// The `Sid` of the statement, if any.
+    pub sid: Option<String>,
+    /// The changes to make to the request context.
+    pub changes: Vec<ContextChange>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns the changes to the context of `request` that would allow it when evaluated against
+/// `policies`, those with the fewest changes first; if the request is allowed, or no change to
+/// the context would allow it, the result is empty.
+///
+pub fn counterexamples(
+    request: &Request,
+    policies: &[&Policy],
+) -> Result<Vec<Counterexample>, EvaluationError> {
+    let policies = compile_all(policies);
+    let request_action = request.action.to_string();
+    let environment = request.context();
+    if matches!(
+        evaluate_in_environment(request, &request_action, &environment, &policies)?,
+        EvaluationResult::Allow(_)
+    ) {
+        return Ok(Default::default());
+    }
+
+    let mut results: Vec<Counterexample> = Default::default();
+    for (policy_index, policy) in policies.iter().enumerate() {
+        for statement in policy.statements() {
+            if statement.statement.effect != Effect::Allow {
+                continue;
+            }
+            let failing =
+                match failing_conditions(request, &request_action, &environment, &statement) {
+                    Some(failing) if !failing.is_empty() => failing,
+                    _ => continue,
+                };
+            let changes: Option<Vec<ContextChange>> = failing
+                .into_iter()
+                .map(|(operator, key, values)| context_change(operator, key, values))
+                .collect();
+            let changes = match changes {
+                Some(changes) => changes,
+                None => continue,
+            };
+            let mut changed = environment.clone();
+            for change in &changes {
+                match change {
+                    ContextChange::Set(key, value) => {
+                        let _ = changed.insert(key.clone(), value.clone());
+                    }
+                    ContextChange::Remove(key) => {
+                        let _ = changed.remove(key);
+                    }
+                }
+            }
+            if matches!(
+                evaluate_in_environment(request, &request_action, &changed, &policies)?,
+                EvaluationResult::Allow(_)
+            ) && !results.iter().any(|r| r.changes == changes)
+            {
+                results.push(Counterexample {
+                    policy: policy_id(policy.policy(), policy_index as i32),
+                    statement: statement.index,
+                    sid: sid_string(statement.statement),
+                    changes,
+                });
+            }
+        }
+    }
+    results.sort_by_key(|r| r.changes.len());
+    Ok(results)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for ContextChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Set(key, value) => write!(f, "set {}={}", key, value),
+            Self::Remove(key) => write!(f, "remove {}", key),
+        }
+    }
+}
+
+impl Display for Counterexample {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let changes: Vec<String> = self.changes.iter().map(|c| c.to_string()).collect();
+        write!(
+            f,
+            "{} (allowed by {} Statement[{}]",
+            changes.join(", "),
+            self.policy,
+            self.statement
+        )?;
+        if let Some(sid) = &self.sid {
+            write!(f, " {}", sid)?;
+        }
+        write!(f, ")")
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn context_change(
+    operator: &Operator,
+    key: &QualifiedName,
+    values: &[ConditionValue],
+) -> Option<ContextChange> {
+    let value = match (&operator.quantifier, values) {
+        (None, [value]) => value,
+        _ => return None,
+    };
+    let set = |value: ConditionValue| Some(ContextChange::Set(key.clone(), value));
+    let adjacent = |delta: i64| {
+        value
+            .parse::<i64>()
+            .ok()
+            .and_then(|i| set(ConditionValue::number(i + delta)))
+    };
+    match &operator.operator {
+        GlobalOperator::StringLike | GlobalOperator::ArnLike if value.contains(['*', '?']) => None,
+        _ if value.contains("${") => None,
+        GlobalOperator::StringEquals
+        | GlobalOperator::StringEqualsIgnoreCase
+        | GlobalOperator::StringLike
+        | GlobalOperator::ArnEquals
+        | GlobalOperator::ArnLike
+        | GlobalOperator::DateEquals
+        | GlobalOperator::DateGreaterThanEquals
+        | GlobalOperator::DateLessThanEquals
+        | GlobalOperator::NumericEquals
+        | GlobalOperator::NumericGreaterThanEquals
+        | GlobalOperator::NumericLessThanEquals
+        | GlobalOperator::Bool => set(value.clone()),
+        GlobalOperator::NumericGreaterThan => adjacent(1),
+        GlobalOperator::NumericLessThan => adjacent(-1),
+        GlobalOperator::StringNotEquals
+        | GlobalOperator::StringNotEqualsIgnoreCase
+        | GlobalOperator::StringNotLike
+        | GlobalOperator::ArnNotEquals
+        | GlobalOperator::ArnNotLike
+        | GlobalOperator::NumericNotEquals
+        | GlobalOperator::DateNotEquals
+        | GlobalOperator::NotIpAddress => Some(ContextChange::Remove(key.clone())),
+        GlobalOperator::Null if value.eq_ignore_ascii_case("true") => {
+            Some(ContextChange::Remove(key.clone()))
+        }
+        _ => None,
+    }
+}
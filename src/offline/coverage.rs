@@ -0,0 +1,151 @@
+/*!
+Provides statement coverage, which statements of a set of policies were matched by a suite of
+requests.
+
+A statement is matched by a request if every element of the statement selects the request,
+regardless of the statement's effect and of whether other statements decided the request
+first. A statement that no request matches is either dead, it can never apply, or is not
+tested by the suite.
+*/
+
+use crate::model::Policy;
+use crate::offline::compiled::CompiledStatement;
+use crate::offline::policy::policy_id;
+use crate::offline::statement::{sid_string, statement_selects};
+use crate::offline::{compile_all, Request, TRACE_TARGET};
+use std::fmt::{Display, Formatter};
+use tracing::info;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The number of requests that matched a single statement.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StatementCoverage {
+    /// The `Id` of the policy containing the statement or, if it has none, its index in
+    /// brackets.
+    pub policy: String,
+    /// The index of the statement in the policy.
+    pub statement: usize,
+    /// The `Sid` of the statement, if any.
+    pub sid: Option<String>,
+    /// The number of requests that matched the statement.
+    pub matched: usize,
+    /// The number of requests for which the statement's conditions could not be evaluated.
+    pub errors: usize,
+}
+
+///
+/// The coverage of every statement of a set of policies by a suite of requests.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CoverageReport {
+    statements: Vec<StatementCoverage>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns the coverage of the statements of `policies` by `requests`; a statement whose
+/// conditions cannot be evaluated for a request is not matched by it.
+///
+pub fn statement_coverage(requests: &[&Request], policies: &[&Policy]) -> CoverageReport {
+    let policies = compile_all(policies);
+    // compiled statements are held deny first, coverage follows the order of each policy.
+    let compiled: Vec<(String, CompiledStatement<'_>)> = policies
+        .iter()
+        .enumerate()
+        .flat_map(|(policy_index, policy)| {
+            let id = policy_id(policy.policy(), policy_index as i32);
+            let mut statements: Vec<CompiledStatement<'_>> = policy.statements().collect();
+            statements.sort_by_key(|statement| statement.index);
+            statements
+                .into_iter()
+                .map(move |statement| (id.clone(), statement))
+        })
+        .collect();
+    let mut statements: Vec<StatementCoverage> = compiled
+        .iter()
+        .map(|(id, statement)| StatementCoverage {
+            policy: id.clone(),
+            statement: statement.index,
+            sid: sid_string(statement.statement),
+            matched: 0,
+            errors: 0,
+        })
+        .collect();
+    for request in requests {
+        let request_action = request.action.to_string();
+        let environment = request.context();
+        for (coverage, (_, statement)) in statements.iter_mut().zip(&compiled) {
+            match statement_selects(request, &request_action, &environment, statement) {
+                Ok(true) => coverage.matched += 1,
+                Ok(false) => {}
+                Err(_) => coverage.errors += 1,
+            }
+        }
+    }
+    for coverage in statements.iter().filter(|s| !s.is_covered()) {
+        info!(
+            target: TRACE_TARGET,
+            policy = coverage.policy.as_str(),
+            statement = coverage.statement,
+            sid = coverage.sid.as_deref().unwrap_or_default(),
+            "statement not matched by any request"
+        );
+    }
+    CoverageReport { statements }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for StatementCoverage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} Statement[{}]", self.policy, self.statement)?;
+        if let Some(sid) = &self.sid {
+            write!(f, " ({})", sid)?;
+        }
+        Ok(())
+    }
+}
+
+impl StatementCoverage {
+    /// Returns `true` if at least one request matched the statement.
+    pub fn is_covered(&self) -> bool {
+        self.matched > 0
+    }
+}
+
+impl CoverageReport {
+    /// Returns the coverage of each statement, in policy and then statement order.
+    pub fn statements(&self) -> &[StatementCoverage] {
+        &self.statements
+    }
+
+    /// Returns the statements not matched by any request.
+    pub fn uncovered(&self) -> Vec<&StatementCoverage> {
+        self.statements.iter().filter(|s| !s.is_covered()).collect()
+    }
+
+    /// Returns the number of statements matched by at least one request.
+    pub fn covered(&self) -> usize {
+        self.statements.iter().filter(|s| s.is_covered()).count()
+    }
+
+    /// Returns the fraction of statements matched by at least one request, `1.0` if there are
+    /// no statements.
+    pub fn ratio(&self) -> f64 {
+        if self.statements.is_empty() {
+            1.0
+        } else {
+            self.covered() as f64 / self.statements.len() as f64
+        }
+    }
+}
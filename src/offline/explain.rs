@@ -0,0 +1,164 @@
+/*!
+Provides a what-if analysis of a request, the result of each element of every statement of a
+policy, to explain why a request was allowed or denied.
+
+Evaluation stops at the first explicit deny, and at the first element of a statement that does
+not select the request; [`explain`](../fn.explain.html) does neither, so that every statement,
+element, and condition is reported.
+*/
+
+use crate::model::{Effect, Operator, Policy, QualifiedName};
+use crate::offline::statement::explain_statement;
+use crate::offline::{
+    compile, CompiledPolicy, EvaluationError, EvaluationResult, PartialEvaluationResult, Request,
+};
+use std::fmt::{Display, Formatter};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Whether an element, or condition, of a statement selects the request.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ElementMatch {
+    /// The element selects the request.
+    Matched,
+    /// The element does not select the request.
+    NotMatched,
+    /// The element was not checked, for example the request has no principal or the statement
+    /// lists no principals of the same type.
+    NotApplicable,
+}
+
+///
+/// The contribution of a statement to the decision for a request.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Contribution {
+    /// An `Allow` statement that selects the request.
+    Allow,
+    /// A `Deny` statement that selects the request.
+    ExplicitDeny,
+    /// A statement that does not select the request, or one with a condition that could not
+    /// be evaluated.
+    NoEffect,
+}
+
+///
+/// The result of a single condition of a statement, identified by its operator and key.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConditionMatch {
+    /// The condition operator.
+    pub operator: Operator,
+    /// The condition key.
+    pub key: QualifiedName,
+    /// Whether the condition selects the request, or the error that prevented it being
+    /// evaluated.
+    pub result: Result<ElementMatch, EvaluationError>,
+}
+
+///
+/// The result of every element of a single statement for a request.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatementMatchReport {
+    /// The index of the statement in the policy.
+    pub statement: usize,
+    /// The `Sid` of the statement, if any.
+    pub sid: Option<String>,
+    /// The effect of the statement.
+    pub effect: Effect,
+    /// The result of the `Principal` or `NotPrincipal` element.
+    pub principal: ElementMatch,
+    /// The result of the `Action` or `NotAction` element.
+    pub action: ElementMatch,
+    /// The result of the `Resource` or `NotResource` element.
+    pub resource: ElementMatch,
+    /// The result of each condition, in the order they appear in the statement.
+    pub conditions: Vec<ConditionMatch>,
+    /// The contribution of the statement to the decision.
+    pub contribution: Contribution,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns, for every statement of `policy`, whether each element selects `request` and the
+/// statement's contribution to the decision, in the order the statements appear in the policy.
+///
+pub fn explain(request: &Request, policy: &Policy) -> Vec<StatementMatchReport> {
+    explain_compiled(request, &compile(policy))
+}
+
+///
+/// Returns the same reports as `explain` for a compiled policy; use this in preference to
+/// `explain` when explaining many requests against the same policy.
+///
+pub fn explain_compiled(
+    request: &Request,
+    compiled: &CompiledPolicy<'_>,
+) -> Vec<StatementMatchReport> {
+    let request_action = request.action.to_string();
+    let environment = request.context();
+    let mut reports: Vec<StatementMatchReport> = compiled
+        .statements()
+        .map(|statement| explain_statement(request, &request_action, &environment, &statement))
+        .collect();
+    // compiled statements are held deny first, reports follow the order of the policy.
+    reports.sort_by_key(|report| report.statement);
+    reports
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl From<&PartialEvaluationResult> for ElementMatch {
+    fn from(result: &PartialEvaluationResult) -> Self {
+        match result {
+            Some(EvaluationResult::Allow(_)) => Self::Matched,
+            Some(EvaluationResult::Deny(_, _, _)) => Self::NotMatched,
+            None => Self::NotApplicable,
+        }
+    }
+}
+
+impl Display for ElementMatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Matched => "matched",
+                Self::NotMatched => "not matched",
+                Self::NotApplicable => "not applicable",
+            }
+        )
+    }
+}
+
+impl Display for Contribution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Allow => "allow",
+                Self::ExplicitDeny => "explicit deny",
+                Self::NoEffect => "no effect",
+            }
+        )
+    }
+}
+
+impl StatementMatchReport {
+    /// Returns `true` if every element, and condition, of the statement selects the request.
+    pub fn is_selected(&self) -> bool {
+        self.contribution != Contribution::NoEffect
+    }
+}
@@ -0,0 +1,129 @@
+/*!
+Provides the joint evaluation of the identity policies of a principal and the resource
+policies of a resource, as AWS does for a request between them.
+
+An explicit deny in either set of policies denies the request. Otherwise, when the principal
+and the resource are in the same account, the request is allowed if *either* set of policies
+allows it; when they are in different accounts both must allow it. The accounts are taken from
+the request, see [`Principal::account`](../request/struct.Principal.html#method.account) and
+[`Request::resource_account`](../request/struct.Request.html#method.resource_account); if
+either is not known the request is treated as cross-account, as this is the more restrictive.
+*/
+
+use crate::model::Policy;
+use crate::offline::{evaluate_all, EvaluationError, EvaluationResult, Request, Source};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Whether the principal and resource of a request are in the same account.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccountScope {
+    /// The principal and resource are in the same account; either set of policies may allow.
+    SameAccount,
+    /// The principal and resource are in different, or unknown, accounts; both sets of policies
+    /// must allow.
+    CrossAccount,
+}
+
+///
+/// Identifies one of the sets of policies evaluated jointly.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PolicyKind {
+    /// The identity policies of the principal.
+    Identity,
+    /// The resource policies of the resource.
+    Resource,
+}
+
+///
+/// The result of evaluating a request against identity and resource policies.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct JointEvaluation {
+    /// The rule used to combine the results.
+    pub scope: AccountScope,
+    /// The result of evaluating the identity policies alone.
+    pub identity: EvaluationResult,
+    /// The result of evaluating the resource policies alone.
+    pub resource: EvaluationResult,
+    /// The combined result; its statement, if any, is in the policies identified by
+    /// `decided_by`.
+    pub result: EvaluationResult,
+    /// The set of policies that decided the combined result.
+    pub decided_by: PolicyKind,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Evaluate `request` against the `identity_policies` of its principal and the
+/// `resource_policies` of its resource, combining the results by the rule for the accounts of
+/// the principal and resource; see the module documentation.
+///
+pub fn evaluate_joint(
+    request: &Request,
+    identity_policies: &[&Policy],
+    resource_policies: &[&Policy],
+) -> Result<JointEvaluation, EvaluationError> {
+    let principal_account = request.principal.as_ref().and_then(|p| p.account());
+    let scope = match (principal_account, request.resource_account()) {
+        (Some(principal), Some(resource)) if principal == resource => AccountScope::SameAccount,
+        _ => AccountScope::CrossAccount,
+    };
+    let identity = evaluate_all(request, identity_policies)?;
+    let resource = evaluate_all(request, resource_policies)?;
+
+    let (result, decided_by) = if is_explicit_deny(&identity) {
+        (identity.clone(), PolicyKind::Identity)
+    } else if is_explicit_deny(&resource) {
+        (resource.clone(), PolicyKind::Resource)
+    } else {
+        let identity_allows = matches!(identity, EvaluationResult::Allow(_));
+        let resource_allows = matches!(resource, EvaluationResult::Allow(_));
+        match (scope, identity_allows, resource_allows) {
+            (AccountScope::SameAccount, true, _) => (identity.clone(), PolicyKind::Identity),
+            (AccountScope::SameAccount, false, true) => (resource.clone(), PolicyKind::Resource),
+            (AccountScope::CrossAccount, true, false) => (resource.clone(), PolicyKind::Resource),
+            _ => (identity.clone(), PolicyKind::Identity),
+        }
+    };
+    Ok(JointEvaluation {
+        scope,
+        identity,
+        resource,
+        result,
+        decided_by,
+    })
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl JointEvaluation {
+    /// Returns `true` if the combined result allows the request.
+    pub fn is_allowed(&self) -> bool {
+        matches!(self.result, EvaluationResult::Allow(_))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn is_explicit_deny(result: &EvaluationResult) -> bool {
+    matches!(
+        result,
+        EvaluationResult::Deny(Source::DeniedByAction, _, _)
+            | EvaluationResult::Deny(Source::DeniedByNotAction, _, _)
+            | EvaluationResult::Deny(Source::DeniedByResource, _, _)
+            | EvaluationResult::Deny(Source::DeniedByNotResource, _, _)
+    )
+}
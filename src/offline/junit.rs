@@ -0,0 +1,355 @@
+/*!
+Provides a JUnit XML report for a batch of requests evaluated against a policy, so that CI
+systems can display the results of policy tests.
+
+Each [`TestCase`](struct.TestCase.html) is a request and the decision it is expected to
+receive, optionally with the source of an expected denial. [`run_tests`](fn.run_tests.html)
+evaluates each case and returns a [`TestReport`](struct.TestReport.html), which is written with
+[`write_junit`](struct.TestReport.html#method.write_junit) as a single `testsuite`. A case is a
+`failure` if the request is denied when it is expected to be allowed, allowed when it is
+expected to be denied, or denied by a different source than expected; and an `error` if it
+could not be evaluated. The report also includes the
+[statement coverage](../struct.CoverageReport.html) of the policies by the test cases, written
+as properties of the test suite. Test cases are usually read from a
+[`PolicyTestSpec`](../struct.PolicyTestSpec.html).
+
+# Example
+
+```rust,ignore
+use aws_iam::io;
+use aws_iam::offline::junit::{run_tests, Expected, TestCase};
+use std::fs::File;
+use std::path::PathBuf;
+
+let policy = io::read_from_file(&PathBuf::from("tests/data/good/quantified-001.json")).unwrap();
+let cases: Vec<TestCase> =
+    serde_json::from_reader(File::open("tests/data/requests.json").unwrap()).unwrap();
+
+let report = run_tests("quantified-001", &[&policy], &cases);
+report
+    .write_junit(File::create("target/policy-tests.xml").unwrap())
+    .unwrap();
+assert!(report.is_success());
+```
+*/
+
+use crate::model::Policy;
+use crate::offline::{
+    evaluate_all, statement_coverage, CoverageReport, EvaluationError, EvaluationResult, Request,
+    Source,
+};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The decision a test case expects.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Expected {
+    /// The request is expected to be allowed.
+    Allow,
+    /// The request is expected to be denied, explicitly or implicitly.
+    Deny,
+}
+
+///
+/// A request, and the decision it is expected to receive.
+///
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TestCase {
+    /// The name of the test case; if not present the request's `request_id` is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The request to evaluate.
+    pub request: Request,
+    /// The expected decision.
+    #[serde(rename = "expect")]
+    pub expected: Expected,
+    /// The expected source of a denial, the name of a `Source` variant such as `Action` or
+    /// `DeniedByResource`; if not present any source is accepted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+///
+/// The result of a single test case.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestResult {
+    /// The name of the test case.
+    pub name: String,
+    /// The expected decision.
+    pub expected: Expected,
+    /// The expected source of a denial, if any.
+    pub expected_source: Option<String>,
+    /// The result of evaluating the request.
+    pub result: Result<EvaluationResult, EvaluationError>,
+    /// The time taken to evaluate the request.
+    pub time: Duration,
+}
+
+///
+/// The results of a batch of test cases.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestReport {
+    name: String,
+    results: Vec<TestResult>,
+    coverage: CoverageReport,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Evaluate each of `cases` against `policies`, returning a report named `name` which
+/// includes the statement coverage of `policies` by the cases.
+///
+pub fn run_tests(name: &str, policies: &[&Policy], cases: &[TestCase]) -> TestReport {
+    let results = cases
+        .iter()
+        .enumerate()
+        .map(|(index, case)| {
+            let start = Instant::now();
+            let result = evaluate_all(&case.request, policies);
+            TestResult {
+                name: case_name(index, case),
+                expected: case.expected,
+                expected_source: case.source.clone(),
+                result,
+                time: start.elapsed(),
+            }
+        })
+        .collect();
+    let requests: Vec<&Request> = cases.iter().map(|case| &case.request).collect();
+    TestReport {
+        name: name.to_string(),
+        results,
+        coverage: statement_coverage(&requests, policies),
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl TestResult {
+    /// Returns `true` if the request was evaluated and received the expected decision.
+    pub fn is_success(&self) -> bool {
+        match (&self.result, self.expected) {
+            (Ok(EvaluationResult::Allow(_)), Expected::Allow) => true,
+            (Ok(EvaluationResult::Deny(source, _, _)), Expected::Deny) => {
+                match &self.expected_source {
+                    Some(expected) => expected == source_name(source),
+                    None => true,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the request was evaluated but did not receive the expected decision.
+    pub fn is_failure(&self) -> bool {
+        self.result.is_ok() && !self.is_success()
+    }
+
+    /// Returns `true` if the request could not be evaluated.
+    pub fn is_error(&self) -> bool {
+        self.result.is_err()
+    }
+}
+
+impl TestReport {
+    /// Returns the name of the report, used as the name of the test suite.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the result of each test case, in the order the cases were given.
+    pub fn results(&self) -> &[TestResult] {
+        &self.results
+    }
+
+    /// Returns the statement coverage of the policies by the test cases.
+    pub fn coverage(&self) -> &CoverageReport {
+        &self.coverage
+    }
+
+    /// Returns the number of test cases that did not receive the expected decision.
+    pub fn failures(&self) -> usize {
+        self.results.iter().filter(|r| r.is_failure()).count()
+    }
+
+    /// Returns the number of test cases that could not be evaluated.
+    pub fn errors(&self) -> usize {
+        self.results.iter().filter(|r| r.is_error()).count()
+    }
+
+    /// Returns `true` if every test case received the expected decision.
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(|r| r.is_success())
+    }
+
+    ///
+    /// Write this report as a JUnit XML document, with a single `testsuite`, to any
+    /// implementation of `std::io::Write`.
+    ///
+    pub fn write_junit<W>(&self, writer: W) -> std::io::Result<()>
+    where
+        W: Write,
+    {
+        let mut writer = writer;
+        let time: Duration = self.results.iter().map(|r| r.time).sum();
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            writer,
+            r#"<testsuite name="{}" tests="{}" failures="{}" errors="{}" time="{:.6}">"#,
+            xml_escape(&self.name),
+            self.results.len(),
+            self.failures(),
+            self.errors(),
+            time.as_secs_f64()
+        )?;
+        writeln!(writer, "  <properties>")?;
+        writeln!(
+            writer,
+            r#"    <property name="statements" value="{}"/>"#,
+            self.coverage.statements().len()
+        )?;
+        writeln!(
+            writer,
+            r#"    <property name="statements.covered" value="{}"/>"#,
+            self.coverage.covered()
+        )?;
+        for statement in self.coverage.uncovered() {
+            writeln!(
+                writer,
+                r#"    <property name="statement.uncovered" value="{}"/>"#,
+                xml_escape(&statement.to_string())
+            )?;
+        }
+        writeln!(writer, "  </properties>")?;
+        for result in &self.results {
+            write!(
+                writer,
+                r#"  <testcase name="{}" classname="{}" time="{:.6}""#,
+                xml_escape(&result.name),
+                xml_escape(&self.name),
+                result.time.as_secs_f64()
+            )?;
+            match &result.result {
+                _ if result.is_success() => writeln!(writer, "/>")?,
+                Ok(actual) => {
+                    let message = match &result.expected_source {
+                        Some(source) => format!(
+                            "expected {} by {}, {}",
+                            expected_name(result.expected),
+                            source,
+                            result_message(actual)
+                        ),
+                        None => format!(
+                            "expected {}, {}",
+                            expected_name(result.expected),
+                            result_message(actual)
+                        ),
+                    };
+                    writeln!(writer, ">")?;
+                    writeln!(
+                        writer,
+                        r#"    <failure message="{}" type="{}"/>"#,
+                        xml_escape(&message),
+                        FAILURE_TYPE
+                    )?;
+                    writeln!(writer, "  </testcase>")?;
+                }
+                Err(e) => {
+                    writeln!(writer, ">")?;
+                    writeln!(
+                        writer,
+                        r#"    <error message="{}" type="{}"/>"#,
+                        xml_escape(&e.to_string()),
+                        ERROR_TYPE
+                    )?;
+                    writeln!(writer, "  </testcase>")?;
+                }
+            }
+        }
+        writeln!(writer, "</testsuite>")
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const FAILURE_TYPE: &str = "UnexpectedDecision";
+
+const ERROR_TYPE: &str = "EvaluationError";
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn case_name(index: usize, case: &TestCase) -> String {
+    let request: &Request = &case.request;
+    match (&case.name, &request.request_id) {
+        (Some(name), _) => name.clone(),
+        (None, Some(request_id)) => request_id.clone(),
+        (None, None) => format!("[{}] {} {}", index, request.action, request.resource),
+    }
+}
+
+fn expected_name(expected: Expected) -> &'static str {
+    match expected {
+        Expected::Allow => "allow",
+        Expected::Deny => "deny",
+    }
+}
+
+fn result_message(result: &EvaluationResult) -> String {
+    match result {
+        EvaluationResult::Allow(_) => "allowed".to_string(),
+        EvaluationResult::Deny(source, message, _) => {
+            format!("denied by {} ({})", source_name(source), message)
+        }
+    }
+}
+
+fn source_name(source: &Source) -> &'static str {
+    match source {
+        Source::Default => "Default",
+        Source::Principal => "Principal",
+        Source::NotPrincipal => "NotPrincipal",
+        Source::Action => "Action",
+        Source::NotAction => "NotAction",
+        Source::Resource => "Resource",
+        Source::NotResource => "NotResource",
+        Source::Condition(_, _) => "Condition",
+        Source::DeniedByAction => "DeniedByAction",
+        Source::DeniedByNotAction => "DeniedByNotAction",
+        Source::DeniedByResource => "DeniedByResource",
+        Source::DeniedByNotResource => "DeniedByNotResource",
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -6,46 +6,198 @@ This module provides a simple method for testing a policy by creating one or mor
 [`Request`](request/struct.Request.html) objects and evaluating the policy for the given request.
 This implementation is not exhaustive but for those elements it implements it should be a
 reasonable approximation. Note that the value returned from [`evaluate`](fn.evaluate.html) also
-contains information regarding the reason for any decision, useful for debugging.
+contains information regarding the reason for any decision, useful for debugging, and a
+[`StatementRef`](struct.StatementRef.html) identifying the policy and statement that decided
+it, so that decisions can be linked back to the source documents.
 
 # Example
 
 ```rust
-use aws_iam::{constants, io, model::*, offline::*};
-use std::path::PathBuf;use std::str::FromStr;
+use aws_iam::{context::keys, io, model::*, offline::*};
+use std::path::PathBuf;
+use std::str::FromStr;
 
 let policy = io::read_from_file(
-        &PathBuf::from("tests/data/good/example-021.json")
+        &PathBuf::from("tests/data/good/quantified-001.json")
     ).expect("Error reading file");
 
-let environment: Environment = [
+let environment: Environment = vec![
         (
-            QString::from_str(constants::AWS_EPOCH_TIME).unwrap(),
-            ConditionValue::Integer(1000),
+            QualifiedName::from_str(keys::AWS_EPOCH_TIME).unwrap(),
+            ConditionValue::number(1000),
         ),
         (
-            QString::from_str(constants::AWS_REQUESTED_REGION).unwrap(),
-            ConditionValue::String("us-east-1".to_string()),
+            QualifiedName::from_str(keys::AWS_REQUESTED_REGION).unwrap(),
+            ConditionValue::from("us-east-1"),
         ),
         (
-            QString::from_str(constants::AWS_SECURE_TRANSPORT).unwrap(),
-            ConditionValue::Bool(true),
+            QualifiedName::from_str(keys::AWS_SECURE_TRANSPORT).unwrap(),
+            ConditionValue::boolean(true),
         ),
     ]
-    .iter()
-    .cloned()
+    .into_iter()
     .collect();
 let request = Request {
     request_id: Request::request_id(),
     principal: None,
-    action: QString::from_str("").expect("bad QString"),
+    action: QualifiedName::from_str("dynamodb:GetItem").expect("bad action name"),
     resource: "".to_string(),
+    source_arn: None,
+    source_account: None,
+    called_via: Default::default(),
     environment,
 };
 
 println!("result: {:?}", evaluate(&request, &policy).expect("An error occurred"));
 ```
 
+# Statement Effects
+
+A statement applies to a request only if each of its elements selects the request. An `Action`
+or `Resource` element selects the request if it lists the request's action or resource, and a
+`NotAction` or `NotResource` element selects the request if it does *not* list, that is it does
+not exclude, the request's action or resource. Then:
+
+* an `Allow` statement that applies allows the request; one that does not apply reports why,
+  for example as `Source::NotAction` where the action is excluded, but this is not an
+  explicit deny; and
+* a `Deny` statement that applies explicitly denies the request, reported as one of the
+  `Source::DeniedBy*` values, for example `Source::DeniedByNotAction` where the action is not
+  excluded; one that does not apply has no effect on the result.
+
+# Service-to-Service Requests
+
+When a service such as S3 or SNS acts on behalf of a resource, for example when S3 publishes a
+bucket notification to an SNS topic, the `source_arn` and `source_account` fields of the request
+describe that resource. They are provided to conditions as the `aws:SourceArn` and
+`aws:SourceAccount` keys, so resource policies that guard against the confused deputy problem
+can be tested; see [`Request::context`](request/struct.Request.html#method.context).
+
+When services call other services on behalf of the principal, for example CloudFormation calling
+DynamoDB, the `called_via` field lists those services in order. The list is the multi-valued
+`aws:CalledVia` key, evaluated as a set with `ForAnyValue` and `ForAllValues`, and its first and
+last entries are the `aws:CalledViaFirst` and `aws:CalledViaLast` keys; `aws:ViaAWSService` is
+`true` whenever it is not empty.
+
+# Principal Keys
+
+The condition keys that describe the principal making the request, `aws:PrincipalType`,
+`aws:userid`, `aws:username`, `aws:PrincipalAccount`, `aws:PrincipalArn`,
+`aws:PrincipalServiceName`, and `aws:FederatedProvider`, are derived from the request's
+`principal` in the same way AWS populates them, so they need not be added to the environment;
+see [`Principal::context`](request/struct.Principal.html#method.context) for the rules. A
+principal may be constructed from the result of the STS `GetCallerIdentity` action with
+[`Principal::from_caller_identity`](request/struct.Principal.html#method.from_caller_identity).
+
+# Context Providers
+
+Rather than adding values such as `aws:CurrentTime` to the environment of every request, an
+[`Evaluator`](struct.Evaluator.html) may be given [`ContextProvider`](trait.ContextProvider.html)s
+that compute the value of a condition key when a policy uses it and the request does not have
+it; [`ClockProvider`](struct.ClockProvider.html) provides the current time, and any function of
+the key and request may provide others, such as resource tags. The time is read from a
+[`Clock`](clock/trait.Clock.html), which tests may fix with a
+[`FixedClock`](clock/struct.FixedClock.html) to check `Date` conditions deterministically.
+
+# Repeated Evaluation
+
+Each call to `evaluate` prepares the action and resource patterns of the policy's statements
+before comparing them with the request. To evaluate the same policy against many requests,
+for example as an in-process authorizer, [`compile`](fn.compile.html) it once and use
+[`evaluate_compiled`](fn.evaluate_compiled.html) instead. Note that a compiled policy compares
+actions ignoring case, as IAM does; see [`CompiledPolicy`](struct.CompiledPolicy.html) for
+the details.
+
+An application that evaluates the same requests repeatedly may use a
+[`CachingEvaluator`](struct.CachingEvaluator.html), which retains the most recent decisions
+for a set of compiled policies and discards them when the policies are replaced.
+
+# Parallel Evaluation
+
+To audit many requests against a set of policies, such as those exported from an
+organization, [`evaluate_matrix`](fn.evaluate_matrix.html) evaluates every request against
+every policy. With the feature `parallel`,
+[`evaluate_matrix_par`](fn.evaluate_matrix_par.html) does the same using all available cores,
+and returns its results in the same order.
+
+# Test Reports
+
+The [`junit`](junit/index.html) module evaluates a batch of requests, each with the decision
+it is expected to receive, and writes the results as a JUnit XML report for CI systems. Test
+cases are usually kept in a [`PolicyTestSpec`](struct.PolicyTestSpec.html) file, with the
+policies they apply to, and run with [`run_spec`](fn.run_spec.html).
+
+# Explaining Decisions
+
+To find out why a request was allowed or denied, [`explain`](fn.explain.html) reports, for
+every statement of a policy, whether each element and condition selects the request and the
+statement's contribution to the decision: `allow`, `explicit deny`, or `no effect`. Unlike
+`evaluate` it does not stop at the first explicit deny or the first element that does not
+select the request.
+
+# Identity and Resource Policies
+
+[`evaluate_joint`](fn.evaluate_joint.html) evaluates a request against both the identity
+policies of its principal and the resource policies of its resource. Within an account either
+may allow the request, across accounts both must; the rule is chosen from the accounts of the
+principal and resource, and the result records which set of policies decided it.
+
+# Account Queries
+
+Given the users, groups, roles, and managed policies of an account, as read by
+[`io::read_authorization_details`](../io/fn.read_authorization_details.html),
+[`principals_allowed`](fn.principals_allowed.html) returns every user and role whose identity
+policies, limited by any permissions boundary, allow a request; answering questions such as
+which principals can perform `kms:Decrypt` on a given key.
+
+# Role Sessions
+
+A [`RoleChain`](struct.RoleChain.html) evaluates a request made by a role session, obtained by
+an identity assuming one or more roles in turn; each `sts:AssumeRole` request is checked
+against the role's trust policy and the caller's permissions, and the request against the
+permission policies of the final role limited by its session policy.
+
+# Counterexamples
+
+When a request is denied, [`counterexamples`](fn.counterexamples.html) proposes the changes to
+the request context, such as `set aws:MultiFactorAuthPresent=true` or
+`set aws:RequestedRegion=us-east-1`, that would allow it; each is checked by evaluating the
+changed request. Only the failing conditions of `Allow` statements are considered, so no
+change to the principal, action, or resource is proposed.
+
+# Statement Coverage
+
+[`statement_coverage`](fn.statement_coverage.html) reports which statements of a set of
+policies were matched by a suite of requests; a statement never matched is dead or untested.
+The report of a test run includes the coverage of the policies by its test cases.
+
+# Reconciliation
+
+With the feature `aws_online` the [`simulator`](simulator/index.html) module compares the
+results of the offline evaluator with those of the IAM policy simulator.
+
+# Tracing
+
+The evaluator records its progress using [tracing](https://docs.rs/tracing), with the target
+[`TRACE_TARGET`](constant.TRACE_TARGET.html), and it does not write to the standard output.
+Collectors may rely on the following names and fields:
+
+* an `evaluate` span, at level `INFO`, for each request with the fields `request_id`,
+  `action`, and `resource`;
+* a `policy` span, at level `DEBUG`, for each policy with the field `policy`, the policy's
+  `Id` or, if it has none, its index in brackets;
+* a `statement` span, at level `DEBUG`, for each statement with the fields `statement`, its
+  index in the policy, `sid`, and `effect`;
+* an event, at level `TRACE`, for each element of a statement checked with the fields
+  `element`, one of `principal`, `action`, `resource`, or `condition`, and `result`, one of
+  `allow`, `deny`, or `none`; condition events also have the fields `operator` and `key`;
+* an event, at level `WARN`, for each condition value that could not be compared with the
+  fields `operator` and `error`;
+* an event, at level `INFO`, for each statement not matched by any request when computing
+  statement coverage with the fields `policy`, `statement`, and `sid`; and
+* an event, at level `INFO`, for each decision with the field `result`, one of `allow` or
+  `deny`, and for a denial `source`, the component of the statement that denied the request.
+
 # Request Serialization
 
 The `Request` structure supports Serde serialization and deserialization to supporting testing with
@@ -66,33 +218,45 @@ in the example above.
 ```
 */
 
-use crate::model::{ConditionOperator, Effect, Policy, QString};
+use crate::model::{Effect, Operator, Policy, QualifiedName};
 use crate::offline::policy::evaluate_policy;
-use std::fmt::{Display, Error, Formatter};
-use tracing::instrument;
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
+use tracing::{info, info_span};
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
 // ------------------------------------------------------------------------------------------------
 
 ///
-/// Errors which may occur during evaluation.
+/// Errors which may occur during evaluation; these may be converted into an `IamError`.
 ///
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Error)]
+#[non_exhaustive]
 pub enum EvaluationError {
     /// The condition operator is unknown to this implementation.
+    #[error("The condition operator `{0}` is not supported")]
     UnknownOperator(String),
     /// The variable name is not a key in the context environment.
+    #[error("The variable `{0}` is not a key in the request context")]
     UnknownVariableName(String),
     /// The variable name is not a valid environment key.
+    #[error("The variable name `{0}` is not a valid condition key")]
     InvalidVariableName(String),
     /// The value in the environment for the variable does not match the operator type.
+    #[error("The value of `{0}` in the request context is not of the type the operator expects")]
     ExpectingVariableType(String),
     /// The variable does not have an associated value.
+    #[error("The variable `{0}` has no value")]
     MissingVariableValue(String),
     /// A condition expected more, or less, values than provided.
+    #[error("A condition was given more, or fewer, values than its operator expects")]
     InvalidValueCardinality,
+    /// The ARN of a role assumed in a role chain is not valid.
+    #[error("The role ARN `{0}` is not valid")]
+    InvalidRoleArn(String),
     /// A collection of errors reported by an underlying function.
+    #[error("{} errors occurred during evaluation", .0.len())]
     Errors(Vec<EvaluationError>),
 }
 
@@ -107,34 +271,68 @@ pub enum Source {
     Principal,
     /// The *is-not-a* principal test failed.
     NotPrincipal,
-    /// The *is-a* action test failed.
+    /// The *is-a* action test of an `Allow` statement failed, the action is not listed.
     Action,
-    /// The *is-not-a* action test failed.
+    /// The *is-not-a* action test of an `Allow` statement failed, the action is excluded.
     NotAction,
-    /// The *is-a* resource test failed.
+    /// The *is-a* resource test of an `Allow` statement failed, the resource is not listed.
     Resource,
-    /// The *is-not-a* resource test failed.
+    /// The *is-not-a* resource test of an `Allow` statement failed, the resource is excluded.
     NotResource,
     /// The *match* a condition failed; to help narrow down the actual failure the condition
     /// operator and key are included.
-    Condition(ConditionOperator, QString),
+    Condition(Operator, QualifiedName),
+    /// A `Deny` statement explicitly denied the request, as its action is listed.
+    DeniedByAction,
+    /// A `Deny` statement explicitly denied the request, as its action is not excluded.
+    DeniedByNotAction,
+    /// A `Deny` statement explicitly denied the request, as its resource is listed.
+    DeniedByResource,
+    /// A `Deny` statement explicitly denied the request, as its resource is not excluded.
+    DeniedByNotResource,
+}
+
+///
+/// Identifies a statement, and the policy containing it, that decided a request.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StatementRef {
+    /// The index of the policy in the policies evaluated.
+    pub policy: usize,
+    /// The `Id` of the policy, if any.
+    pub policy_id: Option<String>,
+    /// The index of the statement in the policy.
+    pub statement: usize,
+    /// The `Sid` of the statement, if any.
+    pub sid: Option<String>,
 }
 
 ///
 /// The result of an evaluation, this casts directly into a `model::Effect` but in
-/// the case of `Deny` will return the source of the failure and any message.
+/// the case of `Deny` will return the source of the failure and any message. Both include
+/// the statement that decided the request, except for the default denial where no statement
+/// applies.
 ///
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum EvaluationResult {
-    /// Evaluation resulted in an *allow* effect.
-    Allow,
+    /// Evaluation resulted in an *allow* effect, by the statement identified.
+    Allow(Option<StatementRef>),
     /// Evaluation resulted in an *deny* effect. In this case the source represents a statement
     /// component that caused the denial and the string represents an accompanying message.
-    Deny(Source, String),
+    Deny(Source, String, Option<StatementRef>),
 }
 
 type PartialEvaluationResult = Option<EvaluationResult>;
 
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The target of all spans and events recorded by the evaluator, see the module documentation.
+///
+pub const TRACE_TARGET: &str = "aws_iam::offline";
+
 // ------------------------------------------------------------------------------------------------
 // Public Functions
 // ------------------------------------------------------------------------------------------------
@@ -149,51 +347,172 @@ pub fn evaluate(request: &Request, policy: &Policy) -> Result<EvaluationResult,
 ///
 /// Evaluated a set of policies against the request context.
 ///
-#[instrument]
 pub fn evaluate_all(
     request: &Request,
     policies: &[&Policy],
 ) -> Result<EvaluationResult, EvaluationError> {
-    let results: Result<Vec<PartialEvaluationResult>, EvaluationError> = policies
+    evaluate_all_compiled(request, &compile_all(policies))
+}
+
+///
+/// Compile `policy` for repeated evaluation, see [`CompiledPolicy`](struct.CompiledPolicy.html).
+///
+pub fn compile(policy: &Policy) -> CompiledPolicy<'_> {
+    CompiledPolicy::new(policy)
+}
+
+///
+/// Evaluated a compiled policy against the request context; use this in preference to
+/// `evaluate` when evaluating the same policy against many requests.
+///
+pub fn evaluate_compiled(
+    request: &Request,
+    policy: &CompiledPolicy<'_>,
+) -> Result<EvaluationResult, EvaluationError> {
+    evaluate_all_compiled(request, std::slice::from_ref(policy))
+}
+
+///
+/// Evaluated a set of compiled policies against the request context.
+///
+pub fn evaluate_all_compiled(
+    request: &Request,
+    policies: &[CompiledPolicy<'_>],
+) -> Result<EvaluationResult, EvaluationError> {
+    evaluate_with_context(request, &request.context(), policies)
+}
+
+///
+/// Evaluate each of `requests` against each of `policies`, returning one row per request with
+/// one result per policy; so that `result[r][p]` is the result of evaluating `requests[r]`
+/// against `policies[p]`. Each policy is compiled once for all requests.
+///
+pub fn evaluate_matrix(
+    requests: &[Request],
+    policies: &[&Policy],
+) -> Vec<Vec<Result<EvaluationResult, EvaluationError>>> {
+    let policies = compile_all(policies);
+    requests
         .iter()
-        .enumerate()
-        .map(|(idx, policy)| evaluate_policy(request, policy, idx as i32))
-        .collect();
-    match results {
-        Ok(mut results) => Ok(reduce_results(&mut results)),
-        Err(err) => Err(err),
-    }
+        .map(|request| evaluate_row(request, &policies))
+        .collect()
+}
+
+///
+/// Evaluate each of `requests` against each of `policies`, as `evaluate_matrix`, with the
+/// requests divided between threads using [rayon](https://docs.rs/rayon). The result is in
+/// the same order, and has the same values, as that of `evaluate_matrix`. This function
+/// requires the `parallel` feature.
+///
+#[cfg(feature = "parallel")]
+pub fn evaluate_matrix_par(
+    requests: &[Request],
+    policies: &[&Policy],
+) -> Vec<Vec<Result<EvaluationResult, EvaluationError>>> {
+    use rayon::prelude::*;
+
+    let policies = compile_all(policies);
+    requests
+        .par_iter()
+        .map(|request| evaluate_row(request, &policies))
+        .collect()
 }
 
 // ------------------------------------------------------------------------------------------------
 // Implementations
 // ------------------------------------------------------------------------------------------------
 
+impl Display for StatementRef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.policy_id {
+            Some(id) => write!(f, "{}", id)?,
+            None => write!(f, "[{}]", self.policy)?,
+        }
+        write!(f, " Statement[{}]", self.statement)?;
+        if let Some(sid) = &self.sid {
+            write!(f, " ({})", sid)?;
+        }
+        Ok(())
+    }
+}
+
 impl Display for EvaluationResult {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Allow => write!(f, "Request allowed"),
-            Self::Deny(source, message) => match source {
+            Self::Allow(_) => write!(f, "Request allowed")?,
+            Self::Deny(source, message, _) => match source {
                 Source::Condition(op, key) => write!(
                     f,
-                    "Request denied, statement condition operator {:?} for key {:?}, message: {}",
+                    "Request denied, statement condition operator {} for key {}, message: {}",
                     op, key, message
-                ),
+                )?,
                 _ => write!(
                     f,
                     "Request denied, statement source {:?}, message: {}",
                     source, message
-                ),
+                )?,
             },
         }
+        match self.statement() {
+            Some(statement) => write!(f, ", by {}", statement),
+            None => Ok(()),
+        }
     }
 }
 
-impl Into<Effect> for EvaluationResult {
-    fn into(self) -> Effect {
+impl EvaluationResult {
+    /// Returns `true` if the request was allowed.
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Self::Allow(_))
+    }
+
+    /// Returns the statement that decided the request, if any.
+    pub fn statement(&self) -> Option<&StatementRef> {
         match self {
-            Self::Allow => Effect::Allow,
-            Self::Deny(_, _) => Effect::Deny,
+            Self::Allow(statement) => statement.as_ref(),
+            Self::Deny(_, _, statement) => statement.as_ref(),
+        }
+    }
+
+    ///
+    /// Record the statement at `index`, with `sid`, as the one that decided the result; the
+    /// policy is recorded by `in_policy`.
+    ///
+    pub(crate) fn in_statement(self, index: usize, sid: Option<String>) -> Self {
+        let statement = Some(StatementRef {
+            policy: 0,
+            policy_id: None,
+            statement: index,
+            sid,
+        });
+        match self {
+            Self::Allow(_) => Self::Allow(statement),
+            Self::Deny(source, message, _) => Self::Deny(source, message, statement),
+        }
+    }
+
+    ///
+    /// Record the policy at `index`, with `id`, as the one containing the statement that
+    /// decided the result.
+    ///
+    pub(crate) fn in_policy(mut self, index: usize, id: Option<String>) -> Self {
+        let statement = match &mut self {
+            Self::Allow(statement) => statement,
+            Self::Deny(_, _, statement) => statement,
+        };
+        if let Some(statement) = statement {
+            statement.policy = index;
+            statement.policy_id = id;
+        }
+        self
+    }
+}
+
+impl From<EvaluationResult> for Effect {
+    fn from(result: EvaluationResult) -> Self {
+        match result {
+            EvaluationResult::Allow(_) => Effect::Allow,
+            EvaluationResult::Deny(_, _, _) => Effect::Deny,
         }
     }
 }
@@ -202,58 +521,222 @@ impl Into<Effect> for EvaluationResult {
 // Private Functions
 // ------------------------------------------------------------------------------------------------
 
+///
+/// Evaluate `policies` against the request with the context `context`, which the caller has
+/// built from the request once for all the policies.
+///
+fn evaluate_with_context(
+    request: &Request,
+    context: &Environment,
+    policies: &[CompiledPolicy<'_>],
+) -> Result<EvaluationResult, EvaluationError> {
+    // the action is the same for every statement, so build it once per request.
+    let request_action = request.action.to_string();
+    let span = info_span!(
+        target: TRACE_TARGET,
+        "evaluate",
+        request_id = request.request_id.as_deref().unwrap_or_default(),
+        action = request_action.as_str(),
+        resource = request.resource.as_str(),
+    );
+    let _enter = span.enter();
+    evaluate_in_environment(request, &request_action, context, policies)
+}
+
+fn compile_all<'a>(policies: &[&'a Policy]) -> Vec<CompiledPolicy<'a>> {
+    policies
+        .iter()
+        .map(|policy| CompiledPolicy::new(policy))
+        .collect()
+}
+
+///
+/// Evaluate `policies` against the request with the environment `environment`, rather than
+/// the request's own context.
+///
+fn evaluate_in_environment(
+    request: &Request,
+    request_action: &str,
+    environment: &Environment,
+    policies: &[CompiledPolicy<'_>],
+) -> Result<EvaluationResult, EvaluationError> {
+    let results: Result<Vec<PartialEvaluationResult>, EvaluationError> = policies
+        .iter()
+        .enumerate()
+        .map(|(idx, policy)| {
+            evaluate_policy(request, request_action, environment, policy, idx as i32)
+        })
+        .collect();
+    let result = results.map(|mut results| reduce_results(&mut results))?;
+    match &result {
+        EvaluationResult::Allow(_) => info!(target: TRACE_TARGET, result = "allow"),
+        EvaluationResult::Deny(source, _, _) => {
+            info!(target: TRACE_TARGET, result = "deny", source = ?source)
+        }
+    }
+    Ok(result)
+}
+
+fn evaluate_row(
+    request: &Request,
+    policies: &[CompiledPolicy<'_>],
+) -> Vec<Result<EvaluationResult, EvaluationError>> {
+    policies
+        .iter()
+        .enumerate()
+        .map(|(index, policy)| {
+            evaluate_compiled(request, policy)
+                .map(|result| result.in_policy(index, policy.policy().id.clone()))
+        })
+        .collect()
+}
+
+///
+/// The value of the `result` field of element events, see the module documentation.
+///
+pub(crate) fn result_name(result: &PartialEvaluationResult) -> &'static str {
+    match result {
+        None => "none",
+        Some(EvaluationResult::Allow(_)) => "allow",
+        Some(EvaluationResult::Deny(_, _, _)) => "deny",
+    }
+}
+
 fn reduce_results(results: &mut Vec<PartialEvaluationResult>) -> EvaluationResult {
     match reduce_optional_results(results) {
-        None => EvaluationResult::Deny(Source::Default, "no explicit effect set".to_string()),
+        None => EvaluationResult::Deny(Source::Default, "no explicit effect set".to_string(), None),
         Some(result) => result,
     }
 }
 
+///
+/// An explicit deny overrides any allow, and an allow overrides the reason the first
+/// statement that did not apply gave for not allowing the request.
+///
 pub(crate) fn reduce_optional_results(
     results: &mut Vec<PartialEvaluationResult>,
 ) -> PartialEvaluationResult {
-    let effect_or_none: PartialEvaluationResult =
-        results.drain(0..).fold(None, |acc, result| match result {
-            Some(EvaluationResult::Allow) => {
-                if let Some(EvaluationResult::Deny(_, _)) = acc {
-                    acc
-                } else {
-                    Some(EvaluationResult::Allow)
-                }
-            }
-            Some(EvaluationResult::Deny(s, m)) => Some(EvaluationResult::Deny(s, m)),
-            _ => acc,
-        });
-    effect_or_none
+    results.drain(0..).fold(None, |acc, result| {
+        if overrides_result(&acc, &result) {
+            result
+        } else {
+            acc
+        }
+    })
+}
+
+///
+/// Returns `true` if `result` replaces `acc` as the reduced result, see
+/// `reduce_optional_results`.
+///
+pub(crate) fn overrides_result(
+    acc: &PartialEvaluationResult,
+    result: &PartialEvaluationResult,
+) -> bool {
+    match result {
+        _ if is_explicit_deny(acc) => false,
+        Some(EvaluationResult::Allow(_)) => true,
+        Some(EvaluationResult::Deny(_, _, _)) => acc.is_none() || is_explicit_deny(result),
+        None => false,
+    }
+}
+
+///
+/// Returns `true` if the result is the explicit deny of a `Deny` statement, rather than an
+/// `Allow` statement that does not apply to the request.
+///
+pub(crate) fn is_explicit_deny(result: &PartialEvaluationResult) -> bool {
+    matches!(
+        result,
+        Some(EvaluationResult::Deny(
+            Source::DeniedByAction
+                | Source::DeniedByNotAction
+                | Source::DeniedByResource
+                | Source::DeniedByNotResource,
+            _,
+            _
+        ))
+    )
 }
 
 // ------------------------------------------------------------------------------------------------
 // Modules
 // ------------------------------------------------------------------------------------------------
 
+mod account;
+pub use account::principals_allowed;
+
+mod cache;
+pub use cache::CachingEvaluator;
+
+pub mod clock;
+
+mod compiled;
+pub use compiled::CompiledPolicy;
+
+mod counterexample;
+pub use counterexample::{counterexamples, ContextChange, Counterexample};
+
+mod coverage;
+pub use coverage::{statement_coverage, CoverageReport, StatementCoverage};
+
 mod policy;
 
 mod statement;
 
+mod explain;
+pub use explain::{
+    explain, explain_compiled, ConditionMatch, Contribution, ElementMatch, StatementMatchReport,
+};
+
+mod joint;
+pub use joint::{evaluate_joint, AccountScope, JointEvaluation, PolicyKind};
+
 mod operators;
 
+mod provider;
+pub use provider::{ClockProvider, ContextProvider, Evaluator};
+
 mod request;
-pub use request::{Environment, Principal, Request};
+pub use request::{CallerIdentity, Environment, Principal, PrincipalType, Request};
 
 mod variables;
 
+pub mod junit;
+
+mod session;
+pub use session::{AssumedRole, RoleChain, SessionEvaluation};
+
+mod spec;
+pub use spec::{run_spec, PolicyTestSpec};
+
+#[cfg(feature = "aws_online")]
+pub mod simulator;
+
 // ------------------------------------------------------------------------------------------------
 // Unit Tests
 // ------------------------------------------------------------------------------------------------
 
 #[cfg(test)]
 mod tests {
-    use crate::constants;
+    use crate::context::keys::{
+        AWS_CALLED_VIA, AWS_CURRENT_TIME, AWS_EPOCH_TIME, AWS_PRINCIPAL_ACCOUNT, AWS_PRINCIPAL_ARN,
+        AWS_PRINCIPAL_SERVICE_NAME, AWS_PRINCIPAL_TYPE, AWS_REQUESTED_REGION, AWS_SECURE_TRANSPORT,
+        AWS_SOURCE_ACCOUNT, AWS_USER_ID, AWS_VIA_AWS_SERVICE,
+    };
+    use crate::error::IamError;
     use crate::io;
-    use crate::model::{ConditionValue, QString};
+    use crate::model::{ConditionValue, Policy, QualifiedName, Statement};
+    use crate::offline::clock::{Clock, FixedClock};
+    use crate::offline::junit::{run_tests, Expected, TestCase};
     use crate::offline::{
-        evaluate, request::Environment, EvaluationResult, Principal, Request, Source,
+        compile, counterexamples, evaluate, evaluate_compiled, evaluate_joint, evaluate_matrix,
+        explain, request::Environment, run_spec, statement_coverage, AccountScope, AssumedRole,
+        CachingEvaluator, ClockProvider, Contribution, ElementMatch, EvaluationError,
+        EvaluationResult, Evaluator, PolicyKind, Principal, PrincipalType, Request, RoleChain,
+        Source, StatementRef, TRACE_TARGET,
     };
+    use std::path::Path;
     use std::str::FromStr;
 
     fn make_request(
@@ -262,28 +745,30 @@ mod tests {
         action: &str,
         resource: &str,
     ) -> Request {
-        let environment: Environment = [
+        let environment: Environment = vec![
             (
-                QString::from_str(constants::AWS_EPOCH_TIME).unwrap(),
-                ConditionValue::Integer(1000),
+                QualifiedName::from_str(AWS_EPOCH_TIME).unwrap(),
+                ConditionValue::number(1000),
             ),
             (
-                QString::from_str(constants::AWS_REQUESTED_REGION).unwrap(),
-                ConditionValue::String("us-east-1".to_string()),
+                QualifiedName::from_str(AWS_REQUESTED_REGION).unwrap(),
+                ConditionValue::from("us-east-1"),
             ),
             (
-                QString::from_str(constants::AWS_SECURE_TRANSPORT).unwrap(),
-                ConditionValue::Bool(true),
+                QualifiedName::from_str(AWS_SECURE_TRANSPORT).unwrap(),
+                ConditionValue::boolean(true),
             ),
         ]
-        .iter()
-        .cloned()
+        .into_iter()
         .collect();
         Request {
             request_id: Some(String::from(test_case)),
             principal,
-            action: QString::from_str(action).unwrap(),
+            action: QualifiedName::from_str(action).unwrap(),
             resource: String::from(resource),
+            source_arn: None,
+            source_account: None,
+            called_via: Default::default(),
             environment,
         }
     }
@@ -296,18 +781,21 @@ mod tests {
             "dynamodb:read",
             "arn:aws:dynamodb:us-east-2:123456789012:table/NotBooks",
         );
-        println!("{}", serde_json::to_string_pretty(&request).unwrap());
+        let json = serde_json::to_string_pretty(&request).unwrap();
+        let parsed: Request = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.request_id, request.request_id);
+        assert_eq!(parsed.resource, request.resource);
     }
 
     #[test]
     fn test_deny_resource_string_match() {
         let policy = r#"{
   "Version": "2012-10-17",
-  "Statement": {
+  "Statement": [{
     "Effect": "Allow",
     "Action": "dynamodb:*",
     "Resource": "arn:aws:dynamodb:us-east-2:123456789012:table/Books"
-  }
+  }]
 }"#;
         let policy = io::read_from_string(policy).expect("error parsing policy");
         let request = make_request(
@@ -321,7 +809,13 @@ mod tests {
             result,
             Ok(EvaluationResult::Deny(
                 Source::Resource,
-                String::from("string_match")
+                String::from("string_match"),
+                Some(StatementRef {
+                    policy: 0,
+                    policy_id: None,
+                    statement: 0,
+                    sid: None,
+                })
             ))
         );
     }
@@ -330,11 +824,11 @@ mod tests {
     fn test_deny_action_qstring_match() {
         let policy = r#"{
   "Version": "2012-10-17",
-  "Statement": {
+  "Statement": [{
     "Effect": "Allow",
     "Action": "dynamodb:*",
     "Resource": "arn:aws:dynamodb:us-east-2:123456789012:table/Books"
-  }
+  }]
 }"#;
         let policy = io::read_from_string(policy).expect("error parsing policy");
         let request = make_request(
@@ -348,7 +842,13 @@ mod tests {
             result,
             Ok(EvaluationResult::Deny(
                 Source::Action,
-                String::from("string_match")
+                String::from("string_match"),
+                Some(StatementRef {
+                    policy: 0,
+                    policy_id: None,
+                    statement: 0,
+                    sid: None,
+                })
             ))
         );
     }
@@ -357,11 +857,11 @@ mod tests {
     fn test_simple_allow() {
         let policy = r#"{
   "Version": "2012-10-17",
-  "Statement": {
+  "Statement": [{
     "Effect": "Allow",
     "Action": "dynamodb:*",
     "Resource": "arn:aws:dynamodb:us-east-2:123456789012:table/Books"
-  }
+  }]
 }"#;
         let policy = io::read_from_string(policy).expect("error parsing policy");
         let request = make_request(
@@ -371,6 +871,971 @@ mod tests {
             "arn:aws:dynamodb:us-east-2:123456789012:table/Books",
         );
         let result = evaluate(&request, &policy);
-        assert_eq!(result, Ok(EvaluationResult::Allow));
+        assert!(matches!(result, Ok(EvaluationResult::Allow(_))));
+    }
+
+    #[test]
+    fn test_evaluate_compiled() {
+        let policy = r#"{
+  "Version": "2012-10-17",
+  "Statement": [
+    {
+      "Sid": "ReadWrite",
+      "Effect": "Allow",
+      "Action": "s3:*Object",
+      "Resource": "arn:aws:s3:::bucket/*"
+    },
+    {
+      "Sid": "NoDelete",
+      "Effect": "Deny",
+      "Action": "s3:Delete*",
+      "Resource": "*"
+    }
+  ]
+}"#;
+        let policy = io::read_from_string(policy).expect("error parsing policy");
+        let compiled = compile(&policy);
+        let indices: Vec<usize> = compiled.statements().map(|s| s.index).collect();
+        assert_eq!(indices, vec![1, 0]);
+
+        for action in &["s3:GetObject", "S3:putobject"] {
+            let request = make_request(
+                "test_evaluate_compiled",
+                None,
+                action,
+                "arn:aws:s3:::bucket/key",
+            );
+            assert!(matches!(
+                evaluate_compiled(&request, &compiled),
+                Ok(EvaluationResult::Allow(Some(StatementRef {
+                    statement: 0,
+                    ..
+                })))
+            ));
+        }
+
+        let request = make_request(
+            "test_evaluate_compiled",
+            None,
+            "s3:DeleteObject",
+            "arn:aws:s3:::bucket/key",
+        );
+        assert!(matches!(
+            evaluate_compiled(&request, &compiled),
+            Ok(EvaluationResult::Deny(
+                Source::DeniedByAction,
+                _,
+                Some(StatementRef { statement: 1, .. })
+            ))
+        ));
+        assert_eq!(
+            evaluate_compiled(&request, &compiled).unwrap(),
+            evaluate(&request, &policy).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_tracing_spans_and_events() {
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        #[derive(Clone, Default)]
+        struct Recorder(Arc<Mutex<Vec<String>>>);
+        struct Fields<'a>(&'a mut Vec<String>);
+        impl Visit for Fields<'_> {
+            fn record_str(&mut self, field: &Field, value: &str) {
+                if field.name() == "element" || field.name() == "result" {
+                    self.0.push(format!("{}={}", field.name(), value));
+                }
+            }
+            fn record_debug(&mut self, _: &Field, _: &dyn std::fmt::Debug) {}
+        }
+        impl Subscriber for Recorder {
+            fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+                metadata.target() == TRACE_TARGET
+            }
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let mut recorded = self.0.lock().unwrap();
+                recorded.push(format!("span={}", span.metadata().name()));
+                Id::from_u64(recorded.len() as u64)
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, event: &Event<'_>) {
+                let mut fields = Vec::new();
+                event.record(&mut Fields(&mut fields));
+                self.0.lock().unwrap().push(fields.join(" "));
+            }
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let policy = io::read_from_string(
+            r#"{"Statement": [{"Effect": "Allow", "Action": "s3:GetObject", "Resource": "*"}]}"#,
+        )
+        .expect("error parsing policy");
+        let request = make_request("test_tracing", None, "s3:GetObject", "object");
+        let recorder = Recorder::default();
+        let result =
+            tracing::subscriber::with_default(recorder.clone(), || evaluate(&request, &policy));
+        assert!(matches!(result, Ok(EvaluationResult::Allow(_))));
+
+        let recorded = recorder.0.lock().unwrap();
+        assert_eq!(
+            recorded
+                .iter()
+                .filter(|r| r.starts_with("span="))
+                .collect::<Vec<_>>(),
+            vec!["span=evaluate", "span=policy", "span=statement"]
+        );
+        assert!(recorded.contains(&"element=action result=allow".to_string()));
+        assert!(recorded.contains(&"element=resource result=allow".to_string()));
+        assert_eq!(recorded.last().map(String::as_str), Some("result=allow"));
+    }
+
+    #[test]
+    fn test_evaluation_error_into_iam_error() {
+        let error: IamError =
+            EvaluationError::UnknownOperator("StringSoundsLike".to_string()).into();
+        assert!(matches!(
+            error,
+            IamError::Evaluation(EvaluationError::UnknownOperator(_))
+        ));
+        assert_eq!(
+            error.to_string(),
+            "The condition operator `StringSoundsLike` is not supported"
+        );
+
+        let error = EvaluationError::Errors(vec![
+            EvaluationError::InvalidValueCardinality,
+            EvaluationError::MissingVariableValue("aws:username".to_string()),
+        ]);
+        assert_eq!(error.to_string(), "2 errors occurred during evaluation");
+    }
+
+    #[test]
+    fn test_numeric_bool_ip_and_null_conditions() {
+        let policy = r#"{
+  "Version": "2012-10-17",
+  "Statement": [{
+    "Effect": "Allow",
+    "Action": "s3:GetObject",
+    "Resource": "*",
+    "Condition": {
+      "NumericLessThanEquals": { "aws:MultiFactorAuthAge": "3600" },
+      "Bool": { "aws:SecureTransport": "true" },
+      "IpAddress": { "aws:SourceIp": ["203.0.113.0/24", "2001:db8::/32"] },
+      "Null": { "aws:TokenIssueTime": "false" }
+    }
+  }]
+}"#;
+        let policy = io::read_from_string(policy).expect("error parsing policy");
+        let key = |name: &str| QualifiedName::from_str(name).unwrap();
+        let request_with = |age: i64, source_ip: &str| {
+            let mut request = make_request("test_operators", None, "s3:GetObject", "object");
+            let _ = request
+                .environment
+                .insert(key("aws:MultiFactorAuthAge"), ConditionValue::number(age));
+            let _ = request
+                .environment
+                .insert(key("aws:SourceIp"), ConditionValue::from(source_ip));
+            let _ = request.environment.insert(
+                key("aws:TokenIssueTime"),
+                ConditionValue::from("2020-05-20T18:40:00Z"),
+            );
+            request
+        };
+        let denied_by = |request: &Request| match evaluate(request, &policy) {
+            Ok(EvaluationResult::Deny(Source::Condition(_, key), _, _)) => Some(key.to_string()),
+            _ => None,
+        };
+
+        for source_ip in &["203.0.113.7", "2001:db8:1::1"] {
+            assert!(matches!(
+                evaluate(&request_with(3600, source_ip), &policy),
+                Ok(EvaluationResult::Allow(_))
+            ));
+        }
+        assert_eq!(
+            denied_by(&request_with(3601, "203.0.113.7")),
+            Some("aws:MultiFactorAuthAge".to_string())
+        );
+        assert_eq!(
+            denied_by(&request_with(60, "198.51.100.7")),
+            Some("aws:SourceIp".to_string())
+        );
+        assert_eq!(
+            denied_by(&request_with(60, "::ffff:203.0.113.7")),
+            Some("aws:SourceIp".to_string())
+        );
+
+        let mut request = request_with(60, "203.0.113.7");
+        let _ = request
+            .environment
+            .insert(key(AWS_SECURE_TRANSPORT), ConditionValue::boolean(false));
+        assert_eq!(denied_by(&request), Some(AWS_SECURE_TRANSPORT.to_string()));
+
+        let mut request = request_with(60, "203.0.113.7");
+        let _ = request.environment.remove(&key("aws:TokenIssueTime"));
+        assert_eq!(denied_by(&request), Some("aws:TokenIssueTime".to_string()));
+    }
+
+    #[test]
+    fn test_context_providers() {
+        let policy = r#"{
+  "Version": "2012-10-17",
+  "Statement": [{
+    "Effect": "Allow",
+    "Action": "s3:GetObject",
+    "Resource": "*",
+    "Condition": {
+      "StringLike": { "aws:CurrentTime": "2020-05-*" },
+      "StringEquals": { "aws:ResourceTag/Dept": "Accounting" }
+    }
+  }]
+}"#;
+        let policy = io::read_from_string(policy).expect("error parsing policy");
+        let tags = |key: &QualifiedName, _: &Request| {
+            if key.to_string() == "aws:ResourceTag/Dept" {
+                Some(ConditionValue::from("Accounting"))
+            } else {
+                None
+            }
+        };
+        let evaluator = Evaluator::new(&[&policy])
+            .with_clock(FixedClock::at(1_590_000_000))
+            .with_provider(tags);
+        let mut request = make_request("test_context_providers", None, "s3:GetObject", "object");
+        // the clock is only consulted when the request does not carry its own time.
+        let _ = request
+            .environment
+            .remove(&QualifiedName::from_str(AWS_EPOCH_TIME).unwrap());
+
+        let context = evaluator.context(&request);
+        assert_eq!(
+            context.get(&QualifiedName::from_str(AWS_CURRENT_TIME).unwrap()),
+            Some(&ConditionValue::from("2020-05-20T18:40:00Z"))
+        );
+        assert!(matches!(
+            evaluator.evaluate(&request),
+            Ok(EvaluationResult::Allow(_))
+        ));
+
+        let evaluator = Evaluator::new(&[&policy])
+            .with_provider(ClockProvider::new(FixedClock::at(1_600_000_000)))
+            .with_provider(tags);
+        assert!(matches!(
+            evaluator.evaluate(&request),
+            Ok(EvaluationResult::Deny(Source::Condition(_, _), _, _))
+        ));
+    }
+
+    #[test]
+    fn test_date_conditions() {
+        let policy = r#"{
+  "Version": "2012-10-17",
+  "Statement": [{
+    "Effect": "Allow",
+    "Action": "s3:GetObject",
+    "Resource": "*",
+    "Condition": {
+      "DateGreaterThanEquals": { "aws:CurrentTime": "2020-01-01T00:00:00Z" },
+      "DateLessThan": { "aws:EpochTime": "2020-02-01" }
+    }
+  }]
+}"#;
+        let policy = io::read_from_string(policy).expect("error parsing policy");
+        let clock = FixedClock::at_time("2019-12-31T23:59:59Z").unwrap();
+        let evaluator = Evaluator::new(&[&policy]).with_clock(&clock);
+        let mut request = make_request("test_date_conditions", None, "s3:GetObject", "object");
+        let _ = request
+            .environment
+            .remove(&QualifiedName::from_str(AWS_EPOCH_TIME).unwrap());
+
+        assert!(!matches!(
+            evaluator.evaluate(&request),
+            Ok(EvaluationResult::Allow(_))
+        ));
+
+        clock.advance(1);
+        assert_eq!(clock.current_time(), "2020-01-01T00:00:00Z");
+        assert!(matches!(
+            evaluator.evaluate(&request),
+            Ok(EvaluationResult::Allow(_))
+        ));
+
+        clock.advance(31 * 24 * 60 * 60);
+        assert!(matches!(
+            evaluator.evaluate(&request),
+            Ok(EvaluationResult::Deny(Source::Condition(_, key), _, _))
+                if key.to_string() == AWS_EPOCH_TIME
+        ));
+    }
+
+    #[test]
+    fn test_called_via() {
+        let policy = r#"{
+  "Version": "2012-10-17",
+  "Statement": [{
+    "Effect": "Allow",
+    "Action": "dynamodb:CreateTable",
+    "Resource": "*",
+    "Condition": {
+      "ForAllValues:StringEquals": {
+        "aws:CalledVia": ["cloudformation.amazonaws.com", "dynamodb.amazonaws.com"]
+      },
+      "StringEquals": { "aws:CalledViaFirst": "cloudformation.amazonaws.com" }
+    }
+  }]
+}"#;
+        let policy = io::read_from_string(policy).expect("error parsing policy");
+        let mut request = make_request("test_called_via", None, "dynamodb:CreateTable", "table");
+        assert!(!matches!(
+            evaluate(&request, &policy),
+            Ok(EvaluationResult::Allow(_))
+        ));
+
+        request.called_via = vec!["cloudformation.amazonaws.com".to_string()];
+        assert!(matches!(
+            evaluate(&request, &policy),
+            Ok(EvaluationResult::Allow(_))
+        ));
+        assert_eq!(
+            request
+                .context()
+                .get(&QualifiedName::from_str(AWS_VIA_AWS_SERVICE).unwrap()),
+            Some(&ConditionValue::boolean(true))
+        );
+
+        request.called_via.push("athena.amazonaws.com".to_string());
+        assert!(matches!(
+            evaluate(&request, &policy),
+            Ok(EvaluationResult::Deny(Source::Condition(_, key), _, _))
+                if key.to_string() == AWS_CALLED_VIA
+        ));
+    }
+
+    #[test]
+    fn test_source_arn_and_account() {
+        let policy = r#"{
+  "Version": "2012-10-17",
+  "Statement": [{
+    "Effect": "Allow",
+    "Action": "sns:Publish",
+    "Resource": "arn:aws:sns:us-east-1:123456789012:topic",
+    "Condition": {
+      "ArnLike": { "aws:SourceArn": "arn:aws:s3:::bucket-*" },
+      "StringEquals": { "aws:SourceAccount": "123456789012" }
+    }
+  }]
+}"#;
+        let policy = io::read_from_string(policy).expect("error parsing policy");
+        let mut request = make_request(
+            "test_source_arn_and_account",
+            None,
+            "sns:Publish",
+            "arn:aws:sns:us-east-1:123456789012:topic",
+        );
+        request.source_arn = Some("arn:aws:s3:::bucket-1".to_string());
+        request.source_account = Some("123456789012".to_string());
+        assert!(matches!(
+            evaluate(&request, &policy),
+            Ok(EvaluationResult::Allow(_))
+        ));
+
+        request.source_account = Some("210987654321".to_string());
+        assert!(matches!(
+            evaluate(&request, &policy),
+            Ok(EvaluationResult::Deny(Source::Condition(_, key), _, _))
+                if key.to_string() == AWS_SOURCE_ACCOUNT
+        ));
+
+        request.source_arn = Some("arn:aws:s3:::other-bucket".to_string());
+        request.source_account = Some("123456789012".to_string());
+        assert!(matches!(
+            evaluate(&request, &policy),
+            Ok(EvaluationResult::Deny(Source::Condition(_, _), _, _))
+        ));
+    }
+
+    #[test]
+    fn test_source_vpce() {
+        let policy = Policy::endpoint(vec![
+            Statement::endpoint_access(Default::default(), Default::default()),
+            Statement::deny_unless_source_vpce(
+                vec!["vpce-1a2b3c4d"],
+                Default::default(),
+                Default::default(),
+            ),
+        ])
+        .expect("error creating endpoint policy");
+        let mut request = make_request(
+            "test_source_vpce",
+            Some(Principal {
+                principal_type: PrincipalType::AWS,
+                identifier: "arn:aws:iam::123456789012:user/alice".to_string(),
+                unique_id: None,
+            }),
+            "s3:GetObject",
+            "arn:aws:s3:::bucket/key",
+        );
+        assert_eq!(request.source_vpce(), None);
+
+        request.set_source_vpce("vpce-1a2b3c4d");
+        request.set_source_vpc("vpc-111bbb22");
+        assert_eq!(request.source_vpce(), Some("vpce-1a2b3c4d"));
+        assert_eq!(request.source_vpc(), Some("vpc-111bbb22"));
+        assert!(matches!(
+            evaluate(&request, &policy),
+            Ok(EvaluationResult::Allow(_))
+        ));
+
+        request.set_source_vpce("vpce-4d3c2b1a");
+        assert!(matches!(
+            evaluate(&request, &policy),
+            Ok(EvaluationResult::Deny(Source::DeniedByAction, _, _))
+        ));
+    }
+
+    #[test]
+    fn test_derived_principal_keys() {
+        let policy = r#"{
+  "Version": "2012-10-17",
+  "Statement": [{
+    "Effect": "Allow",
+    "Action": "s3:GetObject",
+    "Resource": "arn:aws:s3:::bucket/*",
+    "Condition": {
+      "StringEquals": { "aws:PrincipalType": "User", "aws:username": "alice" }
+    }
+  }]
+}"#;
+        let policy = io::read_from_string(policy).expect("error parsing policy");
+        let principal = |identifier: &str| Principal {
+            principal_type: PrincipalType::AWS,
+            identifier: identifier.to_string(),
+            unique_id: None,
+        };
+        let request = make_request(
+            "test_derived_principal_keys",
+            Some(principal("arn:aws:iam::123456789012:user/division/alice")),
+            "s3:GetObject",
+            "arn:aws:s3:::bucket/key",
+        );
+        assert!(matches!(
+            evaluate(&request, &policy),
+            Ok(EvaluationResult::Allow(_))
+        ));
+
+        let request = make_request(
+            "test_derived_principal_keys",
+            Some(principal(
+                "arn:aws:sts::123456789012:assumed-role/reader/alice",
+            )),
+            "s3:GetObject",
+            "arn:aws:s3:::bucket/key",
+        );
+        assert!(matches!(
+            evaluate(&request, &policy),
+            Ok(EvaluationResult::Deny(Source::Condition(_, _), _, _))
+        ));
+    }
+
+    #[test]
+    fn test_caller_identity_principal() {
+        let principal = Principal::from_caller_identity(
+            r#"{"UserId": "AROACKCEVSQ6C2EXAMPLE:alice",
+                "Account": "123456789012",
+                "Arn": "arn:aws:sts::123456789012:assumed-role/reader/alice"}"#,
+        )
+        .unwrap();
+        assert_eq!(principal.account(), Some("123456789012".to_string()));
+        assert_eq!(principal.session_name(), Some("alice".to_string()));
+        assert_eq!(
+            principal.context(),
+            vec![
+                (AWS_PRINCIPAL_TYPE, "AssumedRole".to_string()),
+                (AWS_USER_ID, "AROACKCEVSQ6C2EXAMPLE:alice".to_string()),
+                (AWS_PRINCIPAL_ACCOUNT, "123456789012".to_string()),
+                (
+                    AWS_PRINCIPAL_ARN,
+                    "arn:aws:iam::123456789012:role/reader".to_string()
+                ),
+            ]
+        );
+
+        let principal = Principal::service("sns.amazonaws.com");
+        assert_eq!(principal.account(), None);
+        assert_eq!(
+            principal.context(),
+            vec![(AWS_PRINCIPAL_SERVICE_NAME, "sns.amazonaws.com".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_evaluate_joint() {
+        let identity = io::read_from_string(
+            r#"{"Statement": [{"Effect": "Allow", "Action": "s3:GetObject",
+                "Resource": "arn:aws:s3:::bucket/*"}]}"#,
+        )
+        .expect("error parsing policy");
+        let resource = io::read_from_string(
+            r#"{"Statement": [{"Effect": "Allow", "Action": "s3:PutObject",
+                "Principal": {"AWS": "arn:aws:iam::123456789012:user/alice"},
+                "Resource": "arn:aws:s3:::bucket/*"}]}"#,
+        )
+        .expect("error parsing policy");
+        let request = |account: &str, action: &str| {
+            let mut request = make_request(
+                "test_evaluate_joint",
+                Some(Principal::aws("arn:aws:iam::123456789012:user/alice")),
+                action,
+                "arn:aws:s3:::bucket/key",
+            );
+            request.set_resource_account(account);
+            request
+        };
+
+        let result = evaluate_joint(
+            &request("123456789012", "s3:PutObject"),
+            &[&identity],
+            &[&resource],
+        )
+        .unwrap();
+        assert_eq!(result.scope, AccountScope::SameAccount);
+        assert!(result.is_allowed());
+        assert_eq!(result.decided_by, PolicyKind::Resource);
+
+        let result = evaluate_joint(
+            &request("210987654321", "s3:PutObject"),
+            &[&identity],
+            &[&resource],
+        )
+        .unwrap();
+        assert_eq!(result.scope, AccountScope::CrossAccount);
+        assert!(!result.is_allowed());
+        assert_eq!(result.decided_by, PolicyKind::Identity);
+
+        let result = evaluate_joint(
+            &request("210987654321", "s3:GetObject"),
+            &[&identity],
+            &[&resource],
+        )
+        .unwrap();
+        assert!(!result.is_allowed());
+        assert_eq!(result.decided_by, PolicyKind::Resource);
+    }
+
+    #[test]
+    fn test_role_chain() {
+        let identity = io::read_from_string(
+            r#"{"Statement": [{"Effect": "Allow", "Action": "sts:AssumeRole",
+                "Resource": "arn:aws:iam::123456789012:role/reader"}]}"#,
+        )
+        .expect("error parsing policy");
+        let trust = io::read_from_string(
+            r#"{"Statement": [{"Effect": "Allow", "Action": "sts:AssumeRole",
+                "Principal": {"AWS": "arn:aws:iam::123456789012:user/alice"}}]}"#,
+        )
+        .expect("error parsing policy");
+        let permissions = io::read_from_string(
+            r#"{"Statement": [{"Effect": "Allow", "Action": "s3:GetObject",
+                "Resource": "arn:aws:s3:::bucket/*"}]}"#,
+        )
+        .expect("error parsing policy");
+        let session = io::read_from_string(
+            r#"{"Statement": [{"Effect": "Allow", "Action": "s3:*",
+                "Resource": "arn:aws:s3:::bucket/public/*"}]}"#,
+        )
+        .expect("error parsing policy");
+        let role = AssumedRole::new("arn:aws:iam::123456789012:role/reader", "alice", &trust)
+            .with_permission_policy(&permissions)
+            .with_session_policy(&session);
+        assert_eq!(
+            role.session_principal().unwrap().identifier,
+            "arn:aws:sts::123456789012:assumed-role/reader/alice"
+        );
+        let chain = RoleChain::new(
+            Principal::aws("arn:aws:iam::123456789012:user/alice"),
+            &[&identity],
+        )
+        .assume(role);
+
+        let public = make_request(
+            "test_role_chain",
+            None,
+            "s3:GetObject",
+            "arn:aws:s3:::bucket/public/key",
+        );
+        let result = chain.evaluate(&public).unwrap();
+        assert_eq!(result.assume_role.len(), 1);
+        assert!(result.is_allowed());
+
+        let private = make_request(
+            "test_role_chain",
+            None,
+            "s3:GetObject",
+            "arn:aws:s3:::bucket/private/key",
+        );
+        assert!(!chain.evaluate(&private).unwrap().is_allowed());
+
+        let chain = RoleChain::new(
+            Principal::aws("arn:aws:iam::123456789012:user/bob"),
+            &[&identity],
+        )
+        .assume(AssumedRole::new(
+            "arn:aws:iam::123456789012:role/reader",
+            "bob",
+            &trust,
+        ));
+        let result = chain.evaluate(&public).unwrap();
+        assert!(matches!(
+            result.assume_role[0],
+            EvaluationResult::Deny(Source::Principal, _, _)
+        ));
+        assert_eq!(result.result, None);
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let policy = io::read_from_string(
+            r#"{"Statement": [{"Effect": "Allow", "Action": "dynamodb:*",
+                "Resource": "arn:aws:dynamodb:us-east-2:123456789012:table/Books"}]}"#,
+        )
+        .expect("error parsing policy");
+        let table = |name: &str| format!("arn:aws:dynamodb:us-east-2:123456789012:table/{}", name);
+        let cases = vec![
+            TestCase {
+                name: None,
+                request: make_request("read <Books>", None, "dynamodb:GetItem", &table("Books")),
+                expected: Expected::Allow,
+                source: None,
+            },
+            TestCase {
+                name: None,
+                request: make_request(
+                    "read NotBooks",
+                    None,
+                    "dynamodb:GetItem",
+                    &table("NotBooks"),
+                ),
+                expected: Expected::Allow,
+                source: None,
+            },
+            TestCase {
+                name: None,
+                request: make_request(
+                    "write NotBooks",
+                    None,
+                    "dynamodb:PutItem",
+                    &table("NotBooks"),
+                ),
+                expected: Expected::Deny,
+                source: Some("Resource".to_string()),
+            },
+        ];
+        let report = run_tests("books & tables", &[&policy], &cases);
+        assert!(!report.is_success());
+        assert_eq!(report.failures(), 1);
+        assert_eq!(report.errors(), 0);
+
+        let mut buffer: Vec<u8> = Default::default();
+        report.write_junit(&mut buffer).unwrap();
+        let xml = String::from_utf8(buffer).unwrap();
+        assert!(xml
+            .contains(r#"<testsuite name="books &amp; tables" tests="3" failures="1" errors="0""#));
+        assert!(
+            xml.contains(r#"<testcase name="read &lt;Books&gt;" classname="books &amp; tables""#)
+        );
+        assert!(xml.contains(r#"<failure message="expected allow, denied by Resource"#));
+        assert_eq!(xml.matches("<testcase ").count(), 3);
+        assert_eq!(xml.matches("</testcase>").count(), 1);
+        assert!(xml.contains(r#"<property name="statements.covered" value="1"/>"#));
+    }
+
+    #[test]
+    fn test_run_spec() {
+        let report = run_spec(Path::new("tests/data/spec/books.json")).expect("error running spec");
+        assert_eq!(report.name(), "books");
+        assert!(report.is_success());
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_run_spec_yaml() {
+        let report = run_spec(Path::new("tests/data/spec/books.yaml")).expect("error running spec");
+        assert_eq!(report.name(), "books");
+        assert!(report.is_success());
+    }
+
+    #[test]
+    fn test_explain() {
+        let policy = io::read_from_string(
+            r#"{"Statement": [
+                {"Effect": "Allow", "Action": "dynamodb:*",
+                 "Resource": "arn:aws:dynamodb:*:123456789012:table/*"},
+                {"Effect": "Deny", "Action": "dynamodb:GetItem",
+                 "Resource": "arn:aws:dynamodb:*:123456789012:table/Books"},
+                {"Effect": "Allow", "Action": "dynamodb:GetItem",
+                 "Resource": "arn:aws:dynamodb:*:123456789012:table/Books",
+                 "Condition": {"StringEquals": {"aws:RequestedRegion": "us-west-2"}}},
+                {"Effect": "Allow", "Action": "s3:GetObject", "Resource": "*"}]}"#,
+        )
+        .expect("error parsing policy");
+        let request = make_request(
+            "read books",
+            None,
+            "dynamodb:GetItem",
+            "arn:aws:dynamodb:us-east-1:123456789012:table/Books",
+        );
+
+        let reports = explain(&request, &policy);
+        let contributions: Vec<Contribution> = reports.iter().map(|r| r.contribution).collect();
+        assert_eq!(
+            contributions,
+            vec![
+                Contribution::Allow,
+                Contribution::ExplicitDeny,
+                Contribution::NoEffect,
+                Contribution::NoEffect
+            ]
+        );
+        assert_eq!(reports[2].action, ElementMatch::Matched);
+        assert_eq!(
+            reports[2].conditions[0].result,
+            Ok(ElementMatch::NotMatched)
+        );
+        assert_eq!(reports[3].principal, ElementMatch::NotApplicable);
+        assert_eq!(reports[3].action, ElementMatch::NotMatched);
+        assert_eq!(reports[3].resource, ElementMatch::Matched);
+    }
+
+    #[test]
+    fn test_counterexamples() {
+        let policy = io::read_from_string(
+            r#"{"Statement": [
+                {"Sid": "West", "Effect": "Allow", "Action": "dynamodb:GetItem",
+                 "Resource": "arn:aws:dynamodb:*:123456789012:table/*",
+                 "Condition": {"StringEquals": {"aws:RequestedRegion": "us-west-2"}}},
+                {"Sid": "Insecure", "Effect": "Allow", "Action": "dynamodb:GetItem",
+                 "Resource": "arn:aws:dynamodb:*:123456789012:table/*",
+                 "Condition": {"StringEquals": {"aws:RequestedRegion": "eu-west-1"},
+                               "Bool": {"aws:SecureTransport": "false"}}}]}"#,
+        )
+        .expect("error parsing policy");
+        let request = make_request(
+            "read books",
+            None,
+            "dynamodb:GetItem",
+            "arn:aws:dynamodb:us-east-1:123456789012:table/Books",
+        );
+
+        let results = counterexamples(&request, &[&policy]).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            results[0].to_string(),
+            "set aws:RequestedRegion=us-west-2 (allowed by [0] Statement[0] West)"
+        );
+        assert_eq!(results[1].changes.len(), 2);
+    }
+
+    #[test]
+    fn test_statement_coverage() {
+        let policy = io::read_from_string(
+            r#"{"Id": "tables", "Statement": [
+                {"Sid": "Read", "Effect": "Allow", "Action": "dynamodb:GetItem",
+                 "Resource": "arn:aws:dynamodb:us-east-2:123456789012:table/*"},
+                {"Sid": "Write", "Effect": "Allow", "Action": "dynamodb:PutItem",
+                 "Resource": "arn:aws:dynamodb:us-east-2:123456789012:table/*"},
+                {"Effect": "Deny", "Action": "dynamodb:*",
+                 "Resource": "arn:aws:dynamodb:us-east-2:123456789012:table/Secrets"}]}"#,
+        )
+        .expect("error parsing policy");
+        let table = |name: &str| format!("arn:aws:dynamodb:us-east-2:123456789012:table/{}", name);
+        let books = make_request("read books", None, "dynamodb:GetItem", &table("Books"));
+        let secrets = make_request("read secrets", None, "dynamodb:GetItem", &table("Secrets"));
+
+        let coverage = statement_coverage(&[&books, &secrets], &[&policy]);
+        let matched: Vec<usize> = coverage.statements().iter().map(|s| s.matched).collect();
+        assert_eq!(matched, vec![2, 0, 1]);
+        assert_eq!(coverage.covered(), 2);
+        let uncovered = coverage.uncovered();
+        assert_eq!(uncovered.len(), 1);
+        assert_eq!(uncovered[0].to_string(), "tables Statement[1] (Write)");
+    }
+
+    #[test]
+    fn test_evaluate_matrix() {
+        let books = io::read_from_string(
+            r#"{"Statement": [{"Effect": "Allow", "Action": "dynamodb:*",
+                "Resource": "arn:aws:dynamodb:us-east-2:123456789012:table/Books"}]}"#,
+        )
+        .expect("error parsing policy");
+        let any = io::read_from_string(
+            r#"{"Statement": [{"Effect": "Allow", "Action": "dynamodb:*", "Resource": "*"}]}"#,
+        )
+        .expect("error parsing policy");
+        let requests: Vec<Request> = vec!["Books", "NotBooks"]
+            .into_iter()
+            .map(|table| {
+                make_request(
+                    "test_evaluate_matrix",
+                    None,
+                    "dynamodb:read",
+                    &format!("arn:aws:dynamodb:us-east-2:123456789012:table/{}", table),
+                )
+            })
+            .collect();
+
+        let results = evaluate_matrix(&requests, &[&books, &any]);
+        assert_eq!(results.len(), 2);
+        let allow = |policy: usize| {
+            Ok(EvaluationResult::Allow(Some(StatementRef {
+                policy,
+                policy_id: None,
+                statement: 0,
+                sid: None,
+            })))
+        };
+        assert_eq!(results[0], vec![allow(0), allow(1)]);
+        assert!(matches!(
+            results[1][0],
+            Ok(EvaluationResult::Deny(Source::Resource, _, _))
+        ));
+        assert!(matches!(results[1][1], Ok(EvaluationResult::Allow(_))));
+
+        #[cfg(feature = "parallel")]
+        assert_eq!(
+            crate::offline::evaluate_matrix_par(&requests, &[&books, &any]),
+            results
+        );
+    }
+
+    #[test]
+    fn test_caching_evaluator() {
+        let books = io::read_from_string(
+            r#"{"Statement": [{"Effect": "Allow", "Action": "dynamodb:*",
+                "Resource": "arn:aws:dynamodb:us-east-2:123456789012:table/Books"}]}"#,
+        )
+        .expect("error parsing policy");
+        let any = io::read_from_string(
+            r#"{"Statement": [{"Effect": "Allow", "Action": "dynamodb:*", "Resource": "*"}]}"#,
+        )
+        .expect("error parsing policy");
+        let request = make_request(
+            "test_caching_evaluator",
+            None,
+            "dynamodb:read",
+            "arn:aws:dynamodb:us-east-2:123456789012:table/NotBooks",
+        );
+
+        let mut evaluator = CachingEvaluator::new(&[&books], 1);
+        assert!(matches!(
+            evaluator.evaluate(&request),
+            Ok(EvaluationResult::Deny(Source::Resource, _, _))
+        ));
+        assert!(evaluator.evaluate(&request).is_ok());
+        assert_eq!((evaluator.hits(), evaluator.misses()), (1, 1));
+        assert_eq!(evaluator.len(), 1);
+
+        evaluator.set_policies(&[&any]);
+        assert!(evaluator.is_empty());
+        assert!(matches!(
+            evaluator.evaluate(&request),
+            Ok(EvaluationResult::Allow(_))
+        ));
+        assert_eq!(evaluator.misses(), 2);
+    }
+
+    #[test]
+    fn test_statement_effect_matrix() {
+        const BOOKS: &str = "arn:aws:dynamodb:us-east-2:123456789012:table/Books";
+        const NOT_BOOKS: &str = "arn:aws:dynamodb:us-east-2:123456789012:table/NotBooks";
+
+        fn source(result: EvaluationResult) -> Option<Source> {
+            match result {
+                EvaluationResult::Allow(_) => None,
+                EvaluationResult::Deny(source, _, _) => Some(source),
+            }
+        }
+
+        // effect, element, the result for the listed request, and for another request; where
+        // `None` is allow.
+        let matrix = vec![
+            ("Allow", "Action", None, Some(Source::Action)),
+            ("Allow", "NotAction", Some(Source::NotAction), None),
+            ("Allow", "Resource", None, Some(Source::Resource)),
+            ("Allow", "NotResource", Some(Source::NotResource), None),
+            (
+                "Deny",
+                "Action",
+                Some(Source::DeniedByAction),
+                Some(Source::Default),
+            ),
+            (
+                "Deny",
+                "NotAction",
+                Some(Source::Default),
+                Some(Source::DeniedByNotAction),
+            ),
+            (
+                "Deny",
+                "Resource",
+                Some(Source::DeniedByResource),
+                Some(Source::Default),
+            ),
+            (
+                "Deny",
+                "NotResource",
+                Some(Source::Default),
+                Some(Source::DeniedByNotResource),
+            ),
+        ];
+        let listed = make_request(
+            "test_statement_effect_matrix",
+            None,
+            "dynamodb:GetItem",
+            BOOKS,
+        );
+        let other = make_request(
+            "test_statement_effect_matrix",
+            None,
+            "dynamodb:PutItem",
+            NOT_BOOKS,
+        );
+        for (effect, element, listed_source, other_source) in matrix {
+            let (action, resource) = if element.ends_with("Action") {
+                (
+                    format!(r#""{}": "dynamodb:GetItem""#, element),
+                    r#""Resource": "*""#.to_string(),
+                )
+            } else {
+                (
+                    r#""Action": "dynamodb:*""#.to_string(),
+                    format!(r#""{}": "{}""#, element, BOOKS),
+                )
+            };
+            let policy = io::read_from_string(&format!(
+                r#"{{"Statement": [{{"Effect": "{}", {}, {}}}]}}"#,
+                effect, action, resource
+            ))
+            .expect("error parsing policy");
+            assert_eq!(
+                evaluate(&listed, &policy).map(source),
+                Ok(listed_source),
+                "{} {} listed",
+                effect,
+                element
+            );
+            assert_eq!(
+                evaluate(&other, &policy).map(source),
+                Ok(other_source),
+                "{} {} other",
+                effect,
+                element
+            );
+        }
     }
 }
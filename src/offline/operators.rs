@@ -1,10 +1,12 @@
-use crate::model::{ConditionValue, GlobalConditionOperator, QString};
+use crate::arn::{wildcard_match, ArnPattern};
+use crate::model::{ConditionValue, GlobalOperator};
+use crate::offline::request::Environment;
 use crate::offline::variables::expand_string;
 use crate::offline::EvaluationError;
-use std::collections::HashMap;
-use std::fmt::{Display, Error, Formatter};
-use std::string::ToString;
-use tracing::{error, instrument};
+use crate::offline::TRACE_TARGET;
+use std::net::IpAddr;
+use std::str::FromStr;
+use tracing::warn;
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
@@ -16,10 +18,9 @@ pub type OperatorResult = Result<bool, EvaluationError>;
 // Public Functions
 // ------------------------------------------------------------------------------------------------
 
-#[instrument]
 pub fn evaluate_all(
-    environment: &HashMap<QString, ConditionValue>,
-    operator: &GlobalConditionOperator,
+    environment: &Environment,
+    operator: &GlobalOperator,
     lhs: &ConditionValue,
     rhs: &[ConditionValue],
 ) -> OperatorResult {
@@ -28,16 +29,15 @@ pub fn evaluate_all(
         .all(|r| match evaluate(environment, operator, lhs, r) {
             Ok(v) => v,
             Err(err) => {
-                error!("Evaluation error {:?}", err);
+                warn!(target: TRACE_TARGET, operator = ?operator, error = ?err);
                 false
             }
         }))
 }
 
-#[instrument]
 pub fn evaluate_any(
-    environment: &HashMap<QString, ConditionValue>,
-    operator: &GlobalConditionOperator,
+    environment: &Environment,
+    operator: &GlobalOperator,
     lhs: &ConditionValue,
     rhs: &[ConditionValue],
 ) -> OperatorResult {
@@ -46,209 +46,211 @@ pub fn evaluate_any(
         .any(|r| match evaluate(environment, operator, lhs, r) {
             Ok(v) => v,
             Err(err) => {
-                error!("Evaluation error {:?}", err);
+                warn!(target: TRACE_TARGET, operator = ?operator, error = ?err);
                 false
             }
         }))
 }
 
-#[instrument]
+///
+/// Evaluate `operator` with the value from the request, `lhs`, and a value from the policy,
+/// `rhs`. As in IAM all values are compared in their string form, and are parsed as numbers,
+/// dates, booleans, or IP addresses by the operators that expect them; policy variables in
+/// `rhs` are replaced for the string, ARN, date, and boolean operators.
+///
 pub fn evaluate(
-    environment: &HashMap<QString, ConditionValue>,
-    operator: &GlobalConditionOperator,
+    environment: &Environment,
+    operator: &GlobalOperator,
     lhs: &ConditionValue,
     rhs: &ConditionValue,
 ) -> OperatorResult {
     match operator {
-        GlobalConditionOperator::StringEquals => call_operator(
-            environment,
-            string_equals,
-            lhs,
-            rhs,
-            &ExpectedValueType::String,
-        ),
-        GlobalConditionOperator::StringNotEquals => call_operator(
-            environment,
-            string_not_equals,
-            lhs,
-            rhs,
-            &ExpectedValueType::String,
-        ),
-        GlobalConditionOperator::StringEqualsIgnoreCase => call_operator(
-            environment,
-            string_equals_ignore_case,
-            lhs,
-            rhs,
-            &ExpectedValueType::String,
-        ),
-        GlobalConditionOperator::StringNotEqualsIgnoreCase => call_operator(
-            environment,
-            string_not_equals_ignore_case,
-            lhs,
-            rhs,
-            &ExpectedValueType::String,
-        ),
-        GlobalConditionOperator::StringLike => call_operator(
-            environment,
-            string_like,
-            lhs,
-            rhs,
-            &ExpectedValueType::String,
-        ),
-        GlobalConditionOperator::StringNotLike => call_operator(
-            environment,
-            string_not_like,
-            lhs,
-            rhs,
-            &ExpectedValueType::String,
-        ),
-        GlobalConditionOperator::NumericEquals => {
-            Err(EvaluationError::UnknownOperator(String::new()))
-        }
-        GlobalConditionOperator::NumericNotEquals => {
-            Err(EvaluationError::UnknownOperator(String::new()))
+        GlobalOperator::StringEquals => {
+            string_compare(environment, lhs, rhs, |lhs, rhs| lhs == rhs)
         }
-        GlobalConditionOperator::NumericLessThan => {
-            Err(EvaluationError::UnknownOperator(String::new()))
+        GlobalOperator::StringNotEquals => {
+            string_compare(environment, lhs, rhs, |lhs, rhs| lhs != rhs)
         }
-        GlobalConditionOperator::NumericLessThanEquals => {
-            Err(EvaluationError::UnknownOperator(String::new()))
+        GlobalOperator::StringEqualsIgnoreCase => string_compare(environment, lhs, rhs, |l, r| {
+            l.to_lowercase() == r.to_lowercase()
+        }),
+        GlobalOperator::StringNotEqualsIgnoreCase => {
+            string_compare(environment, lhs, rhs, |l, r| {
+                l.to_lowercase() != r.to_lowercase()
+            })
         }
-        GlobalConditionOperator::NumericGreaterThan => {
-            Err(EvaluationError::UnknownOperator(String::new()))
+        GlobalOperator::StringLike => {
+            string_compare(environment, lhs, rhs, |lhs, rhs| wildcard_match(rhs, lhs))
         }
-        GlobalConditionOperator::NumericGreaterThanEquals => {
-            Err(EvaluationError::UnknownOperator(String::new()))
+        GlobalOperator::StringNotLike => {
+            string_compare(environment, lhs, rhs, |lhs, rhs| !wildcard_match(rhs, lhs))
         }
-        GlobalConditionOperator::DateEquals => Err(EvaluationError::UnknownOperator(String::new())),
-        GlobalConditionOperator::DateNotEquals => {
-            Err(EvaluationError::UnknownOperator(String::new()))
+        GlobalOperator::NumericEquals => numeric_compare(lhs, rhs, |lhs, rhs| lhs == rhs),
+        GlobalOperator::NumericNotEquals => numeric_compare(lhs, rhs, |lhs, rhs| lhs != rhs),
+        GlobalOperator::NumericLessThan => numeric_compare(lhs, rhs, |lhs, rhs| lhs < rhs),
+        GlobalOperator::NumericLessThanEquals => numeric_compare(lhs, rhs, |lhs, rhs| lhs <= rhs),
+        GlobalOperator::NumericGreaterThan => numeric_compare(lhs, rhs, |lhs, rhs| lhs > rhs),
+        GlobalOperator::NumericGreaterThanEquals => {
+            numeric_compare(lhs, rhs, |lhs, rhs| lhs >= rhs)
         }
-        GlobalConditionOperator::DateLessThan => {
-            Err(EvaluationError::UnknownOperator(String::new()))
+        GlobalOperator::DateEquals => date_compare(environment, lhs, rhs, |lhs, rhs| lhs == rhs),
+        GlobalOperator::DateNotEquals => date_compare(environment, lhs, rhs, |lhs, rhs| lhs != rhs),
+        GlobalOperator::DateLessThan => date_compare(environment, lhs, rhs, |lhs, rhs| lhs < rhs),
+        GlobalOperator::DateLessThanEquals => {
+            date_compare(environment, lhs, rhs, |lhs, rhs| lhs <= rhs)
         }
-        GlobalConditionOperator::DateLessThanEquals => {
-            Err(EvaluationError::UnknownOperator(String::new()))
+        GlobalOperator::DateGreaterThan => {
+            date_compare(environment, lhs, rhs, |lhs, rhs| lhs > rhs)
         }
-        GlobalConditionOperator::DateGreaterThan => {
-            Err(EvaluationError::UnknownOperator(String::new()))
+        GlobalOperator::DateGreaterThanEquals => {
+            date_compare(environment, lhs, rhs, |lhs, rhs| lhs >= rhs)
         }
-        GlobalConditionOperator::DateGreaterThanEquals => {
-            Err(EvaluationError::UnknownOperator(String::new()))
+        GlobalOperator::Bool => {
+            let lhs = bool_value(lhs)?;
+            let rhs = bool_value(&expand_rhs_value(environment, rhs)?)?;
+            Ok(lhs == rhs)
         }
-        GlobalConditionOperator::Bool => Err(EvaluationError::UnknownOperator(String::new())),
-        GlobalConditionOperator::BinaryEquals => {
-            Err(EvaluationError::UnknownOperator(String::new()))
+        GlobalOperator::BinaryEquals => Ok(lhs == rhs),
+        GlobalOperator::IpAddress => ip_address(lhs, rhs),
+        GlobalOperator::NotIpAddress => ip_address(lhs, rhs).map(|result| !result),
+        GlobalOperator::ArnEquals | GlobalOperator::ArnLike => {
+            string_compare(environment, lhs, rhs, arn_like)
         }
-        GlobalConditionOperator::IpAddress => Err(EvaluationError::UnknownOperator(String::new())),
-        GlobalConditionOperator::NotIpAddress => {
-            Err(EvaluationError::UnknownOperator(String::new()))
+        GlobalOperator::ArnNotEquals | GlobalOperator::ArnNotLike => {
+            string_compare(environment, lhs, rhs, |lhs, rhs| !arn_like(lhs, rhs))
         }
-        GlobalConditionOperator::ArnEquals => Err(EvaluationError::UnknownOperator(String::new())),
-        GlobalConditionOperator::ArnLike => Err(EvaluationError::UnknownOperator(String::new())),
-        GlobalConditionOperator::ArnNotEquals => {
-            Err(EvaluationError::UnknownOperator(String::new()))
+        GlobalOperator::Null => {
+            // the key is present, so it is null only if the policy expects it not to be.
+            bool_value(rhs).map(|rhs| !rhs)
         }
-        GlobalConditionOperator::ArnNotLike => Err(EvaluationError::UnknownOperator(String::new())),
-        GlobalConditionOperator::Null => Err(EvaluationError::UnknownOperator(String::new())),
-        GlobalConditionOperator::Other(id) => Err(EvaluationError::UnknownOperator(id.to_string())),
+        GlobalOperator::Other(id) => Err(EvaluationError::UnknownOperator(id.to_string())),
     }
 }
 
+///
+/// Returns `true` if the operator is one of the negated forms, such as `StringNotEquals`, which
+/// with more than one value in a condition requires that the request value matches none of
+/// them; rather than any of them as for the other operators.
+///
+pub fn is_negated(operator: &GlobalOperator) -> bool {
+    matches!(
+        operator,
+        GlobalOperator::StringNotEquals
+            | GlobalOperator::StringNotEqualsIgnoreCase
+            | GlobalOperator::StringNotLike
+            | GlobalOperator::NumericNotEquals
+            | GlobalOperator::DateNotEquals
+            | GlobalOperator::NotIpAddress
+            | GlobalOperator::ArnNotEquals
+            | GlobalOperator::ArnNotLike
+    )
+}
+
 // ------------------------------------------------------------------------------------------------
 // Private Functions
 // ------------------------------------------------------------------------------------------------
 
-#[derive(Debug)]
-#[allow(dead_code)] // TODO: remove this once all operators are implemented.
-enum ExpectedValueType {
-    String,
-    Integer,
-    Float,
-    Bool,
+fn expand_rhs_value(
+    environment: &Environment,
+    rhs: &ConditionValue,
+) -> Result<String, EvaluationError> {
+    expand_string(environment, rhs)
 }
 
-impl Display for ExpectedValueType {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(f, "{:?}", self)
-    }
+fn string_compare(
+    environment: &Environment,
+    lhs: &ConditionValue,
+    rhs: &ConditionValue,
+    compare: impl Fn(&str, &str) -> bool,
+) -> OperatorResult {
+    let rhs = expand_rhs_value(environment, rhs)?;
+    Ok(compare(lhs, &rhs))
 }
 
-fn call_operator(
-    environment: &HashMap<QString, ConditionValue>,
-    operator: impl Fn(&ConditionValue, &ConditionValue) -> OperatorResult,
+fn numeric_compare(
     lhs: &ConditionValue,
     rhs: &ConditionValue,
-    value_type: &ExpectedValueType,
+    compare: impl Fn(f64, f64) -> bool,
 ) -> OperatorResult {
-    let lhs = match (value_type, lhs) {
-        (ExpectedValueType::String, ConditionValue::String(_))
-        | (ExpectedValueType::Integer, ConditionValue::Integer(_))
-        | (ExpectedValueType::Float, ConditionValue::Float(_))
-        | (ExpectedValueType::Bool, ConditionValue::Bool(_)) => lhs,
-        (ev, _) => return Err(EvaluationError::ExpectingVariableType(ev.to_string())),
-    };
-    let rhs = match (value_type, rhs) {
-        (ExpectedValueType::String, ConditionValue::String(_)) => {
-            expand_rhs_value(environment, rhs.clone())?
-        }
-        (ExpectedValueType::Integer, ConditionValue::Integer(_))
-        | (ExpectedValueType::Float, ConditionValue::Float(_))
-        | (ExpectedValueType::Bool, ConditionValue::Bool(_)) => rhs.clone(),
-        (ev, _) => return Err(EvaluationError::ExpectingVariableType(ev.to_string())),
+    let value = |value: &ConditionValue| {
+        f64::from_str(value)
+            .map_err(|_| EvaluationError::ExpectingVariableType("Numeric".to_string()))
     };
-    operator(lhs, &rhs)
+    Ok(compare(value(lhs)?, value(rhs)?))
 }
 
-fn expand_rhs_value(
-    environment: &HashMap<QString, ConditionValue>,
-    rhs: ConditionValue,
-) -> Result<ConditionValue, EvaluationError> {
-    match rhs {
-        ConditionValue::String(input) => {
-            let output = expand_string(environment, &input)?;
-            Ok(ConditionValue::String(output))
-        }
-        _ => Ok(rhs),
-    }
+///
+/// Dates may be given as ISO 8601 UTC strings or as seconds since the epoch, in either the
+/// request or the policy, and are compared as seconds since the epoch.
+///
+fn date_compare(
+    environment: &Environment,
+    lhs: &ConditionValue,
+    rhs: &ConditionValue,
+    compare: impl Fn(i64, i64) -> bool,
+) -> OperatorResult {
+    let lhs = date_value(lhs)?;
+    let rhs = date_value(&ConditionValue::from(expand_rhs_value(environment, rhs)?))?;
+    Ok(compare(lhs, rhs))
 }
 
-fn string_equals(lhs: &ConditionValue, rhs: &ConditionValue) -> OperatorResult {
-    match (lhs, rhs) {
-        (ConditionValue::String(lhs), ConditionValue::String(rhs)) => Ok(lhs == rhs),
-        (_, _) => Err(EvaluationError::ExpectingVariableType("String".to_string())),
-    }
+fn date_value(value: &ConditionValue) -> Result<i64, EvaluationError> {
+    value
+        .epoch_seconds()
+        .ok_or_else(|| EvaluationError::ExpectingVariableType("Date".to_string()))
 }
 
-fn string_not_equals(lhs: &ConditionValue, rhs: &ConditionValue) -> OperatorResult {
-    match (lhs, rhs) {
-        (ConditionValue::String(lhs), ConditionValue::String(rhs)) => Ok(lhs != rhs),
-        (_, _) => Err(EvaluationError::ExpectingVariableType("String".to_string())),
+fn bool_value(value: &str) -> Result<bool, EvaluationError> {
+    if value.eq_ignore_ascii_case("true") {
+        Ok(true)
+    } else if value.eq_ignore_ascii_case("false") {
+        Ok(false)
+    } else {
+        Err(EvaluationError::ExpectingVariableType("Bool".to_string()))
     }
 }
 
-fn string_equals_ignore_case(lhs: &ConditionValue, rhs: &ConditionValue) -> OperatorResult {
-    match (lhs, rhs) {
-        (ConditionValue::String(lhs), ConditionValue::String(rhs)) => {
-            Ok(lhs.to_lowercase() == rhs.to_lowercase())
-        }
-        (_, _) => Err(EvaluationError::ExpectingVariableType("String".to_string())),
-    }
+///
+/// `ArnEquals` and `ArnLike` are the same test, each of the components of the ARN is matched
+/// separately and may contain wildcards. A value that is not an ARN never matches.
+///
+fn arn_like(lhs: &str, rhs: &str) -> bool {
+    ArnPattern::from_str(rhs)
+        .map(|pattern| pattern.matches_str(lhs))
+        .unwrap_or_default()
 }
 
-fn string_not_equals_ignore_case(lhs: &ConditionValue, rhs: &ConditionValue) -> OperatorResult {
-    match (lhs, rhs) {
-        (ConditionValue::String(lhs), ConditionValue::String(rhs)) => {
-            Ok(lhs.to_lowercase() != rhs.to_lowercase())
+///
+/// The request value must be an IP address and the policy value an address or a CIDR block;
+/// an IPv4 address never matches an IPv6 block, or the reverse.
+///
+fn ip_address(lhs: &ConditionValue, rhs: &ConditionValue) -> OperatorResult {
+    let invalid = || EvaluationError::ExpectingVariableType("IpAddress".to_string());
+    let address = IpAddr::from_str(lhs).map_err(|_| invalid())?;
+    let rhs: &str = rhs;
+    let (network, prefix) = match rhs.split_once('/') {
+        Some((network, prefix)) => (network, Some(prefix)),
+        None => (rhs, None),
+    };
+    let network = IpAddr::from_str(network).map_err(|_| invalid())?;
+    let (address, network, bits) = match (address, network) {
+        (IpAddr::V4(address), IpAddr::V4(network)) => (
+            u128::from(u32::from(address)),
+            u128::from(u32::from(network)),
+            32,
+        ),
+        (IpAddr::V6(address), IpAddr::V6(network)) => {
+            (u128::from(address), u128::from(network), 128)
         }
-        (_, _) => Err(EvaluationError::ExpectingVariableType("String".to_string())),
+        _ => return Ok(false),
+    };
+    let prefix = match prefix {
+        Some(prefix) => prefix.parse::<u32>().map_err(|_| invalid())?,
+        None => bits,
+    };
+    if prefix > bits {
+        return Err(invalid());
     }
-}
-
-fn string_like(_lhs: &ConditionValue, _rhs: &ConditionValue) -> OperatorResult {
-    Ok(false)
-}
-
-fn string_not_like(_lhs: &ConditionValue, _rhs: &ConditionValue) -> OperatorResult {
-    Ok(false)
+    let shift = bits - prefix;
+    Ok(shift == bits || address >> shift == network >> shift)
 }
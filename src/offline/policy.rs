@@ -1,43 +1,57 @@
-use crate::model::{OneOrAll, Policy};
-use crate::offline::request::Request;
-use crate::offline::statement::evaluate_statement;
-use crate::offline::{reduce_optional_results, EvaluationError, PartialEvaluationResult};
-use tracing::{info, instrument};
+use crate::model::Policy;
+use crate::offline::compiled::CompiledPolicy;
+use crate::offline::request::{Environment, Request};
+use crate::offline::statement::{evaluate_statement, sid_string};
+use crate::offline::{
+    is_explicit_deny, overrides_result, EvaluationError, PartialEvaluationResult, TRACE_TARGET,
+};
+use tracing::debug_span;
 
 // ------------------------------------------------------------------------------------------------
 // Public Functions
 // ------------------------------------------------------------------------------------------------
 
-#[instrument]
 pub fn evaluate_policy(
     request: &Request,
-    policy: &Policy,
+    request_action: &str,
+    environment: &Environment,
+    policy: &CompiledPolicy<'_>,
     policy_index: i32,
 ) -> Result<PartialEvaluationResult, EvaluationError> {
-    let id = policy_id(policy, policy_index);
-    let result = match &policy.statement {
-        OneOrAll::One(statement) => evaluate_statement(request, statement, 0),
-        OneOrAll::All(statements) => {
-            let results: Result<Vec<PartialEvaluationResult>, EvaluationError> = statements
-                .iter()
-                .enumerate()
-                .map(|(idx, statement)| evaluate_statement(request, statement, idx as i32))
-                .collect();
-            match results {
-                Ok(mut results) => Ok(reduce_optional_results(&mut results)),
-                Err(err) => Err(err),
+    let span = debug_span!(
+        target: TRACE_TARGET,
+        "policy",
+        policy = policy_id(policy.policy(), policy_index).as_str(),
+    );
+    let _enter = span.enter();
+    // any deny is final, so stop at the first; explicit denies are ordered first. Only the
+    // result kept records its statement, so the statement's sid is copied at most once.
+    let mut effect: PartialEvaluationResult = None;
+    for statement in policy.statements() {
+        let result = evaluate_statement(
+            request,
+            request_action,
+            environment,
+            &statement,
+            statement.index as i32,
+        )?;
+        if overrides_result(&effect, &result) {
+            effect = result.map(|result| {
+                result.in_statement(statement.index, sid_string(statement.statement))
+            });
+            if is_explicit_deny(&effect) {
+                break;
             }
         }
-    };
-    info!("Returning policy {} effect {:?}", id, result);
-    result
+    }
+    Ok(effect.map(|result| result.in_policy(policy_index as usize, policy.policy().id.clone())))
 }
 
 // ------------------------------------------------------------------------------------------------
 // Private Functions
 // ------------------------------------------------------------------------------------------------
 
-fn policy_id(policy: &Policy, policy_index: i32) -> String {
+pub(crate) fn policy_id(policy: &Policy, policy_index: i32) -> String {
     match &policy.id {
         Some(id) => id.to_string(),
         None => format!("[{}]", policy_index),
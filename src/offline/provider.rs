@@ -0,0 +1,212 @@
+/*!
+Provides context key providers, which compute the values of condition keys when a request is
+evaluated rather than requiring them in the environment of every request.
+
+A [`ContextProvider`](trait.ContextProvider.html) is registered on an
+[`Evaluator`](struct.Evaluator.html) and is asked only for the keys used in the conditions of
+its policies, and only when the key is not already in the request's context; values in the
+request are never replaced. Providers are asked in the order they were registered and the first
+value returned is used. Any function `Fn(&QualifiedName, &Request) -> Option<ConditionValue>` is a
+provider, for example to load resource tags, and [`ClockProvider`](struct.ClockProvider.html)
+provides `aws:CurrentTime` and `aws:EpochTime`.
+*/
+
+use crate::context::keys::{AWS_CURRENT_TIME, AWS_EPOCH_TIME};
+use crate::model::{ConditionValue, Policy, QualifiedName};
+use crate::offline::clock::{format_utc_time, parse_utc_time, Clock, SystemClock};
+use crate::offline::compiled::CompiledPolicy;
+use crate::offline::request::{Environment, Request};
+use crate::offline::{compile_all, evaluate_with_context, EvaluationError, EvaluationResult};
+use std::fmt::{Debug, Formatter};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Computes the value of a condition key for a request.
+///
+pub trait ContextProvider {
+    ///
+    /// Return the value of `key` for `request`, or `None` if this provider does not know it.
+    /// `context` is the request's context with any values already provided during this
+    /// evaluation, so that one value may be derived from another.
+    ///
+    fn provide(
+        &self,
+        key: &QualifiedName,
+        request: &Request,
+        context: &Environment,
+    ) -> Option<ConditionValue>;
+}
+
+///
+/// Provides `aws:CurrentTime`, as an ISO 8601 UTC string, and `aws:EpochTime`, in seconds,
+/// from a [`Clock`](clock/trait.Clock.html). If the context already has one of the two keys the
+/// other is derived from it, so both describe the same instant.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ClockProvider<C = SystemClock>
+where
+    C: Clock,
+{
+    clock: C,
+}
+
+///
+/// Evaluates requests against a set of policies, asking its context providers for the values
+/// of condition keys not in a request's context.
+///
+pub struct Evaluator<'a> {
+    policies: Vec<CompiledPolicy<'a>>,
+    providers: Vec<Box<dyn ContextProvider + 'a>>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<F> ContextProvider for F
+where
+    F: Fn(&QualifiedName, &Request) -> Option<ConditionValue>,
+{
+    fn provide(
+        &self,
+        key: &QualifiedName,
+        request: &Request,
+        _context: &Environment,
+    ) -> Option<ConditionValue> {
+        self(key, request)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl ClockProvider {
+    /// A provider that reads the system clock.
+    pub fn system() -> Self {
+        Self::default()
+    }
+}
+
+impl<C> ClockProvider<C>
+where
+    C: Clock,
+{
+    /// A provider that reads `clock`.
+    pub fn new(clock: C) -> Self {
+        Self { clock }
+    }
+}
+
+impl<C> ContextProvider for ClockProvider<C>
+where
+    C: Clock,
+{
+    fn provide(
+        &self,
+        key: &QualifiedName,
+        _request: &Request,
+        context: &Environment,
+    ) -> Option<ConditionValue> {
+        let key: &str = key;
+        if key == AWS_EPOCH_TIME {
+            let seconds = match context.get(&QualifiedName::new_unchecked(AWS_CURRENT_TIME)) {
+                Some(time) => parse_utc_time(time)?,
+                None => self.clock.now(),
+            };
+            Some(ConditionValue::number(seconds))
+        } else if key == AWS_CURRENT_TIME {
+            let seconds = match context.get(&QualifiedName::new_unchecked(AWS_EPOCH_TIME)) {
+                Some(seconds) => seconds.parse().ok()?,
+                None => self.clock.now(),
+            };
+            Some(ConditionValue::from(format_utc_time(seconds)))
+        } else {
+            None
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Debug for Evaluator<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Evaluator")
+            .field("policies", &self.policies)
+            .field("providers", &self.providers.len())
+            .finish()
+    }
+}
+
+impl<'a> Evaluator<'a> {
+    /// Create an evaluator for `policies`, with no context providers.
+    pub fn new(policies: &[&'a Policy]) -> Self {
+        Self {
+            policies: compile_all(policies),
+            providers: Default::default(),
+        }
+    }
+
+    ///
+    /// Provide `aws:CurrentTime` and `aws:EpochTime` from `clock`; this registers a
+    /// [`ClockProvider`](struct.ClockProvider.html) after any providers already registered.
+    ///
+    pub fn with_clock<C>(self, clock: C) -> Self
+    where
+        C: Clock + 'a,
+    {
+        self.with_provider(ClockProvider::new(clock))
+    }
+
+    /// Register a context provider, asked after those already registered.
+    pub fn with_provider<P>(mut self, provider: P) -> Self
+    where
+        P: ContextProvider + 'a,
+    {
+        self.providers.push(Box::new(provider));
+        self
+    }
+
+    ///
+    /// Return the context used to evaluate `request`; this is the request's own context with
+    /// the values provided for the condition keys used by the policies.
+    ///
+    pub fn context(&self, request: &Request) -> Environment {
+        let mut context = request.context();
+        for key in self.condition_keys() {
+            if context.contains_key(key) {
+                continue;
+            }
+            let value = self
+                .providers
+                .iter()
+                .find_map(|provider| provider.provide(key, request, &context));
+            if let Some(value) = value {
+                let _ = context.insert(key.clone(), value);
+            }
+        }
+        context
+    }
+
+    /// Evaluate `request` against all the policies.
+    pub fn evaluate(&self, request: &Request) -> Result<EvaluationResult, EvaluationError> {
+        evaluate_with_context(request, &self.context(request), &self.policies)
+    }
+
+    fn condition_keys(&self) -> Vec<&QualifiedName> {
+        let mut keys: Vec<&QualifiedName> = Default::default();
+        for policy in &self.policies {
+            for compiled in policy.statements() {
+                if let Some(conditions) = &compiled.statement.condition {
+                    for key in conditions.values().flat_map(|values| values.keys()) {
+                        if !keys.contains(&key) {
+                            keys.push(key);
+                        }
+                    }
+                }
+            }
+        }
+        keys
+    }
+}
@@ -1,7 +1,16 @@
-use crate::model::{ConditionValue, PrincipalType, QString};
+use crate::arn::ArnPattern;
+use crate::context::keys::{
+    AWS_CALLED_VIA, AWS_CALLED_VIA_FIRST, AWS_CALLED_VIA_LAST, AWS_FEDERATED_PROVIDER,
+    AWS_PRINCIPAL_ACCOUNT, AWS_PRINCIPAL_ARN, AWS_PRINCIPAL_SERVICE_NAME, AWS_PRINCIPAL_TYPE,
+    AWS_RESOURCE_ACCOUNT, AWS_SOURCE_ACCOUNT, AWS_SOURCE_ARN, AWS_SOURCE_VPC, AWS_SOURCE_VPCE,
+    AWS_USER_ID, AWS_USER_NAME, AWS_VIA_AWS_SERVICE,
+};
+use crate::error::IamError;
+use crate::model::{ConditionValue, QualifiedName};
 use crate::offline::EvaluationError;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::ops::Deref;
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -12,13 +21,13 @@ use uuid::Uuid;
 ///
 /// Alias for the hash used to store environment values.
 ///
-pub type Environment = HashMap<QString, ConditionValue>;
+pub type Environment = HashMap<QualifiedName, ConditionValue>;
 
 ///
 /// This struct represents a request and it's environment against which a policy, or policies,
 /// will be evaluated.
 ///
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Request {
     /// An optional request identifier.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -27,23 +36,71 @@ pub struct Request {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub principal: Option<Principal>,
     /// The action being requested.
-    pub action: QString,
+    pub action: QualifiedName,
     /// The resource to which the action is applied.
     pub resource: String,
+    /// The ARN of the resource that is the source of a service-to-service request, such as the
+    /// S3 bucket notifying an SNS topic; the value of the `aws:SourceArn` key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_arn: Option<String>,
+    /// The account that owns the source of a service-to-service request; the value of the
+    /// `aws:SourceAccount` key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_account: Option<String>,
+    /// The services that made the request on behalf of the principal, in the order they were
+    /// called, such as `cloudformation.amazonaws.com` then `dynamodb.amazonaws.com`; the
+    /// values of the multi-valued `aws:CalledVia` key.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub called_via: Vec<String>,
     /// Additional properties which may be used in conditions.
     #[serde(skip_serializing_if = "HashMap::is_empty")]
     pub environment: Environment,
 }
 
+///
+/// The kind of principal making a request, corresponding to the keys of the `Principal`
+/// element of a statement.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum PrincipalType {
+    /// An AWS account, IAM user or role, or STS session; the `AWS` key.
+    AWS,
+    /// A federated identity provider; the `Federated` key.
+    Federated,
+    /// An AWS service; the `Service` key.
+    Service,
+    /// The canonical user ID of an account, used by S3; the `CanonicalUser` key.
+    CanonicalUser,
+}
+
 ///
 /// A structure representing a single principal.
 ///
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Principal {
     /// The principal type used in Policy documents.
     pub principal_type: PrincipalType,
     /// The corresponding principal ID.
     pub identifier: String,
+    /// The unique ID of an IAM user or role, such as `AIDACKCEVSQ6C2EXAMPLE`, which cannot be
+    /// determined from its ARN; used to derive `aws:userid`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unique_id: Option<String>,
+}
+
+///
+/// The result of the STS `GetCallerIdentity` action, which may be converted into a `Principal`.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CallerIdentity {
+    /// The unique ID of the caller; for an assumed role this is followed by `:` and the
+    /// session name.
+    pub user_id: String,
+    /// The account that owns the caller.
+    pub account: String,
+    /// The ARN of the caller.
+    pub arn: String,
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -52,7 +109,7 @@ pub struct Principal {
 
 impl Request {
     /// Return the value of an environment variable.
-    pub fn get(&self, key: &QString) -> Result<&ConditionValue, EvaluationError> {
+    pub fn get(&self, key: &QualifiedName) -> Result<&ConditionValue, EvaluationError> {
         match self.environment.get(key) {
             Some(v) => Ok(v),
             None => Err(EvaluationError::UnknownVariableName(key.to_string())),
@@ -61,7 +118,7 @@ impl Request {
 
     /// Return the value of an environment variable.
     pub fn get_(&self, key: &str) -> Result<&ConditionValue, EvaluationError> {
-        let key = QString::from_str(key)
+        let key = QualifiedName::from_str(key)
             .map_err(|_| EvaluationError::InvalidVariableName(key.to_string()))?;
         match self.environment.get(&key) {
             Some(v) => Ok(v),
@@ -69,6 +126,118 @@ impl Request {
         }
     }
 
+    /// Return the identifier of the VPC endpoint the request was sent through, if any.
+    pub fn source_vpce(&self) -> Option<&str> {
+        self.get_string(AWS_SOURCE_VPCE)
+    }
+
+    /// Set the identifier of the VPC endpoint the request was sent through, this is the
+    /// value of the `aws:SourceVpce` key.
+    pub fn set_source_vpce<S>(&mut self, vpce_id: S)
+    where
+        S: Into<String>,
+    {
+        self.set_string(AWS_SOURCE_VPCE, vpce_id.into())
+    }
+
+    /// Return the identifier of the VPC the request originated from, if any.
+    pub fn source_vpc(&self) -> Option<&str> {
+        self.get_string(AWS_SOURCE_VPC)
+    }
+
+    /// Set the identifier of the VPC the request originated from, this is the value of
+    /// the `aws:SourceVpc` key.
+    pub fn set_source_vpc<S>(&mut self, vpc_id: S)
+    where
+        S: Into<String>,
+    {
+        self.set_string(AWS_SOURCE_VPC, vpc_id.into())
+    }
+
+    ///
+    /// Return the account that owns the resource, this is the account of the resource ARN or,
+    /// for ARNs without an account such as those of S3 buckets, the value of the
+    /// `aws:ResourceAccount` key.
+    ///
+    pub fn resource_account(&self) -> Option<String> {
+        ArnPattern::from_str(&self.resource)
+            .ok()
+            .map(|arn| arn.account_id().to_string())
+            .filter(|account| !account.is_empty())
+            .or_else(|| self.get_string(AWS_RESOURCE_ACCOUNT).map(str::to_string))
+    }
+
+    /// Set the account that owns the resource, this is the value of the
+    /// `aws:ResourceAccount` key.
+    pub fn set_resource_account<S>(&mut self, account_id: S)
+    where
+        S: Into<String>,
+    {
+        self.set_string(AWS_RESOURCE_ACCOUNT, account_id.into())
+    }
+
+    ///
+    /// Return the values of a multi-valued key, these are not held in the environment; at
+    /// present this is only `aws:CalledVia` which has a value if `called_via` is not empty.
+    ///
+    pub fn context_values(&self, key: &QualifiedName) -> Option<Vec<ConditionValue>> {
+        if self.called_via.is_empty() || key.deref() != AWS_CALLED_VIA {
+            None
+        } else {
+            Some(self.called_via.iter().map(ConditionValue::from).collect())
+        }
+    }
+
+    ///
+    /// Return the environment used to evaluate conditions; this is `environment` with the
+    /// keys derived from the other request fields added. Values already present in
+    /// `environment` are not replaced.
+    ///
+    /// * `aws:SourceArn` is `source_arn`.
+    /// * `aws:SourceAccount` is `source_account` or, if that is not set, the account of
+    ///   `source_arn` if it has one; S3 bucket ARNs, for example, do not.
+    /// * `aws:CalledViaFirst` and `aws:CalledViaLast` are the first and last of
+    ///   `called_via`, and `aws:ViaAWSService` is `true` if it is not empty; `aws:CalledVia`
+    ///   itself is multi-valued, see [`context_values`](#method.context_values).
+    /// * `aws:PrincipalType`, `aws:userid`, and `aws:username` are derived from an AWS
+    ///   `principal`, see [`Principal::context`](struct.Principal.html#method.context).
+    ///
+    pub fn context(&self) -> Environment {
+        let mut context = self.environment.clone();
+        let source_account = self.source_account.clone().or_else(|| {
+            self.source_arn
+                .as_ref()
+                .and_then(|arn| ArnPattern::from_str(arn).ok())
+                .map(|arn| arn.account_id().to_string())
+                .filter(|account| !account.is_empty())
+        });
+        let mut values = vec![
+            (AWS_SOURCE_ARN, self.source_arn.clone()),
+            (AWS_SOURCE_ACCOUNT, source_account),
+            (AWS_CALLED_VIA_FIRST, self.called_via.first().cloned()),
+            (AWS_CALLED_VIA_LAST, self.called_via.last().cloned()),
+        ];
+        if let Some(principal) = &self.principal {
+            values.extend(
+                principal
+                    .context()
+                    .into_iter()
+                    .map(|(key, value)| (key, Some(value))),
+            );
+        }
+        for (key, value) in values {
+            if let Some(value) = value {
+                let _ = context
+                    .entry(QualifiedName::new_unchecked(key))
+                    .or_insert_with(|| ConditionValue::from(value));
+            }
+        }
+        let _ = context
+            .entry(QualifiedName::new_unchecked(AWS_VIA_AWS_SERVICE))
+            .or_insert_with(|| ConditionValue::boolean(!self.called_via.is_empty()));
+        context
+    }
+
     /// Return the request_id within the request or generate one if it is `None`.
     pub fn request_id() -> Option<String> {
         Some(
@@ -78,4 +247,256 @@ impl Request {
                 .to_string(),
         )
     }
+
+    fn get_string(&self, key: &str) -> Option<&str> {
+        self.get_(key).ok().map(ConditionValue::deref)
+    }
+
+    fn set_string(&mut self, key: &str, value: String) {
+        let _ = self.environment.insert(
+            QualifiedName::new_unchecked(key),
+            ConditionValue::from(value),
+        );
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl From<CallerIdentity> for Principal {
+    fn from(identity: CallerIdentity) -> Self {
+        // for an assumed role the user ID is the role's unique ID and the session name.
+        let unique_id = match identity.user_id.split_once(':') {
+            Some((unique_id, _)) => unique_id.to_string(),
+            None => identity.user_id,
+        };
+        Self {
+            principal_type: PrincipalType::AWS,
+            identifier: identity.arn,
+            unique_id: Some(unique_id),
+        }
+    }
+}
+
+impl Principal {
+    /// Construct an AWS principal, an account ID or the ARN of an IAM or STS identity.
+    pub fn aws<S>(identifier: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            principal_type: PrincipalType::AWS,
+            identifier: identifier.into(),
+            unique_id: None,
+        }
+    }
+
+    /// Construct a service principal, such as `sns.amazonaws.com`.
+    pub fn service<S>(service_name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            principal_type: PrincipalType::Service,
+            identifier: service_name.into(),
+            unique_id: None,
+        }
+    }
+
+    /// Construct a federated principal, the identity provider such as
+    /// `cognito-identity.amazonaws.com` or the ARN of a SAML provider.
+    pub fn federated<S>(provider: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            principal_type: PrincipalType::Federated,
+            identifier: provider.into(),
+            unique_id: None,
+        }
+    }
+
+    /// Set the unique ID of an IAM user or role, used to derive `aws:userid`.
+    pub fn with_unique_id<S>(self, unique_id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            unique_id: Some(unique_id.into()),
+            ..self
+        }
+    }
+
+    ///
+    /// Construct an AWS principal from the JSON returned by the STS `GetCallerIdentity`
+    /// action, or the `aws sts get-caller-identity` command, which has the fields `UserId`,
+    /// `Account`, and `Arn`.
+    ///
+    pub fn from_caller_identity(json: &str) -> Result<Self, IamError> {
+        let identity: CallerIdentity = serde_json::from_str(json)?;
+        Ok(identity.into())
+    }
+
+    /// Return the ARN of an AWS principal, if it was identified by ARN.
+    pub fn arn(&self) -> Option<&str> {
+        self.aws_arn().map(|_| self.identifier.as_str())
+    }
+
+    /// Return the account of an AWS principal, if known.
+    pub fn account(&self) -> Option<String> {
+        if self.principal_type != PrincipalType::AWS {
+            None
+        } else if is_account_id(&self.identifier) {
+            Some(self.identifier.clone())
+        } else {
+            self.aws_arn()
+                .map(|arn| arn.account_id().to_string())
+                .filter(|account| !account.is_empty())
+        }
+    }
+
+    /// Return the session name of an assumed-role principal.
+    pub fn session_name(&self) -> Option<String> {
+        let arn = self.aws_arn()?;
+        match (arn.service(), resource_path(&arn).as_slice()) {
+            ("sts", ["assumed-role", _, session]) => Some(session.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Return the identity provider of a federated principal.
+    pub fn federated_provider(&self) -> Option<&str> {
+        if self.principal_type == PrincipalType::Federated {
+            Some(&self.identifier)
+        } else {
+            None
+        }
+    }
+
+    /// Return the service name of a service principal.
+    pub fn service_name(&self) -> Option<&str> {
+        if self.principal_type == PrincipalType::Service {
+            Some(&self.identifier)
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// Return the value of `aws:PrincipalArn` for an AWS principal; this is the ARN of the
+    /// principal except for an assumed-role session, where it is the ARN of the role, and an
+    /// account ID, where it is the ARN of the account root.
+    ///
+    pub fn principal_arn(&self) -> Option<String> {
+        if self.principal_type != PrincipalType::AWS {
+            return None;
+        }
+        if is_account_id(&self.identifier) {
+            return Some(format!("arn:aws:iam::{}:root", self.identifier));
+        }
+        let arn = self.aws_arn()?;
+        match (arn.service(), resource_path(&arn).as_slice()) {
+            ("sts", ["assumed-role", role, _]) => Some(format!(
+                "arn:{}:iam::{}:role/{}",
+                arn.partition(),
+                arn.account_id(),
+                role
+            )),
+            _ => Some(self.identifier.clone()),
+        }
+    }
+
+    ///
+    /// Return the condition keys AWS derives from this principal, following the table in
+    /// [Information about the principal](https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_policies_variables.html#principaltable).
+    /// For AWS principals the identifier may be an account ID or the ARN of the account root,
+    /// an IAM user, an assumed-role session, or a federated user.
+    ///
+    /// | Identifier                  | `aws:PrincipalType` | `aws:userid`            | `aws:username` |
+    /// |-----------------------------|---------------------|-------------------------|----------------|
+    /// | account, or its root ARN    | `Account`           | account ID              |                |
+    /// | `user/name`                 | `User`              | unique ID               | `name`         |
+    /// | `assumed-role/role/session` | `AssumedRole`       | `unique ID:session`     |                |
+    /// | `federated-user/name`       | `FederatedUser`     | `account:name`          |                |
+    /// | `*`                         | `Anonymous`         | `anonymous`             |                |
+    ///
+    /// Where the value requires the principal's `unique_id` and it is not set, the key is
+    /// omitted. Other than for `*`, `aws:PrincipalAccount` and `aws:PrincipalArn`, see
+    /// `principal_arn`, are also included. A service principal provides
+    /// `aws:PrincipalServiceName` and a federated principal `aws:FederatedProvider`.
+    ///
+    pub fn context(&self) -> Vec<(&'static str, String)> {
+        let mut values: Vec<(&'static str, String)> = Default::default();
+        if let Some(service_name) = self.service_name() {
+            values.push((AWS_PRINCIPAL_SERVICE_NAME, service_name.to_string()));
+            return values;
+        }
+        if let Some(provider) = self.federated_provider() {
+            values.push((AWS_FEDERATED_PROVIDER, provider.to_string()));
+            return values;
+        }
+        if self.principal_type != PrincipalType::AWS {
+            return values;
+        }
+        if self.identifier == "*" {
+            values.push((AWS_PRINCIPAL_TYPE, "Anonymous".to_string()));
+            values.push((AWS_USER_ID, "anonymous".to_string()));
+            return values;
+        } else if is_account_id(&self.identifier) {
+            values.push((AWS_PRINCIPAL_TYPE, "Account".to_string()));
+            values.push((AWS_USER_ID, self.identifier.clone()));
+        } else if let Some(arn) = self.aws_arn() {
+            let account = arn.account_id();
+            match (arn.service(), resource_path(&arn).as_slice()) {
+                ("iam", ["root"]) => {
+                    values.push((AWS_PRINCIPAL_TYPE, "Account".to_string()));
+                    values.push((AWS_USER_ID, account.to_string()));
+                }
+                ("iam", ["user", .., name]) => {
+                    values.push((AWS_PRINCIPAL_TYPE, "User".to_string()));
+                    if let Some(unique_id) = &self.unique_id {
+                        values.push((AWS_USER_ID, unique_id.clone()));
+                    }
+                    values.push((AWS_USER_NAME, name.to_string()));
+                }
+                ("sts", ["assumed-role", _, session]) => {
+                    values.push((AWS_PRINCIPAL_TYPE, "AssumedRole".to_string()));
+                    if let Some(unique_id) = &self.unique_id {
+                        values.push((AWS_USER_ID, format!("{}:{}", unique_id, session)));
+                    }
+                }
+                ("sts", ["federated-user", name]) => {
+                    values.push((AWS_PRINCIPAL_TYPE, "FederatedUser".to_string()));
+                    values.push((AWS_USER_ID, format!("{}:{}", account, name)));
+                }
+                _ => {}
+            }
+        }
+        if let Some(account) = self.account() {
+            values.push((AWS_PRINCIPAL_ACCOUNT, account));
+        }
+        if let Some(principal_arn) = self.principal_arn() {
+            values.push((AWS_PRINCIPAL_ARN, principal_arn));
+        }
+        values
+    }
+
+    fn aws_arn(&self) -> Option<ArnPattern> {
+        if self.principal_type == PrincipalType::AWS {
+            ArnPattern::from_str(&self.identifier).ok()
+        } else {
+            None
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn is_account_id(s: &str) -> bool {
+    s.len() == 12 && s.chars().all(|c| c.is_ascii_digit())
+}
+
+fn resource_path(arn: &ArnPattern) -> Vec<&str> {
+    arn.resource().split('/').collect()
 }
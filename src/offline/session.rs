@@ -0,0 +1,236 @@
+/*!
+Provides a simulation of role sessions, evaluating a request made by a session obtained through
+a chain of `sts:AssumeRole` requests.
+
+A [`RoleChain`](../struct.RoleChain.html) starts with an identity, an AWS principal and its
+identity policies, and each [`AssumedRole`](../struct.AssumedRole.html) is a role, its trust
+policy, its permission policies, and an optional session policy passed when it was assumed.
+Each role is assumed by the principal of the previous session, or the identity for the first:
+
+1. the `sts:AssumeRole` request, for the ARN of the role, must be allowed by the trust policy
+   of the role; and
+1. it must be allowed by the permissions of the caller, its identity policies or, for a
+   session, the permission policies of its role limited by its session policy.
+
+The request is then evaluated in the final session, with the principal of the session, an
+`assumed-role` ARN, and must be allowed by the permission policies of the role and, if
+present, by its session policy; an explicit deny in any of them denies the request. As the
+session policy can only limit the permissions of the role, the same request in a session
+without a session policy is allowed at least as often.
+
+This simulation requires the permissions of the caller in every case, where AWS does not
+within an account if the trust policy names the calling user or role itself; and it does not
+model permissions boundaries, service control policies, or session tags.
+*/
+
+use crate::arn::ArnPattern;
+use crate::model::{Policy, QualifiedName};
+use crate::offline::{
+    evaluate, evaluate_all, EvaluationError, EvaluationResult, Principal, Request,
+};
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A role assumed in a [`RoleChain`](struct.RoleChain.html).
+///
+#[derive(Clone, Debug)]
+pub struct AssumedRole<'a> {
+    role_arn: String,
+    session_name: String,
+    trust_policy: &'a Policy,
+    permission_policies: Vec<&'a Policy>,
+    session_policy: Option<&'a Policy>,
+}
+
+///
+/// An identity and the roles it assumes in turn, see the module documentation.
+///
+#[derive(Clone, Debug)]
+pub struct RoleChain<'a> {
+    identity: Principal,
+    identity_policies: Vec<&'a Policy>,
+    roles: Vec<AssumedRole<'a>>,
+}
+
+///
+/// The result of evaluating a request in the session at the end of a role chain.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionEvaluation {
+    /// The result of the `sts:AssumeRole` request for each role, in order; evaluation stops at
+    /// the first that is denied.
+    pub assume_role: Vec<EvaluationResult>,
+    /// The result of the request in the final session, if every role was assumed.
+    pub result: Option<EvaluationResult>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<'a> AssumedRole<'a> {
+    ///
+    /// A role, identified by its ARN, assumed with `session_name`; the role's trust policy
+    /// must allow the caller to assume it.
+    ///
+    pub fn new<S1, S2>(role_arn: S1, session_name: S2, trust_policy: &'a Policy) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        Self {
+            role_arn: role_arn.into(),
+            session_name: session_name.into(),
+            trust_policy,
+            permission_policies: Default::default(),
+            session_policy: None,
+        }
+    }
+
+    /// Add a permission policy attached to the role.
+    pub fn with_permission_policy(mut self, policy: &'a Policy) -> Self {
+        self.permission_policies.push(policy);
+        self
+    }
+
+    /// Set the session policy passed when the role is assumed.
+    pub fn with_session_policy(self, policy: &'a Policy) -> Self {
+        Self {
+            session_policy: Some(policy),
+            ..self
+        }
+    }
+
+    /// Return the ARN of the role.
+    pub fn role_arn(&self) -> &str {
+        &self.role_arn
+    }
+
+    ///
+    /// Return the principal of a session of this role, identified by its `assumed-role` ARN;
+    /// `None` if the role ARN is not valid.
+    ///
+    pub fn session_principal(&self) -> Option<Principal> {
+        let arn = ArnPattern::from_str(&self.role_arn).ok()?;
+        let role_name = arn.resource().rsplit('/').next()?;
+        Some(Principal::aws(format!(
+            "arn:{}:sts::{}:assumed-role/{}/{}",
+            arn.partition(),
+            arn.account_id(),
+            role_name,
+            self.session_name
+        )))
+    }
+
+    fn permits(&self, request: &Request) -> Result<EvaluationResult, EvaluationError> {
+        let result = evaluate_all(request, &self.permission_policies)?;
+        match (&result, self.session_policy) {
+            (EvaluationResult::Allow(_), Some(session_policy)) => evaluate(request, session_policy),
+            _ => Ok(result),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<'a> RoleChain<'a> {
+    ///
+    /// A chain starting with the AWS principal `identity`, with its identity policies.
+    ///
+    pub fn new(identity: Principal, identity_policies: &[&'a Policy]) -> Self {
+        Self {
+            identity,
+            identity_policies: identity_policies.to_vec(),
+            roles: Default::default(),
+        }
+    }
+
+    /// Assume `role` from the current end of the chain.
+    pub fn assume(mut self, role: AssumedRole<'a>) -> Self {
+        self.roles.push(role);
+        self
+    }
+
+    /// Return the roles assumed, in order.
+    pub fn roles(&self) -> &[AssumedRole<'a>] {
+        &self.roles
+    }
+
+    ///
+    /// Evaluate `request` in the session at the end of the chain; the principal of the request
+    /// is replaced with that of the session, or the identity if no role is assumed.
+    ///
+    pub fn evaluate(&self, request: &Request) -> Result<SessionEvaluation, EvaluationError> {
+        let mut assume_role: Vec<EvaluationResult> = Default::default();
+        let mut caller = self.identity.clone();
+        let mut caller_role: Option<&AssumedRole<'a>> = None;
+        for role in &self.roles {
+            let assume_request = Request {
+                request_id: request.request_id.clone(),
+                principal: Some(caller.clone()),
+                action: QualifiedName::new_unchecked(STS_ASSUME_ROLE),
+                resource: role.role_arn.clone(),
+                source_arn: None,
+                source_account: None,
+                called_via: request.called_via.clone(),
+                environment: request.environment.clone(),
+            };
+            let trusted = evaluate(&assume_request, role.trust_policy)?;
+            let result = match trusted {
+                EvaluationResult::Allow(_) => self.permits(caller_role, &assume_request)?,
+                denied => denied,
+            };
+            let allowed = matches!(result, EvaluationResult::Allow(_));
+            assume_role.push(result);
+            if !allowed {
+                return Ok(SessionEvaluation {
+                    assume_role,
+                    result: None,
+                });
+            }
+            caller = role
+                .session_principal()
+                .ok_or_else(|| EvaluationError::InvalidRoleArn(role.role_arn.clone()))?;
+            caller_role = Some(role);
+        }
+
+        let session_request = Request {
+            principal: Some(caller),
+            ..request.clone()
+        };
+        Ok(SessionEvaluation {
+            assume_role,
+            result: Some(self.permits(caller_role, &session_request)?),
+        })
+    }
+
+    fn permits(
+        &self,
+        role: Option<&AssumedRole<'a>>,
+        request: &Request,
+    ) -> Result<EvaluationResult, EvaluationError> {
+        match role {
+            Some(role) => role.permits(request),
+            None => evaluate_all(request, &self.identity_policies),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl SessionEvaluation {
+    /// Returns `true` if every role was assumed and the request was allowed in the session.
+    pub fn is_allowed(&self) -> bool {
+        matches!(self.result, Some(EvaluationResult::Allow(_)))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const STS_ASSUME_ROLE: &str = "sts:AssumeRole";
@@ -0,0 +1,215 @@
+/*!
+Provides reconciliation of the offline evaluator against the IAM policy simulator, enabled by
+the feature `aws_online`.
+
+The [`reconcile`](fn.reconcile.html) function evaluates each request against a local policy
+both offline, using [`evaluate`](../fn.evaluate.html), and online, using a
+[`PolicySimulator`](trait.PolicySimulator.html), and reports every request where the two
+disagree. Each divergence is either a gap in the offline evaluator or a misunderstanding of
+how IAM treats the policy, and so is a useful test case.
+
+This module does not depend on an AWS SDK, the simulator is an extension point to be
+implemented using the client already used by the application. The IAM operation to call is
+[`SimulateCustomPolicy`](https://docs.aws.amazon.com/IAM/latest/APIReference/API_SimulateCustomPolicy.html),
+which evaluates a policy document that is not attached to any identity.
+
+# Example
+
+An implementation using the `aws-sdk-iam` crate.
+
+```rust,ignore
+use aws_iam::io;
+use aws_iam::model::Policy;
+use aws_iam::offline::simulator::{Decision, PolicySimulator, SimulationError};
+use aws_iam::offline::Request;
+
+struct IamSimulator(aws_sdk_iam::Client);
+
+impl PolicySimulator for IamSimulator {
+    async fn simulate(
+        &self,
+        policy: &Policy,
+        request: &Request,
+    ) -> Result<Decision, SimulationError> {
+        let error = |e: &dyn std::error::Error| SimulationError::Simulator(e.to_string());
+        let response = self
+            .0
+            .simulate_custom_policy()
+            .policy_input_list(io::to_string(policy, false).map_err(|e| error(&e))?)
+            .action_names(request.action.to_string())
+            .resource_arns(&request.resource)
+            .send()
+            .await
+            .map_err(|e| error(&e))?;
+        response.evaluation_results()[0].eval_decision().as_str().parse()
+    }
+}
+```
+*/
+
+use crate::model::Policy;
+use crate::offline::{evaluate, EvaluationError, EvaluationResult, Request, Source};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The decision for a single request, as reported by the policy simulator.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Decision {
+    /// The request is allowed.
+    Allowed,
+    /// The request is denied by a statement with the `Deny` effect.
+    ExplicitDeny,
+    /// The request is denied as no statement allows it.
+    ImplicitDeny,
+}
+
+///
+/// Errors which may occur during reconciliation.
+///
+#[derive(Clone, Debug, PartialEq, Error)]
+#[non_exhaustive]
+pub enum SimulationError {
+    /// The offline evaluator reported an error.
+    #[error("offline evaluation failed")]
+    Evaluation(#[from] EvaluationError),
+    /// The simulator reported an error, or returned an unexpected response.
+    #[error("policy simulator failed: {0}")]
+    Simulator(String),
+}
+
+///
+/// Calls the IAM policy simulator, or any equivalent, to evaluate a request against a policy.
+///
+pub trait PolicySimulator {
+    /// Evaluate `request` against `policy`, returning the simulator's decision.
+    #[allow(async_fn_in_trait)]
+    async fn simulate(
+        &self,
+        policy: &Policy,
+        request: &Request,
+    ) -> Result<Decision, SimulationError>;
+}
+
+///
+/// A request for which the offline evaluator and the simulator disagree on whether the request
+/// is allowed.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Divergence {
+    /// The index of the request in the list passed to `reconcile`.
+    pub index: usize,
+    /// The identifier of the request, if it has one.
+    pub request_id: Option<String>,
+    /// The decision of the offline evaluator.
+    pub offline: Decision,
+    /// The decision of the simulator.
+    pub online: Decision,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Evaluate each of `requests` against `policy` offline and using `simulator`, returning the
+/// requests where one allows the request and the other does not. The two kinds of deny are not
+/// distinguished, see `Decision::from`.
+///
+pub async fn reconcile<S>(
+    simulator: &S,
+    policy: &Policy,
+    requests: &[Request],
+) -> Result<Vec<Divergence>, SimulationError>
+where
+    S: PolicySimulator,
+{
+    let mut divergences: Vec<Divergence> = Default::default();
+    for (index, request) in requests.iter().enumerate() {
+        let offline = Decision::from(evaluate(request, policy)?);
+        let online = simulator.simulate(policy, request).await?;
+        if offline.is_allowed() != online.is_allowed() {
+            divergences.push(Divergence {
+                index,
+                request_id: request.request_id.clone(),
+                offline,
+                online,
+            });
+        }
+    }
+    Ok(divergences)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Decision {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Allowed => DECISION_ALLOWED,
+                Self::ExplicitDeny => DECISION_EXPLICIT_DENY,
+                Self::ImplicitDeny => DECISION_IMPLICIT_DENY,
+            }
+        )
+    }
+}
+
+impl FromStr for Decision {
+    type Err = SimulationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            DECISION_ALLOWED => Ok(Self::Allowed),
+            DECISION_EXPLICIT_DENY => Ok(Self::ExplicitDeny),
+            DECISION_IMPLICIT_DENY => Ok(Self::ImplicitDeny),
+            _ => Err(SimulationError::Simulator(format!(
+                "unexpected decision {:?}",
+                s
+            ))),
+        }
+    }
+}
+
+///
+/// Only a deny by a `Deny` statement, one of the `Source::DeniedBy*` values, is reported as
+/// `ExplicitDeny`; an `Allow` statement that does not apply is an implicit deny.
+///
+impl From<EvaluationResult> for Decision {
+    fn from(result: EvaluationResult) -> Self {
+        match result {
+            EvaluationResult::Allow(_) => Self::Allowed,
+            EvaluationResult::Deny(Source::DeniedByAction, _, _)
+            | EvaluationResult::Deny(Source::DeniedByNotAction, _, _)
+            | EvaluationResult::Deny(Source::DeniedByResource, _, _)
+            | EvaluationResult::Deny(Source::DeniedByNotResource, _, _) => Self::ExplicitDeny,
+            EvaluationResult::Deny(_, _, _) => Self::ImplicitDeny,
+        }
+    }
+}
+
+impl Decision {
+    /// Returns `true` if the request was allowed.
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Self::Allowed)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const DECISION_ALLOWED: &str = "allowed";
+
+const DECISION_EXPLICIT_DENY: &str = "explicitDeny";
+
+const DECISION_IMPLICIT_DENY: &str = "implicitDeny";
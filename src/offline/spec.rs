@@ -0,0 +1,152 @@
+/*!
+Provides policy test specifications, files that pair requests with the results they are expected
+to receive when evaluated against a set of policies.
+
+A [`PolicyTestSpec`](struct.PolicyTestSpec.html) names the policy documents to evaluate, as
+paths relative to the specification file, and the test cases, each a
+[`TestCase`](../junit/struct.TestCase.html) with a request, the expected decision in `expect`,
+one of `allow` or `deny`, and optionally the expected `source` of a denial.
+[`run_spec`](fn.run_spec.html) reads a specification, and its policies, and returns a
+[`TestReport`](../junit/struct.TestReport.html) which may also be written as JUnit XML.
+
+Specifications are read as JSON or, with the feature `yaml`, as YAML if the file has the
+extension `.yaml` or `.yml`. As `PolicyTestSpec` implements `Deserialize` one may also be read
+from any other format supported by Serde, and run with
+[`PolicyTestSpec::run`](struct.PolicyTestSpec.html#method.run).
+
+# Example
+
+```json
+{
+  "name": "books",
+  "policies": ["books-policy.json"],
+  "tests": [
+    {
+      "name": "read books",
+      "request": {
+        "action": "dynamodb:GetItem",
+        "resource": "arn:aws:dynamodb:us-east-2:123456789012:table/Books",
+        "environment": {}
+      },
+      "expect": "allow"
+    },
+    {
+      "name": "write books",
+      "request": {
+        "action": "dynamodb:PutItem",
+        "resource": "arn:aws:dynamodb:us-east-2:123456789012:table/Books",
+        "environment": {}
+      },
+      "expect": "deny",
+      "source": "Action"
+    }
+  ]
+}
+```
+
+The same specification in YAML:
+
+```yaml
+name: books
+policies:
+  - books-policy.json
+tests:
+  - name: read books
+    request:
+      action: "dynamodb:GetItem"
+      resource: "arn:aws:dynamodb:us-east-2:123456789012:table/Books"
+      environment: {}
+    expect: allow
+  - name: write books
+    request:
+      action: "dynamodb:PutItem"
+      resource: "arn:aws:dynamodb:us-east-2:123456789012:table/Books"
+      environment: {}
+    expect: deny
+    source: Action
+```
+*/
+
+use crate::error::IamError;
+use crate::io;
+use crate::model::Policy;
+use crate::offline::junit::{run_tests, TestCase, TestReport};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A set of policies and the test cases to evaluate against them, see the module documentation.
+///
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PolicyTestSpec {
+    /// The name of the specification; if not present the name of the file is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The paths of the policy documents, relative to the specification file.
+    pub policies: Vec<PathBuf>,
+    /// The test cases to evaluate.
+    pub tests: Vec<TestCase>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Read the specification at `path`, and the policies it names, and evaluate each of its test
+/// cases returning the results as a report.
+///
+pub fn run_spec(path: &Path) -> Result<TestReport, IamError> {
+    let spec = read_spec(path)?;
+    let name = match &spec.name {
+        Some(name) => name.clone(),
+        None => path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    };
+    spec.run(&name, path.parent().unwrap_or_else(|| Path::new("")))
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl PolicyTestSpec {
+    ///
+    /// Read the policies named by this specification, relative to `dir`, and evaluate each of
+    /// its test cases returning the results as a report named `name`.
+    ///
+    pub fn run(&self, name: &str, dir: &Path) -> Result<TestReport, IamError> {
+        let policies: Vec<Policy> = self
+            .policies
+            .iter()
+            .map(|policy| io::read_from_file(&dir.join(policy)))
+            .collect::<Result<Vec<Policy>, IamError>>()?;
+        let policies: Vec<&Policy> = policies.iter().collect();
+        Ok(run_tests(name, &policies, &self.tests))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(feature = "yaml")]
+fn read_spec(path: &Path) -> Result<PolicyTestSpec, IamError> {
+    let file = File::open(path)?;
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("yaml") | Some("yml") => Ok(serde_yaml::from_reader(file)?),
+        _ => Ok(serde_json::from_reader(file)?),
+    }
+}
+
+#[cfg(not(feature = "yaml"))]
+fn read_spec(path: &Path) -> Result<PolicyTestSpec, IamError> {
+    Ok(serde_json::from_reader(File::open(path)?)?)
+}
@@ -1,430 +1,631 @@
+use crate::arn::WildcardPattern;
 use crate::model::{
-    Action, ConditionOperator, ConditionOperatorQuantifier, ConditionValue, OneOrAll, OneOrAny,
-    Principal, QString, Resource, Statement,
+    Action, Condition, ConditionValue, Effect, GlobalOperator, Operator, OrAny, Principal,
+    PrincipalMap, QualifiedName, Quantifier, Resource, Statement,
 };
-use crate::offline::request::{Environment, Principal as RequestPrincipal, Request};
+use crate::offline::compiled::{CompiledArnPattern, CompiledStatement, Patterns};
+use crate::offline::explain::{ConditionMatch, Contribution, ElementMatch, StatementMatchReport};
+use crate::offline::request::{Environment, Principal as RequestPrincipal, PrincipalType, Request};
 use crate::offline::{
-    operators, reduce_optional_results, EvaluationResult, PartialEvaluationResult,
+    operators, result_name, EvaluationResult, PartialEvaluationResult, TRACE_TARGET,
 };
 use crate::offline::{EvaluationError, Source};
-use std::collections::HashMap;
-use tracing::{debug, info, instrument};
+use indexmap::IndexMap;
+use tracing::{debug_span, trace};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A condition of a statement, its operator, key, and values.
+///
+pub type FailingCondition<'a> = (&'a Operator, &'a QualifiedName, &'a [ConditionValue]);
 
 // ------------------------------------------------------------------------------------------------
 // Public Functions
 // ------------------------------------------------------------------------------------------------
 
-#[instrument]
 pub fn evaluate_statement(
     request: &Request,
-    statement: &Statement,
-    _statement_index: i32,
+    request_action: &str,
+    environment: &Environment,
+    compiled: &CompiledStatement<'_>,
+    statement_index: i32,
 ) -> Result<PartialEvaluationResult, EvaluationError> {
+    let statement = compiled.statement;
+    let span = debug_span!(
+        target: TRACE_TARGET,
+        "statement",
+        statement = statement_index,
+        sid = statement.sid.as_deref().unwrap_or_default(),
+        effect = ?statement.effect,
+    );
+    let _enter = span.enter();
+    let result = eval_statement_elements(request, request_action, environment, compiled)?;
+    match statement.effect {
+        Effect::Allow => Ok(result),
+        // a deny statement only has an effect if every element selects the request.
+        Effect::Deny => match result {
+            Some(EvaluationResult::Allow(r)) => Ok(Some(EvaluationResult::Deny(
+                explicit_deny_source(statement),
+                explicit_deny_message(statement).to_string(),
+                r,
+            ))),
+            _ => Ok(None),
+        },
+    }
+}
+
+///
+/// Returns `true` if every element of the statement selects the request, regardless of the
+/// statement's effect.
+///
+pub fn statement_selects(
+    request: &Request,
+    request_action: &str,
+    environment: &Environment,
+    compiled: &CompiledStatement<'_>,
+) -> Result<bool, EvaluationError> {
+    Ok(matches!(
+        eval_statement_elements(request, request_action, environment, compiled)?,
+        Some(EvaluationResult::Allow(_))
+    ))
+}
+
+///
+/// Returns the result of every element of the statement for the request, see
+/// [`explain`](../fn.explain.html); unlike `evaluate_statement` every element and condition is
+/// evaluated.
+///
+pub fn explain_statement(
+    request: &Request,
+    request_action: &str,
+    environment: &Environment,
+    compiled: &CompiledStatement<'_>,
+) -> StatementMatchReport {
+    let statement = compiled.statement;
+    let principal = ElementMatch::from(&eval_statement_principal(
+        &request.principal,
+        &statement.principal,
+    ));
+    let action = ElementMatch::from(&eval_statement_action(
+        request_action,
+        &statement.action,
+        compiled.actions,
+    ));
+    let resource = ElementMatch::from(&eval_statement_resource(
+        &request.resource,
+        statement.resource.as_ref(),
+        compiled.resources,
+    ));
+    let conditions: Vec<ConditionMatch> = match &statement.condition {
+        None => Default::default(),
+        Some(conditions) => conditions
+            .iter()
+            .flat_map(|(operator, keys)| {
+                keys.iter().map(move |(key, values)| ConditionMatch {
+                    operator: operator.clone(),
+                    key: key.clone(),
+                    result: eval_statement_condition_key(
+                        request,
+                        environment,
+                        operator,
+                        key,
+                        values,
+                    )
+                    .map(|result| ElementMatch::from(&result)),
+                })
+            })
+            .collect(),
+    };
+    let selected = [principal, action, resource]
+        .iter()
+        .all(|m| *m != ElementMatch::NotMatched)
+        && conditions.iter().all(|c| {
+            matches!(
+                c.result,
+                Ok(ElementMatch::Matched) | Ok(ElementMatch::NotApplicable)
+            )
+        });
+    let contribution = match (selected, &statement.effect) {
+        (true, Effect::Allow) => Contribution::Allow,
+        (true, Effect::Deny) => Contribution::ExplicitDeny,
+        (false, _) => Contribution::NoEffect,
+    };
+    StatementMatchReport {
+        statement: compiled.index,
+        sid: sid_string(statement),
+        effect: statement.effect.clone(),
+        principal,
+        action,
+        resource,
+        conditions,
+        contribution,
+    }
+}
+
+///
+/// Returns the conditions of the statement that deny the request in `environment`, as the
+/// operator, key, and values of each; or `None` if the principal, action, or resource element
+/// does not select the request, as no change to the environment would then apply the
+/// statement.
+///
+pub fn failing_conditions<'a>(
+    request: &Request,
+    request_action: &str,
+    environment: &Environment,
+    compiled: &CompiledStatement<'a>,
+) -> Option<Vec<FailingCondition<'a>>> {
+    let statement = compiled.statement;
+    let selected = [
+        eval_statement_principal(&request.principal, &statement.principal),
+        eval_statement_action(request_action, &statement.action, compiled.actions),
+        eval_statement_resource(
+            &request.resource,
+            statement.resource.as_ref(),
+            compiled.resources,
+        ),
+    ];
+    if selected
+        .iter()
+        .any(|result| matches!(result, Some(EvaluationResult::Deny(_, _, _))))
+    {
+        return None;
+    }
+    let mut failing: Vec<FailingCondition<'a>> = Default::default();
+    if let Some(conditions) = &statement.condition {
+        for (operator, keys) in conditions.iter() {
+            for (key, values) in keys.iter() {
+                let result =
+                    eval_statement_condition_key(request, environment, operator, key, values);
+                if !matches!(result, Ok(Some(EvaluationResult::Allow(_))) | Ok(None)) {
+                    failing.push((operator, key, values));
+                }
+            }
+        }
+    }
+    Some(failing)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns `Allow` if every element of the statement selects the request, otherwise the
+/// `Deny` of the first element that does not; regardless of the statement's effect.
+///
+fn eval_statement_elements(
+    request: &Request,
+    request_action: &str,
+    environment: &Environment,
+    compiled: &CompiledStatement<'_>,
+) -> Result<PartialEvaluationResult, EvaluationError> {
+    let statement = compiled.statement;
     let mut effect: Option<EvaluationResult> = None;
 
     // >>>>> eval principal
     let result = eval_statement_principal(&request.principal, &statement.principal);
-    if let Some(EvaluationResult::Deny(_, _)) = result {
+    if let Some(EvaluationResult::Deny(_, _, _)) = result {
         return Ok(result);
-    } else if let Some(EvaluationResult::Allow) = result {
+    } else if let Some(EvaluationResult::Allow(_)) = result {
         effect = result;
     }
 
     // >>>>> eval action
-    let result = eval_statement_action(&request.action, &statement.action);
-    if let Some(EvaluationResult::Deny(_, _)) = result {
+    let result = eval_statement_action(request_action, &statement.action, compiled.actions);
+    if let Some(EvaluationResult::Deny(_, _, _)) = result {
         return Ok(result);
-    } else if let Some(EvaluationResult::Allow) = result {
+    } else if let Some(EvaluationResult::Allow(_)) = result {
         effect = result;
     }
 
     // >>>>> eval resource
-    let result = eval_statement_resource(&request.resource, &statement.resource);
-    if let Some(EvaluationResult::Deny(_, _)) = result {
+    let result = eval_statement_resource(
+        &request.resource,
+        statement.resource.as_ref(),
+        compiled.resources,
+    );
+    if let Some(EvaluationResult::Deny(_, _, _)) = result {
         return Ok(result);
-    } else if let Some(EvaluationResult::Allow) = result {
+    } else if let Some(EvaluationResult::Allow(_)) = result {
         effect = result;
     }
 
     // >>>>> eval conditions
-    match eval_statement_conditions(&request.environment, &statement.condition) {
+    match eval_statement_conditions(request, environment, &statement.condition) {
         Ok(None) => Ok(effect),
         result => result,
     }
 }
 
-// ------------------------------------------------------------------------------------------------
-// Private Functions
-// ------------------------------------------------------------------------------------------------
+///
+/// The element reported for an explicit deny; an exclusion is preferred as it is the less
+/// obvious reason, and the resource as it is usually the more specific. A statement without a
+/// resource element applies to the resource the policy is attached to, as `"Resource": "*"`.
+///
+fn explicit_deny_source(statement: &Statement) -> Source {
+    match (&statement.action, &statement.resource) {
+        (_, Some(Resource::NotResource(_))) => Source::DeniedByNotResource,
+        (Action::NotAction(_), _) => Source::DeniedByNotAction,
+        (_, None) | (_, Some(Resource::Resource(OrAny::Any))) => Source::DeniedByAction,
+        (_, Some(Resource::Resource(_))) => Source::DeniedByResource,
+    }
+}
+
+fn explicit_deny_message(statement: &Statement) -> &'static str {
+    match explicit_deny_source(statement) {
+        Source::DeniedByNotAction | Source::DeniedByNotResource => "not_excluded",
+        _ => "matched",
+    }
+}
 
-#[instrument]
 fn eval_statement_principal(
     request_principal: &Option<RequestPrincipal>,
     statement_principal: &Option<Principal>,
 ) -> PartialEvaluationResult {
-    let effect = if let Some(principal) = request_principal {
-        match statement_principal {
-            None => None,
-            Some(Principal::Principal(ps)) => {
-                if let Some(p) = ps.get(&principal.principal_type) {
-                    match p {
-                        OneOrAny::Any => Some(EvaluationResult::Allow),
-                        OneOrAny::One(v) => {
-                            if string_match(&principal.identifier, v) {
-                                Some(EvaluationResult::Allow)
-                            } else {
-                                Some(EvaluationResult::Deny(
-                                    Source::Principal,
-                                    "string_match".to_string(),
-                                ))
-                            }
-                        }
-                        OneOrAny::AnyOf(vs) => {
-                            if contains_match(&principal.identifier, vs) {
-                                Some(EvaluationResult::Allow)
-                            } else {
-                                Some(EvaluationResult::Deny(
-                                    Source::Principal,
-                                    "contains_match".to_string(),
-                                ))
-                            }
-                        }
-                    }
-                } else {
-                    None
-                }
+    let effect = match (request_principal, statement_principal) {
+        (Some(principal), Some(Principal::Principal(ps))) => {
+            match principal_matches(principal, ps) {
+                None => None,
+                Some(true) => Some(EvaluationResult::Allow(None)),
+                Some(false) => Some(EvaluationResult::Deny(
+                    Source::Principal,
+                    principal_message(ps, principal).to_string(),
+                    None,
+                )),
             }
-            Some(Principal::NotPrincipal(ps)) => {
-                if let Some(p) = ps.get(&principal.principal_type) {
-                    match p {
-                        OneOrAny::Any => Some(EvaluationResult::Deny(
-                            Source::NotPrincipal,
-                            "any".to_string(),
-                        )),
-                        OneOrAny::One(v) => {
-                            if string_match(&principal.identifier, v) {
-                                Some(EvaluationResult::Deny(
-                                    Source::NotPrincipal,
-                                    "string_match".to_string(),
-                                ))
-                            } else {
-                                Some(EvaluationResult::Allow)
-                            }
-                        }
-                        OneOrAny::AnyOf(vs) => {
-                            if contains_match(&principal.identifier, vs) {
-                                Some(EvaluationResult::Deny(
-                                    Source::NotPrincipal,
-                                    "contains_match".to_string(),
-                                ))
-                            } else {
-                                Some(EvaluationResult::Allow)
-                            }
-                        }
-                    }
-                } else {
-                    None
-                }
+        }
+        (Some(principal), Some(Principal::NotPrincipal(ps))) => {
+            match principal_matches(principal, ps) {
+                None => None,
+                Some(true) => Some(EvaluationResult::Deny(
+                    Source::NotPrincipal,
+                    principal_message(ps, principal).to_string(),
+                    None,
+                )),
+                Some(false) => Some(EvaluationResult::Allow(None)),
             }
         }
-    } else {
-        None
+        _ => None,
     };
-    info!(
-        "Matching principal {:?} returned {:?}",
-        request_principal, effect
-    );
+    trace_element("principal", &effect);
     effect
 }
 
-#[instrument]
+///
+/// Returns whether the principal element lists `principal`, or `None` if it lists no principals
+/// of the same type. An account, which is held as the ARN of its root user, lists every
+/// principal in the account.
+///
+fn principal_matches(principal: &RequestPrincipal, ps: &OrAny<PrincipalMap>) -> Option<bool> {
+    let ps = match ps {
+        OrAny::Any => return Some(true),
+        OrAny::Some(ps) => ps,
+    };
+    let identifiers: Vec<String> = match principal.principal_type {
+        PrincipalType::AWS => ps.aws_iter().map(ToString::to_string).collect(),
+        PrincipalType::Federated => ps.federated_iter().map(ToString::to_string).collect(),
+        PrincipalType::Service => ps.service_iter().map(ToString::to_string).collect(),
+        PrincipalType::CanonicalUser => ps.canonical_user_iter().map(ToString::to_string).collect(),
+    };
+    if identifiers.is_empty() {
+        None
+    } else {
+        let account_root = principal
+            .account()
+            .map(|account| format!(":iam::{}:root", account));
+        Some(identifiers.iter().any(|identifier| {
+            string_match(&principal.identifier, identifier)
+                || matches!(&account_root, Some(root) if identifier.ends_with(root.as_str()))
+        }))
+    }
+}
+
+fn principal_message(ps: &OrAny<PrincipalMap>, principal: &RequestPrincipal) -> &'static str {
+    let count = match ps {
+        OrAny::Any => return "any",
+        OrAny::Some(ps) => match principal.principal_type {
+            PrincipalType::AWS => ps.aws_iter().count(),
+            PrincipalType::Federated => ps.federated_iter().count(),
+            PrincipalType::Service => ps.service_iter().count(),
+            PrincipalType::CanonicalUser => ps.canonical_user_iter().count(),
+        },
+    };
+    if count == 1 {
+        "string_match"
+    } else {
+        "contains_match"
+    }
+}
+
 fn eval_statement_action(
-    request_action: &QString,
+    request_action: &str,
     statement_action: &Action,
+    patterns: &Patterns<WildcardPattern>,
 ) -> PartialEvaluationResult {
-    let effect = match statement_action {
-        Action::Action(a) => match a {
-            OneOrAny::Any => Some(EvaluationResult::Allow),
-            OneOrAny::One(v) => {
-                if string_match(&request_action.to_string(), &v.to_string()) {
-                    Some(EvaluationResult::Allow)
-                } else {
-                    debug!(
-                        target = "eval",
-                        "action: {} ≈ {} → false", request_action, v
-                    );
-                    Some(EvaluationResult::Deny(
-                        Source::Action,
-                        "string_match".to_string(),
-                    ))
-                }
-            }
-            OneOrAny::AnyOf(vs) => {
-                if contains_qmatch(&request_action.to_string(), vs) {
-                    Some(EvaluationResult::Allow)
-                } else {
-                    debug!(
-                        target = "eval",
-                        "action: {:?} ≈ {} → false", vs, request_action
-                    );
-                    Some(EvaluationResult::Deny(
-                        Source::Action,
-                        "contains_match".to_string(),
-                    ))
-                }
-            }
-        },
-        Action::NotAction(a) => match a {
-            OneOrAny::Any => Some(EvaluationResult::Deny(Source::NotAction, "any".to_string())),
-            OneOrAny::One(v) => {
-                if string_match(&request_action.to_string(), &v.to_string()) {
-                    debug!(
-                        target = "eval",
-                        "action: {} ≉ {} → false", request_action, v
-                    );
-                    Some(EvaluationResult::Deny(
-                        Source::NotAction,
-                        "string_match".to_string(),
-                    ))
-                } else {
-                    Some(EvaluationResult::Allow)
-                }
-            }
-            OneOrAny::AnyOf(vs) => {
-                if contains_qmatch(&request_action.to_string(), vs) {
-                    debug!(
-                        target = "eval",
-                        "action: {:?} ≉ {} → false", vs, request_action
-                    );
-                    Some(EvaluationResult::Deny(
-                        Source::NotAction,
-                        "contains_match".to_string(),
-                    ))
-                } else {
-                    Some(EvaluationResult::Allow)
-                }
+    let negated = matches!(statement_action, Action::NotAction(_));
+    let effect = match patterns {
+        Patterns::Any if negated => Some(EvaluationResult::Deny(
+            Source::NotAction,
+            "any".to_string(),
+            None,
+        )),
+        Patterns::Any => Some(EvaluationResult::Allow(None)),
+        Patterns::Some(vs) => {
+            let matched = vs.iter().any(|v| v.matches(request_action));
+            if matched == negated {
+                Some(EvaluationResult::Deny(
+                    if negated {
+                        Source::NotAction
+                    } else {
+                        Source::Action
+                    },
+                    match_message(vs, negated).to_string(),
+                    None,
+                ))
+            } else {
+                Some(EvaluationResult::Allow(None))
             }
-        },
+        }
     };
-    info!("Matching action {:?} returned {:?}", request_action, effect);
+    trace_element("action", &effect);
     effect
 }
 
-#[instrument]
 fn eval_statement_resource(
     request_resource: &str,
-    statement_resource: &Resource,
+    statement_resource: Option<&Resource>,
+    patterns: &Patterns<CompiledArnPattern>,
 ) -> PartialEvaluationResult {
-    let effect = match statement_resource {
-        Resource::Resource(a) => match a {
-            OneOrAny::Any => Some(EvaluationResult::Allow),
-            OneOrAny::One(v) => {
-                if resource_match(request_resource, v) {
-                    Some(EvaluationResult::Allow)
-                } else {
-                    debug!(
-                        target = "eval",
-                        "resource: {} ≈ {} → false", request_resource, v
-                    );
-                    Some(EvaluationResult::Deny(
-                        Source::Resource,
-                        "string_match".to_string(),
-                    ))
-                }
-            }
-            OneOrAny::AnyOf(vs) => {
-                if contains_resource(request_resource, vs) {
-                    Some(EvaluationResult::Allow)
-                } else {
-                    debug!(
-                        target = "eval",
-                        "resource: {:?} ≈ {} → false", vs, request_resource
-                    );
-                    Some(EvaluationResult::Deny(
-                        Source::Action,
-                        "contains_match".to_string(),
-                    ))
-                }
-            }
-        },
-        Resource::NotResource(a) => match a {
-            OneOrAny::Any => Some(EvaluationResult::Deny(
-                Source::NotResource,
-                "any".to_string(),
-            )),
-            OneOrAny::One(v) => {
-                if resource_match(request_resource, v) {
-                    debug!(
-                        target = "eval",
-                        "resource: {} ≉ {} → false", request_resource, v
-                    );
-                    Some(EvaluationResult::Deny(
-                        Source::NotResource,
-                        "string_match".to_string(),
-                    ))
-                } else {
-                    Some(EvaluationResult::Allow)
-                }
-            }
-            OneOrAny::AnyOf(vs) => {
-                if contains_resource(request_resource, vs) {
-                    debug!(
-                        target = "eval",
-                        "resource: {:?} ≉ {} → false", vs, request_resource
-                    );
-                    Some(EvaluationResult::Deny(
-                        Source::NotAction,
-                        "contains_match".to_string(),
-                    ))
-                } else {
-                    Some(EvaluationResult::Allow)
-                }
+    let negated = matches!(statement_resource, Some(Resource::NotResource(_)));
+    let effect = match patterns {
+        Patterns::Any if negated => Some(EvaluationResult::Deny(
+            Source::NotResource,
+            "any".to_string(),
+            None,
+        )),
+        Patterns::Any => Some(EvaluationResult::Allow(None)),
+        Patterns::Some(vs) => {
+            let matched = vs.iter().any(|v| v.matches(request_resource));
+            if matched == negated {
+                Some(EvaluationResult::Deny(
+                    if negated {
+                        Source::NotResource
+                    } else {
+                        Source::Resource
+                    },
+                    match_message(vs, negated).to_string(),
+                    None,
+                ))
+            } else {
+                Some(EvaluationResult::Allow(None))
             }
-        },
+        }
     };
-    info!(
-        "Matching resource {:?} returned {:?}",
-        request_resource, effect
-    );
+    trace_element("resource", &effect);
     effect
 }
 
-//#[instrument]
 fn eval_statement_conditions(
+    request: &Request,
     request_environment: &Environment,
-    statement_conditions: &Option<
-        HashMap<ConditionOperator, HashMap<QString, OneOrAll<ConditionValue>>>,
-    >,
+    statement_conditions: &Option<Condition>,
 ) -> Result<PartialEvaluationResult, EvaluationError> {
-    let result = if let Some(conditions) = statement_conditions {
+    if let Some(conditions) = statement_conditions {
         let results = conditions
             .iter()
-            .flat_map(|(op, vs)| eval_statement_condition_op(request_environment, op, vs))
+            .flat_map(|(op, vs)| eval_statement_condition_op(request, request_environment, op, vs))
             .collect();
         match results {
-            Ok(mut results) => Ok(reduce_optional_results(&mut results)),
+            Ok(results) => Ok(reduce_condition_results(results)),
             Err(err) => Err(err),
         }
     } else {
         Ok(None)
-    };
-    info!("Matching statement conditions returned {:?}", result);
-    result
+    }
+}
+
+///
+/// Every condition in a statement must select the request, so the first that does not
+/// decides the result.
+///
+fn reduce_condition_results(results: Vec<PartialEvaluationResult>) -> PartialEvaluationResult {
+    results
+        .into_iter()
+        .fold(None, |acc, result| match (&acc, result) {
+            (Some(EvaluationResult::Deny(_, _, _)), _) => acc,
+            (_, None) => acc,
+            (_, result) => result,
+        })
 }
 
 fn eval_statement_condition_op(
+    request: &Request,
     request_environment: &Environment,
-    condition_operator: &ConditionOperator,
-    condition_values: &HashMap<QString, OneOrAll<ConditionValue>>,
+    condition_operator: &Operator,
+    condition_values: &IndexMap<QualifiedName, Vec<ConditionValue>>,
 ) -> Vec<Result<PartialEvaluationResult, EvaluationError>> {
-    info!("Statement condition, operator {:?}", condition_operator);
-    let results: Vec<Result<Option<EvaluationResult>, EvaluationError>> = condition_values
+    condition_values
         .iter()
         .map(|(key, values)| {
-            eval_statement_condition_key(request_environment, condition_operator, key, values)
+            let result = eval_statement_condition_key(
+                request,
+                request_environment,
+                condition_operator,
+                key,
+                values,
+            );
+            if let Ok(effect) = &result {
+                trace!(
+                    target: TRACE_TARGET,
+                    element = "condition",
+                    operator = %condition_operator,
+                    key = %key,
+                    result = result_name(effect),
+                );
+            }
+            result
         })
-        .collect();
-    info!("Matching statement conditions returned {:?}", results);
-    results
+        .collect()
 }
 
+///
+/// A key missing from the request context does not select the request, other than for an
+/// `...IfExists` operator or with `ForAllValues`, where it does, and the `Null` operator, where
+/// it does if the value of the condition is `true`. Where a condition lists more than one value
+/// the request value must match any of them, or for a negated operator such as
+/// `StringNotEquals` none of them.
+///
 fn eval_statement_condition_key(
+    request: &Request,
     request_environment: &Environment,
-    condition_operator: &ConditionOperator,
-    condition_key: &QString,
-    condition_values: &OneOrAll<ConditionValue>,
+    condition_operator: &Operator,
+    condition_key: &QualifiedName,
+    condition_values: &[ConditionValue],
 ) -> Result<PartialEvaluationResult, EvaluationError> {
+    if condition_values.is_empty() {
+        return Err(EvaluationError::InvalidValueCardinality);
+    }
+    if let Some(lhs) = request.context_values(condition_key) {
+        return eval_multi_valued_condition_key(
+            request_environment,
+            condition_operator,
+            condition_key,
+            &lhs,
+            condition_values,
+        );
+    }
     match request_environment.get(condition_key) {
         None => {
-            if condition_operator.if_exists {
-                Ok(Some(EvaluationResult::Allow))
+            if condition_operator.operator == GlobalOperator::Null {
+                operators::evaluate_any(
+                    request_environment,
+                    &GlobalOperator::Bool,
+                    &ConditionValue::boolean(true),
+                    condition_values,
+                )
+                .map(|r| bool_effect(r, condition_operator, condition_key, "null"))
             } else {
-                Ok(None)
+                Ok(bool_effect(
+                    condition_operator.if_exists
+                        || condition_operator.quantifier == Some(Quantifier::ForAllValues),
+                    condition_operator,
+                    condition_key,
+                    "missing",
+                ))
             }
         }
-        Some(lhs) => match (&condition_operator.quantifier, condition_values) {
-            (None, OneOrAll::One(rhs)) => {
-                operators::evaluate(request_environment, &condition_operator.operator, lhs, rhs)
-                    .map(|r| bool_effect(r, condition_operator, condition_key, "one"))
-            }
-            (Some(ConditionOperatorQuantifier::ForAllValues), OneOrAll::All(rhs)) => {
-                operators::evaluate_all(request_environment, &condition_operator.operator, lhs, rhs)
-                    .map(|r| bool_effect(r, condition_operator, condition_key, "for_all"))
-            }
-            (Some(ConditionOperatorQuantifier::ForAnyValue), OneOrAll::All(rhs)) => {
-                operators::evaluate_any(request_environment, &condition_operator.operator, lhs, rhs)
-                    .map(|r| bool_effect(r, condition_operator, condition_key, "for_any"))
+        Some(lhs) => {
+            let operator = &condition_operator.operator;
+            match &condition_operator.quantifier {
+                None if operators::is_negated(operator) => {
+                    operators::evaluate_all(request_environment, operator, lhs, condition_values)
+                        .map(|r| bool_effect(r, condition_operator, condition_key, "one"))
+                }
+                None => {
+                    operators::evaluate_any(request_environment, operator, lhs, condition_values)
+                        .map(|r| bool_effect(r, condition_operator, condition_key, "one"))
+                }
+                Some(_) => eval_multi_valued_condition_key(
+                    request_environment,
+                    condition_operator,
+                    condition_key,
+                    std::slice::from_ref(lhs),
+                    condition_values,
+                ),
             }
-            _ => Err(EvaluationError::InvalidValueCardinality),
-        },
+        }
     }
 }
 
-#[inline]
-fn string_match(lhs: &str, rhs: &str) -> bool {
-    if rhs.ends_with('*') {
-        lhs.starts_with(&rhs[0..rhs.len() - 1])
-    } else {
-        lhs == rhs
+///
+/// A multi-valued key, such as `aws:CalledVia`, is evaluated as a set; with `ForAnyValue` at
+/// least one value of the request must match a value in the condition, with `ForAllValues`
+/// every value of the request must. Without a set operator the key is treated as though
+/// `ForAnyValue` had been used. A single-valued key used with a set operator is a set of one.
+///
+fn eval_multi_valued_condition_key(
+    request_environment: &Environment,
+    condition_operator: &Operator,
+    condition_key: &QualifiedName,
+    lhs: &[ConditionValue],
+    rhs: &[ConditionValue],
+) -> Result<PartialEvaluationResult, EvaluationError> {
+    let operator = &condition_operator.operator;
+    let matches_any = |lhs: &ConditionValue| {
+        operators::evaluate_any(request_environment, operator, lhs, rhs).unwrap_or(false)
+    };
+    match &condition_operator.quantifier {
+        Some(Quantifier::ForAllValues) => Ok(bool_effect(
+            lhs.iter().all(matches_any),
+            condition_operator,
+            condition_key,
+            "for_all",
+        )),
+        _ => Ok(bool_effect(
+            lhs.iter().any(matches_any),
+            condition_operator,
+            condition_key,
+            "for_any",
+        )),
     }
 }
 
 #[inline]
-fn contains_match(lhs: &str, rhs: &[String]) -> bool {
-    rhs.iter().any(|r| string_match(lhs, r))
-}
-
-#[inline]
-fn contains_qmatch(lhs: &str, rhs: &[QString]) -> bool {
-    rhs.iter().any(|r| string_match(lhs, &r.to_string()))
+fn trace_element(element: &'static str, effect: &PartialEvaluationResult) {
+    trace!(
+        target: TRACE_TARGET,
+        element = element,
+        result = result_name(effect)
+    );
 }
 
 #[inline]
-fn resource_match(lhs: &str, rhs: &str) -> bool {
-    let lhs = resource_split(lhs);
-    let rhs = resource_split(rhs);
-    lhs.iter()
-        .enumerate()
-        .map(|(i, lhs)| string_match(lhs, rhs.get(i).unwrap()))
-        .all(|v| v)
-}
-
-fn resource_split(lhs: &str) -> Vec<String> {
-    let splits: Vec<String> = lhs.split(':').map(|s| s.to_string()).collect();
-    if splits.len() < 6 {
-        Vec::new()
-    } else if splits.len() == 6 {
-        if splits.get(0).unwrap() == "arn" {
-            splits[1..].to_vec()
-        } else {
-            Vec::new()
-        }
-    } else if splits.get(0).unwrap() == "arn" {
-        let mut splits = splits[1..5].to_vec();
-        splits.push(splits[6..].join(":"));
-        splits
+fn string_match(lhs: &str, rhs: &str) -> bool {
+    if rhs.ends_with('*') {
+        lhs.starts_with(&rhs[0..rhs.len() - 1])
     } else {
-        Vec::new()
+        lhs == rhs
     }
 }
 
+///
+/// The message reported when an element does not select the request, retaining the names used
+/// before elements were compiled.
+///
 #[inline]
-fn contains_resource(lhs: &str, rhs: &[String]) -> bool {
-    rhs.iter().any(|r| resource_match(lhs, r))
+fn match_message<T>(patterns: &[T], negated: bool) -> &'static str {
+    if negated {
+        "excluded"
+    } else if patterns.len() == 1 {
+        "string_match"
+    } else {
+        "contains_match"
+    }
 }
 
 fn bool_effect(
     result: bool,
-    condition_operator: &ConditionOperator,
-    condition_key: &QString,
+    condition_operator: &Operator,
+    condition_key: &QualifiedName,
     message: &str,
 ) -> Option<EvaluationResult> {
     if result {
-        Some(EvaluationResult::Allow)
+        Some(EvaluationResult::Allow(None))
     } else {
         Some(EvaluationResult::Deny(
             Source::Condition(condition_operator.clone(), condition_key.clone()),
             String::from(message),
+            None,
         ))
     }
 }
+
+///
+/// The `Sid` of the statement, as recorded in a `StatementRef`.
+///
+pub(crate) fn sid_string(statement: &Statement) -> Option<String> {
+    statement.sid.as_ref().map(ToString::to_string)
+}
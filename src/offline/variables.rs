@@ -1,4 +1,4 @@
-use crate::model::{ConditionValue, QString};
+use crate::model::QualifiedName;
 use crate::offline::request::Environment;
 use crate::offline::EvaluationError;
 use regex::Regex;
@@ -9,8 +9,8 @@ use std::str::FromStr;
 // ------------------------------------------------------------------------------------------------
 
 ///
-/// Expand an input string based on any embedded variables of the form `${QString}`. Values
-/// are determined from the environment properties of the request object.
+/// Expand an input string based on any embedded variables of the form `${key}`. Values are
+/// determined from the environment properties of the request object, in their string form.
 ///
 pub fn expand_string(
     environment: &Environment,
@@ -29,12 +29,11 @@ pub fn expand_string(
         }
         let key: &str = variable.as_str();
         let key = &key[2..key.len() - 1];
-        let key = QString::from_str(key)
+        let key = QualifiedName::from_str(key)
             .map_err(|_| EvaluationError::InvalidVariableName(key.to_string()))?;
         match environment.get(&key) {
-            Some(ConditionValue::String(v)) => output.push_str(v),
+            Some(v) => output.push_str(v),
             None => return Err(EvaluationError::UnknownVariableName(key.to_string())),
-            _ => return Err(EvaluationError::ExpectingVariableType("String".to_string())),
         };
         from_idx = variable.end();
     }
@@ -50,32 +49,30 @@ pub fn expand_string(
 
 #[cfg(test)]
 mod tests {
-    use crate::constants;
-    use crate::model::{ConditionValue, QString};
+    use crate::context::keys::{AWS_EPOCH_TIME, AWS_REQUESTED_REGION, AWS_SECURE_TRANSPORT};
+    use crate::model::{ConditionValue, QualifiedName};
     use crate::offline::request::Environment;
     use crate::offline::variables::expand_string;
     use crate::offline::EvaluationError;
     use std::str::FromStr;
 
     fn make_environment() -> Environment {
-        let environment: Environment = [
+        vec![
             (
-                QString::from_str(constants::AWS_EPOCH_TIME).unwrap(),
-                ConditionValue::Integer(1000),
+                QualifiedName::from_str(AWS_EPOCH_TIME).unwrap(),
+                ConditionValue::number(1000),
             ),
             (
-                QString::from_str(constants::AWS_REQUESTED_REGION).unwrap(),
-                ConditionValue::String("us-east-1".to_string()),
+                QualifiedName::from_str(AWS_REQUESTED_REGION).unwrap(),
+                ConditionValue::from("us-east-1"),
             ),
             (
-                QString::from_str(constants::AWS_SECURE_TRANSPORT).unwrap(),
-                ConditionValue::Bool(true),
+                QualifiedName::from_str(AWS_SECURE_TRANSPORT).unwrap(),
+                ConditionValue::boolean(true),
             ),
         ]
-        .iter()
-        .cloned()
-        .collect();
-        environment
+        .into_iter()
+        .collect()
     }
 
     #[test]
@@ -128,11 +125,11 @@ mod tests {
     }
 
     #[test]
-    fn test_bad_variable_type() {
-        let test_str = "${aws:SecureTransport}";
+    fn test_variable_not_string() {
+        let test_str = "${aws:SecureTransport}/${aws:EpochTime}";
         assert_eq!(
-            expand_string(&make_environment(), test_str),
-            Err(EvaluationError::ExpectingVariableType("String".to_string()))
+            expand_string(&make_environment(), test_str).unwrap(),
+            "true/1000"
         );
     }
 }
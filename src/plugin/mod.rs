@@ -0,0 +1,341 @@
+/*!
+Provides the host side of rule packs, third-party lint rules and policy transforms compiled to
+WebAssembly, for teams that cannot add Rust dependencies to their tools.
+
+A rule pack is a WebAssembly component implementing the `rule-pack` world described in
+`wit/rule-pack.wit`; policies are passed to it, and returned from it, as JSON documents. A
+tool loads each module with the runtime of its choice and adapts the module's exports to the
+[`PluginRuntime`](trait.PluginRuntime.html) trait; a [`PluginPack`](struct.PluginPack.html)
+then registers the pack's rules with a [`LintRegistry`](../lint/struct.LintRegistry.html),
+alongside the built-in rules, and applies its transforms.
+
+[`plugin_files`](fn.plugin_files.html) lists the rule packs in a plugin directory, the files
+with the extension `.wasm`, in the order they should be loaded.
+
+With the `plugins_wasmtime` feature, tools that do not bring their own runtime can use the
+embedded wasmtime runtime instead; [`load_plugins`](fn.load_plugins.html) loads every rule
+pack in a plugin directory, and [`WasmRuntime`](struct.WasmRuntime.html) adapts a single
+component.
+
+# Example
+
+```rust
+use aws_iam::error::PluginError;
+use aws_iam::lint::LintRegistry;
+use aws_iam::model::Policy;
+use aws_iam::plugin::{PluginFinding, PluginPack, PluginRuleInfo, PluginRuntime};
+use aws_iam::validate::Severity;
+use std::str::FromStr;
+
+// A stand-in for a loaded WebAssembly module.
+struct NoWildcards;
+
+impl PluginRuntime for NoWildcards {
+    fn list_rules(&self) -> Result<Vec<PluginRuleInfo>, PluginError> {
+        Ok(vec![PluginRuleInfo::new("ExampleNoWildcards", Severity::Warning)])
+    }
+
+    fn check(&self, _rule: &str, policy: &str) -> Result<Vec<PluginFinding>, PluginError> {
+        Ok(if policy.contains("\"*\"") {
+            vec![PluginFinding::new(Severity::Warning, "ExampleWildcard", "wildcard", None)]
+        } else {
+            vec![]
+        })
+    }
+}
+
+let mut registry = LintRegistry::default();
+assert_eq!(PluginPack::new(NoWildcards).register(&mut registry).unwrap(), 1);
+
+let policy = Policy::from_str(
+    r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject","Resource":"*"}]}"#,
+)
+.unwrap();
+assert!(registry
+    .check(&policy)
+    .iter()
+    .any(|finding| finding.code == "ExampleWildcard"));
+```
+*/
+
+use crate::error::PluginError;
+use crate::lint::{LintRegistry, LintRule};
+use crate::model::Policy;
+use crate::validate::{Finding, Findings, Severity};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The exports of a loaded rule pack, as described by the `rules` interface in
+/// `wit/rule-pack.wit`; implemented by an adapter for the WebAssembly runtime in use.
+///
+pub trait PluginRuntime {
+    /// The rules provided by the pack.
+    fn list_rules(&self) -> Result<Vec<PluginRuleInfo>, PluginError>;
+
+    /// Run the rule named `rule` over the policy document `policy`.
+    fn check(&self, rule: &str, policy: &str) -> Result<Vec<PluginFinding>, PluginError>;
+
+    /// The transforms provided by the pack; by default, none.
+    fn list_transforms(&self) -> Result<Vec<String>, PluginError> {
+        Ok(Default::default())
+    }
+
+    /// Apply the transform named `transform` to the policy document `policy`.
+    fn transform(&self, transform: &str, _policy: &str) -> Result<String, PluginError> {
+        Err(PluginError::UnknownTransform(transform.to_string()))
+    }
+}
+
+///
+/// Describes a rule provided by a rule pack.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginRuleInfo {
+    /// The name of the rule, unique within the registry it is loaded into.
+    pub name: String,
+    /// The most severe finding the rule reports.
+    pub severity: Severity,
+}
+
+///
+/// A finding reported by a rule in a rule pack.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginFinding {
+    /// How serious the problem is.
+    pub severity: Severity,
+    /// A stable identifier for the kind of problem.
+    pub code: String,
+    /// A human-readable description of the problem.
+    pub message: String,
+    /// The index of the statement within the policy, if the problem is specific to one.
+    pub statement: Option<usize>,
+}
+
+///
+/// A loaded rule pack.
+///
+#[derive(Clone)]
+pub struct PluginPack {
+    runtime: Rc<dyn PluginRuntime>,
+}
+
+///
+/// A lint rule provided by a rule pack.
+///
+#[derive(Clone)]
+pub struct PluginRule {
+    runtime: Rc<dyn PluginRuntime>,
+    name: &'static str,
+    severity: Severity,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+/// The extension of rule pack files in a plugin directory.
+pub const PLUGIN_FILE_EXTENSION: &str = "wasm";
+
+/// A rule in a rule pack failed, so the policy was not checked by that rule.
+pub const FINDING_PLUGIN_FAILED: &str = "PluginFailed";
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Return the rule pack files in the plugin directory `dir`, sorted by name so that packs are
+/// always loaded, and their rules registered, in the same order.
+///
+pub fn plugin_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = Default::default();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file()
+            && path.extension().and_then(|ext| ext.to_str()) == Some(PLUGIN_FILE_EXTENSION)
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl PluginRuleInfo {
+    pub fn new<S>(name: S, severity: Severity) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            name: name.into(),
+            severity,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl From<PluginFinding> for Finding {
+    fn from(finding: PluginFinding) -> Self {
+        Finding {
+            severity: finding.severity,
+            code: intern(&finding.code),
+            message: finding.message,
+            statement: finding.statement,
+        }
+    }
+}
+
+impl PluginFinding {
+    pub fn new<S1, S2>(severity: Severity, code: S1, message: S2, statement: Option<usize>) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        Self {
+            severity,
+            code: code.into(),
+            message: message.into(),
+            statement,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl std::fmt::Debug for PluginPack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PluginPack").finish()
+    }
+}
+
+impl PluginPack {
+    /// Create a pack from the adapter for a loaded module.
+    pub fn new<R>(runtime: R) -> Self
+    where
+        R: PluginRuntime + 'static,
+    {
+        Self {
+            runtime: Rc::new(runtime),
+        }
+    }
+
+    /// The rules provided by the pack.
+    pub fn rules(&self) -> Result<Vec<PluginRule>, PluginError> {
+        Ok(self
+            .runtime
+            .list_rules()?
+            .into_iter()
+            .map(|info| PluginRule {
+                runtime: self.runtime.clone(),
+                name: intern(&info.name),
+                severity: info.severity,
+            })
+            .collect())
+    }
+
+    ///
+    /// Register each rule of the pack with `registry`, returning the number of rules; as with
+    /// `LintRegistry::register` a rule replaces any already registered with the same name.
+    ///
+    pub fn register(&self, registry: &mut LintRegistry) -> Result<usize, PluginError> {
+        let rules = self.rules()?;
+        let count = rules.len();
+        for rule in rules {
+            registry.register(rule);
+        }
+        Ok(count)
+    }
+
+    /// The names of the transforms provided by the pack.
+    pub fn transforms(&self) -> Result<Vec<String>, PluginError> {
+        self.runtime.list_transforms()
+    }
+
+    /// Apply the transform named `transform` to `policy`, returning the transformed policy.
+    pub fn transform(&self, transform: &str, policy: &Policy) -> Result<Policy, PluginError> {
+        let result = self.runtime.transform(transform, &policy.to_string())?;
+        Policy::from_str(&result).map_err(|e| PluginError::InvalidPolicy(transform.to_string(), e))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl std::fmt::Debug for PluginRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PluginRule")
+            .field("name", &self.name)
+            .field("severity", &self.severity)
+            .finish()
+    }
+}
+
+impl LintRule for PluginRule {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn check(&self, policy: &Policy, findings: &mut Findings) {
+        match self.runtime.check(self.name, &policy.to_string()) {
+            Ok(results) => {
+                for finding in results {
+                    findings.push(finding.into());
+                }
+            }
+            Err(e) => findings.push(Finding::error(
+                FINDING_PLUGIN_FAILED,
+                format!("the rule {:?} failed: {}", self.name, e),
+                None,
+            )),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// `Finding` codes and `LintRule` names are static strings; those of rule packs are leaked
+/// once each, so their number is bounded by the distinct names in the packs loaded.
+///
+fn intern(s: &str) -> &'static str {
+    lazy_static! {
+        static ref INTERNED: Mutex<HashSet<&'static str>> = Default::default();
+    }
+    let mut interned = INTERNED.lock().unwrap();
+    match interned.get(s) {
+        Some(s) => s,
+        None => {
+            let s: &'static str = Box::leak(s.to_string().into_boxed_str());
+            let _ = interned.insert(s);
+            s
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Modules
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(feature = "plugins_wasmtime")]
+mod wasmtime;
+#[cfg(feature = "plugins_wasmtime")]
+pub use self::wasmtime::{load_plugin, load_plugins, WasmRuntime};
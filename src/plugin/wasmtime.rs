@@ -0,0 +1,173 @@
+/*!
+Loads rule packs compiled as WebAssembly components with an embedded wasmtime runtime. A rule
+pack is given no imports, so it can only compute over the policies passed to it.
+*/
+
+use crate::error::PluginError;
+use crate::plugin::{plugin_files, PluginFinding, PluginPack, PluginRuleInfo, PluginRuntime};
+use crate::validate::Severity;
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::path::Path;
+use wasmtime::component::{Component, Linker};
+use wasmtime::{Engine, Store};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The adapter for a rule pack loaded by the embedded wasmtime runtime.
+///
+pub struct WasmRuntime {
+    store: RefCell<Store<()>>,
+    pack: bindings::RulePack,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Load the rule pack in the WebAssembly component file `path`.
+///
+pub fn load_plugin(path: &Path) -> Result<PluginPack, PluginError> {
+    Ok(PluginPack::new(WasmRuntime::from_file(path)?))
+}
+
+///
+/// Load each rule pack in the plugin directory `dir`, in the order given by
+/// [`plugin_files`](fn.plugin_files.html).
+///
+pub fn load_plugins(dir: &Path) -> Result<Vec<PluginPack>, PluginError> {
+    plugin_files(dir)
+        .map_err(|e| load_error(dir, e))?
+        .iter()
+        .map(|path| load_plugin(path))
+        .collect()
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl std::fmt::Debug for WasmRuntime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmRuntime").finish()
+    }
+}
+
+impl WasmRuntime {
+    /// Compile and instantiate the WebAssembly component file `path`.
+    pub fn from_file(path: &Path) -> Result<Self, PluginError> {
+        let component = Component::from_file(&ENGINE, path).map_err(|e| load_error(path, e))?;
+        let mut store = Store::new(&ENGINE, ());
+        let pack = bindings::RulePack::instantiate(&mut store, &component, &Linker::new(&ENGINE))
+            .map_err(|e| load_error(path, e))?;
+        Ok(Self {
+            store: RefCell::new(store),
+            pack,
+        })
+    }
+
+    fn rules(&self) -> &rules::Guest {
+        self.pack.aws_iam_rule_pack_rules()
+    }
+}
+
+impl PluginRuntime for WasmRuntime {
+    fn list_rules(&self) -> Result<Vec<PluginRuleInfo>, PluginError> {
+        let rules = self
+            .rules()
+            .call_list_rules(&mut *self.store.borrow_mut())
+            .map_err(runtime_error)?;
+        Ok(rules
+            .into_iter()
+            .map(|rule| PluginRuleInfo::new(rule.name, rule.severity.into()))
+            .collect())
+    }
+
+    fn check(&self, rule: &str, policy: &str) -> Result<Vec<PluginFinding>, PluginError> {
+        let findings = self
+            .rules()
+            .call_check(&mut *self.store.borrow_mut(), rule, policy)
+            .map_err(runtime_error)?
+            .map_err(PluginError::Runtime)?;
+        Ok(findings.into_iter().map(PluginFinding::from).collect())
+    }
+
+    fn list_transforms(&self) -> Result<Vec<String>, PluginError> {
+        self.rules()
+            .call_list_transforms(&mut *self.store.borrow_mut())
+            .map_err(runtime_error)
+    }
+
+    fn transform(&self, transform: &str, policy: &str) -> Result<String, PluginError> {
+        self.rules()
+            .call_transform(&mut *self.store.borrow_mut(), transform, policy)
+            .map_err(runtime_error)?
+            .map_err(PluginError::Runtime)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl From<rules::Severity> for Severity {
+    fn from(severity: rules::Severity) -> Self {
+        match severity {
+            rules::Severity::Error => Severity::Error,
+            rules::Severity::Warning => Severity::Warning,
+            rules::Severity::Info => Severity::Info,
+        }
+    }
+}
+
+impl From<rules::Finding> for PluginFinding {
+    fn from(finding: rules::Finding) -> Self {
+        PluginFinding::new(
+            finding.severity.into(),
+            finding.code,
+            finding.message,
+            finding.statement.map(|statement| statement as usize),
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+mod bindings {
+    wasmtime::component::bindgen!({
+        path: "wit/rule-pack.wit",
+        world: "rule-pack",
+    });
+}
+
+use bindings::exports::aws_iam::rule_pack::rules;
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+lazy_static! {
+    static ref ENGINE: Engine = Engine::default();
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn load_error<E>(path: &Path, e: E) -> PluginError
+where
+    E: Display,
+{
+    PluginError::Load {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    }
+}
+
+fn runtime_error(e: wasmtime::Error) -> PluginError {
+    PluginError::Runtime(e.to_string())
+}
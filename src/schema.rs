@@ -0,0 +1,193 @@
+/*!
+Provides [JSON Schema](https://json-schema.org/) documents describing the shapes of the JSON
+this crate reads, so that editors, and tools written in other languages, can validate policy
+documents and evaluation requests before passing them to this crate.
+
+The schemas use draft-07, as it is the most widely supported by editors. They describe the
+syntax accepted when reading a document, not the additional checks made by
+[`validate`](../validate/index.html); for example any string is accepted as a `Resource`,
+whereas the parser also requires each to be an ARN, or ARN pattern.
+
+# Example
+
+```rust
+use aws_iam::schema::policy_json_schema;
+
+let schema = policy_json_schema();
+assert_eq!(schema["required"][0], "Statement");
+std::fs::write(
+    std::env::temp_dir().join("iam-policy.schema.json"),
+    serde_json::to_string_pretty(&schema).unwrap(),
+)
+.unwrap();
+```
+*/
+
+use crate::model::naming::QNAME_PATTERN;
+use crate::syntax::{
+    ACTION_VALUE_ACTION, ACTION_VALUE_NOT_ACTION, COMMENT_NAME, CONDITION_NAME,
+    CONDITION_QUANTIFIER_FOR_ALL, CONDITION_QUANTIFIER_FOR_ANY, EFFECT_NAME, EFFECT_VALUE_ALLOW,
+    EFFECT_VALUE_DENY, ID_NAME, POLICY_WILDCARD_VALUE, PRINCIPAL_TYPE_AWS,
+    PRINCIPAL_TYPE_CANONICAL_USER, PRINCIPAL_TYPE_FEDERATED, PRINCIPAL_TYPE_SERVICE,
+    PRINCIPAL_VALUE_NOT_PRINCIPAL, PRINCIPAL_VALUE_PRINCIPAL, RESOURCE_VALUE_NOT_RESOURCE,
+    RESOURCE_VALUE_RESOURCE, SID_NAME, STATEMENT_NAME, VERSION_NAME, VERSION_VALUE_2008,
+    VERSION_VALUE_2012,
+};
+use serde_json::{json, Value};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+/// The JSON Schema dialect used by the schemas in this module.
+pub const JSON_SCHEMA_DIALECT: &str = "http://json-schema.org/draft-07/schema#";
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Return the schema of a policy document, as read by `Policy::from_str`.
+///
+pub fn policy_json_schema() -> Value {
+    let one_or_many_strings = json!({
+        "oneOf": [
+            { "type": "string", "minLength": 1 },
+            { "type": "array", "items": { "type": "string", "minLength": 1 }, "minItems": 1 }
+        ]
+    });
+    let action = json!({
+        "oneOf": [
+            { "const": POLICY_WILDCARD_VALUE },
+            { "$ref": "#/definitions/QualifiedName" },
+            { "type": "array", "items": { "$ref": "#/definitions/QualifiedName" }, "minItems": 1 }
+        ]
+    });
+    let principal = json!({
+        "oneOf": [
+            { "const": POLICY_WILDCARD_VALUE },
+            {
+                "type": "object",
+                "properties": {
+                    PRINCIPAL_TYPE_AWS: one_or_many_strings,
+                    PRINCIPAL_TYPE_FEDERATED: one_or_many_strings,
+                    PRINCIPAL_TYPE_SERVICE: one_or_many_strings,
+                    PRINCIPAL_TYPE_CANONICAL_USER: one_or_many_strings
+                },
+                "additionalProperties": false,
+                "minProperties": 1
+            }
+        ]
+    });
+    let condition_value = json!({ "type": ["string", "number", "boolean"] });
+    json!({
+        "$schema": JSON_SCHEMA_DIALECT,
+        "title": "AWS IAM Policy",
+        "type": "object",
+        "properties": {
+            VERSION_NAME: { "enum": [VERSION_VALUE_2012, VERSION_VALUE_2008] },
+            ID_NAME: { "type": "string" },
+            COMMENT_NAME: { "type": "string" },
+            STATEMENT_NAME: {
+                "type": "array",
+                "items": { "$ref": "#/definitions/Statement" }
+            }
+        },
+        "required": [STATEMENT_NAME],
+        "additionalProperties": false,
+        "definitions": {
+            "QualifiedName": {
+                "type": "string",
+                "pattern": QNAME_PATTERN
+            },
+            "Statement": {
+                "type": "object",
+                "properties": {
+                    SID_NAME: { "type": "string" },
+                    COMMENT_NAME: { "type": "string" },
+                    EFFECT_NAME: { "enum": [EFFECT_VALUE_ALLOW, EFFECT_VALUE_DENY] },
+                    PRINCIPAL_VALUE_PRINCIPAL: principal,
+                    PRINCIPAL_VALUE_NOT_PRINCIPAL: principal,
+                    ACTION_VALUE_ACTION: action,
+                    ACTION_VALUE_NOT_ACTION: action,
+                    RESOURCE_VALUE_RESOURCE: one_or_many_strings,
+                    RESOURCE_VALUE_NOT_RESOURCE: one_or_many_strings,
+                    CONDITION_NAME: { "$ref": "#/definitions/Condition" }
+                },
+                "required": [EFFECT_NAME],
+                "oneOf": [
+                    { "required": [ACTION_VALUE_ACTION] },
+                    { "required": [ACTION_VALUE_NOT_ACTION] }
+                ],
+                "not": {
+                    "anyOf": [
+                        { "required": [PRINCIPAL_VALUE_PRINCIPAL, PRINCIPAL_VALUE_NOT_PRINCIPAL] },
+                        { "required": [RESOURCE_VALUE_RESOURCE, RESOURCE_VALUE_NOT_RESOURCE] }
+                    ]
+                }
+            },
+            "Condition": {
+                "type": "object",
+                "propertyNames": {
+                    "pattern": format!(
+                        "^(({}|{}):)?[A-Za-z0-9]+$",
+                        CONDITION_QUANTIFIER_FOR_ANY, CONDITION_QUANTIFIER_FOR_ALL
+                    )
+                },
+                "additionalProperties": {
+                    "type": "object",
+                    "propertyNames": { "pattern": QNAME_PATTERN },
+                    "additionalProperties": {
+                        "oneOf": [
+                            condition_value,
+                            { "type": "array", "items": condition_value }
+                        ]
+                    }
+                }
+            }
+        }
+    })
+}
+
+///
+/// Return the schema of an evaluation request, as read by serde into an
+/// `offline::Request`.
+///
+#[cfg(feature = "offline_eval")]
+pub fn request_json_schema() -> Value {
+    json!({
+        "$schema": JSON_SCHEMA_DIALECT,
+        "title": "AWS IAM Evaluation Request",
+        "type": "object",
+        "properties": {
+            "request_id": { "type": "string" },
+            "principal": {
+                "type": "object",
+                "properties": {
+                    "principal_type": {
+                        "enum": [
+                            PRINCIPAL_TYPE_AWS,
+                            PRINCIPAL_TYPE_FEDERATED,
+                            PRINCIPAL_TYPE_SERVICE,
+                            PRINCIPAL_TYPE_CANONICAL_USER
+                        ]
+                    },
+                    "identifier": { "type": "string" },
+                    "unique_id": { "type": "string" }
+                },
+                "required": ["principal_type", "identifier"]
+            },
+            "action": { "type": "string", "pattern": QNAME_PATTERN },
+            "resource": { "type": "string" },
+            "source_arn": { "type": "string" },
+            "source_account": { "type": "string" },
+            "called_via": { "type": "array", "items": { "type": "string" } },
+            "environment": {
+                "type": "object",
+                "propertyNames": { "pattern": QNAME_PATTERN },
+                "additionalProperties": { "type": ["string", "number", "boolean"] }
+            }
+        },
+        "required": ["action", "resource", "environment"]
+    })
+}
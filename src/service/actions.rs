@@ -0,0 +1,346 @@
+/*!
+Provides typed values for the actions of common AWS services, generated from a compact table by
+the `service_actions!` macro in this module. Each service is a module named for its namespace,
+containing a `QualifiedName` value for each action and a `METADATA` table describing them.
+
+From [Actions, resources, and condition keys for AWS services](https://docs.aws.amazon.com/service-authorization/latest/reference/reference_policies_actions-resources-contextkeys.html).
+
+# Example
+
+```rust
+use aws_iam::model::builder::ActionBuilder;
+use aws_iam::service::actions::{lookup, s3, AccessLevel};
+
+let actions = ActionBuilder::any_of().these(vec![s3::GET_OBJECT.clone(), s3::LIST_BUCKET.clone()]);
+
+let metadata = lookup(&s3::GET_OBJECT).unwrap();
+assert_eq!(metadata.name, "GetObject");
+assert_eq!(metadata.access_level, AccessLevel::Read);
+assert!(AccessLevel::Write > AccessLevel::Read);
+```
+*/
+
+use crate::model::QualifiedName;
+use std::fmt::{Display, Formatter};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The access level classification AWS assigns to each action. Levels are ordered from the
+/// least to the most privileged, so that `Write` is greater than `Tagging`.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AccessLevel {
+    /// Permission to list resources, but not to read their contents.
+    List,
+    /// Permission to read, but not edit, resources and their attributes.
+    Read,
+    /// Permission to modify only the tags attached to a resource.
+    Tagging,
+    /// Permission to create, delete, or modify resources.
+    Write,
+    /// Permission to grant or modify resource permissions.
+    PermissionsManagement,
+}
+
+///
+/// Describes a single action of a service.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActionMetadata {
+    /// The service namespace, such as `s3`.
+    pub namespace: &'static str,
+    /// The action name, such as `GetObject`.
+    pub name: &'static str,
+    /// The access level of this action.
+    pub access_level: AccessLevel,
+    /// A short description of the action.
+    pub description: &'static str,
+    /// Other actions the caller may also need permission for, such as `iam:PassRole` for an
+    /// action that gives a role to a resource it creates.
+    pub dependent_actions: &'static [&'static str],
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Return the metadata for `action`, if it is a known action of a known service. As in IAM the
+/// namespace and name are compared case-insensitively.
+///
+pub fn lookup(action: &QualifiedName) -> Option<&'static ActionMetadata> {
+    service_metadata(&action.namespace())?
+        .iter()
+        .find(|metadata| metadata.name.eq_ignore_ascii_case(action.name()))
+}
+
+///
+/// Return the metadata for all the known actions matched by `pattern`, such as `s3:Get*`, see
+/// `QualifiedName::matches`. Actions of services for which there is no metadata are not
+/// included.
+///
+pub fn expand(pattern: &QualifiedName) -> Vec<&'static ActionMetadata> {
+    service_metadata(&pattern.namespace())
+        .unwrap_or_default()
+        .iter()
+        .filter(|metadata| pattern.matches(&metadata.qualified_name()))
+        .collect()
+}
+
+///
+/// Return the access level of `action`, if it is a known action. If `action` is a pattern,
+/// such as `s3:Put*`, the most privileged level of the known actions it matches is returned.
+///
+pub fn access_level(action: &QualifiedName) -> Option<AccessLevel> {
+    expand(action)
+        .iter()
+        .map(|metadata| metadata.access_level)
+        .max()
+}
+
+///
+/// Return the dependent actions, see `ActionMetadata::dependent_actions`, of `action`. If
+/// `action` is a pattern the dependent actions of all the known actions it matches are
+/// returned, without duplicates.
+///
+pub fn dependent_actions(action: &QualifiedName) -> Vec<QualifiedName> {
+    let mut dependents: Vec<QualifiedName> = Default::default();
+    for dependent in expand(action)
+        .iter()
+        .flat_map(|metadata| metadata.dependent_actions.iter())
+    {
+        let dependent = QualifiedName::new_unchecked(*dependent);
+        if !dependents.contains(&dependent) {
+            dependents.push(dependent);
+        }
+    }
+    dependents
+}
+
+///
+/// Return the metadata for all the known actions of all the known services.
+///
+pub fn all_metadata() -> impl Iterator<Item = &'static ActionMetadata> {
+    ALL_SERVICES.iter().flat_map(|actions| actions.iter())
+}
+
+///
+/// Return the metadata for all the known actions of the service `namespace`.
+///
+pub fn service_metadata(namespace: &str) -> Option<&'static [ActionMetadata]> {
+    ALL_SERVICES
+        .iter()
+        .find(|actions| {
+            actions
+                .first()
+                .map(|metadata| metadata.namespace.eq_ignore_ascii_case(namespace))
+                .unwrap_or_default()
+        })
+        .copied()
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for AccessLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::List => "List",
+                Self::Read => "Read",
+                Self::Write => "Write",
+                Self::PermissionsManagement => "Permissions management",
+                Self::Tagging => "Tagging",
+            }
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl From<&ActionMetadata> for QualifiedName {
+    fn from(metadata: &ActionMetadata) -> Self {
+        metadata.qualified_name()
+    }
+}
+
+impl ActionMetadata {
+    pub fn qualified_name(&self) -> QualifiedName {
+        QualifiedName::new_unchecked(format!("{}:{}", self.namespace, self.name))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Macros
+// ------------------------------------------------------------------------------------------------
+
+macro_rules! service_actions {
+    ($(
+        $(#[$meta:meta])*
+        $service:ident => $namespace:literal {
+            $($constant:ident => $name:literal, $level:ident, $description:literal $(, [$($dependent:literal),+])?;)+
+        }
+    )+) => {
+        $(
+            $(#[$meta])*
+            pub mod $service {
+                use super::{AccessLevel, ActionMetadata};
+                use crate::model::QualifiedName;
+                use lazy_static::lazy_static;
+
+                lazy_static! {
+                    $(
+                        #[doc = $description]
+                        pub static ref $constant: QualifiedName =
+                            QualifiedName::new_unchecked(concat!($namespace, ":", $name));
+                    )+
+                }
+
+                /// The metadata for all the known actions of this service.
+                pub const METADATA: &[ActionMetadata] = &[$(
+                    ActionMetadata {
+                        namespace: $namespace,
+                        name: $name,
+                        access_level: AccessLevel::$level,
+                        description: $description,
+                        dependent_actions: &[$($($dependent),+)?],
+                    },
+                )+];
+            }
+        )+
+
+        const ALL_SERVICES: &[&[ActionMetadata]] = &[$($service::METADATA),+];
+    };
+}
+
+// ------------------------------------------------------------------------------------------------
+// Modules
+// ------------------------------------------------------------------------------------------------
+
+service_actions! {
+    /// Actions for Amazon S3.
+    s3 => "s3" {
+        ABORT_MULTIPART_UPLOAD => "AbortMultipartUpload", Write, "Grants permission to abort a multipart upload";
+        CREATE_BUCKET => "CreateBucket", Write, "Grants permission to create a new bucket";
+        DELETE_BUCKET => "DeleteBucket", Write, "Grants permission to delete the bucket named in the URI";
+        DELETE_BUCKET_POLICY => "DeleteBucketPolicy", PermissionsManagement, "Grants permission to delete the policy on a specified bucket";
+        DELETE_OBJECT => "DeleteObject", Write, "Grants permission to remove the null version of an object";
+        GET_BUCKET_LOCATION => "GetBucketLocation", Read, "Grants permission to return the Region that an Amazon S3 bucket resides in";
+        GET_BUCKET_POLICY => "GetBucketPolicy", Read, "Grants permission to return the policy of the specified bucket";
+        GET_OBJECT => "GetObject", Read, "Grants permission to retrieve objects from Amazon S3", ["kms:Decrypt"];
+        GET_OBJECT_ACL => "GetObjectAcl", Read, "Grants permission to return the access control list (ACL) of an object";
+        LIST_ALL_MY_BUCKETS => "ListAllMyBuckets", List, "Grants permission to list all buckets owned by the authenticated sender of the request";
+        LIST_BUCKET => "ListBucket", List, "Grants permission to list some or all of the objects in an Amazon S3 bucket";
+        PUT_BUCKET_POLICY => "PutBucketPolicy", PermissionsManagement, "Grants permission to add or replace a bucket policy on a bucket";
+        PUT_OBJECT => "PutObject", Write, "Grants permission to add an object to a bucket", ["kms:GenerateDataKey"];
+        PUT_OBJECT_ACL => "PutObjectAcl", PermissionsManagement, "Grants permission to set the access control list (ACL) permissions for new or existing objects";
+        PUT_OBJECT_TAGGING => "PutObjectTagging", Tagging, "Grants permission to set the supplied tag-set to an object that already exists in a bucket";
+    }
+
+    /// Actions for Amazon SQS.
+    sqs => "sqs" {
+        ADD_PERMISSION => "AddPermission", PermissionsManagement, "Grants permission to add a permission to a queue for a specific principal";
+        CREATE_QUEUE => "CreateQueue", Write, "Grants permission to create a new queue, or returns the URL of an existing one";
+        DELETE_MESSAGE => "DeleteMessage", Write, "Grants permission to delete the specified message from the specified queue";
+        DELETE_QUEUE => "DeleteQueue", Write, "Grants permission to delete the queue specified by the queue URL";
+        GET_QUEUE_ATTRIBUTES => "GetQueueAttributes", Read, "Grants permission to get attributes for the specified queue";
+        GET_QUEUE_URL => "GetQueueUrl", Read, "Grants permission to return the URL of an existing queue";
+        LIST_QUEUES => "ListQueues", List, "Grants permission to return a list of your queues";
+        RECEIVE_MESSAGE => "ReceiveMessage", Read, "Grants permission to retrieve one or more messages from the specified queue";
+        REMOVE_PERMISSION => "RemovePermission", PermissionsManagement, "Grants permission to revoke any permissions in the queue policy";
+        SEND_MESSAGE => "SendMessage", Write, "Grants permission to deliver a message to the specified queue";
+        SET_QUEUE_ATTRIBUTES => "SetQueueAttributes", Write, "Grants permission to set the value of one or more queue attributes";
+        TAG_QUEUE => "TagQueue", Tagging, "Grants permission to add tags to the specified queue";
+    }
+
+    /// Actions for Amazon SNS.
+    sns => "sns" {
+        ADD_PERMISSION => "AddPermission", PermissionsManagement, "Grants permission to add a statement to a topic's access control policy";
+        CREATE_TOPIC => "CreateTopic", Write, "Grants permission to create a topic to which notifications can be published";
+        DELETE_TOPIC => "DeleteTopic", Write, "Grants permission to delete a topic and all its subscriptions";
+        GET_TOPIC_ATTRIBUTES => "GetTopicAttributes", Read, "Grants permission to return all of the properties of a topic";
+        LIST_TOPICS => "ListTopics", List, "Grants permission to return a list of the requester's topics";
+        PUBLISH => "Publish", Write, "Grants permission to send a message to all of a topic's subscribed endpoints";
+        SET_TOPIC_ATTRIBUTES => "SetTopicAttributes", PermissionsManagement, "Grants permission to allow a topic owner to set an attribute of the topic to a new value";
+        SUBSCRIBE => "Subscribe", Write, "Grants permission to prepare to subscribe an endpoint";
+    }
+
+    /// Actions for AWS Security Token Service.
+    sts => "sts" {
+        ASSUME_ROLE => "AssumeRole", Write, "Grants permission to obtain a set of temporary security credentials to access AWS resources";
+        ASSUME_ROLE_WITH_SAML => "AssumeRoleWithSAML", Write, "Grants permission to obtain temporary credentials for users authenticated via a SAML response";
+        ASSUME_ROLE_WITH_WEB_IDENTITY => "AssumeRoleWithWebIdentity", Write, "Grants permission to obtain temporary credentials for users authenticated with a web identity provider";
+        GET_CALLER_IDENTITY => "GetCallerIdentity", Read, "Grants permission to obtain details about the IAM identity whose credentials are used to call the API";
+        GET_SESSION_TOKEN => "GetSessionToken", Read, "Grants permission to obtain a set of temporary security credentials for an AWS account or IAM user";
+        TAG_SESSION => "TagSession", Tagging, "Grants permission to add tags to a STS session";
+    }
+
+    /// Actions for AWS Identity and Access Management.
+    iam => "iam" {
+        ATTACH_ROLE_POLICY => "AttachRolePolicy", PermissionsManagement, "Grants permission to attach a managed policy to a role";
+        CREATE_ACCESS_KEY => "CreateAccessKey", Write, "Grants permission to create access key and secret access key for the specified IAM user";
+        CREATE_ROLE => "CreateRole", Write, "Grants permission to create a new role";
+        CREATE_USER => "CreateUser", Write, "Grants permission to create a new IAM user";
+        DELETE_ROLE => "DeleteRole", Write, "Grants permission to delete the specified role";
+        GET_ROLE => "GetRole", Read, "Grants permission to retrieve information about the specified role";
+        GET_USER => "GetUser", Read, "Grants permission to retrieve information about the specified IAM user";
+        LIST_ROLES => "ListRoles", List, "Grants permission to list the IAM roles that have the specified path prefix";
+        LIST_USERS => "ListUsers", List, "Grants permission to list the IAM users that have the specified path prefix";
+        PASS_ROLE => "PassRole", Write, "Grants permission to pass a role to a service";
+        PUT_ROLE_POLICY => "PutRolePolicy", PermissionsManagement, "Grants permission to create or update an inline policy document that is embedded with the specified IAM role";
+        TAG_ROLE => "TagRole", Tagging, "Grants permission to add tags to an IAM role";
+    }
+
+    /// Actions for AWS Key Management Service.
+    kms => "kms" {
+        CREATE_GRANT => "CreateGrant", PermissionsManagement, "Controls permission to add a grant to a KMS key";
+        DECRYPT => "Decrypt", Write, "Controls permission to decrypt ciphertext that was encrypted under a KMS key";
+        DESCRIBE_KEY => "DescribeKey", Read, "Controls permission to view detailed information about a KMS key";
+        ENCRYPT => "Encrypt", Write, "Controls permission to use the specified KMS key to encrypt data and data keys";
+        GENERATE_DATA_KEY => "GenerateDataKey", Write, "Controls permission to use the KMS key to generate data keys";
+        LIST_KEYS => "ListKeys", List, "Controls permission to view the key ID and ARN of all KMS keys in the account";
+        PUT_KEY_POLICY => "PutKeyPolicy", PermissionsManagement, "Controls permission to replace the key policy for the specified KMS key";
+    }
+
+    /// Actions for AWS Lambda.
+    lambda => "lambda" {
+        ADD_PERMISSION => "AddPermission", PermissionsManagement, "Grants permission to give an AWS service or another account permission to use an AWS Lambda function";
+        CREATE_FUNCTION => "CreateFunction", Write, "Grants permission to create an AWS Lambda function", ["iam:PassRole"];
+        DELETE_FUNCTION => "DeleteFunction", Write, "Grants permission to delete an AWS Lambda function";
+        GET_FUNCTION => "GetFunction", Read, "Grants permission to view details about an AWS Lambda function";
+        INVOKE_FUNCTION => "InvokeFunction", Write, "Grants permission to invoke a function";
+        LIST_FUNCTIONS => "ListFunctions", List, "Grants permission to retrieve a list of AWS Lambda functions, with the version-specific configuration of each function";
+        TAG_RESOURCE => "TagResource", Tagging, "Grants permission to add tags to an AWS Lambda function";
+        UPDATE_FUNCTION_CODE => "UpdateFunctionCode", Write, "Grants permission to update the code of an AWS Lambda function";
+        UPDATE_FUNCTION_CONFIGURATION => "UpdateFunctionConfiguration", Write, "Grants permission to modify the version-specific settings of an AWS Lambda function", ["iam:PassRole"];
+    }
+
+    /// Actions for Amazon DynamoDB.
+    dynamodb => "dynamodb" {
+        BATCH_GET_ITEM => "BatchGetItem", Read, "Grants permission to return the attributes of one or more items from one or more tables";
+        BATCH_WRITE_ITEM => "BatchWriteItem", Write, "Grants permission to put or delete multiple items in one or more tables";
+        CREATE_TABLE => "CreateTable", Write, "Grants permission to create a table";
+        DELETE_ITEM => "DeleteItem", Write, "Grants permission to delete a single item in a table by primary key";
+        DELETE_RESOURCE_POLICY => "DeleteResourcePolicy", Write, "Grants permission to delete the resource-based policy attached to a table or stream";
+        DELETE_TABLE => "DeleteTable", Write, "Grants permission to delete a table and all of its items";
+        DESCRIBE_TABLE => "DescribeTable", Read, "Grants permission to return information about a table";
+        GET_ITEM => "GetItem", Read, "Grants permission to return a set of attributes for the item with the given primary key";
+        GET_RESOURCE_POLICY => "GetResourcePolicy", Read, "Grants permission to return the resource-based policy attached to a table or stream";
+        LIST_TABLES => "ListTables", List, "Grants permission to return an array of table names associated with the current account and endpoint";
+        LIST_TAGS_OF_RESOURCE => "ListTagsOfResource", Read, "Grants permission to list all tags on an Amazon DynamoDB resource";
+        PUT_ITEM => "PutItem", Write, "Grants permission to create a new item, or replace an old item with a new item";
+        PUT_RESOURCE_POLICY => "PutResourcePolicy", Write, "Grants permission to attach a resource-based policy to a table or stream";
+        QUERY => "Query", Read, "Grants permission to use the primary key of a table or a secondary index to directly access items from that table or index";
+        SCAN => "Scan", Read, "Grants permission to return one or more items and item attributes by accessing every item in a table or a secondary index";
+        TAG_RESOURCE => "TagResource", Tagging, "Grants permission to associate a set of tags with an Amazon DynamoDB resource";
+        UNTAG_RESOURCE => "UntagResource", Tagging, "Grants permission to remove the association of tags from an Amazon DynamoDB resource";
+        UPDATE_ITEM => "UpdateItem", Write, "Grants permission to edit an existing item's attributes, or add a new item to the table if it does not already exist";
+        UPDATE_TABLE => "UpdateTable", Write, "Grants permission to modify the provisioned throughput settings, global secondary indexes, or DynamoDB Streams settings for a given table";
+    }
+}
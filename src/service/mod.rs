@@ -2,30 +2,86 @@
 Provides the ability to configure service-specific rules for validation. Requires feature
 `service_config`.
 
-Details TBD.
+The [`actions`](actions/index.html) module provides typed values for the actions of common
+services, so that policies may reference them without stringly-typed typos.
+
+For interactive tools, [`suggest_values`](fn.suggest_values.html) returns the known values of
+condition keys whose values are drawn from a small, fixed set, such as the regions for
+`aws:RequestedRegion` and the principal types for `aws:PrincipalType`.
+
+Each [`ResourceType`](struct.ResourceType.html) of a service lists the condition keys that may
+be used with it. When the services are provided to validation, see
+`ValidationOptions::with_services`, a condition on one of these keys is reported if it can
+never apply to the resources of its statement.
+
+# Example
+
+```rust
+use aws_iam::model::naming::Namespace;
+use aws_iam::service::{ResourceType, ServiceConfig};
+use std::str::FromStr;
+
+let s3 = ServiceConfig::new(Namespace::from_str("s3").unwrap())
+    .with_resource_type(
+        ResourceType::new("bucket", "arn:${Partition}:s3:::${BucketName}"),
+    )
+    .with_resource_type(
+        ResourceType::new("object", "arn:${Partition}:s3:::${BucketName}/${ObjectName}")
+            .with_condition_key("s3:ExistingObjectTag/${TagKey}"),
+    );
+
+let types = s3.resource_types_for("arn:aws:s3:::bucket/reports/2020.csv");
+assert_eq!(types.len(), 1);
+assert!(types[0].supports_condition_key("s3:ExistingObjectTag/team"));
+assert!(s3.is_resource_condition_key("s3:existingobjecttag/team"));
+```
  */
 
+use crate::error::{unexpected_value_for_type, IamFormatError};
+use crate::model::naming::Namespace;
+use crate::model::QualifiedName;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
 // ------------------------------------------------------------------------------------------------
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[allow(missing_docs)]
 pub struct ServiceConfig {
     pub namespace: Namespace,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub actions: Vec<QualifiedName>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
-    pub resource_types: Vec<String>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub resource_types: Vec<ResourceType>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub condition_keys: Vec<ConditionKey>,
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+///
+/// A type of resource defined by a service, identified by the form of its ARN, and the
+/// condition keys that may be used with it.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceType {
+    /// The name of the type, such as `bucket`.
+    pub name: String,
+    /// The form of the ARN of resources of this type, with placeholders such as
+    /// `arn:${Partition}:s3:::${BucketName}`.
+    pub arn_pattern: String,
+    /// The condition keys that may be used with this type; a placeholder in a key, such as
+    /// `aws:ResourceTag/${TagKey}`, stands for any tag key.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub condition_keys: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[allow(missing_docs)]
 pub enum ConditionKeyType {
+    #[default]
     String,
     Number,
     Boolean,
@@ -35,7 +91,7 @@ pub enum ConditionKeyType {
     IpAddress,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[allow(missing_docs)]
 pub struct ConditionKey {
     name: QualifiedName,
@@ -51,10 +107,12 @@ pub struct ConditionKey {
 // ------------------------------------------------------------------------------------------------
 
 impl ServiceConfig {
-    pub fn new(name: QualifiedName) -> Self {
+    pub fn new(namespace: Namespace) -> Self {
         Self {
-            name,
-            key_type: ConditionKeyType::default(),
+            namespace,
+            actions: Default::default(),
+            resource_types: Default::default(),
+            condition_keys: Default::default(),
         }
     }
 
@@ -62,28 +120,136 @@ impl ServiceConfig {
         &self.namespace
     }
 
-    pub fn actions(&self) -> impl Iterator<Item = QualifiedName> {
-        &self.actions.iter()
+    pub fn actions(&self) -> impl Iterator<Item = &QualifiedName> {
+        self.actions.iter()
+    }
+
+    pub fn resource_types(&self) -> impl Iterator<Item = &ResourceType> {
+        self.resource_types.iter()
     }
 
-    pub fn resource_types(&self) -> impl Iterator<Item = String> {
-        &self.resource_types.iter()
+    pub fn condition_keys(&self) -> impl Iterator<Item = &ConditionKey> {
+        self.condition_keys.iter()
     }
 
-    pub fn condition_keys(&self) -> impl Iterator<Item = ConditionKey> {
-        &self.condition_keys.iter()
+    /// Add `resource_type` to the types of this service.
+    pub fn with_resource_type(mut self, resource_type: ResourceType) -> Self {
+        self.resource_types.push(resource_type);
+        self
+    }
+
+    ///
+    /// Returns the resource types whose ARN pattern matches `resource`. A resource containing
+    /// the wildcards `*` or `?` may name resources of any type, and so all types are returned.
+    ///
+    pub fn resource_types_for(&self, resource: &str) -> Vec<&ResourceType> {
+        let has_wildcards = resource.contains(['*', '?']);
+        self.resource_types
+            .iter()
+            .filter(|resource_type| has_wildcards || resource_type.matches(resource))
+            .collect()
+    }
+
+    ///
+    /// Returns `true` if `key` may be used with any of the resource types of this service, that
+    /// is it is a key describing a resource rather than the request.
+    ///
+    pub fn is_resource_condition_key(&self, key: &str) -> bool {
+        self.resource_types
+            .iter()
+            .any(|resource_type| resource_type.supports_condition_key(key))
     }
 }
 
 // ------------------------------------------------------------------------------------------------
 
-impl Display for ConditionKeyType {}
+impl ResourceType {
+    pub fn new<S1, S2>(name: S1, arn_pattern: S2) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        Self {
+            name: name.into(),
+            arn_pattern: arn_pattern.into(),
+            condition_keys: Default::default(),
+        }
+    }
 
-impl Default for ConditionKeyType {}
+    /// Add `key` to the condition keys that may be used with this type.
+    pub fn with_condition_key<S>(mut self, key: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.condition_keys.push(key.into());
+        self
+    }
 
-impl FromStr for ConditionKeyType {}
+    ///
+    /// Returns `true` if `resource`, an ARN as written in a policy, has the form of this type.
+    /// A placeholder matches any text within one component or path segment, except a final
+    /// placeholder after `/`, which matches the rest of the ARN, as a path or object key may.
+    ///
+    pub fn matches(&self, resource: &str) -> bool {
+        arn_pattern_regex(&self.arn_pattern)
+            .map(|regex| regex.is_match(resource))
+            .unwrap_or_default()
+    }
 
-impl ConditionKeyType {}
+    /// Returns `true` if `key` may be used with this type; keys are not case-sensitive.
+    pub fn supports_condition_key(&self, key: &str) -> bool {
+        self.condition_keys
+            .iter()
+            .any(|supported| condition_key_matches(supported, key))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+const KEY_TYPE_STRING: &str = "String";
+const KEY_TYPE_NUMBER: &str = "Numeric";
+const KEY_TYPE_BOOLEAN: &str = "Bool";
+const KEY_TYPE_DATE: &str = "Date";
+const KEY_TYPE_BINARY: &str = "Binary";
+const KEY_TYPE_RESOURCE_NAME: &str = "ARN";
+const KEY_TYPE_IP_ADDRESS: &str = "IPAddress";
+
+const CONDITION_KEY_TYPE_NAME: &str = "ConditionKeyType";
+
+impl Display for ConditionKeyType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::String => KEY_TYPE_STRING,
+                Self::Number => KEY_TYPE_NUMBER,
+                Self::Boolean => KEY_TYPE_BOOLEAN,
+                Self::Date => KEY_TYPE_DATE,
+                Self::Binary => KEY_TYPE_BINARY,
+                Self::ResourceName => KEY_TYPE_RESOURCE_NAME,
+                Self::IpAddress => KEY_TYPE_IP_ADDRESS,
+            }
+        )
+    }
+}
+
+impl FromStr for ConditionKeyType {
+    type Err = IamFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            KEY_TYPE_STRING => Ok(Self::String),
+            KEY_TYPE_NUMBER => Ok(Self::Number),
+            KEY_TYPE_BOOLEAN => Ok(Self::Boolean),
+            KEY_TYPE_DATE => Ok(Self::Date),
+            KEY_TYPE_BINARY => Ok(Self::Binary),
+            KEY_TYPE_RESOURCE_NAME => Ok(Self::ResourceName),
+            KEY_TYPE_IP_ADDRESS => Ok(Self::IpAddress),
+            _ => unexpected_value_for_type(CONDITION_KEY_TYPE_NAME, s).into(),
+        }
+    }
+}
 
 // ------------------------------------------------------------------------------------------------
 
@@ -95,8 +261,11 @@ impl ConditionKey {
         }
     }
 
-    pub fn number(name: QualifiedName, key_type: ConditionKeyType) -> Self {
-        Self { name, key_type }
+    pub fn number(name: QualifiedName) -> Self {
+        Self {
+            name,
+            key_type: ConditionKeyType::Number,
+        }
     }
 
     pub fn boolean(name: QualifiedName) -> Self {
@@ -143,6 +312,45 @@ impl ConditionKey {
     }
 }
 
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn arn_pattern_regex(arn_pattern: &str) -> Option<Regex> {
+    let mut regex = String::from("^");
+    let mut rest = arn_pattern;
+    while let Some(start) = rest.find("${") {
+        let end = start + rest[start..].find('}')?;
+        regex.push_str(&regex::escape(&rest[..start]));
+        let is_last = end + 1 == rest.len();
+        regex.push_str(if is_last && rest[..start].ends_with('/') {
+            ".*"
+        } else {
+            "[^:/]*"
+        });
+        rest = &rest[end + 1..];
+    }
+    regex.push_str(&regex::escape(rest));
+    regex.push('$');
+    Regex::new(&regex).ok()
+}
+
+fn condition_key_matches(supported: &str, key: &str) -> bool {
+    match supported.find("${") {
+        Some(index) => {
+            key.len() > index
+                && key.is_char_boundary(index)
+                && key[..index].eq_ignore_ascii_case(&supported[..index])
+        }
+        None => supported.eq_ignore_ascii_case(key),
+    }
+}
+
 // ------------------------------------------------------------------------------------------------
 // Modules
 // ------------------------------------------------------------------------------------------------
+
+pub mod actions;
+
+mod values;
+pub use values::{suggest_values, KNOWN_REGIONS};
@@ -0,0 +1,127 @@
+/*!
+Provides the known values of condition keys whose values are drawn from a small, fixed set,
+for tools that suggest values as a policy is written.
+*/
+
+use crate::context::keys::{
+    AWS_CALLED_VIA, AWS_CALLED_VIA_FIRST, AWS_CALLED_VIA_LAST, AWS_MFA_PRESENT, AWS_PRINCIPAL_ARN,
+    AWS_PRINCIPAL_TYPE, AWS_REQUESTED_REGION, AWS_SECURE_TRANSPORT, AWS_SOURCE_ARN,
+    AWS_VIA_AWS_SERVICE,
+};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The names of the AWS regions, in all partitions, as used in ARNs and as the value of the
+/// `aws:RequestedRegion` condition key.
+///
+pub const KNOWN_REGIONS: &[&str] = &[
+    "af-south-1",
+    "ap-east-1",
+    "ap-east-2",
+    "ap-northeast-1",
+    "ap-northeast-2",
+    "ap-northeast-3",
+    "ap-south-1",
+    "ap-south-2",
+    "ap-southeast-1",
+    "ap-southeast-2",
+    "ap-southeast-3",
+    "ap-southeast-4",
+    "ap-southeast-5",
+    "ap-southeast-7",
+    "ca-central-1",
+    "ca-west-1",
+    "cn-north-1",
+    "cn-northwest-1",
+    "eu-central-1",
+    "eu-central-2",
+    "eu-north-1",
+    "eu-south-1",
+    "eu-south-2",
+    "eu-west-1",
+    "eu-west-2",
+    "eu-west-3",
+    "il-central-1",
+    "me-central-1",
+    "me-south-1",
+    "mx-central-1",
+    "sa-east-1",
+    "us-east-1",
+    "us-east-2",
+    "us-gov-east-1",
+    "us-gov-west-1",
+    "us-west-1",
+    "us-west-2",
+];
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Return the known values of the condition key `key`, if its values are drawn from a small,
+/// fixed set; keys are not case-sensitive. For keys whose values are ARNs the prefix of an ARN
+/// in each partition is returned. Returns `None` for keys whose values cannot be enumerated,
+/// such as `aws:SourceIp`, and for keys that are not known.
+///
+pub fn suggest_values(key: &str) -> Option<&'static [&'static str]> {
+    KNOWN_VALUES
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(key))
+        .map(|(_, values)| *values)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const BOOLEAN_VALUES: &[&str] = &["true", "false"];
+
+const PARTITION_ARN_PREFIXES: &[&str] = &["arn:aws:", "arn:aws-cn:", "arn:aws-us-gov:"];
+
+const PRINCIPAL_TYPES: &[&str] = &[
+    "Account",
+    "User",
+    "FederatedUser",
+    "AssumedRole",
+    "Anonymous",
+];
+
+const CALLED_VIA_SERVICES: &[&str] = &[
+    "athena.amazonaws.com",
+    "cloudformation.amazonaws.com",
+    "dynamodb.amazonaws.com",
+    "kms.amazonaws.com",
+];
+
+const S3_SERVER_SIDE_ENCRYPTION: &[&str] = &["AES256", "aws:kms", "aws:kms:dsse"];
+
+const S3_CANNED_ACLS: &[&str] = &[
+    "private",
+    "public-read",
+    "public-read-write",
+    "aws-exec-read",
+    "authenticated-read",
+    "bucket-owner-read",
+    "bucket-owner-full-control",
+    "log-delivery-write",
+];
+
+const KNOWN_VALUES: &[(&str, &[&str])] = &[
+    (AWS_CALLED_VIA, CALLED_VIA_SERVICES),
+    (AWS_CALLED_VIA_FIRST, CALLED_VIA_SERVICES),
+    (AWS_CALLED_VIA_LAST, CALLED_VIA_SERVICES),
+    (AWS_MFA_PRESENT, BOOLEAN_VALUES),
+    (AWS_PRINCIPAL_ARN, PARTITION_ARN_PREFIXES),
+    ("aws:PrincipalIsAWSService", BOOLEAN_VALUES),
+    (AWS_PRINCIPAL_TYPE, PRINCIPAL_TYPES),
+    (AWS_REQUESTED_REGION, KNOWN_REGIONS),
+    (AWS_SECURE_TRANSPORT, BOOLEAN_VALUES),
+    (AWS_SOURCE_ARN, PARTITION_ARN_PREFIXES),
+    (AWS_VIA_AWS_SERVICE, BOOLEAN_VALUES),
+    ("s3:x-amz-acl", S3_CANNED_ACLS),
+    ("s3:x-amz-server-side-encryption", S3_SERVER_SIDE_ENCRYPTION),
+];
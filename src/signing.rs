@@ -0,0 +1,187 @@
+/*!
+Provides detached ed25519 signatures over policies, so that a service only loads policies that
+have been reviewed and signed by a deployment pipeline. Requires feature `signing`.
+
+A signature is computed over the canonical form of a policy, see
+[`canonical_form`](fn.canonical_form.html), rather than the bytes of its file; a policy that is
+reformatted, or whose statement elements are reordered, keeps its signature while any change
+to its content does not. Signatures and keys are exchanged as base64 strings, and the signature
+of a policy file is stored beside it in a file with the extension `.sig` appended, for example
+`read-only.json.sig`.
+
+With the feature `watch` the trusted keys may be set in `io::WatchOptions`, and any policy file
+in the watched directory without a signature from one of them is rejected.
+
+# Example
+
+```rust
+use aws_iam::io;
+use aws_iam::signing::{sign_policy, verify_policy, SigningKey};
+
+let policy = io::read_from_string(
+    r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject","Resource":"*"}]}"#,
+)
+.unwrap();
+let key = SigningKey::from_bytes(&[7; 32]);
+
+let signature = sign_policy(&key, &policy).unwrap();
+assert!(verify_policy(&policy, &signature, &[key.verifying_key()]).is_ok());
+```
+*/
+
+use crate::error::{IamError, SigningError};
+#[cfg(feature = "fs")]
+use crate::io::read_from_file;
+use crate::io::to_canonical_string;
+use crate::model::Policy;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ed25519_dalek::Signer;
+use std::convert::TryInto;
+#[cfg(feature = "fs")]
+use std::fs::{read_to_string, write};
+#[cfg(feature = "fs")]
+use std::path::{Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+pub use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The extension appended to the name of a policy file to name its signature file.
+///
+pub const SIGNATURE_FILE_EXTENSION: &str = "sig";
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The form of `policy` that is signed, see `io::to_canonical_string`.
+///
+pub fn canonical_form(policy: &Policy) -> Result<String, IamError> {
+    to_canonical_string(policy)
+}
+
+///
+/// Sign the canonical form of `policy` with `key`.
+///
+pub fn sign_policy(key: &SigningKey, policy: &Policy) -> Result<Signature, IamError> {
+    Ok(key.sign(canonical_form(policy)?.as_bytes()))
+}
+
+///
+/// Verify that `signature` was made over the canonical form of `policy` by the private key of
+/// one of the `trusted` keys.
+///
+pub fn verify_policy(
+    policy: &Policy,
+    signature: &Signature,
+    trusted: &[VerifyingKey],
+) -> Result<(), IamError> {
+    let canonical = canonical_form(policy)?;
+    if trusted
+        .iter()
+        .any(|key| key.verify_strict(canonical.as_bytes(), signature).is_ok())
+    {
+        Ok(())
+    } else {
+        Err(SigningError::Untrusted.into())
+    }
+}
+
+/// The base64 form of `signature`.
+pub fn signature_to_string(signature: &Signature) -> String {
+    STANDARD.encode(signature.to_bytes())
+}
+
+/// Parse the base64 form of a signature; surrounding whitespace is ignored.
+pub fn signature_from_str(s: &str) -> Result<Signature, SigningError> {
+    let bytes = decode(s).map_err(SigningError::InvalidSignature)?;
+    let bytes: [u8; 64] = bytes
+        .try_into()
+        .map_err(|_| SigningError::InvalidSignature("expecting 64 bytes".to_string()))?;
+    Ok(Signature::from_bytes(&bytes))
+}
+
+/// The base64 form of the public `key`.
+pub fn verifying_key_to_string(key: &VerifyingKey) -> String {
+    STANDARD.encode(key.to_bytes())
+}
+
+/// Parse the base64 form of a public key; surrounding whitespace is ignored.
+pub fn verifying_key_from_str(s: &str) -> Result<VerifyingKey, SigningError> {
+    let bytes = decode(s).map_err(SigningError::InvalidKey)?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| SigningError::InvalidKey("expecting 32 bytes".to_string()))?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| SigningError::InvalidKey(e.to_string()))
+}
+
+/// Parse the base64 form of a private key, its 32 byte seed; surrounding whitespace is ignored.
+pub fn signing_key_from_str(s: &str) -> Result<SigningKey, SigningError> {
+    let bytes = decode(s).map_err(SigningError::InvalidKey)?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| SigningError::InvalidKey("expecting 32 bytes".to_string()))?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+///
+/// The path of the signature file for the policy file at `path`.
+///
+#[cfg(feature = "fs")]
+pub fn signature_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(SIGNATURE_FILE_EXTENSION);
+    path.with_file_name(file_name)
+}
+
+///
+/// Read the policy file at `path`, sign it with `key`, and write the signature to its
+/// signature file, whose path is returned.
+///
+#[cfg(feature = "fs")]
+pub fn write_signature_file(path: &Path, key: &SigningKey) -> Result<PathBuf, IamError> {
+    let signature = sign_policy(key, &read_from_file(path)?)?;
+    let signature_path = signature_path(path);
+    write(&signature_path, signature_to_string(&signature))?;
+    Ok(signature_path)
+}
+
+///
+/// Read the policy file at `path` and verify it against its signature file with the `trusted`
+/// keys; the policy is only returned if the signature was made by one of them.
+///
+#[cfg(feature = "fs")]
+pub fn read_verified_file(path: &Path, trusted: &[VerifyingKey]) -> Result<Policy, IamError> {
+    let policy = read_from_file(path)?;
+    let signature_path = signature_path(path);
+    let signature = match read_to_string(&signature_path) {
+        Ok(signature) => signature_from_str(&signature)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(SigningError::MissingSignature {
+                path: signature_path,
+            }
+            .into())
+        }
+        Err(e) => return Err(e.into()),
+    };
+    verify_policy(&policy, &signature, trusted)?;
+    Ok(policy)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn decode(s: &str) -> Result<Vec<u8>, String> {
+    STANDARD.decode(s.trim()).map_err(|e| e.to_string())
+}
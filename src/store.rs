@@ -0,0 +1,389 @@
+/*!
+Provides a thread-safe store for parsed policies, intended for tools that load thousands of
+policies, such as an organization-wide export, many of which are identical or nearly so.
+
+A [`PolicyStore`](struct.PolicyStore.html) keeps a single copy of each distinct policy, keyed
+by its [fingerprint](fn.fingerprint.html), so that inserting a policy that is already present
+returns the existing copy. A policy is not stored as a `Policy` value but as its JSON
+document, in which every string, the actions, resources, and condition keys included, is
+interned so that each distinct value is held once, behind an `Arc<str>`, however many policies
+reference it. The `Policy` value is rebuilt from the document when it is requested.
+
+# Example
+
+```rust
+use aws_iam::store::PolicyStore;
+
+let store = PolicyStore::default();
+let first = store
+    .parse(r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject","Resource":"*"}]}"#)
+    .unwrap();
+let second = store
+    .parse(r#"{"Statement": [{"Action": ["s3:GetObject"], "Effect": "Allow", "Resource": "*"}]}"#)
+    .unwrap();
+let third = store
+    .parse(r#"{"Statement":[{"Effect":"Deny","Action":"s3:GetObject","Resource":"*"}]}"#)
+    .unwrap();
+
+assert_eq!(store.len(), 2);
+assert_eq!(first.fingerprint(), second.fingerprint());
+assert!(std::sync::Arc::ptr_eq(&first.actions()[0], &third.actions()[0]));
+assert_eq!(third.policy().statement.len(), 1);
+```
+*/
+
+use crate::arn::ArnParseMode;
+use crate::error::IamError;
+use crate::model::Policy;
+use crate::syntax::{
+    IamValue, ACTION_VALUE_ACTION, ACTION_VALUE_NOT_ACTION, CONDITION_NAME,
+    RESOURCE_VALUE_NOT_RESOURCE, RESOURCE_VALUE_RESOURCE, STATEMENT_NAME,
+};
+use serde_json::{Map, Value};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A thread-safe cache of parsed policies, see the module documentation. All operations take
+/// `&self`, so a store may be shared between threads using an `Arc`.
+///
+#[derive(Debug, Default)]
+pub struct PolicyStore {
+    inner: RwLock<StoreInner>,
+}
+
+///
+/// A policy held by a `PolicyStore`, as a JSON document built from the store's interned
+/// strings.
+///
+#[derive(Debug)]
+pub struct StoredPolicy {
+    fingerprint: u64,
+    document: Node,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug, Default)]
+struct StoreInner {
+    strings: HashSet<Arc<str>>,
+    policies: HashMap<u64, Arc<StoredPolicy>>,
+}
+
+///
+/// A JSON value, as `serde_json::Value`, in which every string and object key is interned.
+///
+#[derive(Debug)]
+enum Node {
+    Null,
+    Bool(bool),
+    Number(serde_json::Number),
+    String(Arc<str>),
+    Array(Vec<Node>),
+    Object(Vec<(Arc<str>, Node)>),
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns the fingerprint of `policy`. Policies that are equal have the same fingerprint,
+/// which is independent of the JSON formatting, and of the order of keys within objects, of the
+/// document they were read from. Fingerprints are only stable within a single build of this
+/// crate and should not be persisted.
+///
+pub fn fingerprint(policy: &Policy) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    policy.hash(&mut hasher);
+    hasher.finish()
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl PolicyStore {
+    ///
+    /// Parse the JSON policy document `s` and insert it into the store, see `insert`.
+    ///
+    pub fn parse(&self, s: &str) -> Result<Arc<StoredPolicy>, IamError> {
+        Ok(self.insert(Policy::from_str(s)?))
+    }
+
+    ///
+    /// Insert `policy` into the store, returning the stored copy. If an equal policy is already
+    /// present it is returned and `policy` is dropped. In the unlikely event that a different
+    /// policy has the same fingerprint the new policy is returned, but is not cached.
+    ///
+    pub fn insert(&self, policy: Policy) -> Arc<StoredPolicy> {
+        let fingerprint = fingerprint(&policy);
+        if let Some(existing) = self.get(fingerprint) {
+            if existing.policy() == policy {
+                return existing;
+            }
+        }
+
+        let mut inner = self.inner.write().unwrap();
+        if let Some(existing) = inner.policies.get(&fingerprint) {
+            // another thread may have inserted the policy since the read above.
+            return if existing.policy() == policy {
+                existing.clone()
+            } else {
+                Arc::new(inner.stored(fingerprint, &policy))
+            };
+        }
+        let stored = Arc::new(inner.stored(fingerprint, &policy));
+        let _ = inner.policies.insert(fingerprint, stored.clone());
+        stored
+    }
+
+    ///
+    /// Returns the stored policy with the fingerprint `fingerprint`, if there is one.
+    ///
+    pub fn get(&self, fingerprint: u64) -> Option<Arc<StoredPolicy>> {
+        self.inner
+            .read()
+            .unwrap()
+            .policies
+            .get(&fingerprint)
+            .cloned()
+    }
+
+    ///
+    /// Returns `true` if an equal policy is in the store.
+    ///
+    pub fn contains(&self, policy: &Policy) -> bool {
+        self.get(fingerprint(policy))
+            .map(|existing| &existing.policy() == policy)
+            .unwrap_or_default()
+    }
+
+    ///
+    /// Returns the interned copy of `s`, adding it to the store if it is not present.
+    ///
+    pub fn intern(&self, s: &str) -> Arc<str> {
+        if let Some(existing) = self.inner.read().unwrap().strings.get(s) {
+            return existing.clone();
+        }
+        self.inner.write().unwrap().intern(s)
+    }
+
+    /// Returns the number of distinct policies in the store.
+    pub fn len(&self) -> usize {
+        self.inner.read().unwrap().policies.len()
+    }
+
+    /// Returns `true` if the store contains no policies.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of distinct strings interned by the store.
+    pub fn interned_len(&self) -> usize {
+        self.inner.read().unwrap().strings.len()
+    }
+
+    /// Returns all the policies in the store, in no particular order.
+    pub fn policies(&self) -> Vec<Arc<StoredPolicy>> {
+        self.inner
+            .read()
+            .unwrap()
+            .policies
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    ///
+    /// Returns all the policies in the store that list `action`, exactly, in an `Action` or
+    /// `NotAction` element.
+    ///
+    pub fn policies_with_action(&self, action: &str) -> Vec<Arc<StoredPolicy>> {
+        self.inner
+            .read()
+            .unwrap()
+            .policies
+            .values()
+            .filter(|stored| stored.actions().iter().any(|a| a.as_ref() == action))
+            .cloned()
+            .collect()
+    }
+
+    /// Remove all policies, and interned strings, from the store.
+    pub fn clear(&self) {
+        let mut inner = self.inner.write().unwrap();
+        inner.policies.clear();
+        inner.strings.clear();
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl StoredPolicy {
+    /// Returns the fingerprint of the policy, see `fingerprint`.
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    ///
+    /// Returns the stored policy; this is rebuilt from the stored document on each call, so
+    /// callers that use it repeatedly should keep the result.
+    ///
+    pub fn policy(&self) -> Policy {
+        Policy::from_json_with(&Value::from(&self.document), ArnParseMode::Permissive)
+            .expect("a stored policy document is always valid")
+    }
+
+    ///
+    /// Returns the distinct actions listed in the policy's `Action` and `NotAction` elements,
+    /// `*` included, in the order first seen.
+    ///
+    pub fn actions(&self) -> Vec<Arc<str>> {
+        let mut actions: Vec<Arc<str>> = Default::default();
+        for statement in self.statements() {
+            for name in &[ACTION_VALUE_ACTION, ACTION_VALUE_NOT_ACTION] {
+                push_strings(&mut actions, statement.get(name));
+            }
+        }
+        actions
+    }
+
+    ///
+    /// Returns the distinct resources listed in the policy's `Resource` and `NotResource`
+    /// elements, `*` included, in the order first seen.
+    ///
+    pub fn resources(&self) -> Vec<Arc<str>> {
+        let mut resources: Vec<Arc<str>> = Default::default();
+        for statement in self.statements() {
+            for name in &[RESOURCE_VALUE_RESOURCE, RESOURCE_VALUE_NOT_RESOURCE] {
+                push_strings(&mut resources, statement.get(name));
+            }
+        }
+        resources
+    }
+
+    /// Returns the distinct condition keys tested by the policy, in the order first seen.
+    pub fn condition_keys(&self) -> Vec<Arc<str>> {
+        let mut condition_keys: Vec<Arc<str>> = Default::default();
+        for statement in self.statements() {
+            if let Some(Node::Object(operators)) = statement.get(CONDITION_NAME) {
+                for (_, matches) in operators {
+                    if let Node::Object(keys) = matches {
+                        for (key, _) in keys {
+                            push_distinct(&mut condition_keys, key.clone());
+                        }
+                    }
+                }
+            }
+        }
+        condition_keys
+    }
+
+    fn statements(&self) -> impl Iterator<Item = &Node> {
+        match self.document.get(STATEMENT_NAME) {
+            Some(Node::Array(statements)) => statements.iter(),
+            _ => [].iter(),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl StoreInner {
+    fn intern(&mut self, s: &str) -> Arc<str> {
+        if let Some(existing) = self.strings.get(s) {
+            return existing.clone();
+        }
+        let interned: Arc<str> = Arc::from(s);
+        let _ = self.strings.insert(interned.clone());
+        interned
+    }
+
+    fn stored(&mut self, fingerprint: u64, policy: &Policy) -> StoredPolicy {
+        let document = policy
+            .to_json()
+            .expect("a policy can always be written as JSON");
+        StoredPolicy {
+            fingerprint,
+            document: self.node(document),
+        }
+    }
+
+    fn node(&mut self, value: Value) -> Node {
+        match value {
+            Value::Null => Node::Null,
+            Value::Bool(v) => Node::Bool(v),
+            Value::Number(v) => Node::Number(v),
+            Value::String(v) => Node::String(self.intern(&v)),
+            Value::Array(vs) => Node::Array(vs.into_iter().map(|v| self.node(v)).collect()),
+            Value::Object(vs) => Node::Object(
+                vs.into_iter()
+                    .map(|(k, v)| (self.intern(&k), self.node(v)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl From<&Node> for Value {
+    fn from(node: &Node) -> Self {
+        match node {
+            Node::Null => Value::Null,
+            Node::Bool(v) => Value::Bool(*v),
+            Node::Number(v) => Value::Number(v.clone()),
+            Node::String(v) => Value::String(v.to_string()),
+            Node::Array(vs) => Value::Array(vs.iter().map(Value::from).collect()),
+            Node::Object(vs) => Value::Object(
+                vs.iter()
+                    .map(|(k, v)| (k.to_string(), Value::from(v)))
+                    .collect::<Map<String, Value>>(),
+            ),
+        }
+    }
+}
+
+impl Node {
+    fn get(&self, key: &str) -> Option<&Node> {
+        match self {
+            Node::Object(vs) => vs.iter().find(|(k, _)| k.as_ref() == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Add the string, or each string in the array, `node` to `values`.
+///
+fn push_strings(values: &mut Vec<Arc<str>>, node: Option<&Node>) {
+    match node {
+        Some(Node::String(value)) => push_distinct(values, value.clone()),
+        Some(Node::Array(nodes)) => {
+            for node in nodes {
+                push_strings(values, Some(node));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn push_distinct(values: &mut Vec<Arc<str>>, value: Arc<str>) {
+    if !values.iter().any(|v| Arc::ptr_eq(v, &value)) {
+        values.push(value);
+    }
+}
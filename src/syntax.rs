@@ -4,12 +4,14 @@ More detailed description, with
 # Example
  */
 
+use crate::context::keys::GLOBAL_CONDITION_KEYS;
 use crate::error::{type_mismatch, IamFormatError};
+use crate::model::GlobalOperator;
+#[cfg(feature = "service_config")]
+use crate::service::actions::{service_metadata, ActionMetadata};
 use aws_arn::ARN;
 use serde_json::{Map, Value};
-use std::collections::HashMap;
-use std::fmt::Display;
-use std::iter::FromIterator;
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
 // ------------------------------------------------------------------------------------------------
@@ -96,6 +98,12 @@ pub const CONDITION_NAME: &str = "Condition";
 
 pub const CONDITION_VALUE_NAME: &str = "Value";
 
+///
+/// An extension element, not part of the IAM policy grammar, for annotations on a policy or a
+/// statement; see `model::Comments`.
+///
+pub const COMMENT_NAME: &str = "x-comment";
+
 pub const CONDITION_QUANTIFIER_FOR_ANY: &str = "ForAnyValue";
 pub const CONDITION_QUANTIFIER_FOR_ALL: &str = "ForAllValues";
 
@@ -198,12 +206,51 @@ pub const VALUE_NAME: &str = "Value";
 
 pub const ARN_NAME: &str = "ARN";
 
+pub const ARN_PATTERN_NAME: &str = "ArnPattern";
+
+pub const PARTITION_NAME: &str = "Partition";
+
+pub const REGION_NAME: &str = "Region";
+
+pub const RESOURCE_PATH_SEPARATOR: char = '/';
+
 pub const CHAR_WILD: char = '?';
 
 pub const CHAR_WILD_ALL: char = '*';
 
 pub const HOSTNAME_SEPARATOR: char = '.';
 
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Return the condition operators documented by IAM, for editors and other tools that offer
+/// completions; each has a `description`. The quantifiers and the `IfExists` suffix may be
+/// combined with any of these.
+///
+pub fn all_condition_operators() -> &'static [GlobalOperator] {
+    GlobalOperator::KNOWN
+}
+
+///
+/// Return the global condition keys, each with its description, for editors and other tools
+/// that offer completions; the tag keys end in `/` and are completed with a tag key name.
+///
+pub fn all_global_condition_keys() -> &'static [(&'static str, &'static str)] {
+    GLOBAL_CONDITION_KEYS
+}
+
+///
+/// Return the known actions of the service `namespace`, such as `s3`, from the catalog in
+/// [`service::actions`](../service/actions/index.html); this is empty if the service is not
+/// in the catalog.
+///
+#[cfg(feature = "service_config")]
+pub fn all_actions_for_service(namespace: &str) -> &'static [ActionMetadata] {
+    service_metadata(namespace).unwrap_or_default()
+}
+
 // ------------------------------------------------------------------------------------------------
 // Implementations
 // ------------------------------------------------------------------------------------------------
@@ -261,25 +308,6 @@ pub(crate) fn json_type_name(v: &Value) -> String {
 //     Ok(Value::Object(object))
 // }
 
-#[inline]
-pub(crate) fn display_vec_map_to_json<K, V>(
-    map: &HashMap<K, Vec<V>>,
-) -> Result<Value, IamFormatError>
-where
-    K: Display,
-    V: Display,
-{
-    let result: Result<Vec<(String, Value)>, IamFormatError> = map
-        .iter()
-        .map(|(k, v)| match display_vec_to_json(v) {
-            Ok(v) => Ok((k.to_string(), v)),
-            Err(e) => Err(e),
-        })
-        .collect();
-    let object = Map::from_iter(result?.into_iter());
-    Ok(Value::Object(object))
-}
-
 // #[inline]
 // pub(crate) fn map_to_json<K, V>(map: &HashMap<K, V>) -> Result<Value, IamFormatError>
 // where
@@ -337,32 +365,6 @@ where
 
 // ------------------------------------------------------------------------------------------------
 
-#[inline]
-pub(crate) fn string_vec_from_json<T>(value: &Value, name: &str) -> Result<Vec<T>, IamFormatError>
-where
-    T: From<String>,
-{
-    if let Value::String(s) = value {
-        Ok(vec![s.clone().into()])
-    } else if let Value::Array(arr) = value {
-        arr.iter()
-            .map(|v| {
-                if let Value::String(s) = v {
-                    Ok(s.clone().into())
-                } else {
-                    Err(type_mismatch(
-                        name,
-                        JSON_TYPE_NAME_STRING,
-                        json_type_name(value),
-                    ))
-                }
-            })
-            .collect()
-    } else {
-        type_mismatch(name, JSON_TYPE_NAME_ARRAY, json_type_name(value)).into()
-    }
-}
-
 #[inline]
 pub(crate) fn vec_from_str_json<V, E>(value: &Value, name: &str) -> Result<Vec<V>, IamFormatError>
 where
@@ -403,6 +405,62 @@ where
     }
 }
 
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Format `value` as a compact JSON fragment, used to implement `Display` for types that only
+/// have a JSON representation.
+///
+pub(crate) fn value_to_fragment<T>(value: &T, f: &mut Formatter<'_>) -> std::fmt::Result
+where
+    T: IamValue,
+{
+    let value = value.to_json().map_err(|_| std::fmt::Error)?;
+    write!(f, "{}", value)
+}
+
+///
+/// Parse a JSON fragment created by `value_to_fragment`, used to implement `FromStr`.
+///
+pub(crate) fn value_from_fragment<T>(s: &str) -> Result<T, IamFormatError>
+where
+    T: IamValue,
+{
+    let value: Value = serde_json::from_str(s)?;
+    T::from_json(&value)
+}
+
+///
+/// Format `property` as a compact JSON object fragment containing only the property, for
+/// example `{"Effect":"Allow"}`, used to implement `Display` for properties of a statement.
+///
+pub(crate) fn property_to_fragment<T>(property: &T, f: &mut Formatter<'_>) -> std::fmt::Result
+where
+    T: IamProperty,
+{
+    let mut object = Map::default();
+    property
+        .into_json_object(&mut object)
+        .map_err(|_| std::fmt::Error)?;
+    write!(f, "{}", Value::Object(object))
+}
+
+///
+/// Parse a JSON object fragment created by `property_to_fragment`, used to implement
+/// `FromStr`.
+///
+pub(crate) fn property_from_fragment<T>(s: &str, name: &str) -> Result<T, IamFormatError>
+where
+    T: IamProperty,
+{
+    let value: Value = serde_json::from_str(s)?;
+    if let Value::Object(object) = value {
+        T::from_json_object(&object)
+    } else {
+        type_mismatch(name, JSON_TYPE_NAME_OBJECT, json_type_name(&value)).into()
+    }
+}
+
 // ------------------------------------------------------------------------------------------------
 // Modules
 // ------------------------------------------------------------------------------------------------
@@ -0,0 +1,359 @@
+/*!
+Provides policy templates, documents containing named placeholders that are replaced with
+values to produce a policy.
+
+A placeholder has the form `{{name}}`, where the name is made up of ASCII letters, digits, and
+underscores and does not start with a digit; whitespace is allowed within the braces. These
+are distinct from IAM policy variables, such as `${aws:username}`, which are left in place to
+be evaluated by IAM. Placeholders may only appear within JSON string values.
+
+When a template is read it is checked as a policy with each placeholder treated as a policy
+variable, so that a placeholder is accepted anywhere a variable is, including as any component
+of an ARN in the `Resource` and `Principal` elements. When a template is rendered every
+placeholder must be given a value, and every value given must be used by a placeholder.
+
+# Example
+
+```rust
+use aws_iam::template::PolicyTemplate;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+let template = PolicyTemplate::from_str(
+    r#"{"Statement":[{
+        "Effect":"Allow",
+        "Action":"s3:GetObject",
+        "Resource":"arn:aws:s3:::{{bucket_name}}/home/${aws:username}/notes.txt"
+    }]}"#,
+)
+.unwrap();
+assert_eq!(template.parameters(), &["bucket_name"]);
+
+let mut params: HashMap<String, &str> = Default::default();
+let _ = params.insert("bucket_name".to_string(), "examplebucket");
+let policy = template.render(&params).unwrap();
+assert_eq!(
+    policy.statement[0].resource().unwrap().to_string(),
+    r#"{"Resource":"arn:aws:s3:::examplebucket/home/${aws:username}/notes.txt"}"#
+);
+```
+
+# Handlebars Templates
+
+With the feature `templating`, a [`HandlebarsTemplate`](struct.HandlebarsTemplate.html) is
+rendered by the [Handlebars](https://handlebarsjs.com/guide/) engine, so a template may also
+use block helpers, such as `{{#each}}` and `{{#if}}`, to generate statements or values from a
+map of values; this is the form used by many repositories that keep policies as templates.
+Every value used must be provided, and values written with `{{name}}` are escaped as JSON
+string content, while `{{{name}}}` writes the value unchanged. The rendered text is only then
+parsed as a policy, and errors in the template, in rendering, or in the rendered policy are
+reported with the line of the template that produced them, where it is known.
+
+```rust
+# #[cfg(feature = "templating")]
+# {
+use aws_iam::template::HandlebarsTemplate;
+use serde_json::json;
+use std::str::FromStr;
+
+let template = HandlebarsTemplate::from_str(
+    r#"{"Statement":[{
+        "Effect":"Allow",
+        "Action":"s3:GetObject",
+        "Resource":[
+          {{#each buckets}}
+          "arn:aws:s3:::{{this}}/2020.csv"{{#unless @last}},{{/unless}}
+          {{/each}}
+        ]
+    }]}"#,
+)
+.unwrap();
+
+let policy = template
+    .render(&json!({"buckets": ["reports", "archive"]}))
+    .unwrap();
+assert!(policy.statement[0]
+    .resource()
+    .unwrap()
+    .to_string()
+    .contains("arn:aws:s3:::archive/2020.csv"));
+# }
+```
+*/
+
+use crate::arn::ArnParseMode;
+use crate::error::{IamError, TemplateError};
+use crate::io;
+use crate::model::Policy;
+#[cfg(feature = "templating")]
+use crate::syntax::IamValue;
+#[cfg(feature = "templating")]
+use handlebars::Handlebars;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+#[cfg(feature = "templating")]
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A policy document containing placeholders, see the module documentation.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyTemplate {
+    text: String,
+    parameters: Vec<String>,
+}
+
+///
+/// A policy document rendered by the Handlebars engine, see the module documentation.
+///
+#[cfg(feature = "templating")]
+#[derive(Debug, Clone)]
+pub struct HandlebarsTemplate {
+    registry: Handlebars<'static>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for PolicyTemplate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl FromStr for PolicyTemplate {
+    type Err = IamError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parameters: Vec<String> = Default::default();
+        for captures in PLACEHOLDER_SYNTAX.captures_iter(s) {
+            let name = &captures[1];
+            if !parameters.iter().any(|p| p == name) {
+                parameters.push(name.to_string());
+            }
+        }
+
+        let remainder = PLACEHOLDER_SYNTAX.replace_all(s, "");
+        if let Some(index) = remainder.find("{{") {
+            let value: String = remainder[index..]
+                .chars()
+                .take_while(|c| !c.is_whitespace() && *c != '"')
+                .collect();
+            return Err(TemplateError::InvalidPlaceholder { value }.into());
+        }
+
+        // placeholders are treated as variables, which are tolerated in ARN positions.
+        let checked = PLACEHOLDER_SYNTAX.replace_all(s, "$${$1}");
+        let value: Value = serde_json::from_str(&checked)?;
+        let _ = Policy::from_json_with(&value, ArnParseMode::Permissive)?;
+
+        Ok(Self {
+            text: s.to_string(),
+            parameters,
+        })
+    }
+}
+
+impl PolicyTemplate {
+    ///
+    /// Returns the names of the placeholders in this template, in the order they first appear.
+    ///
+    pub fn parameters(&self) -> &[String] {
+        &self.parameters
+    }
+
+    ///
+    /// Replace each placeholder with its value from `params` and parse the result as a policy.
+    /// Values are escaped as JSON string content, so may contain any character. It is an error
+    /// if a placeholder has no value, or if a value is not used by any placeholder.
+    ///
+    pub fn render<V>(&self, params: &HashMap<String, V>) -> Result<Policy, IamError>
+    where
+        V: Clone + Into<String>,
+    {
+        if let Some(name) = self.parameters.iter().find(|p| !params.contains_key(*p)) {
+            return Err(TemplateError::MissingParameter { name: name.clone() }.into());
+        }
+        let mut unexpected: Vec<&String> = params
+            .keys()
+            .filter(|name| !self.parameters.contains(name))
+            .collect();
+        unexpected.sort();
+        if let Some(name) = unexpected.first() {
+            return Err(TemplateError::UnexpectedParameter {
+                name: name.to_string(),
+            }
+            .into());
+        }
+
+        let rendered = PLACEHOLDER_SYNTAX.replace_all(&self.text, |captures: &Captures<'_>| {
+            let value: String = params.get(&captures[1]).unwrap().clone().into();
+            let quoted = Value::String(value).to_string();
+            quoted[1..quoted.len() - 1].to_string()
+        });
+        io::read_from_string(&rendered)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(feature = "templating")]
+impl FromStr for HandlebarsTemplate {
+    type Err = IamError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut registry = Handlebars::new();
+        registry.set_strict_mode(true);
+        registry.register_escape_fn(escape_json_string);
+        registry
+            .register_template_string(TEMPLATE_NAME, mark_lines(s))
+            .map_err(|e| TemplateError::Syntax {
+                line: e.pos().map(|(line, _)| line),
+                message: e.reason().to_string(),
+            })?;
+        Ok(Self { registry })
+    }
+}
+
+#[cfg(feature = "templating")]
+impl HandlebarsTemplate {
+    ///
+    /// Render the template with `values`, usually a map or a `serde_json::Value`, and parse the
+    /// result as a policy.
+    ///
+    pub fn render<T>(&self, values: &T) -> Result<Policy, IamError>
+    where
+        T: Serialize,
+    {
+        let (text, lines) = self.render_marked(values)?;
+        let value: Value =
+            serde_json::from_str(&text).map_err(|e| TemplateError::InvalidPolicy {
+                line: lines.get(e.line().saturating_sub(1)).copied().flatten(),
+                source: Box::new(e.into()),
+            })?;
+        Ok(
+            Policy::from_json(&value).map_err(|e| TemplateError::InvalidPolicy {
+                line: None,
+                source: Box::new(e.into()),
+            })?,
+        )
+    }
+
+    ///
+    /// Render the template with `values`, returning the text without parsing it.
+    ///
+    pub fn render_to_string<T>(&self, values: &T) -> Result<String, IamError>
+    where
+        T: Serialize,
+    {
+        Ok(self.render_marked(values)?.0)
+    }
+
+    ///
+    /// Returns the rendered text, without markers, and the template line of each of its lines.
+    ///
+    fn render_marked<T>(&self, values: &T) -> Result<(String, Vec<Option<usize>>), IamError>
+    where
+        T: Serialize,
+    {
+        let rendered =
+            self.registry
+                .render(TEMPLATE_NAME, values)
+                .map_err(|e| TemplateError::Render {
+                    line: e.line_no,
+                    message: e.reason().to_string(),
+                })?;
+        Ok(unmark_lines(&rendered))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+lazy_static! {
+    static ref PLACEHOLDER_SYNTAX: Regex =
+        Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap();
+}
+
+#[cfg(feature = "templating")]
+const TEMPLATE_NAME: &str = "policy";
+
+#[cfg(feature = "templating")]
+const LINE_MARKER_START: char = '\u{E000}';
+
+#[cfg(feature = "templating")]
+const LINE_MARKER_END: char = '\u{E001}';
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(feature = "templating")]
+fn escape_json_string(value: &str) -> String {
+    let quoted = Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+///
+/// Append a marker, holding its line number, to each line of `template` that does not end
+/// within a Handlebars expression. Each rendered line then carries the number of the template
+/// line that produced it, however many times a block repeats it.
+///
+#[cfg(feature = "templating")]
+fn mark_lines(template: &str) -> String {
+    let mut marked = String::with_capacity(template.len() * 2);
+    let mut depth: isize = 0;
+    for (index, line) in template.split('\n').enumerate() {
+        if index > 0 {
+            marked.push('\n');
+        }
+        marked.push_str(line);
+        depth += line.matches("{{").count() as isize - line.matches("}}").count() as isize;
+        if depth <= 0 {
+            depth = 0;
+            marked.push(LINE_MARKER_START);
+            marked.push_str(&(index + 1).to_string());
+            marked.push(LINE_MARKER_END);
+        }
+    }
+    marked
+}
+
+///
+/// Remove the markers added by `mark_lines`, returning the text and, for each of its lines,
+/// the template line of the first marker on it, if any.
+///
+#[cfg(feature = "templating")]
+fn unmark_lines(rendered: &str) -> (String, Vec<Option<usize>>) {
+    let mut text = String::with_capacity(rendered.len());
+    let mut lines = Vec::new();
+    for (index, line) in rendered.split('\n').enumerate() {
+        if index > 0 {
+            text.push('\n');
+        }
+        let mut template_line = None;
+        let mut rest = line;
+        while let Some(start) = rest.find(LINE_MARKER_START) {
+            text.push_str(&rest[..start]);
+            let marker = &rest[start + LINE_MARKER_START.len_utf8()..];
+            let end = marker.find(LINE_MARKER_END).unwrap_or(marker.len());
+            if template_line.is_none() {
+                template_line = marker[..end].parse().ok();
+            }
+            rest = &marker[(end + LINE_MARKER_END.len_utf8()).min(marker.len())..];
+        }
+        text.push_str(rest);
+        lines.push(template_line);
+    }
+    (text, lines)
+}
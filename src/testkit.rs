@@ -0,0 +1,268 @@
+/*!
+Provides helpers for testing with corpora of policy documents, enabled by the feature
+`testkit`. These are used by the tests of this crate, and may be used by downstream crates to
+check the documents they generate.
+
+A [`Corpus`](struct.Corpus.html) is a set of _good_ documents, each of which must be read and
+written back without change to the policy, and _bad_ documents, each of which must fail to be
+read. A bad document may have a sibling file with the extension `txt` containing text that must
+appear in the error message. The corpus of this crate, in `tests/data`, is returned by
+[`Corpus::crate_corpus`](struct.Corpus.html#method.crate_corpus) and other documents may be
+added to it, or to an empty corpus.
+
+Golden files are supported by [`assert_golden`](fn.assert_golden.html), which compares text
+with the content of a file; setting the environment variable `AWS_IAM_UPDATE_GOLDEN` writes the
+text to the file instead, to create or update it.
+
+# Example
+
+```rust
+use aws_iam::testkit::Corpus;
+
+let corpus = Corpus::crate_corpus().unwrap();
+let failures = corpus.check();
+assert!(failures.is_empty(), "{:#?}", failures);
+```
+*/
+
+use crate::error::IamError;
+use crate::io;
+use crate::model::Policy;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A set of good and bad policy documents, see the module documentation.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Corpus {
+    good: Vec<PathBuf>,
+    bad: Vec<PathBuf>,
+}
+
+///
+/// A document in a corpus which did not behave as expected.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorpusFailure {
+    /// The path of the document.
+    pub path: PathBuf,
+    /// A description of the failure.
+    pub message: String,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+/// Set this environment variable to write, rather than compare, golden files.
+pub const UPDATE_GOLDEN_VARIABLE: &str = "AWS_IAM_UPDATE_GOLDEN";
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns the JSON files, those with the extension `json`, in the directory `dir` sorted by
+/// name. Sub-directories are not included.
+///
+pub fn json_files(dir: &Path) -> Result<Vec<PathBuf>, IamError> {
+    let mut files: Vec<PathBuf> = Default::default();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().map(|e| e == "json").unwrap_or_default() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+///
+/// Check that `policy` is unchanged when written and read back, and that writing it again
+/// produces the same document, returning the written document.
+///
+pub fn check_round_trip(policy: &Policy) -> Result<String, String> {
+    let written = io::to_string(policy, true).map_err(|e| e.to_string())?;
+    let read = io::read_from_string(&written)
+        .map_err(|e| format!("the written document could not be read: {}", e))?;
+    if &read != policy {
+        return Err(format!(
+            "the policy changed when written and read back:\n{}",
+            written
+        ));
+    }
+    let rewritten = io::to_string(&read, true).map_err(|e| e.to_string())?;
+    if rewritten != written {
+        return Err(format!(
+            "the document changed when written a second time:\n{}\n{}",
+            written, rewritten
+        ));
+    }
+    Ok(written)
+}
+
+///
+/// Panics if `policy` does not round-trip, see `check_round_trip`.
+///
+pub fn assert_round_trip(policy: &Policy) {
+    if let Err(message) = check_round_trip(policy) {
+        panic!("{}", message);
+    }
+}
+
+///
+/// Panics if `actual` is not the same as the content of the golden file at `path`. If the
+/// environment variable `AWS_IAM_UPDATE_GOLDEN` is set `actual` is written to the file instead.
+///
+pub fn assert_golden(path: &Path, actual: &str) {
+    if std::env::var_os(UPDATE_GOLDEN_VARIABLE).is_some() {
+        if let Err(e) = fs::write(path, actual) {
+            panic!("could not write golden file {:?}: {}", path, e);
+        }
+        return;
+    }
+    match fs::read_to_string(path) {
+        Ok(expected) => assert_eq!(
+            actual, expected,
+            "the golden file {:?} differs, set {} to update it",
+            path, UPDATE_GOLDEN_VARIABLE
+        ),
+        Err(e) => panic!(
+            "could not read golden file {:?}, set {} to create it: {}",
+            path, UPDATE_GOLDEN_VARIABLE, e
+        ),
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for CorpusFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+impl Corpus {
+    ///
+    /// Returns the corpus of this crate, the documents in `tests/data/good` and
+    /// `tests/data/bad` of the crate's source directory.
+    ///
+    pub fn crate_corpus() -> Result<Self, IamError> {
+        Self::from_dir(
+            &Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("data"),
+        )
+    }
+
+    ///
+    /// Returns the documents in the `good` and `bad` sub-directories of `dir`; either may be
+    /// missing.
+    ///
+    pub fn from_dir(dir: &Path) -> Result<Self, IamError> {
+        let mut corpus = Self::default();
+        let _ = corpus.add_dir(dir)?;
+        Ok(corpus)
+    }
+
+    ///
+    /// Add the documents in the `good` and `bad` sub-directories of `dir`; either may be
+    /// missing.
+    ///
+    pub fn add_dir(&mut self, dir: &Path) -> Result<&mut Self, IamError> {
+        let good = dir.join("good");
+        if good.is_dir() {
+            self.good.extend(json_files(&good)?);
+        }
+        let bad = dir.join("bad");
+        if bad.is_dir() {
+            self.bad.extend(json_files(&bad)?);
+        }
+        Ok(self)
+    }
+
+    /// Add a document that must be read, and round-trip, successfully.
+    pub fn add_good<P>(&mut self, path: P) -> &mut Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.good.push(path.into());
+        self
+    }
+
+    /// Add a document that must fail to be read.
+    pub fn add_bad<P>(&mut self, path: P) -> &mut Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.bad.push(path.into());
+        self
+    }
+
+    /// Returns the documents that must be read successfully.
+    pub fn good(&self) -> &[PathBuf] {
+        &self.good
+    }
+
+    /// Returns the documents that must fail to be read.
+    pub fn bad(&self) -> &[PathBuf] {
+        &self.bad
+    }
+
+    /// Returns `true` if there are no documents in this corpus.
+    pub fn is_empty(&self) -> bool {
+        self.good.is_empty() && self.bad.is_empty()
+    }
+
+    ///
+    /// Check every document in the corpus, returning those that did not behave as expected.
+    ///
+    pub fn check(&self) -> Vec<CorpusFailure> {
+        let failures = self
+            .good
+            .iter()
+            .filter_map(|path| check_good(path).err().map(|m| (path, m)));
+        let bad_failures = self
+            .bad
+            .iter()
+            .filter_map(|path| check_bad(path).err().map(|m| (path, m)));
+        failures
+            .chain(bad_failures)
+            .map(|(path, message)| CorpusFailure {
+                path: path.clone(),
+                message,
+            })
+            .collect()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn check_good(path: &Path) -> Result<(), String> {
+    let policy = io::read_from_file(path).map_err(|e| e.to_string())?;
+    check_round_trip(&policy).map(|_| ())
+}
+
+fn check_bad(path: &Path) -> Result<(), String> {
+    match io::read_from_file(path) {
+        Ok(_) => Err("the document was read successfully".to_string()),
+        Err(e) => match fs::read_to_string(path.with_extension("txt")) {
+            Ok(expected) if !e.to_string().contains(expected.trim()) => Err(format!(
+                "expected an error containing {:?}, not {:?}",
+                expected.trim(),
+                e.to_string()
+            )),
+            _ => Ok(()),
+        },
+    }
+}
@@ -0,0 +1,826 @@
+/*!
+Provides validation of policies beyond the syntax checks performed when reading a policy
+document. Many constraints depend on the kind of policy being validated, for example the
+`Resource` element is required in an identity-based policy but optional in a resource-based
+policy; these are described by [`PolicyType`](enum.PolicyType.html).
+
+Validation does not fail on the first problem, it returns a set of
+[`Findings`](struct.Findings.html) which may be errors, that make the policy invalid for the
+selected type, or warnings.
+
+Duplicate keys are reported as warnings: condition keys repeated under one operator that differ
+only in case, which IAM treats as the same key, and, when validating a document with
+[`validate_str`](fn.validate_str.html), any key repeated within a JSON object, of which only the
+last value would otherwise be used. Condition operators that are not known to this crate, which
+are only read with `ArnParseMode::Permissive`, see `ValidationOptions::with_parse_mode`, are
+also reported as warnings, as they may be service-specific, as are condition values that do not have the form required by their operator. Statements that share
+a `Sid` are reported as errors, as IAM rejects such policies; `Policy::rename_duplicate_sids`
+renames them.
+
+Role trust policies are also checked for common confused-deputy mistakes: principals in other
+accounts that are not required to provide an `sts:ExternalId`, service principals without an
+`aws:SourceArn` or `aws:SourceAccount` condition, and public principals. Which principals are
+in other accounts can only be determined if the role's account is set with
+`ValidationOptions::with_account`.
+
+With the feature `service_config`, the configuration of services may be provided by
+`ValidationOptions::with_services`; a condition on a key that none of the resources in its
+statement support, such as an object tag key on a bucket, can never apply and is reported as a
+warning.
+
+An organization's own guardrails, such as conditions required on every statement that allows
+some set of actions, may be written as a [`Baseline`](../baseline/struct.Baseline.html) and
+enforced by `ValidationOptions::with_baseline`.
+
+# Example
+
+```rust
+use aws_iam::model::{Policy, Statement};
+use aws_iam::validate::{PolicyType, ValidationOptions};
+
+let mut statement = Statement::unnamed();
+statement.unset_resource();
+let policy = Policy::from(statement);
+
+assert!(policy
+    .validate(&ValidationOptions::for_type(PolicyType::Resource))
+    .is_valid());
+assert!(!policy
+    .validate(&ValidationOptions::for_type(PolicyType::Identity))
+    .is_valid());
+```
+*/
+
+use crate::arn::{arn_account, AccountIdentifier, ArnParseMode, Partition};
+use crate::baseline::Baseline;
+use crate::error::{IamError, IamFormatError};
+use crate::model::condition::GlobalConditionKey;
+use crate::model::{Effect, OrAny, Policy, Principal, SidProfile, Statement, Version};
+#[cfg(feature = "service_config")]
+use crate::model::{Resource, ResourceEntry};
+#[cfg(feature = "service_config")]
+use crate::service::ServiceConfig;
+use crate::syntax::STATEMENT_NAME;
+use serde::de::{DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::ops::Deref;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The kind of policy being validated; from [Policies and permissions in
+/// IAM](https://docs.aws.amazon.com/IAM/latest/UserGuide/access_policies.html).
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PolicyType {
+    /// Attached to an IAM identity (user, group, or role); the principal is implied.
+    #[default]
+    Identity,
+    /// Attached to a resource such as an S3 bucket or SQS queue.
+    Resource,
+    /// A role trust policy, the resource-based policy that controls who may assume the role.
+    Trust,
+    /// A permissions boundary, setting the maximum permissions of an identity.
+    PermissionsBoundary,
+    /// An AWS Organizations service control policy (SCP).
+    ServiceControl,
+    /// A VPC endpoint policy.
+    Endpoint,
+}
+
+///
+/// The options used to validate a policy.
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationOptions {
+    /// The kind of policy being validated.
+    pub policy_type: PolicyType,
+    /// The rules used to check `Sid` values, which depend on the service the policy targets.
+    pub sid_profile: SidProfile,
+    /// The account the policy belongs to, used to identify principals in other accounts.
+    pub account: Option<AccountIdentifier>,
+    /// The organization's baseline, if any, which every statement must meet.
+    pub baseline: Option<Baseline>,
+    /// The configuration of services, used to check that condition keys apply to resources.
+    #[cfg(feature = "service_config")]
+    pub services: Vec<ServiceConfig>,
+    /// How strictly `validate_str` parses the document; in `ArnParseMode::Permissive` unknown
+    /// condition operators are reported as findings rather than failing the parse.
+    pub parse_mode: ArnParseMode,
+}
+
+///
+/// The severity of a validation finding.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The policy is not valid for the selected policy type.
+    Error,
+    /// The policy is valid, but may not behave as intended.
+    Warning,
+    /// Informational only.
+    Info,
+}
+
+///
+/// A single problem found while validating a policy.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// How serious the problem is.
+    pub severity: Severity,
+    /// A stable identifier for the kind of problem, one of the `FINDING_*` constants.
+    pub code: &'static str,
+    /// A human-readable description of the problem.
+    pub message: String,
+    /// The index of the statement within the policy, if the problem is specific to one.
+    pub statement: Option<usize>,
+}
+
+///
+/// The set of findings from validating a policy.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Findings(Vec<Finding>);
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+/// A statement in a policy that requires it has no `Resource` or `NotResource` element.
+pub const FINDING_MISSING_RESOURCE: &str = "MissingResource";
+
+/// A statement contains policy variables, but the policy version is not `2012-10-17` and so
+/// they will be treated as literal strings.
+pub const FINDING_VARIABLES_REQUIRE_VERSION: &str = "VariablesRequireVersion";
+
+/// A statement's `Sid` contains characters not allowed by the selected `SidProfile`.
+pub const FINDING_INVALID_SID: &str = "InvalidSid";
+
+/// More than one statement in a policy has the same `Sid`, which IAM rejects; see
+/// `Policy::rename_duplicate_sids`.
+pub const FINDING_DUPLICATE_SID: &str = "DuplicateSid";
+
+/// The ARNs in a policy refer to more than one partition, such as `aws` and `aws-cn`.
+pub const FINDING_MIXED_PARTITIONS: &str = "MixedPartitions";
+
+/// A JSON object in the policy document contains the same key more than once; only the last
+/// value is used.
+pub const FINDING_DUPLICATE_KEY: &str = "DuplicateKey";
+
+/// A condition operator tests keys that differ only in case, which IAM treats as the same key.
+pub const FINDING_DUPLICATE_CONDITION_KEY: &str = "DuplicateConditionKey";
+
+/// A condition uses an operator that is not known, it may be service-specific or a typing
+/// mistake.
+pub const FINDING_UNKNOWN_CONDITION_OPERATOR: &str = "UnknownConditionOperator";
+
+/// A condition value does not have the form required by its operator, see
+/// `Condition::parse_warnings`.
+pub const FINDING_INVALID_CONDITION_VALUE: &str = "InvalidConditionValue";
+
+/// A trust policy allows any principal, `"*"`, to assume the role.
+pub const FINDING_TRUST_PUBLIC_PRINCIPAL: &str = "TrustPublicPrincipal";
+
+/// A trust policy allows a principal in another account to assume the role without requiring
+/// an `sts:ExternalId`, leaving the role open to the confused deputy problem.
+pub const FINDING_TRUST_MISSING_EXTERNAL_ID: &str = "TrustMissingExternalId";
+
+/// A trust policy allows a service principal to assume the role without an `aws:SourceArn` or
+/// `aws:SourceAccount` condition, so the service may assume it on behalf of any account.
+pub const FINDING_TRUST_MISSING_SOURCE: &str = "TrustMissingSource";
+
+/// A condition tests a key that none of the resources in its statement support, and so can
+/// never apply; only reported with the feature `service_config`.
+pub const FINDING_INAPPLICABLE_CONDITION_KEY: &str = "InapplicableConditionKey";
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Validate `policy` according to `options`, returning all findings.
+///
+pub fn validate_policy(policy: &Policy, options: &ValidationOptions) -> Findings {
+    let mut findings = Findings::default();
+    let version = policy.effective_version();
+    let mut sids: Vec<&str> = Default::default();
+    for (index, statement) in policy.statements().enumerate() {
+        validate_statement(statement, index, version, options, &mut findings);
+        if let Some(sid) = &statement.sid {
+            let sid: &str = sid;
+            if sids.contains(&sid) {
+                findings.push(Finding::error(
+                    FINDING_DUPLICATE_SID,
+                    format!("the Sid {:?} is used by an earlier statement", sid),
+                    Some(index),
+                ));
+            } else {
+                sids.push(sid);
+            }
+        }
+    }
+    let partitions = policy.referenced_partitions();
+    if partitions.len() > 1 {
+        findings.push(Finding::warning(
+            FINDING_MIXED_PARTITIONS,
+            format!(
+                "ARNs refer to more than one partition ({}), those not in the policy's own partition never match",
+                partitions
+                    .iter()
+                    .map(Partition::to_string)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            None,
+        ));
+    }
+    if let Some(baseline) = &options.baseline {
+        findings.0.extend(baseline.check(policy));
+    }
+    findings
+}
+
+///
+/// Parse the policy document `s`, according to the options' `parse_mode`, and validate it
+/// according to `options`, see `validate_policy`; additionally reporting any key repeated
+/// within a JSON object in the document, which is otherwise ignored by the parser.
+///
+pub fn validate_str(s: &str, options: &ValidationOptions) -> Result<Findings, IamError> {
+    let value: Value = serde_json::from_str(s).map_err(IamFormatError::from)?;
+    let policy = Policy::from_json_with(&value, options.parse_mode)?;
+    let mut findings = Findings::default();
+    let mut deserializer = serde_json::Deserializer::from_str(s);
+    DuplicateKeys::new(&mut findings).deserialize(&mut deserializer)?;
+    findings.0.extend(validate_policy(&policy, options));
+    Ok(findings)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Walks a JSON document reporting repeated object keys; `path` is the location of the value
+/// being visited, such as `Statement[1].Condition`, and `in_statement` is `true` for the value
+/// of the policy's `Statement` element.
+///
+struct DuplicateKeys<'a> {
+    findings: &'a mut Findings,
+    path: String,
+    statement: Option<usize>,
+    in_statement: bool,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for PolicyType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Identity => "identity-based",
+                Self::Resource => "resource-based",
+                Self::Trust => "trust",
+                Self::PermissionsBoundary => "permissions boundary",
+                Self::ServiceControl => "service control",
+                Self::Endpoint => "endpoint",
+            }
+        )
+    }
+}
+
+impl PolicyType {
+    /// Returns `true` if every statement in a policy of this type must include a `Resource`
+    /// or `NotResource` element.
+    pub fn requires_resource(&self) -> bool {
+        matches!(
+            self,
+            Self::Identity | Self::PermissionsBoundary | Self::ServiceControl | Self::Endpoint
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl ValidationOptions {
+    pub fn for_type(policy_type: PolicyType) -> Self {
+        Self {
+            policy_type,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_sid_profile(self, sid_profile: SidProfile) -> Self {
+        Self {
+            sid_profile,
+            ..self
+        }
+    }
+
+    pub fn with_account(self, account: AccountIdentifier) -> Self {
+        Self {
+            account: Some(account),
+            ..self
+        }
+    }
+
+    pub fn with_baseline(self, baseline: Baseline) -> Self {
+        Self {
+            baseline: Some(baseline),
+            ..self
+        }
+    }
+
+    #[cfg(feature = "service_config")]
+    pub fn with_services(self, services: Vec<ServiceConfig>) -> Self {
+        Self { services, ..self }
+    }
+
+    pub fn with_parse_mode(self, parse_mode: ArnParseMode) -> Self {
+        Self { parse_mode, ..self }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Error => "error",
+                Self::Warning => "warning",
+                Self::Info => "info",
+            }
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.statement {
+            Some(index) => write!(
+                f,
+                "{} [{}] statement {}: {}",
+                self.severity, self.code, index, self.message
+            ),
+            None => write!(f, "{} [{}]: {}", self.severity, self.code, self.message),
+        }
+    }
+}
+
+impl Finding {
+    pub fn error<S>(code: &'static str, message: S, statement: Option<usize>) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            severity: Severity::Error,
+            code,
+            message: message.into(),
+            statement,
+        }
+    }
+
+    pub fn warning<S>(code: &'static str, message: S, statement: Option<usize>) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            severity: Severity::Warning,
+            code,
+            message: message.into(),
+            statement,
+        }
+    }
+
+    pub fn info<S>(code: &'static str, message: S, statement: Option<usize>) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            severity: Severity::Info,
+            code,
+            message: message.into(),
+            statement,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Deref for Findings {
+    type Target = Vec<Finding>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Vec<Finding>> for Findings {
+    fn from(v: Vec<Finding>) -> Self {
+        Self(v)
+    }
+}
+
+impl IntoIterator for Findings {
+    type Item = Finding;
+    type IntoIter = std::vec::IntoIter<Finding>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl Findings {
+    /// Returns `true` if there are no error findings; warnings do not make a policy invalid.
+    pub fn is_valid(&self) -> bool {
+        !self.0.iter().any(Finding::is_error)
+    }
+
+    pub fn push(&mut self, finding: Finding) {
+        self.0.push(finding)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Finding> {
+        self.0.iter().filter(|f| f.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Finding> {
+        self.0.iter().filter(|f| f.severity == Severity::Warning)
+    }
+
+    pub fn into_inner(self) -> Vec<Finding> {
+        self.0
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Policy {
+    ///
+    /// Validate this policy according to `options`, see
+    /// [`validate_policy`](../validate/fn.validate_policy.html).
+    ///
+    pub fn validate(&self, options: &ValidationOptions) -> Findings {
+        validate_policy(self, options)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<'a> DuplicateKeys<'a> {
+    fn new(findings: &'a mut Findings) -> Self {
+        Self {
+            findings,
+            path: String::new(),
+            statement: None,
+            in_statement: false,
+        }
+    }
+
+    fn child(
+        &mut self,
+        path: String,
+        statement: Option<usize>,
+        in_statement: bool,
+    ) -> DuplicateKeys<'_> {
+        DuplicateKeys {
+            findings: self.findings,
+            path,
+            statement,
+            in_statement,
+        }
+    }
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for DuplicateKeys<'a> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for DuplicateKeys<'a> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "a JSON value")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_seq<A>(mut self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut index = 0;
+        loop {
+            let path = format!("{}[{}]", self.path, index);
+            let statement = if self.in_statement {
+                Some(index)
+            } else {
+                self.statement
+            };
+            if seq
+                .next_element_seed(self.child(path, statement, false))?
+                .is_none()
+            {
+                return Ok(());
+            }
+            index += 1;
+        }
+    }
+
+    fn visit_map<A>(mut self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let statement = if self.in_statement {
+            Some(0)
+        } else {
+            self.statement
+        };
+        let mut keys: HashSet<String> = Default::default();
+        while let Some(key) = map.next_key::<String>()? {
+            let path = if self.path.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", self.path, key)
+            };
+            if keys.contains(&key) {
+                self.findings.push(Finding::warning(
+                    FINDING_DUPLICATE_KEY,
+                    format!(
+                        "the key {} appears more than once, only the last value is used",
+                        path
+                    ),
+                    statement,
+                ));
+            }
+            let in_statement = self.path.is_empty() && key == STATEMENT_NAME;
+            map.next_value_seed(self.child(path, statement, in_statement))?;
+            let _ = keys.insert(key);
+        }
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Values
+// ------------------------------------------------------------------------------------------------
+
+const STS_CONDITION_KEY_EXTERNAL_ID: &str = "sts:ExternalId";
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn validate_statement(
+    statement: &Statement,
+    index: usize,
+    version: Version,
+    options: &ValidationOptions,
+    findings: &mut Findings,
+) {
+    if version != Version::V2012 && statement.has_variables() {
+        findings.push(Finding::warning(
+            FINDING_VARIABLES_REQUIRE_VERSION,
+            format!(
+                "policy variables are treated as literal strings in version {}, set the version to 2012-10-17 to use them as variables",
+                version
+            ),
+            Some(index),
+        ));
+    }
+    if let Some(sid) = &statement.sid {
+        if !sid.is_valid_for(options.sid_profile) {
+            findings.push(Finding::error(
+                FINDING_INVALID_SID,
+                format!(
+                    "the Sid {:?} is not valid for the {} profile",
+                    sid.to_string(),
+                    options.sid_profile
+                ),
+                Some(index),
+            ));
+        }
+    }
+    if statement.resource.is_none() && options.policy_type.requires_resource() {
+        findings.push(Finding::error(
+            FINDING_MISSING_RESOURCE,
+            format!(
+                "a Resource or NotResource element is required in a {} policy",
+                options.policy_type
+            ),
+            Some(index),
+        ));
+    }
+    if let Some(condition) = &statement.condition {
+        for (operator, matches) in condition.iter() {
+            if !operator.operator.is_known() {
+                findings.push(Finding::warning(
+                    FINDING_UNKNOWN_CONDITION_OPERATOR,
+                    format!("the condition operator {} is not known", operator),
+                    Some(index),
+                ));
+            }
+            let mut keys: HashSet<String> = Default::default();
+            for key in matches.keys() {
+                if !keys.insert(key.to_lowercase()) {
+                    findings.push(Finding::warning(
+                        FINDING_DUPLICATE_CONDITION_KEY,
+                        format!(
+                            "the operator {} tests the key {} more than once, keys are not case-sensitive",
+                            operator, key
+                        ),
+                        Some(index),
+                    ));
+                }
+            }
+        }
+    }
+    if let Some(condition) = &statement.condition {
+        for warning in condition.parse_warnings() {
+            findings.push(Finding::warning(
+                FINDING_INVALID_CONDITION_VALUE,
+                warning.to_string(),
+                Some(index),
+            ));
+        }
+    }
+    #[cfg(feature = "service_config")]
+    validate_condition_keys(statement, index, options, findings);
+    if options.policy_type == PolicyType::Trust {
+        validate_trust_statement(statement, index, options, findings);
+    }
+}
+
+///
+/// Report condition keys that describe resources, according to `options.services`, but that
+/// are supported by none of the resource types in the statement. Statements are skipped if any
+/// resource is not an ARN of a configured service with a known resource type, as the key may
+/// apply to it.
+///
+#[cfg(feature = "service_config")]
+fn validate_condition_keys(
+    statement: &Statement,
+    index: usize,
+    options: &ValidationOptions,
+    findings: &mut Findings,
+) {
+    let (condition, entries) = match (&statement.condition, &statement.resource) {
+        (Some(condition), Some(Resource::Resource(OrAny::Some(entries)))) => (condition, entries),
+        _ => return,
+    };
+    let mut resource_types = Vec::new();
+    for entry in entries {
+        let arn = match entry {
+            ResourceEntry::Arn(arn) => arn.to_string(),
+            _ => return,
+        };
+        let service = match arn.split(':').nth(2).and_then(|service| {
+            options
+                .services
+                .iter()
+                .find(|config| config.namespace.to_string() == service)
+        }) {
+            Some(service) => service,
+            None => return,
+        };
+        let types = service.resource_types_for(&arn);
+        if types.is_empty() {
+            return;
+        }
+        resource_types.push((service, types));
+    }
+    let mut reported: HashSet<String> = Default::default();
+    for key in condition.values().flat_map(|matches| matches.keys()) {
+        let key = key.to_string();
+        let is_resource_key = resource_types
+            .iter()
+            .any(|(service, _)| service.is_resource_condition_key(&key));
+        let is_supported = resource_types.iter().any(|(_, types)| {
+            types
+                .iter()
+                .any(|resource_type| resource_type.supports_condition_key(&key))
+        });
+        if is_resource_key && !is_supported && reported.insert(key.to_lowercase()) {
+            findings.push(Finding::warning(
+                FINDING_INAPPLICABLE_CONDITION_KEY,
+                format!(
+                    "the condition key {} is not supported by any resource in the statement, and so can never apply",
+                    key
+                ),
+                Some(index),
+            ));
+        }
+    }
+}
+
+///
+/// The rules specific to role trust policies, applied only to `Allow` statements. Principals
+/// are only considered to be in another account if `options.account` is set.
+///
+fn validate_trust_statement(
+    statement: &Statement,
+    index: usize,
+    options: &ValidationOptions,
+    findings: &mut Findings,
+) {
+    if statement.effect != Effect::Allow {
+        return;
+    }
+    if statement.principal_is_public() {
+        findings.push(Finding::warning(
+            FINDING_TRUST_PUBLIC_PRINCIPAL,
+            "any principal may assume this role, unless prevented by a condition",
+            Some(index),
+        ));
+    }
+    let map = match &statement.principal {
+        Some(Principal::Principal(OrAny::Some(map))) => map,
+        _ => return,
+    };
+    let has_key = |key: &str| {
+        statement
+            .condition
+            .as_ref()
+            .map(|condition| condition.tests_key(key))
+            .unwrap_or_default()
+    };
+    if let Some(account) = &options.account {
+        let others: Vec<String> = map
+            .aws_iter()
+            .filter_map(|principal| arn_account(&principal.to_string()))
+            .filter(|principal_account| principal_account != account)
+            .map(|principal_account| principal_account.to_string())
+            .collect();
+        if !others.is_empty() && !has_key(STS_CONDITION_KEY_EXTERNAL_ID) {
+            findings.push(Finding::warning(
+                FINDING_TRUST_MISSING_EXTERNAL_ID,
+                format!(
+                    "principals in other accounts ({}) may assume this role without a {} condition",
+                    others.join(", "),
+                    STS_CONDITION_KEY_EXTERNAL_ID
+                ),
+                Some(index),
+            ));
+        }
+    }
+    if map.service_iter().next().is_some()
+        && !has_key(&GlobalConditionKey::SourceArn.to_string())
+        && !has_key(&GlobalConditionKey::SourceAccount.to_string())
+    {
+        findings.push(Finding::warning(
+            FINDING_TRUST_MISSING_SOURCE,
+            format!(
+                "service principals may assume this role without a {} or {} condition",
+                GlobalConditionKey::SourceArn,
+                GlobalConditionKey::SourceAccount
+            ),
+            Some(index),
+        ));
+    }
+}
@@ -0,0 +1,432 @@
+use aws_iam::analysis::{
+    check_contradictions, check_expired, check_sensitive_actions, corpus_stats,
+    corpus_stats_for_directory, invert_to_guardrail, invert_to_guardrail_with_findings, risk_score,
+    stats, validity_window, who_can, RiskFactor, FINDING_DUPLICATE_STATEMENT,
+    FINDING_ESCALATION_PATH, FINDING_GUARDRAIL_CONDITION_DROPPED, FINDING_GUARDRAIL_NOT_ACTION,
+    FINDING_GUARDRAIL_OVERLAPPING_ACTIONS, FINDING_SENSITIVE_ACTION, FINDING_SHADOWED_ALLOW,
+    FINDING_STATEMENT_EXPIRED, MANAGED_POLICY_SIZE_LIMIT,
+};
+use aws_iam::arn::ARN;
+use aws_iam::io::{read_authorization_details, PolicySource};
+use aws_iam::model::{Policy, Version};
+use std::path::Path;
+use std::str::FromStr;
+
+fn is_denied(policy: &Policy, action: &str, resource: &str) -> bool {
+    let resource = ARN::from_str(resource).unwrap();
+    policy
+        .statement
+        .iter()
+        .any(|st| st.is_deny() && st.action().matches(action) && st.applies_to_resource(&resource))
+}
+
+#[test]
+fn test_invert_allow_list() {
+    let policy = Policy::from_str(
+        r#"{"Statement":[
+            {"Effect":"Allow","Action":["s3:GetObject","s3:ListBucket"],"Resource":["arn:aws:s3:::bucket","arn:aws:s3:::bucket/*"]},
+            {"Effect":"Allow","Action":"sqs:SendMessage","Resource":"arn:aws:sqs:us-east-1:123456789012:queue"},
+            {"Effect":"Deny","Action":"s3:DeleteBucket","Resource":"*"}
+        ]}"#,
+    )
+    .unwrap();
+    let (guardrail, findings) = invert_to_guardrail_with_findings(&policy);
+
+    assert!(findings.is_empty());
+    assert_eq!(guardrail.version, Some(Version::V2012));
+    assert_eq!(guardrail.statement.len(), 4);
+
+    assert!(!is_denied(
+        &guardrail,
+        "s3:GetObject",
+        "arn:aws:s3:::bucket/key"
+    ));
+    assert!(!is_denied(
+        &guardrail,
+        "sqs:SendMessage",
+        "arn:aws:sqs:us-east-1:123456789012:queue"
+    ));
+    assert!(is_denied(
+        &guardrail,
+        "s3:GetObject",
+        "arn:aws:s3:::other/key"
+    ));
+    assert!(is_denied(
+        &guardrail,
+        "s3:PutObject",
+        "arn:aws:s3:::bucket/key"
+    ));
+    assert!(is_denied(
+        &guardrail,
+        "sqs:SendMessage",
+        "arn:aws:sqs:us-east-1:123456789012:other"
+    ));
+    assert!(is_denied(
+        &guardrail,
+        "s3:DeleteBucket",
+        "arn:aws:s3:::bucket"
+    ));
+}
+
+#[test]
+fn test_invert_lossy() {
+    let policy = Policy::from_str(
+        r#"{"Statement":[
+            {"Effect":"Allow","Action":"s3:*","Resource":"arn:aws:s3:::bucket/*",
+             "Condition":{"Bool":{"aws:SecureTransport":"true"}}},
+            {"Effect":"Allow","Action":"s3:GetObject","Resource":"arn:aws:s3:::other/*"},
+            {"Effect":"Allow","NotAction":"iam:*","Resource":"*"}
+        ]}"#,
+    )
+    .unwrap();
+    let (guardrail, findings) = invert_to_guardrail_with_findings(&policy);
+
+    let codes: Vec<&str> = findings.iter().map(|f| f.code).collect();
+    assert!(codes.contains(&FINDING_GUARDRAIL_CONDITION_DROPPED));
+    assert!(codes.contains(&FINDING_GUARDRAIL_OVERLAPPING_ACTIONS));
+    assert!(codes.contains(&FINDING_GUARDRAIL_NOT_ACTION));
+    assert!(findings.is_valid());
+
+    assert!(!is_denied(
+        &guardrail,
+        "iam:CreateUser",
+        "arn:aws:iam::123456789012:user/bob"
+    ));
+    assert_eq!(guardrail, invert_to_guardrail(&policy));
+}
+
+#[test]
+fn test_validity_window() {
+    let policy = Policy::from_str(
+        r#"{"Statement":[
+            {"Effect":"Allow","Action":"s3:GetObject","Resource":"*",
+             "Condition":{
+                "DateGreaterThanEquals":{"aws:CurrentTime":"2020-01-01T00:00:00Z"},
+                "DateLessThan":{"aws:CurrentTime":"2020-02-01"}}},
+            {"Effect":"Allow","Action":"s3:PutObject","Resource":"*",
+             "Condition":{"DateLessThanEquals":{"aws:EpochTime":"1600000000"}}},
+            {"Effect":"Deny","Action":"s3:*","Resource":"*",
+             "Condition":{"DateGreaterThan":{"aws:CurrentTime":"2030-01-01T00:00:00+01:00"}}}
+        ]}"#,
+    )
+    .unwrap();
+
+    assert_eq!(validity_window(&policy), Some((None, Some(1_600_000_001))));
+
+    let findings = check_expired(&policy, 1_590_000_000);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].code, FINDING_STATEMENT_EXPIRED);
+    assert_eq!(findings[0].statement, Some(0));
+    assert_eq!(check_expired(&policy, 1_600_000_001).len(), 2);
+
+    let policy = Policy::from_str(
+        r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject","Resource":"*",
+             "Condition":{"DateGreaterThan":{"aws:CurrentTime":"2020-01-01T00:00:00Z"}}}]}"#,
+    )
+    .unwrap();
+    assert_eq!(validity_window(&policy), Some((Some(1_577_836_801), None)));
+
+    let policy = Policy::from_str(
+        r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject","Resource":"*"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(validity_window(&policy), None);
+}
+
+#[test]
+fn test_risk_score() {
+    let policy = Policy::from_str(
+        r#"{"Statement":[
+            {"Sid":"Admin","Effect":"Allow","Action":"*","Resource":"*"},
+            {"Effect":"Allow","Action":["iam:PassRole","s3:GetObject"],"Resource":"arn:aws:s3:::bucket/*",
+             "Condition":{"Bool":{"aws:SecureTransport":"true"}}},
+            {"Effect":"Allow","Principal":"*","Action":"kms:*","Resource":"*"},
+            {"Effect":"Deny","Action":"*","Resource":"*"}
+        ]}"#,
+    )
+    .unwrap();
+    let report = risk_score(&policy);
+
+    assert_eq!(
+        report.statements[0].factors,
+        vec![
+            RiskFactor::AnyAction,
+            RiskFactor::AnyResource,
+            RiskFactor::NoCondition
+        ]
+    );
+    assert_eq!(report.statements[0].sid, Some("Admin".to_string()));
+    assert_eq!(report.statements[0].score, 70);
+    assert_eq!(
+        report.statements[1].factors,
+        vec![RiskFactor::PowerfulAction("iam:*")]
+    );
+    assert_eq!(
+        report.statements[2].factors,
+        vec![
+            RiskFactor::PowerfulAction("kms:Decrypt"),
+            RiskFactor::AnyResource,
+            RiskFactor::AnyPrincipal,
+            RiskFactor::NoCondition
+        ]
+    );
+    assert!(report.statements[3].factors.is_empty());
+    assert_eq!(report.score, 70 + 25 + 105);
+    assert_eq!(report.risky_statements().count(), 3);
+}
+
+#[test]
+fn test_sensitive_actions() {
+    let policy = Policy::from_str(
+        r#"{"Statement":[
+            {"Effect":"Allow","Action":"iam:PassRole","Resource":"arn:aws:iam::123456789012:role/app"},
+            {"Effect":"Allow","Action":["ec2:RunInstances","s3:GetObject"],"Resource":"*"},
+            {"Effect":"Allow","Action":"lambda:*","Resource":"*"},
+            {"Effect":"Deny","Action":"lambda:InvokeFunction","Resource":"*"}
+        ]}"#,
+    )
+    .unwrap();
+    let findings = check_sensitive_actions(&policy);
+
+    let sensitive: Vec<Option<usize>> = findings
+        .iter()
+        .filter(|f| f.code == FINDING_SENSITIVE_ACTION)
+        .map(|f| f.statement)
+        .collect();
+    assert_eq!(sensitive, vec![Some(0), Some(1), Some(2)]);
+    assert!(!findings[2].message.contains("lambda:InvokeFunction"));
+
+    let paths: Vec<&str> = findings
+        .iter()
+        .filter(|f| f.code == FINDING_ESCALATION_PATH)
+        .map(|f| f.message.as_str())
+        .collect();
+    assert_eq!(paths.len(), 2);
+    assert!(paths[0].contains("PassRoleToEc2"));
+    assert!(paths[1].contains("PassRoleToLambdaEventSource"));
+}
+
+#[test]
+fn test_contradictions() {
+    let policy = Policy::from_str(
+        r#"{"Statement":[
+            {"Effect":"Allow","Action":"s3:Get*","Resource":"arn:aws:s3:::bucket/*"},
+            {"Effect":"Allow","Action":"s3:GetObject","Resource":"arn:aws:s3:::other/*"},
+            {"Sid":"Copy","Effect":"Allow","Action":"s3:GetObject","Resource":"arn:aws:s3:::other/*"},
+            {"Effect":"Allow","Action":"ssm:StartSession","Resource":"arn:aws:ec2:us-east-1:123456789012:instance/i-0123"},
+            {"Effect":"Allow","Action":"sts:AssumeRole","Resource":"arn:aws:iam::123456789012:role/app"},
+            {"Effect":"Deny","Action":"s3:*","Resource":"arn:aws:s3:::bucket/*"}
+        ]}"#,
+    )
+    .unwrap();
+    let findings: Vec<(&str, Option<usize>)> = check_contradictions(&policy)
+        .iter()
+        .map(|f| (f.code, f.statement))
+        .collect();
+
+    assert_eq!(
+        findings,
+        vec![
+            (FINDING_SHADOWED_ALLOW, Some(0)),
+            (FINDING_DUPLICATE_STATEMENT, Some(2)),
+        ]
+    );
+}
+
+#[test]
+fn test_stats() {
+    let policy = Policy::from_str(
+        r#"{"Statement":[
+            {"Effect":"Allow","Action":["s3:GetObject","S3:getobject","s3:List*"],"Resource":["arn:aws:s3:::bucket/*","arn:aws:s3:::bucket"]},
+            {"Effect":"Allow","Action":"sqs:SendMessage","Resource":"*","Condition":{"Bool":{"aws:SecureTransport":"true"}}},
+            {"Effect":"Deny","Principal":"*","NotAction":"iam:*","Resource":"arn:aws:s3:::bucket"}
+        ]}"#,
+    )
+    .unwrap();
+    let stats = stats(&policy);
+
+    assert_eq!(stats.statements, 3);
+    assert_eq!(stats.allow_statements, 2);
+    assert_eq!(stats.deny_statements, 1);
+    assert_eq!(stats.conditional_statements, 1);
+    assert_eq!(stats.public_principals, 1);
+    assert_eq!(stats.unique_actions, 4);
+    assert_eq!(stats.unique_services, 3);
+    assert_eq!(stats.unique_resources, 3);
+    assert_eq!(stats.wildcard_actions, 2);
+    assert_eq!(stats.wildcard_resources, 2);
+    assert_eq!(stats.size_limit, MANAGED_POLICY_SIZE_LIMIT);
+    assert!(stats.size > 200);
+    assert!(stats.is_within_limit());
+}
+
+#[test]
+fn test_corpus_stats() {
+    let policies = [
+        Policy::from_str(
+            r#"{"Statement":[
+                {"Effect":"Allow","Action":["s3:GetObject","s3:List*"],"Resource":["arn:aws:s3:::acme-reports/2020.csv","arn:aws:s3:::acme-reports"]},
+                {"Effect":"Allow","Action":"iam:PassRole","Resource":"arn:aws:iam::123456789012:role/Deployer","Condition":{"StringEquals":{"iam:PassedToService":"ec2.amazonaws.com"}}}
+            ]}"#,
+        )
+        .unwrap(),
+        Policy::from_str(
+            r#"{"Statement":[
+                {"Effect":"Deny","Principal":"*","Action":"*","Resource":"*","Condition":{"Bool":{"aws:SecureTransport":"false"},"StringNotEqualsIfExists":{"aws:SourceVpc":"vpc-1a2b3c4d"}}},
+                {"Effect":"Allow","Action":"s3:GetObjectVersion","Resource":"arn:aws:s3:::acme-*"}
+            ]}"#,
+        )
+        .unwrap(),
+    ];
+    let stats = corpus_stats(&policies);
+
+    assert_eq!(stats.policies, 2);
+    assert_eq!(stats.statements, 4);
+    assert_eq!(stats.allow_statements, 3);
+    assert_eq!(stats.deny_statements, 1);
+    assert_eq!(stats.conditional_statements, 2);
+    assert_eq!(stats.operators.get("Bool"), Some(&1));
+    assert_eq!(stats.operators.get("StringEquals"), Some(&1));
+    assert_eq!(stats.operators.get("StringNotEqualsIfExists"), Some(&1));
+    assert_eq!(stats.action_families.get("s3:Get"), Some(&2));
+    assert_eq!(stats.action_families.get("s3:List"), Some(&1));
+    assert_eq!(stats.action_families.get("iam:Pass"), Some(&1));
+    assert_eq!(stats.action_families.get("*"), Some(&1));
+    assert_eq!(stats.resource_types.get("s3"), Some(&3));
+    assert_eq!(stats.resource_types.get("iam:role"), Some(&1));
+    assert_eq!(stats.resource_types.get("*"), Some(&1));
+    assert_eq!(stats.actions, 5);
+    assert_eq!(stats.wildcard_actions, 2);
+    assert_eq!(stats.resources, 5);
+    assert_eq!(stats.wildcard_resources, 2);
+    assert_eq!(stats.public_principals, 1);
+    assert_eq!(stats.wildcard_action_rate(), 0.4);
+    assert_eq!(stats.public_principal_rate(), 0.25);
+    assert_eq!(stats.conditional_rate(), 0.5);
+
+    let json = serde_json::to_string(&stats).unwrap();
+    assert!(!json.contains("acme"));
+    assert!(!json.contains("123456789012"));
+    assert!(!json.contains("Deployer"));
+    assert!(!json.contains("vpc-1a2b3c4d"));
+}
+
+#[test]
+fn test_corpus_stats_minimum_count() {
+    let policy = Policy::from_str(
+        r#"{"Statement":[{"Effect":"Allow","Action":["s3:GetObject","s3:GetObjectAcl","acme:Launch"],"Resource":"*"}]}"#,
+    )
+    .unwrap();
+    let stats = corpus_stats(&[policy]).with_minimum_count(2);
+
+    assert_eq!(stats.action_families.get("s3:Get"), Some(&2));
+    assert_eq!(stats.action_families.get("acme:Launch"), None);
+    assert!(stats.resource_types.is_empty());
+    assert_eq!(stats.actions, 3);
+}
+
+#[test]
+fn test_corpus_stats_for_directory() {
+    let stats = corpus_stats_for_directory(Path::new("tests/data/good")).unwrap();
+    assert_eq!(stats.policies, 3);
+    assert_eq!(stats.unreadable_files, 0);
+    assert!(stats.statements >= 3);
+
+    let stats = corpus_stats_for_directory(Path::new("tests/data/bad")).unwrap();
+    assert!(stats.unreadable_files > 0);
+}
+
+#[test]
+fn test_who_can() {
+    let details = read_authorization_details(
+        r#"{
+        "UserDetailList": [
+            {
+                "UserName": "alice",
+                "Arn": "arn:aws:iam::123456789012:user/alice",
+                "GroupList": ["crypto"],
+                "UserPolicyList": [],
+                "AttachedManagedPolicies": []
+            },
+            {
+                "UserName": "bob",
+                "Arn": "arn:aws:iam::123456789012:user/bob",
+                "GroupList": ["crypto"],
+                "UserPolicyList": [{
+                    "PolicyName": "no-decrypt",
+                    "PolicyDocument": {"Statement": [
+                        {"Effect": "Deny", "Action": "kms:Decrypt", "Resource": "*"}
+                    ]}
+                }],
+                "AttachedManagedPolicies": []
+            }
+        ],
+        "GroupDetailList": [{
+            "GroupName": "crypto",
+            "Arn": "arn:aws:iam::123456789012:group/crypto",
+            "GroupPolicyList": [],
+            "AttachedManagedPolicies": [
+                {"PolicyArn": "arn:aws:iam::123456789012:policy/decrypt"}
+            ]
+        }],
+        "RoleDetailList": [{
+            "RoleName": "app",
+            "Arn": "arn:aws:iam::123456789012:role/app",
+            "RolePolicyList": [{
+                "PolicyName": "keys",
+                "PolicyDocument": {"Statement": [
+                    {
+                        "Sid": "AppKey",
+                        "Effect": "Allow",
+                        "Action": "kms:*",
+                        "Resource": "arn:aws:kms:us-east-1:123456789012:key/app",
+                        "Condition": {"Bool": {"aws:SecureTransport": "true"}}
+                    },
+                    {
+                        "Effect": "Allow",
+                        "Action": "kms:Decrypt",
+                        "Resource": "arn:aws:kms:us-east-1:123456789012:key/other"
+                    }
+                ]}
+            }],
+            "AttachedManagedPolicies": []
+        }],
+        "Policies": [{
+            "PolicyName": "decrypt",
+            "Arn": "arn:aws:iam::123456789012:policy/decrypt",
+            "PolicyVersionList": [{
+                "Document": {"Statement": [
+                    {"Sid": "Decrypt", "Effect": "Allow", "Action": "kms:Decrypt", "Resource": "*"}
+                ]},
+                "IsDefaultVersion": true
+            }]
+        }]
+    }"#
+        .as_bytes(),
+    )
+    .unwrap();
+    let key = ARN::from_str("arn:aws:kms:us-east-1:123456789012:key/app").unwrap();
+
+    let matches = who_can(&details, "kms:Decrypt", &key);
+    assert_eq!(matches.len(), 2);
+
+    assert_eq!(matches[0].principal, "arn:aws:iam::123456789012:user/alice");
+    assert_eq!(
+        matches[0].policy,
+        PolicySource::Managed("arn:aws:iam::123456789012:policy/decrypt".to_string())
+    );
+    assert_eq!(matches[0].sid.as_ref().unwrap().to_string(), "Decrypt");
+    assert!(!matches[0].conditional);
+
+    assert_eq!(matches[1].principal, "arn:aws:iam::123456789012:role/app");
+    assert_eq!(
+        matches[1].policy.to_string(),
+        "arn:aws:iam::123456789012:role/app#keys"
+    );
+    assert_eq!(matches[1].statement, 0);
+    assert!(matches[1].conditional);
+
+    assert!(who_can(&details, "kms:Encrypt", &key)
+        .iter()
+        .all(|m| m.principal == "arn:aws:iam::123456789012:role/app"));
+}
@@ -0,0 +1,238 @@
+use aws_iam::arn::{
+    retarget_arn_str, rewrite_account_str, wildcard_match, wildcard_match_ignore_case,
+    AccountIdentifier, ArnBuilder, ArnPattern, Partition, Region, WildcardPattern, ARN,
+};
+use aws_iam::model::Resource;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[test]
+fn test_wildcard_match() {
+    assert!(wildcard_match("", ""));
+    assert!(wildcard_match("*", ""));
+    assert!(wildcard_match("*", "anything"));
+    assert!(wildcard_match("abc", "abc"));
+    assert!(wildcard_match("a?c", "abc"));
+    assert!(wildcard_match("a*c", "abbbbc"));
+    assert!(wildcard_match("a*b*c", "aXbYbZc"));
+    assert!(!wildcard_match("abc", "abd"));
+    assert!(!wildcard_match("a?c", "ac"));
+    assert!(!wildcard_match("a*c", "abcd"));
+    assert!(!wildcard_match("", "a"));
+    assert!(wildcard_match("caf?", "café"));
+    assert!(wildcard_match("*é*", "résumé"));
+}
+
+#[test]
+fn test_wildcard_match_ignore_case() {
+    assert!(wildcard_match_ignore_case("s3:Get*", "S3:GETOBJECT"));
+    assert!(wildcard_match_ignore_case("S3:?ETOBJECT", "s3:getobject"));
+    assert!(!wildcard_match_ignore_case("s3:Get*", "s3:PutObject"));
+    assert!(!wildcard_match("s3:Get*", "S3:GETOBJECT"));
+}
+
+#[test]
+fn test_wildcard_pattern() {
+    for (pattern, value) in [
+        ("*", "anything"),
+        ("abc", "abc"),
+        ("a?c", "abc"),
+        ("abc*", "abcdef"),
+        ("a*b*c", "aXbYbZc"),
+        ("a.c*", "a.cd"),
+        ("caf?", "café"),
+    ] {
+        assert!(WildcardPattern::new(pattern).matches(value));
+        assert!(wildcard_match(pattern, value));
+    }
+    for (pattern, value) in [
+        ("abc", "abd"),
+        ("a?c", "ac"),
+        ("abc*", "ab"),
+        ("a*c", "abcd"),
+        ("a.c", "abc"),
+        ("", "a"),
+    ] {
+        assert!(!WildcardPattern::new(pattern).matches(value));
+        assert!(!wildcard_match(pattern, value));
+    }
+}
+
+#[test]
+fn test_wildcard_pattern_ignore_case() {
+    let pattern = WildcardPattern::new_ignore_case("s3:Get*");
+    assert!(pattern.matches("S3:GETOBJECT"));
+    assert!(!pattern.matches("s3:PutObject"));
+    assert!(!WildcardPattern::new("s3:Get*").matches("S3:GETOBJECT"));
+    assert!(WildcardPattern::new_ignore_case("S3:?et*Acl").matches("s3:getObjectACL"));
+    assert!(WildcardPattern::new_ignore_case("CAFÉ").matches("café"));
+    assert_eq!(pattern.to_string(), "s3:Get*");
+}
+
+#[test]
+fn test_pattern_matches_components() {
+    let pattern = ArnPattern::from_str("arn:aws:s3:::bucket/*").unwrap();
+    assert!(pattern.matches_str("arn:aws:s3:::bucket/key"));
+    assert!(pattern.matches(&ARN::from_str("arn:aws:s3:::bucket/key").unwrap()));
+    assert!(!pattern.matches_str("arn:aws:s3:::other/key"));
+    assert!(!pattern.matches_str("arn:aws:s3:::bucket"));
+    assert!(!pattern.matches_str("arn:aws:s3"));
+    assert!(!pattern.matches_str("arn:aws::::bucket/key"));
+}
+
+#[test]
+fn test_build_s3_object() {
+    let arn = ArnBuilder::s3_object("examplebucket", "reports/2021.csv")
+        .build()
+        .unwrap();
+    assert_eq!(
+        arn.to_string(),
+        "arn:aws:s3:::examplebucket/reports/2021.csv"
+    );
+}
+
+#[test]
+fn test_build_iam_role() {
+    let arn = ArnBuilder::iam_role("123456789012", "admin")
+        .in_partition("aws-cn")
+        .build()
+        .unwrap();
+    assert_eq!(arn.to_string(), "arn:aws-cn:iam::123456789012:role/admin");
+}
+
+#[test]
+fn test_build_invalid() {
+    assert!(ArnBuilder::iam_user("not-an-account", "bob")
+        .build()
+        .is_err());
+}
+
+#[test]
+fn test_pattern_parse() {
+    assert!(ArnPattern::from_str("*").unwrap().is_any());
+    assert_eq!(
+        ArnPattern::from_str("arn:aws:s3:::bucket/*")
+            .unwrap()
+            .to_string(),
+        "arn:aws:s3:::bucket/*"
+    );
+    assert!(ArnPattern::from_str("bucket/*").is_err());
+    assert!(ArnPattern::from_str("arn:aws:s3::").is_err());
+}
+
+#[test]
+fn test_pattern_matches() {
+    let arn = ARN::from_str("arn:aws:dynamodb:us-east-2:123456789012:table/Books").unwrap();
+    assert!(ArnPattern::any().matches(&arn));
+    assert!(
+        ArnPattern::from_str("arn:aws:dynamodb:*:123456789012:table/*")
+            .unwrap()
+            .matches(&arn)
+    );
+    assert!(
+        ArnPattern::from_str("arn:aws:dynamodb:us-east-?:*:table/Books")
+            .unwrap()
+            .matches(&arn)
+    );
+    assert!(!ArnPattern::from_str("arn:aws:dynamodb:*:*:table/NotBooks")
+        .unwrap()
+        .matches(&arn));
+    assert!(!ArnPattern::from_str("arn:aws:s3:*:*:*")
+        .unwrap()
+        .matches(&arn));
+}
+
+#[test]
+fn test_pattern_matches_str() {
+    let pattern = ArnPattern::from_str("arn:aws-iso:s3:::bucket/*").unwrap();
+    assert!(pattern.matches_str("arn:aws-iso:s3:::bucket/key"));
+    assert!(!pattern.matches_str("arn:aws:s3:::bucket/key"));
+    assert!(!pattern.matches_str("not-an-arn"));
+}
+
+#[test]
+fn test_resource_matches() {
+    let arn = ARN::from_str("arn:aws:s3:::bucket/key").unwrap();
+    let resource = Resource::this_resource(ARN::from_str("arn:aws:s3:::bucket/*").unwrap());
+    assert!(resource.matches(&arn));
+
+    let not_resource = Resource::not_this_resource(ARN::from_str("arn:aws:s3:::bucket/*").unwrap());
+    assert!(!not_resource.matches(&arn));
+
+    assert!(Resource::default().matches(&arn));
+}
+
+#[test]
+fn test_partition_and_region() {
+    assert_eq!(Partition::from_str("aws-cn").unwrap(), Partition::AwsCn);
+    assert_eq!(Partition::AwsUsGov.to_string(), "aws-us-gov");
+    assert!(Partition::from_str("aws-mars").is_err());
+
+    let region = Region::from_str("us-gov-west-1").unwrap();
+    assert_eq!(region.partition(), Partition::AwsUsGov);
+    assert_eq!(
+        Region::from_str("cn-north-1").unwrap().partition(),
+        Partition::AwsCn
+    );
+    assert_eq!(
+        Region::from_str("eu-west-1").unwrap().partition(),
+        Partition::Aws
+    );
+    assert!(Region::from_str("*").is_err());
+    assert!(Region::from_str("us-east").is_err());
+}
+
+#[test]
+fn test_retarget_arn_str() {
+    let region = Region::from_str("cn-north-1").unwrap();
+    assert_eq!(
+        retarget_arn_str(
+            "arn:aws:sqs:us-east-1:123456789012:queue",
+            Partition::AwsCn,
+            Some(&region)
+        ),
+        "arn:aws-cn:sqs:cn-north-1:123456789012:queue"
+    );
+    assert_eq!(
+        retarget_arn_str("arn:aws:s3:::bucket/a:b", Partition::AwsCn, Some(&region)),
+        "arn:aws-cn:s3:::bucket/a:b"
+    );
+    assert_eq!(
+        retarget_arn_str(
+            "arn:*:sqs:*:123456789012:queue",
+            Partition::AwsCn,
+            Some(&region)
+        ),
+        "arn:*:sqs:*:123456789012:queue"
+    );
+    assert_eq!(
+        retarget_arn_str("o-a1b2c3d4e5", Partition::AwsCn, None),
+        "o-a1b2c3d4e5"
+    );
+}
+
+#[test]
+fn test_rewrite_account_str() {
+    let accounts: HashMap<AccountIdentifier, AccountIdentifier> = vec![(
+        AccountIdentifier::from_str("111111111111").unwrap(),
+        AccountIdentifier::from_str("222222222222").unwrap(),
+    )]
+    .into_iter()
+    .collect();
+    assert_eq!(
+        rewrite_account_str("arn:aws:iam::111111111111:role/a:b", &accounts),
+        "arn:aws:iam::222222222222:role/a:b"
+    );
+    assert_eq!(
+        rewrite_account_str("111111111111", &accounts),
+        "222222222222"
+    );
+    assert_eq!(
+        rewrite_account_str("arn:aws:iam::333333333333:root", &accounts),
+        "arn:aws:iam::333333333333:root"
+    );
+    assert_eq!(
+        rewrite_account_str("arn:aws:iam::*:root", &accounts),
+        "arn:aws:iam::*:root"
+    );
+}
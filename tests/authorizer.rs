@@ -0,0 +1,113 @@
+#![cfg(feature = "authorizer")]
+
+use aws_iam::authorizer::{Authorizer, AuthorizerLayer};
+use aws_iam::io;
+use aws_iam::model::{Policy, QualifiedName};
+use aws_iam::offline::Request;
+use futures_util::future::{ready, Ready};
+use futures_util::FutureExt;
+use http::request::Parts;
+use http::StatusCode;
+use std::str::FromStr;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+#[derive(Clone)]
+struct Echo;
+
+impl Service<http::Request<String>> for Echo {
+    type Response = http::Response<String>;
+    type Error = ();
+    type Future = Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<String>) -> Self::Future {
+        let resource = request
+            .extensions()
+            .get::<Request>()
+            .map(|request| request.resource.clone())
+            .unwrap_or_default();
+        ready(Ok(http::Response::new(resource)))
+    }
+}
+
+fn shop_policy() -> &'static Policy {
+    let policy = io::read_from_string(
+        r#"{
+  "Version": "2012-10-17",
+  "Statement": [{
+    "Effect": "Allow",
+    "Action": "shop:GetOrder",
+    "Resource": "arn:aws:shop:::order/1*"
+  }]
+}"#,
+    )
+    .unwrap();
+    Box::leak(Box::new(policy))
+}
+
+fn map_order(parts: &Parts) -> Option<Request> {
+    let order = parts.uri.path().strip_prefix("/orders/")?;
+    Some(Request {
+        request_id: None,
+        principal: None,
+        action: QualifiedName::from_str("shop:GetOrder").unwrap(),
+        resource: format!("arn:aws:shop:::order/{}", order),
+        source_arn: None,
+        source_account: None,
+        called_via: Default::default(),
+        environment: Default::default(),
+    })
+}
+
+fn call(
+    service: &mut impl Service<http::Request<String>, Response = http::Response<String>, Error = ()>,
+    path: &str,
+) -> http::Response<String> {
+    service
+        .call(http::Request::get(path).body(String::new()).unwrap())
+        .now_or_never()
+        .unwrap()
+        .unwrap()
+}
+
+#[test]
+fn test_authorizer_is_allowed() {
+    let authorizer = Authorizer::new(&[shop_policy()]);
+    assert_eq!(authorizer.policies().count(), 1);
+
+    let parts = http::Request::get("/orders/1234")
+        .body(())
+        .unwrap()
+        .into_parts()
+        .0;
+    assert!(authorizer.is_allowed(&map_order(&parts).unwrap()));
+
+    let parts = http::Request::get("/orders/2345")
+        .body(())
+        .unwrap()
+        .into_parts()
+        .0;
+    assert!(!authorizer.is_allowed(&map_order(&parts).unwrap()));
+}
+
+#[test]
+fn test_authorizer_layer() {
+    let layer = AuthorizerLayer::new(Authorizer::new(&[shop_policy()]), map_order);
+    let mut service = layer.layer(Echo);
+
+    let response = call(&mut service, "/orders/1234");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.body(), "arn:aws:shop:::order/1234");
+
+    let response = call(&mut service, "/orders/2345");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(response.body().is_empty());
+
+    let response = call(&mut service, "/customers/1");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
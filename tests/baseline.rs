@@ -0,0 +1,93 @@
+use aws_iam::baseline::{Baseline, BaselineRule, Requirement, FINDING_BASELINE_VIOLATION};
+use aws_iam::model::{Condition, Effect, Policy};
+use aws_iam::validate::{Severity, ValidationOptions};
+use std::str::FromStr;
+
+const BASELINE: &str = r#"{"Rules":[
+    {"Id":"S3RequiresTls","Effect":"Allow","Action":"s3:*",
+     "Condition":{"Bool":{"aws:SecureTransport":"true"}}},
+    {"Id":"OrgPrincipalsOnly","Effect":"Allow","HasPrincipal":true,
+     "Condition":{"StringEquals":{"aws:PrincipalOrgID":["o-123","o-456"]}}},
+    {"Id":"NoIamWildcard","Description":"iam:* must not be allowed","Severity":"Warning",
+     "Effect":"Allow","Action":["iam:*"],"Forbid":true}
+]}"#;
+
+#[test]
+fn test_read_baseline() {
+    let baseline = Baseline::from_str(BASELINE).unwrap();
+    assert_eq!(baseline.rules.len(), 3);
+
+    let rule = &baseline.rules[0];
+    assert_eq!(rule.id, "S3RequiresTls");
+    assert_eq!(rule.severity, Severity::Error);
+    assert_eq!(rule.effect, Some(Effect::Allow));
+    assert_eq!(rule.has_principal, None);
+    assert!(matches!(rule.requirement, Requirement::Condition(_)));
+
+    let rule = &baseline.rules[2];
+    assert_eq!(rule.severity, Severity::Warning);
+    assert_eq!(rule.requirement, Requirement::Forbidden);
+
+    assert_eq!(Baseline::from_str(&baseline.to_string()).unwrap(), baseline);
+
+    assert!(Baseline::from_str(r#"{"Rules":[{"Id":"Empty"}]}"#).is_err());
+    assert!(Baseline::from_str(
+        r#"{"Rules":[{"Id":"Both","Forbid":true,"Condition":{"Bool":{"aws:SecureTransport":"true"}}}]}"#
+    )
+    .is_err());
+    assert!(Baseline::from_str(r#"{"Rules":[{"Forbid":true}]}"#).is_err());
+}
+
+#[test]
+fn test_check_baseline() {
+    let policy = Policy::from_str(
+        r#"{"Statement":[
+            {"Effect":"Allow","Action":"s3:GetObject","Resource":"*",
+             "Condition":{"Bool":{"aws:SecureTransport":"true"}}},
+            {"Effect":"Allow","Action":"s3:PutObject","Resource":"*"},
+            {"Effect":"Allow","Principal":"*","Action":"sqs:SendMessage","Resource":"*",
+             "Condition":{"StringEquals":{"aws:PrincipalOrgID":"o-123"}}},
+            {"Effect":"Allow","Principal":"*","Action":"sqs:ReceiveMessage","Resource":"*",
+             "Condition":{"StringEquals":{"aws:PrincipalOrgID":["o-123","o-999"]}}},
+            {"Effect":"Allow","NotAction":"s3:*","Resource":"*"},
+            {"Effect":"Deny","Action":"s3:*","Resource":"*"}
+        ]}"#,
+    )
+    .unwrap();
+    let baseline = Baseline::from_str(BASELINE).unwrap();
+
+    let findings: Vec<(&str, Option<usize>)> = baseline
+        .check(&policy)
+        .iter()
+        .map(|f| (f.code, f.statement))
+        .collect();
+    assert_eq!(
+        findings,
+        vec![
+            (FINDING_BASELINE_VIOLATION, Some(1)),
+            (FINDING_BASELINE_VIOLATION, Some(3)),
+            (FINDING_BASELINE_VIOLATION, Some(4)),
+        ]
+    );
+
+    let findings = policy.validate(&ValidationOptions::default().with_baseline(baseline));
+    let violations: Vec<_> = findings
+        .iter()
+        .filter(|f| f.code == FINDING_BASELINE_VIOLATION)
+        .collect();
+    assert_eq!(violations.len(), 3);
+    assert!(violations[0].message.contains("S3RequiresTls"));
+    assert!(violations[2].message.contains("iam:* must not be allowed"));
+    assert!(!violations[2].is_error());
+
+    let baseline = Baseline::default().with_rule(
+        BaselineRule::require_condition(
+            "RequireMfa",
+            Condition::from_str(r#"{"Condition":{"Bool":{"aws:MultiFactorAuthPresent":"true"}}}"#)
+                .unwrap(),
+        )
+        .with_effect(Effect::Allow)
+        .with_actions(vec!["sqs:*".parse().unwrap()]),
+    );
+    assert_eq!(baseline.check(&policy).len(), 3);
+}
@@ -0,0 +1,102 @@
+;; The core module of the test rule pack `rule-pack.wasm`, which implements the `rule-pack`
+;; world of `wit/rule-pack.wit`. The component is built with:
+;;
+;;   wasm-tools component embed wit/rule-pack.wit tests/data/plugins/rule-pack.wat \
+;;     | wasm-tools component new -o tests/data/plugins/rule-pack.wasm
+;;
+;; * rule `WasmNoWildcards` reports `WasmWildcard` if the policy contains a `*`,
+;; * transform `WasmIdentity` returns the policy unchanged, and
+;; * any other rule or transform name is an error.
+(module
+  (memory (export "memory") 4)
+
+  ;; strings
+  (data (i32.const 0) "WasmNoWildcards")
+  (data (i32.const 16) "WasmWildcard")
+  (data (i32.const 32) "the policy contains a wildcard")
+  (data (i32.const 64) "WasmIdentity")
+  (data (i32.const 80) "not provided by this pack")
+
+  ;; list<rule-info>: [{name: "WasmNoWildcards", severity: warning}]
+  (data (i32.const 128) "\00\00\00\00\0f\00\00\00\01\00\00\00")
+  ;; list<string>: ["WasmIdentity"]
+  (data (i32.const 144) "\40\00\00\00\0c\00\00\00")
+  ;; list<finding>: [{severity: warning, code: "WasmWildcard", message: ..., statement: none}]
+  (data (i32.const 160) "\01\00\00\00\10\00\00\00\0c\00\00\00\20\00\00\00\1e\00\00\00\00\00\00\00\00\00\00\00")
+
+  ;; the return area shared by every function, and the start of the heap.
+  (global $return i32 (i32.const 192))
+  (global $heap (mut i32) (i32.const 1024))
+
+  (func (export "cabi_realloc") (param $ptr i32) (param $old i32) (param $align i32) (param $size i32) (result i32)
+    (local $result i32)
+    (local.set $result
+      (i32.and
+        (i32.add (global.get $heap) (i32.sub (local.get $align) (i32.const 1)))
+        (i32.sub (i32.const 0) (local.get $align))))
+    (global.set $heap (i32.add (local.get $result) (local.get $size)))
+    (local.get $result))
+
+  ;; returns 1 if the `len` bytes at `lhs` and `rhs` are equal.
+  (func $equal (param $lhs i32) (param $rhs i32) (param $len i32) (result i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.eqz (local.get $len)))
+        (if (i32.ne (i32.load8_u (local.get $lhs)) (i32.load8_u (local.get $rhs)))
+          (then (return (i32.const 0))))
+        (local.set $lhs (i32.add (local.get $lhs) (i32.const 1)))
+        (local.set $rhs (i32.add (local.get $rhs) (i32.const 1)))
+        (local.set $len (i32.sub (local.get $len) (i32.const 1)))
+        (br $next)))
+    (i32.const 1))
+
+  ;; returns 1 if any of the `len` bytes at `ptr` is `*`.
+  (func $wildcard (param $ptr i32) (param $len i32) (result i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.eqz (local.get $len)))
+        (if (i32.eq (i32.load8_u (local.get $ptr)) (i32.const 42))
+          (then (return (i32.const 1))))
+        (local.set $ptr (i32.add (local.get $ptr) (i32.const 1)))
+        (local.set $len (i32.sub (local.get $len) (i32.const 1)))
+        (br $next)))
+    (i32.const 0))
+
+  ;; write a list or string, or the `ok` or `err` case of a result, to the return area.
+  (func $list (param $ptr i32) (param $len i32) (result i32)
+    (i32.store (global.get $return) (local.get $ptr))
+    (i32.store offset=4 (global.get $return) (local.get $len))
+    (global.get $return))
+
+  (func $result (param $case i32) (param $ptr i32) (param $len i32) (result i32)
+    (i32.store8 (global.get $return) (local.get $case))
+    (i32.store offset=4 (global.get $return) (local.get $ptr))
+    (i32.store offset=8 (global.get $return) (local.get $len))
+    (global.get $return))
+
+  (func (export "aws-iam:rule-pack/rules@0.1.0#list-rules") (result i32)
+    (call $list (i32.const 128) (i32.const 1)))
+
+  (func (export "aws-iam:rule-pack/rules@0.1.0#check")
+    (param $rule i32) (param $rule_len i32) (param $policy i32) (param $policy_len i32) (result i32)
+    (if (i32.eqz
+          (i32.and
+            (i32.eq (local.get $rule_len) (i32.const 15))
+            (call $equal (local.get $rule) (i32.const 0) (i32.const 15))))
+      (then (return (call $result (i32.const 1) (i32.const 80) (i32.const 25)))))
+    (call $result
+      (i32.const 0)
+      (i32.const 160)
+      (call $wildcard (local.get $policy) (local.get $policy_len))))
+
+  (func (export "aws-iam:rule-pack/rules@0.1.0#list-transforms") (result i32)
+    (call $list (i32.const 144) (i32.const 1)))
+
+  (func (export "aws-iam:rule-pack/rules@0.1.0#transform")
+    (param $transform i32) (param $transform_len i32) (param $policy i32) (param $policy_len i32) (result i32)
+    (if (i32.eqz
+          (i32.and
+            (i32.eq (local.get $transform_len) (i32.const 12))
+            (call $equal (local.get $transform) (i32.const 64) (i32.const 12))))
+      (then (return (call $result (i32.const 1) (i32.const 80) (i32.const 25)))))
+    (call $result (i32.const 0) (local.get $policy) (local.get $policy_len))))
@@ -0,0 +1,33 @@
+use aws_iam::io;
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use test_generator::test_resources;
+
+#[test_resources("tests/data/good/*.json")]
+fn verify_good_example_round_trip(resource: &str) {
+    let file_name = PathBuf::from(resource);
+    let policy = io::read_from_file(&file_name).unwrap();
+    let expected: Value = serde_json::from_str(&fs::read_to_string(&file_name).unwrap()).unwrap();
+
+    let written: Value = serde_json::from_str(&io::to_string(&policy, true).unwrap()).unwrap();
+    assert_eq!(written, expected);
+
+    let operators = |value: &Value| -> Vec<String> {
+        value["Statement"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|statement| statement["Condition"].as_object())
+            .flat_map(|condition| condition.keys().cloned())
+            .collect()
+    };
+    assert_eq!(operators(&written), operators(&expected));
+    for statement in &policy.statement {
+        if let Some(condition) = &statement.condition {
+            assert!(condition
+                .keys()
+                .all(|operator| operator.operator.is_known()));
+        }
+    }
+}
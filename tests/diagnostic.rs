@@ -0,0 +1,117 @@
+use aws_iam::analysis::{check_sensitive_actions, FINDING_SENSITIVE_ACTION};
+use aws_iam::arn::ArnParseMode;
+use aws_iam::diagnostic::{
+    diagnose_str, SourceMap, DIAGNOSTIC_FORMAT_ERROR, DIAGNOSTIC_SYNTAX_ERROR,
+};
+use aws_iam::model::Policy;
+use aws_iam::validate::{
+    Severity, ValidationOptions, FINDING_DUPLICATE_KEY, FINDING_INVALID_SID,
+    FINDING_UNKNOWN_CONDITION_OPERATOR,
+};
+use std::str::FromStr;
+
+const DOCUMENT: &str = r#"{
+  "Version": "2012-10-17",
+  "Statement": [
+    {
+      "Sid": "Ünïcode",
+      "Effect": "Allow",
+      "Action": "iam:PassRole",
+      "Resource": "*",
+      "Condition": {"StringEqualz": {"aws:RequestedRegion": "us-east-1"}}
+    }
+  ],
+  "Version": "2012-10-17"
+}"#;
+
+#[test]
+fn test_source_map() {
+    let source_map = SourceMap::from_str(DOCUMENT).unwrap();
+    assert_eq!(source_map.document().range(), 0..DOCUMENT.len());
+
+    let sid = source_map.value("Statement[0].Sid").unwrap();
+    assert_eq!(&DOCUMENT[sid.range()], "\"Ünïcode\"");
+    assert_eq!((sid.start.line, sid.start.column), (5, 14));
+    assert_eq!((sid.end.line, sid.end.column), (5, 23));
+
+    let key = source_map
+        .key("Statement[0].Condition.StringEqualz.aws:RequestedRegion")
+        .unwrap();
+    assert_eq!(&DOCUMENT[key.range()], "\"aws:RequestedRegion\"");
+
+    let statement = source_map.statement(0).unwrap();
+    assert!(DOCUMENT[statement.range()].starts_with('{'));
+    assert!(DOCUMENT[statement.range()].ends_with('}'));
+    assert!(source_map.statement(1).is_none());
+
+    let duplicates: Vec<usize> = source_map.duplicates().map(|s| s.start.line).collect();
+    assert_eq!(duplicates, vec![12]);
+    assert_eq!(source_map.key("Version").unwrap().start.line, 12);
+    assert!(source_map
+        .paths()
+        .any(|path| path == "Statement[0].Resource"));
+}
+
+#[test]
+fn test_source_map_key_at() {
+    let source_map = SourceMap::from_str(DOCUMENT).unwrap();
+    let offset = DOCUMENT.find("aws:RequestedRegion").unwrap();
+
+    let (path, key) = source_map.key_at(offset).unwrap();
+    assert_eq!(
+        path,
+        "Statement[0].Condition.StringEqualz.aws:RequestedRegion"
+    );
+    assert_eq!(&DOCUMENT[key.range()], "\"aws:RequestedRegion\"");
+
+    let value = DOCUMENT.find("us-east-1").unwrap();
+    assert!(source_map.key_at(value).is_none());
+}
+
+#[test]
+fn test_diagnose_str() {
+    let diagnostics = diagnose_str(DOCUMENT, &ValidationOptions::default());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, DIAGNOSTIC_FORMAT_ERROR);
+
+    let options = ValidationOptions::default().with_parse_mode(ArnParseMode::Permissive);
+    let diagnostics = diagnose_str(DOCUMENT, &options);
+    let located: Vec<(&str, usize, usize)> = diagnostics
+        .iter()
+        .map(|d| (d.code, d.span.start.line, d.span.start.column))
+        .collect();
+    assert_eq!(
+        located,
+        vec![
+            (FINDING_DUPLICATE_KEY, 12, 3),
+            (FINDING_INVALID_SID, 5, 14),
+            (FINDING_UNKNOWN_CONDITION_OPERATOR, 9, 7),
+        ]
+    );
+
+    let source_map = SourceMap::from_str(DOCUMENT).unwrap();
+    let value: serde_json::Value = serde_json::from_str(DOCUMENT).unwrap();
+    let policy = Policy::from_json_with(&value, ArnParseMode::Permissive).unwrap();
+    let findings = check_sensitive_actions(&policy);
+    assert_eq!(findings[0].code, FINDING_SENSITIVE_ACTION);
+    assert_eq!(source_map.locate(&findings[0]).start.line, 4);
+}
+
+#[test]
+fn test_diagnose_errors() {
+    let diagnostics = diagnose_str("{\n  \"Statement\": [\n}", &ValidationOptions::default());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, DIAGNOSTIC_SYNTAX_ERROR);
+    assert_eq!(diagnostics[0].span.start.line, 3);
+
+    let document = r#"{"Statement": [{"Effect": "Maybe", "Action": "s3:GetObject"}]}"#;
+    let diagnostics = diagnose_str(document, &ValidationOptions::default());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, DIAGNOSTIC_FORMAT_ERROR);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert_eq!(&document[diagnostics[0].span.range()], "\"Maybe\"");
+
+    let document = r#"{"Statement": [{"Effect": "Allow", "Action": 42}]}"#;
+    let diagnostics = diagnose_str(document, &ValidationOptions::default());
+    assert_eq!(&document[diagnostics[0].span.range()], "\"Action\"");
+}
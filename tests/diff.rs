@@ -0,0 +1,221 @@
+use aws_iam::diff::{diff, three_way_merge, ConflictKind, MergeConflict, StatementChange};
+use aws_iam::model::{Policy, Sid};
+use std::str::FromStr;
+
+const BASE: &str = r#"{"Version":"2012-10-17","Statement":[
+    {"Sid":"Read","Effect":"Allow","Action":"s3:GetObject","Resource":"arn:aws:s3:::reports"},
+    {"Sid":"Write","Effect":"Allow","Action":"s3:PutObject","Resource":"arn:aws:s3:::reports"},
+    {"Effect":"Deny","Action":"s3:DeleteBucket","Resource":"*"}
+]}"#;
+
+fn policy(s: &str) -> Policy {
+    Policy::from_str(s).unwrap()
+}
+
+fn sid(s: &str) -> Option<Sid> {
+    Some(Sid::from_str(s).unwrap())
+}
+
+#[test]
+fn test_diff() {
+    let after = policy(
+        r#"{"Version":"2012-10-17","Statement":[
+        {"Sid":"Write","Effect":"Allow","Action":"s3:PutObject","Resource":"arn:aws:s3:::archive"},
+        {"Sid":"Read","Effect":"Allow","Action":"s3:GetObject","Resource":"arn:aws:s3:::reports"},
+        {"Sid":"List","Effect":"Allow","Action":"s3:ListBucket","Resource":"arn:aws:s3:::reports"}
+    ]}"#,
+    );
+    let changes = diff(&policy(BASE), &after);
+
+    assert_eq!(changes.len(), 3);
+    match &changes[0] {
+        StatementChange::Modified {
+            after, elements, ..
+        } => {
+            assert_eq!(after.sid, sid("Write"));
+            assert_eq!(elements, &vec!["Resource"]);
+        }
+        change => panic!("expecting a modification, not {:?}", change),
+    }
+    assert!(matches!(&changes[1], StatementChange::Removed(statement) if statement.sid.is_none()));
+    assert!(
+        matches!(&changes[2], StatementChange::Added(statement) if statement.sid == sid("List"))
+    );
+}
+
+#[test]
+fn test_diff_unchanged() {
+    assert!(diff(&policy(BASE), &policy(BASE)).is_empty());
+}
+
+#[test]
+fn test_merge_different_statements() {
+    let ours = policy(
+        r#"{"Version":"2012-10-17","Statement":[
+        {"Sid":"Read","Effect":"Allow","Action":["s3:GetObject","s3:ListBucket"],"Resource":"arn:aws:s3:::reports"},
+        {"Sid":"Write","Effect":"Allow","Action":"s3:PutObject","Resource":"arn:aws:s3:::reports"},
+        {"Effect":"Deny","Action":"s3:DeleteBucket","Resource":"*"}
+    ]}"#,
+    );
+    let theirs = policy(
+        r#"{"Version":"2012-10-17","Statement":[
+        {"Sid":"Read","Effect":"Allow","Action":"s3:GetObject","Resource":"arn:aws:s3:::reports"},
+        {"Effect":"Deny","Action":"s3:DeleteBucket","Resource":"*"},
+        {"Sid":"Tag","Effect":"Allow","Action":"s3:PutObjectTagging","Resource":"arn:aws:s3:::reports"}
+    ]}"#,
+    );
+    let merge = three_way_merge(&policy(BASE), &ours, &theirs);
+
+    assert!(merge.is_clean());
+    let sids: Vec<Option<Sid>> = merge
+        .policy
+        .statement
+        .iter()
+        .map(|st| st.sid.clone())
+        .collect();
+    assert_eq!(sids, vec![sid("Read"), None, sid("Tag")]);
+    assert!(merge.policy.statement[0].action().matches("s3:ListBucket"));
+}
+
+#[test]
+fn test_merge_different_elements() {
+    let ours = policy(
+        r#"{"Version":"2012-10-17","Statement":[
+        {"Sid":"Read","Effect":"Allow","Action":"s3:GetObject","Resource":"arn:aws:s3:::archive"},
+        {"Sid":"Write","Effect":"Allow","Action":"s3:PutObject","Resource":"arn:aws:s3:::reports"},
+        {"Effect":"Deny","Action":"s3:DeleteBucket","Resource":"*"}
+    ]}"#,
+    );
+    let theirs = policy(
+        r#"{"Version":"2012-10-17","Statement":[
+        {"Sid":"Read","Effect":"Allow","Action":"s3:GetObjectVersion","Resource":"arn:aws:s3:::reports"},
+        {"Sid":"Write","Effect":"Allow","Action":"s3:PutObject","Resource":"arn:aws:s3:::reports"},
+        {"Effect":"Deny","Action":"s3:DeleteBucket","Resource":"*"}
+    ]}"#,
+    );
+    let merge = three_way_merge(&policy(BASE), &ours, &theirs);
+
+    assert!(merge.is_clean());
+    assert_eq!(
+        merge.policy.statement[0].to_string(),
+        policy(
+            r#"{"Statement":[{"Sid":"Read","Effect":"Allow","Action":"s3:GetObjectVersion","Resource":"arn:aws:s3:::archive"}]}"#
+        )
+        .statement[0]
+            .to_string()
+    );
+}
+
+#[test]
+fn test_merge_conflicting_actions() {
+    let ours = policy(&BASE.replace("s3:PutObject\"", "s3:PutObjectAcl\""));
+    let theirs = policy(&BASE.replace("s3:PutObject\"", "s3:PutObjectTagging\""));
+    let merge = three_way_merge(&policy(BASE), &ours, &theirs);
+
+    assert_eq!(
+        merge.conflicts,
+        vec![MergeConflict {
+            sid: sid("Write"),
+            element: Some("Action"),
+            kind: ConflictKind::BothModified,
+        }]
+    );
+    assert!(merge.policy.statement[1]
+        .action()
+        .matches("s3:PutObjectAcl"));
+    assert_eq!(
+        merge.conflicts[0].to_string(),
+        "statement Write, Action modified differently in both versions"
+    );
+}
+
+#[test]
+fn test_merge_modified_and_removed() {
+    let ours = policy(&BASE.replace("s3:PutObject\"", "s3:PutObjectAcl\""));
+    let theirs = policy(
+        r#"{"Version":"2012-10-17","Statement":[
+        {"Sid":"Read","Effect":"Allow","Action":"s3:GetObject","Resource":"arn:aws:s3:::reports"},
+        {"Effect":"Deny","Action":"s3:DeleteBucket","Resource":"*"}
+    ]}"#,
+    );
+    let merge = three_way_merge(&policy(BASE), &ours, &theirs);
+
+    assert_eq!(merge.conflicts.len(), 1);
+    assert_eq!(merge.conflicts[0].sid, sid("Write"));
+    assert_eq!(merge.conflicts[0].kind, ConflictKind::ModifiedAndRemoved);
+    assert_eq!(merge.policy.statement.len(), 3);
+}
+
+#[test]
+fn test_merge_both_added() {
+    let ours = policy(&BASE.replace(
+        "]}",
+        r#",{"Sid":"List","Effect":"Allow","Action":"s3:ListBucket","Resource":"*"}]}"#,
+    ));
+    let theirs = policy(&BASE.replace(
+        "]}",
+        r#",{"Sid":"List","Effect":"Allow","Action":"s3:ListAllMyBuckets","Resource":"*"}]}"#,
+    ));
+    let merge = three_way_merge(&policy(BASE), &ours, &theirs);
+
+    assert_eq!(merge.conflicts.len(), 1);
+    assert_eq!(merge.conflicts[0].kind, ConflictKind::BothAdded);
+    assert_eq!(merge.policy.statement.len(), 4);
+
+    let merge = three_way_merge(&policy(BASE), &ours, &ours);
+    assert!(merge.is_clean());
+    assert_eq!(merge.policy, ours);
+}
+
+#[test]
+fn test_diff_unnamed_modified() {
+    let after = policy(&BASE.replace("s3:DeleteBucket", "s3:DeleteObject"));
+    let changes = diff(&policy(BASE), &after);
+
+    assert_eq!(changes.len(), 1);
+    match &changes[0] {
+        StatementChange::Modified {
+            before,
+            after,
+            elements,
+        } => {
+            assert!(before.sid.is_none() && after.sid.is_none());
+            assert_eq!(elements, &vec!["Action"]);
+        }
+        change => panic!("expecting a modification, not {:?}", change),
+    }
+}
+
+#[test]
+fn test_merge_unnamed_both_modified() {
+    let ours = policy(&BASE.replace("s3:DeleteBucket", "s3:DeleteObject"));
+    let theirs = policy(&BASE.replace("s3:DeleteBucket", "s3:DeleteBucketPolicy"));
+    let merge = three_way_merge(&policy(BASE), &ours, &theirs);
+
+    assert_eq!(
+        merge.conflicts,
+        vec![MergeConflict {
+            sid: None,
+            element: Some("Action"),
+            kind: ConflictKind::BothModified,
+        }]
+    );
+    assert_eq!(merge.policy, ours);
+}
+
+#[test]
+fn test_merge_unnamed_different_elements() {
+    let ours = policy(&BASE.replace("s3:DeleteBucket", "s3:DeleteObject"));
+    let theirs = policy(&BASE.replace(r#""Resource":"*""#, r#""Resource":"arn:aws:s3:::reports""#));
+    let merge = three_way_merge(&policy(BASE), &ours, &theirs);
+
+    assert!(merge.is_clean());
+    assert_eq!(merge.policy.statement.len(), 3);
+    assert_eq!(
+        merge.policy,
+        policy(&BASE.replace(
+            r#"{"Effect":"Deny","Action":"s3:DeleteBucket","Resource":"*"}"#,
+            r#"{"Effect":"Deny","Action":"s3:DeleteObject","Resource":"arn:aws:s3:::reports"}"#,
+        ))
+    );
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "document")]
+
+use aws_iam::document::visitor::walk_policy;
+use aws_iam::document::{LatexGenerator, MarkdownGenerator, TerminalGenerator};
+use aws_iam::io;
+use aws_iam::model::Policy;
+use std::cell::RefCell;
+use std::io::Write;
+use std::rc::Rc;
+
+const POLICY: &str = r#"{
+  "Version": "2012-10-17",
+  "Id": "threads",
+  "Statement": [
+    {
+      "Sid": "NoAttributes",
+      "Effect": "Deny",
+      "Principal": { "AWS": ["arn:aws:iam::123456789012:root"], "Service": "lambda.amazonaws.com" },
+      "Action": "dynamodb:PutItem",
+      "Resource": "arn:aws:dynamodb:*:*:table/Thread",
+      "Condition": {
+        "ForAnyValue:StringEquals": { "dynamodb:Attributes": ["ID", "PostDateTime"] },
+        "NumericLessThanIfExists": { "aws:MultiFactorAuthAge": 3600 }
+      }
+    },
+    {
+      "Effect": "Allow",
+      "NotAction": ["s3:DeleteObject", "s3:DeleteBucket"]
+    }
+  ]
+}"#;
+
+#[derive(Clone, Default)]
+struct Buffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Buffer {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).unwrap()
+    }
+}
+
+fn policy() -> Policy {
+    io::read_from_string(POLICY).unwrap()
+}
+
+#[test]
+fn test_markdown() {
+    let buffer = Buffer::default();
+    walk_policy(&policy(), &mut MarkdownGenerator::new(buffer.clone()));
+    let markdown = buffer.contents();
+    assert!(markdown.contains("> Policy ID: threads"));
+    assert_eq!(markdown.matches("## Statement").count(), 2);
+    assert!(markdown.contains("* `Principal `**`IN`**"));
+    assert!(markdown
+        .contains("   * *`type`*` = Service `**`AND`**` `*`id`*` = \"lambda.amazonaws.com\"`"));
+    assert!(markdown.contains(
+        "* `Condition `*`dynamodb:Attributes`*` `**`StringEquals`**` `**`∃`**` [\"ID\", \"PostDateTime\"]`"
+    ));
+    assert!(markdown.contains("`**`NumericLessThan`**` 3600`"));
+    assert!(markdown
+        .contains("* `Action `**`NOT`**` `**`IN`**` [\"s3:DeleteObject\", \"s3:DeleteBucket\"]`"));
+}
+
+#[test]
+fn test_latex() {
+    let buffer = Buffer::default();
+    walk_policy(&policy(), &mut LatexGenerator::new(buffer.clone(), false));
+    let latex = buffer.contents();
+    assert!(!latex.contains("\\documentclass"));
+    assert!(latex.contains(
+        "\\textit{type} $=$ AWS $\\wedge$ \\textit{id} $=$ ``arn:aws:iam::123456789012:root''."
+    ));
+    assert!(latex
+        .contains("$\\exists(v)$\\textit{dynamodb:Attributes} $=$ \\{``ID'', ``PostDateTime''\\}"));
+    assert!(latex.contains("\\textit{aws:MultiFactorAuthAge} $<$ 3600"));
+}
+
+#[test]
+fn test_terminal() {
+    let buffer = Buffer::default();
+    walk_policy(
+        &policy(),
+        &mut TerminalGenerator::new(buffer.clone()).with_color(false),
+    );
+    let text = buffer.contents();
+    assert!(text.contains("  Effect      DENY\n"));
+    assert!(text.contains("  Principal   AWS arn:aws:iam::123456789012:root\n"));
+    assert!(text.contains("              Service lambda.amazonaws.com\n"));
+    assert!(text.contains(
+        "  Condition   dynamodb:Attributes ForAnyValue:StringEquals \"ID\", \"PostDateTime\"\n"
+    ));
+    assert!(text.contains("aws:MultiFactorAuthAge NumericLessThanIfExists 3600\n"));
+    assert!(text.contains("  Action      NOT s3:DeleteObject, s3:DeleteBucket\n"));
+    assert!(!text.contains('\x1b'));
+}
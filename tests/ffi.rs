@@ -0,0 +1,71 @@
+#![cfg(feature = "ffi")]
+
+use aws_iam::ffi::*;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+
+fn take_string(s: *mut c_char) -> String {
+    assert!(!s.is_null());
+    let result = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string();
+    unsafe { aws_iam_string_free(s) };
+    result
+}
+
+#[test]
+fn test_parse_and_validate() {
+    let json = CString::new(
+        r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject","Resource":"*"}]}"#,
+    )
+    .unwrap();
+    let mut error: *mut c_char = ptr::null_mut();
+    let policy = unsafe { aws_iam_parse(json.as_ptr(), &mut error) };
+    assert!(!policy.is_null());
+    assert!(error.is_null());
+
+    let findings =
+        unsafe { aws_iam_validate(policy, AWS_IAM_POLICY_TYPE_RESOURCE, ptr::null_mut()) };
+    let findings: serde_json::Value = serde_json::from_str(&take_string(findings)).unwrap();
+    assert!(findings.is_array());
+
+    let findings = unsafe { aws_iam_validate(policy, 99, &mut error) };
+    assert!(findings.is_null());
+    assert_eq!(take_string(error), "unknown policy type 99");
+
+    unsafe { aws_iam_policy_free(policy) };
+}
+
+#[test]
+fn test_parse_error() {
+    let json = CString::new(r#"{"Statement":"#).unwrap();
+    let mut error: *mut c_char = ptr::null_mut();
+    let policy = unsafe { aws_iam_parse(json.as_ptr(), &mut error) };
+    assert!(policy.is_null());
+    assert!(!take_string(error).is_empty());
+
+    let policy = unsafe { aws_iam_parse(ptr::null(), ptr::null_mut()) };
+    assert!(policy.is_null());
+}
+
+#[cfg(feature = "offline_eval")]
+#[test]
+fn test_evaluate_error() {
+    let json = CString::new(
+        r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject","Resource":"*"}]}"#,
+    )
+    .unwrap();
+    let policy = unsafe { aws_iam_parse(json.as_ptr(), ptr::null_mut()) };
+    assert!(!policy.is_null());
+
+    let request = CString::new(r#"{"action":"s3:GetObject","resource":42}"#).unwrap();
+    let mut error: *mut c_char = ptr::null_mut();
+    let result = unsafe { aws_iam_evaluate(policy, request.as_ptr(), &mut error) };
+    assert_eq!(result, AWS_IAM_ERROR);
+    assert!(take_string(error).starts_with("invalid type"));
+
+    let result = unsafe { aws_iam_evaluate(ptr::null(), request.as_ptr(), &mut error) };
+    assert_eq!(result, AWS_IAM_ERROR);
+    assert_eq!(take_string(error), "policy is NULL");
+
+    unsafe { aws_iam_policy_free(policy) };
+}
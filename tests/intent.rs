@@ -0,0 +1,70 @@
+#![cfg(feature = "service_config")]
+
+use aws_iam::intent::{generate_policy, Intent};
+use aws_iam::service::actions::AccessLevel;
+
+const TABLE: &str = "arn:aws:dynamodb:us-east-1:123456789012:table/orders";
+
+#[test]
+fn test_intent_levels() {
+    let list = Intent::ListOnly("dynamodb".to_string(), TABLE.to_string())
+        .statement()
+        .unwrap();
+    assert_eq!(list.sid.as_ref().unwrap().to_string(), "DynamodbListOnly");
+    assert!(list.allows_action("dynamodb:ListTables"));
+    assert!(!list.allows_action("dynamodb:GetItem"));
+
+    let read = Intent::ReadOnly("dynamodb".to_string(), TABLE.to_string())
+        .statement()
+        .unwrap();
+    assert!(read.allows_action("dynamodb:Query"));
+    assert!(!read.allows_action("dynamodb:PutItem"));
+    assert!(!read.allows_action("dynamodb:TagResource"));
+
+    let write = Intent::ReadWrite("dynamodb".to_string(), TABLE.to_string())
+        .statement()
+        .unwrap();
+    assert!(write.allows_action("dynamodb:PutItem"));
+    assert!(!write.allows_action("dynamodb:TagResource"));
+
+    let admin = Intent::Admin("s3".to_string(), "arn:aws:s3:::bucket".to_string());
+    assert_eq!(admin.access_levels().len(), 5);
+    assert!(admin
+        .access_levels()
+        .contains(&AccessLevel::PermissionsManagement));
+    let admin = admin.statement().unwrap();
+    assert!(admin.allows_action("s3:PutBucketPolicy"));
+    assert!(admin.allows_action("s3:PutObjectTagging"));
+    // Least privilege: actions are listed, never wildcards.
+    assert!(!admin.allows_action("s3:PutBucketVersioning"));
+}
+
+#[test]
+fn test_generate_policy() {
+    let policy = generate_policy(&[
+        Intent::ReadOnly("s3".to_string(), "arn:aws:s3:::bucket".to_string()),
+        Intent::ReadWrite("sqs".to_string(), "*".to_string()),
+    ])
+    .unwrap();
+    assert_eq!(policy.statement.len(), 2);
+    assert_eq!(
+        policy.statement[1].sid.as_ref().unwrap().to_string(),
+        "SqsReadWrite"
+    );
+    assert!(policy.statement[1].allows_action("sqs:SendMessage"));
+}
+
+#[test]
+fn test_generate_policy_errors() {
+    assert!(generate_policy(&[]).is_err());
+    assert!(generate_policy(&[Intent::ReadOnly(
+        "nosuchservice".to_string(),
+        "*".to_string()
+    )])
+    .is_err());
+    assert!(generate_policy(&[Intent::ReadOnly(
+        "s3".to_string(),
+        "arn:aws:s3:::bucket name".to_string()
+    )])
+    .is_err());
+}
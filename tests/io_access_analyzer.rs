@@ -0,0 +1,81 @@
+use aws_iam::io::read_access_analyzer_findings;
+use aws_iam::model::Policy;
+use std::str::FromStr;
+
+const POLICY: &str = r#"{
+  "Version": "2012-10-17",
+  "Statement": [
+    {
+      "Sid": "PublicRead",
+      "Effect": "Allow",
+      "Principal": "*",
+      "Action": "s3:GetObject",
+      "Resource": "arn:aws:s3:::bucket/public/*"
+    },
+    {
+      "Sid": "PartnerRead",
+      "Effect": "Allow",
+      "Principal": { "AWS": "444455556666" },
+      "Action": ["s3:GetObject", "s3:ListBucket"],
+      "Resource": ["arn:aws:s3:::bucket", "arn:aws:s3:::bucket/*"]
+    },
+    {
+      "Sid": "Logging",
+      "Effect": "Allow",
+      "Principal": { "Service": "cloudtrail.amazonaws.com" },
+      "Action": "s3:PutObject",
+      "Resource": "arn:aws:s3:::bucket/logs/*"
+    }
+  ]
+}"#;
+
+#[test]
+fn test_read_findings_forms() {
+    let finding = r#"{"id":"f1","principal":{"AWS":"444455556666"},"action":["s3:ListBucket"],
+        "resource":"arn:aws:s3:::bucket","isPublic":false,"status":"ACTIVE"}"#;
+    for content in &[
+        format!(r#"{{"findings":[{}]}}"#, finding),
+        format!(r#"{{"finding":{}}}"#, finding),
+        format!("[{}]", finding),
+        finding.to_string(),
+    ] {
+        let findings = read_access_analyzer_findings(content.as_bytes()).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].id, "f1");
+        assert_eq!(findings[0].action, vec!["s3:ListBucket"]);
+        assert_eq!(findings[0].status.as_deref(), Some("ACTIVE"));
+    }
+    assert!(read_access_analyzer_findings("42".as_bytes()).is_err());
+}
+
+#[test]
+fn test_correlate_findings() {
+    let policy = Policy::from_str(POLICY).unwrap();
+    let findings = read_access_analyzer_findings(
+        r#"{"findings":[
+            {"id":"public","principal":{"AWS":"*"},"action":["s3:GetObject"],
+             "resource":"arn:aws:s3:::bucket/public/index.html","isPublic":true},
+            {"id":"partner","principal":{"AWS":"444455556666"},"action":["s3:GetObject"],
+             "resource":"arn:aws:s3:::bucket/public/index.html","isPublic":false},
+            {"id":"service","principal":{"Service":"cloudtrail.amazonaws.com"},
+             "action":["s3:PutObject"],"resource":"arn:aws:s3:::bucket/logs/1"},
+            {"id":"unknown","principal":{"AWS":"777788889999"},"action":["s3:GetObject"],
+             "resource":"arn:aws:s3:::bucket/private/key"}
+        ]}"#
+        .as_bytes(),
+    )
+    .unwrap();
+    let statements: Vec<(&str, Vec<usize>)> = findings
+        .iter()
+        .map(|finding| (finding.id.as_str(), finding.statements(&policy)))
+        .collect();
+    assert_eq!(
+        statements,
+        vec![
+            ("public", vec![0]),
+            ("partner", vec![0, 1]),
+            ("service", vec![2]),
+            ("unknown", vec![]),
+        ]
+    );
+}
@@ -0,0 +1,200 @@
+use aws_iam::io::read_authorization_details;
+
+const DETAILS: &str = r#"{
+  "UserDetailList": [
+    {
+      "Path": "/",
+      "UserName": "alice",
+      "UserId": "AIDAEXAMPLEALICE",
+      "Arn": "arn:aws:iam::123456789012:user/alice",
+      "GroupList": ["admins"],
+      "UserPolicyList": [
+        {
+          "PolicyName": "own-keys",
+          "PolicyDocument": "%7B%22Statement%22%3A%5B%7B%22Effect%22%3A%22Allow%22%2C%22Action%22%3A%22iam%3ACreateAccessKey%22%2C%22Resource%22%3A%22*%22%7D%5D%7D"
+        }
+      ],
+      "AttachedManagedPolicies": [],
+      "PermissionsBoundary": {
+        "PermissionsBoundaryType": "Policy",
+        "PermissionsBoundaryArn": "arn:aws:iam::123456789012:policy/boundary"
+      }
+    }
+  ],
+  "GroupDetailList": [
+    {
+      "Path": "/",
+      "GroupName": "admins",
+      "GroupId": "AGPAEXAMPLEADMINS",
+      "Arn": "arn:aws:iam::123456789012:group/admins",
+      "GroupPolicyList": [],
+      "AttachedManagedPolicies": [
+        {
+          "PolicyName": "kms-decrypt",
+          "PolicyArn": "arn:aws:iam::123456789012:policy/kms-decrypt"
+        },
+        {
+          "PolicyName": "AdministratorAccess",
+          "PolicyArn": "arn:aws:iam::aws:policy/AdministratorAccess"
+        }
+      ]
+    }
+  ],
+  "RoleDetailList": [
+    {
+      "Path": "/service-role/",
+      "RoleName": "lambda",
+      "RoleId": "AROAEXAMPLELAMBDA",
+      "Arn": "arn:aws:iam::123456789012:role/service-role/lambda",
+      "AssumeRolePolicyDocument": {
+        "Version": "2012-10-17",
+        "Statement": [
+          {
+            "Effect": "Allow",
+            "Principal": { "Service": "lambda.amazonaws.com" },
+            "Action": "sts:AssumeRole"
+          }
+        ]
+      },
+      "RolePolicyList": [
+        {
+          "PolicyName": "logs",
+          "PolicyDocument": {
+            "Statement": [
+              { "Effect": "Allow", "Action": "logs:PutLogEvents", "Resource": "*" }
+            ]
+          }
+        }
+      ],
+      "AttachedManagedPolicies": [
+        {
+          "PolicyName": "kms-decrypt",
+          "PolicyArn": "arn:aws:iam::123456789012:policy/kms-decrypt"
+        }
+      ]
+    }
+  ],
+  "Policies": [
+    {
+      "PolicyName": "kms-decrypt",
+      "PolicyId": "ANPAEXAMPLEKMS",
+      "Arn": "arn:aws:iam::123456789012:policy/kms-decrypt",
+      "Path": "/",
+      "DefaultVersionId": "v2",
+      "PolicyVersionList": [
+        {
+          "Document": {
+            "Statement": [
+              { "Effect": "Allow", "Action": "kms:Encrypt", "Resource": "*" }
+            ]
+          },
+          "VersionId": "v1",
+          "IsDefaultVersion": false
+        },
+        {
+          "Document": {
+            "Statement": [
+              { "Effect": "Allow", "Action": "kms:Decrypt", "Resource": "*" }
+            ]
+          },
+          "VersionId": "v2",
+          "IsDefaultVersion": true
+        }
+      ]
+    },
+    {
+      "PolicyName": "boundary",
+      "PolicyId": "ANPAEXAMPLEBOUNDARY",
+      "Arn": "arn:aws:iam::123456789012:policy/boundary",
+      "Path": "/",
+      "DefaultVersionId": "v1",
+      "PolicyVersionList": [
+        {
+          "Document": {
+            "Statement": [
+              { "Effect": "Allow", "Action": "kms:*", "Resource": "*" }
+            ]
+          },
+          "VersionId": "v1",
+          "IsDefaultVersion": true
+        }
+      ]
+    }
+  ]
+}"#;
+
+const ALICE: &str = "arn:aws:iam::123456789012:user/alice";
+const LAMBDA: &str = "arn:aws:iam::123456789012:role/service-role/lambda";
+
+#[test]
+fn test_read_authorization_details() {
+    let details = read_authorization_details(DETAILS.as_bytes()).unwrap();
+
+    assert_eq!(details.users.len(), 1);
+    assert_eq!(details.groups.len(), 1);
+    assert_eq!(details.roles.len(), 1);
+    assert_eq!(details.policies.len(), 2);
+
+    let alice = &details.users[0];
+    assert_eq!(alice.name, "alice");
+    assert_eq!(alice.groups, vec!["admins".to_string()]);
+    assert_eq!(
+        alice.permissions_boundary.as_deref(),
+        Some("arn:aws:iam::123456789012:policy/boundary")
+    );
+    // URL-encoded documents, as returned by the API, are decoded.
+    assert!(alice.inline_policies[0].document.statement[0].allows_action("iam:CreateAccessKey"));
+
+    let lambda = &details.roles[0];
+    assert_eq!(lambda.path, "/service-role/");
+    assert!(lambda.trust_policy.as_ref().unwrap().statement[0].allows_action("sts:AssumeRole"));
+
+    assert_eq!(
+        details.principal_arns().collect::<Vec<&str>>(),
+        vec![ALICE, LAMBDA]
+    );
+}
+
+#[test]
+fn test_default_policy_version() {
+    let details = read_authorization_details(DETAILS.as_bytes()).unwrap();
+
+    let policy = details
+        .managed_policy("arn:aws:iam::123456789012:policy/kms-decrypt")
+        .unwrap();
+    assert!(policy.statement[0].allows_action("kms:Decrypt"));
+    assert!(!policy.statement[0].allows_action("kms:Encrypt"));
+}
+
+#[test]
+fn test_identity_policies() {
+    let details = read_authorization_details(DETAILS.as_bytes()).unwrap();
+
+    // Inline policy, and the group's attached policy; AdministratorAccess is not included.
+    let policies = details.identity_policies(ALICE);
+    assert_eq!(policies.len(), 2);
+    assert!(policies[1].statement[0].allows_action("kms:Decrypt"));
+    assert!(details.permissions_boundary(ALICE).is_some());
+
+    let policies = details.identity_policies(LAMBDA);
+    assert_eq!(policies.len(), 2);
+    assert!(details.permissions_boundary(LAMBDA).is_none());
+
+    assert!(details
+        .identity_policies("arn:aws:iam::123456789012:user/nobody")
+        .is_empty());
+}
+
+#[test]
+fn test_read_bad_document() {
+    assert!(read_authorization_details(r#"{"UserDetailList": 1}"#.as_bytes()).is_err());
+    assert!(read_authorization_details(
+        r#"{"Policies": [{
+            "PolicyName": "bad",
+            "Arn": "arn:aws:iam::123456789012:policy/bad",
+            "PolicyVersionList": [{"Document": {"Statement": 42}, "IsDefaultVersion": true}]
+        }]}"#
+            .as_bytes()
+    )
+    .is_err());
+}
@@ -0,0 +1,142 @@
+#![cfg(feature = "bundle")]
+
+use aws_iam::error::{BundleError, IamError};
+use aws_iam::io::bundle::{read, read_from_reader, write, write_to_writer, Bundle, BundleOptions};
+use aws_iam::io::read_from_string;
+use std::fs::remove_file;
+use uuid::Uuid;
+
+const ALLOW_GET: &str = r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject","Resource":"arn:aws:s3:::bucket/*"}]}"#;
+
+const DENY_DELETE: &str =
+    r#"{"Statement":[{"Effect":"Deny","Action":"s3:DeleteObject","Resource":"*"}]}"#;
+
+fn bundle() -> Bundle {
+    Bundle::default()
+        .with_policy("deny.json", read_from_string(DENY_DELETE).unwrap())
+        .unwrap()
+        .with_policy("allow.json", read_from_string(ALLOW_GET).unwrap())
+        .unwrap()
+}
+
+fn to_bytes(bundle: &Bundle, options: &BundleOptions) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    write_to_writer(&mut bytes, bundle, options).unwrap();
+    bytes
+}
+
+#[test]
+fn test_bundle_entries() {
+    let bundle = bundle();
+    assert_eq!(bundle.len(), 2);
+    let names: Vec<&str> = bundle.entries().map(|entry| entry.name()).collect();
+    assert_eq!(names, vec!["allow.json", "deny.json"]);
+    assert!(bundle.get("allow.json").is_some());
+    assert!(bundle
+        .entries()
+        .all(|entry| entry.fingerprint().starts_with("sha256:")
+            && entry.fingerprint().len() == 71
+            && entry.signature().is_none()));
+
+    assert!(matches!(
+        Bundle::default().with_policy("../allow.json", read_from_string(ALLOW_GET).unwrap()),
+        Err(IamError::Bundle(BundleError::InvalidName(_)))
+    ));
+}
+
+#[test]
+fn test_bundle_round_trip() {
+    let bundle = bundle();
+    let options = BundleOptions::default();
+    let bytes = to_bytes(&bundle, &options);
+    assert_eq!(bytes, to_bytes(&bundle, &options));
+    assert_eq!(
+        read_from_reader(bytes.as_slice(), &options).unwrap(),
+        bundle
+    );
+}
+
+#[test]
+fn test_bundle_file_round_trip() {
+    let path = std::env::temp_dir().join(format!("aws-iam-{}.iampack", Uuid::new_v4()));
+    let options = BundleOptions::default().with_encryption_key([1; 32]);
+    write(&path, &bundle(), &options).unwrap();
+    assert_eq!(read(&path, &options).unwrap(), bundle());
+    remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_bundle_encrypted() {
+    let bundle = bundle();
+    let options = BundleOptions::default().with_encryption_key([1; 32]);
+    let bytes = to_bytes(&bundle, &options);
+    assert!(!String::from_utf8_lossy(&bytes).contains("s3:GetObject"));
+    assert_eq!(
+        read_from_reader(bytes.as_slice(), &options).unwrap(),
+        bundle
+    );
+
+    assert!(matches!(
+        read_from_reader(bytes.as_slice(), &BundleOptions::default()),
+        Err(IamError::Bundle(BundleError::MissingKey))
+    ));
+    assert!(matches!(
+        read_from_reader(
+            bytes.as_slice(),
+            &BundleOptions::default().with_encryption_key([2; 32])
+        ),
+        Err(IamError::Bundle(BundleError::Decryption))
+    ));
+}
+
+#[test]
+fn test_bundle_rejects_unencrypted_with_key() {
+    let bundle = bundle();
+    let bytes = to_bytes(&bundle, &BundleOptions::default());
+    assert!(matches!(
+        read_from_reader(
+            bytes.as_slice(),
+            &BundleOptions::default().with_encryption_key([1; 32])
+        ),
+        Err(IamError::Bundle(BundleError::NotEncrypted))
+    ));
+}
+
+#[test]
+fn test_bundle_rejects_tampered_policy() {
+    let bytes = to_bytes(&bundle(), &BundleOptions::default());
+    let tampered = String::from_utf8_lossy(&bytes).replace("s3:DeleteObject", "s3:DeleteBucket");
+    assert_eq!(tampered.len(), bytes.len());
+    assert!(matches!(
+        read_from_reader(tampered.as_bytes(), &BundleOptions::default()),
+        Err(IamError::Bundle(BundleError::FingerprintMismatch(name))) if name == "deny.json"
+    ));
+}
+
+#[cfg(feature = "signing")]
+#[test]
+fn test_bundle_signatures() {
+    use aws_iam::signing::SigningKey;
+
+    let key = SigningKey::from_bytes(&[7; 32]);
+    let trusted = BundleOptions::default().with_trusted_keys(vec![key.verifying_key()]);
+
+    let unsigned = to_bytes(&bundle(), &BundleOptions::default());
+    assert!(matches!(
+        read_from_reader(unsigned.as_slice(), &trusted),
+        Err(IamError::Bundle(BundleError::Unsigned(_)))
+    ));
+
+    let mut signed = bundle();
+    signed.sign(&key).unwrap();
+    assert!(signed.entries().all(|entry| entry.signature().is_some()));
+    let bytes = to_bytes(&signed, &BundleOptions::default());
+    assert_eq!(
+        read_from_reader(bytes.as_slice(), &trusted).unwrap(),
+        signed
+    );
+
+    let other = BundleOptions::default()
+        .with_trusted_keys(vec![SigningKey::from_bytes(&[8; 32]).verifying_key()]);
+    assert!(read_from_reader(bytes.as_slice(), &other).is_err());
+}
@@ -0,0 +1,80 @@
+use aws_iam::io::{
+    read_from_string, read_from_string_with_comments, to_string, to_string_with_comments,
+};
+use aws_iam::model::Comments;
+
+const COMMENTED: &str = r#"{
+  "Version": "2012-10-17",
+  "x-comment": "Owned by the data team",
+  "Statement": [
+    {
+      "Sid": "ReadReports",
+      "x-comment": "Reports are shared within the account",
+      "Effect": "Allow",
+      "Action": "s3:GetObject",
+      "Resource": "arn:aws:s3:::reports/2020.csv"
+    },
+    {
+      "Effect": "Deny",
+      "x-comment": "Never delete reports",
+      "Action": "s3:DeleteObject",
+      "Resource": "*"
+    }
+  ]
+}"#;
+
+#[test]
+fn test_read_ignores_comments() {
+    let policy = read_from_string(COMMENTED).unwrap();
+    assert_eq!(policy.statement.len(), 2);
+    assert!(!to_string(&policy, false).unwrap().contains("x-comment"));
+}
+
+#[test]
+fn test_read_comments() {
+    let (_, comments) = read_from_string_with_comments(COMMENTED).unwrap();
+    assert_eq!(comments.policy(), Some("Owned by the data team"));
+    assert_eq!(
+        comments.statement(0),
+        Some("Reports are shared within the account")
+    );
+    assert_eq!(comments.statement(1), Some("Never delete reports"));
+    assert_eq!(comments.statements().count(), 2);
+}
+
+#[test]
+fn test_comment_must_be_string() {
+    assert!(read_from_string_with_comments(
+        r#"{"x-comment": 42, "Statement": [{"Effect": "Allow", "Action": "s3:GetObject", "Resource": "*"}]}"#
+    )
+    .is_err());
+}
+
+#[test]
+fn test_write_comments() {
+    let (policy, comments) = read_from_string_with_comments(COMMENTED).unwrap();
+    let written = to_string_with_comments(&policy, &comments, false).unwrap();
+    assert!(written.starts_with(r#"{"x-comment":"Owned by the data team","#));
+    assert!(written.contains(
+        r#"{"Sid":"ReadReports","x-comment":"Reports are shared within the account","Effect""#
+    ));
+    assert!(written.contains(r#"{"x-comment":"Never delete reports","Effect""#));
+
+    let (reread, recomments) = read_from_string_with_comments(&written).unwrap();
+    assert_eq!(reread, policy);
+    assert_eq!(recomments, comments);
+}
+
+#[test]
+fn test_added_comments() {
+    let policy = read_from_string(COMMENTED).unwrap();
+    let comments = Comments::default()
+        .with_policy_comment("Reviewed")
+        .with_statement_comment(1, "Guard rail")
+        .with_statement_comment(7, "No such statement");
+    let written = to_string_with_comments(&policy, &comments, false).unwrap();
+    assert!(written.contains(r#""x-comment":"Reviewed""#));
+    assert!(written.contains(r#""x-comment":"Guard rail""#));
+    assert!(!written.contains("No such statement"));
+    assert!(Comments::default().is_empty());
+}
@@ -0,0 +1,173 @@
+use aws_iam::io::{
+    read_authorization_details, write_graph_json, write_graphml, EdgeKind, NodeKind,
+    PermissionGraph,
+};
+use serde_json::Value;
+
+const DETAILS: &str = r#"{
+  "UserDetailList": [
+    {
+      "UserName": "alice",
+      "Arn": "arn:aws:iam::123456789012:user/alice",
+      "GroupList": ["ops"],
+      "PermissionsBoundary": {
+        "PermissionsBoundaryArn": "arn:aws:iam::123456789012:policy/boundary"
+      }
+    }
+  ],
+  "GroupDetailList": [
+    {
+      "GroupName": "ops",
+      "Arn": "arn:aws:iam::123456789012:group/ops",
+      "AttachedManagedPolicies": [
+        { "PolicyArn": "arn:aws:iam::123456789012:policy/ops" }
+      ]
+    }
+  ],
+  "RoleDetailList": [
+    {
+      "RoleName": "deploy",
+      "Arn": "arn:aws:iam::123456789012:role/deploy",
+      "AssumeRolePolicyDocument": {
+        "Statement": [
+          {
+            "Effect": "Allow",
+            "Principal": { "Service": "codebuild.amazonaws.com" },
+            "Action": "sts:AssumeRole"
+          }
+        ]
+      },
+      "RolePolicyList": [
+        {
+          "PolicyName": "deploy",
+          "PolicyDocument": {
+            "Statement": [
+              {
+                "Sid": "NotLogs",
+                "Effect": "Deny",
+                "NotAction": "logs:*",
+                "Resource": "arn:aws:s3:::logs/<tag>"
+              }
+            ]
+          }
+        }
+      ],
+      "AttachedManagedPolicies": [
+        { "PolicyArn": "arn:aws:iam::123456789012:policy/ops" }
+      ]
+    }
+  ],
+  "Policies": [
+    {
+      "PolicyName": "ops",
+      "Arn": "arn:aws:iam::123456789012:policy/ops",
+      "PolicyVersionList": [
+        {
+          "Document": {
+            "Statement": [
+              { "Effect": "Allow", "Action": ["ec2:*", "s3:GetObject"], "Resource": "*" }
+            ]
+          },
+          "IsDefaultVersion": true
+        }
+      ]
+    }
+  ]
+}"#;
+
+fn has_edge(graph: &PermissionGraph, source: &str, target: &str, kind: EdgeKind) -> bool {
+    graph
+        .edges
+        .iter()
+        .any(|edge| edge.source == source && edge.target == target && edge.kind == kind)
+}
+
+#[test]
+fn test_graph_from_details() {
+    let details = read_authorization_details(DETAILS.as_bytes()).unwrap();
+    let graph = PermissionGraph::from_details(&details);
+
+    let ops_policy = "arn:aws:iam::123456789012:policy/ops";
+    assert_eq!(graph.node(ops_policy).unwrap().kind, NodeKind::Policy);
+    assert_eq!(graph.node(ops_policy).unwrap().label, "ops");
+    // The shared policy and its action nodes are only created once.
+    let ids: Vec<&str> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+    assert_eq!(ids.iter().filter(|id| **id == ops_policy).count(), 1);
+    assert_eq!(ids.iter().filter(|id| **id == "action:ec2:*").count(), 1);
+
+    assert!(has_edge(
+        &graph,
+        "arn:aws:iam::123456789012:user/alice",
+        "arn:aws:iam::123456789012:group/ops",
+        EdgeKind::MemberOf
+    ));
+    assert!(has_edge(
+        &graph,
+        "arn:aws:iam::123456789012:group/ops",
+        ops_policy,
+        EdgeKind::Attached
+    ));
+    assert!(has_edge(
+        &graph,
+        "arn:aws:iam::123456789012:role/deploy",
+        ops_policy,
+        EdgeKind::Attached
+    ));
+    assert!(has_edge(
+        &graph,
+        "arn:aws:iam::123456789012:user/alice",
+        "arn:aws:iam::123456789012:policy/boundary",
+        EdgeKind::PermissionsBoundary
+    ));
+    assert!(has_edge(
+        &graph,
+        "arn:aws:iam::123456789012:policy/ops/0",
+        "action:s3:GetObject",
+        EdgeKind::Allows
+    ));
+    assert!(has_edge(
+        &graph,
+        "arn:aws:iam::123456789012:policy/ops/0",
+        "resource:*",
+        EdgeKind::AppliesTo
+    ));
+    assert!(has_edge(
+        &graph,
+        "arn:aws:iam::123456789012:role/deploy#trust/0",
+        "principal:codebuild.amazonaws.com",
+        EdgeKind::Principal
+    ));
+
+    let inline_statement = "arn:aws:iam::123456789012:role/deploy#deploy/0";
+    assert_eq!(graph.node(inline_statement).unwrap().label, "NotLogs");
+    let denies: Vec<_> = graph
+        .edges
+        .iter()
+        .filter(|edge| edge.source == inline_statement && edge.kind == EdgeKind::Denies)
+        .collect();
+    assert_eq!(denies.len(), 1);
+    assert!(denies[0].negated);
+}
+
+#[test]
+fn test_write_graph() {
+    let details = read_authorization_details(DETAILS.as_bytes()).unwrap();
+    let graph = PermissionGraph::from_details(&details);
+
+    let mut json: Vec<u8> = Vec::new();
+    write_graph_json(&mut json, &graph).unwrap();
+    let json: Value = serde_json::from_slice(&json).unwrap();
+    assert_eq!(json["nodes"].as_array().unwrap().len(), graph.nodes.len());
+    assert_eq!(json["edges"].as_array().unwrap().len(), graph.edges.len());
+    assert_eq!(json["nodes"][0]["kind"], "policy");
+
+    let mut xml: Vec<u8> = Vec::new();
+    write_graphml(&mut xml, &graph).unwrap();
+    let xml = String::from_utf8(xml).unwrap();
+    assert!(xml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+    assert_eq!(xml.matches("<node ").count(), graph.nodes.len());
+    assert_eq!(xml.matches("<edge ").count(), graph.edges.len());
+    assert!(xml.contains(r#"<data key="kind">member_of</data>"#));
+    assert!(xml.contains("arn:aws:s3:::logs/&lt;tag&gt;"));
+    assert!(xml.trim_end().ends_with("</graphml>"));
+}
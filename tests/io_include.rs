@@ -0,0 +1,54 @@
+use aws_iam::error::{IamError, IncludeError};
+use aws_iam::io::{read_with_includes, to_string};
+use std::path::PathBuf;
+
+fn include_file(name: &str) -> PathBuf {
+    PathBuf::from("tests/data/includes").join(name)
+}
+
+#[test]
+fn test_read_with_includes() {
+    let policy = read_with_includes(&include_file("policy.json")).unwrap();
+    assert_eq!(policy.id.as_deref(), Some("composed"));
+
+    let sids: Vec<String> = policy
+        .statement
+        .iter()
+        .map(|statement| statement.sid.as_ref().unwrap().to_string())
+        .collect();
+    assert_eq!(
+        sids,
+        vec![
+            "ListBucket",
+            "ReadObjects",
+            "DenyDelete",
+            "DenyDeleteBucket"
+        ]
+    );
+
+    let condition = policy.statement[1].condition.as_ref().unwrap();
+    assert_eq!(condition.len(), 2);
+
+    let json = to_string(&policy, false).unwrap();
+    assert!(json.contains("\"Version\":\"2012-10-17\""));
+    assert!(json.contains("aws:MultiFactorAuthPresent"));
+    assert!(!json.contains("x-include"));
+}
+
+#[test]
+fn test_read_with_includes_cycle() {
+    assert!(matches!(
+        read_with_includes(&include_file("cycle.json")),
+        Err(IamError::Include(IncludeError::Cycle { .. }))
+    ));
+}
+
+#[test]
+fn test_read_with_includes_missing() {
+    match read_with_includes(&include_file("missing.json")) {
+        Err(IamError::Include(IncludeError::Read { path, .. })) => {
+            assert!(path.ends_with("fragments/missing.json"))
+        }
+        result => panic!("unexpected result {:?}", result),
+    }
+}
@@ -0,0 +1,129 @@
+use aws_iam::analysis::{permissions_matrix, Access};
+use aws_iam::arn::ARN;
+use aws_iam::io::{read_authorization_details, write_matrix_csv, write_matrix_json};
+use serde_json::Value;
+use std::str::FromStr;
+
+const DETAILS: &str = r#"{
+  "UserDetailList": [
+    {
+      "UserName": "alice",
+      "Arn": "arn:aws:iam::123456789012:user/alice",
+      "UserPolicyList": [
+        {
+          "PolicyName": "bucket",
+          "PolicyDocument": {
+            "Statement": [
+              {
+                "Effect": "Allow",
+                "Action": "s3:*",
+                "Resource": "arn:aws:s3:::bucket/*"
+              },
+              {
+                "Effect": "Deny",
+                "Action": "s3:DeleteObject",
+                "Resource": "*"
+              }
+            ]
+          }
+        }
+      ]
+    }
+  ],
+  "RoleDetailList": [
+    {
+      "RoleName": "auditor",
+      "Arn": "arn:aws:iam::123456789012:role/auditor",
+      "RolePolicyList": [
+        {
+          "PolicyName": "read",
+          "PolicyDocument": {
+            "Statement": [
+              {
+                "Effect": "Allow",
+                "Action": "s3:GetObject",
+                "Resource": "*",
+                "Condition": { "Bool": { "aws:MultiFactorAuthPresent": "true" } }
+              }
+            ]
+          }
+        }
+      ]
+    }
+  ]
+}"#;
+
+const ACTIONS: &[&str] = &["s3:GetObject", "s3:PutObject", "s3:DeleteObject"];
+
+#[test]
+fn test_permissions_matrix() {
+    let details = read_authorization_details(DETAILS.as_bytes()).unwrap();
+    let matrix = permissions_matrix(&details, ACTIONS, &[]);
+
+    assert_eq!(matrix.actions, ACTIONS);
+    assert_eq!(matrix.rows.len(), 2);
+    assert_eq!(
+        matrix.rows[0].principal,
+        "arn:aws:iam::123456789012:user/alice"
+    );
+    assert_eq!(matrix.rows[0].resource, None);
+    assert_eq!(
+        matrix.rows[0].access,
+        vec![Access::Allow, Access::Allow, Access::Deny]
+    );
+    assert_eq!(
+        matrix.rows[1].access,
+        vec![Access::Conditional, Access::Deny, Access::Deny]
+    );
+}
+
+#[test]
+fn test_permissions_matrix_resources() {
+    let details = read_authorization_details(DETAILS.as_bytes()).unwrap();
+    let resources = vec![
+        ARN::from_str("arn:aws:s3:::bucket/key").unwrap(),
+        ARN::from_str("arn:aws:s3:::other/key").unwrap(),
+    ];
+    let matrix = permissions_matrix(&details, ACTIONS, &resources);
+
+    assert_eq!(matrix.rows.len(), 4);
+    assert_eq!(
+        matrix.rows[1].resource.as_deref(),
+        Some("arn:aws:s3:::other/key")
+    );
+    assert_eq!(
+        matrix.rows[1].access,
+        vec![Access::Deny, Access::Deny, Access::Deny]
+    );
+
+    let mut csv: Vec<u8> = Vec::new();
+    write_matrix_csv(&mut csv, &matrix).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "principal,resource,s3:GetObject,s3:PutObject,s3:DeleteObject"
+    );
+    assert_eq!(
+        lines[1],
+        "arn:aws:iam::123456789012:user/alice,arn:aws:s3:::bucket/key,allow,allow,deny"
+    );
+    assert_eq!(lines.len(), 5);
+}
+
+#[test]
+fn test_write_matrix_json() {
+    let details = read_authorization_details(DETAILS.as_bytes()).unwrap();
+    let matrix = permissions_matrix(&details, ACTIONS, &[]);
+
+    let mut json: Vec<u8> = Vec::new();
+    write_matrix_json(&mut json, &matrix).unwrap();
+    let json: Value = serde_json::from_slice(&json).unwrap();
+    assert_eq!(json["actions"][1], "s3:PutObject");
+    assert_eq!(
+        json["rows"][1]["principal"],
+        "arn:aws:iam::123456789012:role/auditor"
+    );
+    assert_eq!(json["rows"][1]["access"][0], "conditional");
+    assert!(json["rows"][1].get("resource").is_none());
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "relaxed_json")]
+
+use aws_iam::io::{read_from_file_relaxed, read_from_string, read_from_string_relaxed, to_string};
+use std::fs::{remove_file, write};
+use uuid::Uuid;
+
+const STRICT: &str = r#"{"Version":"2012-10-17","Statement":[{"Sid":"ReadReports","Effect":"Allow","Action":["s3:GetObject","s3:ListBucket"],"Resource":"*"}]}"#;
+
+const JSONC: &str = r#"{
+  // Reviewed by the platform team
+  "Version": "2012-10-17",
+  "Statement": [
+    {
+      "Sid": "ReadReports",
+      "Effect": "Allow",
+      /* listing is needed by the report viewer */
+      "Action": [
+        "s3:GetObject",
+        "s3:ListBucket",
+      ],
+      "Resource": "*",
+    },
+  ],
+}"#;
+
+const JSON5: &str = r#"{
+  Version: '2012-10-17',
+  Statement: [{Sid: 'ReadReports', Effect: 'Allow', Action: ['s3:GetObject', 's3:ListBucket'], Resource: '*'}],
+}"#;
+
+#[test]
+fn test_relaxed_jsonc() {
+    assert!(read_from_string(JSONC).is_err());
+    let policy = read_from_string_relaxed(JSONC).unwrap();
+    assert_eq!(policy, read_from_string(STRICT).unwrap());
+    assert_eq!(to_string(&policy, false).unwrap(), STRICT);
+}
+
+#[test]
+fn test_relaxed_json5() {
+    let policy = read_from_string_relaxed(JSON5).unwrap();
+    assert_eq!(to_string(&policy, false).unwrap(), STRICT);
+}
+
+#[test]
+fn test_relaxed_strict() {
+    assert_eq!(
+        read_from_string_relaxed(STRICT).unwrap(),
+        read_from_string(STRICT).unwrap()
+    );
+    assert!(read_from_string_relaxed("{\"Statement\": [").is_err());
+}
+
+#[test]
+fn test_relaxed_file() {
+    let path = std::env::temp_dir().join(format!("aws-iam-{}.jsonc", Uuid::new_v4()));
+    write(&path, JSONC).unwrap();
+    let policy = read_from_file_relaxed(&path).unwrap();
+    assert_eq!(policy.statement.len(), 1);
+    remove_file(&path).unwrap();
+}
@@ -0,0 +1,108 @@
+use aws_iam::io::{findings_to_sarif, write_findings_sarif, SarifArtifact};
+use aws_iam::validate::{validate_str, PolicyType, ValidationOptions};
+use serde_json::Value;
+
+const IDENTITY: &str = r#"{
+  "Version": "2012-10-17",
+  "Statement": [
+    {
+      "Sid": "Read",
+      "Effect": "Allow",
+      "Action": "s3:GetObject",
+      "Resource": "arn:aws:s3:::bucket/{\"key\"}"
+    },
+    {"Effect": "Allow", "Action": "s3:PutObject"},
+    {"Effect": "Deny", "Action": "s3:DeleteObject"}
+  ]
+}"#;
+
+const TRUST: &str =
+    r#"{"Statement":[{"Effect":"Allow","Principal":"*","Action":"sts:AssumeRole"}]}"#;
+
+#[test]
+fn test_findings_to_sarif() {
+    let identity = validate_str(IDENTITY, &ValidationOptions::default()).unwrap();
+    let trust = validate_str(TRUST, &ValidationOptions::for_type(PolicyType::Trust)).unwrap();
+    let sarif = findings_to_sarif(&[
+        SarifArtifact::new("policies/identity.json", &identity).with_text(IDENTITY),
+        SarifArtifact::new("policies/trust.json", &trust),
+    ]);
+
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "aws-iam");
+    let rules: Vec<&str> = run["tool"]["driver"]["rules"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(rules, vec!["MissingResource", "TrustPublicPrincipal"]);
+
+    let results: Vec<(&str, u64, &str, u64, &Value)> = run["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            let location = &r["locations"][0];
+            (
+                r["ruleId"].as_str().unwrap(),
+                r["ruleIndex"].as_u64().unwrap(),
+                location["physicalLocation"]["artifactLocation"]["uri"]
+                    .as_str()
+                    .unwrap(),
+                location["physicalLocation"]["region"]["startLine"]
+                    .as_u64()
+                    .unwrap(),
+                &location["logicalLocations"][0]["fullyQualifiedName"],
+            )
+        })
+        .collect();
+    assert_eq!(
+        results,
+        vec![
+            (
+                "MissingResource",
+                0,
+                "policies/identity.json",
+                10,
+                &Value::from("Statement[1]")
+            ),
+            (
+                "MissingResource",
+                0,
+                "policies/identity.json",
+                11,
+                &Value::from("Statement[2]")
+            ),
+            (
+                "TrustPublicPrincipal",
+                1,
+                "policies/trust.json",
+                1,
+                &Value::from("Statement[0]")
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_write_findings_sarif_single_statement() {
+    let text = "{\n  \"Statement\":\n    {\"Effect\": \"Allow\", \"Action\": \"s3:GetObject\"}\n}";
+    let findings = validate_str(
+        r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject"}]}"#,
+        &ValidationOptions::default(),
+    )
+    .unwrap();
+    let mut buffer: Vec<u8> = Default::default();
+    write_findings_sarif(
+        &mut buffer,
+        &[SarifArtifact::new("p.json", &findings).with_text(text)],
+    )
+    .unwrap();
+    let sarif: Value = serde_json::from_slice(&buffer).unwrap();
+    assert_eq!(
+        sarif["runs"][0]["results"][0]["locations"][0]["physicalLocation"]["region"]["startLine"],
+        3
+    );
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "watch")]
+
+use aws_iam::io::{watch_directory_with, PolicySetEvent, WatchOptions};
+use aws_iam::offline::{evaluate_all_compiled, EvaluationResult, Request};
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::path::PathBuf;
+use std::time::Duration;
+use uuid::Uuid;
+
+const ALLOW_GET: &str = r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject","Resource":"arn:aws:s3:::bucket/*"}]}"#;
+
+const DENY_DELETE: &str =
+    r#"{"Statement":[{"Effect":"Deny","Action":"s3:DeleteObject","Resource":"*"}]}"#;
+
+const MISSING_RESOURCE: &str = r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject"}]}"#;
+
+fn policy_directory() -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("aws-iam-watch-{}", Uuid::new_v4()));
+    create_dir_all(&directory).unwrap();
+    write(directory.join("allow.json"), ALLOW_GET).unwrap();
+    write(directory.join("README.md"), "not a policy").unwrap();
+    directory
+}
+
+fn request(action: &str) -> Request {
+    serde_json::from_value(serde_json::json!({
+        "action": action,
+        "resource": "arn:aws:s3:::bucket/key",
+        "environment": {}
+    }))
+    .unwrap()
+}
+
+fn options() -> WatchOptions {
+    WatchOptions::default().with_interval(Duration::from_secs(3600))
+}
+
+#[test]
+fn test_watch_initial_set() {
+    let directory = policy_directory();
+    let handle = watch_directory_with(&directory, options()).unwrap();
+    let current = handle.current();
+    assert_eq!(current.generation(), 0);
+    assert_eq!(current.len(), 1);
+    assert!(current.get("allow.json").is_some());
+    assert!(current.get("README.md").is_none());
+    assert!(!handle.reload().unwrap());
+    remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn test_watch_reload() {
+    let directory = policy_directory();
+    let handle = watch_directory_with(&directory, options()).unwrap();
+    let events = handle.subscribe();
+    let before = handle.current();
+
+    write(directory.join("deny.json"), DENY_DELETE).unwrap();
+    assert!(handle.reload().unwrap());
+
+    let after = handle.current();
+    assert_eq!(after.generation(), 1);
+    assert_eq!(after.len(), 2);
+    assert_eq!(before.len(), 1);
+    assert_eq!(after.compiled().len(), 2);
+    assert!(matches!(
+        evaluate_all_compiled(&request("s3:GetObject"), after.compiled()),
+        Ok(EvaluationResult::Allow(_))
+    ));
+    assert!(matches!(
+        evaluate_all_compiled(&request("s3:DeleteObject"), after.compiled()),
+        Ok(EvaluationResult::Deny(_, _, _))
+    ));
+    match events.try_recv().unwrap() {
+        PolicySetEvent::Reloaded(set) => assert_eq!(set.generation(), 1),
+        event => panic!("unexpected event {:?}", event),
+    }
+    remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn test_watch_rejects_invalid() {
+    let directory = policy_directory();
+    let handle = watch_directory_with(&directory, options()).unwrap();
+    let events = handle.subscribe();
+
+    write(directory.join("invalid.json"), MISSING_RESOURCE).unwrap();
+    assert!(handle.reload().is_err());
+    assert_eq!(handle.current().generation(), 0);
+    assert_eq!(handle.current().len(), 1);
+    assert!(matches!(
+        events.try_recv().unwrap(),
+        PolicySetEvent::Rejected(_)
+    ));
+
+    // the rejected files are not read again until they change.
+    assert!(!handle.reload().unwrap());
+    assert!(events.try_recv().is_err());
+    remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn test_watch_invalid_initial_set() {
+    let directory = policy_directory();
+    write(directory.join("invalid.json"), MISSING_RESOURCE).unwrap();
+    assert!(watch_directory_with(&directory, options()).is_err());
+    remove_dir_all(&directory).unwrap();
+}
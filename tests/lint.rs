@@ -0,0 +1,112 @@
+#[cfg(feature = "service_config")]
+use aws_iam::analysis::{FINDING_MISSING_DEPENDENT_ACTION, FINDING_WRITE_ACCESS_ANY_RESOURCE};
+use aws_iam::analysis::{FINDING_SENSITIVE_ACTION, FINDING_STATEMENT_EXPIRED};
+use aws_iam::baseline::{Baseline, FINDING_BASELINE_VIOLATION};
+use aws_iam::lint::{
+    LintRegistry, LintRule, ValidationRule, RULE_BASELINE, RULE_CONTRADICTIONS,
+    RULE_EXPIRED_STATEMENTS, RULE_SENSITIVE_ACTIONS, RULE_VALIDATION,
+};
+#[cfg(feature = "service_config")]
+use aws_iam::lint::{RULE_ACCESS_LEVEL, RULE_DEPENDENT_ACTIONS};
+use aws_iam::model::Policy;
+use aws_iam::validate::{
+    Finding, Findings, PolicyType, Severity, ValidationOptions, FINDING_DUPLICATE_SID,
+};
+use std::str::FromStr;
+
+const FINDING_EXAMPLE_MISSING_SID: &str = "ExampleMissingSid";
+
+struct RequireSid;
+
+impl LintRule for RequireSid {
+    fn name(&self) -> &'static str {
+        "ExampleRequireSid"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check(&self, policy: &Policy, findings: &mut Findings) {
+        for (index, statement) in policy.statements().enumerate() {
+            if statement.sid.is_none() {
+                findings.push(Finding::error(
+                    FINDING_EXAMPLE_MISSING_SID,
+                    "statement has no Sid",
+                    Some(index),
+                ));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_registry() {
+    let mut registry = LintRegistry::default();
+    let mut names = vec![
+        RULE_VALIDATION,
+        RULE_SENSITIVE_ACTIONS,
+        RULE_CONTRADICTIONS,
+        RULE_EXPIRED_STATEMENTS,
+    ];
+    #[cfg(feature = "service_config")]
+    names.extend([RULE_ACCESS_LEVEL, RULE_DEPENDENT_ACTIONS]);
+    assert_eq!(registry.names(), names);
+
+    registry.register(RequireSid);
+    registry.register(ValidationRule(ValidationOptions::for_type(
+        PolicyType::Resource,
+    )));
+    registry.register(
+        Baseline::from_str(r#"{"Rules":[{"Id":"NoDeny","Effect":"Deny","Forbid":true}]}"#).unwrap(),
+    );
+    // the validation rule replaces the built-in one.
+    assert_eq!(registry.names().len(), names.len() + 2);
+    assert_eq!(registry.names()[0], RULE_VALIDATION);
+    assert_eq!(
+        registry.get(RULE_BASELINE).unwrap().severity(),
+        Severity::Error
+    );
+
+    assert!(registry.unregister(RULE_EXPIRED_STATEMENTS));
+    assert!(!registry.unregister(RULE_EXPIRED_STATEMENTS));
+    assert!(registry.get(RULE_EXPIRED_STATEMENTS).is_none());
+    assert!(LintRegistry::new().names().is_empty());
+}
+
+#[test]
+fn test_registry_check() {
+    let policy = Policy::from_str(
+        r#"{"Statement":[
+            {"Sid":"Pass","Effect":"Allow","Action":"iam:PassRole","Resource":"*"},
+            {"Sid":"Pass","Effect":"Allow","Action":"s3:GetObject","Resource":"*",
+             "Condition":{"DateLessThan":{"aws:CurrentTime":"2000-01-01T00:00:00Z"}}},
+            {"Effect":"Deny","Action":"s3:DeleteBucket","Resource":"*"}
+        ]}"#,
+    )
+    .unwrap();
+    let registry = LintRegistry::default().with_rule(RequireSid).with_rule(
+        Baseline::from_str(r#"{"Rules":[{"Id":"NoDeny","Effect":"Deny","Forbid":true}]}"#).unwrap(),
+    );
+    let codes: Vec<(&str, Option<usize>)> = registry
+        .check(&policy)
+        .iter()
+        .map(|f| (f.code, f.statement))
+        .collect();
+
+    let mut expected = vec![
+        (FINDING_DUPLICATE_SID, Some(1)),
+        (FINDING_SENSITIVE_ACTION, Some(0)),
+        (FINDING_STATEMENT_EXPIRED, Some(1)),
+    ];
+    #[cfg(feature = "service_config")]
+    expected.extend([
+        (FINDING_WRITE_ACCESS_ANY_RESOURCE, Some(0)),
+        (FINDING_MISSING_DEPENDENT_ACTION, Some(1)),
+    ]);
+    expected.extend([
+        (FINDING_EXAMPLE_MISSING_SID, Some(2)),
+        (FINDING_BASELINE_VIOLATION, Some(2)),
+    ]);
+    assert_eq!(codes, expected);
+}
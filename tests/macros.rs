@@ -0,0 +1,56 @@
+#![cfg(feature = "macros")]
+
+use aws_iam::error::BuildError;
+use aws_iam::model::{Effect, Version};
+use aws_iam::policy;
+use aws_iam::syntax::IamValue;
+use serde_json::json;
+
+#[test]
+fn test_single_statement() {
+    let policy = policy! {
+        allow ["s3:GetObject"] on ["arn:aws:s3:::bucket/*"] when Bool("aws:SecureTransport") == true
+    }
+    .unwrap();
+    assert_eq!(policy.version, Some(Version::V2012));
+    assert_eq!(
+        policy.to_json().unwrap()["Statement"][0],
+        json!({
+            "Effect": "Allow",
+            "Action": "s3:GetObject",
+            "Resource": "arn:aws:s3:::bucket/*",
+            "Condition": {"Bool": {"aws:SecureTransport": "true"}}
+        })
+    );
+}
+
+#[test]
+fn test_multiple_statements() {
+    let policy = policy! {
+        allow ["s3:GetObject", "s3:ListBucket"]
+            on ["arn:aws:s3:::bucket", "arn:aws:s3:::bucket/*"]
+            when Bool("aws:SecureTransport") == true
+                and StringEquals("aws:PrincipalOrgID") == "o-a1b2c3d4e5";
+        deny ["*"] on ["*"];
+    }
+    .unwrap();
+    assert_eq!(policy.statement.len(), 2);
+    assert_eq!(policy.statement[0].condition.as_ref().unwrap().len(), 2);
+    assert_eq!(policy.statement[1].effect, Effect::Deny);
+    assert_eq!(
+        policy.to_json().unwrap()["Statement"][1],
+        json!({"Effect": "Deny", "Action": "*", "Resource": "*"})
+    );
+}
+
+#[test]
+fn test_without_resource() {
+    let policy = policy! { allow ["sqs:SendMessage"] }.unwrap();
+    assert!(policy.statement[0].resource.is_none());
+}
+
+#[test]
+fn test_invalid_action() {
+    let result = policy! { allow ["not an action"] on ["*"] };
+    assert!(matches!(result, Err(BuildError::Format(_))));
+}
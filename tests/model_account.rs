@@ -0,0 +1,72 @@
+use aws_iam::arn::AccountIdentifier;
+use aws_iam::model::Policy;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+const POLICY: &str = r#"{
+  "Version": "2012-10-17",
+  "Statement": [
+    {
+      "Effect": "Allow",
+      "Principal": { "AWS": ["111111111111", "arn:aws:iam::333333333333:role/deployer"] },
+      "Action": "sqs:SendMessage",
+      "Resource": "arn:aws:sqs:us-east-1:111111111111:queue",
+      "Condition": {
+        "ArnEquals": { "aws:SourceArn": "arn:aws:sns:us-east-1:111111111111:topic" },
+        "StringEquals": { "aws:SourceAccount": "222222222222" }
+      }
+    },
+    {
+      "Effect": "Allow",
+      "Action": "s3:GetObject",
+      "Resource": "arn:aws:s3:::bucket/${aws:PrincipalAccount}/*"
+    }
+  ]
+}"#;
+
+fn account(s: &str) -> AccountIdentifier {
+    AccountIdentifier::from_str(s).unwrap()
+}
+
+fn account_strings(policy: &Policy) -> Vec<String> {
+    policy
+        .referenced_accounts()
+        .iter()
+        .map(|account| account.to_string())
+        .collect()
+}
+
+#[test]
+fn test_referenced_accounts() {
+    let policy = Policy::from_str(POLICY).unwrap();
+    assert_eq!(
+        account_strings(&policy),
+        vec!["111111111111", "222222222222", "333333333333"]
+    );
+}
+
+#[test]
+fn test_rewrite_accounts() {
+    let policy = Policy::from_str(POLICY).unwrap();
+    let accounts: HashMap<AccountIdentifier, AccountIdentifier> = vec![
+        (account("111111111111"), account("444444444444")),
+        (account("222222222222"), account("111111111111")),
+    ]
+    .into_iter()
+    .collect();
+    let rewritten = policy.rewrite_accounts(&accounts).unwrap();
+
+    assert_eq!(
+        account_strings(&rewritten),
+        vec!["111111111111", "333333333333", "444444444444"]
+    );
+    assert_eq!(
+        rewritten.statement[0].resource().unwrap().to_string(),
+        r#"{"Resource":"arn:aws:sqs:us-east-1:444444444444:queue"}"#
+    );
+    assert_eq!(rewritten.statement[1], policy.statement[1]);
+    assert_eq!(
+        rewritten.rewrite_accounts(&HashMap::new()).unwrap(),
+        rewritten
+    );
+}
@@ -179,3 +179,39 @@ fn test_name_vec_from_json() {
         ]))
     );
 }
+
+#[test]
+fn test_qualified_name_matches() {
+    let get_object = QualifiedName::from_str("s3:GetObject").unwrap();
+    for (pattern, expected) in vec![
+        ("s3:GetObject", true),
+        ("S3:getobject", true),
+        ("s3:Get*", true),
+        ("s3:*", true),
+        ("s3:Get?bject", true),
+        ("s3:Put*", false),
+        ("s3:GetObjectAcl", false),
+        ("s3-object-lambda:GetObject", false),
+        ("ec2:*", false),
+    ] {
+        assert_eq!(
+            QualifiedName::from_str(pattern)
+                .unwrap()
+                .matches(&get_object),
+            expected,
+            "{}",
+            pattern
+        );
+    }
+}
+
+#[test]
+fn test_action_matches_wildcards() {
+    let action = Action::Action(OrAny::Some(vec![
+        QualifiedName::from_str("s3:Get*").unwrap()
+    ]));
+    assert!(action.matches("s3:GetObject"));
+    assert!(action.matches("S3:GETOBJECT"));
+    assert!(!action.matches("s3:PutObject"));
+    assert!(!action.matches("sqs:GetQueueUrl"));
+}
@@ -0,0 +1,198 @@
+use aws_iam::arn::ARN;
+use aws_iam::error::{BuildError, IamError};
+use aws_iam::model::builder::*;
+use aws_iam::model::{Effect, Policy, Statement};
+use aws_iam::syntax::IamValue;
+use serde_json::json;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::str::FromStr;
+
+#[test]
+fn test_statement_requires_action() {
+    let result = StatementBuilder::new().allows().build();
+    assert!(matches!(result, Err(BuildError::MissingAction)));
+}
+
+#[test]
+fn test_statement_requires_non_empty_actions() {
+    let result = StatementBuilder::new()
+        .actions(ActionBuilder::any_of())
+        .build();
+    assert!(matches!(result, Err(BuildError::Format(_))));
+}
+
+#[test]
+fn test_statement_without_resource() {
+    let statement = Statement::try_from(
+        StatementBuilder::new()
+            .allows()
+            .principals(PrincipalBuilder::any())
+            .actions(ActionBuilder::any()),
+    )
+    .unwrap();
+    assert_eq!(statement.effect, Effect::Allow);
+    assert!(statement.resource.is_none());
+    assert!(statement.principal.is_some());
+}
+
+#[test]
+fn test_policy_requires_statements() {
+    let result = PolicyBuilder::new().named("no-statements").build();
+    assert!(matches!(result, Err(BuildError::NoStatements)));
+}
+
+#[test]
+fn test_policy_reports_statement_index() {
+    let result = Policy::try_from(PolicyBuilder::new().evaluate_all(vec![
+        StatementBuilder::new().actions(ActionBuilder::any()),
+        StatementBuilder::new(),
+    ]));
+    assert!(matches!(
+        result,
+        Err(BuildError::Statement { index: 1, .. })
+    ));
+}
+
+#[test]
+fn test_policy_error_source() {
+    let error: IamError = Policy::try_from(PolicyBuilder::new().evaluate_all(vec![
+        StatementBuilder::new().actions(ActionBuilder::any()),
+        StatementBuilder::new(),
+    ]))
+    .unwrap_err()
+    .into();
+    assert!(matches!(
+        error,
+        IamError::Build(BuildError::Statement { .. })
+    ));
+    assert_eq!(
+        error.source().map(|source| source.to_string()),
+        Some(BuildError::MissingAction.to_string())
+    );
+}
+
+#[test]
+fn test_policy_invalid_id() {
+    let result = PolicyBuilder::new()
+        .named("")
+        .evaluate(StatementBuilder::new().actions(ActionBuilder::any()))
+        .build();
+    assert!(matches!(result, Err(BuildError::Format(_))));
+}
+
+#[test]
+fn test_principal_helpers() {
+    let statement = StatementBuilder::new()
+        .allows()
+        .principal_aws(ARN::from_str("arn:aws:iam::123456789012:root").unwrap())
+        .principal_service("lambda")
+        .federated("cognito-identity.amazonaws.com")
+        .actions(ActionBuilder::any_of().this("sts:AssumeRole".parse().unwrap()))
+        .build()
+        .unwrap();
+    let value = statement.to_json().unwrap();
+    assert_eq!(
+        value["Principal"]["AWS"],
+        json!("arn:aws:iam::123456789012:root")
+    );
+    assert!(value["Principal"]["Service"].is_string());
+    assert_eq!(
+        value["Principal"]["Federated"],
+        json!("cognito-identity.amazonaws.com")
+    );
+}
+
+#[test]
+fn test_invalid_service_principal() {
+    let result = StatementBuilder::new()
+        .principal_service("not a service")
+        .actions(ActionBuilder::any())
+        .build();
+    assert!(matches!(result, Err(BuildError::Format(_))));
+}
+
+#[test]
+fn test_conditions_are_merged() {
+    let statement = StatementBuilder::new()
+        .actions(ActionBuilder::any())
+        .resources(ResourceBuilder::any())
+        .if_condition(
+            ConditionBuilder::new_bool()
+                .right_hand_bool("aws:SecureTransport".parse().unwrap(), true),
+        )
+        .if_condition(
+            ConditionBuilder::new_string_equals()
+                .right_hand_str("aws:PrincipalOrgID".parse().unwrap(), "o-a1b2c3d4e5"),
+        )
+        .build()
+        .unwrap();
+    assert_eq!(statement.condition.unwrap().len(), 2);
+}
+
+#[test]
+fn test_condition_right_hand_tag() {
+    let condition = ConditionBuilder::new_string_equals()
+        .right_hand_tag("aws:PrincipalTag", "team", "payments")
+        .right_hand_tag("aws:ResourceTag", "cost-center", "1234")
+        .build()
+        .unwrap();
+    let mut statement = Statement::unnamed();
+    let _ = statement.set_condition(condition);
+    let value = statement.to_json().unwrap();
+    assert_eq!(
+        value["Condition"]["StringEquals"],
+        json!({"aws:PrincipalTag/team": "payments", "aws:ResourceTag/cost-center": "1234"})
+    );
+}
+
+#[test]
+fn test_condition_right_hand_tag_invalid() {
+    let result = ConditionBuilder::new_string_equals()
+        .right_hand_tag("PrincipalTag", "team", "payments")
+        .build();
+    assert!(matches!(result, Err(BuildError::Format(_))));
+}
+
+#[test]
+fn test_condition_operators() {
+    let condition = ConditionBuilder::new_not_ip_address()
+        .right_hand_str("aws:SourceIp".parse().unwrap(), "192.0.2.0/24")
+        .build()
+        .unwrap();
+    let mut statement = Statement::unnamed();
+    let _ = statement.set_condition(condition);
+    assert_eq!(
+        statement.to_json().unwrap()["Condition"],
+        json!({"NotIpAddress": {"aws:SourceIp": "192.0.2.0/24"}})
+    );
+
+    let condition = ConditionBuilder::new_numeric_less_than()
+        .if_exists()
+        .right_hand_number("aws:MultiFactorAuthAge".parse().unwrap(), 3600)
+        .build()
+        .unwrap();
+    let _ = statement.set_condition(condition);
+    assert_eq!(
+        statement.to_json().unwrap()["Condition"],
+        json!({"NumericLessThanIfExists": {"aws:MultiFactorAuthAge": "3600"}})
+    );
+
+    let condition = ConditionBuilder::new_arn_like()
+        .right_hand_arn(
+            "aws:SourceArn".parse().unwrap(),
+            &ARN::from_str("arn:aws:sns:us-east-1:123456789012:topic").unwrap(),
+        )
+        .build()
+        .unwrap();
+    let _ = statement.set_condition(condition);
+    assert_eq!(
+        statement.to_json().unwrap()["Condition"],
+        json!({"ArnLike": {"aws:SourceArn": "arn:aws:sns:us-east-1:123456789012:topic"}})
+    );
+
+    assert!(ConditionBuilder::new_null()
+        .right_hand_bool("aws:TokenIssueTime".parse().unwrap(), true)
+        .build()
+        .is_ok());
+}
@@ -1,6 +1,9 @@
-use aws_iam::context::keys::AWS_RESOURCE_TAG;
-use aws_iam::model::{Condition, Operator, QualifiedName};
-use aws_iam::syntax::IamProperty;
+use aws_iam::arn::ArnParseMode;
+use aws_iam::context::keys::{
+    describe_key, AWS_RESOURCE_TAG, AWS_SOURCE_IP, GLOBAL_CONDITION_KEYS,
+};
+use aws_iam::model::{Condition, GlobalOperator, Operator, Policy, QualifiedName, Quantifier};
+use aws_iam::syntax::{all_condition_operators, all_global_condition_keys, IamProperty};
 use serde_json::Map;
 use std::str::FromStr;
 
@@ -49,3 +52,412 @@ fn condition_to_json() {
     let _ = c.into_json_object(&mut json);
     println!("2: {:?}", json);
 }
+
+#[test]
+fn condition_not_source_vpce_to_json() {
+    let c = Condition::not_source_vpce(vec!["vpce-1a2b3c4d"]);
+
+    let mut json = Map::default();
+    let _ = c.into_json_object(&mut json);
+    assert_eq!(
+        serde_json::Value::Object(json),
+        serde_json::json!({
+            "Condition": {
+                "StringNotEquals": {
+                    "aws:SourceVpce": "vpce-1a2b3c4d"
+                }
+            }
+        })
+    );
+}
+
+#[test]
+fn condition_values_keep_text() {
+    let value: serde_json::Value = serde_json::from_str(
+        r#"{"Condition":{
+            "NumericLessThan": {"s3:max-keys": [10, 1.50, 123456789012345678901234567890, "1.50"]},
+            "Bool": {"aws:SecureTransport": true}
+        }}"#,
+    )
+    .unwrap();
+    let condition = Condition::from_json_object(value.as_object().unwrap()).unwrap();
+
+    let matches = condition
+        .get(&Operator::from_str("NumericLessThan").unwrap())
+        .unwrap();
+    let values: Vec<String> = matches
+        .get(&QualifiedName::from_str("s3:max-keys").unwrap())
+        .unwrap()
+        .iter()
+        .map(|v| v.to_string())
+        .collect();
+    assert_eq!(
+        values,
+        vec!["10", "1.50", "123456789012345678901234567890", "1.50"]
+    );
+
+    let matches = condition.get(&Operator::from_str("Bool").unwrap()).unwrap();
+    assert_eq!(
+        matches
+            .get(&QualifiedName::from_str("aws:SecureTransport").unwrap())
+            .unwrap()[0]
+            .to_string(),
+        "true"
+    );
+
+    let mut object = Map::default();
+    condition.into_json_object(&mut object).unwrap();
+    assert_eq!(serde_json::Value::Object(object.clone()), value);
+    assert_eq!(Condition::from_json_object(&object).unwrap(), condition);
+}
+
+#[test]
+fn condition_values_round_trip_numbers() {
+    let policy = Policy::from_str(
+        r#"{"Statement":[{"Effect":"Allow","Action":"s3:ListBucket","Resource":"*","Condition":{"NumericLessThan":{"s3:max-keys":[1.50,9007199254740993]}}}]}"#,
+    )
+    .unwrap();
+    let text = policy.to_string();
+    assert!(text.contains(r#"{"s3:max-keys":[1.50,9007199254740993]}"#));
+    assert_eq!(Policy::from_str(&text).unwrap(), policy);
+}
+
+#[test]
+fn condition_values_keep_type() {
+    let condition =
+        Condition::from_json_str(r#"{"Condition":{"NumericLessThanEquals":{"s3:max-keys":10}}}"#)
+            .unwrap();
+    assert_eq!(
+        condition.to_string(),
+        r#"{"Condition":{"NumericLessThanEquals":{"s3:max-keys":10}}}"#
+    );
+    assert_eq!(
+        condition,
+        Condition::from_json_str(r#"{"Condition":{"NumericLessThanEquals":{"s3:max-keys":"10"}}}"#)
+            .unwrap()
+    );
+
+    let condition = Condition::from_json_str(
+        r#"{"Condition":{"Bool":{"aws:SecureTransport":[true, "TRUE"]}}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        condition.normalize().to_string(),
+        r#"{"Condition":{"Bool":{"aws:SecureTransport":true}}}"#
+    );
+}
+
+#[test]
+fn condition_values_reject_objects() {
+    let value: serde_json::Value =
+        serde_json::from_str(r#"{"Condition":{"Bool": {"aws:SecureTransport": {"a": 1}}}}"#)
+            .unwrap();
+    assert!(Condition::from_json_object(value.as_object().unwrap()).is_err());
+}
+
+#[test]
+fn condition_preserves_order() {
+    let value: serde_json::Value = serde_json::from_str(
+        r#"{"Condition":{
+            "StringLike": {"aws:UserAgent": "b*", "aws:PrincipalTag/team": "a*"},
+            "ForAnyValue:StringEquals": {"aws:TagKeys": ["a", "b"]},
+            "StringEquals": {"aws:TagKeys": "a"}
+        }}"#,
+    )
+    .unwrap();
+    let condition = Condition::from_json_object(value.as_object().unwrap()).unwrap();
+
+    let operators: Vec<String> = condition.keys().map(|o| o.to_string()).collect();
+    assert_eq!(
+        operators,
+        vec!["StringLike", "ForAnyValue:StringEquals", "StringEquals"]
+    );
+    let keys: Vec<String> = condition
+        .get(&Operator::from_str("StringLike").unwrap())
+        .unwrap()
+        .keys()
+        .map(|k| k.to_string())
+        .collect();
+    assert_eq!(keys, vec!["aws:UserAgent", "aws:PrincipalTag/team"]);
+
+    let mut object = Map::default();
+    condition.into_json_object(&mut object).unwrap();
+    assert_eq!(
+        serde_json::Value::Object(object).to_string(),
+        value.to_string()
+    );
+}
+
+///
+/// Every operator, its constructor, and its name; `exhaustive` fails to compile if a new
+/// operator is not added here.
+///
+fn all_operators() -> Vec<(Operator, &'static str)> {
+    vec![
+        (Operator::string_equals(), "StringEquals"),
+        (Operator::string_not_equals(), "StringNotEquals"),
+        (
+            Operator::string_equals_ignore_case(),
+            "StringEqualsIgnoreCase",
+        ),
+        (
+            Operator::string_not_equals_ignore_case(),
+            "StringNotEqualsIgnoreCase",
+        ),
+        (Operator::string_like(), "StringLike"),
+        (Operator::string_not_like(), "StringNotLike"),
+        (Operator::numeric_equals(), "NumericEquals"),
+        (Operator::numeric_not_equals(), "NumericNotEquals"),
+        (Operator::numeric_less_than(), "NumericLessThan"),
+        (
+            Operator::numeric_less_than_or_equals(),
+            "NumericLessThanEquals",
+        ),
+        (Operator::numeric_greater_than(), "NumericGreaterThan"),
+        (
+            Operator::numeric_greater_than_or_equals(),
+            "NumericGreaterThanEquals",
+        ),
+        (Operator::date_equals(), "DateEquals"),
+        (Operator::date_not_equals(), "DateNotEquals"),
+        (Operator::date_less_than(), "DateLessThan"),
+        (Operator::date_less_than_or_equals(), "DateLessThanEquals"),
+        (Operator::date_greater_than(), "DateGreaterThan"),
+        (
+            Operator::date_greater_than_or_equals(),
+            "DateGreaterThanEquals",
+        ),
+        (Operator::bool_equals(), "Bool"),
+        (Operator::binary_equals(), "BinaryEquals"),
+        (Operator::ip_address(), "IpAddress"),
+        (Operator::not_ip_address(), "NotIpAddress"),
+        (Operator::arn_equals(), "ArnEquals"),
+        (Operator::arn_not_equals(), "ArnNotEquals"),
+        (Operator::arn_like(), "ArnLike"),
+        (Operator::arn_not_like(), "ArnNotLike"),
+        (Operator::null(), "Null"),
+    ]
+}
+
+fn exhaustive(operator: &GlobalOperator) -> usize {
+    match operator {
+        GlobalOperator::StringEquals => 0,
+        GlobalOperator::StringNotEquals => 1,
+        GlobalOperator::StringEqualsIgnoreCase => 2,
+        GlobalOperator::StringNotEqualsIgnoreCase => 3,
+        GlobalOperator::StringLike => 4,
+        GlobalOperator::StringNotLike => 5,
+        GlobalOperator::NumericEquals => 6,
+        GlobalOperator::NumericNotEquals => 7,
+        GlobalOperator::NumericLessThan => 8,
+        GlobalOperator::NumericLessThanEquals => 9,
+        GlobalOperator::NumericGreaterThan => 10,
+        GlobalOperator::NumericGreaterThanEquals => 11,
+        GlobalOperator::DateEquals => 12,
+        GlobalOperator::DateNotEquals => 13,
+        GlobalOperator::DateLessThan => 14,
+        GlobalOperator::DateLessThanEquals => 15,
+        GlobalOperator::DateGreaterThan => 16,
+        GlobalOperator::DateGreaterThanEquals => 17,
+        GlobalOperator::Bool => 18,
+        GlobalOperator::BinaryEquals => 19,
+        GlobalOperator::IpAddress => 20,
+        GlobalOperator::NotIpAddress => 21,
+        GlobalOperator::ArnEquals => 22,
+        GlobalOperator::ArnNotEquals => 23,
+        GlobalOperator::ArnLike => 24,
+        GlobalOperator::ArnNotLike => 25,
+        GlobalOperator::Null => 26,
+        GlobalOperator::Other(_) => usize::MAX,
+    }
+}
+
+#[test]
+fn condition_operator_round_trip_all() {
+    let operators = all_operators();
+    for (index, (operator, name)) in operators.iter().enumerate() {
+        assert_eq!(exhaustive(&operator.operator), index, "{}", name);
+        assert_eq!(operator.to_string(), *name);
+        assert_eq!(GlobalOperator::from_str(name).unwrap(), operator.operator);
+
+        for quantifier in [
+            None,
+            Some(Quantifier::ForAllValues),
+            Some(Quantifier::ForAnyValue),
+        ] {
+            for if_exists in [false, true] {
+                let operator = Operator {
+                    quantifier: quantifier.clone(),
+                    operator: operator.operator.clone(),
+                    if_exists,
+                };
+                let expected = format!(
+                    "{}{}{}",
+                    match quantifier {
+                        None => "",
+                        Some(Quantifier::ForAllValues) => "ForAllValues:",
+                        Some(Quantifier::ForAnyValue) => "ForAnyValue:",
+                    },
+                    name,
+                    if if_exists { "IfExists" } else { "" }
+                );
+                assert_eq!(operator.to_string(), expected);
+                assert_eq!(Operator::from_str(&expected).unwrap(), operator);
+            }
+        }
+    }
+    assert!(GlobalOperator::from_str("StringEqual").is_err());
+    assert!(GlobalOperator::from_str("").is_err());
+    assert!(GlobalOperator::parse("", ArnParseMode::Permissive).is_err());
+    assert!(GlobalOperator::parse("String Equals", ArnParseMode::Permissive).is_err());
+    assert!(Operator::from_str("ForSomeValues:StringEquals").is_err());
+}
+
+#[test]
+fn condition_other_operator() {
+    assert!(Operator::from_str("ForAnyValue:VpcEndpointMatchesIfExists").is_err());
+    let operator = Operator::parse(
+        "ForAnyValue:VpcEndpointMatchesIfExists",
+        ArnParseMode::Permissive,
+    )
+    .unwrap();
+    assert_eq!(
+        operator.operator,
+        GlobalOperator::Other("VpcEndpointMatches".to_string())
+    );
+    assert!(!operator.operator.is_known());
+    assert!(operator.if_exists);
+    assert_eq!(
+        operator.to_string(),
+        "ForAnyValue:VpcEndpointMatchesIfExists"
+    );
+
+    let value: serde_json::Value = serde_json::from_str(
+        r#"{"Condition":{"VpcEndpointMatches": {"aws:SourceVpce": "vpce-1a2b3c4d"}}}"#,
+    )
+    .unwrap();
+    assert!(Condition::from_json_object(value.as_object().unwrap()).is_err());
+    let condition =
+        Condition::from_json_object_with(value.as_object().unwrap(), ArnParseMode::Permissive)
+            .unwrap();
+    let mut object = Map::default();
+    condition.into_json_object(&mut object).unwrap();
+    assert_eq!(serde_json::Value::Object(object), value);
+}
+
+#[test]
+fn condition_value_parse_warnings() {
+    let condition = Condition::from_json_str(
+        r#"{"Condition":{
+            "DateGreaterThan": {"aws:CurrentTime": ["2019-07-16T12:00:00Z", "2019-07-16", "1563278400", "2019-13-01", "yesterday"]},
+            "NumericLessThanEquals": {"s3:max-keys": ["10", "1.5", "ten", "${aws:username}"]},
+            "IpAddress": {"aws:SourceIp": ["203.0.113.0/24", "2001:DB8:1234:5678::/64", "10.0.0.1", "10.0.0.0/33", "10.0.0"]},
+            "StringEquals": {"aws:username": "anything"}
+        }}"#,
+    )
+    .unwrap();
+    let invalid: Vec<(String, String)> = condition
+        .parse_warnings()
+        .into_iter()
+        .map(|w| (w.operator.to_string(), w.value.to_string()))
+        .collect();
+    assert_eq!(
+        invalid,
+        vec![
+            ("DateGreaterThan".to_string(), "2019-13-01".to_string()),
+            ("DateGreaterThan".to_string(), "yesterday".to_string()),
+            ("NumericLessThanEquals".to_string(), "ten".to_string()),
+            ("IpAddress".to_string(), "10.0.0.0/33".to_string()),
+            ("IpAddress".to_string(), "10.0.0".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn condition_descriptions() {
+    assert!(GlobalOperator::KNOWN.iter().all(|op| op.is_known()));
+    assert!(GlobalOperator::KNOWN
+        .iter()
+        .all(|op| op.description().is_some()));
+    assert!(
+        GlobalOperator::parse("StringEqualz", ArnParseMode::Permissive)
+            .unwrap()
+            .description()
+            .is_none()
+    );
+
+    assert_eq!(GLOBAL_CONDITION_KEYS.len(), 32);
+    assert!(describe_key(AWS_SOURCE_IP).is_some());
+    assert_eq!(describe_key("AWS:SOURCEIP"), describe_key(AWS_SOURCE_IP));
+    assert!(describe_key("aws:ResourceTag/Dept").is_some());
+    assert!(describe_key(AWS_RESOURCE_TAG).is_none());
+    assert!(describe_key("s3:prefix").is_none());
+}
+
+#[test]
+fn condition_completion_data() {
+    let operators = all_condition_operators();
+    assert_eq!(operators.len(), 27);
+    assert_eq!(operators[0].to_string(), "StringEquals");
+
+    let keys = all_global_condition_keys();
+    assert!(keys.iter().any(|(key, _)| *key == AWS_RESOURCE_TAG));
+    assert!(keys.iter().all(|(_, description)| !description.is_empty()));
+}
+
+#[test]
+fn condition_normalize() {
+    let condition = Condition::from_json_str_with(
+        r#"{"Condition":{
+            "StringEquals": {"AWS:PrincipalTag/Team": ["data", "data"], "aws:PrincipalTag/Team": "ops"},
+            "stringequals": {"s3:prefix": "home/"},
+            "Bool": {"aws:SecureTransport": ["TRUE"]},
+            "NullIfExists": {"aws:TokenIssueTime": "False"},
+            "stringLikeIfExists": {"aws:userid": "AIDA*"}
+        }}"#,
+        ArnParseMode::Permissive,
+    )
+    .unwrap();
+    let normalized = condition.normalize();
+
+    let mut object = Map::new();
+    normalized.into_json_object(&mut object).unwrap();
+    assert_eq!(
+        serde_json::Value::Object(object).to_string(),
+        r#"{"Condition":{"StringEquals":{"AWS:PrincipalTag/Team":"data","aws:PrincipalTag/Team":"ops","s3:prefix":"home/"},"Bool":{"aws:SecureTransport":"true"},"NullIfExists":{"aws:TokenIssueTime":"false"},"StringLikeIfExists":{"aws:userid":"AIDA*"}}}"#
+    );
+    assert_eq!(normalized.normalize(), normalized);
+}
+
+#[test]
+fn condition_normalize_duplicate_keys() {
+    let condition = Condition::from_json_str_with(
+        r#"{"Condition":{
+            "StringEquals": {"aws:PrincipalTag/team": "a", "AWS:PrincipalTag/team": "b", "AWS:SourceVpc": "vpc-1"},
+            "stringEquals": {"aws:username": "alice", "aws:userid": "AIDA1"},
+            "StringEqualsIgnoreCase": {"aws:username": "bob"},
+            "stringEqualsIgnoreCase": {"aws:username": "carol"}
+        }}"#,
+        ArnParseMode::Permissive,
+    )
+    .unwrap();
+    let normalized = condition.normalize();
+
+    let mut object = Map::new();
+    normalized.into_json_object(&mut object).unwrap();
+    assert_eq!(
+        serde_json::Value::Object(object).to_string(),
+        r#"{"Condition":{"StringEquals":{"aws:PrincipalTag/team":"a","AWS:PrincipalTag/team":"b","aws:SourceVpc":"vpc-1","aws:username":"alice","aws:userid":"AIDA1"},"StringEqualsIgnoreCase":{"aws:username":"bob"},"stringEqualsIgnoreCase":{"aws:username":"carol"}}}"#
+    );
+    assert_eq!(normalized.normalize(), normalized);
+}
+
+#[test]
+fn condition_normalize_unchanged() {
+    let condition = Condition::from_json_str_with(
+        r#"{"Condition":{"StringEqualsIgnoreCase": {"aws:username": ["Alice", "alice"]}, "StringEqualz": {"aws:username": "TRUE"}}}"#,
+        ArnParseMode::Permissive,
+    )
+    .unwrap();
+    assert_eq!(condition.normalize(), condition);
+}
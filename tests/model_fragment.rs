@@ -0,0 +1,103 @@
+use aws_iam::error::IamError;
+use aws_iam::model::{
+    Action, Condition, ConditionValue, Match, Policy, Principal, PrincipalMap, Resource,
+    SidProfile, Statement,
+};
+use std::fmt::{Debug, Display};
+use std::str::FromStr;
+
+fn round_trip<T>(fragment: &str)
+where
+    T: Display + FromStr + PartialEq + Debug,
+    T::Err: Debug,
+{
+    let value = T::from_str(fragment).unwrap();
+    let displayed = value.to_string();
+    println!("{} => {}", fragment, displayed);
+    assert_eq!(T::from_str(&displayed).unwrap(), value);
+}
+
+#[test]
+fn test_action_round_trip() {
+    round_trip::<Action>(r#"{"Action":"*"}"#);
+    round_trip::<Action>(r#"{"Action":"s3:GetObject"}"#);
+    round_trip::<Action>(r#"{"NotAction":["s3:GetObject","s3:PutObject"]}"#);
+}
+
+#[test]
+fn test_resource_round_trip() {
+    round_trip::<Resource>(r#"{"Resource":"*"}"#);
+    round_trip::<Resource>(r#"{"NotResource":["arn:aws:s3:::bucket","arn:aws:s3:::other"]}"#);
+}
+
+#[test]
+fn test_principal_round_trip() {
+    round_trip::<Principal>(r#"{"Principal":"*"}"#);
+    round_trip::<Principal>(r#"{"Principal":{"AWS":"*"}}"#);
+    round_trip::<Principal>(
+        r#"{"NotPrincipal":{"AWS":"arn:aws:iam::123456789012:root","Service":"ec2.amazonaws.com"}}"#,
+    );
+    round_trip::<PrincipalMap>(r#"{"Federated":"cognito-identity.amazonaws.com"}"#);
+}
+
+#[test]
+fn test_condition_round_trip() {
+    round_trip::<Condition>(
+        r#"{"Condition":{"StringEquals":{"aws:PrincipalOrgID":"o-a1b2c3d4e5"},"Bool":{"aws:SecureTransport":"true"}}}"#,
+    );
+    round_trip::<Match>(r#"{"aws:SourceVpc":["vpc-111bbb22","vpc-222ccc33"]}"#);
+    round_trip::<ConditionValue>("o-a1b2c3d4e5");
+}
+
+#[test]
+fn test_statement_round_trip() {
+    round_trip::<Statement>(
+        r#"{"Sid":"ReadOnly","Effect":"Allow","Action":["s3:GetObject","s3:ListBucket"],"Resource":"arn:aws:s3:::bucket"}"#,
+    );
+}
+
+#[test]
+fn test_policy_round_trip() {
+    round_trip::<Policy>(
+        r#"{"Version":"2012-10-17","Id":"test","Statement":[{"Effect":"Deny","NotAction":"iam:*","Resource":"*"}]}"#,
+    );
+}
+
+#[test]
+fn test_sid_profile_round_trip() {
+    round_trip::<SidProfile>("SQS");
+}
+
+#[test]
+fn test_invalid_fragments() {
+    assert!(Action::from_str("s3:GetObject").is_err());
+    assert!(Action::from_str(r#"["s3:GetObject"]"#).is_err());
+    assert!(Principal::from_str(r#"{"Action":"*"}"#).is_err());
+    assert!(Condition::from_str("{}").is_err());
+    assert!(Statement::from_str(r#"{"Effect":"Allow"}"#).is_err());
+}
+
+#[test]
+fn test_element_from_json_str() {
+    let statement =
+        Statement::from_json_str(r#"{"Effect":"Allow","Action":"s3:GetObject","Resource":"*"}"#)
+            .unwrap();
+    assert!(statement.is_allow());
+
+    let condition =
+        Condition::from_json_str(r#"{"Condition":{"Bool":{"aws:SecureTransport":"true"}}}"#)
+            .unwrap();
+    assert_eq!(condition.len(), 1);
+
+    let principal = Principal::from_json_str(r#"{"NotPrincipal":{"AWS":"*"}}"#).unwrap();
+    assert!(matches!(principal, Principal::NotPrincipal(_)));
+
+    assert!(matches!(
+        Statement::from_json_str(r#"{"Action":"s3:GetObject"}"#),
+        Err(IamError::Format(_))
+    ));
+    assert!(matches!(
+        Principal::from_json_str("{"),
+        Err(IamError::Format(_))
+    ));
+}
@@ -0,0 +1,49 @@
+use aws_iam::model::id::{
+    is_valid_org_id, is_valid_org_path, is_valid_org_root_id, is_valid_org_unit_id,
+};
+
+#[test]
+fn test_valid_org_ids() {
+    assert!(is_valid_org_id("o-a1b2c3d4e5"));
+    assert!(is_valid_org_id("o-exampleorgid1234567890"));
+}
+
+#[test]
+fn test_invalid_org_ids() {
+    assert!(!is_valid_org_id(""));
+    assert!(!is_valid_org_id("o-"));
+    assert!(!is_valid_org_id("o-abc123"));
+    assert!(!is_valid_org_id("o-A1B2C3D4E5"));
+    assert!(!is_valid_org_id("a1b2c3d4e5"));
+    assert!(!is_valid_org_id("o-a1b2c3d4e5/"));
+}
+
+#[test]
+fn test_valid_org_paths() {
+    assert!(is_valid_org_path("o-a1b2c3d4e5/*"));
+    assert!(is_valid_org_path("o-a1b2c3d4e5/r-ab12/"));
+    assert!(is_valid_org_path("o-a1b2c3d4e5/r-ab12/ou-ab12-11111111/"));
+    assert!(is_valid_org_path(
+        "o-a1b2c3d4e5/r-ab12/ou-ab12-11111111/ou-ab12-22222222/*"
+    ));
+    assert!(is_valid_org_path("o-a1b2c3d4e5/*/ou-ab12-11111111/*"));
+}
+
+#[test]
+fn test_invalid_org_paths() {
+    assert!(!is_valid_org_path(""));
+    assert!(!is_valid_org_path("o-abc/r-ab12/"));
+    assert!(!is_valid_org_path("r-ab12/o-a1b2c3d4e5/"));
+    assert!(!is_valid_org_path("o-a1b2c3d4e5//"));
+    assert!(!is_valid_org_path("o-a1b2c3d4e5/r-ab12/ou-bad/"));
+}
+
+#[test]
+fn test_org_root_and_unit_ids() {
+    assert!(is_valid_org_root_id("r-ab12"));
+    assert!(!is_valid_org_root_id("r-ab"));
+    assert!(!is_valid_org_root_id("ou-ab12-11111111"));
+    assert!(is_valid_org_unit_id("ou-ab12-11111111"));
+    assert!(!is_valid_org_unit_id("ou-ab12"));
+    assert!(!is_valid_org_unit_id("r-ab12"));
+}
@@ -0,0 +1,66 @@
+use aws_iam::model::{GlobalOperator, Policy, Preset, QualifiedName, Version};
+use aws_iam::syntax::IamValue;
+use aws_iam::validate::ValidationOptions;
+use std::str::FromStr;
+
+fn all_presets() -> Vec<Preset> {
+    vec![
+        Preset::ReadOnlyAccess,
+        Preset::PowerUserAccess,
+        Preset::DenyAllOutsideRegions(vec!["us-east-1".to_string(), "us-gov-west-1".to_string()]),
+        Preset::RequireMfa,
+    ]
+}
+
+#[test]
+fn test_presets_are_valid() {
+    for preset in all_presets() {
+        let policy = Policy::preset(preset).unwrap();
+        assert_eq!(policy.version, Some(Version::V2012));
+        assert!(policy.validate(&ValidationOptions::default()).is_valid());
+        for statement in &policy.statement {
+            for action in statement.action().some().unwrap() {
+                assert!(QualifiedName::from_str(&action.to_string()).is_ok());
+            }
+        }
+        assert!(policy.to_json().is_ok());
+    }
+}
+
+#[test]
+fn test_power_user_preset() {
+    let policy = Policy::preset(Preset::PowerUserAccess).unwrap();
+    assert!(policy.statement[0].allows_action("s3:PutObject"));
+    assert!(!policy.statement[0].allows_action("iam:CreateUser"));
+    assert!(policy.statement[1].allows_action("iam:CreateServiceLinkedRole"));
+}
+
+#[test]
+fn test_require_mfa_preset() {
+    let policy = Policy::preset(Preset::RequireMfa).unwrap();
+    assert_eq!(policy.statement.len(), 1);
+    let statement = &policy.statement[0];
+    assert!(statement.is_deny());
+    assert!(statement.has_condition(GlobalOperator::Bool, "aws:MultiFactorAuthPresent"));
+    assert_eq!(
+        statement.to_json().unwrap().get("Condition").unwrap(),
+        &serde_json::json!({
+            "BoolIfExists": {
+                "aws:MultiFactorAuthPresent": "false"
+            }
+        })
+    );
+}
+
+#[test]
+fn test_deny_outside_regions_preset() {
+    let policy =
+        Policy::preset(Preset::DenyAllOutsideRegions(vec!["eu-west-1".to_string()])).unwrap();
+    let statement = &policy.statement[0];
+    assert!(statement.has_condition(GlobalOperator::StringNotEquals, "aws:RequestedRegion"));
+    assert!(statement.action().matches("s3:GetObject"));
+    assert!(!statement.action().matches("iam:CreateUser"));
+
+    assert!(Policy::preset(Preset::DenyAllOutsideRegions(vec![])).is_err());
+    assert!(Policy::preset(Preset::DenyAllOutsideRegions(vec!["EU-WEST-1".to_string()])).is_err());
+}
@@ -0,0 +1,106 @@
+use aws_iam::io::{read_from_string, to_string};
+use aws_iam::model::RedactionOptions;
+
+const POLICY: &str = r#"{
+  "Version": "2012-10-17",
+  "Statement": [
+    {
+      "Sid": "ReadReports",
+      "Effect": "Allow",
+      "Principal": {
+        "AWS": [
+          "arn:aws:iam::123456789012:root",
+          "arn:aws:iam::123456789012:role/ReportReader",
+          "210987654321"
+        ]
+      },
+      "Action": ["s3:GetObject", "s3:ListBucket"],
+      "Resource": [
+        "arn:aws:s3:::acme-reports",
+        "arn:aws:s3:::acme-reports/${aws:username}/2020.csv",
+        "arn:aws:s3:::acme-reports/quarterly*"
+      ],
+      "Condition": {
+        "IpAddress": {"aws:SourceIp": ["203.0.113.7/24", "2001:0db8:85a3::8a2e:370:7334"]},
+        "ForAnyValue:StringLike": {
+          "aws:PrincipalOrgPaths": "o-a1b2c3d4e5/r-ab12/ou-ab12-11111111/"
+        }
+      }
+    },
+    {
+      "Effect": "Deny",
+      "Action": "dynamodb:DeleteTable",
+      "Resource": "arn:aws:dynamodb:us-east-1:123456789012:table/Reports",
+      "Condition": {"StringEquals": {"aws:PrincipalOrgID": "o-a1b2c3d4e5"}}
+    }
+  ]
+}"#;
+
+fn redacted(options: RedactionOptions) -> String {
+    let policy = read_from_string(POLICY).unwrap();
+    to_string(&policy.redact(options).unwrap(), false).unwrap()
+}
+
+#[test]
+fn test_redact_accounts() {
+    let redacted = redacted(RedactionOptions::default());
+    assert!(!redacted.contains("123456789012"));
+    assert!(!redacted.contains("210987654321"));
+    assert!(redacted.contains("arn:aws:iam::000000000001:root"));
+    assert!(redacted.contains("arn:aws:iam::000000000002:root"));
+    assert!(redacted.contains("arn:aws:dynamodb:us-east-1:000000000001:table/resource-"));
+}
+
+#[test]
+fn test_redact_resource_names() {
+    let redacted = redacted(RedactionOptions::default());
+    assert!(!redacted.contains("acme-reports"));
+    assert!(!redacted.contains("ReportReader"));
+    assert!(redacted.contains("\"arn:aws:s3:::bucket-1\""));
+    assert!(redacted.contains("arn:aws:s3:::bucket-1/${aws:username}/resource-2"));
+    assert!(redacted.contains("arn:aws:s3:::bucket-1/resource-3*"));
+    assert!(redacted.contains("arn:aws:iam::000000000001:role/resource-1"));
+}
+
+#[test]
+fn test_redact_organizations_and_addresses() {
+    let redacted = redacted(RedactionOptions::default());
+    assert!(!redacted.contains("a1b2c3d4e5"));
+    assert!(redacted.contains("o-example001/r-0001/ou-0001-example1/"));
+    assert!(redacted.contains("\"aws:PrincipalOrgID\":\"o-example001\""));
+    assert!(redacted.contains("192.0.2.1/24"));
+    assert!(redacted.contains("2001:db8::1"));
+}
+
+#[test]
+fn test_redact_preserves_structure() {
+    let policy = read_from_string(POLICY).unwrap();
+    let redacted = policy.redact(RedactionOptions::default()).unwrap();
+    assert_eq!(redacted.statement.len(), policy.statement.len());
+    let redacted = to_string(&redacted, false).unwrap();
+    assert!(redacted.contains("\"Sid\":\"ReadReports\""));
+    assert!(redacted.contains("s3:GetObject"));
+    assert!(redacted.contains("aws:SourceIp"));
+}
+
+#[test]
+fn test_redact_is_stable() {
+    assert_eq!(
+        redacted(RedactionOptions::default()),
+        redacted(RedactionOptions::default())
+    );
+}
+
+#[test]
+fn test_redact_options() {
+    let redacted = redacted(
+        RedactionOptions::default()
+            .with_resource_names(false)
+            .with_ip_addresses(false)
+            .with_organizations(false),
+    );
+    assert!(!redacted.contains("123456789012"));
+    assert!(redacted.contains("acme-reports"));
+    assert!(redacted.contains("203.0.113.7/24"));
+    assert!(redacted.contains("o-a1b2c3d4e5"));
+}
@@ -0,0 +1,77 @@
+use aws_iam::arn::{Partition, Region};
+use aws_iam::model::Policy;
+use aws_iam::validate::{ValidationOptions, FINDING_MIXED_PARTITIONS};
+use std::str::FromStr;
+
+const POLICY: &str = r#"{
+  "Version": "2012-10-17",
+  "Statement": [
+    {
+      "Effect": "Allow",
+      "Principal": { "AWS": "arn:aws:iam::123456789012:role/reader" },
+      "Action": "sqs:ReceiveMessage",
+      "Resource": "arn:aws:sqs:us-east-1:123456789012:queue",
+      "Condition": {
+        "ArnEquals": { "aws:SourceArn": "arn:aws:sns:eu-west-1:123456789012:topic" },
+        "StringEquals": { "aws:RequestedRegion": ["us-east-1", "us-west-2"] }
+      }
+    }
+  ]
+}"#;
+
+#[test]
+fn test_referenced_regions_and_partitions() {
+    let policy = Policy::from_str(POLICY).unwrap();
+    let regions: Vec<String> = policy
+        .referenced_regions()
+        .iter()
+        .map(Region::to_string)
+        .collect();
+    assert_eq!(regions, vec!["eu-west-1", "us-east-1", "us-west-2"]);
+    assert_eq!(
+        policy
+            .referenced_partitions()
+            .into_iter()
+            .collect::<Vec<Partition>>(),
+        vec![Partition::Aws]
+    );
+    assert!(policy.validate(&ValidationOptions::default()).is_empty());
+}
+
+#[test]
+fn test_retarget_policy() {
+    let policy = Policy::from_str(POLICY).unwrap();
+    let region = Region::from_str("cn-north-1").unwrap();
+    let retargeted = policy.retarget(Partition::AwsCn, Some(&region)).unwrap();
+
+    assert_eq!(
+        retargeted
+            .referenced_partitions()
+            .into_iter()
+            .collect::<Vec<Partition>>(),
+        vec![Partition::AwsCn]
+    );
+    let regions: Vec<String> = retargeted
+        .referenced_regions()
+        .iter()
+        .map(Region::to_string)
+        .collect();
+    assert_eq!(regions, vec!["cn-north-1", "us-east-1", "us-west-2"]);
+    assert_eq!(
+        retargeted.statement[0].resource().unwrap().to_string(),
+        r#"{"Resource":"arn:aws-cn:sqs:cn-north-1:123456789012:queue"}"#
+    );
+}
+
+#[test]
+fn test_mixed_partitions() {
+    let policy = Policy::from_str(
+        r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject",
+            "Resource":["arn:aws:s3:::bucket/*","arn:aws-us-gov:s3:::bucket/*"]}]}"#,
+    )
+    .unwrap();
+    let findings = policy.validate(&ValidationOptions::default());
+    assert!(findings.is_valid());
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].code, FINDING_MIXED_PARTITIONS);
+}
@@ -1,7 +1,9 @@
 use aws_arn::ARN;
-use aws_iam::model::{OrAny, Resource};
+use aws_iam::arn::ArnParseMode;
+use aws_iam::model::{OrAny, Resource, ResourceEntry};
 use aws_iam::syntax::IamProperty;
 use serde_json::{Map, Value};
+use std::collections::HashMap;
 use std::str::FromStr;
 
 #[test]
@@ -133,10 +135,7 @@ fn test_one_name_from_json() {
 
     assert_eq!(
         result,
-        Resource::Resource(OrAny::Some(vec![ARN::from_str(
-            "arn:aws:s3:::examplebucket/*"
-        )
-        .unwrap()]))
+        Resource::this_resource(ARN::from_str("arn:aws:s3:::examplebucket/*").unwrap())
     );
 }
 
@@ -154,9 +153,115 @@ fn test_name_vec_from_json() {
 
     assert_eq!(
         result,
-        Resource::Resource(OrAny::Some(vec![
+        Resource::these_resources(vec![
             ARN::from_str("arn:aws:s3:::examplebucket/*").unwrap(),
             ARN::from_str("arn:aws:iam::123456789012:user/Bob").unwrap()
-        ]))
+        ])
     );
 }
+
+#[test]
+fn test_strict_rejects_patterns() {
+    for value in &["examplebucket-abc123-s3alias", "arn:aws:s3"] {
+        let mut container = Map::default();
+        container.insert("Resource".to_string(), Value::String(value.to_string()));
+
+        assert!(Resource::from_json_object(&container).is_err());
+        assert!(Resource::from_json_object_with(&container, ArnParseMode::Strict).is_err());
+    }
+}
+
+#[test]
+fn test_permissive_accepts_patterns() {
+    for value in &["examplebucket-abc123-s3alias", "arn:aws:s3"] {
+        let mut container = Map::default();
+        container.insert("Resource".to_string(), Value::String(value.to_string()));
+
+        let result = Resource::from_json_object_with(&container, ArnParseMode::Permissive).unwrap();
+        assert_eq!(
+            result,
+            Resource::this_resource(ResourceEntry::Pattern(value.to_string()))
+        );
+    }
+}
+
+#[test]
+fn test_permissive_validates_arns() {
+    let mut container = Map::default();
+    container.insert(
+        "Resource".to_string(),
+        Value::Array(vec![
+            Value::String("arn:aws-cn:s3:::examplebucket/*".to_string()),
+            Value::String("examplebucket-abc123-s3alias".to_string()),
+        ]),
+    );
+
+    let result = Resource::from_json_object_with(&container, ArnParseMode::Permissive).unwrap();
+    let entries = result.some().unwrap();
+    assert!(entries[0].is_arn());
+    assert!(!entries[1].is_arn());
+
+    let mut container = Map::default();
+    container.insert(
+        "Resource".to_string(),
+        Value::String("arn:aws:iam::not-an-account:user/Bob".to_string()),
+    );
+    assert!(Resource::from_json_object_with(&container, ArnParseMode::Permissive).is_err());
+}
+
+#[test]
+fn test_templates_from_json() {
+    for mode in &[ArnParseMode::Strict, ArnParseMode::Permissive] {
+        for value in &[
+            "arn:${Partition}:s3:::examplebucket/*",
+            "arn:aws:ec2:${AWS::Region}:${AWS::AccountId}:instance/*",
+            "arn:aws:s3:::examplebucket/home/${aws:username}/*",
+        ] {
+            let mut container = Map::default();
+            container.insert("Resource".to_string(), Value::String(value.to_string()));
+
+            let result = Resource::from_json_object_with(&container, *mode).unwrap();
+            assert_eq!(
+                result,
+                Resource::this_resource(ResourceEntry::Template(value.to_string()))
+            );
+            assert!(result.has_variables());
+        }
+    }
+}
+
+#[test]
+fn test_strict_rejects_malformed_templates() {
+    let mut container = Map::default();
+    container.insert(
+        "Resource".to_string(),
+        Value::String("${aws:username}/*".to_string()),
+    );
+    assert!(Resource::from_json_object_with(&container, ArnParseMode::Strict).is_err());
+    assert!(Resource::from_json_object_with(&container, ArnParseMode::Permissive).is_ok());
+}
+
+#[test]
+fn test_template_substitution() {
+    let entry =
+        ResourceEntry::from_str("arn:aws:s3:::examplebucket/home/${aws:username}/*").unwrap();
+    assert!(entry.is_template());
+    assert_eq!(entry.variable_names(), vec!["aws:username".to_string()]);
+
+    let mut context: HashMap<String, String> = Default::default();
+    let partial = entry.substitute(&context).unwrap();
+    assert_eq!(partial, entry);
+
+    context.insert("aws:username".to_string(), "alice".to_string());
+    let result = entry.substitute(&context).unwrap();
+    assert_eq!(
+        result,
+        ResourceEntry::Arn(ARN::from_str("arn:aws:s3:::examplebucket/home/alice/*").unwrap())
+    );
+
+    let resource = Resource::this_resource(entry);
+    assert!(resource
+        .substitute(&context)
+        .unwrap()
+        .matches(&ARN::from_str("arn:aws:s3:::examplebucket/home/alice/notes.txt").unwrap()));
+}
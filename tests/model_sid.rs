@@ -0,0 +1,31 @@
+use aws_iam::model::{Sid, SidProfile};
+use std::str::FromStr;
+
+#[test]
+fn test_iam_sids() {
+    assert!(Sid::from_str("1").is_ok());
+    assert!(Sid::from_str("ThisStatementProvidesPermissionsForConsoleAccess").is_ok());
+    assert!(Sid::from_str("").is_err());
+    assert!(Sid::from_str("sid-001").is_err());
+    assert!(Sid::from_str("sid_001").is_err());
+    assert!(Sid::from_str("sid 001").is_err());
+}
+
+#[test]
+fn test_service_sids() {
+    for profile in &[SidProfile::Sqs, SidProfile::Sns] {
+        assert!(Sid::new_for("sid-001", *profile).is_ok());
+        assert!(Sid::new_for("sid_001", *profile).is_ok());
+        assert!(Sid::new_for("", *profile).is_err());
+        assert!(Sid::new_for("sid 001", *profile).is_err());
+        assert!(Sid::new_for("sid:001", *profile).is_err());
+    }
+}
+
+#[test]
+fn test_random_sid_valid_for_all_profiles() {
+    let sid = Sid::new_random();
+    assert!(sid.is_valid_for(SidProfile::Iam));
+    assert!(sid.is_valid_for(SidProfile::Sqs));
+    assert!(sid.is_valid_for(SidProfile::Sns));
+}
@@ -0,0 +1,111 @@
+use aws_iam::model::{Policy, Statement};
+use std::str::FromStr;
+
+fn statement(sid: &str, action: &str, resource: &str) -> String {
+    format!(
+        r#"{{"Sid":"{}","Effect":"Allow","Action":"{}","Resource":"{}"}}"#,
+        sid, action, resource
+    )
+}
+
+fn policy(statements: &[String]) -> Policy {
+    Policy::from_str(&format!(
+        r#"{{"Version":"2012-10-17","Statement":[{}]}}"#,
+        statements.join(",")
+    ))
+    .unwrap()
+}
+
+fn sids(policy: &Policy) -> Vec<String> {
+    policy
+        .statement
+        .iter()
+        .map(|st: &Statement| st.sid.as_ref().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn test_size() {
+    let policy = Policy::from_str(
+        r#"{ "Statement": [ { "Effect": "Allow", "Action": "s3:GetObject", "Resource": "*" } ] }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        policy.size(),
+        r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject","Resource":"*"}]}"#.len()
+    );
+}
+
+#[test]
+fn test_split_small_policy() {
+    let policy = policy(&[statement("Read", "s3:GetObject", "*")]);
+    assert_eq!(policy.split(6144), vec![policy.clone()]);
+}
+
+#[test]
+fn test_split_keeps_related_statements() {
+    let policy = policy(&[
+        statement("S3Read", "s3:GetObject", "arn:aws:s3:::reports/2020.csv"),
+        statement(
+            "SqsSend",
+            "sqs:SendMessage",
+            "arn:aws:sqs:us-east-1:123456789012:orders",
+        ),
+        statement("S3List", "s3:ListBucket", "arn:aws:s3:::reports"),
+        statement(
+            "SqsReceive",
+            "sqs:ReceiveMessage",
+            "arn:aws:sqs:us-east-1:123456789012:orders",
+        ),
+    ]);
+    let limit = 300;
+    let parts = policy.split(limit);
+
+    assert_eq!(parts.len(), 2);
+    assert!(parts.iter().all(|part| part.size() <= limit));
+    assert_eq!(sids(&parts[0]), vec!["S3Read", "S3List"]);
+    assert_eq!(sids(&parts[1]), vec!["SqsSend", "SqsReceive"]);
+    assert!(parts.iter().all(|part| part.version == policy.version));
+}
+
+#[test]
+fn test_split_large_group() {
+    let statements: Vec<String> = (0..10)
+        .map(|i| {
+            statement(
+                &format!("Read{}", i),
+                "s3:GetObject",
+                &format!("arn:aws:s3:::bucket-{}/2020.csv", i),
+            )
+        })
+        .collect();
+    let policy = policy(&statements);
+    let limit = policy.size() / 3;
+    let parts = policy.split(limit);
+
+    assert!(parts.len() >= 3);
+    assert!(parts.iter().all(|part| part.size() <= limit));
+    let mut all: Vec<String> = parts.iter().flat_map(sids).collect();
+    all.sort();
+    let mut expected: Vec<String> = (0..10).map(|i| format!("Read{}", i)).collect();
+    expected.sort();
+    assert_eq!(all, expected);
+}
+
+#[test]
+fn test_split_oversized_statement() {
+    let policy = policy(&[
+        statement("Small", "s3:GetObject", "*"),
+        statement(
+            "Large",
+            "sqs:SendMessage",
+            &format!("arn:aws:sqs:us-east-1:123456789012:{}", "q".repeat(200)),
+        ),
+    ]);
+    let parts = policy.split(150);
+
+    assert_eq!(parts.len(), 2);
+    assert!(parts[0].size() <= 150);
+    assert_eq!(sids(&parts[1]), vec!["Large"]);
+    assert!(parts[1].size() > 150);
+}
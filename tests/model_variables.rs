@@ -0,0 +1,116 @@
+use aws_iam::model::variables::{
+    escape_variables, has_variables, replace_variables, replace_variables_with, variable_names,
+};
+use aws_iam::model::{PrincipalMap, ResourceEntry};
+use aws_iam::syntax::IamValue;
+use serde_json::json;
+use std::collections::HashMap;
+
+fn context() -> HashMap<String, String> {
+    let mut context: HashMap<String, String> = Default::default();
+    context.insert("aws:username".to_string(), "alice".to_string());
+    context.insert(
+        "aws:PrincipalAccount".to_string(),
+        "123456789012".to_string(),
+    );
+    context
+}
+
+#[test]
+fn test_has_variables() {
+    assert!(has_variables("home/${aws:username}"));
+    assert!(has_variables("${aws:username, 'anonymous'}"));
+    assert!(has_variables("${*}"));
+    assert!(!has_variables("home/alice"));
+    assert!(!has_variables("home/$aws:username"));
+    assert!(!has_variables("home/${}"));
+}
+
+#[test]
+fn test_variable_names() {
+    assert_eq!(
+        variable_names("${aws:PrincipalAccount}/${aws:username, 'anonymous'}/${*}"),
+        vec![
+            "aws:PrincipalAccount".to_string(),
+            "aws:username".to_string()
+        ]
+    );
+}
+
+#[test]
+fn test_replace_variables() {
+    assert_eq!(
+        replace_variables("home/${aws:username}/*", &context()),
+        "home/alice/*"
+    );
+    assert_eq!(
+        replace_variables("home/${aws:userid}/*", &context()),
+        "home/${aws:userid}/*"
+    );
+    assert_eq!(
+        replace_variables("home/${aws:userid, 'nobody'}/*", &context()),
+        "home/nobody/*"
+    );
+    assert_eq!(
+        replace_variables("literal-${*}-${?}-${$}", &context()),
+        "literal-*-?-$"
+    );
+}
+
+#[test]
+fn test_escape_variables() {
+    for literal in &[
+        "home/${aws:username}/*",
+        "${aws:username, 'anonymous'}",
+        "${${aws:username}}",
+        "${$}",
+    ] {
+        let escaped = escape_variables(literal);
+        assert!(variable_names(&escaped).is_empty());
+        assert_eq!(replace_variables(&escaped, &context()), *literal);
+    }
+    assert_eq!(
+        escape_variables("home/${aws:username}"),
+        "home/${$}{aws:username}"
+    );
+    assert_eq!(escape_variables("home/${alice"), "home/${alice");
+}
+
+#[test]
+fn test_replace_variables_with() {
+    assert_eq!(
+        replace_variables_with(
+            "arn:aws:ec2:${AWS::Region}:${AWS::AccountId}:instance/*",
+            "x"
+        ),
+        "arn:aws:ec2:x:x:instance/*"
+    );
+}
+
+#[test]
+fn test_principal_templates() {
+    let principals = PrincipalMap::from_json(&json!({
+        "AWS": [
+            "arn:aws:iam::${aws:PrincipalAccount}:root",
+            "arn:aws:iam::999999999999:root"
+        ]
+    }))
+    .unwrap();
+    assert!(principals.has_variables());
+
+    let entries: Vec<&ResourceEntry> = principals.aws_iter().collect();
+    assert!(entries[0].is_template());
+    assert!(entries[1].is_arn());
+
+    let principals = principals.substitute(&context()).unwrap();
+    assert!(!principals.has_variables());
+    assert_eq!(
+        principals.to_json().unwrap(),
+        json!({
+            "AWS": [
+                "arn:aws:iam::123456789012:root",
+                "arn:aws:iam::999999999999:root"
+            ]
+        })
+    );
+}
@@ -0,0 +1,157 @@
+#![cfg(feature = "plugins")]
+
+use aws_iam::error::PluginError;
+use aws_iam::lint::LintRegistry;
+use aws_iam::model::{Effect, Policy};
+use aws_iam::plugin::{
+    plugin_files, PluginFinding, PluginPack, PluginRuleInfo, PluginRuntime, FINDING_PLUGIN_FAILED,
+};
+use aws_iam::validate::Severity;
+use std::str::FromStr;
+
+struct TestPack;
+
+impl PluginRuntime for TestPack {
+    fn list_rules(&self) -> Result<Vec<PluginRuleInfo>, PluginError> {
+        Ok(vec![
+            PluginRuleInfo::new("TestNoDeny", Severity::Warning),
+            PluginRuleInfo::new("TestBroken", Severity::Error),
+        ])
+    }
+
+    fn check(&self, rule: &str, policy: &str) -> Result<Vec<PluginFinding>, PluginError> {
+        match rule {
+            "TestNoDeny" if policy.contains("\"Deny\"") => Ok(vec![PluginFinding::new(
+                Severity::Warning,
+                "TestDeny",
+                "deny statement",
+                Some(0),
+            )]),
+            "TestNoDeny" => Ok(vec![]),
+            _ => Err(PluginError::Runtime("trapped".to_string())),
+        }
+    }
+
+    fn list_transforms(&self) -> Result<Vec<String>, PluginError> {
+        Ok(vec!["TestAllow".to_string()])
+    }
+
+    fn transform(&self, transform: &str, policy: &str) -> Result<String, PluginError> {
+        match transform {
+            "TestAllow" => Ok(policy.replace("\"Deny\"", "\"Allow\"")),
+            _ => Err(PluginError::UnknownTransform(transform.to_string())),
+        }
+    }
+}
+
+#[test]
+fn test_plugin_rules() {
+    let pack = PluginPack::new(TestPack);
+    let mut registry = LintRegistry::new();
+    assert_eq!(pack.register(&mut registry).unwrap(), 2);
+    assert_eq!(registry.names(), vec!["TestNoDeny", "TestBroken"]);
+    assert_eq!(
+        registry.get("TestBroken").unwrap().severity(),
+        Severity::Error
+    );
+
+    let policy = Policy::from_str(
+        r#"{"Statement":[{"Effect":"Deny","Action":"s3:DeleteBucket","Resource":"*"}]}"#,
+    )
+    .unwrap();
+    let findings = registry.check(&policy);
+    let codes: Vec<&str> = findings.iter().map(|f| f.code).collect();
+    assert_eq!(codes, vec!["TestDeny", FINDING_PLUGIN_FAILED]);
+    assert!(findings[1].message.contains("trapped"));
+}
+
+#[test]
+fn test_plugin_transforms() {
+    let pack = PluginPack::new(TestPack);
+    assert_eq!(pack.transforms().unwrap(), vec!["TestAllow".to_string()]);
+
+    let policy = Policy::from_str(
+        r#"{"Statement":[{"Effect":"Deny","Action":"s3:DeleteBucket","Resource":"*"}]}"#,
+    )
+    .unwrap();
+    let result = pack.transform("TestAllow", &policy).unwrap();
+    assert_eq!(result.statement[0].effect, Effect::Allow);
+    assert!(matches!(
+        pack.transform("Other", &policy),
+        Err(PluginError::UnknownTransform(_))
+    ));
+}
+
+#[test]
+fn test_plugin_files() {
+    let dir = std::env::temp_dir().join(format!("aws-iam-plugins-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for name in &["b.wasm", "a.wasm", "readme.txt"] {
+        std::fs::write(dir.join(name), b"").unwrap();
+    }
+    let files = plugin_files(&dir).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(files, vec![dir.join("a.wasm"), dir.join("b.wasm")]);
+}
+
+#[cfg(feature = "plugins_wasmtime")]
+#[test]
+fn test_wasmtime_plugin() {
+    use aws_iam::plugin::load_plugin;
+    use std::path::Path;
+
+    let pack = load_plugin(Path::new("tests/data/plugins/rule-pack.wasm")).unwrap();
+    let mut registry = LintRegistry::new();
+    assert_eq!(pack.register(&mut registry).unwrap(), 1);
+    assert_eq!(
+        registry.get("WasmNoWildcards").unwrap().severity(),
+        Severity::Warning
+    );
+
+    let policy = Policy::from_str(
+        r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject","Resource":"*"}]}"#,
+    )
+    .unwrap();
+    let findings = registry.check(&policy);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].code, "WasmWildcard");
+    assert_eq!(findings[0].statement, None);
+
+    let policy = Policy::from_str(
+        r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject","Resource":"arn:aws:s3:::bucket"}]}"#,
+    )
+    .unwrap();
+    assert!(registry.check(&policy).is_empty());
+
+    assert_eq!(pack.transforms().unwrap(), vec!["WasmIdentity".to_string()]);
+    assert_eq!(pack.transform("WasmIdentity", &policy).unwrap(), policy);
+    assert!(matches!(
+        pack.transform("Other", &policy),
+        Err(PluginError::Runtime(message)) if message == "not provided by this pack"
+    ));
+}
+
+#[cfg(feature = "plugins_wasmtime")]
+#[test]
+fn test_wasmtime_plugin_load_errors() {
+    use aws_iam::plugin::load_plugins;
+
+    let dir = std::env::temp_dir().join(format!("aws-iam-wasm-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy(
+        "tests/data/plugins/rule-pack.wasm",
+        dir.join("a-rule-pack.wasm"),
+    )
+    .unwrap();
+    assert_eq!(load_plugins(&dir).unwrap().len(), 1);
+
+    std::fs::write(dir.join("b-broken.wasm"), b"not a component").unwrap();
+    let result = load_plugins(&dir);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(matches!(
+        result,
+        Err(PluginError::Load { path, .. }) if path == dir.join("b-broken.wasm")
+    ));
+}
@@ -0,0 +1,49 @@
+use aws_iam::schema::{policy_json_schema, JSON_SCHEMA_DIALECT};
+use regex::Regex;
+use serde_json::Value;
+
+fn pattern(value: &Value) -> Regex {
+    Regex::new(value.as_str().unwrap()).unwrap()
+}
+
+#[test]
+fn test_policy_schema() {
+    let schema = policy_json_schema();
+    assert_eq!(schema["$schema"], JSON_SCHEMA_DIALECT);
+    assert_eq!(schema["required"], serde_json::json!(["Statement"]));
+    assert_eq!(
+        schema["properties"]["Version"]["enum"],
+        serde_json::json!(["2012-10-17", "2008-10-17"])
+    );
+
+    let statement = &schema["definitions"]["Statement"];
+    for name in &[
+        "Sid",
+        "Effect",
+        "Principal",
+        "NotPrincipal",
+        "Action",
+        "NotAction",
+        "Resource",
+        "NotResource",
+        "Condition",
+    ] {
+        assert!(
+            statement["properties"].get(name).is_some(),
+            "missing {}",
+            name
+        );
+    }
+
+    let name = pattern(&schema["definitions"]["QualifiedName"]["pattern"]);
+    assert!(name.is_match("s3:GetObject"));
+    assert!(name.is_match("s3:Get*"));
+    assert!(name.is_match("aws:PrincipalTag/team"));
+    assert!(!name.is_match("*"));
+    assert!(!name.is_match("GetObject"));
+
+    let operator = pattern(&schema["definitions"]["Condition"]["propertyNames"]["pattern"]);
+    assert!(operator.is_match("StringEquals"));
+    assert!(operator.is_match("ForAnyValue:StringLikeIfExists"));
+    assert!(!operator.is_match("ForSomeValues:StringLike"));
+}
@@ -0,0 +1,313 @@
+#![cfg(feature = "service_config")]
+
+use aws_iam::analysis::{
+    check_access_levels, check_dependent_actions, rewrite_not_elements,
+    FINDING_MISSING_DEPENDENT_ACTION, FINDING_REWRITE_NOT_EQUIVALENT, FINDING_REWRITE_NOT_POSSIBLE,
+    FINDING_WRITE_ACCESS_ANY_RESOURCE,
+};
+use aws_iam::lint::{LintRegistry, RULE_ACCESS_LEVEL, RULE_DEPENDENT_ACTIONS};
+use aws_iam::model::naming::Namespace;
+use aws_iam::model::{Action, OrAny, Policy, QualifiedName, Resource};
+use aws_iam::service::actions::{
+    access_level, all_metadata, dependent_actions, expand, iam, lambda, lookup, s3,
+    service_metadata, sqs, sts, AccessLevel,
+};
+use aws_iam::service::{ResourceType, ServiceConfig};
+use aws_iam::syntax::all_actions_for_service;
+use aws_iam::validate::Severity;
+use std::str::FromStr;
+
+#[test]
+fn test_action_values() {
+    assert_eq!(s3::GET_OBJECT.to_string(), "s3:GetObject");
+    assert_eq!(sqs::SEND_MESSAGE.to_string(), "sqs:SendMessage");
+    assert_eq!(sts::ASSUME_ROLE.to_string(), "sts:AssumeRole");
+    assert!(QualifiedName::is_valid(&s3::PUT_OBJECT_TAGGING));
+}
+
+#[test]
+fn test_lookup() {
+    let metadata = lookup(&s3::PUT_BUCKET_POLICY).unwrap();
+    assert_eq!(metadata.namespace, "s3");
+    assert_eq!(metadata.access_level, AccessLevel::PermissionsManagement);
+    assert_eq!(metadata.qualified_name(), *s3::PUT_BUCKET_POLICY);
+
+    let metadata = lookup(&QualifiedName::from_str("S3:getobject").unwrap()).unwrap();
+    assert_eq!(metadata.name, "GetObject");
+
+    assert!(lookup(&QualifiedName::from_str("s3:NoSuchAction").unwrap()).is_none());
+    assert!(lookup(&QualifiedName::from_str("nosuchservice:GetObject").unwrap()).is_none());
+}
+
+#[test]
+fn test_all_values_valid() {
+    for namespace in &[
+        "s3", "sqs", "sns", "sts", "iam", "kms", "lambda", "dynamodb",
+    ] {
+        let actions = service_metadata(namespace).unwrap();
+        assert!(!actions.is_empty());
+        for metadata in actions {
+            assert!(QualifiedName::is_valid(&metadata.qualified_name()));
+            assert_eq!(lookup(&metadata.qualified_name()), Some(metadata));
+            for dependent in metadata.dependent_actions {
+                assert!(lookup(&QualifiedName::from_str(dependent).unwrap()).is_some());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_expand() {
+    let actions = expand(&QualifiedName::from_str("s3:Get*").unwrap());
+    assert!(actions.contains(&lookup(&s3::GET_OBJECT).unwrap()));
+    assert!(actions
+        .iter()
+        .all(|metadata| metadata.name.starts_with("Get")));
+
+    assert!(expand(&QualifiedName::from_str("s3:NoSuch*").unwrap()).is_empty());
+    assert!(expand(&QualifiedName::from_str("nosuchservice:*").unwrap()).is_empty());
+}
+
+#[test]
+fn test_all_actions_for_service() {
+    let actions = all_actions_for_service("s3");
+    assert!(actions.iter().any(|metadata| metadata.name == "GetObject"));
+    assert!(actions.iter().all(|metadata| metadata.namespace == "s3"));
+    assert!(all_actions_for_service("nope").is_empty());
+}
+
+#[test]
+fn test_access_level() {
+    assert!(AccessLevel::List < AccessLevel::Read);
+    assert!(AccessLevel::Tagging < AccessLevel::Write);
+    assert!(AccessLevel::Write < AccessLevel::PermissionsManagement);
+
+    assert_eq!(access_level(&s3::LIST_BUCKET), Some(AccessLevel::List));
+    assert_eq!(
+        access_level(&QualifiedName::from_str("s3:Put*").unwrap()),
+        Some(AccessLevel::PermissionsManagement)
+    );
+    assert_eq!(
+        access_level(&QualifiedName::from_str("s3:NoSuch*").unwrap()),
+        None
+    );
+    assert!(all_metadata().any(|metadata| metadata.namespace == "kms"));
+}
+
+#[test]
+fn test_statement_max_access_level() {
+    let policy = Policy::from_str(
+        r#"{"Statement": [
+            {"Effect": "Allow", "Action": ["s3:GetObject", "s3:ListBucket"], "Resource": "*"},
+            {"Effect": "Allow", "Action": "s3:*", "Resource": "arn:aws:s3:::bucket/*"},
+            {"Effect": "Allow", "NotAction": "iam:*", "Resource": "*"},
+            {"Effect": "Allow", "Action": "ec2:RunInstances", "Resource": "*"}
+        ]}"#,
+    )
+    .unwrap();
+    let levels: Vec<Option<AccessLevel>> = policy
+        .statement
+        .iter()
+        .map(|statement| statement.max_access_level())
+        .collect();
+    assert_eq!(
+        levels,
+        vec![
+            Some(AccessLevel::Read),
+            Some(AccessLevel::PermissionsManagement),
+            Some(AccessLevel::PermissionsManagement),
+            None
+        ]
+    );
+
+    let findings = check_access_levels(&policy);
+    assert_eq!(findings.len(), 1);
+    let finding = findings.iter().next().unwrap();
+    assert_eq!(finding.code, FINDING_WRITE_ACCESS_ANY_RESOURCE);
+    assert_eq!(finding.statement, Some(2));
+
+    let registry = LintRegistry::default();
+    assert!(registry.names().contains(&RULE_ACCESS_LEVEL));
+    assert!(registry
+        .check(&policy)
+        .iter()
+        .any(|finding| finding.code == FINDING_WRITE_ACCESS_ANY_RESOURCE));
+}
+
+#[test]
+fn test_dependent_actions() {
+    assert_eq!(
+        dependent_actions(&lambda::CREATE_FUNCTION),
+        vec![iam::PASS_ROLE.clone()]
+    );
+    assert!(dependent_actions(&lambda::INVOKE_FUNCTION).is_empty());
+    assert_eq!(
+        dependent_actions(&QualifiedName::from_str("lambda:*").unwrap()),
+        vec![iam::PASS_ROLE.clone()]
+    );
+    assert!(dependent_actions(&QualifiedName::from_str("ec2:RunInstances").unwrap()).is_empty());
+}
+
+#[test]
+fn test_check_dependent_actions() {
+    let policy = Policy::from_str(
+        r#"{"Statement": [
+            {"Effect": "Allow", "Action": ["lambda:CreateFunction", "lambda:UpdateFunctionConfiguration"], "Resource": "*"},
+            {"Effect": "Allow", "Action": "s3:PutObject", "Resource": "arn:aws:s3:::bucket/*"},
+            {"Effect": "Allow", "Action": "kms:GenerateDataKey", "Resource": "*"}
+        ]}"#,
+    )
+    .unwrap();
+    let findings = check_dependent_actions(&policy);
+    assert_eq!(findings.len(), 1);
+    let finding = findings.iter().next().unwrap();
+    assert_eq!(finding.code, FINDING_MISSING_DEPENDENT_ACTION);
+    assert_eq!(finding.severity, Severity::Info);
+    assert_eq!(finding.statement, Some(0));
+    assert!(finding.message.contains("iam:PassRole"));
+
+    let registry = LintRegistry::default();
+    assert!(registry.names().contains(&RULE_DEPENDENT_ACTIONS));
+    assert!(registry
+        .check(&policy)
+        .iter()
+        .any(|finding| finding.code == FINDING_MISSING_DEPENDENT_ACTION));
+
+    let policy = Policy::from_str(
+        r#"{"Statement": [
+            {"Effect": "Allow", "Action": "lambda:CreateFunction", "Resource": "*"},
+            {"Effect": "Allow", "Action": "iam:PassRole", "Resource": "arn:aws:iam::123456789012:role/lambda"}
+        ]}"#,
+    )
+    .unwrap();
+    assert!(check_dependent_actions(&policy).is_empty());
+}
+
+fn sqs_service() -> ServiceConfig {
+    ServiceConfig::new(Namespace::from_str("sqs").unwrap()).with_resource_type(ResourceType::new(
+        "queue",
+        "arn:${Partition}:sqs:${Region}:${Account}:${QueueName}",
+    ))
+}
+
+fn sns_service() -> ServiceConfig {
+    ServiceConfig::new(Namespace::from_str("sns").unwrap()).with_resource_type(ResourceType::new(
+        "topic",
+        "arn:${Partition}:sns:${Region}:${Account}:${TopicName}",
+    ))
+}
+
+#[test]
+fn test_rewrite_not_action_exact() {
+    let policy = Policy::from_str(
+        r#"{"Statement":[{"Effect":"Allow","NotAction":["sqs:Delete*","sqs:*Permission"],"Resource":"arn:aws:sqs:us-east-1:123456789012:orders"}]}"#,
+    )
+    .unwrap();
+    let (rewritten, findings) = rewrite_not_elements(&policy, &[]);
+
+    assert!(findings.is_empty());
+    let action = &rewritten.statement[0].action;
+    assert!(matches!(action, Action::Action(_)));
+    assert!(action.matches("sqs:SendMessage"));
+    assert!(action.matches("sqs:ReceiveMessage"));
+    assert!(!action.matches("sqs:DeleteQueue"));
+    assert!(!action.matches("sqs:AddPermission"));
+}
+
+#[test]
+fn test_rewrite_not_action_narrowed() {
+    let policy = Policy::from_str(
+        r#"{"Statement":[
+            {"Effect":"Allow","NotAction":"sqs:DeleteQueue","Resource":"*"},
+            {"Effect":"Deny","NotAction":"sqs:ListQueues","Resource":"*"}
+        ]}"#,
+    )
+    .unwrap();
+    let (rewritten, findings) = rewrite_not_elements(&policy, &[]);
+
+    assert!(rewritten.statement[0].action.matches("sqs:SendMessage"));
+    assert!(!rewritten.statement[0].action.matches("s3:GetObject"));
+    assert_eq!(rewritten.statement[1], policy.statement[1]);
+    let codes: Vec<(&str, Option<usize>)> = findings
+        .iter()
+        .map(|finding| (finding.code, finding.statement))
+        .collect();
+    assert_eq!(
+        codes,
+        vec![
+            (FINDING_REWRITE_NOT_EQUIVALENT, Some(0)),
+            (FINDING_REWRITE_NOT_POSSIBLE, Some(1))
+        ]
+    );
+}
+
+#[test]
+fn test_rewrite_not_action_unknown_service() {
+    let policy = Policy::from_str(
+        r#"{"Statement":[{"Effect":"Allow","NotAction":"acme:Launch","Resource":"*"}]}"#,
+    )
+    .unwrap();
+    let (rewritten, findings) = rewrite_not_elements(&policy, &[]);
+
+    assert_eq!(rewritten, policy);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(
+        findings.iter().next().unwrap().code,
+        FINDING_REWRITE_NOT_POSSIBLE
+    );
+}
+
+#[test]
+fn test_rewrite_not_resource() {
+    let policy = Policy::from_str(
+        r#"{"Statement":[{"Effect":"Allow","Action":["sqs:SendMessage","sns:Publish"],"NotResource":"arn:aws:sns:*:*:*"}]}"#,
+    )
+    .unwrap();
+    let (rewritten, findings) = rewrite_not_elements(&policy, &[sqs_service(), sns_service()]);
+
+    assert!(findings.is_empty());
+    match &rewritten.statement[0].resource {
+        Some(Resource::Resource(OrAny::Some(resources))) => {
+            let resources: Vec<String> = resources.iter().map(|r| r.to_string()).collect();
+            assert_eq!(resources, vec!["arn:aws:sqs:*:*:*"]);
+        }
+        resource => panic!("expecting a Resource, not {:?}", resource),
+    }
+}
+
+#[test]
+fn test_rewrite_not_resource_partial() {
+    let allow = Policy::from_str(
+        r#"{"Statement":[{"Effect":"Allow","Action":["sqs:SendMessage","sns:Publish"],"NotResource":"arn:aws:sns:us-east-1:123456789012:alerts"}]}"#,
+    )
+    .unwrap();
+    let (rewritten, findings) = rewrite_not_elements(&allow, &[sqs_service(), sns_service()]);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(
+        findings.iter().next().unwrap().code,
+        FINDING_REWRITE_NOT_EQUIVALENT
+    );
+    assert!(!rewritten.to_string().contains("sns:*"));
+    assert!(rewritten.to_string().contains("arn:aws:sqs:*:*:*"));
+
+    let deny = Policy::from_str(&allow.to_string().replace("Allow", "Deny")).unwrap();
+    let (rewritten, findings) = rewrite_not_elements(&deny, &[sqs_service(), sns_service()]);
+    assert_eq!(findings.len(), 1);
+    assert!(rewritten.to_string().contains("arn:aws:sns:*:*:*"));
+    assert!(rewritten.to_string().contains("arn:aws:sqs:*:*:*"));
+}
+
+#[test]
+fn test_rewrite_not_resource_unknown_service() {
+    let policy = Policy::from_str(
+        r#"{"Statement":[{"Effect":"Allow","Action":"sqs:SendMessage","NotResource":"arn:aws:sqs:*:*:dead-letters"}]}"#,
+    )
+    .unwrap();
+    let (rewritten, findings) = rewrite_not_elements(&policy, &[sns_service()]);
+
+    assert_eq!(rewritten, policy);
+    assert_eq!(
+        findings.iter().next().unwrap().code,
+        FINDING_REWRITE_NOT_POSSIBLE
+    );
+}
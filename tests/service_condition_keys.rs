@@ -0,0 +1,111 @@
+#![cfg(feature = "service_config")]
+
+use aws_iam::model::naming::Namespace;
+use aws_iam::model::Policy;
+use aws_iam::service::{ResourceType, ServiceConfig};
+use aws_iam::validate::{PolicyType, ValidationOptions, FINDING_INAPPLICABLE_CONDITION_KEY};
+use std::str::FromStr;
+
+fn s3_config() -> ServiceConfig {
+    ServiceConfig::new(Namespace::from_str("s3").unwrap())
+        .with_resource_type(
+            ResourceType::new("bucket", "arn:${Partition}:s3:::${BucketName}")
+                .with_condition_key("aws:ResourceTag/${TagKey}"),
+        )
+        .with_resource_type(
+            ResourceType::new(
+                "object",
+                "arn:${Partition}:s3:::${BucketName}/${ObjectName}",
+            )
+            .with_condition_key("s3:ExistingObjectTag/${TagKey}"),
+        )
+}
+
+fn policy_with(resource: &str) -> Policy {
+    Policy::from_str(&format!(
+        r#"{{
+            "Version": "2012-10-17",
+            "Statement": [{{
+                "Effect": "Allow",
+                "Action": "s3:GetObject",
+                "Resource": {},
+                "Condition": {{
+                    "StringEquals": {{
+                        "s3:ExistingObjectTag/team": "finance",
+                        "aws:SourceVpc": "vpc-111bbb22"
+                    }}
+                }}
+            }}]
+        }}"#,
+        resource
+    ))
+    .unwrap()
+}
+
+fn inapplicable(policy: &Policy) -> usize {
+    policy
+        .validate(
+            &ValidationOptions::for_type(PolicyType::Identity).with_services(vec![s3_config()]),
+        )
+        .iter()
+        .filter(|finding| finding.code == FINDING_INAPPLICABLE_CONDITION_KEY)
+        .count()
+}
+
+#[test]
+fn test_resource_type_matches() {
+    let config = s3_config();
+    let types = config.resource_types_for("arn:aws:s3:::bucket");
+    assert_eq!(types.len(), 1);
+    assert_eq!(types[0].name, "bucket");
+
+    let types = config.resource_types_for("arn:aws:s3:::bucket/reports/2020.csv");
+    assert_eq!(types.len(), 1);
+    assert_eq!(types[0].name, "object");
+
+    assert_eq!(config.resource_types_for("arn:aws:s3:::bucket/*").len(), 2);
+    assert!(config
+        .resource_types_for("arn:aws:sqs:us-east-1:123456789012:queue")
+        .is_empty());
+}
+
+#[test]
+fn test_condition_key_support() {
+    let config = s3_config();
+    assert!(config.resource_types[0].supports_condition_key("aws:resourcetag/Owner"));
+    assert!(!config.resource_types[0].supports_condition_key("aws:ResourceTag/"));
+    assert!(config.resource_types[1].supports_condition_key("s3:ExistingObjectTag/team"));
+    assert!(config.is_resource_condition_key("s3:ExistingObjectTag/team"));
+    assert!(!config.is_resource_condition_key("aws:SourceVpc"));
+}
+
+#[test]
+fn test_validate_inapplicable_condition_key() {
+    assert_eq!(inapplicable(&policy_with(r#""arn:aws:s3:::bucket""#)), 1);
+    assert_eq!(
+        inapplicable(&policy_with(r#""arn:aws:s3:::bucket/report.csv""#)),
+        0
+    );
+    assert_eq!(
+        inapplicable(&policy_with(
+            r#"["arn:aws:s3:::bucket", "arn:aws:s3:::bucket/report.csv"]"#
+        )),
+        0
+    );
+    assert_eq!(inapplicable(&policy_with(r#""arn:aws:s3:::bucket/*""#)), 0);
+    assert_eq!(
+        inapplicable(&policy_with(
+            r#"["arn:aws:s3:::bucket", "arn:aws:sqs:us-east-1:123456789012:queue"]"#
+        )),
+        0
+    );
+}
+
+#[test]
+fn test_validate_without_services() {
+    let policy = policy_with(r#""arn:aws:s3:::bucket""#);
+    assert!(policy
+        .validate(&ValidationOptions::for_type(PolicyType::Identity))
+        .iter()
+        .all(|finding| finding.code != FINDING_INAPPLICABLE_CONDITION_KEY));
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "service_config")]
+
+use aws_iam::arn::{Partition, Region};
+use aws_iam::service::{suggest_values, KNOWN_REGIONS};
+
+#[test]
+fn test_suggest_regions() {
+    let regions = suggest_values("aws:RequestedRegion").unwrap();
+    assert_eq!(regions, KNOWN_REGIONS);
+    assert!(regions.contains(&"us-east-1"));
+    assert_eq!(suggest_values("AWS:requestedregion"), Some(regions));
+    for region in regions {
+        assert!(Region::is_valid(region));
+    }
+    assert!(regions.contains(&"cn-north-1"));
+    assert!(regions.contains(&"us-gov-west-1"));
+}
+
+#[test]
+fn test_suggest_principal_types() {
+    let types = suggest_values("aws:PrincipalType").unwrap();
+    assert!(types.contains(&"AssumedRole"));
+    assert!(types.contains(&"Anonymous"));
+}
+
+#[test]
+fn test_suggest_partitions() {
+    let prefixes = suggest_values("aws:SourceArn").unwrap();
+    for partition in &[Partition::Aws, Partition::AwsCn, Partition::AwsUsGov] {
+        assert!(prefixes.contains(&format!("arn:{}:", partition).as_str()));
+    }
+}
+
+#[test]
+fn test_suggest_other_values() {
+    assert_eq!(
+        suggest_values("aws:SecureTransport"),
+        Some(&["true", "false"][..])
+    );
+    assert!(suggest_values("s3:x-amz-server-side-encryption")
+        .unwrap()
+        .contains(&"aws:kms"));
+    assert!(suggest_values("aws:SourceIp").is_none());
+    assert!(suggest_values("nosuchservice:Key").is_none());
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "signing")]
+
+use aws_iam::error::{IamError, SigningError};
+use aws_iam::io::read_from_string;
+use aws_iam::signing::{
+    canonical_form, read_verified_file, sign_policy, signature_from_str, signature_to_string,
+    verify_policy, verifying_key_from_str, verifying_key_to_string, write_signature_file,
+    SigningKey,
+};
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+const ALLOW_GET: &str = r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject","Resource":"arn:aws:s3:::bucket/*"}]}"#;
+
+const ALLOW_GET_REFORMATTED: &str = r#"{
+  "Statement": [
+    {
+      "Resource": "arn:aws:s3:::bucket/*",
+      "Action": "s3:GetObject",
+      "Effect": "Allow"
+    }
+  ]
+}"#;
+
+const ALLOW_PUT: &str = r#"{"Statement":[{"Effect":"Allow","Action":"s3:PutObject","Resource":"arn:aws:s3:::bucket/*"}]}"#;
+
+fn key(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32])
+}
+
+fn policy_directory() -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("aws-iam-signing-{}", Uuid::new_v4()));
+    create_dir_all(&directory).unwrap();
+    directory
+}
+
+#[test]
+fn test_canonical_form_ignores_formatting() {
+    let policy = read_from_string(ALLOW_GET).unwrap();
+    let reformatted = read_from_string(ALLOW_GET_REFORMATTED).unwrap();
+    assert_eq!(
+        canonical_form(&policy).unwrap(),
+        canonical_form(&reformatted).unwrap()
+    );
+}
+
+#[test]
+fn test_sign_and_verify() {
+    let policy = read_from_string(ALLOW_GET).unwrap();
+    let signature = sign_policy(&key(1), &policy).unwrap();
+
+    let reformatted = read_from_string(ALLOW_GET_REFORMATTED).unwrap();
+    assert!(verify_policy(&reformatted, &signature, &[key(1).verifying_key()]).is_ok());
+    assert!(verify_policy(
+        &policy,
+        &signature,
+        &[key(2).verifying_key(), key(1).verifying_key()]
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_verify_rejects_changed_policy() {
+    let policy = read_from_string(ALLOW_GET).unwrap();
+    let signature = sign_policy(&key(1), &policy).unwrap();
+
+    let changed = read_from_string(ALLOW_PUT).unwrap();
+    assert!(matches!(
+        verify_policy(&changed, &signature, &[key(1).verifying_key()]),
+        Err(IamError::Signing(SigningError::Untrusted))
+    ));
+    assert!(verify_policy(&policy, &signature, &[key(2).verifying_key()]).is_err());
+    assert!(verify_policy(&policy, &signature, &[]).is_err());
+}
+
+#[test]
+fn test_signature_and_key_strings() {
+    let policy = read_from_string(ALLOW_GET).unwrap();
+    let signature = sign_policy(&key(1), &policy).unwrap();
+    let parsed = signature_from_str(&format!("{}\n", signature_to_string(&signature))).unwrap();
+    assert_eq!(parsed, signature);
+
+    let verifying_key = key(1).verifying_key();
+    let parsed = verifying_key_from_str(&verifying_key_to_string(&verifying_key)).unwrap();
+    assert_eq!(parsed, verifying_key);
+
+    assert!(matches!(
+        signature_from_str("c2hvcnQ="),
+        Err(SigningError::InvalidSignature(_))
+    ));
+    assert!(matches!(
+        verifying_key_from_str("not base64!"),
+        Err(SigningError::InvalidKey(_))
+    ));
+}
+
+#[test]
+fn test_signature_files() {
+    let directory = policy_directory();
+    let path = directory.join("allow.json");
+    write(&path, ALLOW_GET).unwrap();
+    let trusted = [key(1).verifying_key()];
+
+    assert!(matches!(
+        read_verified_file(&path, &trusted),
+        Err(IamError::Signing(SigningError::MissingSignature { .. }))
+    ));
+
+    let signature_path = write_signature_file(&path, &key(1)).unwrap();
+    assert_eq!(signature_path, directory.join("allow.json.sig"));
+    assert!(read_verified_file(&path, &trusted).is_ok());
+
+    write(&path, ALLOW_PUT).unwrap();
+    assert!(read_verified_file(&path, &trusted).is_err());
+    remove_dir_all(&directory).unwrap();
+}
+
+#[cfg(feature = "watch")]
+#[test]
+fn test_watch_trusted_keys() {
+    use aws_iam::io::{watch_directory_with, WatchOptions};
+    use std::time::Duration;
+
+    let directory = policy_directory();
+    let path = directory.join("allow.json");
+    write(&path, ALLOW_GET).unwrap();
+    let options = WatchOptions::default()
+        .with_interval(Duration::from_secs(3600))
+        .with_trusted_keys(vec![key(1).verifying_key()]);
+
+    assert!(watch_directory_with(&directory, options.clone()).is_err());
+
+    let _ = write_signature_file(&path, &key(1)).unwrap();
+    let handle = watch_directory_with(&directory, options).unwrap();
+    assert_eq!(handle.current().len(), 1);
+
+    let other = directory.join("other.json");
+    write(&other, ALLOW_PUT).unwrap();
+    let _ = write_signature_file(&other, &key(2)).unwrap();
+    assert!(handle.reload().is_err());
+    assert_eq!(handle.current().len(), 1);
+
+    // a new signature is a change to the directory, the policy is read again.
+    let _ = write_signature_file(&other, &key(1)).unwrap();
+    assert!(handle.reload().unwrap());
+    assert_eq!(handle.current().len(), 2);
+    remove_dir_all(&directory).unwrap();
+}
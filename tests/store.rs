@@ -0,0 +1,99 @@
+use aws_iam::model::Policy;
+use aws_iam::store::{fingerprint, PolicyStore};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::thread;
+
+const POLICY: &str = r#"{
+    "Statement": [{
+        "Effect": "Allow",
+        "Action": ["s3:GetObject", "s3:PutObject"],
+        "Resource": "arn:aws:s3:::bucket/*",
+        "Condition": {"StringEquals": {"aws:PrincipalOrgID": "o-123456"}}
+    }]
+}"#;
+
+#[test]
+fn test_fingerprint_ignores_formatting() {
+    let first = Policy::from_str(POLICY).unwrap();
+    let second = Policy::from_str(&POLICY.replace(char::is_whitespace, "")).unwrap();
+    assert_eq!(fingerprint(&first), fingerprint(&second));
+}
+
+#[test]
+fn test_insert_deduplicates() {
+    let store = PolicyStore::default();
+    assert!(store.is_empty());
+
+    let first = store.parse(POLICY).unwrap();
+    let second = store.parse(POLICY).unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(store.len(), 1);
+    assert!(store.contains(&first.policy()));
+    assert!(store.get(first.fingerprint()).is_some());
+
+    assert_eq!(first.actions().len(), 2);
+    assert_eq!(first.resources()[0].as_ref(), "arn:aws:s3:::bucket/*");
+    assert_eq!(first.condition_keys()[0].as_ref(), "aws:PrincipalOrgID");
+
+    store.clear();
+    assert!(store.is_empty());
+    assert_eq!(store.interned_len(), 0);
+}
+
+#[test]
+fn test_strings_are_shared() {
+    let store = PolicyStore::default();
+    let first = store.parse(POLICY).unwrap();
+    let second = store
+        .parse(&POLICY.replace("s3:PutObject", "s3:DeleteObject"))
+        .unwrap();
+    assert_eq!(store.len(), 2);
+    assert!(Arc::ptr_eq(&first.actions()[0], &second.actions()[0]));
+    assert!(Arc::ptr_eq(&first.resources()[0], &second.resources()[0]));
+    assert!(Arc::ptr_eq(
+        &store.intern("s3:GetObject"),
+        &first.actions()[0]
+    ));
+    // the element and operator names, and the values, of both documents.
+    assert_eq!(store.interned_len(), 13);
+    assert_eq!(store.policies_with_action("s3:DeleteObject").len(), 1);
+    assert_eq!(store.policies_with_action("s3:GetObject").len(), 2);
+}
+
+#[test]
+fn test_documents_share_strings() {
+    let store = PolicyStore::default();
+    let first = store.parse(POLICY).unwrap();
+    let second = store.parse(&POLICY.replace("bucket/*", "other/*")).unwrap();
+    assert!(!Arc::ptr_eq(&first, &second));
+    assert!(Arc::ptr_eq(&first.actions()[1], &second.actions()[1]));
+    assert!(Arc::ptr_eq(
+        &first.condition_keys()[0],
+        &second.condition_keys()[0]
+    ));
+    assert!(!Arc::ptr_eq(&first.resources()[0], &second.resources()[0]));
+    assert!(Arc::ptr_eq(
+        &store.intern("Statement"),
+        &store.intern(&"Statement".to_string())
+    ));
+    assert_eq!(store.interned_len(), 13);
+
+    assert_eq!(first.policy(), Policy::from_str(POLICY).unwrap());
+    assert_eq!(second.resources()[0].as_ref(), "arn:aws:s3:::other/*");
+}
+
+#[test]
+fn test_shared_between_threads() {
+    let store = Arc::new(PolicyStore::default());
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let store = store.clone();
+            thread::spawn(move || store.parse(POLICY).unwrap().fingerprint())
+        })
+        .collect();
+    for handle in handles {
+        let _ = handle.join().unwrap();
+    }
+    assert_eq!(store.len(), 1);
+}
@@ -0,0 +1,92 @@
+use aws_iam::error::{IamError, TemplateError};
+use aws_iam::model::Effect;
+use aws_iam::template::PolicyTemplate;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+const TEMPLATE: &str = r#"{"Statement":[{
+    "Sid":"ReadBucket",
+    "Effect":"Allow",
+    "Principal":{"AWS":"arn:aws:iam::{{ account }}:root"},
+    "Action":"s3:GetObject",
+    "Resource":["arn:aws:s3:::{{bucket_name}}", "arn:aws:s3:::{{bucket_name}}/{{prefix}}*"],
+    "Condition":{"StringEquals":{"aws:PrincipalTag/team":"{{team}}"}}
+}]}"#;
+
+fn params(values: &[(&str, &str)]) -> HashMap<String, String> {
+    values
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_template_parameters() {
+    let template = PolicyTemplate::from_str(TEMPLATE).unwrap();
+    assert_eq!(
+        template.parameters(),
+        &["account", "bucket_name", "prefix", "team"]
+    );
+    assert_eq!(template.to_string(), TEMPLATE);
+}
+
+#[test]
+fn test_template_render() {
+    let template = PolicyTemplate::from_str(TEMPLATE).unwrap();
+    let policy = template
+        .render(&params(&[
+            ("account", "123456789012"),
+            ("bucket_name", "examplebucket"),
+            ("prefix", "home/"),
+            ("team", "say \"hello\""),
+        ]))
+        .unwrap();
+    let statement = &policy.statement[0];
+    assert_eq!(statement.effect, Effect::Allow);
+    assert_eq!(
+        statement.resource().unwrap().to_string(),
+        r#"{"Resource":["arn:aws:s3:::examplebucket","arn:aws:s3:::examplebucket/home/*"]}"#
+    );
+    assert_eq!(
+        statement.condition.as_ref().unwrap().to_string(),
+        r#"{"Condition":{"StringEquals":{"aws:PrincipalTag/team":"say \"hello\""}}}"#
+    );
+}
+
+#[test]
+fn test_template_parameter_errors() {
+    let template = PolicyTemplate::from_str(TEMPLATE).unwrap();
+    let mut values = params(&[
+        ("account", "123456789012"),
+        ("bucket_name", "examplebucket"),
+        ("prefix", "home/"),
+    ]);
+    assert!(matches!(
+        template.render(&values),
+        Err(IamError::Template(TemplateError::MissingParameter { name })) if name == "team"
+    ));
+
+    let _ = values.insert("team".to_string(), "platform".to_string());
+    let _ = values.insert("region".to_string(), "us-east-1".to_string());
+    assert!(matches!(
+        template.render(&values),
+        Err(IamError::Template(TemplateError::UnexpectedParameter { name })) if name == "region"
+    ));
+}
+
+#[test]
+fn test_template_invalid() {
+    assert!(matches!(
+        PolicyTemplate::from_str(
+            r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject","Resource":"arn:aws:s3:::{{bucket-name}}"}]}"#
+        ),
+        Err(IamError::Template(TemplateError::InvalidPlaceholder { value })) if value == "{{bucket-name}}"
+    ));
+    assert!(matches!(
+        PolicyTemplate::from_str(
+            r#"{"Statement":[{"Effect":"{{effect}}","Action":"s3:GetObject"}]}"#
+        ),
+        Err(IamError::Format(_))
+    ));
+    assert!(PolicyTemplate::from_str(r#"{"Statement":{{statements}}}"#).is_err());
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "templating")]
+
+use aws_iam::error::{IamError, TemplateError};
+use aws_iam::template::HandlebarsTemplate;
+use serde_json::json;
+use std::str::FromStr;
+
+const BUCKETS: &str = r#"{
+  "Version": "2012-10-17",
+  "Statement": [
+    {{#each buckets}}
+    {
+      "Sid": "Read{{@index}}",
+      "Effect": "Allow",
+      "Action": "s3:GetObject",
+      "Resource": "arn:aws:s3:::{{this}}/${aws:username}/notes.txt"
+    }{{#unless @last}},{{/unless}}
+    {{/each}}
+  ]
+}"#;
+
+#[test]
+fn test_render_each() {
+    let template = HandlebarsTemplate::from_str(BUCKETS).unwrap();
+    let policy = template
+        .render(&json!({"buckets": ["reports", "archive", "scratch"]}))
+        .unwrap();
+    assert_eq!(policy.statement.len(), 3);
+    assert_eq!(
+        policy.statement[2].sid.as_ref().unwrap().to_string(),
+        "Read2"
+    );
+    assert!(policy.statement[1]
+        .resource()
+        .unwrap()
+        .to_string()
+        .contains("arn:aws:s3:::archive/${aws:username}/notes.txt"));
+}
+
+#[test]
+fn test_render_to_string() {
+    let template = HandlebarsTemplate::from_str(r#"{"Id": "{{id}}"}"#).unwrap();
+    let rendered = template
+        .render_to_string(&json!({"id": "say \"hello\""}))
+        .unwrap();
+    assert_eq!(rendered, r#"{"Id": "say \"hello\""}"#);
+}
+
+#[test]
+fn test_syntax_error_line() {
+    match HandlebarsTemplate::from_str("{\n  \"Id\": \"{{#if id}}\"\n}") {
+        Err(IamError::Template(TemplateError::Syntax { line: Some(_), .. })) => {}
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn test_missing_value_line() {
+    let template = HandlebarsTemplate::from_str(BUCKETS).unwrap();
+    match template.render(&json!({})) {
+        Err(IamError::Template(TemplateError::Render { line, .. })) => {
+            assert_eq!(line, Some(4))
+        }
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn test_invalid_policy_line() {
+    let template = HandlebarsTemplate::from_str(
+        r#"{
+  "Statement": [
+    {{#each actions}}
+    {"Effect": "Allow", "Action": "{{this}}", "Resource": "*"}
+    {{/each}}
+  ]
+}"#,
+    )
+    .unwrap();
+    assert!(template
+        .render(&json!({"actions": ["s3:GetObject"]}))
+        .is_ok());
+    match template.render(&json!({"actions": ["s3:GetObject", "s3:PutObject"]})) {
+        Err(IamError::Template(TemplateError::InvalidPolicy { line, .. })) => {
+            assert_eq!(line, Some(4))
+        }
+        result => panic!("unexpected result {:?}", result),
+    }
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "testkit")]
+
+use aws_iam::io;
+use aws_iam::testkit::{assert_golden, assert_round_trip, check_round_trip, Corpus};
+use std::path::Path;
+
+#[test]
+fn test_crate_corpus() {
+    let corpus = Corpus::crate_corpus().unwrap();
+    assert!(!corpus.good().is_empty());
+    assert!(!corpus.bad().is_empty());
+    let failures = corpus.check();
+    assert!(failures.is_empty(), "{:#?}", failures);
+}
+
+#[test]
+fn test_corpus_reports_failures() {
+    let mut corpus = Corpus::default();
+    let _ = corpus
+        .add_bad("tests/data/good/quantified-001.json")
+        .add_good("tests/data/bad/effect-001.json")
+        .add_good("tests/data/missing.json");
+    let failures: Vec<String> = corpus
+        .check()
+        .iter()
+        .map(|f| f.path.display().to_string())
+        .collect();
+    assert_eq!(
+        failures,
+        vec![
+            "tests/data/bad/effect-001.json",
+            "tests/data/missing.json",
+            "tests/data/good/quantified-001.json",
+        ]
+    );
+}
+
+#[test]
+fn test_round_trip_and_golden() {
+    let policy = io::read_from_file(Path::new("tests/data/good/quantified-002.json")).unwrap();
+    assert_round_trip(&policy);
+    let written = check_round_trip(&policy).unwrap();
+    assert_golden(
+        Path::new("tests/data/good/quantified-002.json"),
+        &format!("{}\n", written),
+    );
+}
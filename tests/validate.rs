@@ -0,0 +1,277 @@
+use aws_iam::arn::{AccountIdentifier, ArnParseMode};
+use aws_iam::model::{Policy, SidProfile, Statement, Version};
+use aws_iam::syntax::IamValue;
+use aws_iam::validate::{
+    validate_str, PolicyType, ValidationOptions, FINDING_DUPLICATE_CONDITION_KEY,
+    FINDING_DUPLICATE_KEY, FINDING_DUPLICATE_SID, FINDING_INVALID_CONDITION_VALUE,
+    FINDING_INVALID_SID, FINDING_MISSING_RESOURCE, FINDING_TRUST_MISSING_EXTERNAL_ID,
+    FINDING_TRUST_MISSING_SOURCE, FINDING_TRUST_PUBLIC_PRINCIPAL,
+    FINDING_UNKNOWN_CONDITION_OPERATOR, FINDING_VARIABLES_REQUIRE_VERSION,
+};
+use serde_json::json;
+use std::str::FromStr;
+
+#[test]
+fn test_statement_without_resource_from_json() {
+    let statement = Statement::from_json(&json!({
+        "Effect": "Allow",
+        "Principal": {"AWS": "arn:aws:iam::123456789012:root"},
+        "Action": "s3:GetObject"
+    }))
+    .unwrap();
+    assert!(statement.resource().is_none());
+    assert!(statement.to_json().unwrap().get("Resource").is_none());
+}
+
+#[test]
+fn test_missing_resource_allowed_in_resource_policy() {
+    let mut statement = Statement::unnamed();
+    let _ = statement.unset_resource();
+    let policy = Policy::from(statement);
+
+    let findings = policy.validate(&ValidationOptions::for_type(PolicyType::Resource));
+    assert!(findings.is_valid());
+    assert!(findings.is_empty());
+
+    let findings = policy.validate(&ValidationOptions::for_type(PolicyType::Trust));
+    assert!(findings.is_valid());
+}
+
+#[test]
+fn test_missing_resource_rejected_in_identity_policy() {
+    let mut statement = Statement::unnamed();
+    let _ = statement.unset_resource();
+    let policy = Policy::from(statement);
+
+    let findings = policy.validate(&ValidationOptions::default());
+    assert!(!findings.is_valid());
+    let errors: Vec<_> = findings.errors().collect();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].code, FINDING_MISSING_RESOURCE);
+    assert_eq!(errors[0].statement, Some(0));
+}
+
+#[test]
+fn test_resource_present_is_valid() {
+    let policy = Policy::from(Statement::unnamed());
+    for policy_type in &[
+        PolicyType::Identity,
+        PolicyType::Resource,
+        PolicyType::ServiceControl,
+    ] {
+        assert!(policy
+            .validate(&ValidationOptions::for_type(*policy_type))
+            .is_valid());
+    }
+}
+
+#[test]
+fn test_sid_validated_with_profile() {
+    let statement = Statement::from_json(&json!({
+        "Sid": "allow-send-message",
+        "Effect": "Allow",
+        "Principal": "*",
+        "Action": "sqs:SendMessage"
+    }))
+    .unwrap();
+    let policy = Policy::from(statement);
+
+    let options = ValidationOptions::for_type(PolicyType::Resource);
+    let findings = policy.validate(&options);
+    assert!(!findings.is_valid());
+    assert_eq!(findings[0].code, FINDING_INVALID_SID);
+
+    let findings = policy.validate(&options.with_sid_profile(SidProfile::Sqs));
+    assert!(findings.is_valid());
+}
+
+#[test]
+fn test_variables_require_version_2012() {
+    let value = json!({
+        "Statement": [{
+            "Effect": "Allow",
+            "Action": "s3:GetObject",
+            "Resource": "arn:aws:s3:::bucket/home/${aws:username}/*"
+        }]
+    });
+    let mut policy = Policy::from_json(&value).unwrap();
+    assert!(policy.has_variables());
+    assert_eq!(policy.effective_version(), Version::V2008);
+
+    let findings = policy.validate(&ValidationOptions::default());
+    assert!(findings.is_valid());
+    let warnings: Vec<_> = findings.warnings().collect();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].code, FINDING_VARIABLES_REQUIRE_VERSION);
+
+    assert!(policy.upgrade_version());
+    assert_eq!(policy.version(), Some(Version::V2012));
+    assert!(!policy.upgrade_version());
+    assert!(policy.validate(&ValidationOptions::default()).is_empty());
+}
+
+#[test]
+fn test_upgrade_version_from_2008() {
+    let mut policy = Policy::from(Statement::unnamed());
+    policy.set_version(Version::V2008);
+    assert!(policy.upgrade_version());
+    assert_eq!(policy.version(), Some(Version::V2012));
+}
+
+#[test]
+fn test_upgrade_version_escapes_literals() {
+    let value = json!({
+        "Version": "2008-10-17",
+        "Statement": [{
+            "Effect": "Allow",
+            "Principal": {"AWS": "arn:aws:iam::123456789012:user/${team}"},
+            "Action": "s3:GetObject",
+            "Resource": [
+                "arn:aws:s3:::bucket/${aws:username}/*",
+                "arn:aws:s3:::bucket/public"
+            ],
+            "Condition": {"StringEquals": {"s3:prefix": "${aws:username}", "s3:delimiter": "/"}}
+        }]
+    });
+    let mut policy = Policy::from_json(&value).unwrap();
+    assert!(policy.upgrade_version());
+
+    assert_eq!(
+        policy.to_json().unwrap(),
+        json!({
+            "Version": "2012-10-17",
+            "Statement": [{
+                "Effect": "Allow",
+                "Principal": {"AWS": "arn:aws:iam::123456789012:user/${$}{team}"},
+                "Action": "s3:GetObject",
+                "Resource": [
+                    "arn:aws:s3:::bucket/${$}{aws:username}/*",
+                    "arn:aws:s3:::bucket/public"
+                ],
+                "Condition": {"StringEquals": {"s3:prefix": "${$}{aws:username}", "s3:delimiter": "/"}}
+            }]
+        })
+    );
+    assert!(policy.validate(&ValidationOptions::default()).is_empty());
+}
+
+#[test]
+fn test_trust_policy_rules() {
+    let policy = Policy::from_str(
+        r#"{"Statement":[
+            {"Effect":"Allow","Principal":{"AWS":["111111111111","arn:aws:iam::222222222222:root"]},
+             "Action":"sts:AssumeRole"},
+            {"Effect":"Allow","Principal":{"AWS":"arn:aws:iam::222222222222:root"},
+             "Action":"sts:AssumeRole",
+             "Condition":{"StringEquals":{"sts:ExternalId":"a1b2c3"}}},
+            {"Effect":"Allow","Principal":{"Service":"sns.amazonaws.com"},"Action":"sts:AssumeRole"},
+            {"Effect":"Allow","Principal":{"Service":"sns.amazonaws.com"},"Action":"sts:AssumeRole",
+             "Condition":{"StringEquals":{"aws:SourceAccount":"111111111111"}}},
+            {"Effect":"Allow","Principal":"*","Action":"sts:AssumeRole"}
+        ]}"#,
+    )
+    .unwrap();
+    let options = ValidationOptions::for_type(PolicyType::Trust)
+        .with_account(AccountIdentifier::from_str("111111111111").unwrap());
+    let findings = policy.validate(&options);
+    assert!(findings.is_valid());
+    let codes: Vec<(&str, Option<usize>)> =
+        findings.iter().map(|f| (f.code, f.statement)).collect();
+    assert_eq!(
+        codes,
+        vec![
+            (FINDING_TRUST_MISSING_EXTERNAL_ID, Some(0)),
+            (FINDING_TRUST_MISSING_SOURCE, Some(2)),
+            (FINDING_TRUST_PUBLIC_PRINCIPAL, Some(4)),
+        ]
+    );
+
+    let findings = policy.validate(&ValidationOptions::for_type(PolicyType::Trust));
+    assert_eq!(findings.len(), 2);
+    assert!(policy
+        .validate(&ValidationOptions::for_type(PolicyType::Resource))
+        .is_empty());
+}
+
+#[test]
+fn test_duplicate_keys() {
+    let document = r#"{
+        "Version": "2012-10-17",
+        "Statement": [
+            {"Effect":"Allow","Action":"s3:GetObject","Resource":"*"},
+            {"Effect":"Allow","Action":"s3:GetObject","Resource":"*",
+             "Condition":{
+                "StringEquals":{"aws:SourceIp":"10.0.0.1","aws:SourceIp":"10.0.0.2"},
+                "StringLike":{"aws:UserAgent":"a*","AWS:useragent":"b*"}}}
+        ]
+    }"#;
+    let findings = validate_str(document, &ValidationOptions::default()).unwrap();
+    assert!(findings.is_valid());
+    let codes: Vec<(&str, Option<usize>)> =
+        findings.iter().map(|f| (f.code, f.statement)).collect();
+    assert_eq!(
+        codes,
+        vec![
+            (FINDING_DUPLICATE_KEY, Some(1)),
+            (FINDING_DUPLICATE_CONDITION_KEY, Some(1)),
+        ]
+    );
+    assert!(findings
+        .iter()
+        .next()
+        .unwrap()
+        .message
+        .contains("Statement[1].Condition.StringEquals.aws:SourceIp"));
+
+    assert!(validate_str("{", &ValidationOptions::default()).is_err());
+}
+
+#[test]
+fn test_unknown_condition_operator() {
+    let document = r#"{"Statement":[{"Effect":"Allow","Action":"s3:GetObject","Resource":"*",
+        "Condition":{"StringEqualsIgnorecase":{"aws:PrincipalTag/team":"admin"},
+                     "DateLessThan":{"aws:CurrentTime":"2020-02-30T25:00:00Z"}}}]}"#;
+    assert!(validate_str(document, &ValidationOptions::default()).is_err());
+
+    let options = ValidationOptions::default().with_parse_mode(ArnParseMode::Permissive);
+    let findings = validate_str(document, &options).unwrap();
+    assert!(findings.is_valid());
+    let codes: Vec<(&str, Option<usize>)> =
+        findings.iter().map(|f| (f.code, f.statement)).collect();
+    assert_eq!(
+        codes,
+        vec![
+            (FINDING_UNKNOWN_CONDITION_OPERATOR, Some(0)),
+            (FINDING_INVALID_CONDITION_VALUE, Some(0)),
+        ]
+    );
+}
+
+#[test]
+fn test_duplicate_sids() {
+    let mut policy = Policy::from_str(
+        r#"{"Statement":[
+            {"Sid":"Read","Effect":"Allow","Action":"s3:GetObject","Resource":"*"},
+            {"Sid":"Read","Effect":"Allow","Action":"s3:ListBucket","Resource":"*"},
+            {"Sid":"Read2","Effect":"Allow","Action":"s3:GetBucketPolicy","Resource":"*"},
+            {"Sid":"Read","Effect":"Allow","Action":"s3:GetObjectAcl","Resource":"*"}
+        ]}"#,
+    )
+    .unwrap();
+    let findings = policy.validate(&ValidationOptions::default());
+    let duplicates: Vec<Option<usize>> = findings
+        .errors()
+        .filter(|f| f.code == FINDING_DUPLICATE_SID)
+        .map(|f| f.statement)
+        .collect();
+    assert_eq!(duplicates, vec![Some(1), Some(3)]);
+
+    assert_eq!(policy.rename_duplicate_sids(), 2);
+    let sids: Vec<String> = policy
+        .statements()
+        .map(|statement| statement.sid.as_ref().unwrap().to_string())
+        .collect();
+    assert_eq!(sids, vec!["Read", "Read3", "Read2", "Read4"]);
+    assert!(policy.validate(&ValidationOptions::default()).is_valid());
+    assert_eq!(policy.rename_duplicate_sids(), 0);
+}